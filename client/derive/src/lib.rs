@@ -0,0 +1,139 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{Data, DeriveInput, Fields, LitStr, parse_macro_input, spanned::Spanned};
+
+// #[derive(ConfigBind)]：为具名字段的结构体生成 config_manager_client::ConfigBind 实现
+//
+// 字段属性：
+//   #[config(path = "db.port")]       相对当前结构体的点分路径，默认为字段名
+//   #[config(default)]                缺失或为 null 时使用 Default::default()
+//   #[config(default = "fn_name")]    缺失或为 null 时调用 fn_name()
+//   #[config(validate = "fn_name")]   绑定后调用 fn_name(&value) -> Result<(), String>
+// 结构体属性：
+//   #[config(validate = "fn_name")]   所有字段绑定后调用 fn_name(&self) -> Result<(), String>
+#[proc_macro_derive(ConfigBind, attributes(config))]
+pub fn derive_config_bind(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Default)]
+struct FieldOptions {
+    path: Option<String>,
+    default: Option<Option<syn::Path>>,
+    validate: Option<syn::Path>,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.span(),
+                    "ConfigBind only supports structs with named fields",
+                ));
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "ConfigBind only supports structs",
+            ));
+        }
+    };
+
+    let validate = parse_options(&input.attrs)?.validate.map(|validate| {
+        quote! {
+            #validate(&bound).map_err(|message| {
+                ::config_manager_client::BindError::invalid(path, message)
+            })?;
+        }
+    });
+
+    let mut bindings = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().unwrap();
+        let ty = &field.ty;
+        let options = parse_options(&field.attrs)?;
+        let key = options.path.unwrap_or_else(|| ident.to_string());
+        let bind = quote! {
+            <#ty as ::config_manager_client::ConfigBind>::bind(value, &field_path)?
+        };
+        let bind = match options.default {
+            Some(default) => {
+                let default = match default {
+                    Some(function) => quote! { #function() },
+                    None => quote! { ::core::default::Default::default() },
+                };
+                quote! {
+                    match value {
+                        None | Some(::config_manager_client::ConfigValue::Null) => #default,
+                        Some(_) => #bind,
+                    }
+                }
+            }
+            None => bind,
+        };
+        let validate = options.validate.map(|validate| {
+            quote! {
+                #validate(&field).map_err(|message| {
+                    ::config_manager_client::BindError::invalid(&field_path, message)
+                })?;
+            }
+        });
+        bindings.push(quote! {
+            #ident: {
+                let field_path = ::config_manager_client::bind::join(path, #key);
+                let value = ::config_manager_client::bind::lookup(object, #key);
+                let field = #bind;
+                #validate
+                field
+            }
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::config_manager_client::ConfigBind for #name #ty_generics #where_clause {
+            fn bind(
+                value: ::core::option::Option<&::config_manager_client::ConfigValue>,
+                path: &str,
+            ) -> ::core::result::Result<Self, ::config_manager_client::BindError> {
+                let object = ::config_manager_client::bind::object(value, path)?;
+                let bound = Self {
+                    #(#bindings,)*
+                };
+                #validate
+                ::core::result::Result::Ok(bound)
+            }
+        }
+    })
+}
+
+fn parse_options(attrs: &[syn::Attribute]) -> syn::Result<FieldOptions> {
+    let mut options = FieldOptions::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("config")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("path") {
+                options.path = Some(meta.value()?.parse::<LitStr>()?.value());
+            } else if meta.path.is_ident("default") {
+                let function = match meta.input.peek(syn::Token![=]) {
+                    true => Some(meta.value()?.parse::<LitStr>()?.parse::<syn::Path>()?),
+                    false => None,
+                };
+                options.default = Some(function);
+            } else if meta.path.is_ident("validate") {
+                options.validate = Some(meta.value()?.parse::<LitStr>()?.parse::<syn::Path>()?);
+            } else {
+                return Err(meta.error("expected path, default or validate"));
+            }
+            Ok(())
+        })?;
+    }
+    Ok(options)
+}
@@ -0,0 +1,198 @@
+use std::collections::{BTreeMap, HashMap};
+
+use config_manager::domain::entities::configuration::{Config, ConfigValue};
+use thiserror::Error;
+
+// 把 ConfigValue 树绑定到应用自己的类型，结构体通过 #[derive(ConfigBind)] 实现
+pub trait ConfigBind: Sized {
+    // value 为 None 表示 path 上没有值；path 为点分路径，根为 "."
+    fn bind(value: Option<&ConfigValue>, path: &str) -> Result<Self, BindError>;
+}
+
+// 绑定失败的位置与原因，path 与 serde_path_to_error 的格式一致，例如 servers[0].port
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum BindError {
+    #[error("missing value at {path}")]
+    Missing { path: String },
+    #[error("expected {expected} at {path}, found {found}")]
+    Type {
+        path: String,
+        expected: String,
+        found: String,
+    },
+    #[error("invalid value at {path}: {message}")]
+    Invalid { path: String, message: String },
+}
+
+impl BindError {
+    pub fn invalid(path: &str, message: impl Into<String>) -> Self {
+        Self::Invalid {
+            path: path.to_string(),
+            message: message.into(),
+        }
+    }
+
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Missing { path } | Self::Type { path, .. } | Self::Invalid { path, .. } => path,
+        }
+    }
+
+    fn mismatch(path: &str, expected: &str, value: &ConfigValue) -> Self {
+        Self::Type {
+            path: path.to_string(),
+            expected: expected.to_string(),
+            found: value.summary(),
+        }
+    }
+}
+
+// 把整份配置绑定为 T
+pub fn bind<T: ConfigBind>(config: &Config) -> Result<T, BindError> {
+    T::bind(Some(&ConfigValue::Object(config.config.clone())), ".")
+}
+
+// 以下供 derive 生成的代码使用
+pub fn join(path: &str, key: &str) -> String {
+    match path {
+        "." => key.to_string(),
+        path => format!("{}.{}", path, key),
+    }
+}
+
+pub fn object<'a>(
+    value: Option<&'a ConfigValue>,
+    path: &str,
+) -> Result<&'a HashMap<String, ConfigValue>, BindError> {
+    match required(value, path)? {
+        ConfigValue::Object(object) => Ok(object),
+        value => Err(BindError::mismatch(path, "Object", value)),
+    }
+}
+
+// 按点分路径逐级查找，中间层不是对象时视为缺失
+pub fn lookup<'a>(object: &'a HashMap<String, ConfigValue>, key: &str) -> Option<&'a ConfigValue> {
+    let mut keys = key.split('.');
+    let mut current = object.get(keys.next()?)?;
+    for key in keys {
+        match current {
+            ConfigValue::Object(object) => current = object.get(key)?,
+            _ => return None,
+        }
+    }
+    Some(current)
+}
+
+fn required<'a>(value: Option<&'a ConfigValue>, path: &str) -> Result<&'a ConfigValue, BindError> {
+    match value {
+        None | Some(ConfigValue::Null) => Err(BindError::Missing {
+            path: path.to_string(),
+        }),
+        Some(value) => Ok(value),
+    }
+}
+
+impl ConfigBind for ConfigValue {
+    fn bind(value: Option<&ConfigValue>, path: &str) -> Result<Self, BindError> {
+        required(value, path).cloned()
+    }
+}
+
+impl ConfigBind for String {
+    fn bind(value: Option<&ConfigValue>, path: &str) -> Result<Self, BindError> {
+        match required(value, path)? {
+            ConfigValue::String(value) => Ok(value.clone()),
+            value => Err(BindError::mismatch(path, "String", value)),
+        }
+    }
+}
+
+impl ConfigBind for bool {
+    fn bind(value: Option<&ConfigValue>, path: &str) -> Result<Self, BindError> {
+        match required(value, path)? {
+            ConfigValue::Boolean(value) => Ok(*value),
+            value => Err(BindError::mismatch(path, "Boolean", value)),
+        }
+    }
+}
+
+// 整数超出目标类型范围时同样报类型错误，expected 为目标类型名
+macro_rules! bind_integer {
+    ($($ty:ty),*) => {$(
+        impl ConfigBind for $ty {
+            fn bind(value: Option<&ConfigValue>, path: &str) -> Result<Self, BindError> {
+                let value = required(value, path)?;
+                let number = match value {
+                    ConfigValue::Number(number) => number,
+                    value => return Err(BindError::mismatch(path, stringify!($ty), value)),
+                };
+                let bound = match number.as_i64() {
+                    Some(number) => <$ty>::try_from(number).ok(),
+                    None => number.as_u64().and_then(|number| <$ty>::try_from(number).ok()),
+                };
+                bound.ok_or_else(|| BindError::mismatch(path, stringify!($ty), value))
+            }
+        }
+    )*};
+}
+
+bind_integer!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+macro_rules! bind_float {
+    ($($ty:ty),*) => {$(
+        impl ConfigBind for $ty {
+            fn bind(value: Option<&ConfigValue>, path: &str) -> Result<Self, BindError> {
+                match required(value, path)? {
+                    ConfigValue::Number(number) => number
+                        .as_f64()
+                        .map(|number| number as $ty)
+                        .ok_or_else(|| BindError::invalid(path, "not a finite number")),
+                    value => Err(BindError::mismatch(path, stringify!($ty), value)),
+                }
+            }
+        }
+    )*};
+}
+
+bind_float!(f32, f64);
+
+// 缺失或为 null 时为 None
+impl<T: ConfigBind> ConfigBind for Option<T> {
+    fn bind(value: Option<&ConfigValue>, path: &str) -> Result<Self, BindError> {
+        match value {
+            None | Some(ConfigValue::Null) => Ok(None),
+            value => T::bind(value, path).map(Some),
+        }
+    }
+}
+
+impl<T: ConfigBind> ConfigBind for Vec<T> {
+    fn bind(value: Option<&ConfigValue>, path: &str) -> Result<Self, BindError> {
+        match required(value, path)? {
+            ConfigValue::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(index, item)| T::bind(Some(item), &format!("{}[{}]", path, index)))
+                .collect(),
+            value => Err(BindError::mismatch(path, "Array", value)),
+        }
+    }
+}
+
+impl<T: ConfigBind> ConfigBind for HashMap<String, T> {
+    fn bind(value: Option<&ConfigValue>, path: &str) -> Result<Self, BindError> {
+        object(value, path)?
+            .iter()
+            .map(|(key, item)| Ok((key.clone(), T::bind(Some(item), &join(path, key))?)))
+            .collect()
+    }
+}
+
+impl<T: ConfigBind> ConfigBind for BTreeMap<String, T> {
+    fn bind(value: Option<&ConfigValue>, path: &str) -> Result<Self, BindError> {
+        object(value, path)?
+            .iter()
+            .map(|(key, item)| Ok((key.clone(), T::bind(Some(item), &join(path, key))?)))
+            .collect()
+    }
+}
@@ -0,0 +1,37 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+// 按配置名称缓存读取到的 JSON，超过 ttl 后重新请求；watch 收到的更新会直接刷新缓存
+pub struct ConfigCache {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, (Instant, serde_json::Value)>>,
+}
+
+impl ConfigCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<serde_json::Value> {
+        let entries = self.entries.lock().unwrap();
+        entries
+            .get(name)
+            .filter(|(stored, _)| stored.elapsed() < self.ttl)
+            .map(|(_, config)| config.clone())
+    }
+
+    pub fn insert(&self, name: &str, config: serde_json::Value) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.insert(name.to_string(), (Instant::now(), config));
+    }
+
+    pub fn remove(&self, name: &str) {
+        self.entries.lock().unwrap().remove(name);
+    }
+}
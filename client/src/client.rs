@@ -0,0 +1,204 @@
+use std::{sync::Arc, time::Duration};
+
+use config_manager::{
+    domain::{
+        entities::configuration::{Config, ConfigValue},
+        events::config_changed::ConfigUpdate,
+        value_objects::{config_format::ConfigType, config_path::ConfigPath},
+    },
+    shared::app_state::RestResponse,
+};
+use futures_util::Stream;
+use serde::{Serialize, de::DeserializeOwned};
+
+use crate::{
+    bind::ConfigBind,
+    cache::ConfigCache,
+    error::ClientError,
+    watch::{ReconnectPolicy, Watcher},
+    watched::Watched,
+};
+
+// 访问运行中 serve --http 实例的异步客户端：读写配置走 REST API，监听变更走 WebSocket；
+// clone 后共享连接池和缓存
+#[derive(Clone)]
+pub struct ConfigClient {
+    base_url: String,
+    http: reqwest::Client,
+    // 服务端启用 API key 鉴权时以 Bearer 方式携带，WebSocket 升级请求同样携带
+    api_key: Option<String>,
+    cache: Option<Arc<ConfigCache>>,
+    reconnect: ReconnectPolicy,
+}
+
+// GET /api/configs/{name} 的响应，这里只需要配置内容
+#[derive(serde::Deserialize)]
+struct RemoteConfig {
+    config: serde_json::Value,
+}
+
+impl ConfigClient {
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http: reqwest::Client::new(),
+            api_key: None,
+            cache: None,
+            reconnect: ReconnectPolicy::default(),
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    // 启用本地缓存：ttl 内的重复读取不再请求服务端
+    pub fn with_cache(mut self, ttl: Duration) -> Self {
+        self.cache = Some(Arc::new(ConfigCache::new(ttl)));
+        self
+    }
+
+    // watch 断线后的重连间隔，从 initial 开始每次翻倍，不超过 max
+    pub fn with_reconnect(mut self, initial: Duration, max: Duration) -> Self {
+        self.reconnect = ReconnectPolicy { initial, max };
+        self
+    }
+
+    // 读取应用环境变量覆盖后的配置并反序列化为 T，失败时错误中带上出错字段的路径
+    pub async fn get<T: DeserializeOwned>(&self, name: &str) -> Result<T, ClientError> {
+        let config = self.get_json(name).await?;
+        serde_path_to_error::deserialize(config).map_err(|e| ClientError::Deserialize {
+            name: name.to_string(),
+            path: e.path().to_string(),
+            error: e.inner().to_string(),
+        })
+    }
+
+    // 与 get 相同，但通过 ConfigBind 绑定，支持 #[config(...)] 声明的路径、默认值和校验
+    pub async fn bind<T: ConfigBind>(&self, name: &str) -> Result<T, ClientError> {
+        let config = self.get_json(name).await?;
+        Self::bind_json(name, config)
+    }
+
+    fn bind_json<T: ConfigBind>(name: &str, config: serde_json::Value) -> Result<T, ClientError> {
+        let config = ConfigValue::from_serde_json(config)
+            .map_err(|e| ClientError::Request(e.to_string()))?;
+        T::bind(Some(&config), ".").map_err(|error| ClientError::Bind {
+            name: name.to_string(),
+            error,
+        })
+    }
+
+    pub async fn get_json(&self, name: &str) -> Result<serde_json::Value, ClientError> {
+        if let Some(config) = self.cache.as_ref().and_then(|cache| cache.get(name)) {
+            return Ok(config);
+        }
+        let request = self.request(reqwest::Method::GET, self.config_url(name));
+        let remote: RemoteConfig = Self::send(name, request).await?;
+        if let Some(cache) = &self.cache {
+            cache.insert(name, remote.config.clone());
+        }
+        Ok(remote.config)
+    }
+
+    // 按配置名称的扩展名序列化后整份写入，服务端校验通过后推送给所有订阅者
+    pub async fn put<T: Serialize>(&self, name: &str, config: &T) -> Result<(), ClientError> {
+        let value =
+            serde_json::to_value(config).map_err(|e| ClientError::Serialize(e.to_string()))?;
+        let config_type = ConfigType::from(name.rsplit('.').next().unwrap_or_default());
+        let content = ConfigValue::from_serde_json(value.clone())
+            .and_then(ConfigValue::into_object)
+            .and_then(|config| {
+                Config {
+                    path: ConfigPath::new(name)?,
+                    config,
+                    config_type: config_type.clone(),
+                }
+                .serialize_to(&config_type)
+            })
+            .map_err(|e| ClientError::Serialize(e.to_string()))?;
+        let request = self.request(reqwest::Method::PUT, self.config_url(name));
+        let _: serde_json::Value = Self::send(name, request.body(content)).await?;
+        if let Some(cache) = &self.cache {
+            cache.insert(name, value);
+        }
+        Ok(())
+    }
+
+    // 订阅配置的变更；连接断开或服务端重启后自动重连并重新订阅，能恢复会话时补发错过的更新，
+    // 否则在内容变化时推送一条 source 为 resync 的更新。订阅时的当前内容不会推送，需要时先 get
+    pub fn watch(&self, name: &str) -> impl Stream<Item = ConfigUpdate> + Send + 'static {
+        self.watcher(name, false).spawn()
+    }
+
+    // 绑定当前配置并持续跟随变更，返回可随时读取最新值的句柄；当前配置无法绑定时返回错误
+    pub async fn watched<T>(&self, name: &str) -> Result<Watched<T>, ClientError>
+    where
+        T: ConfigBind + Send + Sync + 'static,
+    {
+        let config = self.get_json(name).await?;
+        let current = Self::bind_json(name, config.clone())?;
+        // 订阅时服务端发送的完整配置也作为更新推送，覆盖读取与订阅之间发生的修改
+        let updates = self.watcher(name, true).spawn();
+        Ok(Watched::spawn(name, current, config, updates))
+    }
+
+    fn watcher(&self, name: &str, initial: bool) -> Watcher {
+        Watcher {
+            url: self.ws_url(),
+            file: name.to_string(),
+            api_key: self.api_key.clone(),
+            cache: self.cache.clone(),
+            reconnect: self.reconnect.clone(),
+            initial,
+        }
+    }
+
+    fn request(&self, method: reqwest::Method, url: String) -> reqwest::RequestBuilder {
+        let request = self.http.request(method, url);
+        match &self.api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
+        }
+    }
+
+    fn config_url(&self, name: &str) -> String {
+        format!("{}/api/configs/{}", self.base_url, name)
+    }
+
+    fn ws_url(&self) -> String {
+        let base_url = match self.base_url.split_once("://") {
+            Some(("https", rest)) => format!("wss://{}", rest),
+            Some((_, rest)) => format!("ws://{}", rest),
+            None => format!("ws://{}", self.base_url),
+        };
+        format!("{}/ws/listen", base_url)
+    }
+
+    async fn send<T: DeserializeOwned>(
+        name: &str,
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, ClientError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ClientError::Request(e.to_string()))?;
+        let body: RestResponse<T> = response
+            .json()
+            .await
+            .map_err(|e| ClientError::Request(e.to_string()))?;
+        match body {
+            RestResponse {
+                success: true,
+                data: Some(data),
+                ..
+            } => Ok(data),
+            RestResponse { success: true, .. } => {
+                Err(ClientError::Request("empty response".to_string()))
+            }
+            RestResponse { code: 404, .. } => Err(ClientError::NotFound(name.to_string())),
+            RestResponse { code, message, .. } => Err(ClientError::Rejected { code, message }),
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use thiserror::Error;
+
+use crate::bind::BindError;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("request failed: {0}")]
+    Request(String),
+    #[error("config not found: {0}")]
+    NotFound(String),
+    // 服务端拒绝请求，code 为响应中的业务状态码（401、403、409 等）
+    #[error("server rejected request ({code}): {message}")]
+    Rejected { code: u16, message: String },
+    // path 为出错字段的路径，例如 database.port
+    #[error("config {name} cannot be deserialized at {path}: {error}")]
+    Deserialize {
+        name: String,
+        path: String,
+        error: String,
+    },
+    #[error("config {name} cannot be bound: {error}")]
+    Bind { name: String, error: BindError },
+    #[error("config cannot be serialized: {0}")]
+    Serialize(String),
+}
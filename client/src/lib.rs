@@ -0,0 +1,16 @@
+pub mod bind;
+pub mod cache;
+pub mod client;
+pub mod error;
+pub mod watch;
+pub mod watched;
+
+pub use bind::{BindError, ConfigBind};
+pub use client::ConfigClient;
+pub use config_manager::domain::{
+    entities::configuration::ConfigValue,
+    events::config_changed::{ChangeKind, ConfigUpdate},
+};
+pub use config_manager_derive::ConfigBind;
+pub use error::ClientError;
+pub use watched::Watched;
@@ -0,0 +1,287 @@
+use std::{sync::Arc, time::Duration};
+
+use chrono::{DateTime, Utc};
+use config_manager::{
+    application::dtos::capabilities::PROTOCOL_VERSION,
+    domain::{
+        events::config_changed::{ChangeKind, ConfigUpdate},
+        services::config_patch::PatchOperation,
+    },
+};
+use futures_util::{Stream, StreamExt};
+use serde::Deserialize;
+use tokio::sync::mpsc::{self, UnboundedSender};
+use tokio_tungstenite::tungstenite::{
+    self,
+    client::IntoClientRequest,
+    http::{HeaderValue, StatusCode, header::AUTHORIZATION},
+    protocol::Message,
+};
+use tracing::{debug, info};
+
+use crate::cache::ConfigCache;
+
+// 断线重连的退避策略
+#[derive(Debug, Clone)]
+pub struct ReconnectPolicy {
+    pub initial: Duration,
+    pub max: Duration,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(500),
+            max: Duration::from_secs(30),
+        }
+    }
+}
+
+// 一次 watch 的后台任务：维护 WebSocket 连接，把推送转换为 ConfigUpdate 交给 Stream
+pub(crate) struct Watcher {
+    // ws://<host>/ws/listen
+    pub url: String,
+    pub file: String,
+    pub api_key: Option<String>,
+    pub cache: Option<Arc<ConfigCache>>,
+    pub reconnect: ReconnectPolicy,
+    // 是否把首次订阅时的完整配置作为 source 为 initial 的更新推送
+    pub initial: bool,
+}
+
+// 跨连接保留的状态：恢复令牌用于补发错过的更新，最后的内容用于令牌失效时判断是否需要 resync
+#[derive(Default)]
+struct Session {
+    resume_token: Option<String>,
+    last: Option<serde_json::Value>,
+    // 本次连接是否已完成订阅，完成后重连间隔回到初始值
+    synced: bool,
+}
+
+enum Disconnect {
+    // 连接断开或服务端关闭，稍后重连
+    Retry(String),
+    // 鉴权失败或配置不存在，重连也不会成功
+    Fatal(String),
+    // Stream 已被丢弃
+    Closed,
+}
+
+// 服务端推送的消息，按 type 区分；协议 2 中 config 为 JSON 对象
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ServerMessage {
+    Initial {
+        resume_token: String,
+        seq: u64,
+        config: serde_json::Value,
+    },
+    Resumed {
+        missed: Vec<PushedUpdate>,
+    },
+    Created(PushedUpdate),
+    Update(PushedUpdate),
+    Deleted(PushedUpdate),
+    Shutdown,
+    Error {
+        message: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+// 恢复会话时补发的更新不带 file 和 type
+#[derive(Deserialize)]
+struct PushedUpdate {
+    seq: u64,
+    #[serde(default)]
+    config: serde_json::Value,
+    #[serde(default)]
+    patch: Vec<PatchOperation>,
+    #[serde(default)]
+    hash: Option<String>,
+    #[serde(default)]
+    source: String,
+    timestamp: DateTime<Utc>,
+}
+
+impl Watcher {
+    pub fn spawn(self) -> impl Stream<Item = ConfigUpdate> + Send + 'static {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(self.run(sender));
+        futures_util::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|update| (update, receiver))
+        })
+    }
+
+    async fn run(self, sender: UnboundedSender<ConfigUpdate>) {
+        let mut session = Session::default();
+        let mut delay = self.reconnect.initial;
+        loop {
+            let disconnect = tokio::select! {
+                disconnect = self.listen(&mut session, &sender) => disconnect,
+                _ = sender.closed() => Disconnect::Closed,
+            };
+            match disconnect {
+                Disconnect::Retry(reason) => {
+                    debug!("watch {} disconnected: {}", self.file, reason);
+                }
+                Disconnect::Fatal(reason) => {
+                    info!("watch {} stopped: {}", self.file, reason);
+                    return;
+                }
+                Disconnect::Closed => return,
+            }
+            if std::mem::take(&mut session.synced) {
+                delay = self.reconnect.initial;
+            }
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = sender.closed() => return,
+            }
+            delay = (delay * 2).min(self.reconnect.max);
+        }
+    }
+
+    async fn listen(
+        &self,
+        session: &mut Session,
+        sender: &UnboundedSender<ConfigUpdate>,
+    ) -> Disconnect {
+        let request = match self.request(session) {
+            Ok(request) => request,
+            Err(e) => return Disconnect::Fatal(e),
+        };
+        let mut stream = match tokio_tungstenite::connect_async(request).await {
+            Ok((stream, _)) => stream,
+            Err(tungstenite::Error::Http(response))
+                if matches!(
+                    response.status(),
+                    StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN | StatusCode::NOT_FOUND
+                ) =>
+            {
+                return Disconnect::Fatal(format!("HTTP {}", response.status()));
+            }
+            Err(e) => return Disconnect::Retry(e.to_string()),
+        };
+        while let Some(message) = stream.next().await {
+            let text = match message {
+                Ok(Message::Text(text)) => text,
+                Ok(Message::Close(_)) => break,
+                Ok(_) => continue,
+                Err(e) => return Disconnect::Retry(e.to_string()),
+            };
+            let message = match serde_json::from_str::<ServerMessage>(&text) {
+                Ok(message) => message,
+                Err(e) => {
+                    debug!("ignore WebSocket message {}: {}", text, e);
+                    continue;
+                }
+            };
+            let updates = match message {
+                ServerMessage::Initial {
+                    resume_token,
+                    seq,
+                    config,
+                } => {
+                    session.resume_token = Some(resume_token);
+                    session.synced = true;
+                    // 令牌失效时服务端重新发送完整配置，内容变化说明断线期间有更新
+                    let source = match &session.last {
+                        Some(last) if *last != config => "resync",
+                        Some(_) => continue,
+                        None if self.initial => "initial",
+                        None => {
+                            session.last = Some(config);
+                            continue;
+                        }
+                    };
+                    vec![self.update(ChangeKind::Updated, snapshot(seq, config, source))]
+                }
+                ServerMessage::Resumed { missed } => {
+                    session.synced = true;
+                    missed
+                        .into_iter()
+                        .map(|update| {
+                            let kind = if update.config.is_null() {
+                                ChangeKind::Deleted
+                            } else {
+                                ChangeKind::Updated
+                            };
+                            self.update(kind, update)
+                        })
+                        .collect()
+                }
+                ServerMessage::Created(update) => vec![self.update(ChangeKind::Created, update)],
+                ServerMessage::Update(update) => vec![self.update(ChangeKind::Updated, update)],
+                ServerMessage::Deleted(update) => vec![self.update(ChangeKind::Deleted, update)],
+                ServerMessage::Shutdown => {
+                    return Disconnect::Retry("server shutting down".to_string());
+                }
+                ServerMessage::Error { message } => return Disconnect::Retry(message),
+                ServerMessage::Other => continue,
+            };
+            for update in updates {
+                session.last = serde_json::from_str(&update.config).ok();
+                if sender.send(update).is_err() {
+                    return Disconnect::Closed;
+                }
+            }
+        }
+        Disconnect::Retry("connection closed".to_string())
+    }
+
+    // 重连时带上恢复令牌，服务端据此补发断线期间的更新
+    fn request(
+        &self,
+        session: &Session,
+    ) -> Result<tungstenite::handshake::client::Request, String> {
+        let mut url = reqwest::Url::parse(&self.url).map_err(|e| e.to_string())?;
+        {
+            let mut query = url.query_pairs_mut();
+            query.append_pair("file", &self.file);
+            query.append_pair("protocol", &PROTOCOL_VERSION.to_string());
+            if let Some(token) = &session.resume_token {
+                query.append_pair("resume", token);
+            }
+        }
+        let mut request = url.into_client_request().map_err(|e| e.to_string())?;
+        if let Some(api_key) = &self.api_key {
+            let value =
+                HeaderValue::from_str(&format!("Bearer {}", api_key)).map_err(|e| e.to_string())?;
+            request.headers_mut().insert(AUTHORIZATION, value);
+        }
+        Ok(request)
+    }
+
+    // 转换为与服务端订阅通道一致的 ConfigUpdate，并刷新本地缓存
+    fn update(&self, kind: ChangeKind, update: PushedUpdate) -> ConfigUpdate {
+        if let Some(cache) = &self.cache {
+            match kind {
+                ChangeKind::Deleted => cache.remove(&self.file),
+                _ => cache.insert(&self.file, update.config.clone()),
+            }
+        }
+        ConfigUpdate {
+            seq: update.seq,
+            file: self.file.clone(),
+            kind,
+            config: update.config.to_string(),
+            patch: update.patch,
+            hash: update.hash,
+            source: update.source,
+            timestamp: update.timestamp,
+        }
+    }
+}
+
+fn snapshot(seq: u64, config: serde_json::Value, source: &str) -> PushedUpdate {
+    PushedUpdate {
+        seq,
+        config,
+        patch: vec![],
+        hash: None,
+        source: source.to_string(),
+        timestamp: Utc::now(),
+    }
+}
@@ -0,0 +1,122 @@
+use std::sync::{Arc, Mutex, Weak};
+
+use arc_swap::ArcSwap;
+use config_manager::domain::{
+    entities::configuration::ConfigValue,
+    events::config_changed::{ChangeKind, ConfigUpdate},
+};
+use futures_util::{Stream, StreamExt};
+use tokio::task::AbortHandle;
+use tracing::info;
+
+use crate::bind::ConfigBind;
+
+type Callback<T> = Box<dyn Fn(&T) + Send + Sync>;
+
+// 跟随配置变更自动更新的值：收到更新后重新绑定并原子替换，随后依次调用 on_change 注册的回调。
+// clone 共享同一份值和回调，全部 drop 后停止订阅
+pub struct Watched<T> {
+    shared: Arc<Shared<T>>,
+}
+
+struct Shared<T> {
+    name: String,
+    value: ArcSwap<T>,
+    callbacks: Mutex<Vec<Callback<T>>>,
+    task: Mutex<Option<AbortHandle>>,
+}
+
+impl<T> Drop for Shared<T> {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.lock().unwrap().take() {
+            task.abort();
+        }
+    }
+}
+
+impl<T> Clone for Watched<T> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T: ConfigBind + Send + Sync + 'static> Watched<T> {
+    // config 为 current 对应的 JSON，内容相同的更新（例如订阅时的完整配置）不触发回调
+    pub(crate) fn spawn(
+        name: &str,
+        current: T,
+        config: serde_json::Value,
+        updates: impl Stream<Item = ConfigUpdate> + Send + 'static,
+    ) -> Self {
+        let shared = Arc::new(Shared {
+            name: name.to_string(),
+            value: ArcSwap::from_pointee(current),
+            callbacks: Mutex::new(Vec::new()),
+            task: Mutex::new(None),
+        });
+        let task = tokio::spawn(Self::follow(Arc::downgrade(&shared), config, updates));
+        *shared.task.lock().unwrap() = Some(task.abort_handle());
+        Self { shared }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.shared.name
+    }
+
+    // 当前值；返回的 Arc 不受之后的更新影响，需要最新值时重新调用
+    pub fn get(&self) -> Arc<T> {
+        self.shared.value.load_full()
+    }
+
+    // 回调在后台任务中以新值调用，应尽快返回，且不能在回调中再注册回调
+    pub fn on_change(&self, callback: impl Fn(&T) + Send + Sync + 'static) {
+        self.shared
+            .callbacks
+            .lock()
+            .unwrap()
+            .push(Box::new(callback));
+    }
+
+    // 删除或无法绑定的更新保留原值，避免一次错误的修改让应用拿不到配置
+    async fn follow(
+        shared: Weak<Shared<T>>,
+        mut last: serde_json::Value,
+        updates: impl Stream<Item = ConfigUpdate>,
+    ) {
+        let mut updates = std::pin::pin!(updates);
+        while let Some(update) = updates.next().await {
+            let Some(shared) = shared.upgrade() else {
+                return;
+            };
+            if update.kind == ChangeKind::Deleted {
+                info!("config {} was deleted, keep the last value", shared.name);
+                continue;
+            }
+            let Ok(config) = serde_json::from_str::<serde_json::Value>(&update.config) else {
+                continue;
+            };
+            if config == last {
+                continue;
+            }
+            let bound = ConfigValue::from_serde_json(config.clone())
+                .map_err(|e| e.to_string())
+                .and_then(|value| T::bind(Some(&value), ".").map_err(|e| e.to_string()));
+            last = config;
+            match bound {
+                Ok(value) => {
+                    let value = Arc::new(value);
+                    shared.value.store(value.clone());
+                    for callback in shared.callbacks.lock().unwrap().iter() {
+                        callback(&value);
+                    }
+                }
+                Err(e) => info!(
+                    "config {} update {} cannot be bound, keep the last value: {}",
+                    shared.name, update.seq, e
+                ),
+            }
+        }
+    }
+}
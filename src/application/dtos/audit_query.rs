@@ -0,0 +1,21 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::domain::entities::audit::AuditRecord;
+
+// GET /api/audit 的查询参数，时间为 RFC 3339 格式
+#[derive(Debug, Default, Deserialize)]
+pub struct AuditQuery {
+    pub config: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub limit: Option<usize>, // 只返回最近的若干条
+}
+
+impl AuditQuery {
+    pub fn matches(&self, record: &AuditRecord) -> bool {
+        self.config.as_ref().is_none_or(|config| &record.config == config)
+            && self.since.is_none_or(|since| record.timestamp >= since)
+            && self.until.is_none_or(|until| record.timestamp <= until)
+    }
+}
@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+// POST /api/backups/restore 的请求体
+#[derive(Deserialize)]
+pub struct RestoreBackupRequest {
+    pub backup: Option<String>, // 备份名称，未指定时恢复最近一份
+}
@@ -0,0 +1,7 @@
+use serde::Deserialize;
+
+// GET/POST /api/admin/bundle 的查询参数
+#[derive(Deserialize)]
+pub struct BundleQuery {
+    pub namespace: Option<String>, // 只导出该命名空间；导入时覆盖清单中记录的命名空间
+}
@@ -0,0 +1,52 @@
+use serde::Serialize;
+
+// 当前协议版本；新增可选字段不升级版本，破坏性变更才升级
+pub const PROTOCOL_VERSION: u32 = 2;
+pub const MIN_PROTOCOL_VERSION: u32 = 1;
+// 从该版本起推送中的 config 为 JSON 对象，之前的版本为 JSON 字符串
+pub const STRUCTURED_CONFIG_VERSION: u32 = 2;
+
+// 服务端支持的协议特性，客户端据此决定启用哪些行为
+pub const SUPPORTED_FEATURES: [&str; 5] = [
+    "resume",
+    "sequence_numbers",
+    "subscriber_quota",
+    "key_subscriptions",
+    "json_patch",
+];
+
+// 🤝 版本与能力协商结果
+#[derive(Debug, Clone, Serialize)]
+pub struct Capabilities {
+    pub server_version: String,
+    pub protocol_version: u32,
+    pub min_protocol_version: u32,
+    pub max_protocol_version: u32,
+    pub features: Vec<String>,
+}
+
+impl Capabilities {
+    // 客户端未声明版本时按当前版本处理；高于服务端的版本降级到服务端版本
+    pub fn negotiate(requested: Option<u32>) -> Result<Self, String> {
+        let requested = requested.unwrap_or(PROTOCOL_VERSION);
+        if requested < MIN_PROTOCOL_VERSION {
+            return Err(format!(
+                "unsupported protocol version {}, server supports {}..={}",
+                requested, MIN_PROTOCOL_VERSION, PROTOCOL_VERSION
+            ));
+        }
+        Ok(Self {
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            protocol_version: requested.min(PROTOCOL_VERSION),
+            min_protocol_version: MIN_PROTOCOL_VERSION,
+            max_protocol_version: PROTOCOL_VERSION,
+            features: SUPPORTED_FEATURES.iter().map(|f| f.to_string()).collect(),
+        })
+    }
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self::negotiate(None).unwrap()
+    }
+}
@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+// GET /api/configs/{path} 的查询参数
+#[derive(Deserialize)]
+pub struct ConfigQuery {
+    #[serde(default)]
+    pub watch: bool,          // 长轮询：阻塞直到配置版本大于 version 或超时
+    pub version: Option<u64>, // 客户端已有的版本，未指定时等待下一次更新
+    pub timeout: Option<u64>, // 长轮询最长等待秒数
+}
@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConfigLoadState {
+    // 已解析并在缓存中
+    Loaded,
+    // 懒加载模式下尚未解析
+    Unloaded,
+    // 最近一次加载失败，继续提供上一个有效版本
+    Stale,
+    // 从未成功加载
+    Failed,
+}
+
+// 最近一次加载配置文件失败的原因
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadFailure {
+    pub error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+// GET /api/configs/{name}/status
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigStatus {
+    pub name: String,
+    pub state: ConfigLoadState,
+    #[serde(flatten)]
+    pub failure: Option<LoadFailure>,
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::config_map::ConfigMetadata;
+
+// GET /api/configs 列表中的一项
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigSummary {
+    pub name: String,
+    #[serde(flatten)]
+    pub metadata: ConfigMetadata,
+}
+
+// GET /api/namespaces 列表中的一项，configs 为调用方可读的配置数量
+#[derive(Debug, Clone, Serialize)]
+pub struct NamespaceSummary {
+    pub name: String,
+    pub configs: usize,
+}
+
+// POST /api/configs 的请求体；未指定 format 时按 name 的扩展名识别
+#[derive(Debug, Deserialize)]
+pub struct CreateConfigRequest {
+    pub name: String,
+    pub content: String,
+    pub format: Option<String>,
+}
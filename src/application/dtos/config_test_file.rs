@@ -0,0 +1,72 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+// test 命令的断言文件（tests/*.yaml）：
+//   tests:
+//     - name: production database
+//       file: config/app.yaml
+//       profile: prod            # 叠加 config/app.prod.yaml
+//       env:
+//         APP_DATABASE_HOST: prod-db
+//       expect:
+//         - database.port == 5432
+//         - database.host == "prod-db"
+// file 相对于 configmanager.toml 所在目录，没有项目文件时相对于当前目录
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigTestFile {
+    pub tests: Vec<ConfigTestCase>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigTestCase {
+    pub name: Option<String>,
+    pub file: String,
+    pub profile: Option<String>,
+    // 值可以写成数字或布尔，按环境变量的字符串形式处理
+    #[serde(default)]
+    pub env: BTreeMap<String, serde_yaml::Value>,
+    pub expect: Expectations,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Expectations {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl Expectations {
+    pub fn to_vec(&self) -> Vec<String> {
+        match self {
+            Expectations::One(expression) => vec![expression.clone()],
+            Expectations::Many(expressions) => expressions.clone(),
+        }
+    }
+}
+
+impl ConfigTestCase {
+    pub fn name(&self) -> String {
+        self.name.clone().unwrap_or_else(|| match &self.profile {
+            Some(profile) => format!("{} ({})", self.file, profile),
+            None => self.file.clone(),
+        })
+    }
+
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        self.env
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    serde_yaml::Value::String(s) => s.clone(),
+                    other => serde_yaml::to_string(other)
+                        .map(|s| s.trim_end().to_string())
+                        .unwrap_or_default(),
+                };
+                (key.clone(), value)
+            })
+            .collect()
+    }
+}
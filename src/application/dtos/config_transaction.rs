@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::configuration::ConfigValue;
+
+// 暂存的一次键更新
+#[derive(Debug, Clone)]
+pub struct StagedChange {
+    pub file: String,
+    pub key: String,
+    pub value: ConfigValue,
+}
+
+// 📦 跨文件的批量修改，提交时要么全部生效要么全部不生效
+#[derive(Debug, Clone, Default)]
+pub struct ConfigTransaction {
+    pub changes: Vec<StagedChange>,
+}
+
+impl ConfigTransaction {
+    pub fn stage(&mut self, file: String, key: String, value: ConfigValue) {
+        self.changes.push(StagedChange { file, key, value });
+    }
+}
+
+// HTTP 请求体中的单个修改，value 为任意 JSON 值
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransactionChange {
+    pub file: String,
+    pub key: String,
+    pub value: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TransactionRequest {
+    pub changes: Vec<TransactionChange>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionResult {
+    pub changes: usize,
+    pub files: Vec<String>,
+}
@@ -0,0 +1,28 @@
+use serde::Serialize;
+
+use crate::{
+    application::dtos::startup_status::{StartupProgress, StartupState},
+    infrastructure::watchers::config_watcher::WatcherReport,
+};
+
+// /healthz 与 /readyz 的检查结果
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthReport {
+    // 配置目录可访问且监听器存活
+    pub healthy: bool,
+    // 健康且启动加载已完成
+    pub ready: bool,
+    pub config_dir: DirectoryCheck,
+    pub watcher: Option<WatcherReport>,
+    pub startup: StartupState,
+    // 启动加载的文件数，解析失败的文件不影响就绪
+    pub startup_progress: StartupProgress,
+    pub configs: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DirectoryCheck {
+    pub path: String,
+    pub accessible: bool,
+    pub error: Option<String>,
+}
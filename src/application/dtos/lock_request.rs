@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+// POST /api/configs/{name}/lock 与 POST /api/admin/freeze 的请求体，ttl_secs 未指定时直到手动解锁
+#[derive(Debug, Deserialize)]
+pub struct LockRequest {
+    pub owner: String,
+    #[serde(default)]
+    pub reason: String,
+    pub ttl_secs: Option<u64>,
+}
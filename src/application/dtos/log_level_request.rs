@@ -0,0 +1,14 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::shared::config::LogLevel;
+
+// PUT /api/admin/log-level 的请求体，例如 {"level": "info", "modules": {"hyper": "warn"}}
+#[derive(Deserialize)]
+pub struct LogLevelRequest {
+    #[serde(default)]
+    pub level: Option<LogLevel>, // 全局级别，未指定时使用启动时的 --log-filter
+    #[serde(default)]
+    pub modules: BTreeMap<String, LogLevel>, // 按模块（tracing target）覆盖的级别
+}
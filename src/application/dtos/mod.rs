@@ -1 +1,22 @@
-pub mod ws_query;
\ No newline at end of file
+pub mod audit_query;
+pub mod backup_request;
+pub mod bundle_query;
+pub mod capabilities;
+pub mod config_summary;
+pub mod config_query;
+pub mod config_status;
+pub mod config_test_file;
+pub mod config_transaction;
+pub mod health_report;
+pub mod lock_request;
+pub mod log_level_request;
+pub mod rebuild_status;
+pub mod rollout_request;
+pub mod rules_file;
+pub mod schedule_request;
+pub mod server_state;
+pub mod startup_status;
+pub mod ttl_request;
+pub mod validate_query;
+pub mod ws_message;
+pub mod ws_query;
@@ -0,0 +1,47 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RebuildState {
+    Idle,
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RebuildFailure {
+    pub file: String,
+    pub error: String,
+}
+
+// 📋 状态重建进度
+#[derive(Debug, Clone, Serialize)]
+pub struct RebuildStatus {
+    pub state: RebuildState,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub total: usize,
+    pub loaded: usize,
+    pub changed: usize,
+    pub removed: usize,
+    pub failures: Vec<RebuildFailure>,
+    pub error: Option<String>,
+}
+
+impl Default for RebuildStatus {
+    fn default() -> Self {
+        Self {
+            state: RebuildState::Idle,
+            started_at: None,
+            finished_at: None,
+            total: 0,
+            loaded: 0,
+            changed: 0,
+            removed: 0,
+            failures: vec![],
+            error: None,
+        }
+    }
+}
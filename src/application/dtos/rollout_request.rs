@@ -0,0 +1,12 @@
+use serde::Deserialize;
+
+use crate::domain::entities::rollout::RolloutTarget;
+
+// POST /api/configs/{name}/rollout/canary 的请求体：
+// {"content": "...", "percentage": 10} 或 {"content": "...", "selector": {"tier": "beta"}}
+#[derive(Debug, Deserialize)]
+pub struct RolloutRequest {
+    pub content: String,
+    #[serde(flatten)]
+    pub target: RolloutTarget,
+}
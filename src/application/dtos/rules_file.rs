@@ -0,0 +1,161 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use serde::{Deserialize, Deserializer, de::Error};
+
+use crate::domain::{
+    entities::validation_rule::{CompareOp, Severity},
+    value_objects::units::{parse_byte_size, parse_duration},
+};
+
+// v2 规则文件：带 version 字段的强类型格式，未知键和错误类型都会报出具体位置
+//   version: 2
+//   required_fields: [database.host]
+//   field_types:
+//     database.port: { type: number, min: 1, max: 65535 }
+//     server.timeout: { type: duration, max: 5m }
+//   severities: { database.port: warning }
+//   cross_field_rules:
+//     - { rule: compare, left: pool.min, op: "<=", right: pool.max }
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RulesFileV2 {
+    pub version: u32,
+    #[serde(default)]
+    pub required_fields: Vec<String>,
+    #[serde(default)]
+    pub field_types: BTreeMap<String, FieldRule>,
+    #[serde(default)]
+    pub severities: BTreeMap<String, Severity>,
+    #[serde(default)]
+    pub cross_field_rules: Vec<CrossFieldRuleSpec>,
+    #[serde(default)]
+    pub deprecated_fields: BTreeMap<String, DeprecatedFieldSpec>,
+    #[serde(default)]
+    pub deny_unknown_fields: bool,
+    #[serde(default)]
+    pub defaults: BTreeMap<String, serde_json::Value>,
+    pub custom_rules_dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case", deny_unknown_fields)]
+pub enum FieldRule {
+    String {
+        min_length: Option<usize>,
+        max_length: Option<usize>,
+        pattern: Option<String>,
+    },
+    Number {
+        min: Option<f64>,
+        max: Option<f64>,
+    },
+    Integer {
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    Boolean,
+    // min / max 写成 "30s"、"512MB" 这样的字符串
+    Duration {
+        #[serde(default, deserialize_with = "duration")]
+        min: Option<Duration>,
+        #[serde(default, deserialize_with = "duration")]
+        max: Option<Duration>,
+    },
+    ByteSize {
+        #[serde(default, deserialize_with = "byte_size")]
+        min: Option<u64>,
+        #[serde(default, deserialize_with = "byte_size")]
+        max: Option<u64>,
+    },
+    Url {
+        #[serde(default)]
+        schemes: Vec<String>,
+    },
+    IpAddr,
+    FilePath {
+        #[serde(default)]
+        must_exist: bool,
+    },
+    Enum {
+        allowed: Vec<serde_json::Value>,
+    },
+    Array {
+        items: Option<Box<FieldRule>>,
+        min_items: Option<usize>,
+        max_items: Option<usize>,
+        #[serde(default)]
+        unique: bool,
+    },
+    Object {
+        #[serde(default)]
+        fields: BTreeMap<String, FieldRule>,
+        #[serde(default)]
+        required: Vec<String>,
+        #[serde(default = "default_allow_unknown")]
+        allow_unknown: bool,
+    },
+}
+
+fn default_allow_unknown() -> bool {
+    true
+}
+
+// 数字按秒 / 字节处理，字符串需带合法单位
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum UnitValue {
+    Number(f64),
+    Text(String),
+}
+
+fn duration<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Duration>, D::Error> {
+    match UnitValue::deserialize(deserializer)? {
+        UnitValue::Number(seconds) => Duration::try_from_secs_f64(seconds).map_err(D::Error::custom),
+        UnitValue::Text(text) => {
+            parse_duration(&text).ok_or_else(|| D::Error::custom(format!("invalid duration: {}", text)))
+        }
+    }
+    .map(Some)
+}
+
+fn byte_size<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    match UnitValue::deserialize(deserializer)? {
+        UnitValue::Number(bytes) if bytes >= 0.0 && bytes.fract() == 0.0 => Ok(bytes as u64),
+        UnitValue::Number(bytes) => Err(D::Error::custom(format!("invalid byte size: {}", bytes))),
+        UnitValue::Text(text) => {
+            parse_byte_size(&text).ok_or_else(|| D::Error::custom(format!("invalid byte size: {}", text)))
+        }
+    }
+    .map(Some)
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case", deny_unknown_fields)]
+pub enum CrossFieldRuleSpec {
+    Requires {
+        when: String,
+        equals: Option<serde_json::Value>,
+        fields: Vec<String>,
+        #[serde(default)]
+        severity: Severity,
+    },
+    MutuallyExclusive {
+        fields: Vec<String>,
+        #[serde(default)]
+        severity: Severity,
+    },
+    Compare {
+        left: String,
+        op: CompareOp,
+        right: String,
+        #[serde(default)]
+        severity: Severity,
+    },
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DeprecatedFieldSpec {
+    pub replacement: Option<String>,
+    pub removed_in: Option<String>,
+}
@@ -0,0 +1,9 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+// POST /api/configs/{name}/schedule 的请求体：{"content": "...", "apply_at": "2030-01-01T00:00:00Z"}
+#[derive(Debug, Deserialize)]
+pub struct ScheduleRequest {
+    pub content: String,
+    pub apply_at: DateTime<Utc>,
+}
@@ -0,0 +1,43 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{
+    application::dtos::config_status::ConfigLoadState,
+    domain::entities::config_map::CacheMetrics,
+    infrastructure::watchers::config_watcher::WatcherReport,
+};
+
+// GET /api/admin/state：运行中服务的快照
+#[derive(Debug, Clone, Serialize)]
+pub struct ServerState {
+    pub started_at: DateTime<Utc>,
+    pub uptime_secs: i64,
+    pub configs: Vec<LoadedConfig>,
+    pub clients: Vec<ClientState>,
+    pub watcher: Option<WatcherReport>,
+    // 缓存的条目数与内存占用（原始字节与解析结果的估算）
+    pub cache: CacheMetrics,
+}
+
+// 缓存中的配置；version 为内容哈希（即 HTTP ETag），懒加载模式下未解析过的配置没有
+#[derive(Debug, Clone, Serialize)]
+pub struct LoadedConfig {
+    pub name: String,
+    pub version: Option<String>,
+    pub size: u64,
+    pub modified_at: DateTime<Utc>,
+    pub state: ConfigLoadState,
+}
+
+// 已连接的 TCP / WebSocket 订阅者，subscriptions 为订阅的文件及最后一次拿到最新配置的时间
+#[derive(Debug, Clone, Serialize)]
+pub struct ClientState {
+    pub client_id: String,
+    pub transport: String,
+    pub connected_at: DateTime<Utc>,
+    pub subscriptions: BTreeMap<String, Option<DateTime<Utc>>>,
+    pub bytes_pushed: u64,
+    pub messages_pushed: u64,
+}
@@ -0,0 +1,59 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::application::dtos::rebuild_status::RebuildFailure;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StartupState {
+    Pending,
+    Loading,
+    Ready,
+    Failed,
+}
+
+// 🚀 启动加载进度
+#[derive(Debug, Clone, Serialize)]
+pub struct StartupStatus {
+    pub state: StartupState,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub workers: usize,
+    pub total: usize,
+    pub loaded: usize,
+    pub failures: Vec<RebuildFailure>,
+    pub error: Option<String>,
+}
+
+impl Default for StartupStatus {
+    fn default() -> Self {
+        Self {
+            state: StartupState::Pending,
+            started_at: None,
+            finished_at: None,
+            workers: 0,
+            total: 0,
+            loaded: 0,
+            failures: vec![],
+            error: None,
+        }
+    }
+}
+
+// /readyz 中的启动加载进度
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct StartupProgress {
+    pub total: usize,
+    pub loaded: usize,
+    pub failed: usize,
+}
+
+impl StartupStatus {
+    pub fn progress(&self) -> StartupProgress {
+        StartupProgress {
+            total: self.total,
+            loaded: self.loaded,
+            failed: self.failures.len(),
+        }
+    }
+}
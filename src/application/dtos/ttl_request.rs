@@ -0,0 +1,10 @@
+use serde::Deserialize;
+
+// POST /api/configs/{name}/ttl 的请求体：{"key": "features.beta", "ttl_secs": 3600, "default": false}，
+// 不指定 key 时作用于整个配置，不指定 default 时到期删除
+#[derive(Debug, Deserialize)]
+pub struct TtlRequest {
+    pub key: Option<String>,
+    pub ttl_secs: u64,
+    pub default: Option<serde_json::Value>,
+}
@@ -0,0 +1,8 @@
+use serde::Deserialize;
+
+// POST /api/validate 的查询参数
+#[derive(Deserialize)]
+pub struct ValidateQuery {
+    pub format: Option<String>, // 请求体格式，未指定时自动识别
+    pub rules: Option<String>,  // rules/ 目录下的规则文件名
+}
@@ -0,0 +1,9 @@
+use serde::Deserialize;
+
+// 📨 WebSocket 客户端消息；key 为点分路径，未指定时订阅整个文件
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum WsClientMessage {
+    Subscribe { file: String, key: Option<String> },
+    Unsubscribe { file: String, key: Option<String> },
+}
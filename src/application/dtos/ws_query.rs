@@ -1,7 +1,29 @@
+use std::collections::BTreeMap;
+
 use serde::Deserialize;
 
 // 📋 WebSocket 查询参数
 #[derive(Deserialize)]
 pub struct WsQuery {
-    pub file: String, // 要监听的配置文件名
-}
\ No newline at end of file
+    pub file: Option<String>,   // 要监听的配置文件名，可以连接后再订阅
+    pub resume: Option<String>, // 断线重连时携带的恢复令牌
+    pub protocol: Option<u32>,  // 客户端期望的协议版本
+    pub labels: Option<String>, // 订阅者标签，如 region=eu,tier=beta，金丝雀发布按标签选择
+}
+
+impl WsQuery {
+    pub fn labels(&self) -> Result<BTreeMap<String, String>, String> {
+        let Some(labels) = self.labels.as_deref().filter(|labels| !labels.is_empty()) else {
+            return Ok(BTreeMap::new());
+        };
+        labels
+            .split(',')
+            .map(|label| match label.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => {
+                    Ok((key.trim().to_string(), value.trim().to_string()))
+                }
+                _ => Err(format!("invalid label '{}', expected key=value", label)),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,88 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tracing::debug;
+
+use crate::{
+    application::services::validation_service::ValidationService,
+    domain::entities::{
+        configuration::Config,
+        validation_rule::{Validation, ValidationResult},
+    },
+    shared::{app_state::AppState, error::ConfigError},
+};
+
+pub const ATTACHED_RULES_DIR: &str = "rules";
+pub const ATTACHED_RULES_SUFFIX: &str = ".rules.yaml";
+
+// serve 模式下附加在单个配置上的校验规则：与配置目录同级的 rules/ 目录中，
+// app.yaml.rules.yaml 约束 app.yaml。放在配置目录之外，避免规则文件本身被当作配置加载
+pub struct AttachedRulesService;
+
+impl AttachedRulesService {
+    pub fn rules_dir(config_path: &str) -> PathBuf {
+        let config_dir = std::fs::canonicalize(config_path)
+            .or_else(|_| std::path::absolute(config_path))
+            .unwrap_or_else(|_| PathBuf::from(config_path));
+        config_dir
+            .parent()
+            .unwrap_or(Path::new("/"))
+            .join(ATTACHED_RULES_DIR)
+    }
+
+    pub fn rules_file(config_path: &str, file: &str) -> PathBuf {
+        Self::rules_dir(config_path).join(format!("{}{}", file, ATTACHED_RULES_SUFFIX))
+    }
+
+    // 按名称加载 rules/ 目录下的规则文件，名称不能包含路径
+    pub fn load_named(config_path: &str, name: &str) -> Result<Validation, ConfigError> {
+        if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+            return Err(ConfigError::InvalidConfigPath(name.to_string()));
+        }
+        let rules_file = Self::rules_dir(config_path).join(name);
+        if !rules_file.is_file() {
+            return Err(ConfigError::ConfigNotFound(name.to_string()));
+        }
+        ValidationService::load_validation_file(&rules_file.to_string_lossy())
+    }
+
+    // 按指定的规则文件校验，未指定时使用服务端的全局规则；HTTP 与 TCP 的校验共用
+    pub fn validate(
+        app_state: &AppState,
+        config: &Config,
+        rules: Option<&str>,
+    ) -> Result<ValidationResult, ConfigError> {
+        let validation = match rules {
+            Some(rules) => Some(Arc::new(Self::load_named(&app_state.config_path(), rules)?)),
+            None => app_state.validation.clone(),
+        };
+        Ok(validation
+            .map(|validation| validation.validate(config))
+            .unwrap_or_default())
+    }
+
+    // 没有附加规则时直接通过；只有 error 级别的问题会拒绝配置
+    pub fn check(config_path: &str, file: &str, config: &Config) -> Result<(), ConfigError> {
+        let rules_file = Self::rules_file(config_path, file);
+        if !rules_file.is_file() {
+            return Ok(());
+        }
+        debug!(
+            "validate {} with attached rules {}",
+            file,
+            rules_file.display()
+        );
+        let validation = ValidationService::load_validation_file(&rules_file.to_string_lossy())?;
+        let result = validation.validate(config);
+        if result.is_valid {
+            Ok(())
+        } else {
+            Err(ConfigError::AttachedRulesViolation {
+                file: file.to_string(),
+                errors: result.errors.iter().map(|e| e.to_string()).collect(),
+            })
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::{
+    application::dtos::audit_query::AuditQuery,
+    domain::{
+        entities::{
+            audit::{AuditAction, AuditActor, AuditRecord},
+            configuration::Config,
+        },
+        services::config_diff::ConfigDiffService,
+    },
+    shared::{app_state::AppState, config::Interface, error::ConfigError},
+};
+
+// 客户端修改的审计：记录操作者以及修改前后的键级差异
+pub struct AuditService;
+
+impl AuditService {
+    // 新建时 before 为 None，删除时 after 为 None
+    pub fn record_change(
+        state: &AppState,
+        action: AuditAction,
+        config: &str,
+        actor: &AuditActor,
+        before: Option<&Config>,
+        after: Option<&Config>,
+        detail: Option<String>,
+    ) {
+        let empty = Config::new();
+        let changes = ConfigDiffService::diff(
+            config,
+            before.unwrap_or(&empty),
+            config,
+            after.unwrap_or(&empty),
+        )
+        .entries
+        .into_iter()
+        .map(Into::into)
+        .collect();
+        state.write_audit(AuditRecord {
+            id: state.id_generator.next_id("audit"),
+            timestamp: state.clock.now(),
+            action,
+            config: config.to_string(),
+            source: actor.source.clone(),
+            actor: actor.name(),
+            detail,
+            changes,
+        });
+    }
+
+    // 按配置名和时间范围过滤，只返回当前接口可见的配置的记录
+    pub fn filter(
+        state: &AppState,
+        interface: Interface,
+        records: Vec<AuditRecord>,
+        query: &AuditQuery,
+    ) -> Vec<AuditRecord> {
+        let mut records: Vec<AuditRecord> = records
+            .into_iter()
+            .filter(|record| query.matches(record))
+            .filter(|record| state.route(interface, &record.config).is_ok())
+            .collect();
+        if let Some(limit) = query.limit {
+            records.drain(..records.len().saturating_sub(limit));
+        }
+        records
+    }
+
+    // 未配置审计 sink 或 sink 不支持回读时返回错误
+    pub async fn read(state: &AppState) -> Result<Vec<AuditRecord>, ConfigError> {
+        let sink = state.audit_sink.clone();
+        match sink {
+            Some(sink) => sink.read().await.unwrap_or(Err(ConfigError::AuditNotReadable)),
+            None => Err(ConfigError::AuditNotReadable),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use crate::{
+    domain::entities::{
+        access_policy::{ANONYMOUS, AccessPolicy, Permission},
+        audit::AuditActor,
+    },
+    shared::{app_state::AppState, error::ConfigError, utils::read_file},
+};
+
+// 服务端管理接口（重建、审计、死信等）不属于某个配置，需要在 "*" 上拥有 admin 权限
+const ALL_CONFIGS: &str = "*";
+
+// HTTP 与 TCP 共用的授权检查，调用方身份为 API key 名称
+pub struct AuthorizationService;
+
+impl AuthorizationService {
+    // 策略文件为 YAML 或 JSON；模式无法解析或绑定了未定义的角色时拒绝加载
+    pub fn load_policy(path: &str) -> Result<AccessPolicy, ConfigError> {
+        let invalid = |error: String| ConfigError::InvalidPolicy {
+            path: path.to_string(),
+            error,
+        };
+        let policy: AccessPolicy =
+            serde_yaml::from_str(&read_file(path)?).map_err(|e| invalid(e.to_string()))?;
+        for (role, grants) in &policy.roles {
+            for grant in grants {
+                glob::Pattern::new(&grant.configs).map_err(|e| {
+                    invalid(format!("role {} has invalid pattern {:?}: {}", role, grant.configs, e))
+                })?;
+            }
+        }
+        for (subject, roles) in &policy.bindings {
+            if let Some(role) = roles.iter().find(|role| !policy.roles.contains_key(*role)) {
+                return Err(invalid(format!("{} is bound to unknown role {}", subject, role)));
+            }
+        }
+        Ok(policy)
+    }
+
+    // 未加载策略文件时不做限制
+    pub fn authorize(
+        state: &AppState,
+        actor: &AuditActor,
+        permission: Permission,
+        config: &str,
+    ) -> Result<(), ConfigError> {
+        let subject = actor.api_key.as_deref();
+        match &state.policy {
+            Some(policy) if !policy.allows(subject, permission, config) => {
+                tracing::debug!("{:?} denied {} on {}", subject, permission, config);
+                Err(ConfigError::AccessDenied {
+                    subject: subject.unwrap_or(ANONYMOUS).to_string(),
+                    permission: permission.to_string(),
+                    config: config.to_string(),
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn authorize_admin(state: &AppState, actor: &AuditActor) -> Result<(), ConfigError> {
+        Self::authorize(state, actor, Permission::Admin, ALL_CONFIGS)
+    }
+
+    // 只保留调用方有读权限的配置
+    pub fn readable(state: &AppState, actor: &AuditActor, configs: Vec<String>) -> Vec<String> {
+        configs
+            .into_iter()
+            .filter(|config| Self::authorize(state, actor, Permission::Read, config).is_ok())
+            .collect()
+    }
+}
@@ -0,0 +1,200 @@
+use std::{path::Path, sync::Arc};
+
+use chrono::SubsecRound;
+use tracing::{info, warn};
+
+use crate::{
+    application::services::{
+        audit_service::AuditService,
+        bundle_service::{BundleService, BundleVerification},
+    },
+    domain::{
+        entities::{
+            audit::{AuditAction, AuditActor},
+            backup::{BackupInfo, BackupPlan},
+        },
+        services::format_converter::FormatConverterService,
+        value_objects::{config_path::ConfigPath, namespace::Namespace},
+    },
+    shared::{
+        app_state::{AppState, wait_for_shutdown},
+        error::{BackupError, BundleError},
+    },
+};
+
+pub struct BackupService;
+
+impl BackupService {
+    // 按计划定时备份，直到服务关闭；单次失败只记录日志
+    pub async fn schedule(app_state: Arc<AppState>) {
+        let Some(plan) = app_state.backup.clone() else {
+            return;
+        };
+        info!("scheduled backups to {}", plan.store.id());
+        let mut shutdown = app_state.shutdown_receiver();
+        loop {
+            let now = app_state.clock.now();
+            let Some(next) = plan.schedule.next_after(now) else {
+                warn!("backup schedule has no upcoming run, scheduled backups stopped");
+                return;
+            };
+            let delay = (next - now).to_std().unwrap_or_default();
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => {}
+                _ = wait_for_shutdown(&mut shutdown) => return,
+            }
+            if let Err(e) = Self::backup(&app_state).await {
+                warn!("scheduled backup to {} failed: {}", plan.store.id(), e);
+            }
+        }
+    }
+
+    // 立即备份一次，随后按保留策略清理旧备份
+    pub async fn backup(app_state: &AppState) -> Result<BackupInfo, BackupError> {
+        let plan = Self::plan(app_state)?;
+        let config_path = app_state.config_path();
+        let namespace = plan.namespace.clone();
+        let content = tokio::task::spawn_blocking(move || {
+            let mut content = Vec::new();
+            BundleService::export(&config_path, namespace.as_ref(), &mut content, None)
+                .map(|_| content)
+        })
+        .await
+        .map_err(|e| BackupError::Storage(e.to_string()))??;
+
+        // 名称中的时间精确到毫秒，与列举时解析出的时间保持一致
+        let now = app_state.clock.now().trunc_subsecs(3);
+        let backup = BackupInfo {
+            name: BackupInfo::name_for(plan.prefix(), now),
+            created_at: now,
+            size: content.len() as u64,
+        };
+        plan.store.put(&backup.name, content).await?;
+        info!("backup {} written to {}", backup.name, plan.store.id());
+
+        let backups = plan.store.list().await?;
+        for expired in plan.retention.expired(&backups, now) {
+            match plan.store.delete(&expired.name).await {
+                Ok(()) => info!("backup {} expired and was deleted", expired.name),
+                Err(e) => warn!("failed to delete expired backup {}: {}", expired.name, e),
+            }
+        }
+        Ok(backup)
+    }
+
+    pub async fn list(app_state: &AppState) -> Result<Vec<BackupInfo>, BackupError> {
+        Self::plan(app_state)?.store.list().await
+    }
+
+    // 恢复指定备份，未指定时恢复最近一份；备份中的配置覆盖现有文件，备份之外的配置保留
+    pub async fn restore(
+        app_state: &AppState,
+        name: Option<&str>,
+        actor: &AuditActor,
+    ) -> Result<Vec<String>, BackupError> {
+        let plan = Self::plan(app_state)?;
+        let name = match name {
+            Some(name) => name.to_string(),
+            None => match plan.store.list().await?.pop() {
+                Some(latest) => latest.name,
+                None => {
+                    return Err(BackupError::NotFound {
+                        name: "latest".to_string(),
+                    });
+                }
+            },
+        };
+        let content = plan.store.get(&name).await?;
+        let verification = BundleService::verify_archive(content.as_slice(), None, true)?;
+        if !verification.is_valid() {
+            return Err(BackupError::Invalid {
+                name,
+                errors: Self::errors(&verification),
+            });
+        }
+        let namespace = plan.namespace.clone();
+        let restored = Self::import(
+            app_state,
+            &verification,
+            namespace.as_ref(),
+            actor,
+            &format!("restore backup {}", name),
+        )
+        .await?;
+        info!("backup {} restored: {} configs", name, restored.len());
+        Ok(restored)
+    }
+
+    // 把校验通过的 bundle 写入配置目录并更新内存中的配置，按接口写入处理：
+    // 记录审计并推送给订阅者
+    pub async fn import(
+        app_state: &AppState,
+        verification: &BundleVerification,
+        namespace: Option<&Namespace>,
+        actor: &AuditActor,
+        note: &str,
+    ) -> Result<Vec<String>, BackupError> {
+        let _writes = app_state.lock_writes().await;
+        let config_path = app_state.config_path();
+        let imported = BundleService::import(verification, &config_path, namespace)?;
+        for name in imported.iter() {
+            let content = tokio::fs::read_to_string(Path::new(&config_path).join(name)).await?;
+            // 校验阶段已解析过，这里只是填充默认值后放入内存
+            let Ok(mut loaded) =
+                FormatConverterService::new(ConfigPath::new(name.clone()).unwrap(), content)
+                    .validate_config()
+            else {
+                continue;
+            };
+            if let Some(validation) = app_state.validation.clone() {
+                validation
+                    .apply_defaults(&mut loaded)
+                    .map_err(BundleError::from)?;
+            }
+            let before = app_state.config_map.get(name);
+            let action = if before.is_some() {
+                AuditAction::Update
+            } else {
+                AuditAction::Create
+            };
+            AuditService::record_change(
+                app_state,
+                action,
+                name,
+                actor,
+                before.as_ref(),
+                Some(&loaded),
+                Some(note.to_string()),
+            );
+            app_state.store_write(name.clone(), loaded, &actor.source);
+        }
+        Ok(imported)
+    }
+
+    // 校验失败的文件及原因，格式为 "<文件>: <错误>"
+    pub fn errors(verification: &BundleVerification) -> Vec<String> {
+        verification
+            .reports
+            .iter()
+            .filter(|report| !report.is_valid())
+            .map(|report| match (&report.parse_error, &report.result) {
+                (Some(e), _) => format!("{}: {}", report.file, e),
+                (None, Some(result)) => format!(
+                    "{}: {}",
+                    report.file,
+                    result
+                        .errors
+                        .iter()
+                        .map(|e| e.to_string())
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                ),
+                (None, None) => report.file.clone(),
+            })
+            .collect()
+    }
+
+    fn plan(app_state: &AppState) -> Result<Arc<BackupPlan>, BackupError> {
+        app_state.backup.clone().ok_or(BackupError::NotConfigured)
+    }
+}
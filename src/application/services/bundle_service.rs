@@ -0,0 +1,509 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::Sha256;
+use tracing::{debug, warn};
+
+use crate::{
+    application::services::{
+        attached_rules_service::{ATTACHED_RULES_SUFFIX, AttachedRulesService},
+        validation_service::{FileValidationReport, ValidationService},
+    },
+    domain::{
+        entities::{
+            bundle::{BundleEntry, BundleEntryKind, BundleManifest, MANIFEST_FILE, SIGNATURE_FILE},
+            configuration::Config,
+        },
+        services::format_converter::FormatConverterService,
+        value_objects::{config_path::ConfigPath, namespace::Namespace},
+    },
+    infrastructure::repositories::file_config_repository::FileConfigRepository,
+    shared::{
+        error::{BundleError, ConfigError},
+        utils::{expand_config_paths, from_hex, is_valid_config_name, sha256_hex, to_hex},
+    },
+};
+
+pub struct BundleVerification {
+    pub manifest: BundleManifest,
+    pub signed: bool,
+    pub reports: Vec<FileValidationReport>,
+    files: HashMap<String, Vec<u8>>,
+}
+
+impl BundleVerification {
+    pub fn is_valid(&self) -> bool {
+        self.reports.iter().all(|r| r.is_valid())
+    }
+}
+
+pub struct BundleService;
+
+impl BundleService {
+    pub fn create(
+        configs_dir: &str,
+        schemas_dir: Option<&str>,
+        policies_dir: Option<&str>,
+        output: &str,
+        key: Option<&[u8]>,
+    ) -> Result<BundleManifest, BundleError> {
+        let mut manifest = BundleManifest::new();
+        let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+
+        let sources = [
+            (Some(configs_dir), BundleEntryKind::Config),
+            (schemas_dir, BundleEntryKind::Schema),
+            (policies_dir, BundleEntryKind::Policy),
+        ];
+        for (dir, kind) in sources {
+            let Some(dir) = dir else { continue };
+            for file in expand_config_paths(&[dir.to_string()])? {
+                let relative = Path::new(&file)
+                    .strip_prefix(dir)
+                    .map(|p| p.to_string_lossy().to_string())
+                    .unwrap_or_else(|_| file.clone());
+                let content = std::fs::read(&file)?;
+                let path = format!("{}/{}", kind.dir(), relative);
+                debug!("bundle add: {} -> {}", file, path);
+                manifest.entries.push(BundleEntry {
+                    path: path.clone(),
+                    kind,
+                    sha256: sha256_hex(&content),
+                    size: content.len() as u64,
+                });
+                files.push((path, content));
+            }
+        }
+
+        Self::write_archive(std::fs::File::create(output)?, &manifest, files, key)?;
+        Ok(manifest)
+    }
+
+    // 导出 serve 配置目录（或其中一个命名空间）下的全部配置，以及 rules/ 目录中对应的附加规则；
+    // 附加规则作为 schema 打包，verify 时据此校验同名配置
+    pub fn export<W: Write>(
+        config_path: &str,
+        namespace: Option<&Namespace>,
+        writer: W,
+        key: Option<&[u8]>,
+    ) -> Result<BundleManifest, BundleError> {
+        let repository = FileConfigRepository::new(config_path.to_string());
+        let root = match namespace {
+            Some(namespace) => Path::new(config_path).join(namespace.as_str()),
+            None => PathBuf::from(config_path),
+        };
+        let mut manifest = BundleManifest::new();
+        manifest.namespace = namespace.map(|namespace| namespace.as_str().to_string());
+        let mut files: Vec<(String, Vec<u8>)> = Vec::new();
+        let dir = root.to_string_lossy().to_string();
+        let configs = if root.is_dir() {
+            expand_config_paths(&[dir])?
+        } else {
+            vec![]
+        };
+        for file in configs {
+            // 隐藏目录（如 .history）中的文件不是配置
+            let Some(name) = repository.config_name(Path::new(&file)) else {
+                continue;
+            };
+            let relative = match namespace {
+                Some(namespace) => namespace.file(&name).unwrap_or(&name).to_string(),
+                None => name.clone(),
+            };
+            let rules = AttachedRulesService::rules_file(config_path, &name);
+            let mut sources = vec![(BundleEntryKind::Config, PathBuf::from(&file))];
+            if rules.is_file() {
+                sources.push((BundleEntryKind::Schema, rules));
+            }
+            for (kind, source) in sources {
+                let content = std::fs::read(&source)?;
+                let path = match kind {
+                    BundleEntryKind::Schema => {
+                        format!("{}/{}{}", kind.dir(), relative, ATTACHED_RULES_SUFFIX)
+                    }
+                    _ => format!("{}/{}", kind.dir(), relative),
+                };
+                debug!("bundle export: {:?} -> {}", source, path);
+                manifest.entries.push(BundleEntry {
+                    path: path.clone(),
+                    kind,
+                    sha256: sha256_hex(&content),
+                    size: content.len() as u64,
+                });
+                files.push((path, content));
+            }
+        }
+        Self::write_archive(writer, &manifest, files, key)?;
+        Ok(manifest)
+    }
+
+    // 校验签名、清单哈希，并对包内配置执行解析与规则校验（无需网络）；
+    // 没有密钥时只有 allow_unsigned 才接受，包内带签名时记录未校验的警告
+    pub fn verify(
+        bundle: &str,
+        key: Option<&[u8]>,
+        allow_unsigned: bool,
+    ) -> Result<BundleVerification, BundleError> {
+        Self::verify_archive(std::fs::File::open(bundle)?, key, allow_unsigned)
+    }
+
+    pub fn verify_archive<R: Read>(
+        reader: R,
+        key: Option<&[u8]>,
+        allow_unsigned: bool,
+    ) -> Result<BundleVerification, BundleError> {
+        let mut files = Self::read_archive(reader)?;
+
+        let manifest_bytes = files
+            .remove(MANIFEST_FILE)
+            .ok_or(BundleError::ManifestMissing)?;
+        let manifest: BundleManifest =
+            serde_json::from_slice(&manifest_bytes).map_err(|_| BundleError::InvalidManifest)?;
+
+        let signature = files.remove(SIGNATURE_FILE);
+        match (key, &signature) {
+            (Some(key), Some(signature)) => Self::verify_signature(key, &manifest_bytes, signature)?,
+            (Some(_), None) => return Err(BundleError::SignatureMissing),
+            (None, _) if !allow_unsigned => return Err(BundleError::KeyRequired),
+            (None, Some(_)) => warn!("bundle is signed but no key was given, signature not checked"),
+            (None, None) => {}
+        }
+
+        for entry in manifest.entries.iter() {
+            let content = files
+                .get(&entry.path)
+                .ok_or_else(|| BundleError::MissingEntry {
+                    path: entry.path.clone(),
+                })?;
+            if sha256_hex(content) != entry.sha256 || content.len() as u64 != entry.size {
+                return Err(BundleError::HashMismatch {
+                    path: entry.path.clone(),
+                });
+            }
+        }
+        if let Some(path) = files
+            .keys()
+            .find(|path| !manifest.entries.iter().any(|e| &e.path == *path))
+        {
+            return Err(BundleError::UnexpectedEntry { path: path.clone() });
+        }
+
+        let mut reports = Vec::new();
+        for entry in manifest.configs() {
+            let config = Self::parse_entry(&entry.path, &files[&entry.path]);
+            let schema_path = format!(
+                "{}/{}{}",
+                BundleEntryKind::Schema.dir(),
+                Self::relative_path(entry),
+                ATTACHED_RULES_SUFFIX
+            );
+            let validation = match files.get(&schema_path) {
+                Some(content) => Some(
+                    Self::parse_entry(&schema_path, content)
+                        .and_then(|c| ValidationService::get_validation_by_config(&c))?,
+                ),
+                None => None,
+            };
+            reports.push(ValidationService::validate_loaded(
+                &entry.path,
+                config,
+                validation.as_ref(),
+            ));
+        }
+
+        Ok(BundleVerification {
+            manifest,
+            signed: key.is_some(),
+            reports,
+            files,
+        })
+    }
+
+    // 校验通过后将配置、规则和策略写入目标目录
+    pub fn apply(verification: &BundleVerification, target: &str) -> Result<usize, BundleError> {
+        let target = Path::new(target);
+        // 先检查全部路径，清单中带 ".."、绝对路径的条目会写到目标目录之外
+        if let Some(entry) = verification
+            .manifest
+            .entries
+            .iter()
+            .find(|entry| !Self::is_contained(Self::relative_path(entry)))
+        {
+            return Err(BundleError::InvalidEntryPath {
+                path: entry.path.clone(),
+            });
+        }
+        let mut written = 0;
+        for entry in verification.manifest.entries.iter() {
+            let relative = Self::relative_path(entry);
+            let destination: PathBuf = match entry.kind {
+                BundleEntryKind::Config => target.join(relative),
+                BundleEntryKind::Schema => target.join("rules").join(relative),
+                BundleEntryKind::Policy => target.join("policies").join(relative),
+            };
+            if let Some(parent) = destination.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&destination, &verification.files[&entry.path])?;
+            debug!("bundle apply: {} -> {:?}", entry.path, destination);
+            written += 1;
+        }
+        Ok(written)
+    }
+
+    // 把校验通过的 bundle 导入 serve 配置目录：配置写入目录（或命名空间子目录），schema 作为
+    // 附加规则写入 rules/，策略写入与配置目录同级的 policies/；返回导入的配置名称
+    pub fn import(
+        verification: &BundleVerification,
+        config_path: &str,
+        namespace: Option<&Namespace>,
+    ) -> Result<Vec<String>, BundleError> {
+        let config_root = Path::new(config_path);
+        let rules_root = AttachedRulesService::rules_dir(config_path);
+        let policies_root = rules_root.with_file_name("policies");
+        // 先检查全部路径，不允许写到目标目录之外
+        if let Some(entry) = verification
+            .manifest
+            .entries
+            .iter()
+            .find(|entry| !is_valid_config_name(Self::relative_path(entry)))
+        {
+            return Err(BundleError::InvalidEntryPath {
+                path: entry.path.clone(),
+            });
+        }
+        let mut imported = Vec::new();
+        for entry in verification.manifest.entries.iter() {
+            let relative = Self::relative_path(entry);
+            let name = match namespace {
+                Some(namespace) => namespace.key(relative),
+                None => relative.to_string(),
+            };
+            let destination = match entry.kind {
+                BundleEntryKind::Config => config_root.join(&name),
+                BundleEntryKind::Schema => rules_root.join(&name),
+                BundleEntryKind::Policy => policies_root.join(relative),
+            };
+            if let Some(parent) = destination.parent() {
+                std::fs::create_dir_all(parent)?;
+            }
+            std::fs::write(&destination, &verification.files[&entry.path])?;
+            debug!("bundle import: {} -> {:?}", entry.path, destination);
+            if entry.kind == BundleEntryKind::Config {
+                imported.push(name);
+            }
+        }
+        Ok(imported)
+    }
+
+    pub fn read_key_file(path: &str) -> Result<Vec<u8>, BundleError> {
+        let key = std::fs::read_to_string(path)?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(BundleError::InvalidKey);
+        }
+        Ok(key.as_bytes().to_vec())
+    }
+
+    fn relative_path(entry: &BundleEntry) -> &str {
+        entry
+            .path
+            .strip_prefix(entry.kind.dir())
+            .map(|p| p.trim_start_matches('/'))
+            .unwrap_or(&entry.path)
+    }
+
+    fn parse_entry(path: &str, content: &[u8]) -> Result<Config, ConfigError> {
+        let content =
+            String::from_utf8(content.to_vec()).map_err(|_| ConfigError::ParseConfigError)?;
+        FormatConverterService::new(ConfigPath::new(path)?, content).validate_config()
+    }
+
+    fn sign(key: &[u8], data: &[u8]) -> Result<String, BundleError> {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(key).map_err(|_| BundleError::InvalidKey)?;
+        mac.update(data);
+        Ok(to_hex(&mac.finalize().into_bytes()))
+    }
+
+    // 常量时间比较，签名文件为十六进制的 HMAC-SHA256
+    fn verify_signature(key: &[u8], data: &[u8], signature: &[u8]) -> Result<(), BundleError> {
+        let signature = std::str::from_utf8(signature)
+            .ok()
+            .and_then(|signature| from_hex(signature.trim()))
+            .ok_or(BundleError::SignatureMismatch)?;
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(key).map_err(|_| BundleError::InvalidKey)?;
+        mac.update(data);
+        mac.verify_slice(&signature).map_err(|_| BundleError::SignatureMismatch)
+    }
+
+    // 相对路径只由普通的路径段组成
+    fn is_contained(relative: &str) -> bool {
+        is_valid_config_name(relative)
+            && Path::new(relative)
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+    }
+
+    fn append<W: std::io::Write>(
+        builder: &mut tar::Builder<W>,
+        path: &str,
+        content: &[u8],
+    ) -> Result<(), BundleError> {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_mtime(chrono::Utc::now().timestamp() as u64);
+        header.set_cksum();
+        builder.append_data(&mut header, path, content)?;
+        Ok(())
+    }
+
+    fn write_archive<W: Write>(
+        writer: W,
+        manifest: &BundleManifest,
+        files: Vec<(String, Vec<u8>)>,
+        key: Option<&[u8]>,
+    ) -> Result<(), BundleError> {
+        let manifest_bytes =
+            serde_json::to_vec_pretty(manifest).map_err(|_| BundleError::InvalidManifest)?;
+
+        let encoder = GzEncoder::new(writer, Compression::default());
+        let mut builder = tar::Builder::new(encoder);
+        Self::append(&mut builder, MANIFEST_FILE, &manifest_bytes)?;
+        if let Some(key) = key {
+            let signature = Self::sign(key, &manifest_bytes)?;
+            Self::append(&mut builder, SIGNATURE_FILE, signature.as_bytes())?;
+        }
+        for (path, content) in files {
+            Self::append(&mut builder, &path, &content)?;
+        }
+        builder.into_inner()?.finish()?;
+        Ok(())
+    }
+
+    fn read_archive<R: Read>(reader: R) -> Result<HashMap<String, Vec<u8>>, BundleError> {
+        let decoder = GzDecoder::new(reader);
+        let mut archive = tar::Archive::new(decoder);
+        let mut files = HashMap::new();
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = entry.path()?.to_string_lossy().to_string();
+            let mut content = Vec::new();
+            entry.read_to_end(&mut content)?;
+            files.insert(path, content);
+        }
+        Ok(files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KEY: &[u8] = b"bundle-key";
+
+    // 只含一个配置条目的 bundle；tar::Builder 拒绝带 ".." 的路径，条目名直接写入头部，模拟手工构造的归档
+    fn archive(path: &str, key: Option<&[u8]>) -> Vec<u8> {
+        let content = br#"{"enabled": true}"#.to_vec();
+        let mut manifest = BundleManifest::new();
+        manifest.entries.push(BundleEntry {
+            path: path.to_string(),
+            kind: BundleEntryKind::Config,
+            sha256: sha256_hex(&content),
+            size: content.len() as u64,
+        });
+        let manifest_bytes = serde_json::to_vec(&manifest).unwrap();
+        let mut files = vec![(MANIFEST_FILE.to_string(), manifest_bytes.clone())];
+        if let Some(key) = key {
+            let signature = BundleService::sign(key, &manifest_bytes).unwrap();
+            files.push((SIGNATURE_FILE.to_string(), signature.into_bytes()));
+        }
+        files.push((path.to_string(), content));
+
+        let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+        for (name, content) in files {
+            let mut header = tar::Header::new_gnu();
+            header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name.as_bytes());
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder.append(&header, content.as_slice()).unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bundle-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn signed_bundle_requires_matching_key() {
+        let bundle = archive("configs/app.json", Some(KEY));
+        let verification = BundleService::verify_archive(bundle.as_slice(), Some(KEY), false).unwrap();
+        assert!(verification.signed);
+        assert!(matches!(
+            BundleService::verify_archive(bundle.as_slice(), Some(b"other-key"), false),
+            Err(BundleError::SignatureMismatch)
+        ));
+        assert!(matches!(
+            BundleService::verify_archive(bundle.as_slice(), None, false),
+            Err(BundleError::KeyRequired)
+        ));
+        assert!(BundleService::verify_archive(bundle.as_slice(), None, true).is_ok());
+    }
+
+    #[test]
+    fn unsigned_bundle_needs_allow_unsigned() {
+        let bundle = archive("configs/app.json", None);
+        assert!(matches!(
+            BundleService::verify_archive(bundle.as_slice(), Some(KEY), false),
+            Err(BundleError::SignatureMissing)
+        ));
+        assert!(matches!(
+            BundleService::verify_archive(bundle.as_slice(), None, false),
+            Err(BundleError::KeyRequired)
+        ));
+        assert!(BundleService::verify_archive(bundle.as_slice(), None, true).is_ok());
+    }
+
+    #[test]
+    fn apply_rejects_entries_outside_target() {
+        let dir = temp_dir("traversal");
+        let target = dir.join("target");
+        let absolute = dir.join("absolute.json").display().to_string();
+        for path in ["configs/../escape.json", "configs/a/../../escape.json", &absolute] {
+            let bundle = archive(path, None);
+            let verification = BundleService::verify_archive(bundle.as_slice(), None, true).unwrap();
+            assert!(matches!(
+                BundleService::apply(&verification, target.to_str().unwrap()),
+                Err(BundleError::InvalidEntryPath { .. })
+            ));
+        }
+        assert!(!dir.join("escape.json").exists());
+        assert!(!dir.join("absolute.json").exists());
+        assert!(!target.exists());
+
+        let bundle = archive("configs/team-a/app.json", None);
+        let verification = BundleService::verify_archive(bundle.as_slice(), None, true).unwrap();
+        assert_eq!(BundleService::apply(&verification, target.to_str().unwrap()).unwrap(), 1);
+        assert!(target.join("team-a/app.json").is_file());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn from_hex_rejects_malformed_input() {
+        assert_eq!(from_hex("00ff"), Some(vec![0, 255]));
+        assert_eq!(from_hex("0"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+}
@@ -0,0 +1,328 @@
+use std::path::{Path, PathBuf};
+
+use colored::{Color, Colorize};
+use serde::Serialize;
+use serde_json::json;
+use tracing::debug;
+
+use crate::{
+    application::services::{drift_service::DriftService, validation_service::ValidationService},
+    domain::{
+        entities::validation_rule::{Severity, Validation},
+        services::{
+            config_lint::{ConfigLintService, LintRules},
+            format_converter::FormatConverterService,
+            schema_drift::SchemaDriftService,
+        },
+        value_objects::config_path::ConfigPath,
+    },
+    shared::{
+        config::{PROJECT_FILE, ProjectSettings},
+        error::ConfigError,
+        utils::{expand_config_paths, is_config_file, read_file, relative_to_cwd},
+    },
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStage {
+    Parse,
+    Lint,
+    Validate,
+    Schema,
+}
+
+impl std::fmt::Display for CheckStage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let stage = match self {
+            CheckStage::Parse => "parse",
+            CheckStage::Lint => "lint",
+            CheckStage::Validate => "validate",
+            CheckStage::Schema => "schema",
+        };
+        f.pad(stage)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct CheckIssue {
+    pub stage: CheckStage,
+    pub severity: Severity,
+    pub rule: String,
+    pub field: Option<String>,
+    pub message: String,
+}
+
+// 单个文件在所有检查阶段中发现的问题
+#[derive(Debug, Serialize)]
+pub struct FileCheckReport {
+    pub file: String,
+    pub issues: Vec<CheckIssue>,
+}
+
+impl FileCheckReport {
+    pub fn is_passed(&self) -> bool {
+        self.issues.iter().all(|issue| issue.severity != Severity::Error)
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        self.issues.iter().any(|issue| issue.severity == Severity::Warning)
+    }
+
+    fn issue(
+        &mut self,
+        stage: CheckStage,
+        severity: Severity,
+        rule: &str,
+        field: Option<String>,
+        message: String,
+    ) {
+        self.issues.push(CheckIssue {
+            stage,
+            severity,
+            rule: rule.to_string(),
+            field,
+            message,
+        });
+    }
+}
+
+pub struct CheckOptions {
+    pub lint: LintRules,
+    pub validation: Option<Validation>,
+    pub schema: Option<serde_json::Value>,
+    pub strict: bool,
+}
+
+// 面向 pre-commit 的一站式检查：解析、lint、校验规则和 schema，由 configmanager.toml 配置
+pub struct CheckService;
+
+impl CheckService {
+    // 显式指定的项目文件优先，否则从当前目录向上查找；都没有时使用默认设置
+    pub fn load_project(project: Option<&str>) -> Result<(PathBuf, ProjectSettings), ConfigError> {
+        let path = match project {
+            Some(path) => Some(PathBuf::from(path)),
+            None => ProjectSettings::discover(Path::new(".")),
+        };
+        match path {
+            Some(path) => {
+                debug!("check project: {}", path.display());
+                let settings = ProjectSettings::load(&path)?;
+                let root = path
+                    .parent()
+                    .filter(|p| !p.as_os_str().is_empty())
+                    .unwrap_or(Path::new("."));
+                Ok((relative_to_cwd(root), settings))
+            }
+            None => Ok((PathBuf::from("."), ProjectSettings::default())),
+        }
+    }
+
+    pub fn options(
+        root: &Path,
+        settings: &ProjectSettings,
+        strict: bool,
+    ) -> Result<CheckOptions, ConfigError> {
+        let validation = match &settings.rules {
+            Some(rules) => Some(ValidationService::load_validation_file(
+                &root.join(rules).to_string_lossy(),
+            )?),
+            None => None,
+        };
+        let schema = match &settings.schema {
+            Some(schema) => Some(DriftService::load_schema(&root.join(schema).to_string_lossy())?),
+            None => None,
+        };
+        Ok(CheckOptions {
+            lint: settings.lint.rules(),
+            validation,
+            schema,
+            strict: strict || settings.strict,
+        })
+    }
+
+    // 命令行给出的文件（pre-commit 传入的暂存文件）优先，否则展开项目的 files；
+    // 非配置文件、exclude 命中的文件以及项目自身引用的规则和 schema 文件都会被跳过
+    pub fn select_files(
+        root: &Path,
+        settings: &ProjectSettings,
+        files: &[String],
+    ) -> Result<Vec<String>, ConfigError> {
+        let inputs: Vec<String> = if files.is_empty() {
+            settings
+                .files
+                .iter()
+                .map(|pattern| Self::join(root, pattern))
+                .collect()
+        } else {
+            files.to_vec()
+        };
+        let excludes = settings
+            .exclude
+            .iter()
+            .map(|pattern| {
+                glob::Pattern::new(pattern)
+                    .map(|compiled| (compiled, pattern.clone()))
+                    .map_err(|e| ConfigError::InvalidGlobPattern(e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let root = Self::canonical(root);
+        let reserved: Vec<PathBuf> = [
+            Some(PROJECT_FILE),
+            settings.rules.as_deref(),
+            settings.schema.as_deref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|file| Self::canonical(&root.join(file)))
+        .collect();
+
+        Ok(expand_config_paths(&inputs)?
+            .into_iter()
+            .filter(|file| is_config_file(file))
+            .filter(|file| {
+                let path = Self::canonical(Path::new(file));
+                if reserved.contains(&path) {
+                    return false;
+                }
+                let relative = path.strip_prefix(&root).unwrap_or(&path);
+                !excludes.iter().any(|(pattern, raw)| {
+                    pattern.matches_path(relative) || relative.starts_with(raw)
+                })
+            })
+            .collect())
+    }
+
+    fn join(root: &Path, pattern: &str) -> String {
+        if root == Path::new(".") || Path::new(pattern).is_absolute() {
+            pattern.to_string()
+        } else {
+            root.join(pattern).to_string_lossy().to_string()
+        }
+    }
+
+    fn canonical(path: &Path) -> PathBuf {
+        std::fs::canonicalize(path)
+            .or_else(|_| std::path::absolute(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    }
+
+    pub fn check_files(files: &[String], options: &CheckOptions) -> Vec<FileCheckReport> {
+        files.iter().map(|file| Self::check_file(file, options)).collect()
+    }
+
+    fn check_file(file: &str, options: &CheckOptions) -> FileCheckReport {
+        debug!("check: {}", file);
+        let mut report = FileCheckReport {
+            file: file.to_string(),
+            issues: Vec::new(),
+        };
+        let loaded = read_file(file).and_then(|content| {
+            let config = FormatConverterService::new(ConfigPath::new(file)?, content.clone())
+                .validate_config()?;
+            Ok((content, config))
+        });
+        // 解析失败时后续阶段没有意义
+        let (content, config) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                report.issue(CheckStage::Parse, Severity::Error, "ParseError", None, e.to_string());
+                return report;
+            }
+        };
+
+        for finding in ConfigLintService::lint(&content, &config, &options.lint) {
+            report.issue(CheckStage::Lint, Severity::Error, finding.rule, finding.key, finding.message);
+        }
+
+        if let Some(validation) = &options.validation {
+            let mut result = validation.validate(&config);
+            if options.strict {
+                result = result.strict();
+            }
+            for (severity, errors) in [
+                (Severity::Error, &result.errors),
+                (Severity::Warning, &result.warnings),
+                (Severity::Info, &result.infos),
+            ] {
+                for e in errors {
+                    report.issue(
+                        CheckStage::Validate,
+                        severity,
+                        e.kind(),
+                        e.field().map(str::to_string),
+                        e.to_string(),
+                    );
+                }
+            }
+        }
+
+        // 缺失的可选字段不算问题，完整的差异由 drift 命令给出
+        if let Some(schema) = &options.schema {
+            let drift = SchemaDriftService::compare(schema, &config);
+            for key in drift.unused_keys {
+                let message = format!("key {} is not in schema", key);
+                report.issue(CheckStage::Schema, Severity::Error, "unused-key", Some(key), message);
+            }
+            for field in drift.missing_fields.into_iter().filter(|f| f.required) {
+                let message = format!("required field {} is missing", field.path);
+                let path = Some(field.path);
+                report.issue(CheckStage::Schema, Severity::Error, "missing-field", path, message);
+            }
+            for mismatch in drift.type_mismatches {
+                let message = format!(
+                    "field {} should be {}, got {}",
+                    mismatch.path, mismatch.expected, mismatch.actual
+                );
+                let path = Some(mismatch.path);
+                report.issue(CheckStage::Schema, Severity::Error, "type-mismatch", path, message);
+            }
+        }
+        report
+    }
+
+    pub fn to_json(reports: &[FileCheckReport]) -> serde_json::Value {
+        let failed = reports.iter().filter(|r| !r.is_passed()).count();
+        let warned = reports.iter().filter(|r| r.is_passed() && r.has_warnings()).count();
+        json!({
+            "summary": {
+                "total": reports.len(),
+                "passed": reports.len() - failed - warned,
+                "warnings": warned,
+                "failed": failed,
+            },
+            "files": reports,
+        })
+    }
+
+    pub fn print_summary(reports: &[FileCheckReport]) {
+        for report in reports {
+            let status = if !report.is_passed() {
+                "FAIL".color(Color::Red)
+            } else if report.has_warnings() {
+                "WARN".color(Color::Yellow)
+            } else {
+                "PASS".color(Color::Green)
+            };
+            println!("{:<6}  {}", status, report.file);
+            for issue in report.issues.iter() {
+                let severity = match issue.severity {
+                    Severity::Error => "error".color(Color::Red),
+                    Severity::Warning => "warning".color(Color::Yellow),
+                    Severity::Info => "info".color(Color::Cyan),
+                };
+                println!("  {:<7}  {:<8}  {}", severity, issue.stage, issue.message);
+            }
+        }
+
+        let failed = reports.iter().filter(|r| !r.is_passed()).count();
+        let warned = reports.iter().filter(|r| r.is_passed() && r.has_warnings()).count();
+        println!(
+            "\n{} files checked, {} passed, {} with warnings, {} failed",
+            reports.len(),
+            (reports.len() - failed - warned).to_string().color(Color::Green),
+            warned.to_string().color(Color::Yellow),
+            failed.to_string().color(Color::Red)
+        );
+    }
+}
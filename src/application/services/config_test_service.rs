@@ -0,0 +1,203 @@
+use std::path::{Path, PathBuf};
+
+use colored::{Color, Colorize};
+use serde::Serialize;
+use serde_json::json;
+use tracing::debug;
+
+use crate::{
+    application::dtos::config_test_file::{ConfigTestCase, ConfigTestFile},
+    domain::{
+        entities::configuration::Config,
+        services::{
+            config_assertion::Assertion, config_merger::ConfigMergerService,
+            env_override::EnvOverrideService, format_converter::FormatConverterService,
+        },
+        value_objects::config_path::ConfigPath,
+    },
+    shared::{
+        config::ProjectSettings,
+        error::ConfigError,
+        utils::{expand_config_paths, read_file, relative_to_cwd},
+    },
+};
+
+pub const DEFAULT_TESTS_DIR: &str = "tests";
+
+#[derive(Debug, Serialize)]
+pub struct TestCaseReport {
+    pub name: String,
+    pub passed: bool,
+    pub failures: Vec<String>,
+}
+
+// 单个断言文件的结果；文件本身无法解析时记录在 error 中
+#[derive(Debug, Serialize)]
+pub struct TestFileReport {
+    pub file: String,
+    pub error: Option<String>,
+    pub cases: Vec<TestCaseReport>,
+}
+
+impl TestFileReport {
+    pub fn failed(&self) -> usize {
+        self.cases.iter().filter(|case| !case.passed).count() + usize::from(self.error.is_some())
+    }
+
+    pub fn total(&self) -> usize {
+        self.cases.len() + usize::from(self.error.is_some())
+    }
+}
+
+// 按断言文件测试分层配置：基础文件、profile 覆盖文件、环境变量依次叠加后检查期望值
+pub struct ConfigTestService;
+
+impl ConfigTestService {
+    // 配置路径的基准目录：configmanager.toml 所在目录，没有时为当前目录
+    pub fn root() -> PathBuf {
+        ProjectSettings::discover(Path::new("."))
+            .and_then(|project| project.parent().map(relative_to_cwd))
+            .unwrap_or_else(|| PathBuf::from("."))
+    }
+
+    // 未指定时使用项目根目录下 tests/ 中的 YAML 和 JSON 文件
+    pub fn test_files(root: &Path, inputs: &[String]) -> Result<Vec<String>, ConfigError> {
+        let inputs = if inputs.is_empty() {
+            vec![root.join(DEFAULT_TESTS_DIR).to_string_lossy().to_string()]
+        } else {
+            inputs.to_vec()
+        };
+        Ok(expand_config_paths(&inputs)?
+            .into_iter()
+            .filter(|file| {
+                let file = file.to_lowercase();
+                file.ends_with(".yaml") || file.ends_with(".yml") || file.ends_with(".json")
+            })
+            .collect())
+    }
+
+    pub fn run_files(root: &Path, files: &[String]) -> Vec<TestFileReport> {
+        files.iter().map(|file| Self::run_file(root, file)).collect()
+    }
+
+    fn run_file(root: &Path, file: &str) -> TestFileReport {
+        debug!("config test: {}", file);
+        let loaded = read_file(file).and_then(|content| {
+            serde_yaml::from_str::<ConfigTestFile>(&content).map_err(|e| {
+                ConfigError::InvalidTestFile {
+                    path: file.to_string(),
+                    error: e.to_string(),
+                }
+            })
+        });
+        match loaded {
+            Ok(test_file) => TestFileReport {
+                file: file.to_string(),
+                error: None,
+                cases: test_file
+                    .tests
+                    .iter()
+                    .map(|case| Self::run_case(root, case))
+                    .collect(),
+            },
+            Err(e) => TestFileReport {
+                file: file.to_string(),
+                error: Some(e.to_string()),
+                cases: Vec::new(),
+            },
+        }
+    }
+
+    fn run_case(root: &Path, case: &ConfigTestCase) -> TestCaseReport {
+        let failures = match Self::load_config(root, case) {
+            Ok(config) => case
+                .expect
+                .to_vec()
+                .iter()
+                .filter_map(|expression| match Assertion::parse(expression) {
+                    Ok(assertion) => assertion.evaluate(&config).err(),
+                    Err(e) => Some(e.to_string()),
+                })
+                .collect(),
+            Err(e) => vec![e.to_string()],
+        };
+        TestCaseReport {
+            name: case.name(),
+            passed: failures.is_empty(),
+            failures,
+        }
+    }
+
+    // app.yaml 的 profile prod 对应同目录下的 app.prod.yaml
+    pub fn profile_file(file: &Path, profile: &str) -> PathBuf {
+        let stem = file.file_stem().unwrap_or_default().to_string_lossy();
+        let name = match file.extension() {
+            Some(extension) => format!("{}.{}.{}", stem, profile, extension.to_string_lossy()),
+            None => format!("{}.{}", stem, profile),
+        };
+        file.with_file_name(name)
+    }
+
+    pub fn load_config(root: &Path, case: &ConfigTestCase) -> Result<Config, ConfigError> {
+        let file = root.join(&case.file);
+        let mut config = Self::load_file(&file)?;
+        if let Some(profile) = &case.profile {
+            let profile_file = Self::profile_file(&file, profile);
+            if !profile_file.is_file() {
+                return Err(ConfigError::ConfigNotFound(profile_file.display().to_string()));
+            }
+            ConfigMergerService::merge(&mut config, &Self::load_file(&profile_file)?);
+        }
+        let envs = EnvOverrideService::filter_envs(case.env_vars());
+        EnvOverrideService::apply_overrides(&mut config, envs)?;
+        Ok(config)
+    }
+
+    fn load_file(file: &Path) -> Result<Config, ConfigError> {
+        let file = file.to_string_lossy();
+        let content = read_file(&file)?;
+        FormatConverterService::new(ConfigPath::new(file.as_ref())?, content).validate_config()
+    }
+
+    pub fn to_json(reports: &[TestFileReport]) -> serde_json::Value {
+        let total: usize = reports.iter().map(|r| r.total()).sum();
+        let failed: usize = reports.iter().map(|r| r.failed()).sum();
+        json!({
+            "summary": {
+                "total": total,
+                "passed": total - failed,
+                "failed": failed,
+            },
+            "files": reports,
+        })
+    }
+
+    pub fn print_summary(reports: &[TestFileReport]) {
+        for report in reports {
+            println!("{}", report.file);
+            if let Some(error) = &report.error {
+                println!("  {}  {}", "ERROR".color(Color::Red), error);
+            }
+            for case in report.cases.iter() {
+                let status = if case.passed {
+                    "PASS".color(Color::Green)
+                } else {
+                    "FAIL".color(Color::Red)
+                };
+                println!("  {:<4}  {}", status, case.name);
+                for failure in case.failures.iter() {
+                    println!("        {}", failure);
+                }
+            }
+        }
+
+        let total: usize = reports.iter().map(|r| r.total()).sum();
+        let failed: usize = reports.iter().map(|r| r.failed()).sum();
+        println!(
+            "\n{} tests, {} passed, {} failed",
+            total,
+            (total - failed).to_string().color(Color::Green),
+            failed.to_string().color(Color::Red)
+        );
+    }
+}
@@ -1,12 +1,37 @@
+use std::collections::HashMap;
+
+use colored::{Color, Colorize};
+
 use crate::{
     domain::{
-        entities::{configuration::Config, template::TemplateType},
+        entities::{
+            configuration::{Config, ConfigValue},
+            template::{TemplateMetadata, TemplateType},
+            validation_rule::DeprecatedField,
+        },
+        events::config_changed::ChangeEvent,
         repositories::configuration_repository::ConfigurationRepository,
-        value_objects::config_format::ConfigType,
+        services::{
+            config_formatter::{ConfigFormatterService, FormatOptions},
+            config_stats::ConfigStatsService,
+        },
+        value_objects::{config_format::ConfigType, key_pattern::KeyPattern},
+    },
+    shared::{
+        error::ConfigError,
+        utils::{atomic_write, read_file},
     },
-    shared::error::ConfigError,
 };
 
+// 模板升级时用户已修改的键：current 为用户当前值
+#[derive(Debug, Clone)]
+pub struct TemplateConflict {
+    pub key: String,
+    pub current: Option<ConfigValue>,
+    pub old_default: Option<ConfigValue>,
+    pub new_default: Option<ConfigValue>,
+}
+
 pub struct ConfigurationService {
     pub config_repository: Box<dyn ConfigurationRepository>,
 }
@@ -16,22 +41,82 @@ impl ConfigurationService {
         Self { config_repository }
     }
 
+    // 读取配置，指定 doc_index 时只取多文档 YAML 中的对应文档
+    async fn load_document(
+        &self,
+        path: String,
+        doc_index: Option<usize>,
+    ) -> Result<Config, ConfigError> {
+        let config = self.config_repository.get(&path).await?;
+        match doc_index {
+            Some(index) => config.select_document(index),
+            None => Ok(config),
+        }
+    }
+
     pub async fn display_configuration(
         &self,
         path: String,
         depth: usize,
+        doc_index: Option<usize>,
     ) -> Result<(), ConfigError> {
-        let config = self.config_repository.get(path.clone()).await?;
+        let config = self.load_document(path.clone(), doc_index).await?;
         config.show(&path, depth);
         Ok(())
     }
 
+    pub async fn display_statistics(&self, path: String) -> Result<(), ConfigError> {
+        let config = self.config_repository.get(&path).await?;
+        let stats = ConfigStatsService::analyze(&config);
+
+        println!("📊 {} ({})", path.bold(), config.config_type);
+        println!("\n{}", "keys".bold());
+        println!("  total: {}", stats.total_keys);
+        for (depth, count) in stats.keys_per_depth.iter() {
+            println!("  depth {}: {}", depth, count);
+        }
+
+        println!("\n{}", "value types".bold());
+        for (type_name, count) in stats.type_counts.iter() {
+            println!("  {:<8} {}", type_name, count);
+        }
+
+        if !stats.largest_arrays.is_empty() {
+            println!("\n{}", "largest arrays".bold());
+            for (key, len) in stats.largest_arrays.iter() {
+                println!("  {:<6} {}", len, key);
+            }
+        }
+        if !stats.largest_strings.is_empty() {
+            println!("\n{}", "largest strings".bold());
+            for (key, len) in stats.largest_strings.iter() {
+                println!("  {:<6} {}", len, key);
+            }
+        }
+        if !stats.duplicate_values.is_empty() {
+            println!("\n{}", "duplicate values".bold());
+            for (value, keys) in stats.duplicate_values.iter() {
+                println!("  {} x{}: {}", value.color(Color::Yellow), keys.len(), keys.join(", "));
+            }
+        }
+
+        println!("\n{}", "serialized size".bold());
+        for (format, size) in stats.serialized_sizes.iter() {
+            match size {
+                Some(size) => println!("  {:<5} {} bytes", format.to_string(), size),
+                None => println!("  {:<5} {}", format.to_string(), "not representable".color(Color::Red)),
+            }
+        }
+        Ok(())
+    }
+
     pub async fn get_configuration_value(
         &self,
         path: String,
         key: String,
+        doc_index: Option<usize>,
     ) -> Result<(), ConfigError> {
-        let config = self.config_repository.get(path.clone()).await?;
+        let config = self.load_document(path, doc_index).await?;
         let value = config.get(&key);
         if let Some(value) = value {
             Config::display_config_value(&key, &value, 0, false, 0);
@@ -41,16 +126,219 @@ impl ConfigurationService {
         Ok(())
     }
 
+    // 不指定 version 时列出历史版本，否则输出该版本当时的原始内容
+    pub async fn display_history(&self, path: String, version: Option<u64>) -> Result<(), ConfigError> {
+        if let Some(version) = version {
+            let version = self.config_repository.version(&path, version).await?;
+            print!("{}", version.content);
+            return Ok(());
+        }
+        let versions = self.config_repository.history(&path).await?;
+        if versions.is_empty() {
+            println!("no history recorded for {}", path);
+            return Ok(());
+        }
+        println!("{}", format!("history of {}", path).bold());
+        for version in versions {
+            println!(
+                "  v{:<4} {}  {:>8} bytes  {}",
+                version.version,
+                version.saved_at.to_rfc3339(),
+                version.size,
+                &version.sha256[..version.sha256.len().min(12)]
+            );
+        }
+        Ok(())
+    }
+
+    pub async fn set_configuration_value(
+        &self,
+        path: String,
+        key: String,
+        value: String,
+    ) -> Result<(), ConfigError> {
+        let mut config = self.config_repository.get(&path).await?;
+        let value = ConfigValue::from_string(value);
+        config.set(&key, value.clone())?;
+        self.config_repository.save(&path, config).await?;
+        Config::display_config_value(&key, &value, 0, false, 0);
+        println!("✅ set success: {}", path);
+        Ok(())
+    }
+
+    pub async fn rename_key(
+        &self,
+        path: String,
+        from: String,
+        to: String,
+        update_references: bool,
+    ) -> Result<(), ConfigError> {
+        let mut config = self.config_repository.get(&path).await?;
+        config.rename(&from, &to)?;
+        let references = if update_references {
+            config.rewrite_references(&from, &to)
+        } else {
+            0
+        };
+        self.config_repository.save(&path, config).await?;
+        println!("✅ renamed {} -> {} in {}", from, to, path);
+        if update_references {
+            println!("   {} references updated", references);
+        }
+        Ok(())
+    }
+
+    // 将废弃键迁移到替代键并改写引用；目标键已存在或没有替代键时跳过，返回迁移的键数
+    pub async fn migrate_deprecated_keys(
+        &self,
+        path: String,
+        deprecated_fields: &HashMap<String, DeprecatedField>,
+        dry_run: bool,
+    ) -> Result<usize, ConfigError> {
+        let mut config = self.config_repository.get(&path).await?;
+        let mut fields: Vec<(&String, &DeprecatedField)> = deprecated_fields
+            .iter()
+            .filter(|(field, _)| config.get(field).is_some())
+            .collect();
+        fields.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut migrated = 0;
+        for (from, deprecated) in fields {
+            let Some(to) = &deprecated.replacement else {
+                println!(
+                    "{} {}: {} has no replacement, left as is",
+                    "⚠️".color(Color::Yellow),
+                    path,
+                    from
+                );
+                continue;
+            };
+            match config.rename(from, to) {
+                Ok(()) => {
+                    // 迁移后变空的父对象一并移除
+                    let mut parent = from.as_str();
+                    while let Some((p, _)) = parent.rsplit_once('.')
+                        && matches!(config.get(p), Some(ConfigValue::Object(o)) if o.is_empty())
+                    {
+                        config.remove(p);
+                        parent = p;
+                    }
+                    let references = config.rewrite_references(from, to);
+                    println!(
+                        "✅ {}: {} -> {} ({} references updated)",
+                        path, from, to, references
+                    );
+                    migrated += 1;
+                }
+                Err(e) => println!(
+                    "{} {}: skip {} -> {}: {}",
+                    "⚠️".color(Color::Yellow),
+                    path,
+                    from,
+                    to,
+                    e
+                ),
+            }
+        }
+
+        if migrated > 0 && !dry_run {
+            self.config_repository.save(&path, config).await?;
+        }
+        Ok(migrated)
+    }
+
+    // 对比生成时的模板默认值与当前模板默认值：用户未改动的键直接升级，
+    // 用户改动过的键交给 resolve 决定（返回 true 采用模板新值），返回升级的键数
+    pub async fn upgrade_template(
+        &self,
+        path: String,
+        dry_run: bool,
+        mut resolve: impl FnMut(&TemplateConflict) -> bool,
+    ) -> Result<usize, ConfigError> {
+        let mut config = self.config_repository.get(&path).await?;
+        let metadata = TemplateMetadata::from_config(&config)
+            .ok_or_else(|| ConfigError::TemplateMetadataMissing(path.clone()))?;
+        let mut old_defaults = Config::new();
+        old_defaults.config = metadata.defaults.clone();
+        let new_defaults =
+            Config::get_default_config(metadata.template.clone(), config.config_type.clone())?;
+        let changes = ChangeEvent::diff(&path, Some(&old_defaults), &new_defaults, chrono::Utc::now());
+        if changes.is_empty() && metadata.version == metadata.template.version() {
+            println!(
+                "✅ {}: {} template v{} is up to date",
+                path, metadata.template, metadata.version
+            );
+            return Ok(0);
+        }
+
+        let mut upgraded = 0;
+        for change in changes {
+            let current = config.get(&change.key);
+            if current == change.new {
+                continue;
+            }
+            if current != change.old {
+                let conflict = TemplateConflict {
+                    key: change.key.clone(),
+                    current,
+                    old_default: change.old.clone(),
+                    new_default: change.new.clone(),
+                };
+                if !resolve(&conflict) {
+                    println!(
+                        "{} {}: {} was customized, left as is",
+                        "⚠️".color(Color::Yellow),
+                        path,
+                        change.key
+                    );
+                    continue;
+                }
+            }
+            match &change.new {
+                Some(value) => {
+                    config.set(&change.key, value.clone())?;
+                    println!("✅ {}: {} = {}", path, change.key, value.to_serde_value());
+                }
+                None => {
+                    config.remove(&change.key);
+                    println!("✅ {}: {} removed", path, change.key);
+                }
+            }
+            upgraded += 1;
+        }
+
+        if !dry_run {
+            TemplateMetadata::new(metadata.template.clone(), &new_defaults).annotate(&mut config);
+            self.config_repository.save(&path, config).await?;
+        }
+        println!(
+            "{}: {} template v{} -> v{}",
+            path,
+            metadata.template,
+            metadata.version,
+            metadata.template.version()
+        );
+        Ok(upgraded)
+    }
+
     pub async fn convert_configuration(
         &self,
         input: String,
         output: String,
+        only: &[KeyPattern],
+        exclude: &[KeyPattern],
+        doc_index: Option<usize>,
     ) -> Result<(), ConfigError> {
-        let config = self.config_repository.get(input.clone()).await?;
+        let config = self
+            .load_document(input.clone(), doc_index)
+            .await?
+            .filter(only, exclude);
 
         // 检测目标格式
         let target_format = if output.ends_with(".json") {
             ConfigType::Json
+        } else if output.ends_with(".json5") {
+            ConfigType::Json5
         } else if output.ends_with(".yaml") || output.ends_with(".yml") {
             ConfigType::Yaml
         } else if output.ends_with(".toml") {
@@ -65,7 +353,7 @@ impl ConfigurationService {
         let serde_value = config.to_serde_value();
 
         let converted_content = match target_format {
-            ConfigType::Json => serde_json::to_string_pretty(&serde_value)
+            ConfigType::Json | ConfigType::Json5 => serde_json::to_string_pretty(&serde_value)
                 .map_err(|_| ConfigError::ParseConfigError)?,
             ConfigType::Yaml => {
                 serde_yaml::to_string(&serde_value).map_err(|_| ConfigError::ParseConfigError)?
@@ -80,7 +368,7 @@ impl ConfigurationService {
         };
 
         // 写入目标文件
-        std::fs::write(&output, converted_content).map_err(|e| ConfigError::IoError(e))?;
+        std::fs::write(&output, converted_content).map_err(ConfigError::IoError)?;
 
         println!(
             "✅ convert success: {} ({:?}) -> {} ({:?})",
@@ -90,6 +378,27 @@ impl ConfigurationService {
         Ok(())
     }
 
+    pub async fn format_configuration(
+        &self,
+        path: String,
+        options: FormatOptions,
+    ) -> Result<(), ConfigError> {
+        // 先走一遍正常解析流程，确定格式并确保文件合法
+        let config = self.config_repository.get(&path).await?;
+        let content = read_file(&path)?;
+
+        let formatted = ConfigFormatterService::format(&content, &config.config_type, &options)?;
+        if formatted == content {
+            println!("✅ already formatted: {}", path);
+            return Ok(());
+        }
+
+        atomic_write(&path, formatted).map_err(ConfigError::IoError)?;
+        println!("✅ format success: {} ({:?})", path, config.config_type);
+
+        Ok(())
+    }
+
     pub async fn generate_template(
         &self,
         template: TemplateType,
@@ -112,12 +421,13 @@ impl ConfigurationService {
             }
         };
 
-        let config = Config::get_default_config(template.clone(), format.clone())?;
+        let mut config = Config::get_default_config(template.clone(), format.clone())?;
+        TemplateMetadata::new(template.clone(), &config).annotate(&mut config);
         config.show(".", 5);
         let serde_value = config.to_serde_value();
 
         let converted_content = match format {
-            ConfigType::Json => serde_json::to_string_pretty(&serde_value)
+            ConfigType::Json | ConfigType::Json5 => serde_json::to_string_pretty(&serde_value)
                 .map_err(|_| ConfigError::ParseConfigError)?,
             ConfigType::Yaml => {
                 serde_yaml::to_string(&serde_value).map_err(|_| ConfigError::ParseConfigError)?
@@ -133,6 +443,7 @@ impl ConfigurationService {
         println!("🔧 generate config file: {}", converted_content);
         let format_ext = match format {
             ConfigType::Json => "json",
+            ConfigType::Json5 => "json5",
             ConfigType::Yaml => "yaml",
             ConfigType::Toml => "toml",
             ConfigType::Unknown => "txt",
@@ -140,10 +451,73 @@ impl ConfigurationService {
         let output = format!("{}-config.{}", template, format_ext);
         println!("📝 output file name: {}", output);
         // 写入目标文件
-        std::fs::write(&output, converted_content).map_err(|e| ConfigError::IoError(e))?;
+        std::fs::write(&output, converted_content).map_err(ConfigError::IoError)?;
 
         println!("✅ template file generated: {}", output);
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::repositories::file_config_repository::FileConfigRepository;
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("config-manager-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[tokio::test]
+    async fn migrate_moves_deprecated_keys_and_rewrites_references() {
+        let dir = temp_dir("migrate");
+        std::fs::write(
+            dir.join("app.json"),
+            r#"{"db": {"host": "db"}, "url": "postgres://${db.host}/app", "port": 1, "legacy": true}"#,
+        )
+        .unwrap();
+        let repository = FileConfigRepository::new(dir.display().to_string());
+        let service = ConfigurationService::new(Box::new(repository.clone()));
+        let deprecated = HashMap::from([
+            (
+                "db.host".to_string(),
+                DeprecatedField {
+                    replacement: Some("database.host".to_string()),
+                    removed_in: None,
+                },
+            ),
+            // 目标键已存在时跳过
+            (
+                "port".to_string(),
+                DeprecatedField {
+                    replacement: Some("url".to_string()),
+                    removed_in: None,
+                },
+            ),
+            // 没有替代键时保留
+            ("legacy".to_string(), DeprecatedField::default()),
+        ]);
+
+        let migrated = service
+            .migrate_deprecated_keys("app.json".to_string(), &deprecated, true)
+            .await
+            .unwrap();
+        assert_eq!(migrated, 1);
+        assert!(repository.get("app.json").await.unwrap().get("db.host").is_some());
+
+        service
+            .migrate_deprecated_keys("app.json".to_string(), &deprecated, false)
+            .await
+            .unwrap();
+        let config = repository.get("app.json").await.unwrap();
+        assert_eq!(config.get("database.host").unwrap(), "db");
+        assert!(config.get("db").is_none());
+        assert_eq!(config.get("url").unwrap(), "postgres://${database.host}/app");
+        assert!(config.get("port").is_some());
+        assert!(config.get("legacy").is_some());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,105 @@
+use colored::{Color, Colorize};
+use serde::Serialize;
+
+use crate::{
+    domain::{
+        services::{format_converter::FormatConverterService, schema_drift::{DriftReport, SchemaDriftService}},
+        value_objects::config_path::ConfigPath,
+    },
+    shared::{error::ConfigError, utils::read_file},
+};
+
+// 单个文件的漂移结果
+#[derive(Debug, Serialize)]
+pub struct FileDriftReport {
+    pub file: String,
+    pub error: Option<String>,
+    #[serde(flatten)]
+    pub report: DriftReport,
+}
+
+impl FileDriftReport {
+    pub fn is_clean(&self) -> bool {
+        self.error.is_none() && !self.report.has_drift()
+    }
+}
+
+pub struct DriftService;
+
+impl DriftService {
+    // schema 支持 JSON 或 YAML 写法
+    pub fn load_schema(path: &str) -> Result<serde_json::Value, ConfigError> {
+        let content = read_file(path)?;
+        let lower = path.to_lowercase();
+        if lower.ends_with(".yaml") || lower.ends_with(".yml") {
+            serde_yaml::from_str(&content).map_err(|_| ConfigError::ParseConfigError)
+        } else {
+            serde_json::from_str(&content).map_err(|_| ConfigError::ParseConfigError)
+        }
+    }
+
+    pub fn check_files(schema: &serde_json::Value, files: &[String]) -> Vec<FileDriftReport> {
+        files
+            .iter()
+            .map(|file| {
+                let config = read_file(file).and_then(|content| {
+                    FormatConverterService::new(ConfigPath::new(file)?, content).validate_config()
+                });
+                match config {
+                    Ok(config) => FileDriftReport {
+                        file: file.clone(),
+                        error: None,
+                        report: SchemaDriftService::compare(schema, &config),
+                    },
+                    Err(e) => FileDriftReport {
+                        file: file.clone(),
+                        error: Some(e.to_string()),
+                        report: DriftReport::default(),
+                    },
+                }
+            })
+            .collect()
+    }
+
+    pub fn print_summary(reports: &[FileDriftReport]) {
+        for report in reports {
+            if let Some(error) = &report.error {
+                println!("{}  {}  {}", "ERROR".color(Color::Red), report.file, error);
+                continue;
+            }
+            if !report.report.has_drift() {
+                println!("{}  {}", "OK".color(Color::Green), report.file);
+                continue;
+            }
+            println!("{}  {}", "DRIFT".color(Color::Yellow), report.file);
+            for key in report.report.unused_keys.iter() {
+                println!("  {} {}  (not in schema)", "+".color(Color::Yellow), key);
+            }
+            for field in report.report.missing_fields.iter() {
+                let label = if field.required {
+                    "required".color(Color::Red)
+                } else {
+                    "optional".color(Color::Cyan)
+                };
+                println!("  {} {}  (missing, {})", "-".color(Color::Red), field.path, label);
+            }
+            for mismatch in report.report.type_mismatches.iter() {
+                println!(
+                    "  {} {}  (expected {}, got {})",
+                    "~".color(Color::Red),
+                    mismatch.path,
+                    mismatch.expected,
+                    mismatch.actual
+                );
+            }
+        }
+
+        let drifted = reports.iter().filter(|r| !r.is_clean()).count();
+        println!(
+            "\n{} files checked, {} in sync, {} drifted",
+            reports.len(),
+            (reports.len() - drifted).to_string().color(Color::Green),
+            drifted.to_string().color(Color::Red)
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use std::path::Path;
+
+use crate::{
+    domain::{services::fixture_generator::FixtureGenerator, value_objects::config_format::ConfigType},
+    shared::error::ConfigError,
+};
+
+pub struct FixtureOptions {
+    pub output: String,
+    pub files: usize,
+    pub depth: usize,
+    pub format: String,
+    pub seed: Option<u64>,
+}
+
+pub struct FixtureService;
+
+impl FixtureService {
+    // 在输出目录中批量生成合成配置文件，返回生成的文件路径
+    pub fn generate(options: &FixtureOptions) -> Result<Vec<String>, ConfigError> {
+        let format = ConfigType::from(options.format.trim().to_lowercase().as_str());
+        let extension = match format {
+            ConfigType::Json => "json",
+            ConfigType::Json5 => "json5",
+            ConfigType::Yaml => "yaml",
+            ConfigType::Toml => "toml",
+            ConfigType::Unknown => {
+                return Err(ConfigError::UnsupportedFormat {
+                    format: options.format.clone(),
+                });
+            }
+        };
+
+        std::fs::create_dir_all(&options.output)?;
+        let mut generator = FixtureGenerator::new(options.depth, options.seed);
+        let mut written = Vec::with_capacity(options.files);
+        for index in 0..options.files {
+            let path = Path::new(&options.output)
+                .join(format!("fixture-{:04}.{}", index, extension))
+                .to_string_lossy()
+                .to_string();
+            let config = generator.generate(path.clone(), format.clone());
+            std::fs::write(&path, config.serialize_to(&format)?)?;
+            written.push(path);
+        }
+        Ok(written)
+    }
+}
@@ -0,0 +1,46 @@
+use std::{collections::HashSet, sync::Arc};
+
+use tracing::{info, warn};
+
+use crate::shared::app_state::AppState;
+
+// 检查间隔上限，SLO 较短时按 SLO 的一半检查
+const MAX_CHECK_INTERVAL_SECS: u64 = 30;
+
+pub struct FreshnessService;
+
+impl FreshnessService {
+    // 周期性检查配置新鲜度，消费者首次超出 SLO 时告警，恢复后记录日志
+    pub async fn monitor(app_state: Arc<AppState>) {
+        let slo_secs = app_state.freshness_slo.num_seconds().max(1) as u64;
+        let interval = (slo_secs / 2).clamp(1, MAX_CHECK_INTERVAL_SECS);
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(interval));
+        let mut alerted: HashSet<(String, String)> = HashSet::new();
+
+        loop {
+            ticker.tick().await;
+            let report = app_state.freshness_report();
+
+            let mut current = HashSet::new();
+            for consumer in report.stale.iter() {
+                let key = (consumer.consumer.clone(), consumer.file.clone());
+                if !alerted.contains(&key) {
+                    warn!(
+                        "stale config: {} ({}) has not received {} modified at {}, {}s behind (slo {}s)",
+                        consumer.consumer,
+                        consumer.transport,
+                        consumer.file,
+                        consumer.last_modified.to_rfc3339(),
+                        consumer.lag_secs,
+                        report.slo_secs
+                    );
+                }
+                current.insert(key);
+            }
+            for (consumer, file) in alerted.difference(&current) {
+                info!("config {} is fresh again for {}", file, consumer);
+            }
+            alerted = current;
+        }
+    }
+}
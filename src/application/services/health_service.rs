@@ -0,0 +1,37 @@
+use crate::{
+    application::dtos::{
+        health_report::{DirectoryCheck, HealthReport},
+        startup_status::StartupState,
+    },
+    shared::app_state::AppState,
+};
+
+// 存活与就绪检查：监听器尚未启动（watcher 为 None）时视为不健康
+pub struct HealthService;
+
+impl HealthService {
+    pub fn check(state: &AppState) -> HealthReport {
+        let path = state.config_path();
+        let watcher = state.watcher_health.get().map(|health| health.report());
+        let (startup, startup_progress) = {
+            let status = state.startup_status.lock().unwrap();
+            (status.state, status.progress())
+        };
+        let configs = state.config_map.len();
+        let error = std::fs::read_dir(&path).err().map(|e| e.to_string());
+        let healthy = error.is_none() && watcher.as_ref().is_some_and(|watcher| watcher.alive);
+        HealthReport {
+            healthy,
+            ready: healthy && startup == StartupState::Ready,
+            config_dir: DirectoryCheck {
+                path,
+                accessible: error.is_none(),
+                error,
+            },
+            watcher,
+            startup,
+            startup_progress,
+            configs,
+        }
+    }
+}
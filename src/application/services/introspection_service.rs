@@ -0,0 +1,59 @@
+use crate::{
+    application::dtos::{
+        config_status::ConfigLoadState,
+        server_state::{ClientState, LoadedConfig, ServerState},
+    },
+    shared::app_state::AppState,
+};
+
+// 汇总缓存、订阅者与监听器的运行状态，不触发懒加载
+pub struct IntrospectionService;
+
+impl IntrospectionService {
+    pub fn state(state: &AppState) -> ServerState {
+        let mut configs: Vec<LoadedConfig> = state
+            .config_map
+            .keys()
+            .into_iter()
+            .filter_map(|name| {
+                let metadata = state.config_map.metadata(&name)?;
+                let status = state.config_status(&name);
+                Some(LoadedConfig {
+                    version: state.config_map.cached_hash(&name),
+                    size: metadata.size,
+                    modified_at: metadata.modified_at,
+                    state: status.map_or(ConfigLoadState::Loaded, |status| status.state),
+                    name,
+                })
+            })
+            .collect();
+        configs.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut clients: Vec<ClientState> = state
+            .subscriptions
+            .lock()
+            .unwrap()
+            .subscriber_usage
+            .iter()
+            .map(|(client_id, usage)| ClientState {
+                client_id: client_id.clone(),
+                transport: usage.transport.clone(),
+                connected_at: usage.connected_at,
+                subscriptions: usage.files.clone(),
+                bytes_pushed: usage.bytes_pushed,
+                messages_pushed: usage.messages_pushed,
+            })
+            .collect();
+        clients.sort_by_key(|client| client.connected_at);
+
+        let now = state.clock.now();
+        ServerState {
+            started_at: state.started_at,
+            uptime_secs: (now - state.started_at).num_seconds().max(0),
+            configs,
+            clients,
+            watcher: state.watcher_health.get().map(|health| health.report()),
+            cache: state.config_map.metrics(),
+        }
+    }
+}
@@ -0,0 +1,105 @@
+use std::sync::Arc;
+
+use chrono::Duration;
+use tracing::info;
+
+use crate::{
+    application::{
+        dtos::lock_request::LockRequest,
+        services::{rebuild_service::RebuildService, watch_service::WatchService},
+    },
+    domain::entities::config_lock::ConfigLock,
+    shared::{app_state::AppState, error::ConfigError},
+};
+
+// 事故期间锁定配置或冻结全部修改；解锁（包括到期）后重新加载锁定期间被拒绝的文件修改
+pub struct LockService;
+
+impl LockService {
+    // name 为 None 时全局冻结
+    pub async fn lock(
+        app_state: &Arc<AppState>,
+        name: Option<&str>,
+        request: LockRequest,
+    ) -> Result<ConfigLock, ConfigError> {
+        if request.owner.trim().is_empty() {
+            return Err(ConfigError::InvalidLock("owner is required".to_string()));
+        }
+        let now = app_state.clock.now();
+        let lock = ConfigLock {
+            owner: request.owner,
+            reason: request.reason,
+            locked_at: now,
+            expires_at: request
+                .ttl_secs
+                .map(|ttl| now + Duration::seconds(ttl.min(i64::MAX as u64) as i64)),
+        };
+        // 等待进行中的写入完成，之后的写入都会看到这把锁
+        {
+            let _writes = app_state.lock_writes().await;
+            let mut locks = app_state.locks.lock().unwrap();
+            match name {
+                Some(name) => locks.lock(name, lock.clone()),
+                None => locks.freeze(lock.clone()),
+            }
+        }
+        info!(
+            "{} locked by {}: {}",
+            name.unwrap_or("all configs"),
+            lock.owner,
+            lock.reason
+        );
+        if let Some(ttl) = request.ttl_secs {
+            tokio::spawn(Self::expire(
+                app_state.clone(),
+                name.map(str::to_string),
+                lock.clone(),
+                std::time::Duration::from_secs(ttl),
+            ));
+        }
+        Ok(lock)
+    }
+
+    pub async fn unlock(
+        app_state: &Arc<AppState>,
+        name: Option<&str>,
+    ) -> Result<ConfigLock, ConfigError> {
+        let now = app_state.clock.now();
+        let lock = {
+            let mut locks = app_state.locks.lock().unwrap();
+            match name {
+                Some(name) => locks.unlock(name, now),
+                None => locks.unfreeze(now),
+            }
+        };
+        let lock = lock.ok_or_else(|| ConfigError::NotLocked(name.unwrap_or("config").to_string()))?;
+        info!("{} unlocked", name.unwrap_or("all configs"));
+        Self::reload(app_state, name).await;
+        Ok(lock)
+    }
+
+    // 到期后重新加载；期间被新的锁替换时由新的锁负责
+    async fn expire(
+        app_state: Arc<AppState>,
+        name: Option<String>,
+        lock: ConfigLock,
+        ttl: std::time::Duration,
+    ) {
+        tokio::time::sleep(ttl).await;
+        let expired = app_state.locks.lock().unwrap().expire(name.as_deref(), &lock);
+        if expired {
+            info!("lock on {} expired", name.as_deref().unwrap_or("all configs"));
+            Self::reload(&app_state, name.as_deref()).await;
+        }
+    }
+
+    // 单个配置只重新加载该文件，命名空间和全局冻结重新扫描整个配置目录
+    async fn reload(app_state: &Arc<AppState>, name: Option<&str>) {
+        match name {
+            Some(name) if !name.ends_with('/') => WatchService::reload_config(app_state, name).await,
+            _ => {
+                RebuildService::start(app_state);
+            }
+        }
+    }
+}
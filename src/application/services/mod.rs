@@ -1,3 +1,26 @@
+pub mod attached_rules_service;
+pub mod audit_service;
+pub mod authorization_service;
+pub mod backup_service;
+pub mod bundle_service;
+pub mod check_service;
+pub mod config_test_service;
 pub mod configuration_service;
+pub mod drift_service;
+pub mod fixture_service;
+pub mod freshness_service;
+pub mod health_service;
+pub mod introspection_service;
+pub mod lock_service;
+pub mod preflight_service;
+pub mod rebuild_service;
+pub mod rollout_service;
+pub mod schedule_service;
+pub mod settings_service;
+pub mod startup_service;
+pub mod storage_sync_service;
 pub mod template_service;
-pub mod validation_service;
\ No newline at end of file
+pub mod transaction_service;
+pub mod ttl_service;
+pub mod validation_service;
+pub mod watch_service;
@@ -0,0 +1,209 @@
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Utc};
+use colored::{Color, Colorize};
+
+use crate::{
+    application::services::validation_service::ValidationService,
+    shared::utils::expand_config_paths,
+};
+
+#[derive(Debug)]
+pub struct PreflightCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl PreflightCheck {
+    fn pass(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: true,
+            detail: detail.into(),
+        }
+    }
+
+    fn fail(name: &str, detail: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: false,
+            detail: detail.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PreflightOptions {
+    pub host: String,
+    pub port: u16,
+    pub config_path: String,
+    pub tls_cert: Option<String>,
+    pub tls_expiry_days: i64,
+}
+
+pub struct PreflightService;
+
+impl PreflightService {
+    pub fn run(options: &PreflightOptions) -> Vec<PreflightCheck> {
+        let mut checks = vec![
+            Self::check_config_dir(&options.config_path),
+            Self::check_configs_parse(&options.config_path),
+            Self::check_port(&options.host, options.port),
+            Self::check_watch_limits(&options.config_path),
+            Self::check_clock(&options.config_path),
+        ];
+        if let Some(cert) = &options.tls_cert {
+            checks.push(Self::check_tls_cert(cert, options.tls_expiry_days));
+        }
+        checks
+    }
+
+    pub fn print_summary(checks: &[PreflightCheck]) {
+        let width = checks.iter().map(|c| c.name.len()).max().unwrap_or(5).max(5);
+        println!("{:<6}  {:<width$}  DETAILS", "STATUS", "CHECK");
+        for check in checks {
+            let status = if check.passed {
+                "PASS".color(Color::Green)
+            } else {
+                "FAIL".color(Color::Red)
+            };
+            println!("{:<6}  {:<width$}  {}", status, check.name, check.detail);
+        }
+    }
+
+    fn check_config_dir(config_path: &str) -> PreflightCheck {
+        let name = "config dir";
+        let path = Path::new(config_path);
+        if !path.exists() {
+            // serve 会自动创建目录，只要父目录可写即可
+            let parent = path
+                .parent()
+                .filter(|p| !p.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+            return match Self::probe_writable(parent) {
+                Ok(_) => PreflightCheck::pass(name, format!("{} will be created", config_path)),
+                Err(e) => PreflightCheck::fail(
+                    name,
+                    format!("{} missing and parent not writable: {}", config_path, e),
+                ),
+            };
+        }
+        if !path.is_dir() {
+            return PreflightCheck::fail(name, format!("{} is not a directory", config_path));
+        }
+        if let Err(e) = std::fs::read_dir(path) {
+            return PreflightCheck::fail(name, format!("{} not readable: {}", config_path, e));
+        }
+        match Self::probe_writable(path) {
+            Ok(_) => PreflightCheck::pass(name, format!("{} readable and writable", config_path)),
+            Err(e) => PreflightCheck::fail(name, format!("{} not writable: {}", config_path, e)),
+        }
+    }
+
+    fn probe_writable(dir: &Path) -> std::io::Result<()> {
+        let probe = dir.join(format!(".preflight-{}.tmp", std::process::id()));
+        std::fs::write(&probe, b"")?;
+        std::fs::remove_file(&probe)
+    }
+
+    fn check_configs_parse(config_path: &str) -> PreflightCheck {
+        let name = "config files";
+        if !Path::new(config_path).is_dir() {
+            return PreflightCheck::pass(name, "no config files yet");
+        }
+        let files = match expand_config_paths(&[config_path.to_string()]) {
+            Ok(files) => files,
+            Err(e) => return PreflightCheck::fail(name, e.to_string()),
+        };
+        let failed: Vec<String> = files
+            .iter()
+            .map(|f| ValidationService::validate_file(f, None))
+            .filter(|r| !r.is_valid())
+            .map(|r| r.file)
+            .collect();
+        if failed.is_empty() {
+            PreflightCheck::pass(name, format!("{} files parsed", files.len()))
+        } else {
+            PreflightCheck::fail(name, format!("unparseable: {}", failed.join(", ")))
+        }
+    }
+
+    fn check_port(host: &str, port: u16) -> PreflightCheck {
+        let name = "listen port";
+        match std::net::TcpListener::bind((host, port)) {
+            Ok(_) => PreflightCheck::pass(name, format!("{}:{} is free", host, port)),
+            Err(e) => PreflightCheck::fail(name, format!("{}:{} unavailable: {}", host, port, e)),
+        }
+    }
+
+    // inotify 的 watch 数量上限需要覆盖配置目录下的所有子目录
+    fn check_watch_limits(config_path: &str) -> PreflightCheck {
+        let name = "watch limits";
+        let limit = std::fs::read_to_string("/proc/sys/fs/inotify/max_user_watches")
+            .ok()
+            .and_then(|s| s.trim().parse::<usize>().ok());
+        let Some(limit) = limit else {
+            return PreflightCheck::pass(name, "inotify limits not available on this platform");
+        };
+        let dirs = glob::glob(&format!("{}/**/*", config_path.trim_end_matches('/')))
+            .map(|paths| paths.filter_map(|p| p.ok()).filter(|p| p.is_dir()).count())
+            .unwrap_or(0)
+            + 1;
+        if dirs < limit {
+            PreflightCheck::pass(name, format!("{} directories, max_user_watches {}", dirs, limit))
+        } else {
+            PreflightCheck::fail(
+                name,
+                format!("{} directories exceed max_user_watches {}", dirs, limit),
+            )
+        }
+    }
+
+    // 系统时间不能明显早于已有文件的修改时间
+    fn check_clock(config_path: &str) -> PreflightCheck {
+        let name = "clock";
+        let now = Utc::now();
+        let newest: Option<DateTime<Utc>> = expand_config_paths(&[config_path.to_string()])
+            .unwrap_or_default()
+            .iter()
+            .filter_map(|f| std::fs::metadata(f).and_then(|m| m.modified()).ok())
+            .map(DateTime::<Utc>::from)
+            .max();
+        match newest {
+            Some(newest) if newest > now + Duration::minutes(5) => PreflightCheck::fail(
+                name,
+                format!("system time {} is behind file mtime {}", now, newest),
+            ),
+            _ => PreflightCheck::pass(name, format!("system time {}", now.to_rfc3339())),
+        }
+    }
+
+    fn check_tls_cert(cert_path: &str, expiry_days: i64) -> PreflightCheck {
+        let name = "tls cert";
+        let content = match std::fs::read(cert_path) {
+            Ok(content) => content,
+            Err(e) => return PreflightCheck::fail(name, format!("{}: {}", cert_path, e)),
+        };
+        let pem = match x509_parser::pem::parse_x509_pem(&content) {
+            Ok((_, pem)) => pem,
+            Err(e) => return PreflightCheck::fail(name, format!("invalid PEM: {}", e)),
+        };
+        let cert = match pem.parse_x509() {
+            Ok(cert) => cert,
+            Err(e) => return PreflightCheck::fail(name, format!("invalid certificate: {}", e)),
+        };
+        let validity = cert.validity();
+        let now = Utc::now().timestamp();
+        let not_after = validity.not_after.timestamp();
+        if now < validity.not_before.timestamp() {
+            PreflightCheck::fail(name, "certificate is not valid yet")
+        } else if now > not_after {
+            PreflightCheck::fail(name, format!("certificate expired at {}", validity.not_after))
+        } else if not_after - now < expiry_days * 24 * 3600 {
+            PreflightCheck::fail(name, format!("certificate expires soon: {}", validity.not_after))
+        } else {
+            PreflightCheck::pass(name, format!("valid until {}", validity.not_after))
+        }
+    }
+}
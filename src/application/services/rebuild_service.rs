@@ -0,0 +1,153 @@
+use std::{collections::HashMap, sync::Arc};
+
+use tracing::info;
+
+use crate::{
+    application::{
+        dtos::rebuild_status::{RebuildFailure, RebuildState, RebuildStatus},
+        services::attached_rules_service::AttachedRulesService,
+    },
+    domain::events::config_changed::ConfigChange,
+    infrastructure::{
+        notification::dispatcher::dispatch,
+        repositories::file_config_repository::FileConfigRepository,
+        watchers::config_watcher::ConfigWatcher,
+    },
+    shared::{app_state::AppState, utils::read_file_async},
+};
+
+pub struct RebuildService;
+
+impl RebuildService {
+    // 后台重建 config_map；已有重建在运行时返回 false
+    pub fn start(app_state: &Arc<AppState>) -> bool {
+        if !Self::begin(app_state) {
+            return false;
+        }
+        tokio::spawn(Self::run(app_state.clone()));
+        true
+    }
+
+    // 同 start，但等待重新扫描配置目录完成后返回结果；已有重建在运行时返回 None
+    pub async fn reload(app_state: &Arc<AppState>) -> Option<RebuildStatus> {
+        if !Self::begin(app_state) {
+            return None;
+        }
+        Self::run(app_state.clone()).await;
+        Some(app_state.rebuild_status.lock().unwrap().clone())
+    }
+
+    fn begin(app_state: &AppState) -> bool {
+        let mut status = app_state.rebuild_status.lock().unwrap();
+        if status.state == RebuildState::Running {
+            return false;
+        }
+        *status = RebuildStatus {
+            state: RebuildState::Running,
+            started_at: Some(app_state.clock.now()),
+            ..Default::default()
+        };
+        true
+    }
+
+    async fn run(app_state: Arc<AppState>) {
+        let (config_path, validation) = (app_state.config_path(), app_state.validation.clone());
+        let files = match FileConfigRepository::new(config_path.clone())
+            .files_async()
+            .await
+        {
+            Ok(files) => files,
+            Err(e) => {
+                let mut status = app_state.rebuild_status.lock().unwrap();
+                status.state = RebuildState::Failed;
+                status.error = Some(format!("{}: {}", config_path, e));
+                status.finished_at = Some(app_state.clock.now());
+                return;
+            }
+        };
+        app_state.rebuild_status.lock().unwrap().total = files.len();
+
+        // 先解析全部文件，期间读请求继续使用旧状态
+        let (mut loaded, mut failed_names) = (HashMap::new(), Vec::new());
+        for (name, file) in files {
+            let path = file.clone();
+            let validation = validation.clone();
+            let config_path = config_path.clone();
+            let key = name.clone();
+            let result = match read_file_async(&path.to_string_lossy()).await {
+                Ok(content) => tokio::task::spawn_blocking(move || {
+                    ConfigWatcher::parse_as(content, key, validation.as_deref()).and_then(
+                        |loaded| {
+                            AttachedRulesService::check(&config_path, &loaded.0, &loaded.1)
+                                .map(|_| loaded)
+                        },
+                    )
+                })
+                .await
+                .map_err(|e| e.to_string())
+                .and_then(|r| r.map_err(|e| e.to_string())),
+                Err(e) => Err(e.to_string()),
+            };
+            let mut status = app_state.rebuild_status.lock().unwrap();
+            match result {
+                Ok((file_name, config, config_str)) => {
+                    app_state.clear_load_failure(&file_name);
+                    loaded.insert(file_name, (config, config_str));
+                    status.loaded += 1;
+                }
+                Err(error) => {
+                    app_state.record_load_failure(&name, error.clone());
+                    failed_names.push(name);
+                    status.failures.push(RebuildFailure {
+                        file: file.to_string_lossy().to_string(),
+                        error,
+                    })
+                }
+            }
+        }
+
+        // 在写锁内替换 config_map：逐个覆盖后移除已不存在的配置，读请求不会看到配置暂时缺失；
+        // 解析失败的文件保留旧值，订阅者连接不受影响
+        let changed: Vec<ConfigChange> = {
+            let _writes = app_state.lock_writes().await;
+            let mut status = app_state.rebuild_status.lock().unwrap();
+            // 被锁定的配置保留当前版本
+            let stale: Vec<String> = app_state
+                .config_map
+                .keys()
+                .into_iter()
+                .filter(|name| !loaded.contains_key(name) && !failed_names.contains(name))
+                .filter(|name| app_state.check_unlocked(name).is_ok())
+                .collect();
+
+            let mut changed = Vec::new();
+            for (name, (config, config_str)) in loaded {
+                if app_state.check_unlocked(&name).is_err() {
+                    continue;
+                }
+                let previous = app_state.config_map.get(&name);
+                let unchanged = previous
+                    .as_ref()
+                    .is_some_and(|previous| previous.to_serde_value() == config.to_serde_value());
+                app_state.store_config(name.clone(), config);
+                if !unchanged {
+                    changed.push(app_state.config_change(name, previous, config_str, "rebuild"));
+                }
+            }
+            for name in stale.iter() {
+                app_state.config_map.remove(name);
+            }
+
+            status.changed = changed.len();
+            status.removed = stale.len();
+            status.state = RebuildState::Completed;
+            status.finished_at = Some(app_state.clock.now());
+            changed
+        };
+
+        info!("state rebuild finished, {} configs changed", changed.len());
+        for change in changed {
+            dispatch(&app_state, change);
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::BTreeSet;
+
+use tracing::info;
+
+use crate::{
+    application::{
+        dtos::rollout_request::RolloutRequest,
+        services::{attached_rules_service::AttachedRulesService, audit_service::AuditService},
+    },
+    domain::{
+        entities::{
+            audit::{AuditAction, AuditActor},
+            config_map::ConfigMap,
+            rollout::{Rollout, RolloutTarget},
+        },
+        services::{env_override::EnvOverrideService, format_converter::FormatConverterService},
+        value_objects::config_path::ConfigPath,
+    },
+    infrastructure::repositories::file_config_repository::FileConfigRepository,
+    shared::{app_state::AppState, error::ConfigError},
+};
+
+// 金丝雀发布：新内容先推给部分订阅者，确认后写入文件推给所有人，或给金丝雀订阅者推回稳定版本
+pub struct RolloutService;
+
+impl RolloutService {
+    // 新内容按与 PUT 相同的规则校验，但不写入文件
+    pub fn start(
+        app_state: &AppState,
+        file: &str,
+        request: RolloutRequest,
+        actor: &AuditActor,
+    ) -> Result<Rollout, ConfigError> {
+        match &request.target {
+            RolloutTarget::Percentage(percentage) if *percentage > 100 => {
+                return Err(ConfigError::InvalidRolloutTarget(format!(
+                    "percentage {} is greater than 100",
+                    percentage
+                )));
+            }
+            RolloutTarget::Selector(selector) if selector.is_empty() => {
+                return Err(ConfigError::InvalidRolloutTarget("empty selector".to_string()));
+            }
+            _ => {}
+        }
+        let mut stable = app_state
+            .config_map
+            .get(file)
+            .ok_or_else(|| ConfigError::ConfigNotFound(file.to_string()))?;
+        let mut canary =
+            FormatConverterService::new(ConfigPath::new(file)?, request.content.clone())
+                .validate_config()?;
+        if let Some(validation) = app_state.validation.clone() {
+            validation.apply_defaults(&mut canary)?;
+        }
+        AttachedRulesService::check(&app_state.config_path(), file, &canary)?;
+
+        let rollout = Rollout {
+            file: file.to_string(),
+            target: request.target,
+            content: request.content,
+            hash: ConfigMap::content_hash(&canary),
+            config: EnvOverrideService::apply_env_override(&mut canary)?.to_serde_value(),
+            base: EnvOverrideService::apply_env_override(&mut stable)?.to_serde_value(),
+            base_hash: app_state.config_map.hash(file),
+            started_at: app_state.clock.now(),
+            started_by: actor.source.clone(),
+            clients: BTreeSet::new(),
+        };
+        let rollout = app_state.start_rollout(rollout)?;
+        info!(
+            "rollout of {} started by {}, {} canary subscribers",
+            file,
+            actor.source,
+            rollout.clients.len()
+        );
+        Ok(rollout)
+    }
+
+    // 把金丝雀内容写入文件；文件监听器重新加载后推给所有订阅者，发布随之结束
+    pub async fn promote(
+        app_state: &AppState,
+        file: &str,
+        actor: &AuditActor,
+    ) -> Result<Rollout, ConfigError> {
+        let _writes = app_state.lock_writes().await;
+        let rollout = app_state
+            .rollout(file)
+            .ok_or_else(|| ConfigError::RolloutNotFound(file.to_string()))?;
+        let mut loaded = FormatConverterService::new(ConfigPath::new(file)?, rollout.content.clone())
+            .validate_config()?;
+        if let Some(validation) = app_state.validation.clone() {
+            validation.apply_defaults(&mut loaded)?;
+        }
+        FileConfigRepository::new(app_state.config_path())
+            .save_content(&rollout.content, file)
+            .await?;
+        let before = app_state.config_map.get(file);
+        AuditService::record_change(
+            app_state,
+            AuditAction::Update,
+            file,
+            actor,
+            before.as_ref(),
+            Some(&loaded),
+            Some("promote rollout".to_string()),
+        );
+        app_state.store_write(file.to_string(), loaded, &actor.source);
+        info!("rollout of {} promoted by {}", file, actor.source);
+        Ok(rollout)
+    }
+
+    pub fn rollback(
+        app_state: &AppState,
+        file: &str,
+        actor: &AuditActor,
+    ) -> Result<Rollout, ConfigError> {
+        let rollout = app_state.rollback_rollout(file)?;
+        info!(
+            "rollout of {} rolled back by {}, {} canary subscribers restored",
+            file,
+            actor.source,
+            rollout.clients.len()
+        );
+        Ok(rollout)
+    }
+}
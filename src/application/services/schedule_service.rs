@@ -0,0 +1,165 @@
+use std::sync::Arc;
+
+use tracing::{info, warn};
+
+use crate::{
+    application::{
+        dtos::schedule_request::ScheduleRequest,
+        services::{attached_rules_service::AttachedRulesService, audit_service::AuditService},
+    },
+    domain::{
+        entities::{
+            audit::{AuditAction, AuditActor},
+            configuration::Config,
+            scheduled_change::ScheduledChange,
+        },
+        services::format_converter::FormatConverterService,
+        value_objects::config_path::ConfigPath,
+    },
+    infrastructure::schedules::file_schedule_store::FileScheduleStore,
+    shared::{
+        app_state::{AppState, wait_for_shutdown},
+        error::ConfigError,
+    },
+};
+
+// 生效时写入的来源，订阅者收到的修改和审计记录中可见
+const SCHEDULE_SOURCE: &str = "schedule";
+
+// 定时修改：提交时校验并持久化，到期后按接口写入的方式写盘，由文件监听器推送给订阅者
+pub struct ScheduleService;
+
+impl ScheduleService {
+    // 内容按与 PUT 相同的规则校验，生效时间必须晚于当前时间
+    pub fn schedule(
+        app_state: &AppState,
+        file: &str,
+        request: ScheduleRequest,
+        actor: &AuditActor,
+    ) -> Result<ScheduledChange, ConfigError> {
+        let now = app_state.clock.now();
+        if request.apply_at <= now {
+            return Err(ConfigError::InvalidScheduledChange(format!(
+                "apply_at {} is not in the future",
+                request.apply_at.to_rfc3339()
+            )));
+        }
+        Self::validate(app_state, file, &request.content)?;
+        let change = ScheduledChange {
+            id: app_state.id_generator.next_id("schedule"),
+            file: file.to_string(),
+            content: request.content,
+            apply_at: request.apply_at,
+            scheduled_at: now,
+            scheduled_by: actor.source.clone(),
+        };
+        {
+            let mut schedules = app_state.schedules.lock().unwrap();
+            schedules.add(change.clone());
+            FileScheduleStore::new(&app_state.config_path()).save(schedules.all())?;
+        }
+        app_state.schedules_changed.notify_one();
+        info!(
+            "change to {} scheduled at {} by {}",
+            file,
+            change.apply_at.to_rfc3339(),
+            actor.source
+        );
+        Ok(change)
+    }
+
+    pub fn pending(app_state: &AppState, file: &str) -> Vec<ScheduledChange> {
+        app_state.schedules.lock().unwrap().pending(file)
+    }
+
+    // 恢复上次运行时保存的修改，在开始接受请求之前调用，避免新提交的修改覆盖保存的文件
+    pub fn restore(app_state: &AppState) {
+        match FileScheduleStore::new(&app_state.config_path()).load() {
+            Ok(changes) => {
+                if !changes.is_empty() {
+                    info!("restored {} scheduled changes", changes.len());
+                }
+                let mut schedules = app_state.schedules.lock().unwrap();
+                for change in changes {
+                    schedules.add(change);
+                }
+            }
+            Err(e) => warn!("restore scheduled changes failed: {}", e),
+        }
+    }
+
+    // 按时间应用到期的修改，直到服务关闭；停机期间到期的修改在启动加载完成后立即生效
+    pub async fn run(app_state: Arc<AppState>) {
+        let store = FileScheduleStore::new(&app_state.config_path());
+        let mut shutdown = app_state.shutdown_receiver();
+        loop {
+            let now = app_state.clock.now();
+            let next = app_state.schedules.lock().unwrap().next_at();
+            if let Some(next) = next {
+                let delay = (next - now).to_std().unwrap_or_default();
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = app_state.schedules_changed.notified() => continue,
+                    _ = wait_for_shutdown(&mut shutdown) => return,
+                }
+            } else {
+                tokio::select! {
+                    _ = app_state.schedules_changed.notified() => continue,
+                    _ = wait_for_shutdown(&mut shutdown) => return,
+                }
+            }
+
+            let due = app_state.schedules.lock().unwrap().take_due(app_state.clock.now());
+            for change in &due {
+                match Self::apply(&app_state, change).await {
+                    Ok(()) => info!("scheduled change {} applied to {}", change.id, change.file),
+                    Err(e) => warn!(
+                        "scheduled change {} to {} dropped: {}",
+                        change.id, change.file, e
+                    ),
+                }
+            }
+            let schedules = app_state.schedules.lock().unwrap();
+            if let Err(e) = store.save(schedules.all()) {
+                warn!("save scheduled changes failed: {}", e);
+            }
+        }
+    }
+
+    // 生效时重新校验：提交后规则可能已变化，配置也可能已被锁定
+    async fn apply(app_state: &AppState, change: &ScheduledChange) -> Result<(), ConfigError> {
+        let _writes = app_state.lock_writes().await;
+        app_state.check_unlocked(&change.file)?;
+        let (config, loaded) = Self::validate(app_state, &change.file, &change.content)?;
+        app_state.repository().save(&change.file, config).await?;
+        let before = app_state.config_map.get(&change.file);
+        let action = if before.is_some() {
+            AuditAction::Update
+        } else {
+            AuditAction::Create
+        };
+        AuditService::record_change(
+            app_state,
+            action,
+            &change.file,
+            &AuditActor::new(SCHEDULE_SOURCE, None),
+            before.as_ref(),
+            Some(&loaded),
+            Some(format!("scheduled change {} by {}", change.id, change.scheduled_by)),
+        );
+        app_state.store_write(change.file.clone(), loaded, SCHEDULE_SOURCE);
+        Ok(())
+    }
+
+    // 返回写入文件的内容和填充默认值后的配置
+    fn validate(app_state: &AppState, file: &str, content: &str) -> Result<(Config, Config), ConfigError> {
+        let config = FormatConverterService::new(ConfigPath::new(file)?, content.to_string())
+            .validate_config()?;
+        let mut loaded = config.clone();
+        if let Some(validation) = app_state.validation.clone() {
+            validation.apply_defaults(&mut loaded)?;
+        }
+        AttachedRulesService::check(&app_state.config_path(), file, &loaded)?;
+        Ok((config, loaded))
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tracing::{info, warn};
+
+use crate::{
+    domain::value_objects::namespace::Namespace,
+    infrastructure::{limits::rate_limiter::RateLimiter, watchers::config_watcher::ConfigWatcher},
+    shared::{
+        app_state::{AppState, LiveSettings},
+        config::{ApiKeys, LogLevel, NamespaceRouting, RateLimitSettings, ServerSettings},
+        error::ConfigError,
+        utils::{default_log_filter, set_log_filter},
+    },
+};
+
+// serve 的 --settings 文件：启动时加载，之后监听该文件，修改后替换路由、命名空间、API key、
+// 限流和日志级别；端口、审计、通知 sink、CORS 与安全头在启动时生效，修改后需要重启
+pub struct SettingsService;
+
+impl SettingsService {
+    // 设置文件中的 rate_limit 优先于命令行参数；限额没有变化时沿用当前的限流器
+    pub fn live(
+        settings: &ServerSettings,
+        cli_rate_limit: Option<RateLimitSettings>,
+        current: Option<&LiveSettings>,
+    ) -> Result<LiveSettings, ConfigError> {
+        let namespaces = settings
+            .namespaces
+            .iter()
+            .map(Namespace::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        let rate_limiter = match settings.rate_limit.or(cli_rate_limit) {
+            Some(limit) if limit.rate <= 0.0 => {
+                return Err(ConfigError::InvalidServerSettings(
+                    "rate limit must be positive".to_string(),
+                ));
+            }
+            Some(limit) => {
+                let rate_limiter = RateLimiter::new(limit.rate, limit.burst);
+                match current.and_then(|current| current.rate_limiter.clone()) {
+                    Some(current) if current.same_limits(&rate_limiter) => Some(current),
+                    _ => Some(Arc::new(rate_limiter)),
+                }
+            }
+            None => None,
+        };
+        Ok(LiveSettings {
+            routing: NamespaceRouting::new(settings.routing.clone()),
+            namespaces,
+            api_keys: ApiKeys::new(settings.api_keys.clone()),
+            rate_limiter,
+        })
+    }
+
+    // 设置文件中的日志级别与按模块的级别，都未配置时恢复启动时的 --log-filter
+    pub fn apply_log_level(settings: &ServerSettings) -> Result<(), ConfigError> {
+        set_log_filter(&Self::log_filter(settings.log_level, &settings.log_modules))
+    }
+
+    // 全局级别（未指定时为启动时的过滤规则）加上按模块覆盖的级别
+    pub fn log_filter(level: Option<LogLevel>, modules: &BTreeMap<String, LogLevel>) -> String {
+        let mut directives = vec![level.map_or_else(default_log_filter, |level| level.as_str().to_string())];
+        directives.extend(modules.iter().map(|(module, level)| format!("{}={}", module, level.as_str())));
+        directives.join(",")
+    }
+
+    // 监听设置文件所在目录，只处理该文件自身的修改；返回的监听器释放后停止监听
+    pub fn watch(
+        app_state: &Arc<AppState>,
+        path: &str,
+        cli_rate_limit: Option<RateLimitSettings>,
+    ) -> Result<ConfigWatcher, ConfigError> {
+        let path = std::path::absolute(path)?;
+        let dir = path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("/"));
+        let (app_state_for_watcher, settings_path) = (app_state.clone(), path.clone());
+        let mut watcher = ConfigWatcher::with_options(app_state.watch, move |file_path| {
+            if file_path == settings_path {
+                Self::reload(&app_state_for_watcher, &settings_path, cli_rate_limit);
+            }
+        })?;
+        watcher.watch(&dir, false)?;
+        info!("server settings watcher init finished: {}", path.display());
+        Ok(watcher)
+    }
+
+    // 新设置无效时继续使用当前设置
+    fn reload(app_state: &AppState, path: &Path, cli_rate_limit: Option<RateLimitSettings>) {
+        let loaded = ServerSettings::load(&path.to_string_lossy()).and_then(|settings| {
+            let live = Self::live(&settings, cli_rate_limit, Some(&app_state.settings()))?;
+            Ok((settings, live))
+        });
+        let (settings, live) = match loaded {
+            Ok(loaded) => loaded,
+            Err(e) => {
+                warn!(
+                    "server settings reload failed, keeping current settings: {}",
+                    e
+                );
+                return;
+            }
+        };
+        // 新增的命名空间需要对应的子目录，监听器会递归监听新建的目录
+        let config_path = app_state.config_path();
+        for namespace in live.namespaces.iter() {
+            let dir = Path::new(&config_path).join(namespace.as_str());
+            if let Err(e) = std::fs::create_dir_all(&dir) {
+                warn!("create namespace directory {:?} failed: {}", dir, e);
+            }
+        }
+        app_state.replace_settings(live);
+        if let Err(e) = Self::apply_log_level(&settings) {
+            warn!("server settings log level not applied: {}", e);
+        }
+        info!("server settings reloaded: {}", path.display());
+    }
+}
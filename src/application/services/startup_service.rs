@@ -0,0 +1,209 @@
+use std::{path::Path, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use tracing::{debug, info, warn};
+
+use crate::{
+    application::{
+        dtos::{
+            rebuild_status::RebuildFailure,
+            startup_status::{StartupState, StartupStatus},
+        },
+        services::attached_rules_service::AttachedRulesService,
+    },
+    domain::{
+        entities::config_map::{ConfigLoader, ConfigMetadata},
+        value_objects::config_format::ConfigType,
+    },
+    infrastructure::{
+        repositories::file_config_repository::FileConfigRepository,
+        watchers::config_watcher::ConfigWatcher,
+    },
+    shared::{app_state::AppState, error::ConfigError, utils::read_file_async},
+};
+
+pub const DEFAULT_STARTUP_WORKERS: usize = 8;
+
+pub struct StartupService;
+
+impl StartupService {
+    // 并发加载配置目录（同时处理的文件数不超过 startup_workers），
+    // 解析失败的文件只记录到启动状态中，不阻止服务启动
+    pub async fn load(app_state: &Arc<AppState>) {
+        let workers = app_state.startup_workers.max(1);
+        *app_state.startup_status.lock().unwrap() = StartupStatus {
+            state: StartupState::Loading,
+            started_at: Some(app_state.clock.now()),
+            workers,
+            ..Default::default()
+        };
+        let (config_path, validation, history) = (
+            app_state.config_path(),
+            app_state.validation.clone(),
+            app_state.history(),
+        );
+
+        let files = match FileConfigRepository::new(config_path.clone())
+            .files_async()
+            .await
+        {
+            Ok(files) => files,
+            Err(e) => {
+                let mut status = app_state.startup_status.lock().unwrap();
+                status.state = StartupState::Failed;
+                status.error = Some(format!("{}: {}", config_path, e));
+                status.finished_at = Some(app_state.clock.now());
+                return;
+            }
+        };
+        let total = files.len();
+        app_state.startup_status.lock().unwrap().total = total;
+        if app_state.lazy_load {
+            Self::register(app_state, files).await;
+            return;
+        }
+        info!("loading {} config files with {} workers", total, workers);
+
+        let mut results = futures_util::stream::iter(files)
+            .map(|(name, path)| {
+                let validation = validation.clone();
+                let config_path = config_path.clone();
+                let history = history.clone();
+                let now = app_state.clock.now();
+                async move {
+                    let file = path.to_string_lossy().to_string();
+                    let content = match read_file_async(&file).await {
+                        Ok(content) => content,
+                        Err(e) => return (name, file, Err(e.to_string())),
+                    };
+                    let key = name.clone();
+                    // 解析、附加规则检查和历史记录在阻塞线程池中进行
+                    let result = tokio::task::spawn_blocking(move || {
+                        let loaded =
+                            ConfigWatcher::parse_as(content.clone(), key, validation.as_deref())
+                                .and_then(|loaded| {
+                                AttachedRulesService::check(&config_path, &loaded.0, &loaded.1)
+                                    .map(|_| loaded)
+                            })?;
+                        // 服务停止期间的修改也作为一个版本
+                        if let Err(e) = history.record_content(&loaded.0, content, now) {
+                            warn!("record history for {} failed: {}", loaded.0, e);
+                        }
+                        Ok::<_, ConfigError>(loaded)
+                    })
+                    .await
+                    .map_err(|e| e.to_string())
+                    .and_then(|r| r.map_err(|e| e.to_string()));
+                    (name, file, result)
+                }
+            })
+            .buffer_unordered(workers);
+
+        while let Some((name, file, result)) = results.next().await {
+            match result {
+                Ok((file_name, config, _)) => {
+                    // 加载期间文件监听器可能已写入更新的版本，此时保留监听器的结果
+                    let _writes = app_state.lock_writes().await;
+                    if !app_state.config_map.contains_key(&file_name) {
+                        app_state.store_config(file_name, config);
+                    }
+                    app_state.startup_status.lock().unwrap().loaded += 1;
+                    debug!("loaded config file: {}", file);
+                }
+                Err(error) => {
+                    warn!("load config file failed: {} - {}", file, error);
+                    app_state.record_load_failure(&name, error.clone());
+                    let failure = RebuildFailure { file, error };
+                    app_state.startup_status.lock().unwrap().failures.push(failure);
+                }
+            }
+            let status = app_state.startup_status.lock().unwrap();
+            info!(
+                "startup loading {}/{} files, {} failures",
+                status.loaded + status.failures.len(),
+                status.total,
+                status.failures.len()
+            );
+        }
+
+        let mut status = app_state.startup_status.lock().unwrap();
+        status.state = StartupState::Ready;
+        status.finished_at = Some(app_state.clock.now());
+        info!(
+            "config loaded finished: {} files, {} failures",
+            status.loaded,
+            status.failures.len()
+        );
+    }
+
+    // 懒加载模式：只登记文件的格式、大小和修改时间，首次访问时由 loader 解析；
+    // 启动时不读取文件内容，因此服务停止期间的修改不会记入历史
+    async fn register(app_state: &Arc<AppState>, files: Vec<(String, std::path::PathBuf)>) {
+        app_state.config_map.set_loader(Self::loader(app_state));
+        info!("registering {} config files for lazy loading", files.len());
+        for (name, path) in files {
+            let file = path.to_string_lossy().to_string();
+            match tokio::fs::metadata(&path).await {
+                Ok(meta) => {
+                    let format = match path.extension().and_then(|ext| ext.to_str()) {
+                        Some("yml") => ConfigType::Yaml,
+                        Some(ext) => ConfigType::from(ext),
+                        None => ConfigType::Unknown,
+                    };
+                    let modified_at = meta
+                        .modified()
+                        .map(DateTime::<Utc>::from)
+                        .unwrap_or_else(|_| app_state.clock.now());
+                    let metadata = ConfigMetadata::unloaded(format, meta.len(), modified_at);
+                    app_state.config_map.register(name, metadata);
+                    app_state.startup_status.lock().unwrap().loaded += 1;
+                    debug!("registered config file: {}", file);
+                }
+                Err(e) => {
+                    warn!("register config file failed: {} - {}", file, e);
+                    let error = e.to_string();
+                    let failure = RebuildFailure { file, error };
+                    app_state.startup_status.lock().unwrap().failures.push(failure);
+                }
+            }
+        }
+
+        let mut status = app_state.startup_status.lock().unwrap();
+        status.state = StartupState::Ready;
+        status.finished_at = Some(app_state.clock.now());
+        info!(
+            "config registered finished: {} files, {} failures",
+            status.loaded,
+            status.failures.len()
+        );
+    }
+
+    // 按名称读取并解析配置文件，与启动加载一样应用校验默认值和附加规则；
+    // 加载失败时返回 None，读取方按配置不存在处理
+    fn loader(app_state: &AppState) -> ConfigLoader {
+        let (config_path, validation) = (app_state.config_path(), app_state.validation.clone());
+        let clock = app_state.clock.clone();
+        Arc::new(move |name: &str| {
+            let file = Path::new(&config_path).join(name);
+            let meta = std::fs::metadata(&file).ok()?;
+            let loaded = ConfigWatcher::load_as(&file, name.to_string(), validation.as_deref())
+                .and_then(|loaded| {
+                    AttachedRulesService::check(&config_path, &loaded.0, &loaded.1).map(|_| loaded)
+                });
+            let (_, config, _) = match loaded {
+                Ok(loaded) => loaded,
+                Err(e) => {
+                    warn!("lazy load config failed: {} - {}", name, e);
+                    return None;
+                }
+            };
+            let modified_at = meta
+                .modified()
+                .map(DateTime::<Utc>::from)
+                .unwrap_or_else(|_| clock.now());
+            let metadata = ConfigMetadata::new(&config, meta.len(), modified_at);
+            Some((config, metadata))
+        })
+    }
+}
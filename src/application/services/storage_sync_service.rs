@@ -0,0 +1,220 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::{
+    application::services::attached_rules_service::{ATTACHED_RULES_SUFFIX, AttachedRulesService},
+    domain::{events::config_changed::ConfigChange, repositories::config_store::ConfigStore},
+    infrastructure::repositories::file_config_repository::FileConfigRepository,
+    shared::{
+        app_state::{AppState, wait_for_shutdown},
+        error::ConfigError,
+        utils::{atomic_write_async, sha256_hex},
+    },
+};
+
+// serve --storage 指定存储时配置目录与存储的同步状态：存储中的修改写入配置目录，由文件监听器
+// 照常推送；配置目录的修改（接口写入或直接编辑）由监听器交给这里写回存储
+pub struct StorageSync {
+    pub store: Arc<dyn ConfigStore>,
+    pub poll_interval: Duration,
+    // 只把存储中的配置同步到配置目录，本地的修改不写回
+    pub read_only: bool,
+    // 最近一次同步时各配置的 revision 与内容哈希，两边内容一致时不重复写入
+    synced: Mutex<HashMap<String, SyncedConfig>>,
+    pushes: mpsc::UnboundedSender<String>,
+    receiver: Mutex<Option<mpsc::UnboundedReceiver<String>>>,
+}
+
+#[derive(Debug, Clone)]
+struct SyncedConfig {
+    revision: String,
+    hash: String,
+}
+
+impl StorageSync {
+    pub fn new(store: Arc<dyn ConfigStore>, poll_interval: Duration, read_only: bool) -> Self {
+        let (pushes, receiver) = mpsc::unbounded_channel();
+        Self {
+            store,
+            poll_interval,
+            read_only,
+            synced: Mutex::new(HashMap::new()),
+            pushes,
+            receiver: Mutex::new(Some(receiver)),
+        }
+    }
+}
+
+pub struct StorageSyncService;
+
+impl StorageSyncService {
+    // 启动时把存储中的配置下载到配置目录，再写回只存在于本地的配置；附加规则只在启动时同步
+    pub async fn pull(app_state: &AppState) -> Result<(), ConfigError> {
+        let Some(sync) = app_state.storage.clone() else {
+            return Ok(());
+        };
+        let stored = Self::poll(app_state, &sync).await?;
+        let local = FileConfigRepository::new(app_state.config_path()).files_async().await?;
+        let local = local.into_iter().filter(|(name, _)| !sync.read_only && !stored.contains(name));
+        for (name, _) in local {
+            Self::push(app_state, &sync, &name).await?;
+        }
+        Self::sync_rules(app_state, &sync).await?;
+        info!("config directory synced with {}", sync.store.id());
+        Ok(())
+    }
+
+    // 文件监听器推送的每个变更都交给同步任务，按顺序写回存储
+    pub fn notify(app_state: &AppState, change: &ConfigChange) {
+        if let Some(sync) = app_state.storage.as_ref().filter(|sync| !sync.read_only) {
+            let _ = sync.pushes.send(change.file.clone());
+        }
+    }
+
+    // 存储中的配置可能被修改时重新同步，同时处理待写回的变更，直到服务关闭；单次失败只记录日志
+    pub async fn run(app_state: Arc<AppState>) {
+        let Some(sync) = app_state.storage.clone() else {
+            return;
+        };
+        let Some(mut pushes) = sync.receiver.lock().unwrap().take() else {
+            return;
+        };
+        let mut shutdown = app_state.shutdown_receiver();
+        let mut changed = sync.store.changed(sync.poll_interval);
+        loop {
+            tokio::select! {
+                Some(name) = pushes.recv() => {
+                    if let Err(e) = Self::push(&app_state, &sync, &name).await {
+                        warn!("failed to sync {} to {}: {}", name, sync.store.id(), e);
+                    }
+                }
+                result = &mut changed => {
+                    // watch 连接失败时按轮询间隔重试，避免空转
+                    if let Err(e) = result {
+                        warn!("failed to watch {}: {}", sync.store.id(), e);
+                        tokio::time::sleep(sync.poll_interval).await;
+                    }
+                    if let Err(e) = Self::poll(&app_state, &sync).await {
+                        warn!("failed to poll {}: {}", sync.store.id(), e);
+                    }
+                    changed = sync.store.changed(sync.poll_interval);
+                }
+                _ = wait_for_shutdown(&mut shutdown) => return,
+            }
+        }
+    }
+
+    // 下载 revision 变化的配置，删除存储中已不存在的配置（本地未改动时），返回存储中的配置名称
+    async fn poll(app_state: &AppState, sync: &StorageSync) -> Result<HashSet<String>, ConfigError> {
+        let config_path = app_state.config_path();
+        let stored = sync.store.list().await?;
+        let names: HashSet<String> = stored.iter().map(|config| config.name.clone()).collect();
+        for config in stored {
+            let known = sync.synced.lock().unwrap().get(&config.name).cloned();
+            if known.is_some_and(|known| known.revision == config.revision) {
+                continue;
+            }
+            let Some(content) = sync.store.get_content(&config.name).await? else {
+                continue;
+            };
+            let hash = sha256_hex(content.as_bytes());
+            let local = tokio::fs::read(Path::new(&config_path).join(&config.name)).await.ok();
+            if local.is_none_or(|local| sha256_hex(&local) != hash) {
+                let _writes = app_state.lock_writes().await;
+                FileConfigRepository::new(config_path.clone())
+                    .save_content(&content, &config.name)
+                    .await?;
+                info!("pulled {} from {}", config.name, sync.store.id());
+            }
+            let synced = SyncedConfig {
+                revision: config.revision,
+                hash,
+            };
+            sync.synced.lock().unwrap().insert(config.name, synced);
+        }
+
+        let removed: Vec<(String, SyncedConfig)> = sync
+            .synced
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(name, _)| !names.contains(*name))
+            .map(|(name, synced)| (name.clone(), synced.clone()))
+            .collect();
+        for (name, synced) in removed {
+            sync.synced.lock().unwrap().remove(&name);
+            let path = Path::new(&config_path).join(&name);
+            let local = tokio::fs::read(&path).await.ok();
+            if local.is_some_and(|local| sha256_hex(&local) == synced.hash) {
+                let _writes = app_state.lock_writes().await;
+                tokio::fs::remove_file(&path).await?;
+                info!("{} was deleted from {}", name, sync.store.id());
+            }
+        }
+        Ok(names)
+    }
+
+    // 本地文件存在且与上次同步的内容不同时写回存储；文件已删除时从存储中删除
+    async fn push(app_state: &AppState, sync: &StorageSync, name: &str) -> Result<(), ConfigError> {
+        let path = Path::new(&app_state.config_path()).join(name);
+        match tokio::fs::read_to_string(&path).await {
+            Ok(content) => {
+                let hash = sha256_hex(content.as_bytes());
+                let known = sync.synced.lock().unwrap().get(name).cloned();
+                if known.is_some_and(|known| known.hash == hash) {
+                    return Ok(());
+                }
+                // 存储未返回 revision 时下次检查会重新下载，内容相同不会写回本地
+                let revision = sync.store.save_content(name, &content).await?;
+                let synced = SyncedConfig {
+                    revision: revision.unwrap_or_default(),
+                    hash,
+                };
+                sync.synced.lock().unwrap().insert(name.to_string(), synced);
+                info!("pushed {} to {}", name, sync.store.id());
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                if sync.synced.lock().unwrap().remove(name).is_some() {
+                    sync.store.delete_content(name).await?;
+                    info!("deleted {} from {}", name, sync.store.id());
+                }
+            }
+            Err(e) => return Err(e.into()),
+        }
+        Ok(())
+    }
+
+    // 存储中的规则写入 rules/ 目录，只存在于本地的规则文件写回存储
+    async fn sync_rules(app_state: &AppState, sync: &StorageSync) -> Result<(), ConfigError> {
+        let rules_dir = AttachedRulesService::rules_dir(&app_state.config_path());
+        let stored = sync.store.list_rules().await?;
+        for (name, content) in stored.iter() {
+            let path = rules_dir.join(name);
+            if tokio::fs::read_to_string(&path).await.ok().as_ref() != Some(content) {
+                tokio::fs::create_dir_all(&rules_dir).await?;
+                atomic_write_async(path, content).await?;
+            }
+        }
+        if sync.read_only {
+            return Ok(());
+        }
+        let Ok(mut entries) = tokio::fs::read_dir(&rules_dir).await else {
+            return Ok(());
+        };
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.ends_with(ATTACHED_RULES_SUFFIX) && !stored.iter().any(|(stored, _)| *stored == name) {
+                let content = tokio::fs::read_to_string(entry.path()).await?;
+                sync.store.save_rules(&name, &content).await?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::BTreeMap;
+
+use tracing::{info, warn};
+
+use crate::{
+    application::{
+        dtos::config_transaction::{ConfigTransaction, TransactionResult},
+        services::{attached_rules_service::AttachedRulesService, audit_service::AuditService},
+    },
+    domain::entities::{
+        audit::{AuditAction, AuditActor},
+        configuration::Config,
+    },
+    shared::{app_state::AppState, error::ConfigError},
+};
+
+pub struct TransactionService;
+
+impl TransactionService {
+    // 原子提交：先在副本上应用全部修改，再逐个写盘，任一文件写入失败则回滚已写入的文件
+    pub async fn commit(
+        app_state: &AppState,
+        transaction: ConfigTransaction,
+        actor: &AuditActor,
+    ) -> Result<TransactionResult, ConfigError> {
+        let _writes = app_state.lock_writes().await;
+        let change_count = transaction.changes.len();
+
+        let mut staged: BTreeMap<String, (Config, Config)> = BTreeMap::new();
+        for change in transaction.changes {
+            if !staged.contains_key(&change.file) {
+                app_state.check_unlocked(&change.file)?;
+                let original = app_state
+                    .config_map
+                    .get(&change.file)
+                    .ok_or_else(|| ConfigError::ConfigNotFound(change.file.clone()))?;
+                staged.insert(change.file.clone(), (original.clone(), original));
+            }
+            let (_, updated) = staged.get_mut(&change.file).unwrap();
+            updated.set(&change.key, change.value)?;
+        }
+
+        // 与单键修改一样检查附加规则，任一文件不满足时整个事务不生效
+        let config_path = app_state.config_path();
+        for (file, (_, updated)) in staged.iter() {
+            AttachedRulesService::check(&config_path, file, updated)?;
+        }
+
+        let repository = app_state.repository();
+        let mut written: Vec<&String> = Vec::new();
+        for (file, (_, updated)) in staged.iter() {
+            if let Err(e) = repository.save(file, updated.clone()).await {
+                for file in written {
+                    let (original, _) = &staged[file];
+                    if let Err(e) = repository.save(file, original.clone()).await {
+                        warn!("rollback {} failed: {}", file, e);
+                    }
+                }
+                return Err(e);
+            }
+            written.push(file);
+        }
+
+        let files: Vec<String> = staged.keys().cloned().collect();
+        for (file, (original, updated)) in staged {
+            AuditService::record_change(
+                app_state,
+                AuditAction::Update,
+                &file,
+                actor,
+                Some(&original),
+                Some(&updated),
+                Some(format!("transaction with {} changes", change_count)),
+            );
+            app_state.store_write(file, updated, &actor.source);
+        }
+        info!(
+            "transaction committed: {} changes across {} files",
+            change_count,
+            files.len()
+        );
+
+        Ok(TransactionResult {
+            changes: change_count,
+            files,
+        })
+    }
+}
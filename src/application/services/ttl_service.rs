@@ -0,0 +1,197 @@
+use std::sync::Arc;
+
+use chrono::Duration;
+use tracing::{info, warn};
+
+use crate::{
+    application::{dtos::ttl_request::TtlRequest, services::audit_service::AuditService},
+    domain::entities::{
+        audit::{AuditAction, AuditActor},
+        config_ttl::{ConfigTtl, TTL_KEY, TtlSource},
+        configuration::ConfigValue,
+    },
+    infrastructure::{
+        notification::dispatcher::dispatch, schedules::file_ttl_store::FileTtlStore,
+    },
+    shared::{
+        app_state::{AppState, wait_for_shutdown},
+        error::ConfigError,
+    },
+};
+
+// 到期写入的来源，订阅者据此区分过期与普通修改
+const TTL_SOURCE: &str = "ttl";
+// 到期时配置被锁定，解锁前每隔这段时间重试
+const LOCKED_RETRY_SECS: i64 = 30;
+
+// 临时修改的过期：键到期后删除或恢复为默认值，整个配置到期后删除或整体恢复，随后照常推送
+pub struct TtlService;
+
+impl TtlService {
+    // 通过接口设置的规则替换同一目标已有的规则（包括配置中的声明）
+    pub fn set(app_state: &AppState, file: &str, request: TtlRequest) -> Result<ConfigTtl, ConfigError> {
+        if request.ttl_secs == 0 {
+            return Err(ConfigError::InvalidTtl("ttl_secs must be greater than 0".to_string()));
+        }
+        let config = app_state
+            .config_map
+            .get(file)
+            .ok_or_else(|| ConfigError::ConfigNotFound(file.to_string()))?;
+        match (&request.key, &request.default) {
+            (Some(key), _) if config.get(key).is_none() => return Err(ConfigError::KeyNotFound),
+            (Some(_), Some(default)) => {
+                ConfigValue::from_serde_json(default.clone())?;
+            }
+            (None, Some(default)) if !default.is_object() => {
+                return Err(ConfigError::InvalidTtl(
+                    "default for a whole config must be an object".to_string(),
+                ));
+            }
+            _ => {}
+        }
+        let ttl = ConfigTtl::new(
+            file,
+            request.key,
+            request.ttl_secs,
+            request.default,
+            app_state.clock.now(),
+            TtlSource::Api,
+        );
+        app_state.ttls.lock().unwrap().set(ttl.clone());
+        app_state.ttls_changed.notify_one();
+        info!(
+            "{}{} expires at {}",
+            file,
+            ttl.key.as_deref().map(|key| format!(" key {}", key)).unwrap_or_default(),
+            ttl.expires_at.to_rfc3339()
+        );
+        Ok(ttl)
+    }
+
+    pub fn list(app_state: &AppState, file: &str) -> Vec<ConfigTtl> {
+        app_state.ttls.lock().unwrap().list(file)
+    }
+
+    // 恢复上次运行时保存的规则，在开始接受请求之前调用
+    pub fn restore(app_state: &AppState) {
+        match FileTtlStore::new(&app_state.config_path()).load() {
+            Ok(ttls) => {
+                if !ttls.is_empty() {
+                    info!("restored {} config ttls", ttls.len());
+                }
+                let mut restored = app_state.ttls.lock().unwrap();
+                for ttl in ttls {
+                    restored.set(ttl);
+                }
+            }
+            Err(e) => warn!("restore config ttls failed: {}", e),
+        }
+    }
+
+    // 启动加载完成后同步已加载配置中的 $ttl 声明，之后按时间处理到期的规则直到服务关闭；
+    // 规则每次变化后保存，停机期间到期的规则在启动后立即处理
+    pub async fn run(app_state: Arc<AppState>) {
+        let now = app_state.clock.now();
+        for file in app_state.config_map.keys() {
+            let Some(config) = app_state.config_map.peek(&file) else {
+                continue;
+            };
+            match ConfigTtl::declared(&file, &config.to_serde_value(), now) {
+                Ok(declared) => {
+                    app_state.ttls.lock().unwrap().sync_declared(&file, declared);
+                }
+                Err(e) => warn!("ignore ttl declaration: {}", e),
+            }
+        }
+        let store = FileTtlStore::new(&app_state.config_path());
+        let mut shutdown = app_state.shutdown_receiver();
+        loop {
+            let next = {
+                let ttls = app_state.ttls.lock().unwrap();
+                if let Err(e) = store.save(ttls.all()) {
+                    warn!("save config ttls failed: {}", e);
+                }
+                ttls.next_at()
+            };
+            if let Some(next) = next {
+                let delay = (next - app_state.clock.now()).to_std().unwrap_or_default();
+                tokio::select! {
+                    _ = tokio::time::sleep(delay) => {}
+                    _ = app_state.ttls_changed.notified() => continue,
+                    _ = wait_for_shutdown(&mut shutdown) => return,
+                }
+            } else {
+                tokio::select! {
+                    _ = app_state.ttls_changed.notified() => continue,
+                    _ = wait_for_shutdown(&mut shutdown) => return,
+                }
+            }
+
+            let now = app_state.clock.now();
+            let due = app_state.ttls.lock().unwrap().take_due(now);
+            for mut ttl in due {
+                match Self::expire(&app_state, &ttl).await {
+                    Ok(()) => info!(
+                        "{}{} expired",
+                        ttl.file,
+                        ttl.key.as_deref().map(|key| format!(" key {}", key)).unwrap_or_default()
+                    ),
+                    Err(e @ (ConfigError::ConfigLocked { .. } | ConfigError::ConfigsFrozen { .. })) => {
+                        warn!("ttl on {} postponed: {}", ttl.file, e);
+                        ttl.expires_at = now + Duration::seconds(LOCKED_RETRY_SECS);
+                        app_state.ttls.lock().unwrap().set(ttl);
+                    }
+                    Err(e) => warn!("ttl on {} dropped: {}", ttl.file, e),
+                }
+            }
+        }
+    }
+
+    // 写入方式与接口修改相同；键到期时一并移除配置中对应的 $ttl 声明，避免重新加载后再次计时
+    async fn expire(app_state: &Arc<AppState>, ttl: &ConfigTtl) -> Result<(), ConfigError> {
+        let writes = app_state.lock_writes().await;
+        app_state.check_unlocked(&ttl.file)?;
+        let before = app_state
+            .config_map
+            .get(&ttl.file)
+            .ok_or_else(|| ConfigError::ConfigNotFound(ttl.file.clone()))?;
+        let actor = AuditActor::new(TTL_SOURCE, None);
+        let detail = Some(match &ttl.key {
+            Some(key) => format!("key {} expired", key),
+            None => "config expired".to_string(),
+        });
+        let mut config = before.clone();
+        match (&ttl.key, &ttl.default) {
+            (None, None) => {
+                app_state.repository().delete(&ttl.file).await?;
+                AuditService::record_change(app_state, AuditAction::Delete, &ttl.file, &actor, Some(&before), None, detail);
+                let change = app_state.remove_config(&ttl.file);
+                drop(writes);
+                if let Some(mut change) = change {
+                    change.source = TTL_SOURCE.to_string();
+                    dispatch(app_state, change);
+                }
+                return Ok(());
+            }
+            (None, Some(default)) => {
+                config.config = ConfigValue::from_serde_json(default.clone())?.into_object()?;
+            }
+            (Some(key), Some(default)) => config.set(key, ConfigValue::from_serde_json(default.clone())?)?,
+            (Some(key), None) => {
+                config.remove(key);
+            }
+        }
+        if let Some(key) = &ttl.key
+            && let Some(ConfigValue::Object(mut declared)) = config.config.remove(TTL_KEY)
+        {
+            declared.remove(key);
+            if !declared.is_empty() {
+                config.config.insert(TTL_KEY.to_string(), ConfigValue::Object(declared));
+            }
+        }
+        app_state.repository().save(&ttl.file, config.clone()).await?;
+        AuditService::record_change(app_state, AuditAction::Update, &ttl.file, &actor, Some(&before), Some(&config), detail);
+        app_state.store_write(ttl.file.clone(), config, TTL_SOURCE);
+        Ok(())
+    }
+}
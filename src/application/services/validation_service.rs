@@ -1,108 +1,908 @@
-use tracing::debug;
+use std::{collections::HashMap, sync::Arc};
+
+use colored::{Color, Colorize};
+use tracing::{debug, warn};
 
 use crate::{
-    domain::entities::{
-        configuration::{Config, ConfigValue},
-        validation_rule::{FieldType, Validation},
+    application::dtos::rules_file::{CrossFieldRuleSpec, FieldRule, RulesFileV2},
+    domain::{
+        entities::{
+            configuration::{Config, ConfigValue},
+            validation_rule::{
+                CompareOp, CrossFieldRule, DeprecatedField, FieldType, Severity, Validation,
+                ValidationResult,
+            },
+        },
+        repositories::configuration_repository::ConfigurationRepository,
+        services::{
+            config_validation::ConfigValidationService, format_converter::FormatConverterService,
+            script_rule::ScriptRuleService,
+        },
+        value_objects::{config_format::ConfigType, config_path::ConfigPath},
     },
-    shared::error::ConfigError,
+    infrastructure::sources::config_source_factory::ConfigSourceFactory,
+    shared::{error::ConfigError, utils::read_file},
 };
 
+const DEFAULT_SCRIPT_RULES_DIR: &str = "rules.d";
+
+// 单个文件的校验结果
+#[derive(Debug)]
+pub struct FileValidationReport {
+    pub file: String,
+    pub config_type: Option<ConfigType>,
+    pub parse_error: Option<ConfigError>,
+    pub result: Option<ValidationResult>,
+}
+
+impl FileValidationReport {
+    pub fn is_valid(&self) -> bool {
+        self.parse_error.is_none() && self.result.as_ref().is_none_or(|r| r.is_valid)
+    }
+
+    pub fn has_warnings(&self) -> bool {
+        self.result.as_ref().is_some_and(|r| !r.warnings.is_empty())
+    }
+
+    // --strict：警告视为错误
+    pub fn strict(mut self) -> Self {
+        self.result = self.result.map(ValidationResult::strict);
+        self
+    }
+}
+
 pub struct ValidationService;
 
 impl ValidationService {
+    // 按 version 选择解析方式：缺省或 1 为宽松的 v1 格式，2 为强类型格式
     pub fn get_validation_by_config(config: &Config) -> Result<Validation, ConfigError> {
+        match config.get("version") {
+            None => Self::parse_v1(config),
+            Some(version) => match version.as_number() {
+                Some(1.0) => Self::parse_v1(config),
+                Some(2.0) => Self::parse_v2(config),
+                _ => Err(ConfigError::InvalidRules {
+                    path: "version".to_string(),
+                    error: format!(
+                        "unsupported rules version {}, expected 1 or 2",
+                        version.to_serde_value()
+                    ),
+                }),
+            },
+        }
+    }
+
+    fn parse_v2(config: &Config) -> Result<Validation, ConfigError> {
+        let rules: RulesFileV2 = serde_path_to_error::deserialize(config.to_serde_value())
+            .map_err(|e| ConfigError::InvalidRules {
+                path: e.path().to_string(),
+                error: e.inner().to_string(),
+            })?;
+        let invalid = |path: String, error: &str| ConfigError::InvalidRules {
+            path,
+            error: error.to_string(),
+        };
+
         let mut validation = Validation::default();
-        if let Some(field) = config.get("required_fields") {
-            if let ConfigValue::Array(array) = field {
-                validation.required_fields = array
-                    .iter()
-                    .map(|v| {
-                        if let ConfigValue::String(s) = v {
-                            s.to_string()
-                        } else {
-                            "".to_string()
-                        }
+        validation.required_fields = rules.required_fields;
+        validation.field_types = rules
+            .field_types
+            .into_iter()
+            .map(|(field, rule)| {
+                let path = format!("field_types.{}", field);
+                Ok((field, Self::field_rule_to_type(rule, &path)?))
+            })
+            .collect::<Result<_, ConfigError>>()?;
+        validation.patterns = ConfigValidationService::compile_patterns(&validation.field_types)?;
+        validation.severities = rules
+            .severities
+            .into_iter()
+            .filter(|(_, severity)| *severity != Severity::Error)
+            .collect();
+
+        for (index, rule) in rules.cross_field_rules.into_iter().enumerate() {
+            let path = format!("cross_field_rules[{}]", index);
+            let rule = match rule {
+                CrossFieldRuleSpec::Requires {
+                    when,
+                    equals,
+                    fields,
+                    severity,
+                } => {
+                    if fields.is_empty() {
+                        return Err(invalid(path, "requires needs at least one field"));
+                    }
+                    let equals = equals.map(ConfigValue::from_serde_json).transpose()?;
+                    (CrossFieldRule::Requires { when, equals, then: fields }, severity)
+                }
+                CrossFieldRuleSpec::MutuallyExclusive { fields, severity } => {
+                    if fields.len() < 2 {
+                        return Err(invalid(path, "mutually_exclusive needs at least two fields"));
+                    }
+                    (CrossFieldRule::MutuallyExclusive { fields }, severity)
+                }
+                CrossFieldRuleSpec::Compare {
+                    left,
+                    op,
+                    right,
+                    severity,
+                } => (CrossFieldRule::Compare { left, op, right }, severity),
+            };
+            validation.cross_field_rules.push(rule);
+        }
+
+        validation.deprecated_fields = rules
+            .deprecated_fields
+            .into_iter()
+            .map(|(field, spec)| {
+                (
+                    field,
+                    DeprecatedField {
+                        replacement: spec.replacement,
+                        removed_in: spec.removed_in,
+                    },
+                )
+            })
+            .collect();
+        validation.deny_unknown_fields = rules.deny_unknown_fields;
+        for (field, value) in rules.defaults {
+            validation
+                .defaults
+                .insert(field, ConfigValue::from_serde_json(value)?);
+        }
+        debug!("validation rules v2: {:?}", validation);
+        Ok(validation)
+    }
+
+    // path 为规则在规则文件中的位置，用于错误提示
+    fn field_rule_to_type(rule: FieldRule, path: &str) -> Result<FieldType, ConfigError> {
+        Ok(match rule {
+            FieldRule::String {
+                min_length,
+                max_length,
+                pattern,
+            } => FieldType::String {
+                max_length,
+                min_length,
+                pattern,
+            },
+            FieldRule::Number { min, max } => FieldType::Number { min, max },
+            FieldRule::Integer { min, max } => FieldType::Integer { min, max },
+            FieldRule::Boolean => FieldType::Boolean,
+            FieldRule::Duration { min, max } => FieldType::Duration { min, max },
+            FieldRule::ByteSize { min, max } => FieldType::ByteSize { min, max },
+            FieldRule::Url { schemes } => FieldType::Url { schemes },
+            FieldRule::IpAddr => FieldType::IpAddr,
+            FieldRule::FilePath { must_exist } => FieldType::FilePath { must_exist },
+            FieldRule::Enum { allowed } => FieldType::Enum {
+                values: allowed
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, value)| {
+                        ConfigValue::from_serde_json(value).map_err(|e| ConfigError::InvalidRules {
+                            path: format!("{}.allowed[{}]", path, index),
+                            error: e.to_string(),
+                        })
                     })
-                    .collect::<Vec<String>>();
-            }
+                    .collect::<Result<_, _>>()?,
+            },
+            FieldRule::Array {
+                items,
+                min_items,
+                max_items,
+                unique,
+            } => FieldType::Array {
+                item_type: Box::new(match items {
+                    Some(items) => Self::field_rule_to_type(*items, &format!("{}.items", path))?,
+                    None => FieldType::String {
+                        max_length: None,
+                        min_length: None,
+                        pattern: None,
+                    },
+                }),
+                min_items,
+                max_items,
+                unique,
+            },
+            FieldRule::Object {
+                fields,
+                required,
+                allow_unknown,
+            } => FieldType::Object {
+                fields: fields
+                    .into_iter()
+                    .map(|(name, rule)| {
+                        let path = format!("{}.fields.{}", path, name);
+                        Ok((name, Self::field_rule_to_type(rule, &path)?))
+                    })
+                    .collect::<Result<_, ConfigError>>()?,
+                required,
+                allow_unknown,
+            },
+        })
+    }
+
+    fn parse_v1(config: &Config) -> Result<Validation, ConfigError> {
+        let mut validation = Validation::default();
+        if let Some(ConfigValue::Array(array)) = config.get("required_fields") {
+            validation.required_fields = array
+                .iter()
+                .map(|v| {
+                    if let ConfigValue::String(s) = v {
+                        s.to_string()
+                    } else {
+                        "".to_string()
+                    }
+                })
+                .collect::<Vec<String>>();
         }
         debug!("required_fields: {:?}", validation.required_fields);
 
-        if let Some(field) = config.get("field_types") {
-            if let ConfigValue::Object(object) = field {
-                validation.field_types = object
-                    .iter()
-                    .map(|(k, v)| {
-                        debug!("k: {k}, v: {:?}", v);
+        if let Some(ConfigValue::Object(object)) = config.get("field_types") {
+            validation.field_types = object
+                .iter()
+                .map(|(k, v)| {
+                    debug!("k: {k}, v: {:?}", v);
+                    (k.to_string(), Self::parse_field_type(k, v))
+                })
+                .collect();
+            // severity: warning/info 的字段只产生警告，不导致校验失败
+            for (k, v) in object.iter() {
+                let severity = Self::parse_severity(v)?;
+                if severity != Severity::Error {
+                    validation.severities.insert(k.to_string(), severity);
+                }
+            }
+        }
+        validation.patterns = ConfigValidationService::compile_patterns(&validation.field_types)?;
+
+        if let Some(ConfigValue::Array(rules)) = config.get("cross_field_rules") {
+            validation.cross_field_rules = rules
+                .iter()
+                .map(|rule| Ok((Self::parse_cross_field_rule(rule)?, Self::parse_severity(rule)?)))
+                .collect::<Result<Vec<(CrossFieldRule, Severity)>, ConfigError>>()?;
+        }
+        debug!("cross_field_rules: {:?}", validation.cross_field_rules);
+
+        // deprecated_fields 的值可以是替代键，也可以是 { replacement, removed_in }
+        if let Some(ConfigValue::Object(object)) = config.get("deprecated_fields") {
+            for (field, v) in object.iter() {
+                let deprecated = match v {
+                    ConfigValue::String(replacement) => DeprecatedField {
+                        replacement: Some(replacement.clone()),
+                        removed_in: None,
+                    },
+                    ConfigValue::Object(options) => DeprecatedField {
+                        replacement: options.get("replacement").and_then(|r| r.as_string()).cloned(),
+                        removed_in: options.get("removed_in").map(|r| match r {
+                            ConfigValue::String(s) => s.clone(),
+                            other => other.to_serde_value().to_string(),
+                        }),
+                    },
+                    _ => DeprecatedField::default(),
+                };
+                validation.deprecated_fields.insert(field.to_string(), deprecated);
+            }
+        }
+        debug!("deprecated_fields: {:?}", validation.deprecated_fields);
+
+        if let Some(ConfigValue::Boolean(deny)) = config.get("deny_unknown_fields") {
+            validation.deny_unknown_fields = deny;
+        }
+
+        // defaults 的键为点分路径，例如 { server.port: 8080 }
+        if let Some(ConfigValue::Object(object)) = config.get("defaults") {
+            validation.defaults = object.clone().into_iter().collect();
+        }
+        debug!("defaults: {:?}", validation.defaults);
+
+        Ok(validation)
+    }
+
+    fn parse_severity(rule: &ConfigValue) -> Result<Severity, ConfigError> {
+        match rule.as_object().and_then(|r| r.get("severity")) {
+            Some(ConfigValue::String(severity)) => Severity::parse(severity)
+                .ok_or_else(|| ConfigError::InvalidSeverity(severity.clone())),
+            Some(other) => Err(ConfigError::InvalidSeverity(other.to_serde_value().to_string())),
+            None => Ok(Severity::Error),
+        }
+    }
+
+    // 解析跨字段约束，支持以下三种写法：
+    //   - requires: [tls.cert_path]   # 可为单个字符串
+    //     when: tls.enabled
+    //     equals: true                # 可选，缺省时 when 字段存在且不为 false/null 即触发
+    //   - mutually_exclusive: [auth.password, auth.token]
+    //   - compare: "pool.min <= pool.max"
+    fn parse_cross_field_rule(v: &ConfigValue) -> Result<CrossFieldRule, ConfigError> {
+        let Some(rule) = v.as_object() else {
+            return Err(ConfigError::InvalidCrossFieldRule(v.to_serde_value().to_string()));
+        };
+        let invalid = |reason: &str| {
+            ConfigError::InvalidCrossFieldRule(format!("{} in {}", reason, v.to_serde_value()))
+        };
+        let fields = |value: &ConfigValue| -> Vec<String> {
+            match value {
+                ConfigValue::String(s) => vec![s.clone()],
+                ConfigValue::Array(items) => items.iter().filter_map(|i| i.as_string().cloned()).collect(),
+                _ => vec![],
+            }
+        };
+
+        if let Some(requires) = rule.get("requires") {
+            let when = rule
+                .get("when")
+                .and_then(|w| w.as_string())
+                .cloned()
+                .ok_or_else(|| invalid("requires needs a when field"))?;
+            let then = fields(requires);
+            if then.is_empty() {
+                return Err(invalid("requires needs at least one field"));
+            }
+            return Ok(CrossFieldRule::Requires {
+                when,
+                equals: rule.get("equals").cloned(),
+                then,
+            });
+        }
+
+        if let Some(exclusive) = rule.get("mutually_exclusive") {
+            let fields = fields(exclusive);
+            if fields.len() < 2 {
+                return Err(invalid("mutually_exclusive needs at least two fields"));
+            }
+            return Ok(CrossFieldRule::MutuallyExclusive { fields });
+        }
+
+        if let Some(ConfigValue::String(expression)) = rule.get("compare") {
+            let parts: Vec<&str> = expression.split_whitespace().collect();
+            let [left, op, right] = parts[..] else {
+                return Err(invalid("compare expects \"<field> <op> <field>\""));
+            };
+            let op = CompareOp::parse(op).ok_or_else(|| invalid("unknown compare operator"))?;
+            return Ok(CrossFieldRule::Compare {
+                left: left.to_string(),
+                op,
+                right: right.to_string(),
+            });
+        }
+
+        Err(invalid("expected one of requires, mutually_exclusive, compare"))
+    }
+
+    // 解析单个字段的类型约束，数组的 items 递归解析
+    fn parse_field_type(k: &str, v: &ConfigValue) -> FieldType {
+        if let ConfigValue::Object(field_config) = v {
+            // 先获取类型，只声明了 allowed 时视为枚举
+            let default_type = if field_config.contains_key("allowed") {
+                "enum"
+            } else {
+                "string"
+            };
+            let field_type_str = field_config
+                .get("type")
+                .and_then(|t| t.as_string())
+                .map(|s| s.as_str())
+                .unwrap_or(default_type);
+            
+            debug!("Field {} type: {}", k, field_type_str);
+            
+            // 根据类型解析对应的约束
+            let field_type = match field_type_str {
+                "string" => {
+                    let max_length = field_config
+                        .get("max")
+                        .and_then(|v| v.as_number())
+                        .map(|n| n as usize);
+                        
+                    let min_length = field_config
+                        .get("min")
+                        .and_then(|v| v.as_number())
+                        .map(|n| n as usize);
+                        
+                    let pattern = field_config
+                        .get("pattern")
+                        .and_then(|v| v.as_string())
+                        .cloned();
+
+                    debug!("String constraints - min: {:?}, max: {:?}, pattern: {:?}", min_length, max_length, pattern);
+                    
+                    FieldType::String {
+                        max_length,
+                        min_length,
+                        pattern,
+                    }
+                }
+                "number" => {
+                    let min = field_config
+                        .get("min")
+                        .and_then(|v| v.as_number());
+                        
+                    let max = field_config
+                        .get("max")
+                        .and_then(|v| v.as_number());
                         
-                        if let ConfigValue::Object(field_config) = v {
-                            // 先获取类型
-                            let field_type_str = field_config
-                                .get("type")
-                                .and_then(|t| t.as_string())
-                                .map(|s| s.as_str())
-                                .unwrap_or("string");
-                            
-                            debug!("Field {} type: {}", k, field_type_str);
-                            
-                            // 根据类型解析对应的约束
-                            let field_type = match field_type_str {
-                                "string" => {
-                                    let max_length = field_config
-                                        .get("max")
-                                        .and_then(|v| v.as_number())
-                                        .map(|n| n as usize);
-                                        
-                                    let min_length = field_config
-                                        .get("min")
-                                        .and_then(|v| v.as_number())
-                                        .map(|n| n as usize);
-                                        
-                                    debug!("String constraints - min: {:?}, max: {:?}", min_length, max_length);
-                                    
-                                    FieldType::String {
-                                        max_length,
-                                        min_length,
-                                    }
-                                }
-                                "number" => {
-                                    let min = field_config
-                                        .get("min")
-                                        .and_then(|v| v.as_number());
-                                        
-                                    let max = field_config
-                                        .get("max")
-                                        .and_then(|v| v.as_number());
-                                        
-                                    debug!("Number constraints - min: {:?}, max: {:?}", min, max);
-                                    
-                                    FieldType::Number { min, max }
-                                }
-                                "boolean" => FieldType::Boolean,
-                                _ => {
-                                    debug!("Unknown field type: {}, defaulting to String", field_type_str);
-                                    FieldType::String {
-                                        max_length: None,
-                                        min_length: None,
-                                    }
-                                }
-                            };
-                            
-                            debug!("Final field_type for {}: {:?}", k, field_type);
-                            (k.to_string(), field_type)
-                        } else {
-                            debug!("Invalid field config for {}: not an object", k);
-                            (k.to_string(), FieldType::String {
-                                max_length: None,
-                                min_length: None,
+                    debug!("Number constraints - min: {:?}, max: {:?}", min, max);
+                    
+                    FieldType::Number { min, max }
+                }
+                "integer" => {
+                    let min = field_config.get("min").and_then(|v| v.as_number()).map(|n| n as i64);
+                    let max = field_config.get("max").and_then(|v| v.as_number()).map(|n| n as i64);
+
+                    debug!("Integer constraints - min: {:?}, max: {:?}", min, max);
+
+                    FieldType::Integer { min, max }
+                }
+                "boolean" => FieldType::Boolean,
+                "duration" => {
+                    let min = field_config.get("min").and_then(|v| v.as_duration());
+                    let max = field_config.get("max").and_then(|v| v.as_duration());
+
+                    debug!("Duration constraints - min: {:?}, max: {:?}", min, max);
+
+                    FieldType::Duration { min, max }
+                }
+                "bytesize" | "byte_size" => {
+                    let min = field_config.get("min").and_then(|v| v.as_byte_size());
+                    let max = field_config.get("max").and_then(|v| v.as_byte_size());
+
+                    debug!("ByteSize constraints - min: {:?}, max: {:?}", min, max);
+
+                    FieldType::ByteSize { min, max }
+                }
+                "url" => {
+                    let schemes = match field_config.get("schemes") {
+                        Some(ConfigValue::Array(schemes)) => schemes
+                            .iter()
+                            .filter_map(|v| v.as_string().cloned())
+                            .collect(),
+                        _ => vec![],
+                    };
+                    FieldType::Url { schemes }
+                }
+                "ip" | "ip_addr" | "ipaddr" => FieldType::IpAddr,
+                "path" | "file_path" => {
+                    let must_exist = matches!(field_config.get("must_exist"), Some(ConfigValue::Boolean(true)));
+                    FieldType::FilePath { must_exist }
+                }
+                "object" => {
+                    let fields = match field_config.get("fields") {
+                        Some(ConfigValue::Object(fields)) => fields
+                            .iter()
+                            .map(|(name, v)| {
+                                (name.to_string(), Self::parse_field_type(&format!("{}.{}", k, name), v))
                             })
-                        }
-                    })
-                    .collect();
+                            .collect(),
+                        _ => HashMap::new(),
+                    };
+
+                    let required = match field_config.get("required") {
+                        Some(ConfigValue::Array(required)) => required
+                            .iter()
+                            .filter_map(|v| v.as_string().cloned())
+                            .collect(),
+                        _ => vec![],
+                    };
+
+                    // 默认允许未声明的子字段
+                    let allow_unknown = !matches!(field_config.get("allow_unknown"), Some(ConfigValue::Boolean(false)));
+
+                    debug!("Object constraints - fields: {}, required: {:?}, allow_unknown: {}", fields.len(), required, allow_unknown);
+
+                    FieldType::Object {
+                        fields,
+                        required,
+                        allow_unknown,
+                    }
+                }
+                "array" => {
+                    // items 描述元素类型，缺省时元素不做约束
+                    let item_type = match field_config.get("items") {
+                        Some(items) => Self::parse_field_type(&format!("{}[]", k), items),
+                        None => FieldType::String {
+                            max_length: None,
+                            min_length: None,
+                            pattern: None,
+                        },
+                    };
+
+                    let min_items = field_config
+                        .get("min_items")
+                        .and_then(|v| v.as_number())
+                        .map(|n| n as usize);
+
+                    let max_items = field_config
+                        .get("max_items")
+                        .and_then(|v| v.as_number())
+                        .map(|n| n as usize);
+
+                    let unique = matches!(field_config.get("unique"), Some(ConfigValue::Boolean(true)));
+
+                    debug!("Array constraints - min: {:?}, max: {:?}, unique: {}", min_items, max_items, unique);
+
+                    FieldType::Array {
+                        item_type: Box::new(item_type),
+                        min_items,
+                        max_items,
+                        unique,
+                    }
+                }
+                "enum" => {
+                    let values = match field_config.get("allowed") {
+                        Some(ConfigValue::Array(values)) => values.clone(),
+                        _ => vec![],
+                    };
+
+                    debug!("Enum constraints - allowed: {:?}", values);
+
+                    FieldType::Enum { values }
+                }
+                _ => {
+                    warn!("field {}: unknown type {}, treated as string (use rules version 2 to reject it)", k, field_type_str);
+                    FieldType::String {
+                        max_length: None,
+                        min_length: None,
+                        pattern: None,
+                    }
+                }
+            };
+            
+            debug!("Final field_type for {}: {:?}", k, field_type);
+            field_type
+        } else {
+            warn!("field {}: rule is not an object, treated as string", k);
+            FieldType::String {
+                max_length: None,
+                min_length: None,
+                pattern: None,
             }
         }
+    }
+
+    pub fn load_validation_file(path: &str) -> Result<Validation, ConfigError> {
+        let content = read_file(path)?;
+        let validation_config =
+            FormatConverterService::new(ConfigPath::new(path)?, content).validate_config()?;
+        let mut validation = Self::get_validation_by_config(&validation_config)?;
 
+        // 脚本规则目录相对规则文件所在目录，未配置时使用同级的 rules.d/
+        let base = std::path::Path::new(path).parent().unwrap_or(std::path::Path::new(""));
+        let scripts_dir = match validation_config.get("custom_rules_dir") {
+            Some(ConfigValue::String(dir)) => Some(base.join(dir)),
+            _ => Some(base.join(DEFAULT_SCRIPT_RULES_DIR)).filter(|dir| dir.is_dir()),
+        };
+        if let Some(dir) = scripts_dir {
+            let rules = ScriptRuleService::load_dir(&dir)?;
+            debug!("loaded {} script rules from {}", rules.len(), dir.display());
+            validation.custom_rules.extend(rules);
+        }
         Ok(validation)
     }
+
+    // 填充默认值后按 output 的扩展名写出，返回实际填充的键
+    pub fn write_with_defaults(
+        file: &str,
+        validation: &Validation,
+        output: &str,
+    ) -> Result<Vec<String>, ConfigError> {
+        let content = read_file(file)?;
+        let mut config = FormatConverterService::new(ConfigPath::new(file)?, content).validate_config()?;
+        let extension = std::path::Path::new(output)
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let target = match extension.as_str() {
+            "yml" => ConfigType::Yaml,
+            ext => ConfigType::from(ext),
+        };
+        if target == ConfigType::Unknown {
+            return Err(ConfigError::UnsupportedFormat {
+                format: output.to_string(),
+            });
+        }
+        let applied = validation.apply_defaults(&mut config)?;
+        std::fs::write(output, config.serialize_to(&target)?).map_err(ConfigError::IoError)?;
+        Ok(applied)
+    }
+
+    pub fn validate_file(file: &str, validation: Option<&Validation>) -> FileValidationReport {
+        debug!("validate: {}", file);
+        let config = read_file(file).and_then(|content| {
+            FormatConverterService::new(ConfigPath::new(file)?, content).validate_config()
+        });
+        Self::validate_loaded(file, config, validation)
+    }
+
+    pub fn validate_loaded(
+        file: &str,
+        config: Result<Config, ConfigError>,
+        validation: Option<&Validation>,
+    ) -> FileValidationReport {
+        let mut report = FileValidationReport {
+            file: file.to_string(),
+            config_type: None,
+            parse_error: None,
+            result: None,
+        };
+        match config {
+            Ok(config) => {
+                report.config_type = Some(config.config_type.clone());
+                if let Some(validation) = validation {
+                    report.result = Some(validation.validate(&config));
+                }
+            }
+            Err(e) => report.parse_error = Some(e),
+        }
+        report
+    }
+
+    // 从仓储（例如远程服务）获取配置后逐个校验
+    pub async fn validate_from_repository(
+        repository: &dyn ConfigurationRepository,
+        names: Vec<String>,
+        validation: Option<&Validation>,
+    ) -> Vec<FileValidationReport> {
+        let mut reports = Vec::with_capacity(names.len());
+        for name in names {
+            let config = repository.get(&name).await;
+            reports.push(Self::validate_loaded(&name, config, validation));
+        }
+        reports
+    }
+
+    // 并发校验多个文件，结果顺序与输入顺序一致；输入可以是本地路径、URL 或 "-"（标准输入）
+    pub async fn validate_files(
+        files: Vec<String>,
+        validation: Option<Arc<Validation>>,
+    ) -> Vec<FileValidationReport> {
+        let handles: Vec<_> = files
+            .into_iter()
+            .map(|file| {
+                let validation = validation.clone();
+                tokio::spawn(async move {
+                    let source = ConfigSourceFactory::create(&file);
+                    let config = source.load().await;
+                    Self::validate_loaded(source.name(), config, validation.as_deref())
+                })
+            })
+            .collect();
+
+        let mut reports = Vec::with_capacity(handles.len());
+        for handle in handles {
+            match handle.await {
+                Ok(report) => reports.push(report),
+                Err(e) => debug!("validation task failed: {}", e),
+            }
+        }
+        reports
+    }
+
+    pub fn print_summary(reports: &[FileValidationReport]) {
+        let width = reports
+            .iter()
+            .map(|r| r.file.len())
+            .max()
+            .unwrap_or(4)
+            .max(4);
+        println!("{:<6}  {:<width$}  {:<7}  DETAILS", "STATUS", "FILE", "FORMAT");
+        for report in reports {
+            let status = if !report.is_valid() {
+                "FAIL".color(Color::Red)
+            } else if report.has_warnings() {
+                "WARN".color(Color::Yellow)
+            } else {
+                "PASS".color(Color::Green)
+            };
+            let format = report
+                .config_type
+                .as_ref()
+                .map(|t| t.to_string())
+                .unwrap_or_else(|| "-".to_string());
+            let details = if let Some(e) = &report.parse_error {
+                e.to_string()
+            } else if let Some(result) = &report.result {
+                let findings = [
+                    ("", &result.errors),
+                    ("warning: ", &result.warnings),
+                    ("info: ", &result.infos),
+                ];
+                findings
+                    .iter()
+                    .flat_map(|(prefix, errors)| errors.iter().map(move |e| format!("{}{}", prefix, e)))
+                    .collect::<Vec<String>>()
+                    .join("; ")
+            } else {
+                String::new()
+            };
+            println!(
+                "{:<6}  {:<width$}  {:<7}  {}",
+                status, report.file, format, details
+            );
+        }
+
+        let failed = reports.iter().filter(|r| !r.is_valid()).count();
+        let warned = reports.iter().filter(|r| r.is_valid() && r.has_warnings()).count();
+        println!(
+            "\n{} files checked, {} passed, {} with warnings, {} failed",
+            reports.len(),
+            (reports.len() - failed - warned).to_string().color(Color::Green),
+            warned.to_string().color(Color::Yellow),
+            failed.to_string().color(Color::Red)
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(file: &str, content: &str) -> Config {
+        FormatConverterService::new(ConfigPath::new(file).unwrap(), content.to_string())
+            .validate_config()
+            .unwrap()
+    }
+
+    fn rules(content: &str) -> Result<Validation, ConfigError> {
+        ValidationService::get_validation_by_config(&parse("rules.yaml", content))
+    }
+
+    // 错误的规则标识，按出现顺序
+    fn kinds(validation: &Validation, config: &str) -> Vec<&'static str> {
+        let result = validation.validate(&parse("app.json", config));
+        result.errors.iter().map(|e| e.kind()).collect()
+    }
+
+    #[test]
+    fn requires_checks_fields_when_triggered() {
+        let validation = rules(
+            "cross_field_rules:\n  - requires: [tls.cert_path, tls.key_path]\n    when: tls.enabled\n",
+        )
+        .unwrap();
+        assert!(kinds(&validation, r#"{"tls": {"enabled": false}}"#).is_empty());
+        assert!(kinds(&validation, r#"{"tls": {"enabled": true, "cert_path": "c", "key_path": "k"}}"#).is_empty());
+        let result = validation.validate(&parse("app.json", r#"{"tls": {"enabled": true, "cert_path": "c"}}"#));
+        assert_eq!(result.errors.len(), 1);
+        assert_eq!(result.errors[0].to_string(), "field tls.key_path is required when tls.enabled is set");
+
+        let validation = rules(
+            "cross_field_rules:\n  - requires: auth.token\n    when: auth.mode\n    equals: token\n",
+        )
+        .unwrap();
+        assert!(kinds(&validation, r#"{"auth": {"mode": "password"}}"#).is_empty());
+        assert_eq!(kinds(&validation, r#"{"auth": {"mode": "token"}}"#), ["ConditionalRequired"]);
+    }
+
+    #[test]
+    fn mutually_exclusive_allows_at_most_one_field() {
+        let validation = rules(
+            "cross_field_rules:\n  - mutually_exclusive: [auth.password, auth.token]\n    severity: warning\n",
+        )
+        .unwrap();
+        let result = validation.validate(&parse("app.json", r#"{"auth": {"token": "t"}}"#));
+        assert!(result.warnings.is_empty());
+        let result = validation.validate(&parse("app.json", r#"{"auth": {"password": "p", "token": "t"}}"#));
+        assert!(result.is_valid);
+        assert_eq!(result.warnings[0].to_string(), "fields auth.password, auth.token are mutually exclusive");
+    }
+
+    #[test]
+    fn compare_checks_numeric_fields() {
+        let validation = rules("cross_field_rules:\n  - compare: \"pool.min <= pool.max\"\n").unwrap();
+        assert!(kinds(&validation, r#"{"pool": {"min": 1, "max": 10}}"#).is_empty());
+        assert!(kinds(&validation, r#"{"pool": {"min": 1}}"#).is_empty());
+        assert_eq!(kinds(&validation, r#"{"pool": {"min": 10, "max": 1}}"#), ["ComparisonFailed"]);
+        assert_eq!(kinds(&validation, r#"{"pool": {"min": "a", "max": 1}}"#), ["TypeMismatch"]);
+    }
+
+    #[test]
+    fn invalid_cross_field_rules_are_rejected() {
+        for (rule, reason) in [
+            ("requires: [a]", "requires needs a when field"),
+            ("{requires: [], when: b}", "requires needs at least one field"),
+            ("mutually_exclusive: [a]", "mutually_exclusive needs at least two fields"),
+            ("compare: a <=", "compare expects"),
+            ("compare: a ~ b", "unknown compare operator"),
+            ("unique: [a, b]", "expected one of requires, mutually_exclusive, compare"),
+        ] {
+            let error = rules(&format!("cross_field_rules:\n  - {}\n", rule)).unwrap_err();
+            assert!(
+                matches!(&error, ConfigError::InvalidCrossFieldRule(message) if message.contains(reason)),
+                "{}: unexpected error {:?}",
+                rule,
+                error
+            );
+        }
+        let error = rules("cross_field_rules:\n  - {compare: a < b, severity: fatal}\n").unwrap_err();
+        assert!(matches!(error, ConfigError::InvalidSeverity(severity) if severity == "fatal"));
+    }
+
+    fn invalid_rules(content: &str) -> (String, String) {
+        match rules(content) {
+            Err(ConfigError::InvalidRules { path, error }) => (path, error),
+            other => panic!("expected invalid rules, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn v2_rules_are_parsed_into_typed_constraints() {
+        let validation = rules(
+            "version: 2\nrequired_fields: [database.host]\nfield_types:\n  database.port: { type: integer, min: 1, max: 65535 }\n  server.timeout: { type: duration, max: 5m }\n  mode: { type: enum, allowed: [dev, prod] }\nseverities: { mode: warning }\ncross_field_rules:\n  - { rule: compare, left: pool.min, op: \"<=\", right: pool.max }\ndefaults: { server.timeout: 30s }\n",
+        )
+        .unwrap();
+        assert_eq!(validation.required_fields, ["database.host"]);
+        assert!(matches!(validation.field_types["database.port"], FieldType::Integer { min: Some(1), max: Some(65535) }));
+        assert!(matches!(
+            validation.field_types["server.timeout"],
+            FieldType::Duration { min: None, max: Some(max) } if max == std::time::Duration::from_secs(300)
+        ));
+        assert_eq!(validation.severities["mode"], Severity::Warning);
+        assert_eq!(validation.cross_field_rules.len(), 1);
+
+        let result = validation.validate(&parse(
+            "app.json",
+            r#"{"database": {"host": "db", "port": 70000}, "mode": "test", "pool": {"min": 5, "max": 1}}"#,
+        ));
+        let kinds: Vec<&str> = result.errors.iter().map(|e| e.kind()).collect();
+        assert_eq!(kinds.iter().filter(|kind| **kind == "TypeMismatch").count(), 1);
+        assert!(kinds.contains(&"ComparisonFailed"));
+        assert_eq!(result.warnings.len(), 1);
+    }
+
+    #[test]
+    fn v2_errors_point_at_the_invalid_rule() {
+        let (path, error) = invalid_rules("version: 2\nfield_types:\n  port: { type: integr }\n");
+        assert_eq!(path, "field_types.port.type");
+        assert!(error.contains("unknown variant `integr`"), "{}", error);
+
+        let (path, error) = invalid_rules("version: 2\nfield_types:\n  port: { type: integer, maximum: 10 }\n");
+        assert_eq!(path, "field_types.port");
+        assert!(error.contains("unknown field `maximum`"), "{}", error);
+
+        let (path, error) = invalid_rules("version: 2\nfield_types:\n  timeout: { type: duration, max: 5 minutes }\n");
+        assert_eq!(path, "field_types.timeout");
+        assert!(error.contains("invalid duration: 5 minutes"), "{}", error);
+
+        let (path, error) = invalid_rules("version: 2\nrequired_field: [a]\n");
+        assert_eq!(path, "required_field");
+        assert!(error.contains("unknown field `required_field`"), "{}", error);
+
+        let (path, error) = invalid_rules("version: 2\ncross_field_rules:\n  - { rule: requires, when: a, fields: [] }\n");
+        assert_eq!(path, "cross_field_rules[0]");
+        assert_eq!(error, "requires needs at least one field");
+
+        let (path, error) = invalid_rules("version: 2\ncross_field_rules:\n  - { rule: compare, left: a, op: \"~\", right: b }\n");
+        assert_eq!(path, "cross_field_rules[0]");
+        assert!(error.contains("~"), "{}", error);
+
+        let (path, error) = invalid_rules("version: 3\n");
+        assert_eq!(path, "version");
+        assert!(error.contains("unsupported rules version 3"), "{}", error);
+    }
+
+    #[test]
+    fn v1_rules_keep_unknown_types_as_strings() {
+        let validation = rules("version: 1\nfield_types:\n  port: { type: integr }\n").unwrap();
+        assert!(matches!(validation.field_types["port"], FieldType::String { .. }));
+    }
+
+    #[test]
+    fn rules_file_loads_scripts_from_rules_d() {
+        let dir = std::env::temp_dir().join(format!("config-manager-rules-file-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("rules.d")).unwrap();
+        std::fs::write(dir.join("rules.d/port.rhai"), r#"if config.port < 1024 { "privileged port" }"#).unwrap();
+        std::fs::write(dir.join("rules.yaml"), "required_fields: [port]\n").unwrap();
+        let rules_file = dir.join("rules.yaml").display().to_string();
+
+        let validation = ValidationService::load_validation_file(&rules_file).unwrap();
+        assert_eq!(validation.custom_rules.len(), 1);
+        let result = validation.validate(&parse("app.json", r#"{"port": 80}"#));
+        assert_eq!(result.errors[0].to_string(), "custom rule port: privileged port");
+
+        // custom_rules_dir 指向的目录必须存在
+        std::fs::write(dir.join("rules.yaml"), "custom_rules_dir: checks\n").unwrap();
+        assert!(matches!(ValidationService::load_validation_file(&rules_file), Err(ConfigError::IoError(_))));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
 }
@@ -0,0 +1,112 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use tokio::sync::mpsc::UnboundedSender;
+use tracing::{debug, info, warn};
+
+use crate::{
+    application::services::attached_rules_service::AttachedRulesService,
+    domain::events::config_changed::ConfigChange,
+    infrastructure::{
+        notification::dispatcher::dispatch, watchers::config_watcher::ConfigWatcher,
+    },
+    shared::{app_state::AppState, error::ConfigError},
+};
+
+// serve 的文件监听：HTTP 与 TCP 共用，去抖后重新加载新建、修改的配置并移除已删除的配置，
+// 内容变化时交给推送通道
+pub struct WatchService;
+
+impl WatchService {
+    // 递归监听配置目录，子目录中的配置以相对路径为名称；返回的监听器释放后停止监听
+    pub fn watch(
+        app_state: &Arc<AppState>,
+        tx: UnboundedSender<ConfigChange>,
+    ) -> Result<ConfigWatcher, ConfigError> {
+        let app_state_for_watcher = app_state.clone();
+        let mut watcher = ConfigWatcher::with_options(app_state.watch, move |file_path| {
+            if let Some(change) = Self::reload(&app_state_for_watcher, file_path)
+                && tx.send(change).is_err()
+            {
+                debug!("notify channel is closed");
+            }
+        })?;
+        watcher.watch(Path::new(&app_state.config_path()), true)?;
+        let _ = app_state.watcher_health.set(watcher.health());
+        info!("config watcher init finished ({:?})", app_state.watch.mode);
+        Ok(watcher)
+    }
+
+    // 重新加载配置目录中的一个配置并推送，用于解锁后应用锁定期间被拒绝的修改
+    pub async fn reload_config(app_state: &Arc<AppState>, name: &str) {
+        let file_path = Path::new(&app_state.config_path()).join(name);
+        let state = app_state.clone();
+        let change = tokio::task::spawn_blocking(move || Self::reload(&state, file_path))
+            .await
+            .ok()
+            .flatten();
+        if let Some(change) = change {
+            dispatch(app_state, change);
+        }
+    }
+
+    // 重新加载一个文件，文件不存在时从缓存中移除；内容没有变化或加载失败时返回 None
+    fn reload(app_state: &AppState, file_path: PathBuf) -> Option<ConfigChange> {
+        // 隐藏目录（例如 .history）中的文件不加载
+        let name = app_state.config_key(&file_path)?;
+        let (validation, config_path) = (app_state.validation.clone(), app_state.config_path());
+        // 与接口的写入互斥，避免读到写了一半的文件
+        let writes = app_state.blocking_lock_writes();
+        // 锁定期间文件的外部修改（包括删除）不生效，继续提供锁定时的版本，解锁后重新加载；
+        // 锁定前接口已经写盘的修改照常加载
+        if !app_state.has_pending_write(&name)
+            && let Err(e) = app_state.check_unlocked(&name)
+        {
+            warn!("config reload rejected, keeping previous version: {}", e);
+            app_state.record_load_failure(&name, e.to_string());
+            return None;
+        }
+        // 文件已被删除或移走（去抖窗口内删除后又重新创建的仍按修改处理）
+        if !file_path.exists() {
+            let change = app_state.remove_config(&name);
+            if change.is_some() {
+                info!("config watcher removed: {}", name);
+            }
+            return change;
+        }
+        // 不满足附加规则的修改不进入缓存，继续提供上一个有效版本
+        let loaded = ConfigWatcher::load_as(&file_path, name.clone(), validation.as_deref())
+            .and_then(|loaded| {
+                AttachedRulesService::check(&config_path, &loaded.0, &loaded.1).map(|_| loaded)
+            });
+        match loaded {
+            Ok((file_name, config, config_str)) => {
+                app_state.clear_load_failure(&file_name);
+                let change = app_state.reload_config(file_name.clone(), config, config_str);
+                drop(writes);
+                let Some(change) = change else {
+                    debug!("config content unchanged, skip notify: {}", file_name);
+                    return None;
+                };
+                let (history, now) = (app_state.history(), app_state.clock.now());
+                if let Err(e) = history.record_file(&file_name, &file_path, now) {
+                    warn!("record history for {} failed: {}", file_name, e);
+                }
+                info!("config watcher reloaded: {}", file_name);
+                Some(change)
+            }
+            Err(e @ ConfigError::AttachedRulesViolation { .. }) => {
+                warn!("config reload rejected, keeping previous version: {}", e);
+                app_state.record_load_failure(&name, e.to_string());
+                None
+            }
+            Err(e) => {
+                debug!("config reload failed: {:?} - {}", file_path, e);
+                app_state.record_load_failure(&name, e.to_string());
+                None
+            }
+        }
+    }
+}
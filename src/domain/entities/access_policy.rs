@@ -0,0 +1,74 @@
+use std::{collections::HashMap, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+// 未携带 API key 的客户端在策略文件中的名称
+pub const ANONYMOUS: &str = "anonymous";
+
+// 权限从低到高排列，高权限包含低权限
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Permission {
+    Read,
+    Write,
+    Admin,
+}
+
+impl Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Permission::Read => write!(f, "read"),
+            Permission::Write => write!(f, "write"),
+            Permission::Admin => write!(f, "admin"),
+        }
+    }
+}
+
+// 角色在匹配 configs（glob 模式，例如 "team-a/*"）的配置上拥有的权限
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Grant {
+    pub configs: String,
+    pub permissions: Vec<Permission>,
+}
+
+impl Grant {
+    fn allows(&self, permission: Permission, config: &str) -> bool {
+        self.permissions.iter().any(|granted| *granted >= permission)
+            && glob::Pattern::new(&self.configs).is_ok_and(|pattern| pattern.matches(config))
+    }
+}
+
+// 访问策略文件，bindings 把 API key 名称映射到角色，例如：
+//   roles:
+//     team-a-editor:
+//       - configs: "team-a/*"
+//         permissions: [write]
+//     ops:
+//       - configs: "*"
+//         permissions: [admin]
+//   bindings:
+//     deploy: [team-a-editor]
+//     oncall: [ops]
+//     anonymous: []
+// 未绑定任何角色的调用方没有任何权限
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccessPolicy {
+    #[serde(default)]
+    pub roles: HashMap<String, Vec<Grant>>,
+    #[serde(default)]
+    pub bindings: HashMap<String, Vec<String>>,
+}
+
+impl AccessPolicy {
+    pub fn allows(&self, subject: Option<&str>, permission: Permission, config: &str) -> bool {
+        self.bindings
+            .get(subject.unwrap_or(ANONYMOUS))
+            .into_iter()
+            .flatten()
+            .filter_map(|role| self.roles.get(role))
+            .flatten()
+            .any(|grant| grant.allows(permission, config))
+    }
+}
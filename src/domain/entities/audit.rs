@@ -0,0 +1,90 @@
+use std::net::SocketAddr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::services::config_diff::{DiffEntry, DiffKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Create,
+    Update,
+    Delete,
+    Reload,
+}
+
+impl std::fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            AuditAction::Create => "create",
+            AuditAction::Update => "update",
+            AuditAction::Delete => "delete",
+            AuditAction::Reload => "reload",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// 一条审计记录：谁（source）在什么时候对哪个配置做了什么
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditRecord {
+    pub id: String,
+    pub timestamp: DateTime<Utc>,
+    pub action: AuditAction,
+    pub config: String,
+    pub source: String, // 变更来源：file_watcher, http_api, tcp_client
+    #[serde(default)]
+    pub actor: Option<String>, // 客户端地址
+    pub detail: Option<String>,
+    #[serde(default)]
+    pub changes: Vec<AuditChange>,
+}
+
+// 记录中单个叶子键的前后值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditChange {
+    pub key: String,
+    pub kind: DiffKind,
+    pub old: Option<serde_json::Value>,
+    pub new: Option<serde_json::Value>,
+}
+
+impl From<DiffEntry> for AuditChange {
+    fn from(entry: DiffEntry) -> Self {
+        Self {
+            key: entry.key,
+            kind: entry.kind,
+            old: entry.old.map(|value| value.to_serde_value()),
+            new: entry.new.map(|value| value.to_serde_value()),
+        }
+    }
+}
+
+// 发起修改的一方：来源接口、客户端地址以及鉴权时使用的 API key 名称
+#[derive(Debug, Clone)]
+pub struct AuditActor {
+    pub source: String,
+    pub address: Option<String>,
+    pub api_key: Option<String>,
+}
+
+impl AuditActor {
+    pub fn new(source: &str, address: Option<SocketAddr>) -> Self {
+        Self {
+            source: source.to_string(),
+            address: address.map(|address| address.to_string()),
+            api_key: None,
+        }
+    }
+
+    pub fn with_api_key(mut self, name: &str) -> Self {
+        self.api_key = Some(name.to_string());
+        self
+    }
+
+    // 使用了 API key 时记录 key 名称，否则记录客户端地址
+    pub fn name(&self) -> Option<String> {
+        self.api_key.clone().or_else(|| self.address.clone())
+    }
+}
@@ -0,0 +1,100 @@
+use std::{collections::HashSet, sync::Arc};
+
+use chrono::{DateTime, Duration, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::domain::{
+    repositories::backup_store::BackupStore,
+    value_objects::{cron_schedule::CronSchedule, namespace::Namespace},
+};
+
+pub const BACKUP_SUFFIX: &str = ".tar.gz";
+const BACKUP_TIME_FORMAT: &str = "%Y%m%dT%H%M%S%.3fZ";
+
+// 一份备份：名称为 "<命名空间或 config>-<UTC 时间>.tar.gz"，创建时间从名称中解析
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BackupInfo {
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+    pub size: u64,
+}
+
+impl BackupInfo {
+    pub fn name_for(prefix: &str, at: DateTime<Utc>) -> String {
+        format!("{}-{}{}", prefix, at.format(BACKUP_TIME_FORMAT), BACKUP_SUFFIX)
+    }
+
+    // 不符合命名格式的文件不是备份，返回 None
+    pub fn parse(name: &str, size: u64) -> Option<Self> {
+        let (_, time) = name.strip_suffix(BACKUP_SUFFIX)?.rsplit_once('-')?;
+        let created_at = NaiveDateTime::parse_from_str(time, BACKUP_TIME_FORMAT).ok()?;
+        Some(Self {
+            name: name.to_string(),
+            created_at: created_at.and_utc(),
+            size,
+        })
+    }
+}
+
+// 保留策略：保留最近 keep_last 份，另外在最近 keep_daily 天内每天保留当天最后一份；
+// 两者都为 0 时不清理
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct RetentionPolicy {
+    #[serde(default = "default_keep_last")]
+    pub keep_last: usize,
+    #[serde(default)]
+    pub keep_daily: u32,
+}
+
+fn default_keep_last() -> usize {
+    10
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            keep_last: default_keep_last(),
+            keep_daily: 0,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    // 超出保留策略、应当删除的备份
+    pub fn expired(&self, backups: &[BackupInfo], now: DateTime<Utc>) -> Vec<BackupInfo> {
+        if self.keep_last == 0 && self.keep_daily == 0 {
+            return vec![];
+        }
+        let mut backups = backups.to_vec();
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+        let oldest_day = (now - Duration::days(self.keep_daily as i64 - 1)).date_naive();
+        let mut days = HashSet::new();
+        backups
+            .into_iter()
+            .enumerate()
+            .filter(|(index, backup)| {
+                let day = backup.created_at.date_naive();
+                let daily = self.keep_daily > 0 && day >= oldest_day && days.insert(day);
+                *index >= self.keep_last && !daily
+            })
+            .map(|(_, backup)| backup)
+            .collect()
+    }
+}
+
+// serve 的定时备份：按计划导出配置目录（或一个命名空间）并写入备份存储
+pub struct BackupPlan {
+    pub schedule: CronSchedule,
+    pub store: Arc<dyn BackupStore>,
+    pub namespace: Option<Namespace>,
+    pub retention: RetentionPolicy,
+}
+
+impl BackupPlan {
+    pub fn prefix(&self) -> &str {
+        self.namespace
+            .as_ref()
+            .map(|namespace| namespace.as_str())
+            .unwrap_or("config")
+    }
+}
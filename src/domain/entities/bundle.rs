@@ -0,0 +1,66 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+pub const MANIFEST_FILE: &str = "manifest.json";
+pub const SIGNATURE_FILE: &str = "manifest.sig";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BundleEntryKind {
+    Config,
+    Schema,
+    Policy,
+}
+
+impl BundleEntryKind {
+    // 归档内的目录前缀
+    pub fn dir(&self) -> &'static str {
+        match self {
+            BundleEntryKind::Config => "configs",
+            BundleEntryKind::Schema => "schemas",
+            BundleEntryKind::Policy => "policies",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleEntry {
+    pub path: String,
+    pub kind: BundleEntryKind,
+    pub sha256: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    // bundle export 导出单个命名空间时记录来源，包内路径相对该命名空间
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub namespace: Option<String>,
+    pub entries: Vec<BundleEntry>,
+}
+
+impl BundleManifest {
+    pub fn new() -> Self {
+        Self {
+            version: BUNDLE_FORMAT_VERSION,
+            created_at: Utc::now(),
+            namespace: None,
+            entries: vec![],
+        }
+    }
+
+    pub fn configs(&self) -> impl Iterator<Item = &BundleEntry> {
+        self.entries
+            .iter()
+            .filter(|e| e.kind == BundleEntryKind::Config)
+    }
+}
+
+impl Default for BundleManifest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
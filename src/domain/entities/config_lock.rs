@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::{domain::value_objects::namespace::covers, shared::error::ConfigError};
+
+// 一把锁：谁因为什么锁定，到期时间为 None 时直到手动解锁
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigLock {
+    pub owner: String,
+    pub reason: String,
+    pub locked_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ConfigLock {
+    fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_none_or(|expires_at| now < expires_at)
+    }
+}
+
+// 按配置名称加的锁（以 "/" 结尾时锁定整个命名空间）与全局冻结；过期的锁在检查时清理
+#[derive(Debug, Default)]
+pub struct ConfigLocks {
+    configs: BTreeMap<String, ConfigLock>,
+    freeze: Option<ConfigLock>,
+}
+
+impl ConfigLocks {
+    // 已有的锁被替换（例如延长期限）
+    pub fn lock(&mut self, name: &str, lock: ConfigLock) {
+        self.configs.insert(name.to_string(), lock);
+    }
+
+    pub fn unlock(&mut self, name: &str, now: DateTime<Utc>) -> Option<ConfigLock> {
+        self.prune(now);
+        self.configs.remove(name)
+    }
+
+    pub fn freeze(&mut self, lock: ConfigLock) {
+        self.freeze = Some(lock);
+    }
+
+    pub fn unfreeze(&mut self, now: DateTime<Utc>) -> Option<ConfigLock> {
+        self.prune(now);
+        self.freeze.take()
+    }
+
+    pub fn get(&mut self, name: &str, now: DateTime<Utc>) -> Option<ConfigLock> {
+        self.prune(now);
+        self.configs.get(name).cloned()
+    }
+
+    pub fn frozen(&mut self, now: DateTime<Utc>) -> Option<ConfigLock> {
+        self.prune(now);
+        self.freeze.clone()
+    }
+
+    pub fn list(&mut self, now: DateTime<Utc>) -> BTreeMap<String, ConfigLock> {
+        self.prune(now);
+        self.configs.clone()
+    }
+
+    // 到期时移除这把锁（已被手动解锁或清理时不变），已被新的锁替换时返回 false
+    pub fn expire(&mut self, name: Option<&str>, lock: &ConfigLock) -> bool {
+        let current = match name {
+            Some(name) => self.configs.get(name),
+            None => self.freeze.as_ref(),
+        };
+        if current.is_some_and(|current| current != lock) {
+            return false;
+        }
+        match name {
+            Some(name) => {
+                self.configs.remove(name);
+            }
+            None => self.freeze = None,
+        }
+        true
+    }
+
+    // 全局冻结或配置（及其所在命名空间）被锁定时拒绝修改
+    pub fn check(&mut self, name: &str, now: DateTime<Utc>) -> Result<(), ConfigError> {
+        self.prune(now);
+        if let Some(freeze) = &self.freeze {
+            return Err(ConfigError::ConfigsFrozen {
+                owner: freeze.owner.clone(),
+                reason: freeze.reason.clone(),
+            });
+        }
+        match self.configs.iter().find(|(locked, _)| covers(locked, name)) {
+            Some((locked, lock)) => Err(ConfigError::ConfigLocked {
+                config: locked.clone(),
+                owner: lock.owner.clone(),
+                reason: lock.reason.clone(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    fn prune(&mut self, now: DateTime<Utc>) {
+        self.configs.retain(|_, lock| lock.is_active(now));
+        if self.freeze.as_ref().is_some_and(|freeze| !freeze.is_active(now)) {
+            self.freeze = None;
+        }
+    }
+}
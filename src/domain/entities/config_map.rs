@@ -0,0 +1,359 @@
+use std::sync::{
+    Arc, OnceLock,
+    atomic::{AtomicU64, AtomicUsize, Ordering},
+};
+
+use chrono::{DateTime, Utc};
+use dashmap::{DashMap, mapref::entry::Entry};
+use serde::Serialize;
+
+use crate::{
+    domain::{
+        entities::configuration::{Config, ConfigValue},
+        value_objects::config_format::ConfigType,
+    },
+    shared::utils::sha256_hex,
+};
+
+// 条目元数据：size 与 modified_at 来自源文件，key_count 为叶子键数量，
+// 懒加载模式下从未解析过的配置没有 key_count
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigMetadata {
+    pub size: u64,
+    pub format: ConfigType,
+    pub modified_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key_count: Option<usize>,
+}
+
+impl ConfigMetadata {
+    pub fn new(config: &Config, size: u64, modified_at: DateTime<Utc>) -> Self {
+        Self {
+            size,
+            format: config.config_type.clone(),
+            modified_at,
+            key_count: Some(config.config.values().map(Self::count_keys).sum()),
+        }
+    }
+
+    // 尚未解析的文件，格式由扩展名推断
+    pub fn unloaded(format: ConfigType, size: u64, modified_at: DateTime<Utc>) -> Self {
+        Self {
+            size,
+            format,
+            modified_at,
+            key_count: None,
+        }
+    }
+
+    fn count_keys(value: &ConfigValue) -> usize {
+        match value {
+            ConfigValue::Object(object) if !object.is_empty() => {
+                object.values().map(Self::count_keys).sum()
+            }
+            _ => 1,
+        }
+    }
+}
+
+// 单个缓存条目：保留序列化后的原始字节，解析后的 Config 可被淘汰
+// 解析结果的内存占用按原始字节长度估算；hash 为内容哈希，用作 HTTP ETag
+// 懒加载模式下 raw 为 None 表示尚未加载或已被整体淘汰，访问时由 loader 从文件重新加载；
+// 淘汰后仍保留元数据和最后的哈希，文件监听器据此判断内容是否变化
+struct CacheEntry {
+    raw: Option<Vec<u8>>,
+    parsed: Option<Config>,
+    last_access: AtomicU64,
+    metadata: ConfigMetadata,
+    hash: Option<String>,
+}
+
+impl CacheEntry {
+    fn raw_len(&self) -> usize {
+        self.raw.as_ref().map_or(0, Vec::len)
+    }
+}
+
+// 懒加载模式下按配置名称读取并解析文件，返回配置及其元数据
+pub type ConfigLoader = Arc<dyn Fn(&str) -> Option<(Config, ConfigMetadata)> + Send + Sync>;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CacheMetrics {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub hit_rate: f64,
+    pub entries: usize,
+    pub parsed_entries: usize,
+    pub unloaded_entries: usize,
+    pub raw_bytes: usize,
+    pub parsed_bytes: usize,
+    pub memory_budget: Option<usize>,
+    pub lazy: bool,
+}
+
+/// 用于提供serve下的缓存，可选内存预算，超出时按 LRU 淘汰解析结果
+/// 设置 loader 后为懒加载模式：配置在首次访问时才解析，超出预算时整个条目被淘汰
+/// 条目按分片加锁，命中解析缓存的读取只持有分片读锁，统计使用原子计数
+#[derive(Default)]
+pub struct ConfigMap {
+    entries: DashMap<String, CacheEntry>,
+    memory_budget: Option<usize>,
+    loader: OnceLock<ConfigLoader>,
+    parsed_bytes: AtomicUsize,
+    tick: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+impl ConfigMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_memory_budget(mut self, memory_budget: Option<usize>) -> Self {
+        self.memory_budget = memory_budget;
+        self
+    }
+
+    // 进入懒加载模式，只能设置一次
+    pub fn set_loader(&self, loader: ConfigLoader) {
+        let _ = self.loader.set(loader);
+    }
+
+    pub fn is_lazy(&self) -> bool {
+        self.loader.get().is_some()
+    }
+
+    // 懒加载模式下登记尚未解析的配置，已存在的条目保持不变
+    pub fn register(&self, key: String, metadata: ConfigMetadata) {
+        self.entries.entry(key).or_insert_with(|| CacheEntry {
+            raw: None,
+            parsed: None,
+            last_access: AtomicU64::new(0),
+            metadata,
+            hash: None,
+        });
+    }
+
+    // 插入或替换配置，返回此前是否已存在
+    pub fn insert(&self, key: String, config: Config, metadata: ConfigMetadata) -> bool {
+        let raw = serde_json::to_vec(&config).unwrap_or_default();
+        let hash = Self::content_hash(&config);
+        let size = raw.len();
+        let entry = CacheEntry {
+            raw: Some(raw),
+            parsed: Some(config),
+            last_access: AtomicU64::new(self.next_tick()),
+            metadata,
+            hash: Some(hash),
+        };
+        // 替换条目与解析字节数的增减在同一分片写锁内完成
+        let existed = match self.entries.entry(key.clone()) {
+            Entry::Occupied(mut occupied) => {
+                let previous = occupied.insert(entry);
+                if previous.parsed.is_some() {
+                    self.parsed_bytes.fetch_sub(previous.raw_len(), Ordering::Relaxed);
+                }
+                self.parsed_bytes.fetch_add(size, Ordering::Relaxed);
+                true
+            }
+            Entry::Vacant(vacant) => {
+                vacant.insert(entry);
+                self.parsed_bytes.fetch_add(size, Ordering::Relaxed);
+                false
+            }
+        };
+        self.enforce_budget(&key);
+        existed
+    }
+
+    // 对象键有序的 JSON 表示，内容不变时哈希不变
+    pub fn content_hash(config: &Config) -> String {
+        sha256_hex(&serde_json::to_vec(&config.to_serde_value()).unwrap_or_default())
+    }
+
+    // 命中解析缓存直接返回，否则从原始字节重新解析；懒加载模式下未加载的条目从文件加载
+    pub fn get(&self, key: &str) -> Option<Config> {
+        let tick = self.next_tick();
+        let unloaded = {
+            let entry = self.entries.get(key)?;
+            entry.last_access.store(tick, Ordering::Relaxed);
+            if let Some(config) = &entry.parsed {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return Some(config.clone());
+            }
+            entry.raw.is_none()
+        };
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        if unloaded {
+            return self.load(key);
+        }
+        let config = {
+            let mut entry = self.entries.get_mut(key)?;
+            let config: Config = serde_json::from_slice(entry.raw.as_deref()?).ok()?;
+            // 并发的读取可能已经重新解析过
+            if entry.parsed.is_none() {
+                entry.parsed = Some(config.clone());
+                self.parsed_bytes.fetch_add(entry.raw_len(), Ordering::Relaxed);
+            }
+            config
+        };
+        self.enforce_budget(key);
+        Some(config)
+    }
+
+    // 只返回缓存中已有的配置，不触发加载
+    pub fn peek(&self, key: &str) -> Option<Config> {
+        let entry = self.entries.get(key)?;
+        match &entry.parsed {
+            Some(config) => Some(config.clone()),
+            None => serde_json::from_slice(entry.raw.as_deref()?).ok(),
+        }
+    }
+
+    // 读取文件期间不持有分片锁；加载完成前文件监听器已写入新版本时以监听器的为准
+    fn load(&self, key: &str) -> Option<Config> {
+        let (config, metadata) = (self.loader.get()?)(key)?;
+        let raw = serde_json::to_vec(&config).unwrap_or_default();
+        let size = raw.len();
+        let loaded = {
+            let mut entry = self.entries.get_mut(key)?;
+            match &entry.parsed {
+                Some(current) => current.clone(),
+                None => {
+                    entry.hash = Some(Self::content_hash(&config));
+                    entry.raw = Some(raw);
+                    entry.parsed = Some(config.clone());
+                    entry.metadata = metadata;
+                    self.parsed_bytes.fetch_add(size, Ordering::Relaxed);
+                    config
+                }
+            }
+        };
+        self.enforce_budget(key);
+        Some(loaded)
+    }
+
+    // 懒加载模式下未加载的条目从文件读取删除前的内容，文件已不存在时返回 None
+    pub fn remove(&self, key: &str) -> Option<Config> {
+        let (_, entry) = self.entries.remove(key)?;
+        let size = entry.raw_len();
+        match entry.parsed {
+            Some(config) => {
+                self.parsed_bytes.fetch_sub(size, Ordering::Relaxed);
+                Some(config)
+            }
+            None => match &entry.raw {
+                Some(raw) => serde_json::from_slice(raw).ok(),
+                None => self
+                    .loader
+                    .get()
+                    .and_then(|loader| loader(key))
+                    .map(|(config, _)| config),
+            },
+        }
+    }
+
+    // 懒加载模式下尚未加载或已被整体淘汰
+    pub fn is_cold(&self, key: &str) -> bool {
+        self.entries.get(key).is_some_and(|entry| entry.raw.is_none())
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn metadata(&self, key: &str) -> Option<ConfigMetadata> {
+        self.entries.get(key).map(|entry| entry.metadata.clone())
+    }
+
+    // 懒加载模式下从未加载过的条目先加载再返回哈希
+    pub fn hash(&self, key: &str) -> Option<String> {
+        if let Some(hash) = self.cached_hash(key) {
+            return Some(hash);
+        }
+        self.get(key)?;
+        self.cached_hash(key)
+    }
+
+    // 最后一次加载时的哈希，不触发加载
+    pub fn cached_hash(&self, key: &str) -> Option<String> {
+        self.entries.get(key)?.hash.clone()
+    }
+
+    pub fn keys(&self) -> Vec<String> {
+        self.entries.iter().map(|entry| entry.key().clone()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn metrics(&self) -> CacheMetrics {
+        let hits = self.hits.load(Ordering::Relaxed);
+        let misses = self.misses.load(Ordering::Relaxed);
+        let lookups = hits + misses;
+        let (mut entries, mut parsed_entries, mut unloaded_entries, mut raw_bytes) = (0, 0, 0, 0);
+        for entry in self.entries.iter() {
+            entries += 1;
+            parsed_entries += entry.parsed.is_some() as usize;
+            unloaded_entries += entry.raw.is_none() as usize;
+            raw_bytes += entry.raw_len();
+        }
+        CacheMetrics {
+            hits,
+            misses,
+            evictions: self.evictions.load(Ordering::Relaxed),
+            hit_rate: if lookups == 0 {
+                1.0
+            } else {
+                hits as f64 / lookups as f64
+            },
+            entries,
+            parsed_entries,
+            unloaded_entries,
+            raw_bytes,
+            parsed_bytes: self.parsed_bytes.load(Ordering::Relaxed),
+            memory_budget: self.memory_budget,
+            lazy: self.is_lazy(),
+        }
+    }
+
+    fn next_tick(&self) -> u64 {
+        self.tick.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    // 软限制：淘汰最久未访问的解析结果，刚访问的条目始终保留；懒加载模式下连同原始字节一起淘汰
+    // 挑选淘汰对象时只持有分片读锁，释放后再获取写锁，避免同一分片上的死锁
+    fn enforce_budget(&self, keep: &str) {
+        let Some(budget) = self.memory_budget else {
+            return;
+        };
+        while self.parsed_bytes.load(Ordering::Relaxed) > budget {
+            let victim = self
+                .entries
+                .iter()
+                .filter(|entry| entry.parsed.is_some() && entry.key().as_str() != keep)
+                .min_by_key(|entry| entry.last_access.load(Ordering::Relaxed))
+                .map(|entry| entry.key().clone());
+            let Some(victim) = victim else {
+                break;
+            };
+            if let Some(mut entry) = self.entries.get_mut(&victim)
+                && entry.parsed.take().is_some()
+            {
+                self.parsed_bytes.fetch_sub(entry.raw_len(), Ordering::Relaxed);
+                self.evictions.fetch_add(1, Ordering::Relaxed);
+                if self.is_lazy() {
+                    entry.raw = None;
+                }
+            }
+        }
+    }
+}
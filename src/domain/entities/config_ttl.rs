@@ -0,0 +1,149 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::shared::error::ConfigError;
+
+// 配置中声明过期时间的元数据键：
+// "$ttl": 3600 整个配置一小时后删除；
+// "$ttl": {"features.beta": 3600, "banner": {"ttl_secs": 600, "default": "none"}} 键到期后删除或恢复为 default
+pub const TTL_KEY: &str = "$ttl";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TtlSource {
+    Api,
+    File,
+}
+
+// 一条过期规则：key 为 None 时作用于整个配置，default 为 None 时到期删除，否则恢复为 default
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigTtl {
+    pub file: String,
+    pub key: Option<String>,
+    pub ttl_secs: u64,
+    pub default: Option<serde_json::Value>,
+    pub expires_at: DateTime<Utc>,
+    pub source: TtlSource,
+}
+
+impl ConfigTtl {
+    pub fn new(
+        file: &str,
+        key: Option<String>,
+        ttl_secs: u64,
+        default: Option<serde_json::Value>,
+        now: DateTime<Utc>,
+        source: TtlSource,
+    ) -> Self {
+        Self {
+            file: file.to_string(),
+            key,
+            ttl_secs,
+            default,
+            expires_at: now + Duration::seconds(ttl_secs.min(i64::MAX as u64) as i64),
+            source,
+        }
+    }
+
+    // 同一配置中的同一个键（或整个配置）只有一条规则
+    fn same_target(&self, other: &ConfigTtl) -> bool {
+        self.file == other.file && self.key == other.key
+    }
+
+    // 配置中的 $ttl 声明，未声明时为空
+    pub fn declared(
+        file: &str,
+        config: &serde_json::Value,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<ConfigTtl>, ConfigError> {
+        let declared = |key: Option<String>, ttl_secs: u64, default| {
+            ConfigTtl::new(file, key, ttl_secs, default, now, TtlSource::File)
+        };
+        let invalid = |detail: &str| ConfigError::InvalidTtl(format!("{} in {}: {}", TTL_KEY, file, detail));
+        match config.get(TTL_KEY) {
+            None => Ok(Vec::new()),
+            Some(serde_json::Value::Number(ttl_secs)) => {
+                let ttl_secs = ttl_secs.as_u64().ok_or_else(|| invalid("expected seconds"))?;
+                Ok(vec![declared(None, ttl_secs, None)])
+            }
+            Some(serde_json::Value::Object(keys)) => keys
+                .iter()
+                .map(|(key, rule)| match rule {
+                    serde_json::Value::Number(ttl_secs) => ttl_secs
+                        .as_u64()
+                        .map(|ttl_secs| declared(Some(key.clone()), ttl_secs, None))
+                        .ok_or_else(|| invalid(&format!("{} expected seconds", key))),
+                    serde_json::Value::Object(rule) => rule
+                        .get("ttl_secs")
+                        .and_then(|ttl_secs| ttl_secs.as_u64())
+                        .map(|ttl_secs| declared(Some(key.clone()), ttl_secs, rule.get("default").cloned()))
+                        .ok_or_else(|| invalid(&format!("{} expected ttl_secs", key))),
+                    _ => Err(invalid(&format!("{} expected seconds or {{ttl_secs, default}}", key))),
+                })
+                .collect(),
+            Some(_) => Err(invalid("expected seconds or an object keyed by config key")),
+        }
+    }
+}
+
+// 所有未到期的规则，按到期时间排序
+#[derive(Debug, Default)]
+pub struct ConfigTtls {
+    ttls: Vec<ConfigTtl>,
+}
+
+impl ConfigTtls {
+    // 同一目标已有的规则被替换
+    pub fn set(&mut self, ttl: ConfigTtl) {
+        self.ttls.retain(|existing| !existing.same_target(&ttl));
+        let index = self.ttls.partition_point(|existing| existing.expires_at <= ttl.expires_at);
+        self.ttls.insert(index, ttl);
+    }
+
+    pub fn list(&self, file: &str) -> Vec<ConfigTtl> {
+        self.ttls.iter().filter(|ttl| ttl.file == file).cloned().collect()
+    }
+
+    pub fn all(&self) -> &[ConfigTtl] {
+        &self.ttls
+    }
+
+    pub fn next_at(&self) -> Option<DateTime<Utc>> {
+        self.ttls.first().map(|ttl| ttl.expires_at)
+    }
+
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> Vec<ConfigTtl> {
+        let due = self.ttls.partition_point(|ttl| ttl.expires_at <= now);
+        self.ttls.drain(..due).collect()
+    }
+
+    pub fn remove_file(&mut self, file: &str) -> bool {
+        let before = self.ttls.len();
+        self.ttls.retain(|ttl| ttl.file != file);
+        self.ttls.len() != before
+    }
+
+    // 配置修改后按其中的 $ttl 声明同步：声明不变的规则保留原到期时间，新增或改动的从现在开始计时，
+    // 不再声明的移除；通过接口设置的规则优先于声明。有变化时返回 true
+    pub fn sync_declared(&mut self, file: &str, declared: Vec<ConfigTtl>) -> bool {
+        let before = self.ttls.len();
+        self.ttls.retain(|ttl| {
+            ttl.file != file
+                || ttl.source == TtlSource::Api
+                || declared.iter().any(|declared| declared.same_target(ttl))
+        });
+        let mut changed = self.ttls.len() != before;
+        for ttl in declared {
+            let unchanged = self.ttls.iter().any(|existing| {
+                existing.same_target(&ttl)
+                    && (existing.source == TtlSource::Api
+                        || (existing.ttl_secs == ttl.ttl_secs && existing.default == ttl.default))
+            });
+            if !unchanged {
+                self.set(ttl);
+                changed = true;
+            }
+        }
+        changed
+    }
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 配置文件的一个历史版本，content 为当时磁盘上的原始内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigVersion {
+    pub version: u64,
+    pub saved_at: DateTime<Utc>,
+    pub size: usize,
+    pub sha256: String,
+    pub content: String,
+}
+
+// 历史列表中的一项，不含内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigVersionInfo {
+    pub version: u64,
+    pub saved_at: DateTime<Utc>,
+    pub size: usize,
+    pub sha256: String,
+}
+
+impl From<&ConfigVersion> for ConfigVersionInfo {
+    fn from(version: &ConfigVersion) -> Self {
+        Self {
+            version: version.version,
+            saved_at: version.saved_at,
+            size: version.size,
+            sha256: version.sha256.clone(),
+        }
+    }
+}
@@ -1,16 +1,24 @@
 use std::{collections::HashMap, fmt::Display};
 
 use colored::{Color, Colorize};
+use serde::Deserialize;
 use serde_json::Number;
 
 use crate::{
     domain::{
         entities::template::TemplateType,
-        value_objects::{config_format::ConfigType, config_path::ConfigPath},
+        value_objects::{
+            config_format::ConfigType,
+            config_path::ConfigPath,
+            key_pattern::KeyPattern,
+            units::{parse_byte_size, parse_duration},
+        },
     },
     shared::error::ConfigError,
 };
 
+pub const YAML_DOCUMENTS_KEY: &str = "documents";
+
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Config {
     pub path: ConfigPath,
@@ -18,6 +26,12 @@ pub struct Config {
     pub config_type: ConfigType,
 }
 
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Config {
     pub fn new() -> Self {
         Self {
@@ -38,11 +52,29 @@ impl Config {
                     serde_json::from_str(&config_str).map_err(|_| ConfigError::ParseConfigError)?;
                 ConfigValue::from_serde_json(json_value)?.into_object()?
             }
+            ConfigType::Json5 => {
+                let json_value: serde_json::Value =
+                    json5::from_str(&config_str).map_err(|_| ConfigError::ParseConfigError)?;
+                ConfigValue::from_serde_json(json_value)?.into_object()?
+            }
             ConfigType::Yaml => {
-                let yaml_value: serde_yaml::Value =
-                    serde_yaml::from_str(&config_str).map_err(|_| ConfigError::ParseConfigError)?;
-                let json_value =
-                    serde_json::to_value(yaml_value).map_err(|_| ConfigError::ParseConfigError)?;
+                // 多文档 YAML 以数组形式挂在 documents 键下
+                let mut documents = Vec::new();
+                for document in serde_yaml::Deserializer::from_str(&config_str) {
+                    let yaml_value = serde_yaml::Value::deserialize(document)
+                        .map_err(|_| ConfigError::ParseConfigError)?;
+                    if !yaml_value.is_null() {
+                        documents.push(
+                            serde_json::to_value(yaml_value)
+                                .map_err(|_| ConfigError::ParseConfigError)?,
+                        );
+                    }
+                }
+                let json_value = match documents.len() {
+                    0 => serde_json::Value::Object(serde_json::Map::new()),
+                    1 => documents.remove(0),
+                    _ => serde_json::json!({ YAML_DOCUMENTS_KEY: documents }),
+                };
                 ConfigValue::from_serde_json(json_value)?.into_object()?
             }
             ConfigType::Toml => {
@@ -66,6 +98,29 @@ impl Config {
         })
     }
 
+    // 从多文档 YAML 中选取一个文档；单文档配置只接受下标 0
+    pub fn select_document(self, index: usize) -> Result<Config, ConfigError> {
+        let documents = match self.config.get(YAML_DOCUMENTS_KEY) {
+            Some(ConfigValue::Array(documents))
+                if self.config_type == ConfigType::Yaml && self.config.len() == 1 =>
+            {
+                documents.clone()
+            }
+            _ if index == 0 => return Ok(self),
+            _ => return Err(ConfigError::DocumentNotFound { index, count: 1 }),
+        };
+        let count = documents.len();
+        match documents.into_iter().nth(index) {
+            Some(ConfigValue::Object(config)) => Ok(Config {
+                path: self.path,
+                config,
+                config_type: self.config_type,
+            }),
+            Some(_) => Err(ConfigError::ParseConfigError),
+            None => Err(ConfigError::DocumentNotFound { index, count }),
+        }
+    }
+
     pub fn get(&self, key: &str) -> Option<ConfigValue> {
         let keys: Vec<&str> = key.split(".").collect();
         let mut current_config = &self.config;
@@ -73,12 +128,166 @@ impl Config {
         for k in keys {
             current = current_config.get(k)?;
             if let ConfigValue::Object(obj) = current {
-                current_config = &obj;
+                current_config = obj;
             }
         }
         Some(current.clone())
     }
 
+    // 按点分路径设置值，中间节点不存在或不是对象时会被替换为对象
+    pub fn set(&mut self, key: &str, value: ConfigValue) -> Result<(), ConfigError> {
+        let keys: Vec<&str> = key.split(".").collect();
+        if key.is_empty() || keys.iter().any(|k| k.is_empty()) {
+            return Err(ConfigError::InvalidPath);
+        }
+        let (last, parents) = keys.split_last().ok_or(ConfigError::InvalidPath)?;
+        let mut current = &mut self.config;
+        for k in parents {
+            let entry = current
+                .entry(k.to_string())
+                .or_insert_with(|| ConfigValue::Object(HashMap::new()));
+            if !matches!(entry, ConfigValue::Object(_)) {
+                *entry = ConfigValue::Object(HashMap::new());
+            }
+            current = match entry {
+                ConfigValue::Object(obj) => obj,
+                _ => unreachable!(),
+            };
+        }
+        current.insert(last.to_string(), value);
+        Ok(())
+    }
+
+    // 删除点分路径上的值，返回被删除的值
+    pub fn remove(&mut self, key: &str) -> Option<ConfigValue> {
+        let keys: Vec<&str> = key.split(".").collect();
+        let (last, parents) = keys.split_last()?;
+        let mut current = &mut self.config;
+        for k in parents {
+            current = match current.get_mut(*k)? {
+                ConfigValue::Object(obj) => obj,
+                _ => return None,
+            };
+        }
+        current.remove(*last)
+    }
+
+    // 将 from 处的子树移动到 to，目标已存在或移动到自身内部时报错
+    pub fn rename(&mut self, from: &str, to: &str) -> Result<(), ConfigError> {
+        if from == to || to.starts_with(&format!("{}.", from)) {
+            return Err(ConfigError::InvalidPath);
+        }
+        if self.get(to).is_some() {
+            return Err(ConfigError::KeyAlreadyExists(to.to_string()));
+        }
+        let value = self.remove(from).ok_or(ConfigError::KeyNotFound)?;
+        self.set(to, value)
+    }
+
+    // 将字符串值中的 ${from} 和 ${from.xxx} 引用改写为以 to 开头，返回改写的引用数
+    pub fn rewrite_references(&mut self, from: &str, to: &str) -> usize {
+        fn rewrite_value(value: &mut ConfigValue, from: &str, to: &str) -> usize {
+            match value {
+                ConfigValue::String(s) => {
+                    let (rewritten, count) = rewrite_string(s, from, to);
+                    *s = rewritten;
+                    count
+                }
+                ConfigValue::Array(arr) => arr.iter_mut().map(|v| rewrite_value(v, from, to)).sum(),
+                ConfigValue::Object(obj) => {
+                    obj.values_mut().map(|v| rewrite_value(v, from, to)).sum()
+                }
+                _ => 0,
+            }
+        }
+
+        fn rewrite_string(s: &str, from: &str, to: &str) -> (String, usize) {
+            let mut result = String::with_capacity(s.len());
+            let mut count = 0;
+            let mut rest = s;
+            while let Some(start) = rest.find("${") {
+                let Some(end) = rest[start..].find('}') else {
+                    break;
+                };
+                let reference = &rest[start + 2..start + end];
+                result.push_str(&rest[..start]);
+                if reference == from || reference.starts_with(&format!("{}.", from)) {
+                    result.push_str(&format!("${{{}{}}}", to, &reference[from.len()..]));
+                    count += 1;
+                } else {
+                    result.push_str(&rest[start..=start + end]);
+                }
+                rest = &rest[start + end + 1..];
+            }
+            result.push_str(rest);
+            (result, count)
+        }
+
+        self.config
+            .values_mut()
+            .map(|v| rewrite_value(v, from, to))
+            .sum()
+    }
+
+    // 按路径模式筛选键：only 为空表示全部保留，命中 exclude 的键被移除
+    pub fn filter(&self, only: &[KeyPattern], exclude: &[KeyPattern]) -> Config {
+        fn filter_object(
+            object: &HashMap<String, ConfigValue>,
+            parent: &mut Vec<String>,
+            only: &[KeyPattern],
+            exclude: &[KeyPattern],
+            included: bool,
+        ) -> HashMap<String, ConfigValue> {
+            let mut result = HashMap::new();
+            for (key, value) in object {
+                parent.push(key.clone());
+                let path: Vec<&str> = parent.iter().map(|s| s.as_str()).collect();
+                if !exclude.iter().any(|p| p.matches(&path)) {
+                    let fully = included || only.iter().any(|p| p.matches(&path));
+                    match value {
+                        ConfigValue::Object(obj)
+                            if fully || only.iter().any(|p| p.matches_prefix(&path)) =>
+                        {
+                            let children = filter_object(obj, parent, only, exclude, fully);
+                            if fully || !children.is_empty() {
+                                result.insert(key.clone(), ConfigValue::Object(children));
+                            }
+                        }
+                        ConfigValue::Object(_) => {}
+                        _ if fully => {
+                            result.insert(key.clone(), value.clone());
+                        }
+                        _ => {}
+                    }
+                }
+                parent.pop();
+            }
+            result
+        }
+
+        let mut filtered = self.clone();
+        filtered.config = filter_object(&self.config, &mut vec![], only, exclude, only.is_empty());
+        filtered
+    }
+
+    // 按指定格式序列化整个配置
+    pub fn serialize_to(&self, format: &ConfigType) -> Result<String, ConfigError> {
+        // 转换为serde_json::Value以避免类型标签
+        let serde_value = self.to_serde_value();
+        match format {
+            ConfigType::Json | ConfigType::Json5 => serde_json::to_string_pretty(&serde_value)
+                .map_err(|_| ConfigError::ParseConfigError),
+            ConfigType::Yaml => {
+                serde_yaml::to_string(&serde_value).map_err(|_| ConfigError::ParseConfigError)
+            }
+            ConfigType::Toml => {
+                // TOML需要特殊处理，因为它不支持所有JSON类型
+                toml::to_string_pretty(&serde_value).map_err(|_| ConfigError::ParseConfigError)
+            }
+            ConfigType::Unknown => Err(ConfigError::UnknownConfigType),
+        }
+    }
+
     pub fn show(&self, path: &str, print_deepth: usize) {
         println!(
             "📄 配置文件: {} ({}格式)",
@@ -180,7 +389,7 @@ impl Config {
     }
 
     // 生成树状结构的前缀
-    fn get_tree_prefix(indent_level: usize, is_last: bool) -> String {
+    pub fn get_tree_prefix(indent_level: usize, is_last: bool) -> String {
         let mut prefix = String::new();
 
         // 添加缩进
@@ -218,7 +427,7 @@ impl Config {
             TemplateType::Logger => Ok(Self::get_default_logger_config(format)),
             TemplateType::Monitor => Ok(Self::get_default_monitor_config(format)),
             TemplateType::Unknown => {
-                return Err(ConfigError::UnsupportedTemplateType);
+                Err(ConfigError::UnsupportedTemplateType)
             }
         }
     }
@@ -333,7 +542,7 @@ impl ConfigValue {
             serde_json::Value::Array(arr) => {
                 let config_arr: Result<Vec<ConfigValue>, ConfigError> = arr
                     .into_iter()
-                    .map(|v| ConfigValue::from_serde_json(v))
+                    .map(ConfigValue::from_serde_json)
                     .collect();
                 Ok(ConfigValue::Array(config_arr?))
             }
@@ -405,6 +614,33 @@ impl ConfigValue {
         }
     }
 
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            ConfigValue::Null => "Null",
+            ConfigValue::String(_) => "String",
+            ConfigValue::Number(_) => "Number",
+            ConfigValue::Boolean(_) => "Boolean",
+            ConfigValue::Array(_) => "Array",
+            ConfigValue::Object(_) => "Object",
+        }
+    }
+
+    // 单行摘要，与 display_config_value 的展示保持一致（不带颜色）
+    pub fn summary(&self) -> String {
+        match self {
+            ConfigValue::Null => "null".to_string(),
+            ConfigValue::String(s) => format!("\"{}\" (String)", s),
+            ConfigValue::Number(n) => format!("{} (Number)", n),
+            ConfigValue::Boolean(b) => format!("{} (Boolean)", b),
+            ConfigValue::Array(arr) => format!("(Array[{}])", arr.len()),
+            ConfigValue::Object(_) => "(Object)".to_string(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == Some(0)
+    }
+
     // 检查是否为null
     pub fn is_null(&self) -> bool {
         matches!(self, ConfigValue::Null)
@@ -457,6 +693,52 @@ impl ConfigValue {
             _ => None,
         }
     }
+
+    // 时长："30s"、"5m"、"1h30m"，纯数字按秒
+    pub fn as_duration(&self) -> Option<std::time::Duration> {
+        match self {
+            ConfigValue::String(s) => parse_duration(s),
+            ConfigValue::Number(_) => self
+                .as_number()
+                .and_then(|seconds| std::time::Duration::try_from_secs_f64(seconds).ok()),
+            _ => None,
+        }
+    }
+
+    // 字节数："512MB"、"1GiB"，纯数字按字节
+    pub fn as_byte_size(&self) -> Option<u64> {
+        match self {
+            ConfigValue::String(s) => parse_byte_size(s),
+            ConfigValue::Number(n) => n.as_u64(),
+            _ => None,
+        }
+    }
+
+    pub fn as_url(&self) -> Option<url::Url> {
+        self.as_string().and_then(|s| url::Url::parse(s.trim()).ok())
+    }
+
+    pub fn as_ip_addr(&self) -> Option<std::net::IpAddr> {
+        self.as_string().and_then(|s| s.trim().parse().ok())
+    }
+
+    // 语义类型的规范化值：时长转为毫秒、大小转为字节，URL 和 IP 转为标准写法
+    pub fn normalized_duration(&self) -> Option<ConfigValue> {
+        self.as_duration()
+            .map(|d| ConfigValue::Number(Number::from(d.as_millis() as u64)))
+    }
+
+    pub fn normalized_byte_size(&self) -> Option<ConfigValue> {
+        self.as_byte_size().map(|bytes| ConfigValue::Number(Number::from(bytes)))
+    }
+
+    pub fn normalized_url(&self) -> Option<ConfigValue> {
+        self.as_url().map(|url| ConfigValue::String(url.to_string()))
+    }
+
+    pub fn normalized_ip_addr(&self) -> Option<ConfigValue> {
+        self.as_ip_addr().map(|ip| ConfigValue::String(ip.to_string()))
+    }
 }
 
 // 实现PartialEq<&str>用于字符串比较
@@ -489,5 +771,60 @@ impl PartialOrd<&f64> for ConfigValue {
     }
 }
 
-/// 用于提供serve下的缓存
-pub type ConfigMap = HashMap<String, Config>;
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn value(value: &str) -> ConfigValue {
+        ConfigValue::String(value.to_string())
+    }
+
+    #[test]
+    fn semantic_values_are_normalized() {
+        assert_eq!(value("1m30s").normalized_duration(), Some(ConfigValue::Number(Number::from(90_000))));
+        assert_eq!(value("1KiB").normalized_byte_size(), Some(ConfigValue::Number(Number::from(1024))));
+        assert_eq!(value("HTTPS://Example.com").normalized_url(), Some(value("https://example.com/")));
+        assert_eq!(value(" 0:0:0:0:0:0:0:1 ").normalized_ip_addr(), Some(value("::1")));
+    }
+
+    #[test]
+    fn invalid_semantic_values_are_not_normalized() {
+        assert_eq!(value("90 seconds").normalized_duration(), None);
+        assert_eq!(value("1 KiBs").normalized_byte_size(), None);
+        assert_eq!(value("not a url").normalized_url(), None);
+        assert_eq!(value("300.1.1.1").normalized_ip_addr(), None);
+        assert_eq!(ConfigValue::Boolean(true).normalized_duration(), None);
+    }
+
+    fn config(value: serde_json::Value) -> Config {
+        let mut config = Config::new();
+        config.config = ConfigValue::from_serde_json(value).unwrap().into_object().unwrap();
+        config
+    }
+
+    #[test]
+    fn rename_moves_subtree_and_rewrites_references() {
+        let mut config = config(serde_json::json!({
+            "database": {"url": {"host": "db", "port": 5432}},
+            "dsn": "${database.url.host}:${database.url.port}",
+        }));
+        config.rename("database.url", "database.primary").unwrap();
+        assert_eq!(config.get("database.url"), None);
+        assert_eq!(config.get("database.primary.port"), Some(ConfigValue::Number(Number::from(5432))));
+        assert_eq!(config.rewrite_references("database.url", "database.primary"), 2);
+        assert_eq!(config.get("dsn"), Some(value("${database.primary.host}:${database.primary.port}")));
+    }
+
+    #[test]
+    fn rename_onto_existing_or_missing_key_is_rejected() {
+        let mut config = config(serde_json::json!({"database": {"host": "db", "port": 5432}}));
+        let original = config.config.clone();
+        assert!(matches!(
+            config.rename("database.port", "database.host"),
+            Err(ConfigError::KeyAlreadyExists(key)) if key == "database.host"
+        ));
+        assert!(matches!(config.rename("database.user", "database.login"), Err(ConfigError::KeyNotFound)));
+        assert!(matches!(config.rename("database", "database.inner"), Err(ConfigError::InvalidPath)));
+        assert_eq!(config.config, original);
+    }
+}
@@ -0,0 +1,69 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+pub const DEFAULT_FRESHNESS_SLO_SECS: u64 = 300;
+
+// 单个配置最近一次修改与被拉取（HTTP GET / TCP get）的时间
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConfigFreshness {
+    pub last_modified: Option<DateTime<Utc>>,
+    pub last_fetched: Option<DateTime<Utc>>,
+}
+
+// 超过 SLO 窗口仍未拿到最新配置的消费者
+#[derive(Debug, Clone, Serialize)]
+pub struct StaleConsumer {
+    pub consumer: String,
+    pub transport: String,
+    pub file: String,
+    pub last_modified: DateTime<Utc>,
+    pub last_synced: Option<DateTime<Utc>>,
+    pub lag_secs: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FreshnessReport {
+    pub slo_secs: i64,
+    pub checked_at: DateTime<Utc>,
+    pub configs: BTreeMap<String, ConfigFreshness>,
+    pub stale: Vec<StaleConsumer>,
+}
+
+#[derive(Debug, Default)]
+pub struct FreshnessTracker {
+    configs: HashMap<String, ConfigFreshness>,
+}
+
+impl FreshnessTracker {
+    pub fn modified(&mut self, file: &str, at: DateTime<Utc>) {
+        self.configs.entry(file.to_string()).or_default().last_modified = Some(at);
+    }
+
+    pub fn fetched(&mut self, file: &str, at: DateTime<Utc>) {
+        self.configs.entry(file.to_string()).or_default().last_fetched = Some(at);
+    }
+
+    pub fn configs(&self) -> BTreeMap<String, ConfigFreshness> {
+        self.configs
+            .iter()
+            .map(|(file, freshness)| (file.clone(), freshness.clone()))
+            .collect()
+    }
+
+    // 配置在 synced 之后被修改，且修改距今已超过 SLO 时返回 (修改时间, 落后秒数)
+    pub fn staleness(
+        &self,
+        file: &str,
+        synced: Option<DateTime<Utc>>,
+        now: DateTime<Utc>,
+        slo: Duration,
+    ) -> Option<(DateTime<Utc>, i64)> {
+        let last_modified = self.configs.get(file)?.last_modified?;
+        if synced.is_some_and(|synced| synced >= last_modified) || now - last_modified <= slo {
+            return None;
+        }
+        Some((last_modified, (now - last_modified).num_seconds()))
+    }
+}
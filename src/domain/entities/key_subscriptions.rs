@@ -0,0 +1,141 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    domain::{
+        entities::configuration::{Config, ConfigValue},
+        events::config_changed::ConfigUpdate,
+    },
+    shared::error::ConfigError,
+};
+
+// 单个推送连接（WebSocket、TCP SUBSCRIBE）上的订阅：文件 -> 键路径 -> 订阅状态，
+// 键路径为 None 表示整个文件
+#[derive(Default)]
+pub struct KeySubscriptions {
+    files: HashMap<String, BTreeMap<Option<String>, KeySubscription>>,
+}
+
+struct KeySubscription {
+    // 订阅时的最新事件序号，更早的更新已包含在订阅时发送的快照中
+    since_seq: u64,
+    // 上次发给客户端的子树，用于判断子树是否真的发生了变化
+    last: Option<ConfigValue>,
+}
+
+// 需要推送给客户端的变更
+pub enum SubscriptionChange {
+    File,
+    Key { key: String, value: serde_json::Value },
+}
+
+impl SubscriptionChange {
+    // 推送给客户端的消息：type 区分新建、修改和删除，整个文件的更新带上 JSON Patch；
+    // hash 与 HTTP 的 ETag 一致，source 区分文件修改和接口写入
+    pub fn message(&self, update: &ConfigUpdate) -> serde_json::Value {
+        match self {
+            Self::File => serde_json::json!({
+                "type": update.kind.message_type(),
+                "file": update.file,
+                "seq": update.seq,
+                "config": serde_json::from_str::<serde_json::Value>(&update.config)
+                    .unwrap_or_default(),
+                "patch": update.patch,
+                "hash": update.hash,
+                "source": update.source,
+                "timestamp": update.timestamp.to_rfc3339()
+            }),
+            Self::Key { key, value } => serde_json::json!({
+                "type": update.kind.message_type(),
+                "file": update.file,
+                "key": key,
+                "seq": update.seq,
+                "value": value,
+                "hash": update.hash,
+                "source": update.source,
+                "timestamp": update.timestamp.to_rfc3339()
+            }),
+        }
+    }
+}
+
+impl KeySubscriptions {
+    pub fn is_watching(&self, file: &str) -> bool {
+        self.files.contains_key(file)
+    }
+
+    pub fn subscribe(
+        &mut self,
+        file: &str,
+        key: Option<String>,
+        since_seq: u64,
+        current: Option<ConfigValue>,
+    ) {
+        let subscription = KeySubscription {
+            since_seq,
+            last: current,
+        };
+        self.files.entry(file.to_string()).or_default().insert(key, subscription);
+    }
+
+    // key 为 None 时取消该文件的所有订阅；返回该文件是否已没有订阅
+    pub fn unsubscribe(&mut self, file: &str, key: Option<&str>) -> bool {
+        let Some(keys) = self.files.get_mut(file) else {
+            return true;
+        };
+        match key {
+            Some(key) => {
+                keys.remove(&Some(key.to_string()));
+            }
+            None => keys.clear(),
+        }
+        if keys.is_empty() {
+            self.files.remove(file);
+            return true;
+        }
+        false
+    }
+
+    // 整个文件的订阅收到每次更新；键路径订阅只在对应子树与上次推送的不同时才收到
+    pub fn changes(&mut self, update: &ConfigUpdate) -> Vec<SubscriptionChange> {
+        let Some(keys) = self.files.get_mut(&update.file) else {
+            return vec![];
+        };
+        let mut config = None;
+        let mut changes = vec![];
+        for (key, subscription) in keys.iter_mut() {
+            if update.seq <= subscription.since_seq {
+                continue;
+            }
+            let Some(key) = key else {
+                changes.push(SubscriptionChange::File);
+                continue;
+            };
+            let config = config.get_or_insert_with(|| Self::parse(&update.config));
+            let current = config.get(key);
+            if current == subscription.last {
+                continue;
+            }
+            changes.push(SubscriptionChange::Key {
+                key: key.clone(),
+                value: current.as_ref().map(ConfigValue::to_serde_value).unwrap_or_default(),
+            });
+            subscription.last = current;
+        }
+        changes
+    }
+
+    // 推送内容是应用环境变量覆盖后的 JSON
+    fn parse(config: &str) -> Config {
+        let config = serde_json::from_str(config)
+            .map_err(|_| ConfigError::ParseConfigError)
+            .and_then(ConfigValue::from_serde_json)
+            .and_then(ConfigValue::into_object);
+        match config {
+            Ok(config) => Config {
+                config,
+                ..Config::new()
+            },
+            Err(_) => Config::new(),
+        }
+    }
+}
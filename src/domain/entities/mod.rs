@@ -1,3 +1,15 @@
+pub mod access_policy;
+pub mod audit;
+pub mod backup;
+pub mod bundle;
+pub mod config_lock;
+pub mod config_map;
+pub mod config_ttl;
+pub mod config_version;
 pub mod configuration;
+pub mod freshness;
+pub mod key_subscriptions;
+pub mod rollout;
+pub mod scheduled_change;
 pub mod template;
-pub mod validation_rule;
\ No newline at end of file
+pub mod validation_rule;
@@ -0,0 +1,101 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        events::config_changed::{ChangeKind, ConfigUpdate},
+        services::config_patch::ConfigPatchService,
+    },
+    shared::utils::sha256_hex,
+};
+
+// 金丝雀的目标订阅者：按订阅者 ID 的哈希取一定百分比，或按订阅时声明的标签选择（全部匹配）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RolloutTarget {
+    Percentage(u8),
+    Selector(BTreeMap<String, String>),
+}
+
+impl RolloutTarget {
+    pub fn matches(&self, client_id: &str, labels: &BTreeMap<String, String>) -> bool {
+        match self {
+            RolloutTarget::Percentage(percentage) => {
+                // 同一订阅者的分桶固定，百分比调大时已选中的订阅者仍被选中
+                let bucket = u32::from_str_radix(&sha256_hex(client_id.as_bytes())[..8], 16)
+                    .unwrap_or_default()
+                    % 100;
+                bucket < u32::from(*percentage)
+            }
+            RolloutTarget::Selector(selector) => selector
+                .iter()
+                .all(|(key, value)| labels.get(key) == Some(value)),
+        }
+    }
+}
+
+// 进行中的金丝雀发布：只有 clients 中的订阅者收到新内容，文件和其他订阅者保持稳定版本。
+// promote 把 content 写入文件，rollback 给金丝雀订阅者推送回稳定版本；
+// 期间文件被直接修改时发布结束，所有订阅者都收到新的文件内容
+#[derive(Debug, Clone, Serialize)]
+pub struct Rollout {
+    pub file: String,
+    pub target: RolloutTarget,
+    // 要写入文件的原始内容
+    #[serde(skip)]
+    pub content: String,
+    // 推送内容（应用环境变量覆盖后的 JSON）：金丝雀版本与开始时的稳定版本
+    pub config: serde_json::Value,
+    #[serde(skip)]
+    pub base: serde_json::Value,
+    pub hash: String,
+    #[serde(skip)]
+    pub base_hash: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub started_by: String,
+    pub clients: BTreeSet<String>,
+}
+
+impl Rollout {
+    // 推给新加入金丝雀的订阅者，时间为发布开始的时间
+    pub fn canary_update(&self, seq: u64) -> ConfigUpdate {
+        ConfigUpdate {
+            seq,
+            file: self.file.clone(),
+            kind: ChangeKind::Updated,
+            config: self.config.to_string(),
+            patch: ConfigPatchService::diff(&self.base, &self.config),
+            hash: Some(self.hash.clone()),
+            source: "rollout".to_string(),
+            timestamp: self.started_at,
+        }
+    }
+
+    // 回滚时推给金丝雀订阅者的稳定版本
+    pub fn rollback_update(&self, seq: u64, timestamp: DateTime<Utc>) -> ConfigUpdate {
+        ConfigUpdate {
+            seq,
+            file: self.file.clone(),
+            kind: ChangeKind::Updated,
+            config: self.base.to_string(),
+            patch: ConfigPatchService::diff(&self.config, &self.base),
+            hash: self.base_hash.clone(),
+            source: "rollout".to_string(),
+            timestamp,
+        }
+    }
+
+    // 文件更新后发布结束，金丝雀订阅者的补丁基于他们手中的金丝雀版本
+    pub fn superseded_update(&self, update: &ConfigUpdate) -> ConfigUpdate {
+        let new = match update.kind {
+            ChangeKind::Deleted => serde_json::json!({}),
+            _ => serde_json::from_str(&update.config).unwrap_or_default(),
+        };
+        ConfigUpdate {
+            patch: ConfigPatchService::diff(&self.config, &new),
+            ..update.clone()
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// 在 apply_at 时刻整份替换配置内容的修改，谁在什么时候提交
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScheduledChange {
+    pub id: String,
+    pub file: String,
+    pub content: String,
+    pub apply_at: DateTime<Utc>,
+    pub scheduled_at: DateTime<Utc>,
+    pub scheduled_by: String,
+}
+
+// 等待生效的修改，按生效时间排序；同一时刻的修改按提交顺序生效
+#[derive(Debug, Default)]
+pub struct ScheduledChanges {
+    changes: Vec<ScheduledChange>,
+}
+
+impl ScheduledChanges {
+    pub fn add(&mut self, change: ScheduledChange) {
+        let index = self.changes.partition_point(|pending| pending.apply_at <= change.apply_at);
+        self.changes.insert(index, change);
+    }
+
+    pub fn pending(&self, file: &str) -> Vec<ScheduledChange> {
+        self.changes.iter().filter(|change| change.file == file).cloned().collect()
+    }
+
+    pub fn all(&self) -> &[ScheduledChange] {
+        &self.changes
+    }
+
+    pub fn next_at(&self) -> Option<DateTime<Utc>> {
+        self.changes.first().map(|change| change.apply_at)
+    }
+
+    // 取出到期的修改
+    pub fn take_due(&mut self, now: DateTime<Utc>) -> Vec<ScheduledChange> {
+        let due = self.changes.partition_point(|change| change.apply_at <= now);
+        self.changes.drain(..due).collect()
+    }
+}
@@ -1,4 +1,11 @@
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display};
+
+use serde_json::Number;
+
+use super::configuration::{Config, ConfigValue};
+
+// 生成的配置中记录模板来源的键：{ name, version, defaults }
+pub const TEMPLATE_METADATA_KEY: &str = "_template";
 
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum TemplateType {
@@ -36,3 +43,67 @@ impl From<String> for TemplateType {
         }
     }
 }
+
+impl TemplateType {
+    // 模板默认值有变化时递增，template upgrade 据此判断配置是否落后
+    pub fn version(&self) -> u32 {
+        1
+    }
+}
+
+// 生成配置时的模板名、版本和当时的默认值快照，升级时与当前模板默认值对比
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateMetadata {
+    pub template: TemplateType,
+    pub version: u32,
+    pub defaults: HashMap<String, ConfigValue>,
+}
+
+impl TemplateMetadata {
+    pub fn new(template: TemplateType, defaults: &Config) -> Self {
+        Self {
+            version: template.version(),
+            template,
+            defaults: defaults.config.clone(),
+        }
+    }
+
+    pub fn from_config(config: &Config) -> Option<Self> {
+        let ConfigValue::Object(metadata) = config.config.get(TEMPLATE_METADATA_KEY)? else {
+            return None;
+        };
+        let template = TemplateType::from(metadata.get("name")?.as_string()?.clone());
+        if template == TemplateType::Unknown {
+            return None;
+        }
+        let version = metadata.get("version")?.as_number()? as u32;
+        let defaults = match metadata.get("defaults") {
+            Some(ConfigValue::Object(defaults)) => defaults.clone(),
+            _ => HashMap::new(),
+        };
+        Some(Self {
+            template,
+            version,
+            defaults,
+        })
+    }
+
+    pub fn annotate(&self, config: &mut Config) {
+        let mut metadata = HashMap::new();
+        metadata.insert(
+            "name".to_string(),
+            ConfigValue::String(self.template.to_string()),
+        );
+        metadata.insert(
+            "version".to_string(),
+            ConfigValue::Number(Number::from(self.version)),
+        );
+        metadata.insert(
+            "defaults".to_string(),
+            ConfigValue::Object(self.defaults.clone()),
+        );
+        config
+            .config
+            .insert(TEMPLATE_METADATA_KEY.to_string(), ConfigValue::Object(metadata));
+    }
+}
@@ -1,13 +1,16 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     fmt::{Display, Formatter},
+    time::Duration,
 };
 
+use regex::Regex;
+use serde::{Deserialize, Serialize, Serializer};
 use tracing::{debug, info};
 
-use crate::shared::error::ValidationError;
+use crate::shared::error::{ConfigError, ValidationError};
 
-use super::configuration::Config;
+use super::configuration::{Config, ConfigValue};
 
 // 将ValidationRule定义为类型别名，而不是trait
 pub type ValidationRule = dyn Fn(&Config) -> Result<(), ValidationError> + Send + Sync + 'static;
@@ -17,6 +20,12 @@ pub struct Validation {
     pub required_fields: Vec<String>,
     pub field_types: HashMap<String, FieldType>, // 字段类型约束
     pub custom_rules: Vec<Box<ValidationRule>>,  // 自定义规则
+    pub patterns: HashMap<String, Regex>,        // 预编译的字符串正则约束
+    pub cross_field_rules: Vec<(CrossFieldRule, Severity)>, // 跨字段约束
+    pub severities: HashMap<String, Severity>, // 字段级别的严重程度，缺省为 Error
+    pub deprecated_fields: HashMap<String, DeprecatedField>, // 已废弃的键，出现时给出警告
+    pub deny_unknown_fields: bool, // 规则中未声明的键视为错误，用于发现拼写错误
+    pub defaults: BTreeMap<String, ConfigValue>, // 缺失键的默认值，校验之后填充
 }
 
 impl std::fmt::Debug for Validation {
@@ -28,6 +37,11 @@ impl std::fmt::Debug for Validation {
                 "custom_rules",
                 &format!("[{} rules]", self.custom_rules.len()),
             )
+            .field("cross_field_rules", &self.cross_field_rules)
+            .field("severities", &self.severities)
+            .field("deprecated_fields", &self.deprecated_fields)
+            .field("deny_unknown_fields", &self.deny_unknown_fields)
+            .field("defaults", &self.defaults)
             .finish()
     }
 }
@@ -38,6 +52,12 @@ impl Validation {
             required_fields: vec![],
             field_types: HashMap::new(),
             custom_rules: vec![],
+            patterns: HashMap::new(),
+            cross_field_rules: vec![],
+            severities: HashMap::new(),
+            deprecated_fields: HashMap::new(),
+            deny_unknown_fields: false,
+            defaults: BTreeMap::new(),
         }
     }
 
@@ -55,6 +75,459 @@ impl Validation {
         self.custom_rules.push(rule);
         self
     }
+
+    pub fn cross_field_rule(mut self, rule: CrossFieldRule, severity: Severity) -> Self {
+        self.cross_field_rules.push((rule, severity));
+        self
+    }
+
+    pub fn severity(mut self, field: &str, severity: Severity) -> Self {
+        self.severities.insert(field.to_string(), severity);
+        self
+    }
+
+    pub fn deprecated_field(mut self, field: &str, deprecated: DeprecatedField) -> Self {
+        self.deprecated_fields.insert(field.to_string(), deprecated);
+        self
+    }
+
+    pub fn deny_unknown_fields(mut self, deny: bool) -> Self {
+        self.deny_unknown_fields = deny;
+        self
+    }
+
+    pub fn default_value(mut self, field: &str, value: ConfigValue) -> Self {
+        self.defaults.insert(field.to_string(), value);
+        self
+    }
+
+    // 按键名顺序填充缺失的默认值（父键先于子键），返回实际填充的键
+    pub fn apply_defaults(&self, config: &mut Config) -> Result<Vec<String>, ConfigError> {
+        let mut applied = Vec::new();
+        for (field, value) in self.defaults.iter() {
+            if config.get(field).is_none() {
+                config.set(field, value.clone())?;
+                applied.push(field.clone());
+            }
+        }
+        Ok(applied)
+    }
+
+    pub fn validate(&self, config: &Config) -> ValidationResult {
+        let mut result = ValidationResult::default();
+
+        info!("validation: {:?}", self);
+
+        for field in self.required_fields.iter() {
+            let value = config.get(field);
+            debug!("field: {}, value: {:?}", field, value);
+            if value.is_none() {
+                result.push(
+                    self.field_severity(field),
+                    ValidationError::RequiredField {
+                        field: field.clone(),
+                    },
+                );
+            }
+        }
+
+        for (field, field_type) in self.field_types.iter() {
+            let value = config.get(field);
+            debug!("field: {}, value: {:?}", field, value);
+            let mut errors = Vec::new();
+            if let Some(value) = value {
+                info!("field_type: {:?}", field_type);
+                self.check_value(field, field, &value, field_type, &mut errors);
+            } else {
+                errors.push(ValidationError::UndefinedField {
+                    field: field.clone(),
+                });
+            }
+            result.extend(self.field_severity(field), errors);
+        }
+
+        for (rule, severity) in self.cross_field_rules.iter() {
+            let mut errors = Vec::new();
+            rule.check(config, &mut errors);
+            result.extend(*severity, errors);
+        }
+
+        for rule in self.custom_rules.iter() {
+            if let Err(e) = rule(config) {
+                result.push(Severity::Error, e);
+            }
+        }
+
+        let mut deprecated: Vec<(&String, &DeprecatedField)> = self
+            .deprecated_fields
+            .iter()
+            .filter(|(field, _)| config.get(field).is_some())
+            .collect();
+        deprecated.sort_by(|a, b| a.0.cmp(b.0));
+        for (field, deprecated) in deprecated {
+            result.push(
+                Severity::Warning,
+                ValidationError::DeprecatedField {
+                    field: field.clone(),
+                    hint: deprecated.hint(),
+                },
+            );
+        }
+
+        if self.deny_unknown_fields {
+            let declared = self.declared_fields();
+            let mut errors = Vec::new();
+            Self::check_unknown_fields("", &config.config, &declared, &mut errors);
+            errors.sort_by_key(|e| e.to_string());
+            result.extend(Severity::Error, errors);
+        }
+
+        result
+    }
+
+    // 规则中提到的所有键：required_fields、field_types、跨字段约束、废弃键和默认值
+    fn declared_fields(&self) -> Vec<&str> {
+        let mut declared: Vec<&str> = self
+            .required_fields
+            .iter()
+            .chain(self.field_types.keys())
+            .chain(self.deprecated_fields.keys())
+            .chain(self.defaults.keys())
+            .map(|field| field.as_str())
+            .collect();
+        for (rule, _) in self.cross_field_rules.iter() {
+            declared.extend(rule.fields());
+        }
+        declared
+    }
+
+    // 已声明的键由其类型约束负责（对象 schema 通过 allow_unknown 控制），
+    // 只有作为某个已声明键前缀的对象才继续向下检查
+    fn check_unknown_fields(
+        prefix: &str,
+        object: &HashMap<String, ConfigValue>,
+        declared: &[&str],
+        errors: &mut Vec<ValidationError>,
+    ) {
+        for (key, value) in object.iter() {
+            let field = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            if declared.contains(&field.as_str()) {
+                continue;
+            }
+            let is_parent = declared
+                .iter()
+                .any(|d| d.strip_prefix(field.as_str()).is_some_and(|rest| rest.starts_with('.')));
+            match value {
+                ConfigValue::Object(child) if is_parent => {
+                    Self::check_unknown_fields(&field, child, declared, errors)
+                }
+                _ => errors.push(ValidationError::UndefinedField { field }),
+            }
+        }
+    }
+
+    fn field_severity(&self, field: &str) -> Severity {
+        self.severities.get(field).copied().unwrap_or_default()
+    }
+
+    // 按字段类型检查单个值；pattern_key 用于查找预编译的正则
+    fn check_value(
+        &self,
+        field: &str,
+        pattern_key: &str,
+        value: &ConfigValue,
+        field_type: &FieldType,
+        errors: &mut Vec<ValidationError>,
+    ) {
+        match field_type {
+            FieldType::String {
+                max_length,
+                min_length,
+                pattern,
+            } => {
+                if let Some(pattern) = pattern {
+                    // 未预编译的正则（例如通过 builder 添加）在这里临时编译
+                    let compiled = match self.patterns.get(pattern_key) {
+                        Some(regex) => Ok(regex.clone()),
+                        None => Regex::new(pattern),
+                    };
+                    match compiled {
+                        Ok(regex) => {
+                            let text = match value {
+                                ConfigValue::String(s) => s.clone(),
+                                other => other.to_string(),
+                            };
+                            if !regex.is_match(&text) {
+                                errors.push(ValidationError::PatternMismatch {
+                                    field: field.to_string(),
+                                    pattern: pattern.clone(),
+                                    value: text,
+                                });
+                            }
+                        }
+                        Err(e) => errors.push(ValidationError::CustomRuleViolation {
+                            field: field.to_string(),
+                            rule: format!("invalid pattern {}: {}", pattern, e),
+                        }),
+                    }
+                }
+                if let Some(max_length) = max_length
+                    && let Some(len) = value.len()
+                    && len > *max_length
+                {
+                    debug!("len: {}, max_length: {}", len, max_length);
+
+                    errors.push(ValidationError::TypeMismatch {
+                        field: field.to_string(),
+                        expected: field_type.to_string(),
+                        actual: value.to_string(),
+                    });
+                }
+                if let Some(min_length) = min_length
+                    && let Some(len) = value.len()
+                    && len < *min_length
+                {
+                    debug!("len: {}, min_length: {}", len, min_length);
+
+                    errors.push(ValidationError::TypeMismatch {
+                        field: field.to_string(),
+                        expected: field_type.to_string(),
+                        actual: value.to_string(),
+                    });
+                }
+            }
+            FieldType::Number { min, max } => {
+                info!("value.as_number(): {:?}", value.as_number());
+
+                if let Some(num_value) = value.as_number() {
+                    debug!("num_value: {}", num_value);
+
+                    // 检查最小值
+                    if let Some(min) = min {
+                        debug!("num_value: {}, min: {}", num_value, min);
+                        if num_value < *min {
+                            errors.push(ValidationError::TypeMismatch {
+                                field: field.to_string(),
+                                expected: field_type.to_string(),
+                                actual: value.to_string(),
+                            });
+                        }
+                    }
+
+                    // 检查最大值
+                    if let Some(max) = max {
+                        debug!("num_value: {}, max: {}", num_value, max);
+                        if num_value > *max {
+                            errors.push(ValidationError::TypeMismatch {
+                                field: field.to_string(),
+                                expected: field_type.to_string(),
+                                actual: value.to_string(),
+                            });
+                        }
+                    }
+                } else {
+                    debug!("as_number() returned None for value: {:?}", value);
+                    errors.push(ValidationError::TypeMismatch {
+                        field: field.to_string(),
+                        expected: "number".to_string(),
+                        actual: format!("{:?}", value),
+                    });
+                }
+            }
+            FieldType::Enum { values } => {
+                if !values.contains(value) {
+                    errors.push(ValidationError::NotAllowed {
+                        field: field.to_string(),
+                        value: value.to_serde_value().to_string(),
+                        allowed: values
+                            .iter()
+                            .map(|v| v.to_serde_value().to_string())
+                            .collect::<Vec<String>>()
+                            .join(", "),
+                    });
+                }
+            }
+            FieldType::Array {
+                item_type,
+                min_items,
+                max_items,
+                unique,
+            } => {
+                let ConfigValue::Array(items) = value else {
+                    errors.push(ValidationError::TypeMismatch {
+                        field: field.to_string(),
+                        expected: field_type.to_string(),
+                        actual: value.to_string(),
+                    });
+                    return;
+                };
+                if let Some(min_items) = min_items
+                    && items.len() < *min_items
+                {
+                    errors.push(ValidationError::ItemCount {
+                        field: field.to_string(),
+                        expected: format!("at least {}", min_items),
+                        actual: items.len(),
+                    });
+                }
+                if let Some(max_items) = max_items
+                    && items.len() > *max_items
+                {
+                    errors.push(ValidationError::ItemCount {
+                        field: field.to_string(),
+                        expected: format!("at most {}", max_items),
+                        actual: items.len(),
+                    });
+                }
+                let item_pattern_key = format!("{}[]", pattern_key);
+                for (index, item) in items.iter().enumerate() {
+                    let item_field = format!("{}[{}]", field, index);
+                    if *unique && items[..index].contains(item) {
+                        errors.push(ValidationError::DuplicateItem {
+                            field: item_field.clone(),
+                            value: item.to_serde_value().to_string(),
+                        });
+                    }
+                    self.check_value(&item_field, &item_pattern_key, item, item_type, errors);
+                }
+            }
+            FieldType::Object {
+                fields,
+                required,
+                allow_unknown,
+            } => {
+                let ConfigValue::Object(object) = value else {
+                    errors.push(ValidationError::TypeMismatch {
+                        field: field.to_string(),
+                        expected: field_type.to_string(),
+                        actual: value.to_string(),
+                    });
+                    return;
+                };
+                for name in required {
+                    if !object.contains_key(name) {
+                        errors.push(ValidationError::RequiredField {
+                            field: format!("{}.{}", field, name),
+                        });
+                    }
+                }
+                let mut keys: Vec<&String> = object.keys().collect();
+                keys.sort();
+                for key in keys {
+                    let child_field = format!("{}.{}", field, key);
+                    match fields.get(key) {
+                        Some(child_type) => self.check_value(
+                            &child_field,
+                            &format!("{}.{}", pattern_key, key),
+                            &object[key],
+                            child_type,
+                            errors,
+                        ),
+                        None if !allow_unknown => {
+                            errors.push(ValidationError::UndefinedField { field: child_field })
+                        }
+                        None => {}
+                    }
+                }
+            }
+            FieldType::Integer { min, max } => {
+                // u64 可能超出 i64 范围，统一按 i128 比较
+                let integer = match value {
+                    ConfigValue::Number(n) => n
+                        .as_i64()
+                        .map(i128::from)
+                        .or_else(|| n.as_u64().map(i128::from)),
+                    _ => None,
+                };
+                let in_range = integer.is_some_and(|integer| {
+                    min.is_none_or(|min| integer >= i128::from(min))
+                        && max.is_none_or(|max| integer <= i128::from(max))
+                });
+                if !in_range {
+                    errors.push(ValidationError::TypeMismatch {
+                        field: field.to_string(),
+                        expected: field_type.to_string(),
+                        actual: value.to_string(),
+                    });
+                }
+            }
+            FieldType::Duration { min, max } => {
+                let in_range = value.as_duration().is_some_and(|duration| {
+                    min.is_none_or(|min| duration >= min) && max.is_none_or(|max| duration <= max)
+                });
+                if !in_range {
+                    errors.push(ValidationError::TypeMismatch {
+                        field: field.to_string(),
+                        expected: field_type.to_string(),
+                        actual: value.to_string(),
+                    });
+                }
+            }
+            FieldType::ByteSize { min, max } => {
+                let in_range = value.as_byte_size().is_some_and(|bytes| {
+                    min.is_none_or(|min| bytes >= min) && max.is_none_or(|max| bytes <= max)
+                });
+                if !in_range {
+                    errors.push(ValidationError::TypeMismatch {
+                        field: field.to_string(),
+                        expected: field_type.to_string(),
+                        actual: value.to_string(),
+                    });
+                }
+            }
+            FieldType::Url { schemes } => {
+                let valid = value.as_url().is_some_and(|url| {
+                    schemes.is_empty() || schemes.iter().any(|s| s.eq_ignore_ascii_case(url.scheme()))
+                });
+                if !valid {
+                    errors.push(ValidationError::TypeMismatch {
+                        field: field.to_string(),
+                        expected: field_type.to_string(),
+                        actual: value.to_string(),
+                    });
+                }
+            }
+            FieldType::IpAddr => {
+                if value.as_ip_addr().is_none() {
+                    errors.push(ValidationError::TypeMismatch {
+                        field: field.to_string(),
+                        expected: field_type.to_string(),
+                        actual: value.to_string(),
+                    });
+                }
+            }
+            FieldType::FilePath { must_exist } => match value.as_string() {
+                Some(path) if !path.trim().is_empty() => {
+                    // 相对路径按进程工作目录解析
+                    if *must_exist && !std::path::Path::new(path).exists() {
+                        errors.push(ValidationError::PathNotFound {
+                            field: field.to_string(),
+                            path: path.clone(),
+                        });
+                    }
+                }
+                _ => errors.push(ValidationError::TypeMismatch {
+                    field: field.to_string(),
+                    expected: field_type.to_string(),
+                    actual: value.to_string(),
+                }),
+            },
+            FieldType::Boolean => {
+                if !matches!(value, ConfigValue::Boolean(_)) && *value != "true" && *value != "false" {
+                    errors.push(ValidationError::TypeMismatch {
+                        field: field.to_string(),
+                        expected: field_type.to_string(),
+                        actual: value.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
 }
 
 #[derive(Debug, Clone)]
@@ -62,12 +535,48 @@ pub enum FieldType {
     String {
         max_length: Option<usize>,
         min_length: Option<usize>,
+        pattern: Option<String>,
     },
     Number {
         min: Option<f64>,
         max: Option<f64>,
     },
+    // 只接受整数，3.5、8080.0 都视为类型不匹配
+    Integer {
+        min: Option<i64>,
+        max: Option<i64>,
+    },
     Boolean,
+    // 语义类型：时长 "30s"、字节大小 "512MB"、URL、IP 地址、文件路径
+    Duration {
+        min: Option<Duration>,
+        max: Option<Duration>,
+    },
+    ByteSize {
+        min: Option<u64>,
+        max: Option<u64>,
+    },
+    Url {
+        schemes: Vec<String>,
+    },
+    IpAddr,
+    FilePath {
+        must_exist: bool,
+    },
+    Enum {
+        values: Vec<ConfigValue>,
+    },
+    Array {
+        item_type: Box<FieldType>,
+        min_items: Option<usize>,
+        max_items: Option<usize>,
+        unique: bool,
+    },
+    Object {
+        fields: HashMap<String, FieldType>,
+        required: Vec<String>,
+        allow_unknown: bool,
+    },
 }
 
 impl Display for FieldType {
@@ -76,21 +585,353 @@ impl Display for FieldType {
             FieldType::String {
                 max_length,
                 min_length,
-            } => {
-                write!(f, "String(max: {:?}, min: {:?})", max_length, min_length)
-            }
+                pattern,
+            } => match pattern {
+                Some(pattern) => write!(
+                    f,
+                    "String(max: {:?}, min: {:?}, pattern: {})",
+                    max_length, min_length, pattern
+                ),
+                None => write!(f, "String(max: {:?}, min: {:?})", max_length, min_length),
+            },
             FieldType::Number { min, max } => {
                 write!(f, "Number(min: {:?}, max: {:?})", min, max)
             }
+            FieldType::Integer { min, max } => {
+                write!(f, "Integer(min: {:?}, max: {:?})", min, max)
+            }
             FieldType::Boolean => write!(f, "Boolean"),
+            FieldType::Duration { min, max } => {
+                write!(f, "Duration(min: {:?}, max: {:?})", min, max)
+            }
+            FieldType::ByteSize { min, max } => {
+                write!(f, "ByteSize(min: {:?}, max: {:?})", min, max)
+            }
+            FieldType::Url { schemes } => write!(f, "Url(schemes: {:?})", schemes),
+            FieldType::IpAddr => write!(f, "IpAddr"),
+            FieldType::FilePath { must_exist } => {
+                write!(f, "FilePath(must_exist: {})", must_exist)
+            }
+            FieldType::Array {
+                item_type,
+                min_items,
+                max_items,
+                unique,
+            } => write!(
+                f,
+                "Array<{}>(min: {:?}, max: {:?}, unique: {})",
+                item_type, min_items, max_items, unique
+            ),
+            FieldType::Object {
+                fields,
+                required,
+                allow_unknown,
+            } => write!(
+                f,
+                "Object(fields: {}, required: {:?}, allow_unknown: {})",
+                fields.len(),
+                required,
+                allow_unknown
+            ),
+            FieldType::Enum { values } => write!(
+                f,
+                "Enum({})",
+                values
+                    .iter()
+                    .map(|v| v.to_serde_value().to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
         }
     }
 }
 
-#[derive(Debug)]
+// 废弃键的替代键与计划移除的版本
+#[derive(Debug, Clone, Default)]
+pub struct DeprecatedField {
+    pub replacement: Option<String>,
+    pub removed_in: Option<String>,
+}
+
+impl DeprecatedField {
+    pub fn hint(&self) -> String {
+        let mut hint = String::new();
+        if let Some(replacement) = &self.replacement {
+            hint.push_str(&format!(", use {} instead", replacement));
+        }
+        if let Some(removed_in) = &self.removed_in {
+            hint.push_str(&format!(", will be removed in {}", removed_in));
+        }
+        hint
+    }
+}
+
+// 跨字段约束，字段均为点分路径
+#[derive(Debug, Clone)]
+pub enum CrossFieldRule {
+    // when 字段为真（或等于 equals）时，then 中的字段必须存在
+    Requires {
+        when: String,
+        equals: Option<ConfigValue>,
+        then: Vec<String>,
+    },
+    // 最多只能出现其中一个字段
+    MutuallyExclusive { fields: Vec<String> },
+    // 两个数值字段之间的比较，任一字段缺失时跳过（由 required_fields 负责）
+    Compare {
+        left: String,
+        op: CompareOp,
+        right: String,
+    },
+}
+
+impl CrossFieldRule {
+    fn fields(&self) -> Vec<&str> {
+        match self {
+            CrossFieldRule::Requires { when, then, .. } => std::iter::once(when)
+                .chain(then.iter())
+                .map(|field| field.as_str())
+                .collect(),
+            CrossFieldRule::MutuallyExclusive { fields } => {
+                fields.iter().map(|field| field.as_str()).collect()
+            }
+            CrossFieldRule::Compare { left, right, .. } => vec![left.as_str(), right.as_str()],
+        }
+    }
+
+    fn check(&self, config: &Config, errors: &mut Vec<ValidationError>) {
+        match self {
+            CrossFieldRule::Requires { when, equals, then } => {
+                let triggered = match (config.get(when), equals) {
+                    (Some(value), Some(expected)) => value == *expected,
+                    (Some(value), None) => {
+                        !matches!(value, ConfigValue::Null | ConfigValue::Boolean(false))
+                    }
+                    (None, _) => false,
+                };
+                if !triggered {
+                    return;
+                }
+                let condition = match equals {
+                    Some(expected) => format!("{} is {}", when, expected.to_serde_value()),
+                    None => format!("{} is set", when),
+                };
+                for field in then {
+                    if config.get(field).is_none() {
+                        errors.push(ValidationError::ConditionalRequired {
+                            field: field.clone(),
+                            condition: condition.clone(),
+                        });
+                    }
+                }
+            }
+            CrossFieldRule::MutuallyExclusive { fields } => {
+                let present: Vec<&String> = fields
+                    .iter()
+                    .filter(|field| config.get(field).is_some())
+                    .collect();
+                if present.len() > 1 {
+                    errors.push(ValidationError::MutuallyExclusive {
+                        fields: present
+                            .iter()
+                            .map(|f| f.as_str())
+                            .collect::<Vec<&str>>()
+                            .join(", "),
+                    });
+                }
+            }
+            CrossFieldRule::Compare { left, op, right } => {
+                let (Some(left_value), Some(right_value)) = (config.get(left), config.get(right))
+                else {
+                    return;
+                };
+                for (field, value) in [(left, &left_value), (right, &right_value)] {
+                    if value.as_number().is_none() {
+                        errors.push(ValidationError::TypeMismatch {
+                            field: field.clone(),
+                            expected: "Number".to_string(),
+                            actual: value.to_string(),
+                        });
+                    }
+                }
+                if let (Some(l), Some(r)) = (left_value.as_number(), right_value.as_number())
+                    && !op.holds(l, r)
+                {
+                    errors.push(ValidationError::ComparisonFailed {
+                        left: left.clone(),
+                        op: op.to_string(),
+                        right: right.clone(),
+                        left_value: l,
+                        right_value: r,
+                    });
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum CompareOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CompareOp {
+    pub fn parse(op: &str) -> Option<Self> {
+        match op {
+            "<" => Some(CompareOp::Lt),
+            "<=" => Some(CompareOp::Le),
+            ">" => Some(CompareOp::Gt),
+            ">=" => Some(CompareOp::Ge),
+            "==" => Some(CompareOp::Eq),
+            "!=" => Some(CompareOp::Ne),
+            _ => None,
+        }
+    }
+
+    pub fn accepts(&self, ordering: std::cmp::Ordering) -> bool {
+        match self {
+            CompareOp::Lt => ordering.is_lt(),
+            CompareOp::Le => ordering.is_le(),
+            CompareOp::Gt => ordering.is_gt(),
+            CompareOp::Ge => ordering.is_ge(),
+            CompareOp::Eq => ordering.is_eq(),
+            CompareOp::Ne => ordering.is_ne(),
+        }
+    }
+
+    fn holds(&self, left: f64, right: f64) -> bool {
+        match self {
+            CompareOp::Lt => left < right,
+            CompareOp::Le => left <= right,
+            CompareOp::Gt => left > right,
+            CompareOp::Ge => left >= right,
+            CompareOp::Eq => left == right,
+            CompareOp::Ne => left != right,
+        }
+    }
+}
+
+impl TryFrom<String> for CompareOp {
+    type Error = String;
+
+    fn try_from(op: String) -> Result<Self, Self::Error> {
+        Self::parse(&op).ok_or_else(|| format!("unknown compare operator {:?}, expected <, <=, >, >=, == or !=", op))
+    }
+}
+
+impl Display for CompareOp {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let symbol = match self {
+            CompareOp::Lt => "<",
+            CompareOp::Le => "<=",
+            CompareOp::Gt => ">",
+            CompareOp::Ge => ">=",
+            CompareOp::Eq => "==",
+            CompareOp::Ne => "!=",
+        };
+        write!(f, "{}", symbol)
+    }
+}
+
+// 只有 Error 级别的问题会导致校验失败
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase", try_from = "String")]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn parse(severity: &str) -> Option<Self> {
+        match severity.to_lowercase().as_str() {
+            "error" => Some(Severity::Error),
+            "warning" | "warn" => Some(Severity::Warning),
+            "info" => Some(Severity::Info),
+            _ => None,
+        }
+    }
+}
+
+impl TryFrom<String> for Severity {
+    type Error = String;
+
+    fn try_from(severity: String) -> Result<Self, String> {
+        Self::parse(&severity)
+            .ok_or_else(|| format!("invalid severity {:?}, expected error, warning or info", severity))
+    }
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Info => write!(f, "info"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
 pub struct ValidationResult {
     pub is_valid: bool,
+    #[serde(serialize_with = "serialize_messages")]
     pub errors: Vec<ValidationError>,
+    #[serde(serialize_with = "serialize_messages")]
+    pub warnings: Vec<ValidationError>,
+    #[serde(serialize_with = "serialize_messages")]
+    pub infos: Vec<ValidationError>,
+}
+
+impl Default for ValidationResult {
+    fn default() -> Self {
+        Self {
+            is_valid: true,
+            errors: vec![],
+            warnings: vec![],
+            infos: vec![],
+        }
+    }
+}
+
+impl ValidationResult {
+    pub fn push(&mut self, severity: Severity, error: ValidationError) {
+        match severity {
+            Severity::Error => {
+                self.errors.push(error);
+                self.is_valid = false;
+            }
+            Severity::Warning => self.warnings.push(error),
+            Severity::Info => self.infos.push(error),
+        }
+    }
+
+    pub fn extend(&mut self, severity: Severity, errors: Vec<ValidationError>) {
+        for error in errors {
+            self.push(severity, error);
+        }
+    }
+
+    // --strict：警告视为错误
+    pub fn strict(mut self) -> Self {
+        let warnings = std::mem::take(&mut self.warnings);
+        self.extend(Severity::Error, warnings);
+        self
+    }
+}
+
+fn serialize_messages<S: Serializer>(
+    errors: &[ValidationError],
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.collect_seq(errors.iter().map(|e| e.to_string()))
 }
 
 pub struct ValidationConfig {
@@ -104,121 +945,306 @@ impl ValidationConfig {
     }
 
     pub fn validate(&self) -> ValidationResult {
-        let mut errors: Vec<ValidationError> = Vec::new();
+        self.validation.validate(&self.config)
+    }
+}
 
-        info!("validation: {:?}", self.validation);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-        for field in self.validation.required_fields.iter() {
-            let value = self.config.get(field);
-            debug!("field: {}, value: {:?}", field, value);
-            if value.is_none() {
-                errors.push(ValidationError::RequiredField {
-                    field: field.clone(),
-                });
-            }
-        }
+    // 对单个字段 value 按 field_type 校验，返回错误的规则标识
+    fn check(field_type: FieldType, value: serde_json::Value) -> Vec<&'static str> {
+        let mut config = Config::new();
+        config.set("value", ConfigValue::from_serde_json(value).unwrap()).unwrap();
+        let result = Validation::new().field_type("value", field_type).validate(&config);
+        result.errors.iter().map(|e| e.kind()).collect()
+    }
 
-        for (field, field_type) in self.validation.field_types.iter() {
-            let value = self.config.get(field);
-            debug!("field: {}, value: {:?}", field, value);
-            if let Some(value) = value {
-                info!("field_type: {:?}", field_type);
-                match field_type {
-                    FieldType::String {
-                        max_length,
-                        min_length,
-                    } => {
-                        if let Some(max_length) = max_length {
-                            if let Some(len) = value.len() {
-                                if len > *max_length {
-                                    debug!("len: {}, max_length: {}", len, max_length);
-
-                                    errors.push(ValidationError::TypeMismatch {
-                                        field: field.clone(),
-                                        expected: field_type.to_string(),
-                                        actual: value.to_string(),
-                                    });
-                                }
-                            }
-                        }
-                        if let Some(min_length) = min_length {
-                            if let Some(len) = value.len() {
-                                if len < *min_length {
-                                    debug!("len: {}, min_length: {}", len, min_length);
-
-                                    errors.push(ValidationError::TypeMismatch {
-                                        field: field.clone(),
-                                        expected: field_type.to_string(),
-                                        actual: value.to_string(),
-                                    });
-                                }
-                            }
-                        }
-                    }
-                    FieldType::Number { min, max } => {
-                        info!("value.as_number(): {:?}", value.as_number());
-
-                        if let Some(num_value) = value.as_number() {
-                            debug!("num_value: {}", num_value);
-
-                            // 检查最小值
-                            if let Some(min) = min {
-                                debug!("num_value: {}, min: {}", num_value, min);
-                                if num_value < *min {
-                                    errors.push(ValidationError::TypeMismatch {
-                                        field: field.clone(),
-                                        expected: field_type.to_string(),
-                                        actual: value.to_string(),
-                                    });
-                                }
-                            }
+    #[test]
+    fn duration_fields_accept_units_within_range() {
+        let field_type = || FieldType::Duration {
+            min: Some(Duration::from_secs(1)),
+            max: Some(Duration::from_secs(300)),
+        };
+        assert!(check(field_type(), "30s".into()).is_empty());
+        assert!(check(field_type(), "1m30s".into()).is_empty());
+        assert!(check(field_type(), 60.into()).is_empty());
+        assert_eq!(check(field_type(), "10m".into()), ["TypeMismatch"]);
+        assert_eq!(check(field_type(), "500ms".into()), ["TypeMismatch"]);
+        assert_eq!(check(field_type(), "soon".into()), ["TypeMismatch"]);
+        assert_eq!(check(field_type(), true.into()), ["TypeMismatch"]);
+    }
 
-                            // 检查最大值
-                            if let Some(max) = max {
-                                debug!("num_value: {}, max: {}", num_value, max);
-                                if num_value > *max {
-                                    errors.push(ValidationError::TypeMismatch {
-                                        field: field.clone(),
-                                        expected: field_type.to_string(),
-                                        actual: value.to_string(),
-                                    });
-                                }
-                            }
-                        } else {
-                            debug!("as_number() returned None for value: {:?}", value);
-                            errors.push(ValidationError::TypeMismatch {
-                                field: field.clone(),
-                                expected: "number".to_string(),
-                                actual: format!("{:?}", value),
-                            });
-                        }
-                    }
-                    FieldType::Boolean => {
-                        if value != "true" && value != "false" {
-                            errors.push(ValidationError::TypeMismatch {
-                                field: field.clone(),
-                                expected: field_type.to_string(),
-                                actual: value.to_string(),
-                            });
-                        }
-                    }
-                }
-            } else {
-                errors.push(ValidationError::UndefinedField {
-                    field: field.clone(),
-                });
-            }
-        }
+    #[test]
+    fn byte_size_fields_accept_units_within_range() {
+        let field_type = || FieldType::ByteSize {
+            min: None,
+            max: Some(1 << 30),
+        };
+        assert!(check(field_type(), "512MB".into()).is_empty());
+        assert!(check(field_type(), "1GiB".into()).is_empty());
+        assert!(check(field_type(), 1024.into()).is_empty());
+        assert_eq!(check(field_type(), "2GB".into()), ["TypeMismatch"]);
+        assert_eq!(check(field_type(), "lots".into()), ["TypeMismatch"]);
+        assert_eq!(check(field_type(), (-1).into()), ["TypeMismatch"]);
+    }
 
-        for rule in self.validation.custom_rules.iter() {
-            if let Err(e) = rule(&self.config) {
-                errors.push(e);
-            }
-        }
+    #[test]
+    fn url_fields_check_scheme() {
+        let field_type = || FieldType::Url {
+            schemes: vec!["https".to_string()],
+        };
+        assert!(check(field_type(), "https://example.com/api".into()).is_empty());
+        assert!(check(FieldType::Url { schemes: vec![] }, "redis://cache:6379".into()).is_empty());
+        assert_eq!(check(field_type(), "http://example.com".into()), ["TypeMismatch"]);
+        assert_eq!(check(field_type(), "example.com".into()), ["TypeMismatch"]);
+    }
 
-        ValidationResult {
-            is_valid: errors.is_empty(),
-            errors,
-        }
+    #[test]
+    fn ip_fields_accept_v4_and_v6() {
+        assert!(check(FieldType::IpAddr, "10.0.0.1".into()).is_empty());
+        assert!(check(FieldType::IpAddr, "::1".into()).is_empty());
+        assert_eq!(check(FieldType::IpAddr, "10.0.0.256".into()), ["TypeMismatch"]);
+        assert_eq!(check(FieldType::IpAddr, "localhost".into()), ["TypeMismatch"]);
+    }
+
+    #[test]
+    fn path_fields_optionally_must_exist() {
+        let existing = std::env::temp_dir().display().to_string();
+        let missing = std::env::temp_dir().join("config-manager-missing-path").display().to_string();
+        assert!(check(FieldType::FilePath { must_exist: false }, missing.clone().into()).is_empty());
+        assert!(check(FieldType::FilePath { must_exist: true }, existing.into()).is_empty());
+        assert_eq!(check(FieldType::FilePath { must_exist: true }, missing.into()), ["PathNotFound"]);
+        assert_eq!(check(FieldType::FilePath { must_exist: false }, " ".into()), ["TypeMismatch"]);
+    }
+
+    #[test]
+    fn string_patterns_match_and_mismatch() {
+        let field_type = |pattern: &str| FieldType::String {
+            max_length: None,
+            min_length: None,
+            pattern: Some(pattern.to_string()),
+        };
+        assert!(check(field_type("^[a-z]+-\\d+$"), "web-1".into()).is_empty());
+        assert_eq!(check(field_type("^[a-z]+-\\d+$"), "Web-1".into()), ["PatternMismatch"]);
+        assert_eq!(check(field_type("^[a-z]+-\\d+$"), "web-".into()), ["PatternMismatch"]);
+        // 未加锚点时只要包含匹配即可
+        assert!(check(field_type("\\d+"), "web-1".into()).is_empty());
+        assert_eq!(check(field_type("("), "web-1".into()), ["CustomRuleViolation"]);
+
+        let mut config = Config::new();
+        config.set("host", ConfigValue::String("db.internal".to_string())).unwrap();
+        let result = Validation::new().field_type("host", field_type("^[a-z]+$")).validate(&config);
+        assert_eq!(
+            result.errors[0].to_string(),
+            "field host value \"db.internal\" does not match pattern ^[a-z]+$"
+        );
+    }
+
+    #[test]
+    fn enum_fields_accept_only_listed_values() {
+        let field_type = || FieldType::Enum {
+            values: [serde_json::json!("debug"), "info".into(), 3.into()]
+                .map(|value| ConfigValue::from_serde_json(value).unwrap())
+                .to_vec(),
+        };
+        assert!(check(field_type(), "info".into()).is_empty());
+        assert!(check(field_type(), 3.into()).is_empty());
+        assert_eq!(check(field_type(), "trace".into()), ["NotAllowed"]);
+        // 类型也要一致，字符串 "3" 不等于数字 3
+        assert_eq!(check(field_type(), "3".into()), ["NotAllowed"]);
+
+        let mut config = Config::new();
+        config.set("level", ConfigValue::String("trace".to_string())).unwrap();
+        let result = Validation::new().field_type("level", field_type()).validate(&config);
+        assert_eq!(
+            result.errors[0].to_string(),
+            "field level value \"trace\" is not allowed, expected one of: \"debug\", \"info\", 3"
+        );
+    }
+
+    #[test]
+    fn array_fields_check_size_and_item_type() {
+        let field_type = || FieldType::Array {
+            item_type: Box::new(FieldType::Integer {
+                min: Some(1),
+                max: None,
+            }),
+            min_items: Some(1),
+            max_items: Some(3),
+            unique: true,
+        };
+        assert!(check(field_type(), serde_json::json!([1, 2, 3])).is_empty());
+        assert_eq!(check(field_type(), serde_json::json!([])), ["ItemCount"]);
+        assert_eq!(check(field_type(), serde_json::json!([1, 2, 3, 4])), ["ItemCount"]);
+        assert_eq!(check(field_type(), serde_json::json!([1, "two"])), ["TypeMismatch"]);
+        assert_eq!(check(field_type(), serde_json::json!([1, 0])), ["TypeMismatch"]);
+        assert_eq!(check(field_type(), serde_json::json!([1, 1])), ["DuplicateItem"]);
+        assert_eq!(check(field_type(), "1,2".into()), ["TypeMismatch"]);
+
+        // 元素错误带下标
+        let mut config = Config::new();
+        let ports = ConfigValue::from_serde_json(serde_json::json!([8080, "http"])).unwrap();
+        config.set("ports", ports).unwrap();
+        let result = Validation::new().field_type("ports", field_type()).validate(&config);
+        assert!(result.errors[0].to_string().starts_with("field ports[1] type mismatch"));
+    }
+
+    #[test]
+    fn object_fields_check_nested_required_keys() {
+        let tls = FieldType::Object {
+            fields: HashMap::from([("cert".to_string(), FieldType::FilePath { must_exist: false })]),
+            required: vec!["cert".to_string(), "key".to_string()],
+            allow_unknown: true,
+        };
+        let field_type = || FieldType::Object {
+            fields: HashMap::from([
+                ("host".to_string(), FieldType::IpAddr),
+                ("tls".to_string(), tls.clone()),
+            ]),
+            required: vec!["host".to_string()],
+            allow_unknown: false,
+        };
+        let server = |value: serde_json::Value| {
+            let mut config = Config::new();
+            config.set("server", ConfigValue::from_serde_json(value).unwrap()).unwrap();
+            let result = Validation::new().field_type("server", field_type()).validate(&config);
+            result.errors.iter().map(|e| e.to_string()).collect::<Vec<String>>()
+        };
+
+        let valid = serde_json::json!({"host": "10.0.0.1", "tls": {"cert": "a.pem", "key": "a.key"}});
+        assert!(server(valid).is_empty());
+        assert_eq!(
+            server(serde_json::json!({"host": "10.0.0.1", "tls": {"cert": "a.pem"}})),
+            ["field server.tls.key is required"]
+        );
+        assert_eq!(
+            server(serde_json::json!({"tls": {"cert": "a.pem", "key": "a.key", "ca": "ca.pem"}})),
+            ["field server.host is required"]
+        );
+        assert_eq!(
+            server(serde_json::json!({"host": "10.0.0.1", "port": 80})),
+            ["field server.port is not defined"]
+        );
+        assert_eq!(check(field_type(), "10.0.0.1".into()), ["TypeMismatch"]);
+    }
+
+    #[test]
+    fn severities_split_findings_and_strict_promotes_warnings() {
+        let validation = Validation::new()
+            .require_field("host")
+            .require_field("port")
+            .require_field("region")
+            .severity("port", Severity::Warning)
+            .severity("region", Severity::Info);
+
+        let result = validation.validate(&Config::new());
+        assert!(!result.is_valid);
+        assert_eq!(result.errors[0].to_string(), "field host is required");
+        assert_eq!(result.warnings[0].to_string(), "field port is required");
+        assert_eq!(result.infos[0].to_string(), "field region is required");
+
+        // 只有警告时校验通过，--strict 后警告变为错误，info 保持不变
+        let mut config = Config::new();
+        config.set("host", ConfigValue::String("db".to_string())).unwrap();
+        let result = validation.validate(&config);
+        assert!(result.is_valid);
+        assert_eq!(result.warnings.len(), 1);
+        let result = result.strict();
+        assert!(!result.is_valid);
+        assert_eq!(result.errors[0].to_string(), "field port is required");
+        assert!(result.warnings.is_empty());
+        assert_eq!(result.infos.len(), 1);
+    }
+
+    #[test]
+    fn deprecated_fields_warn_with_replacement_hint() {
+        let validation = Validation::new()
+            .deprecated_field(
+                "db_host",
+                DeprecatedField {
+                    replacement: Some("database.host".to_string()),
+                    removed_in: Some("2.0".to_string()),
+                },
+            )
+            .deprecated_field("legacy", DeprecatedField::default());
+        assert!(validation.validate(&Config::new()).warnings.is_empty());
+
+        let mut config = Config::new();
+        config.set("db_host", ConfigValue::String("db".to_string())).unwrap();
+        config.set("legacy", ConfigValue::Boolean(true)).unwrap();
+        let result = validation.validate(&config);
+        assert!(result.is_valid);
+        let warnings: Vec<String> = result.warnings.iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            warnings,
+            [
+                "field db_host is deprecated, use database.host instead, will be removed in 2.0",
+                "field legacy is deprecated",
+            ]
+        );
+    }
+
+    #[test]
+    fn deny_unknown_fields_rejects_undeclared_keys() {
+        let mut config = Config::new();
+        let value = serde_json::json!({"host": "db", "prot": 5432, "pool": {"size": 4}});
+        config.set("database", ConfigValue::from_serde_json(value).unwrap()).unwrap();
+        config.set("debug", ConfigValue::Boolean(false)).unwrap();
+        let validation = || {
+            Validation::new()
+                .require_field("database.host")
+                .field_type(
+                    "database.pool",
+                    FieldType::Object {
+                        fields: HashMap::new(),
+                        required: vec![],
+                        allow_unknown: true,
+                    },
+                )
+                .default_value("debug", ConfigValue::Boolean(true))
+        };
+
+        assert!(validation().validate(&config).is_valid);
+        let result = validation().deny_unknown_fields(true).validate(&config);
+        let errors: Vec<String> = result.errors.iter().map(|e| e.to_string()).collect();
+        assert_eq!(errors, ["field database.prot is not defined"]);
+    }
+
+    #[test]
+    fn defaults_fill_missing_keys_only() {
+        let mut config = Config::new();
+        config.set("server.port", ConfigValue::from_serde_json(8080.into()).unwrap()).unwrap();
+        let validation = Validation::new()
+            .default_value("server.port", ConfigValue::from_serde_json(80.into()).unwrap())
+            .default_value("server.tls.enabled", ConfigValue::Boolean(false))
+            .default_value("log_level", ConfigValue::String("info".to_string()));
+
+        let applied = validation.apply_defaults(&mut config).unwrap();
+        assert_eq!(applied, ["log_level", "server.tls.enabled"]);
+        assert_eq!(config.get("server.port").unwrap().as_number(), Some(8080.0));
+        assert_eq!(config.get("server.tls.enabled").unwrap(), ConfigValue::Boolean(false));
+        assert_eq!(config.get("log_level").unwrap(), "info");
+        // 再次填充时没有缺失的键
+        assert!(validation.apply_defaults(&mut config).unwrap().is_empty());
+    }
+
+    #[test]
+    fn integer_fields_check_range_and_reject_floats() {
+        let field_type = || FieldType::Integer {
+            min: Some(1),
+            max: Some(65535),
+        };
+        assert!(check(field_type(), 1.into()).is_empty());
+        assert!(check(field_type(), 65535.into()).is_empty());
+        assert_eq!(check(field_type(), 0.into()), ["TypeMismatch"]);
+        assert_eq!(check(field_type(), 65536.into()), ["TypeMismatch"]);
+        assert_eq!(check(field_type(), 3.5.into()), ["TypeMismatch"]);
+        assert_eq!(check(field_type(), 8080.0.into()), ["TypeMismatch"]);
+        assert_eq!(check(field_type(), "8080".into()), ["TypeMismatch"]);
+        // 超出 i64 的 u64 按 i128 比较，不会溢出
+        let unbounded = FieldType::Integer { min: Some(0), max: None };
+        assert!(check(unbounded, u64::MAX.into()).is_empty());
     }
 }
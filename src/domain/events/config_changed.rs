@@ -1,5 +1,10 @@
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::domain::{
+    entities::configuration::{Config, ConfigValue},
+    services::{config_diff::ConfigDiffService, config_patch::PatchOperation},
+};
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ConfigChangedEvent {
@@ -26,3 +31,91 @@ impl ConfigChangedEvent {
         }
     }
 }
+
+// 变更类型：配置文件新建、修改或删除
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    Created,
+    #[default]
+    Updated,
+    Deleted,
+}
+
+impl ChangeKind {
+    // 推送给订阅者的消息类型（WebSocket 的 type、SSE 的事件名）
+    pub fn message_type(&self) -> &'static str {
+        match self {
+            ChangeKind::Created => "created",
+            ChangeKind::Updated => "update",
+            ChangeKind::Deleted => "deleted",
+        }
+    }
+}
+
+// 推送给订阅者的配置更新
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigUpdate {
+    pub seq: u64,
+    pub file: String,
+    #[serde(default)]
+    pub kind: ChangeKind,
+    // 删除时为 "null"
+    pub config: String,
+    // 相对订阅者上一次收到的版本的 JSON Patch
+    #[serde(default)]
+    pub patch: Vec<PatchOperation>,
+    // 内容哈希，与 HTTP 接口的 ETag 一致
+    #[serde(default)]
+    pub hash: Option<String>,
+    #[serde(default)]
+    pub source: String, // 变更来源：file_watcher, http_api, tcp_client, rebuild
+    pub timestamp: DateTime<Utc>,
+}
+
+// 待发布的配置变更，写入事件日志时分配序号
+#[derive(Debug, Clone)]
+pub struct ConfigChange {
+    pub file: String,
+    pub kind: ChangeKind,
+    pub config: String,
+    pub patch: Vec<PatchOperation>,
+    pub hash: Option<String>,
+    pub source: String,
+}
+
+// 单个键的变更，old/new 为 None 表示键被新增/删除；数组按整体比较
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeEvent {
+    pub file: String,
+    pub key: String,
+    pub old: Option<ConfigValue>,
+    pub new: Option<ConfigValue>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ChangeEvent {
+    // 对比同一文件的新旧配置，按键名排序返回所有发生变化的叶子键
+    pub fn diff(file: &str, old: Option<&Config>, new: &Config, timestamp: DateTime<Utc>) -> Vec<Self> {
+        let empty = Config::new();
+        ConfigDiffService::diff(file, old.unwrap_or(&empty), file, new)
+            .entries
+            .into_iter()
+            .map(|entry| Self {
+                file: file.to_string(),
+                key: entry.key,
+                old: entry.old,
+                new: entry.new,
+                timestamp,
+            })
+            .collect()
+    }
+
+    pub fn old_as<T: DeserializeOwned>(&self) -> Option<T> {
+        self.old.as_ref().and_then(|v| serde_json::from_value(v.to_serde_value()).ok())
+    }
+
+    pub fn new_as<T: DeserializeOwned>(&self) -> Option<T> {
+        self.new.as_ref().and_then(|v| serde_json::from_value(v.to_serde_value()).ok())
+    }
+}
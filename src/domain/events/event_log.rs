@@ -0,0 +1,88 @@
+use std::collections::{HashMap, VecDeque};
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::value_objects::namespace::covers;
+
+use super::config_changed::{ConfigChange, ConfigUpdate};
+
+pub const DEFAULT_EVENT_LOG_CAPACITY: usize = 1024;
+
+// 最近的配置更新记录（环形缓冲），用于断线重连后补发
+#[derive(Debug)]
+pub struct EventLog {
+    capacity: usize,
+    next_seq: u64,
+    records: VecDeque<ConfigUpdate>,
+    // 每个文件最近一次更新的序号，不随环形缓冲淘汰
+    versions: HashMap<String, u64>,
+}
+
+impl Default for EventLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_LOG_CAPACITY)
+    }
+}
+
+impl EventLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            next_seq: 1,
+            records: VecDeque::new(),
+            versions: HashMap::new(),
+        }
+    }
+
+    pub fn record(&mut self, change: ConfigChange, timestamp: DateTime<Utc>) -> ConfigUpdate {
+        let update = ConfigUpdate {
+            seq: self.next_seq,
+            file: change.file,
+            kind: change.kind,
+            config: change.config,
+            patch: change.patch,
+            hash: change.hash,
+            source: change.source,
+            timestamp,
+        };
+        self.next_seq += 1;
+        self.versions.insert(update.file.clone(), update.seq);
+        if self.records.len() == self.capacity {
+            self.records.pop_front();
+        }
+        self.records.push_back(update.clone());
+        update
+    }
+
+    // 只发给部分订阅者的更新（金丝雀发布）占用序号但不进入日志，断线重连时不会补发
+    pub fn next_seq(&mut self) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        seq
+    }
+
+    pub fn latest_seq(&self) -> u64 {
+        self.next_seq - 1
+    }
+
+    // 文件当前内容对应的版本（最近一次更新的序号），启动以来未更新过时为 0
+    pub fn version(&self, file: &str) -> u64 {
+        self.versions.get(file).copied().unwrap_or(0)
+    }
+
+    // 返回 seq 之后该文件（或命名空间）的所有更新；若所需记录已被淘汰则返回 None
+    pub fn since(&self, file: &str, seq: u64) -> Option<Vec<ConfigUpdate>> {
+        if let Some(oldest) = self.records.front()
+            && oldest.seq > seq + 1
+        {
+            return None;
+        }
+        Some(
+            self.records
+                .iter()
+                .filter(|u| u.seq > seq && covers(file, &u.file))
+                .cloned()
+                .collect(),
+        )
+    }
+}
@@ -1,2 +1,3 @@
 pub mod config_changed;
-pub mod config_validated;
\ No newline at end of file
+pub mod config_validated;
+pub mod event_log;
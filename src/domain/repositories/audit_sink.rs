@@ -0,0 +1,12 @@
+use crate::{domain::entities::audit::AuditRecord, shared::error::ConfigError};
+use async_trait::async_trait;
+
+#[async_trait]
+pub trait AuditSink: Send + Sync {
+    async fn write(&self, record: &AuditRecord) -> Result<(), ConfigError>;
+
+    // 读回已写入的记录；syslog、HTTP 等无法回读的 sink 返回 None
+    async fn read(&self) -> Option<Result<Vec<AuditRecord>, ConfigError>> {
+        None
+    }
+}
@@ -0,0 +1,19 @@
+use async_trait::async_trait;
+
+use crate::{domain::entities::backup::BackupInfo, shared::error::BackupError};
+
+// 备份的存放位置：本地目录或对象存储
+#[async_trait]
+pub trait BackupStore: Send + Sync {
+    // 带类型前缀的位置，例如 "dir:/var/backups" 或 "s3://bucket/prefix"，用于日志
+    fn id(&self) -> String;
+
+    async fn put(&self, name: &str, content: Vec<u8>) -> Result<(), BackupError>;
+
+    async fn get(&self, name: &str) -> Result<Vec<u8>, BackupError>;
+
+    // 按创建时间从旧到新排列
+    async fn list(&self) -> Result<Vec<BackupInfo>, BackupError>;
+
+    async fn delete(&self, name: &str) -> Result<(), BackupError>;
+}
@@ -0,0 +1,10 @@
+use crate::{domain::entities::configuration::Config, shared::error::ConfigError};
+use async_trait::async_trait;
+
+// 只读的配置来源：本地文件、HTTP(S) 地址或标准输入
+#[async_trait]
+pub trait ConfigSource: Send + Sync {
+    // 报告和错误信息中显示的名称
+    fn name(&self) -> &str;
+    async fn load(&self) -> Result<Config, ConfigError>;
+}
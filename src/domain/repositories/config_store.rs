@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::shared::error::ConfigError;
+
+// serve --storage 指定的配置存储，配置目录是它的本地副本；两边按 revision 判断是否需要同步
+#[async_trait]
+pub trait ConfigStore: Send + Sync {
+    // 带类型前缀的位置，例如 "s3://bucket/prefix" 或 "sqlite:configs.db"，用于日志
+    fn id(&self) -> String;
+
+    async fn list(&self) -> Result<Vec<StoredConfig>, ConfigError>;
+
+    // 原始文本内容，不存在时返回 None
+    async fn get_content(&self, name: &str) -> Result<Option<String>, ConfigError>;
+
+    // 原样写入文本内容，返回新的 revision（存储未返回时为 None）
+    async fn save_content(&self, name: &str, content: &str) -> Result<Option<String>, ConfigError>;
+
+    async fn delete_content(&self, name: &str) -> Result<(), ConfigError>;
+
+    // 等到存储中的配置可能已被修改时返回；etcd、Consul 使用原生的 watch，其他存储按 interval 轮询
+    async fn changed(&self, interval: Duration) -> Result<(), ConfigError> {
+        tokio::time::sleep(interval).await;
+        Ok(())
+    }
+
+    // 附加规则文件 (文件名, 内容)，不保存规则的存储返回空
+    async fn list_rules(&self) -> Result<Vec<(String, String)>, ConfigError> {
+        Ok(vec![])
+    }
+
+    async fn save_rules(&self, _name: &str, _content: &str) -> Result<(), ConfigError> {
+        Ok(())
+    }
+}
+
+// 存储中的一个配置；revision 在内容变化时改变，例如 S3 的 ETag
+#[derive(Debug, Clone)]
+pub struct StoredConfig {
+    pub name: String,
+    pub revision: String,
+}
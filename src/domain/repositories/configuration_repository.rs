@@ -1,11 +1,60 @@
-use crate::{domain::entities::configuration::Config, shared::error::ConfigError};
+use std::any::Any;
+
 use async_trait::async_trait;
+use tokio::sync::mpsc;
+
+use crate::{
+    domain::entities::{
+        config_version::{ConfigVersion, ConfigVersionInfo},
+        configuration::Config,
+    },
+    shared::error::ConfigError,
+};
 
+// 按名称读写配置的仓储：本地文件仓储中名称是相对根目录的路径（CLI 以当前目录为根），
+// 远程仓储中是服务端的配置名称
 #[async_trait]
-pub trait ConfigurationRepository {
-    async fn save(&self, config: Config, path: &str) -> Result<(), ConfigError>;
-    async fn get(&self, path: String) -> Result<Config, ConfigError>;
-    async fn get_all(&self) -> Result<Vec<Config>, ConfigError>;
-    async fn delete(&self, path: String) -> Result<(), ConfigError>;
-    async fn update(&self, config: Config, path: String) -> Result<(), ConfigError>;
+pub trait ConfigurationRepository: Send + Sync {
+    async fn get(&self, name: &str) -> Result<Config, ConfigError>;
+
+    // 仓储中全部配置的名称
+    async fn list(&self) -> Result<Vec<String>, ConfigError>;
+
+    // 按配置自身的格式序列化后写入
+    async fn save(&self, name: &str, config: Config) -> Result<(), ConfigError>;
+
+    async fn delete(&self, name: &str) -> Result<(), ConfigError>;
+
+    // 监听仓储中配置的新建、修改和删除，不支持监听的仓储返回错误
+    async fn watch(&self) -> Result<ConfigWatch, ConfigError> {
+        Err(ConfigError::NowRepositoryConfigNotSupportFunction)
+    }
+
+    // 配置的历史版本（不含内容），按版本号升序；不保存历史的仓储返回错误
+    async fn history(&self, _name: &str) -> Result<Vec<ConfigVersionInfo>, ConfigError> {
+        Err(ConfigError::NowRepositoryConfigNotSupportFunction)
+    }
+
+    async fn version(&self, _name: &str, _version: u64) -> Result<ConfigVersion, ConfigError> {
+        Err(ConfigError::NowRepositoryConfigNotSupportFunction)
+    }
+}
+
+// watch 返回的变更流，逐个产出被修改的配置名称；持有底层的监听资源，drop 后停止监听
+pub struct ConfigWatch {
+    receiver: mpsc::UnboundedReceiver<String>,
+    _guard: Box<dyn Any + Send>,
+}
+
+impl ConfigWatch {
+    pub fn new(receiver: mpsc::UnboundedReceiver<String>, guard: impl Any + Send) -> Self {
+        Self {
+            receiver,
+            _guard: Box::new(guard),
+        }
+    }
+
+    pub async fn next(&mut self) -> Option<String> {
+        self.receiver.recv().await
+    }
 }
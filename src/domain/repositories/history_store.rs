@@ -0,0 +1,46 @@
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+
+use crate::{domain::entities::config_version::ConfigVersion, shared::error::ConfigError};
+
+// 配置的历史版本：默认保存在配置目录的 .history 中，--storage sqlite: 时保存在数据库
+pub trait HistoryStore: Send + Sync {
+    fn list(&self, file: &str) -> Result<Vec<ConfigVersion>, ConfigError>;
+
+    fn get(&self, file: &str, version: u64) -> Result<Option<ConfigVersion>, ConfigError> {
+        Ok(self.list(file)?.into_iter().find(|v| v.version == version))
+    }
+
+    // 内容与最新版本相同时不重复记录（一次写入可能触发多次修改事件），返回新版本号
+    fn record(
+        &self,
+        file: &str,
+        content: String,
+        saved_at: DateTime<Utc>,
+    ) -> Result<Option<u64>, ConfigError>;
+
+    // 记录配置目录下文件的当前内容
+    fn record_file(
+        &self,
+        file: &str,
+        path: &Path,
+        saved_at: DateTime<Utc>,
+    ) -> Result<Option<u64>, ConfigError> {
+        let content = std::fs::read_to_string(path)?;
+        self.record_content(file, content, saved_at)
+    }
+
+    // 同 record_file，内容已经由调用方读出；编辑器截断后再写入时可能读到空文件，空内容不作为版本
+    fn record_content(
+        &self,
+        file: &str,
+        content: String,
+        saved_at: DateTime<Utc>,
+    ) -> Result<Option<u64>, ConfigError> {
+        if content.trim().is_empty() {
+            return Ok(None);
+        }
+        self.record(file, content, saved_at)
+    }
+}
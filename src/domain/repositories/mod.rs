@@ -1,2 +1,8 @@
+pub mod audit_sink;
+pub mod backup_store;
+pub mod config_source;
+pub mod config_store;
 pub mod configuration_repository;
+pub mod history_store;
+pub mod notification_sink;
 pub mod template_repository;
@@ -0,0 +1,14 @@
+use async_trait::async_trait;
+
+use crate::domain::events::config_changed::ConfigUpdate;
+
+// 变更通知目标：订阅者连接、webhook 或消息总线，每个 sink 由独立任务按发布顺序投递
+#[async_trait]
+pub trait NotificationSink: Send + Sync {
+    // 带类型前缀的目标标识，例如 "webhook:http://..." 或 "nats:127.0.0.1:4222/config.changes"，
+    // 用于日志和死信重放
+    fn id(&self) -> String;
+
+    // 失败时返回 (尝试次数, 最后一次错误)
+    async fn publish(&self, update: &ConfigUpdate) -> Result<(), (u32, String)>;
+}
@@ -0,0 +1,242 @@
+use std::{cmp::Ordering, fmt::Display};
+
+use crate::{
+    domain::entities::{
+        configuration::{Config, ConfigValue},
+        validation_rule::CompareOp,
+    },
+    shared::error::ConfigError,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssertOp {
+    Compare(CompareOp),
+    Contains,
+    Matches,
+    Exists,
+    Missing,
+}
+
+impl Display for AssertOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AssertOp::Compare(op) => write!(f, "{}", op),
+            AssertOp::Contains => write!(f, "contains"),
+            AssertOp::Matches => write!(f, "matches"),
+            AssertOp::Exists => write!(f, "exists"),
+            AssertOp::Missing => write!(f, "missing"),
+        }
+    }
+}
+
+// 针对配置的单条断言，例如：
+//   database.port == 5432
+//   server.timeout <= 30s
+//   features contains "billing"
+//   database.host matches "^prod-"
+//   tls exists
+// 右侧按 JSON 字面量解析，解析不了时作为字符串
+#[derive(Debug, Clone)]
+pub struct Assertion {
+    pub path: String,
+    pub op: AssertOp,
+    pub expected: Option<ConfigValue>,
+}
+
+impl Assertion {
+    pub fn parse(expression: &str) -> Result<Self, ConfigError> {
+        let invalid = || ConfigError::InvalidAssertion(expression.to_string());
+        let expression = expression.trim();
+        let (path, rest) = expression.split_once(char::is_whitespace).ok_or_else(invalid)?;
+        let rest = rest.trim_start();
+        let (op, literal) = rest
+            .split_once(char::is_whitespace)
+            .map(|(op, literal)| (op, literal.trim()))
+            .unwrap_or((rest, ""));
+        let op = match op {
+            "exists" => AssertOp::Exists,
+            "missing" => AssertOp::Missing,
+            "contains" => AssertOp::Contains,
+            "matches" => AssertOp::Matches,
+            op => AssertOp::Compare(CompareOp::parse(op).ok_or_else(invalid)?),
+        };
+        let expected = match op {
+            AssertOp::Exists | AssertOp::Missing if literal.is_empty() => None,
+            AssertOp::Exists | AssertOp::Missing => return Err(invalid()),
+            _ if literal.is_empty() => return Err(invalid()),
+            _ => Some(Self::parse_literal(literal)),
+        };
+        if let (AssertOp::Matches, Some(pattern)) = (op, &expected) {
+            let pattern = pattern.as_string().ok_or_else(invalid)?;
+            regex::Regex::new(pattern).map_err(|e| ConfigError::InvalidRegexPattern {
+                field: path.to_string(),
+                error: e.to_string(),
+            })?;
+        }
+        Ok(Self {
+            path: path.to_string(),
+            op,
+            expected,
+        })
+    }
+
+    fn parse_literal(literal: &str) -> ConfigValue {
+        if let Some(quoted) = literal.strip_prefix('\'').and_then(|l| l.strip_suffix('\'')) {
+            return ConfigValue::String(quoted.to_string());
+        }
+        serde_json::from_str(literal)
+            .ok()
+            .and_then(|value| ConfigValue::from_serde_json(value).ok())
+            .unwrap_or_else(|| ConfigValue::String(literal.to_string()))
+    }
+
+    // 不成立时返回说明实际值的失败信息
+    pub fn evaluate(&self, config: &Config) -> Result<(), String> {
+        let actual = config.get(&self.path);
+        let (actual, expected) = match (self.op, actual, &self.expected) {
+            (AssertOp::Exists, Some(_), _) | (AssertOp::Missing, None, _) => return Ok(()),
+            (AssertOp::Exists, None, _) => return Err(format!("{} is missing", self.path)),
+            (AssertOp::Missing, Some(actual), _) => {
+                return Err(format!("{} exists with {}", self.path, actual.to_serde_value()));
+            }
+            (_, None, _) => return Err(format!("{} is missing", self.path)),
+            (_, Some(actual), Some(expected)) => (actual, expected),
+            (_, Some(_), None) => return Err(format!("{} has no expected value", self.path)),
+        };
+        let holds = match self.op {
+            AssertOp::Compare(CompareOp::Eq) => Ok(Self::equals(&actual, expected)),
+            AssertOp::Compare(CompareOp::Ne) => Ok(!Self::equals(&actual, expected)),
+            AssertOp::Compare(op) => Self::ordering(&actual, expected).map(|o| op.accepts(o)),
+            AssertOp::Contains => Self::contains(&actual, expected),
+            AssertOp::Matches => match (actual.as_string(), expected.as_string()) {
+                (Some(value), Some(pattern)) => regex::Regex::new(pattern)
+                    .map(|re| re.is_match(value))
+                    .map_err(|e| e.to_string()),
+                _ => Err(format!("{} is not a string", self.path)),
+            },
+            AssertOp::Exists | AssertOp::Missing => Ok(true),
+        };
+        match holds {
+            Ok(true) => Ok(()),
+            Ok(false) => Err(format!(
+                "expected {} {} {}, got {}",
+                self.path,
+                self.op,
+                expected.to_serde_value(),
+                actual.to_serde_value()
+            )),
+            Err(reason) => Err(format!("{} {} {}: {}", self.path, self.op, expected.to_serde_value(), reason)),
+        }
+    }
+
+    // 数字按数值比较，5432 与 5432.0 相等
+    fn equals(actual: &ConfigValue, expected: &ConfigValue) -> bool {
+        match (actual, expected) {
+            (ConfigValue::Number(_), ConfigValue::Number(_)) => actual.as_number() == expected.as_number(),
+            _ => actual == expected,
+        }
+    }
+
+    // 依次尝试数字、时长、字节大小和字符串的顺序
+    fn ordering(actual: &ConfigValue, expected: &ConfigValue) -> Result<Ordering, String> {
+        if let (ConfigValue::Number(_), ConfigValue::Number(_)) = (actual, expected) {
+            return actual
+                .as_number()
+                .zip(expected.as_number())
+                .and_then(|(a, e)| a.partial_cmp(&e))
+                .ok_or_else(|| "numbers are not comparable".to_string());
+        }
+        if let (Some(a), Some(e)) = (actual.as_duration(), expected.as_duration()) {
+            return Ok(a.cmp(&e));
+        }
+        if let (Some(a), Some(e)) = (actual.as_byte_size(), expected.as_byte_size()) {
+            return Ok(a.cmp(&e));
+        }
+        if let (Some(a), Some(e)) = (actual.as_string(), expected.as_string()) {
+            return Ok(a.cmp(e));
+        }
+        Err(format!("cannot compare {} with {}", actual.type_name(), expected.type_name()))
+    }
+
+    fn contains(actual: &ConfigValue, expected: &ConfigValue) -> Result<bool, String> {
+        match actual {
+            ConfigValue::Array(items) => Ok(items.iter().any(|item| Self::equals(item, expected))),
+            ConfigValue::String(value) => expected
+                .as_string()
+                .map(|needle| value.contains(needle.as_str()))
+                .ok_or_else(|| "expected a string".to_string()),
+            ConfigValue::Object(object) => expected
+                .as_string()
+                .map(|key| object.contains_key(key))
+                .ok_or_else(|| "expected a key name".to_string()),
+            other => Err(format!("cannot search in {}", other.type_name())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn evaluate(expression: &str) -> Result<(), String> {
+        let mut config = Config::new();
+        let value = json!({"host": "prod-db", "port": 5432, "timeout": "30s", "features": ["billing"]});
+        config.set("database", ConfigValue::from_serde_json(value).unwrap()).unwrap();
+        Assertion::parse(expression).unwrap().evaluate(&config)
+    }
+
+    #[test]
+    fn passing_assertions() {
+        for expression in [
+            "database.port == 5432",
+            "database.port == 5432.0",
+            "database.port < 65536",
+            "database.timeout <= 1m",
+            "database.features contains billing",
+            "database.host matches '^prod-'",
+            "database exists",
+            "cache missing",
+        ] {
+            assert_eq!(evaluate(expression), Ok(()), "{}", expression);
+        }
+    }
+
+    #[test]
+    fn failing_assertions_report_the_actual_value() {
+        assert_eq!(
+            evaluate("database.port == 6543").unwrap_err(),
+            "expected database.port == 6543, got 5432"
+        );
+        assert_eq!(
+            evaluate("database.features contains audit").unwrap_err(),
+            "expected database.features contains \"audit\", got [\"billing\"]"
+        );
+        assert_eq!(
+            evaluate("database.host missing").unwrap_err(),
+            "database.host exists with \"prod-db\""
+        );
+        assert_eq!(
+            evaluate("database.port > 'high'").unwrap_err(),
+            "database.port > \"high\": cannot compare Number with String"
+        );
+    }
+
+    #[test]
+    fn missing_paths_fail_with_the_path() {
+        assert_eq!(evaluate("database.user == app").unwrap_err(), "database.user is missing");
+        assert_eq!(evaluate("cache.ttl exists").unwrap_err(), "cache.ttl is missing");
+    }
+
+    #[test]
+    fn malformed_assertions_are_rejected() {
+        for expression in ["database.port", "database.port ~ 1", "database.port ==", "tls exists yes"] {
+            assert!(
+                matches!(Assertion::parse(expression), Err(ConfigError::InvalidAssertion(_))),
+                "{}",
+                expression
+            );
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::entities::configuration::{Config, ConfigValue};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffKind {
+    Added,
+    Removed,
+    Changed,
+}
+
+// 单个叶子键的差异；数组和空对象按整体比较
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DiffEntry {
+    pub key: String,
+    pub kind: DiffKind,
+    #[serde(serialize_with = "serialize_value")]
+    pub old: Option<ConfigValue>,
+    #[serde(serialize_with = "serialize_value")]
+    pub new: Option<ConfigValue>,
+}
+
+// 两份配置之间的结构化差异，各种渲染器共用
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConfigDiff {
+    pub left: String,
+    pub right: String,
+    pub entries: Vec<DiffEntry>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn count(&self, kind: DiffKind) -> usize {
+        self.entries.iter().filter(|e| e.kind == kind).count()
+    }
+}
+
+pub struct ConfigDiffService;
+
+impl ConfigDiffService {
+    // 按键名排序返回所有发生变化的叶子键
+    pub fn diff(left_name: &str, left: &Config, right_name: &str, right: &Config) -> ConfigDiff {
+        let old_leaves = Self::leaves(left);
+        let new_leaves = Self::leaves(right);
+        let keys: BTreeSet<&String> = old_leaves.keys().chain(new_leaves.keys()).collect();
+        let entries = keys
+            .into_iter()
+            .filter_map(|key| {
+                let (old, new) = (old_leaves.get(key), new_leaves.get(key));
+                let kind = match (old, new) {
+                    (None, Some(_)) => DiffKind::Added,
+                    (Some(_), None) => DiffKind::Removed,
+                    (Some(old), Some(new)) if old != new => DiffKind::Changed,
+                    _ => return None,
+                };
+                Some(DiffEntry {
+                    key: key.clone(),
+                    kind,
+                    old: old.cloned(),
+                    new: new.cloned(),
+                })
+            })
+            .collect();
+        ConfigDiff {
+            left: left_name.to_string(),
+            right: right_name.to_string(),
+            entries,
+        }
+    }
+
+    pub fn leaves(config: &Config) -> BTreeMap<String, ConfigValue> {
+        let mut leaves = BTreeMap::new();
+        Self::flatten("", &config.config, &mut leaves);
+        leaves
+    }
+
+    fn flatten(
+        prefix: &str,
+        object: &HashMap<String, ConfigValue>,
+        leaves: &mut BTreeMap<String, ConfigValue>,
+    ) {
+        for (key, value) in object.iter() {
+            let path = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+            match value {
+                ConfigValue::Object(child) if !child.is_empty() => {
+                    Self::flatten(&path, child, leaves)
+                }
+                _ => {
+                    leaves.insert(path, value.clone());
+                }
+            }
+        }
+    }
+}
+
+fn serialize_value<S: serde::Serializer>(
+    value: &Option<ConfigValue>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    value
+        .as_ref()
+        .map(|value| value.to_serde_value())
+        .serialize(serializer)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn config(value: serde_json::Value) -> Config {
+        let mut config = Config::new();
+        for (key, value) in value.as_object().unwrap() {
+            config.set(key, ConfigValue::from_serde_json(value.clone()).unwrap()).unwrap();
+        }
+        config
+    }
+
+    fn summary(diff: &ConfigDiff) -> Vec<(&str, DiffKind)> {
+        diff.entries.iter().map(|e| (e.key.as_str(), e.kind)).collect()
+    }
+
+    #[test]
+    fn added_removed_and_changed_keys() {
+        let left = config(json!({"port": 5432, "debug": false, "host": "db"}));
+        let right = config(json!({"port": 6543, "host": "db", "user": "app"}));
+        let diff = ConfigDiffService::diff("a.json", &left, "b.json", &right);
+        assert_eq!(
+            summary(&diff),
+            [("debug", DiffKind::Removed), ("port", DiffKind::Changed), ("user", DiffKind::Added)]
+        );
+        let changed = &diff.entries[1];
+        assert_eq!(changed.old.as_ref().unwrap().as_number(), Some(5432.0));
+        assert_eq!(changed.new.as_ref().unwrap().as_number(), Some(6543.0));
+        assert_eq!(diff.count(DiffKind::Changed), 1);
+        assert!(ConfigDiffService::diff("a", &left, "b", &left).is_empty());
+    }
+
+    #[test]
+    fn nested_objects_diff_by_leaf_and_arrays_as_a_whole() {
+        let left = config(json!({
+            "db": {"primary": {"host": "a", "port": 1}, "replicas": ["b"]},
+            "cache": {}
+        }));
+        let right = config(json!({
+            "db": {"primary": {"host": "a", "port": 2}, "replicas": ["b", "c"]},
+            "cache": {"ttl": 60}
+        }));
+        let diff = ConfigDiffService::diff("a", &left, "b", &right);
+        // 空对象是叶子，变为非空对象后按新的叶子比较
+        assert_eq!(
+            summary(&diff),
+            [
+                ("cache", DiffKind::Removed),
+                ("cache.ttl", DiffKind::Added),
+                ("db.primary.port", DiffKind::Changed),
+                ("db.replicas", DiffKind::Changed),
+            ]
+        );
+    }
+}
@@ -0,0 +1,159 @@
+use serde::Serialize;
+
+use crate::{domain::value_objects::config_format::ConfigType, shared::error::ConfigError};
+
+#[derive(Debug, Clone)]
+pub struct FormatOptions {
+    pub sort_keys: bool,
+    pub indent: usize,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            sort_keys: false,
+            indent: 2,
+        }
+    }
+}
+
+pub struct ConfigFormatterService;
+
+impl ConfigFormatterService {
+    // 以规范格式重写配置内容（保持原格式）
+    // 使用 serde_yaml::Value 作为中间表示，因为它的 Mapping 保留键的原始顺序
+    // 引号由序列化器统一决定：YAML 只给有歧义的值（'true'、'1.0'）加单引号，
+    // TOML 使用双引号，只有需要转义时才用单引号字面量，键不需要时不加引号
+    pub fn format(
+        content: &str,
+        config_type: &ConfigType,
+        options: &FormatOptions,
+    ) -> Result<String, ConfigError> {
+        let mut value: serde_yaml::Value = match config_type {
+            ConfigType::Json => {
+                serde_json::from_str(content).map_err(|_| ConfigError::ParseConfigError)?
+            }
+            ConfigType::Json5 => {
+                json5::from_str(content).map_err(|_| ConfigError::ParseConfigError)?
+            }
+            ConfigType::Yaml => {
+                serde_yaml::from_str(content).map_err(|_| ConfigError::ParseConfigError)?
+            }
+            ConfigType::Toml => toml::from_str(content).map_err(|_| ConfigError::ParseConfigError)?,
+            ConfigType::Unknown => return Err(ConfigError::UnknownConfigType),
+        };
+
+        if options.sort_keys {
+            Self::sort_keys(&mut value);
+        }
+
+        let formatted = match config_type {
+            // JSON5 输出为标准 JSON，它本身就是合法的 JSON5
+            ConfigType::Json | ConfigType::Json5 => {
+                let indent = " ".repeat(options.indent);
+                let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+                let mut buf = Vec::new();
+                let mut serializer = serde_json::Serializer::with_formatter(&mut buf, formatter);
+                value
+                    .serialize(&mut serializer)
+                    .map_err(|_| ConfigError::ParseConfigError)?;
+                String::from_utf8(buf).map_err(|_| ConfigError::ParseConfigError)?
+            }
+            ConfigType::Yaml => {
+                let content =
+                    serde_yaml::to_string(&value).map_err(|_| ConfigError::ParseConfigError)?;
+                // serde_yaml 固定使用 2 空格缩进
+                Self::reindent(&content, 2, options.indent)
+            }
+            ConfigType::Toml => {
+                let content =
+                    toml::to_string_pretty(&value).map_err(|_| ConfigError::ParseConfigError)?;
+                // toml 的多行数组固定使用 4 空格缩进
+                Self::reindent(&content, 4, options.indent)
+            }
+            ConfigType::Unknown => return Err(ConfigError::UnknownConfigType),
+        };
+
+        Ok(format!("{}\n", formatted.trim_end()))
+    }
+
+    fn sort_keys(value: &mut serde_yaml::Value) {
+        match value {
+            serde_yaml::Value::Mapping(mapping) => {
+                let mut entries: Vec<(serde_yaml::Value, serde_yaml::Value)> =
+                    std::mem::take(mapping).into_iter().collect();
+                entries.sort_by_key(|(key, _)| Self::key_to_string(key));
+                for (key, mut value) in entries {
+                    Self::sort_keys(&mut value);
+                    mapping.insert(key, value);
+                }
+            }
+            serde_yaml::Value::Sequence(seq) => {
+                for item in seq.iter_mut() {
+                    Self::sort_keys(item);
+                }
+            }
+            serde_yaml::Value::Tagged(tagged) => Self::sort_keys(&mut tagged.value),
+            _ => {}
+        }
+    }
+
+    fn key_to_string(key: &serde_yaml::Value) -> String {
+        match key {
+            serde_yaml::Value::String(s) => s.clone(),
+            other => serde_yaml::to_string(other)
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default(),
+        }
+    }
+
+    // 将每行的前导空格从 from 的倍数换算为 to 的倍数
+    fn reindent(content: &str, from: usize, to: usize) -> String {
+        if from == to || from == 0 {
+            return content.to_string();
+        }
+        content
+            .lines()
+            .map(|line| {
+                let trimmed = line.trim_start_matches(' ');
+                let leading = line.len() - trimmed.len();
+                let level = leading / from;
+                let rest = leading % from;
+                format!("{}{}{}", " ".repeat(level * to), " ".repeat(rest), trimmed)
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn format(content: &str, config_type: ConfigType) -> String {
+        ConfigFormatterService::format(content, &config_type, &FormatOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn yaml_quoting_is_normalized() {
+        let content = "a: \"foo\"\nb: 'bar'\nc: \"true\"\n\"d\": '1.0'\n";
+        assert_eq!(format(content, ConfigType::Yaml), "a: foo\nb: bar\nc: 'true'\nd: '1.0'\n");
+    }
+
+    #[test]
+    fn toml_quoting_is_normalized() {
+        let content = "a = 'foo'\n'b' = \"bar\"\nc = 'say \"hi\"'\n";
+        assert_eq!(format(content, ConfigType::Toml), "a = \"foo\"\nb = \"bar\"\nc = 'say \"hi\"'\n");
+    }
+
+    #[test]
+    fn sort_keys_and_indent() {
+        let options = FormatOptions {
+            sort_keys: true,
+            indent: 4,
+        };
+        let formatted =
+            ConfigFormatterService::format(r#"{"b": {"d": 1, "c": 2}, "a": 3}"#, &ConfigType::Json, &options).unwrap();
+        assert_eq!(formatted, "{\n    \"a\": 3,\n    \"b\": {\n        \"c\": 2,\n        \"d\": 1\n    }\n}\n");
+    }
+}
@@ -0,0 +1,223 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::domain::{
+    entities::configuration::Config,
+    services::config_formatter::{ConfigFormatterService, FormatOptions},
+    value_objects::config_format::ConfigType,
+};
+
+// 键的命名风格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeyCase {
+    #[serde(rename = "snake_case")]
+    Snake,
+    #[serde(rename = "camelCase")]
+    Camel,
+    #[serde(rename = "kebab-case")]
+    Kebab,
+}
+
+impl KeyCase {
+    pub fn matches(&self, key: &str) -> bool {
+        let mut chars = key.chars();
+        let Some(first) = chars.next() else {
+            return false;
+        };
+        match self {
+            KeyCase::Snake => Self::is_delimited(key, '_'),
+            KeyCase::Kebab => Self::is_delimited(key, '-'),
+            KeyCase::Camel => first.is_ascii_lowercase() && chars.all(|c| c.is_ascii_alphanumeric()),
+        }
+    }
+
+    // 小写字母和数字组成的若干段，以单个分隔符连接
+    fn is_delimited(key: &str, delimiter: char) -> bool {
+        key.split(delimiter).all(|segment| {
+            !segment.is_empty()
+                && segment.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+        })
+    }
+}
+
+impl std::fmt::Display for KeyCase {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            KeyCase::Snake => write!(f, "snake_case"),
+            KeyCase::Camel => write!(f, "camelCase"),
+            KeyCase::Kebab => write!(f, "kebab-case"),
+        }
+    }
+}
+
+// 启用的 lint 规则，未设置的规则不检查
+#[derive(Debug, Clone, Default)]
+pub struct LintRules {
+    pub format: Option<FormatOptions>,
+    pub key_case: Option<KeyCase>,
+    pub max_depth: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LintFinding {
+    pub rule: &'static str,
+    pub key: Option<String>,
+    pub message: String,
+}
+
+// 与语义无关的风格检查：格式是否规范、键的命名风格和嵌套深度
+pub struct ConfigLintService;
+
+impl ConfigLintService {
+    pub fn lint(content: &str, config: &Config, rules: &LintRules) -> Vec<LintFinding> {
+        let mut findings = Vec::new();
+        // JSON5 会被 format 改写为标准 JSON，不做格式检查
+        if let Some(options) = &rules.format
+            && config.config_type != ConfigType::Json5
+            && let Ok(formatted) = ConfigFormatterService::format(content, &config.config_type, options)
+            && formatted.trim_end() != content.trim_end()
+        {
+            findings.push(LintFinding {
+                rule: "format",
+                key: None,
+                message: "file is not formatted, run `config-manager format`".to_string(),
+            });
+        }
+        if rules.key_case.is_some() || rules.max_depth.is_some() {
+            Self::lint_keys(&config.to_serde_value(), "", 0, rules, &mut findings);
+        }
+        findings
+    }
+
+    fn lint_keys(
+        value: &Value,
+        path: &str,
+        depth: usize,
+        rules: &LintRules,
+        findings: &mut Vec<LintFinding>,
+    ) {
+        match value {
+            Value::Object(object) => {
+                if let Some(max_depth) = rules.max_depth
+                    && depth >= max_depth
+                    && !object.is_empty()
+                {
+                    findings.push(LintFinding {
+                        rule: "max-depth",
+                        key: Some(path.to_string()),
+                        message: format!("{} nests deeper than {} levels", path, max_depth),
+                    });
+                    return;
+                }
+                for (key, child) in object {
+                    let child_path = if path.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}.{}", path, key)
+                    };
+                    if let Some(key_case) = rules.key_case
+                        && !key_case.matches(key)
+                    {
+                        findings.push(LintFinding {
+                            rule: "key-case",
+                            key: Some(child_path.clone()),
+                            message: format!("key {} is not {}", child_path, key_case),
+                        });
+                    }
+                    Self::lint_keys(child, &child_path, depth + 1, rules, findings);
+                }
+            }
+            Value::Array(items) => {
+                for (index, item) in items.iter().enumerate() {
+                    let item_path = format!("{}[{}]", path, index);
+                    Self::lint_keys(item, &item_path, depth, rules, findings);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::services::format_converter::FormatConverterService;
+    use crate::domain::value_objects::config_path::ConfigPath;
+
+    fn lint(path: &str, content: &str, rules: &LintRules) -> Vec<LintFinding> {
+        let config = FormatConverterService::new(ConfigPath::new(path.to_string()).unwrap(), content.to_string())
+            .validate_config()
+            .unwrap();
+        ConfigLintService::lint(content, &config, rules)
+    }
+
+    fn rules(findings: &[LintFinding]) -> Vec<(&str, Option<&str>)> {
+        findings.iter().map(|f| (f.rule, f.key.as_deref())).collect()
+    }
+
+    #[test]
+    fn key_case_styles() {
+        assert!(KeyCase::Snake.matches("max_pool_size2"));
+        assert!(!KeyCase::Snake.matches("max__pool"));
+        assert!(!KeyCase::Snake.matches("maxPool"));
+        assert!(KeyCase::Kebab.matches("max-pool-size"));
+        assert!(!KeyCase::Kebab.matches("max_pool"));
+        assert!(KeyCase::Camel.matches("maxPoolSize"));
+        assert!(!KeyCase::Camel.matches("MaxPool"));
+        assert!(!KeyCase::Camel.matches("max_pool"));
+        assert!(!KeyCase::Snake.matches(""));
+    }
+
+    #[test]
+    fn format_rule() {
+        let rules = LintRules {
+            format: Some(FormatOptions::default()),
+            ..Default::default()
+        };
+        let compact = r#"{"port":8080,"host":"localhost"}"#;
+        assert_eq!(self::rules(&lint("app.json", compact, &rules)), vec![("format", None)]);
+
+        let formatted =
+            ConfigFormatterService::format(compact, &ConfigType::Json, &FormatOptions::default()).unwrap();
+        assert!(lint("app.json", &formatted, &rules).is_empty());
+    }
+
+    #[test]
+    fn key_case_rule() {
+        let rules = LintRules {
+            key_case: Some(KeyCase::Snake),
+            ..Default::default()
+        };
+        let content = r#"{"database": {"maxPool": 10, "pool_size": 5}, "items": [{"badKey": 1}]}"#;
+        let findings = lint("app.json", content, &rules);
+        assert_eq!(
+            self::rules(&findings),
+            vec![("key-case", Some("database.maxPool")), ("key-case", Some("items[0].badKey"))]
+        );
+        assert_eq!(findings[0].message, "key database.maxPool is not snake_case");
+
+        let content = r#"{"database": {"max_pool": 10}}"#;
+        assert!(lint("app.json", content, &rules).is_empty());
+    }
+
+    #[test]
+    fn max_depth_rule() {
+        let rules = LintRules {
+            max_depth: Some(2),
+            ..Default::default()
+        };
+        let content = r#"{"a": {"b": {"c": 1}, "empty": {}}}"#;
+        let findings = lint("app.json", content, &rules);
+        assert_eq!(self::rules(&findings), vec![("max-depth", Some("a.b"))]);
+        assert_eq!(findings[0].message, "a.b nests deeper than 2 levels");
+
+        let content = r#"{"a": {"b": 1}}"#;
+        assert!(lint("app.json", content, &rules).is_empty());
+    }
+
+    #[test]
+    fn disabled_rules_report_nothing() {
+        let content = r#"{"Bad":{"Deep":{"Deeper":1}}}"#;
+        assert!(lint("app.json", content, &LintRules::default()).is_empty());
+    }
+}
@@ -0,0 +1,25 @@
+use std::collections::HashMap;
+
+use crate::domain::entities::configuration::{Config, ConfigValue};
+
+pub struct ConfigMergerService;
+
+impl ConfigMergerService {
+    // 深度合并：对象逐键合并，其余值（包括数组）由 overlay 整体覆盖
+    pub fn merge(base: &mut Config, overlay: &Config) {
+        Self::merge_object(&mut base.config, &overlay.config);
+    }
+
+    fn merge_object(base: &mut HashMap<String, ConfigValue>, overlay: &HashMap<String, ConfigValue>) {
+        for (key, value) in overlay {
+            match (base.get_mut(key), value) {
+                (Some(ConfigValue::Object(base_child)), ConfigValue::Object(overlay_child)) => {
+                    Self::merge_object(base_child, overlay_child)
+                }
+                _ => {
+                    base.insert(key.clone(), value.clone());
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,447 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    domain::entities::configuration::{Config, ConfigValue},
+    shared::error::ConfigError,
+};
+
+// RFC 6902 JSON Patch 中的单个操作，路径为 JSON Pointer（RFC 6901）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add { path: String, value: Value },
+    Remove { path: String },
+    Replace { path: String, value: Value },
+    Move { from: String, path: String },
+    Copy { from: String, path: String },
+    Test { path: String, value: Value },
+}
+
+// 按 Content-Type 区分的两种补丁
+#[derive(Debug, Clone)]
+pub enum ConfigPatch {
+    Json(Vec<PatchOperation>),
+    Merge(Value),
+}
+
+impl ConfigPatch {
+    pub const JSON_PATCH: &str = "application/json-patch+json";
+    pub const MERGE_PATCH: &str = "application/merge-patch+json";
+
+    // content_type 不是两种补丁类型时返回 None
+    pub fn parse(content_type: &str, body: &str) -> Option<Result<Self, ConfigError>> {
+        let invalid = |e: serde_json::Error| ConfigError::InvalidPatch(e.to_string());
+        match content_type {
+            Self::JSON_PATCH => Some(
+                serde_json::from_str(body)
+                    .map(ConfigPatch::Json)
+                    .map_err(invalid),
+            ),
+            Self::MERGE_PATCH => Some(
+                serde_json::from_str(body)
+                    .map(ConfigPatch::Merge)
+                    .map_err(invalid),
+            ),
+            _ => None,
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            ConfigPatch::Json(operations) => {
+                format!("json patch with {} operations", operations.len())
+            }
+            ConfigPatch::Merge(_) => "merge patch".to_string(),
+        }
+    }
+}
+
+// 在配置副本上应用补丁，全部操作成功才返回新配置，原配置不受影响
+pub struct ConfigPatchService;
+
+impl ConfigPatchService {
+    pub fn apply(config: &Config, patch: &ConfigPatch) -> Result<Config, ConfigError> {
+        match patch {
+            ConfigPatch::Json(operations) => Self::apply_json_patch(config, operations),
+            ConfigPatch::Merge(patch) => Self::apply_merge_patch(config, patch),
+        }
+    }
+
+    pub fn apply_json_patch(
+        config: &Config,
+        operations: &[PatchOperation],
+    ) -> Result<Config, ConfigError> {
+        let mut root = ConfigValue::Object(config.config.clone());
+        for (index, operation) in operations.iter().enumerate() {
+            Self::apply_operation(&mut root, operation).map_err(|e| match e {
+                ConfigError::PatchConflict(reason) => {
+                    ConfigError::PatchConflict(format!("operation {}: {}", index, reason))
+                }
+                e => e,
+            })?;
+        }
+        Self::with_root(config, root)
+    }
+
+    // RFC 7386：对象逐键合并，null 表示删除，其余值整体替换
+    pub fn apply_merge_patch(config: &Config, patch: &Value) -> Result<Config, ConfigError> {
+        if !patch.is_object() {
+            return Err(ConfigError::InvalidPatch(
+                "merge patch must be a JSON object".to_string(),
+            ));
+        }
+        let mut root = ConfigValue::Object(config.config.clone());
+        Self::merge(&mut root, patch)?;
+        Self::with_root(config, root)
+    }
+
+    fn with_root(config: &Config, root: ConfigValue) -> Result<Config, ConfigError> {
+        let ConfigValue::Object(object) = root else {
+            return Err(ConfigError::PatchConflict(
+                "document root must stay an object".to_string(),
+            ));
+        };
+        Ok(Config {
+            config: object,
+            ..config.clone()
+        })
+    }
+
+    fn merge(target: &mut ConfigValue, patch: &Value) -> Result<(), ConfigError> {
+        let Value::Object(patch) = patch else {
+            *target = ConfigValue::from_serde_json(patch.clone())?;
+            return Ok(());
+        };
+        if !matches!(target, ConfigValue::Object(_)) {
+            *target = ConfigValue::Object(HashMap::new());
+        }
+        let ConfigValue::Object(object) = target else {
+            unreachable!()
+        };
+        for (key, value) in patch {
+            if value.is_null() {
+                object.remove(key);
+            } else {
+                Self::merge(
+                    object.entry(key.clone()).or_insert(ConfigValue::Null),
+                    value,
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_operation(
+        root: &mut ConfigValue,
+        operation: &PatchOperation,
+    ) -> Result<(), ConfigError> {
+        match operation {
+            PatchOperation::Add { path, value } => Self::add(
+                root,
+                &Self::parse_pointer(path)?,
+                ConfigValue::from_serde_json(value.clone())?,
+            ),
+            PatchOperation::Remove { path } => {
+                Self::remove(root, &Self::parse_pointer(path)?).map(|_| ())
+            }
+            PatchOperation::Replace { path, value } => {
+                let tokens = Self::parse_pointer(path)?;
+                let value = ConfigValue::from_serde_json(value.clone())?;
+                match Self::get_mut(root, &tokens) {
+                    Some(target) => {
+                        *target = value;
+                        Ok(())
+                    }
+                    None => Err(ConfigError::PatchConflict(format!(
+                        "path {} does not exist",
+                        path
+                    ))),
+                }
+            }
+            PatchOperation::Move { from, path } => {
+                let from_tokens = Self::parse_pointer(from)?;
+                let tokens = Self::parse_pointer(path)?;
+                if tokens.len() > from_tokens.len() && tokens.starts_with(&from_tokens) {
+                    return Err(ConfigError::PatchConflict(format!(
+                        "cannot move {} into its own child {}",
+                        from, path
+                    )));
+                }
+                let value = Self::remove(root, &from_tokens)?;
+                Self::add(root, &tokens, value)
+            }
+            PatchOperation::Copy { from, path } => {
+                let value = Self::get(root, &Self::parse_pointer(from)?)
+                    .cloned()
+                    .ok_or_else(|| {
+                        ConfigError::PatchConflict(format!("path {} does not exist", from))
+                    })?;
+                Self::add(root, &Self::parse_pointer(path)?, value)
+            }
+            PatchOperation::Test { path, value } => {
+                let actual = Self::get(root, &Self::parse_pointer(path)?)
+                    .map(ConfigValue::to_serde_value)
+                    .ok_or_else(|| {
+                        ConfigError::PatchConflict(format!("path {} does not exist", path))
+                    })?;
+                if &actual == value {
+                    Ok(())
+                } else {
+                    Err(ConfigError::PatchConflict(format!(
+                        "test failed: {} is {}, expected {}",
+                        path, actual, value
+                    )))
+                }
+            }
+        }
+    }
+
+    // 生成把 old 变为 new 的 JSON Patch：对象逐键比较，数组和类型不同的值整体替换
+    pub fn diff(old: &Value, new: &Value) -> Vec<PatchOperation> {
+        let mut operations = Vec::new();
+        Self::diff_value("", old, new, &mut operations);
+        operations
+    }
+
+    fn diff_value(path: &str, old: &Value, new: &Value, operations: &mut Vec<PatchOperation>) {
+        if old == new {
+            return;
+        }
+        let (Value::Object(old), Value::Object(new)) = (old, new) else {
+            operations.push(PatchOperation::Replace {
+                path: path.to_string(),
+                value: new.clone(),
+            });
+            return;
+        };
+        for (key, old_value) in old {
+            let child = format!("{}/{}", path, Self::escape_token(key));
+            match new.get(key) {
+                Some(new_value) => Self::diff_value(&child, old_value, new_value, operations),
+                None => operations.push(PatchOperation::Remove { path: child }),
+            }
+        }
+        for (key, new_value) in new {
+            if !old.contains_key(key) {
+                operations.push(PatchOperation::Add {
+                    path: format!("{}/{}", path, Self::escape_token(key)),
+                    value: new_value.clone(),
+                });
+            }
+        }
+    }
+
+    fn escape_token(key: &str) -> String {
+        key.replace('~', "~0").replace('/', "~1")
+    }
+
+    // "" 指向整个文档，其余必须以 / 开头；~1 表示 /，~0 表示 ~
+    fn parse_pointer(pointer: &str) -> Result<Vec<String>, ConfigError> {
+        if pointer.is_empty() {
+            return Ok(Vec::new());
+        }
+        let Some(rest) = pointer.strip_prefix('/') else {
+            return Err(ConfigError::InvalidPatch(format!(
+                "invalid JSON pointer {:?}",
+                pointer
+            )));
+        };
+        Ok(rest
+            .split('/')
+            .map(|token| token.replace("~1", "/").replace("~0", "~"))
+            .collect())
+    }
+
+    // 数组下标不允许前导零；"-" 只在 add 中表示追加
+    fn parse_index(token: &str, len: usize) -> Option<usize> {
+        if token.is_empty() || (token.len() > 1 && token.starts_with('0')) {
+            return None;
+        }
+        token.parse().ok().filter(|index| *index < len)
+    }
+
+    fn get<'a>(root: &'a ConfigValue, tokens: &[String]) -> Option<&'a ConfigValue> {
+        tokens
+            .iter()
+            .try_fold(root, |current, token| match current {
+                ConfigValue::Object(object) => object.get(token),
+                ConfigValue::Array(items) => items.get(Self::parse_index(token, items.len())?),
+                _ => None,
+            })
+    }
+
+    fn get_mut<'a>(root: &'a mut ConfigValue, tokens: &[String]) -> Option<&'a mut ConfigValue> {
+        tokens
+            .iter()
+            .try_fold(root, |current, token| match current {
+                ConfigValue::Object(object) => object.get_mut(token),
+                ConfigValue::Array(items) => {
+                    let index = Self::parse_index(token, items.len())?;
+                    items.get_mut(index)
+                }
+                _ => None,
+            })
+    }
+
+    fn add(
+        root: &mut ConfigValue,
+        tokens: &[String],
+        value: ConfigValue,
+    ) -> Result<(), ConfigError> {
+        let Some((last, parents)) = tokens.split_last() else {
+            *root = value;
+            return Ok(());
+        };
+        let parent = Self::get_mut(root, parents).ok_or_else(|| {
+            ConfigError::PatchConflict(format!("parent of /{} does not exist", tokens.join("/")))
+        })?;
+        match parent {
+            ConfigValue::Object(object) => {
+                object.insert(last.clone(), value);
+                Ok(())
+            }
+            ConfigValue::Array(items) if last == "-" => {
+                items.push(value);
+                Ok(())
+            }
+            ConfigValue::Array(items) => match Self::parse_index(last, items.len() + 1) {
+                Some(index) => {
+                    items.insert(index, value);
+                    Ok(())
+                }
+                None => Err(ConfigError::PatchConflict(format!(
+                    "array index {} is out of range",
+                    last
+                ))),
+            },
+            other => Err(ConfigError::PatchConflict(format!(
+                "cannot add {} to {}",
+                last,
+                other.type_name()
+            ))),
+        }
+    }
+
+    fn remove(root: &mut ConfigValue, tokens: &[String]) -> Result<ConfigValue, ConfigError> {
+        let missing =
+            || ConfigError::PatchConflict(format!("path /{} does not exist", tokens.join("/")));
+        let (last, parents) = tokens.split_last().ok_or_else(|| {
+            ConfigError::PatchConflict("cannot remove the document root".to_string())
+        })?;
+        match Self::get_mut(root, parents).ok_or_else(missing)? {
+            ConfigValue::Object(object) => object.remove(last).ok_or_else(missing),
+            ConfigValue::Array(items) => {
+                let index = Self::parse_index(last, items.len()).ok_or_else(missing)?;
+                Ok(items.remove(index))
+            }
+            _ => Err(missing()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn config(value: Value) -> Config {
+        let mut config = Config::new();
+        for (key, value) in value.as_object().unwrap() {
+            config.set(key, ConfigValue::from_serde_json(value.clone()).unwrap()).unwrap();
+        }
+        config
+    }
+
+    fn json_patch(config: &Config, operations: Value) -> Result<Value, ConfigError> {
+        let operations: Vec<PatchOperation> = serde_json::from_value(operations).unwrap();
+        ConfigPatchService::apply_json_patch(config, &operations).map(|c| c.to_serde_value())
+    }
+
+    #[test]
+    fn failing_test_operation_rejects_the_patch() {
+        let original = config(json!({"version": 1, "debug": false}));
+        let operations = json!([
+            {"op": "test", "path": "/version", "value": 1},
+            {"op": "replace", "path": "/debug", "value": true},
+        ]);
+        assert_eq!(json_patch(&original, operations).unwrap()["debug"], true);
+
+        let operations = json!([
+            {"op": "replace", "path": "/debug", "value": true},
+            {"op": "test", "path": "/version", "value": 2},
+        ]);
+        let Err(ConfigError::PatchConflict(reason)) = json_patch(&original, operations) else {
+            panic!("test operation should fail");
+        };
+        assert_eq!(reason, "operation 1: test failed: /version is 1, expected 2");
+        let operations = json!([{"op": "test", "path": "/missing", "value": 1}]);
+        assert!(matches!(json_patch(&original, operations), Err(ConfigError::PatchConflict(_))));
+    }
+
+    #[test]
+    fn move_and_copy_to_nested_paths() {
+        let original = config(json!({"db": {"host": "db", "port": 5432}, "cache": {}}));
+        let operations = json!([
+            {"op": "copy", "from": "/db/host", "path": "/cache/host"},
+            {"op": "move", "from": "/db/port", "path": "/cache/port"},
+        ]);
+        assert_eq!(
+            json_patch(&original, operations).unwrap(),
+            json!({"db": {"host": "db"}, "cache": {"host": "db", "port": 5432}})
+        );
+
+        // 目标的父节点必须存在，也不能移动到自己的子节点下
+        let operations = json!([{"op": "copy", "from": "/db", "path": "/a/b"}]);
+        assert!(matches!(json_patch(&original, operations), Err(ConfigError::PatchConflict(_))));
+        let operations = json!([{"op": "move", "from": "/db", "path": "/db/inner"}]);
+        assert!(matches!(json_patch(&original, operations), Err(ConfigError::PatchConflict(_))));
+    }
+
+    #[test]
+    fn array_indexes_are_checked_and_dash_appends() {
+        let original = config(json!({"hosts": ["a", "b"]}));
+        let operations = json!([
+            {"op": "add", "path": "/hosts/-", "value": "d"},
+            {"op": "add", "path": "/hosts/2", "value": "c"},
+            {"op": "remove", "path": "/hosts/0"},
+        ]);
+        assert_eq!(json_patch(&original, operations).unwrap(), json!({"hosts": ["b", "c", "d"]}));
+
+        for operations in [
+            json!([{"op": "add", "path": "/hosts/3", "value": "x"}]),
+            json!([{"op": "add", "path": "/hosts/01", "value": "x"}]),
+            json!([{"op": "replace", "path": "/hosts/2", "value": "x"}]),
+            json!([{"op": "remove", "path": "/hosts/-"}]),
+        ] {
+            let result = json_patch(&original, operations.clone());
+            assert!(matches!(result, Err(ConfigError::PatchConflict(_))), "{}", operations);
+        }
+    }
+
+    #[test]
+    fn merge_patch_null_deletes_keys() {
+        let original = config(json!({"debug": true, "db": {"host": "db", "port": 5432}}));
+        let patch = json!({"debug": null, "db": {"port": null, "user": "app"}, "missing": null});
+        let patched = ConfigPatchService::apply_merge_patch(&original, &patch).unwrap();
+        assert_eq!(patched.to_serde_value(), json!({"db": {"host": "db", "user": "app"}}));
+
+        let result = ConfigPatchService::apply_merge_patch(&original, &json!(["debug"]));
+        assert!(matches!(result, Err(ConfigError::InvalidPatch(_))));
+    }
+
+    #[test]
+    fn failed_patch_leaves_the_original_unchanged() {
+        let original = config(json!({"debug": false, "hosts": ["a"]}));
+        let operations = json!([
+            {"op": "replace", "path": "/debug", "value": true},
+            {"op": "add", "path": "/hosts/-", "value": "b"},
+            {"op": "remove", "path": "/missing"},
+        ]);
+        assert!(json_patch(&original, operations).is_err());
+        assert_eq!(original.to_serde_value(), json!({"debug": false, "hosts": ["a"]}));
+    }
+}
@@ -0,0 +1,153 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::domain::{
+    entities::configuration::{Config, ConfigValue},
+    value_objects::config_format::ConfigType,
+};
+
+const TOP_N: usize = 5;
+
+#[derive(Debug, Default)]
+pub struct ConfigStats {
+    pub total_keys: usize,
+    pub keys_per_depth: BTreeMap<usize, usize>,
+    pub type_counts: BTreeMap<&'static str, usize>,
+    pub largest_arrays: Vec<(String, usize)>,
+    pub largest_strings: Vec<(String, usize)>,
+    pub duplicate_values: Vec<(String, Vec<String>)>,
+    pub serialized_sizes: Vec<(ConfigType, Option<usize>)>,
+}
+
+pub struct ConfigStatsService;
+
+impl ConfigStatsService {
+    pub fn analyze(config: &Config) -> ConfigStats {
+        let mut stats = ConfigStats::default();
+        let mut arrays = Vec::new();
+        let mut strings = Vec::new();
+        let mut values: HashMap<String, Vec<String>> = HashMap::new();
+
+        let mut stack: Vec<(String, &ConfigValue, usize)> = config
+            .config
+            .iter()
+            .map(|(k, v)| (k.clone(), v, 1))
+            .collect();
+        while let Some((path, value, depth)) = stack.pop() {
+            *stats.type_counts.entry(value.type_name()).or_default() += 1;
+            match value {
+                ConfigValue::Object(obj) => {
+                    for (k, v) in obj {
+                        stack.push((format!("{}.{}", path, k), v, depth + 1));
+                    }
+                }
+                ConfigValue::Array(arr) => {
+                    arrays.push((path.clone(), arr.len()));
+                    for (i, v) in arr.iter().enumerate() {
+                        stack.push((format!("{}[{}]", path, i), v, depth + 1));
+                    }
+                }
+                ConfigValue::String(s) => {
+                    strings.push((path.clone(), s.len()));
+                    if !s.is_empty() {
+                        values.entry(value.summary()).or_default().push(path.clone());
+                    }
+                }
+                ConfigValue::Number(_) => {
+                    values.entry(value.summary()).or_default().push(path.clone());
+                }
+                ConfigValue::Boolean(_) | ConfigValue::Null => {}
+            }
+            // 数组元素不计入键数量
+            if !path.ends_with(']') {
+                stats.total_keys += 1;
+                *stats.keys_per_depth.entry(depth).or_default() += 1;
+            }
+        }
+
+        stats.largest_arrays = Self::top(arrays);
+        stats.largest_strings = Self::top(strings);
+
+        // 布尔值和 null 重复没有意义，只统计字符串和数字
+        let mut duplicates: Vec<(String, Vec<String>)> = values
+            .into_iter()
+            .filter(|(_, paths)| paths.len() > 1)
+            .map(|(value, mut paths)| {
+                paths.sort();
+                (value, paths)
+            })
+            .collect();
+        duplicates.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+        duplicates.truncate(TOP_N);
+        stats.duplicate_values = duplicates;
+
+        stats.serialized_sizes = [ConfigType::Json, ConfigType::Yaml, ConfigType::Toml]
+            .into_iter()
+            .map(|format| {
+                let size = config.serialize_to(&format).ok().map(|s| s.len());
+                (format, size)
+            })
+            .collect();
+        stats
+    }
+
+    fn top(mut items: Vec<(String, usize)>) -> Vec<(String, usize)> {
+        items.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        items.truncate(TOP_N);
+        items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(value: serde_json::Value) -> Config {
+        let mut config = Config::new();
+        config.config = ConfigValue::from_serde_json(value).unwrap().into_object().unwrap();
+        config
+    }
+
+    #[test]
+    fn counts_keys_depth_and_types() {
+        let stats = ConfigStatsService::analyze(&config(serde_json::json!({
+            "debug": false,
+            "name": "api",
+            "database": {"host": "db", "port": 5432, "replica": {"port": 5432}},
+            "hosts": ["a", "bb", "ccc"],
+            "extra": null,
+        })));
+        // 数组元素计入类型统计，不计入键数量
+        assert_eq!(stats.total_keys, 9);
+        assert_eq!(stats.keys_per_depth, BTreeMap::from([(1, 5), (2, 3), (3, 1)]));
+        assert_eq!(
+            stats.type_counts,
+            BTreeMap::from([
+                ("Array", 1),
+                ("Boolean", 1),
+                ("Null", 1),
+                ("Number", 2),
+                ("Object", 2),
+                ("String", 5),
+            ])
+        );
+        assert_eq!(stats.largest_arrays, vec![("hosts".to_string(), 3)]);
+        assert_eq!(stats.largest_strings[0], ("hosts[2]".to_string(), 3));
+        assert_eq!(
+            stats.duplicate_values,
+            vec![(
+                "5432 (Number)".to_string(),
+                vec!["database.port".to_string(), "database.replica.port".to_string()]
+            )]
+        );
+    }
+
+    #[test]
+    fn empty_config_has_no_keys() {
+        let stats = ConfigStatsService::analyze(&Config::new());
+        assert_eq!(stats.total_keys, 0);
+        assert!(stats.keys_per_depth.is_empty());
+        assert!(stats.type_counts.is_empty());
+        assert!(stats.duplicate_values.is_empty());
+        assert_eq!(stats.serialized_sizes.len(), 3);
+    }
+}
@@ -1,4 +1,14 @@
-use crate::domain::entities::{configuration::Config, validation_rule::{Validation, ValidationConfig, ValidationResult}};
+use std::collections::HashMap;
+
+use regex::Regex;
+
+use crate::{
+    domain::entities::{
+        configuration::Config,
+        validation_rule::{FieldType, Validation, ValidationConfig, ValidationResult},
+    },
+    shared::error::ConfigError,
+};
 
 pub struct ConfigValidationService;
 
@@ -10,5 +20,45 @@ impl ConfigValidationService {
         let validation_config = ValidationConfig::new(validation, config);
         validation_config.validate()
     }
-}
 
+    // 预编译所有字符串字段的正则约束，校验多个文件时复用
+    pub fn compile_patterns(
+        field_types: &HashMap<String, FieldType>,
+    ) -> Result<HashMap<String, Regex>, ConfigError> {
+        let mut patterns = HashMap::new();
+        for (field, field_type) in field_types {
+            Self::compile_pattern(field, field_type, &mut patterns)?;
+        }
+        Ok(patterns)
+    }
+
+    // 数组元素的正则以 "field[]" 为键，对象子字段以 "field.sub" 为键
+    fn compile_pattern(
+        key: &str,
+        field_type: &FieldType,
+        patterns: &mut HashMap<String, Regex>,
+    ) -> Result<(), ConfigError> {
+        match field_type {
+            FieldType::String {
+                pattern: Some(pattern),
+                ..
+            } => {
+                let regex = Regex::new(pattern).map_err(|e| ConfigError::InvalidRegexPattern {
+                    field: key.to_string(),
+                    error: e.to_string(),
+                })?;
+                patterns.insert(key.to_string(), regex);
+            }
+            FieldType::Array { item_type, .. } => {
+                Self::compile_pattern(&format!("{}[]", key), item_type, patterns)?;
+            }
+            FieldType::Object { fields, .. } => {
+                for (name, child_type) in fields {
+                    Self::compile_pattern(&format!("{}.{}", key, name), child_type, patterns)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+use crate::{domain::value_objects::config_format::ConfigType, shared::error::ConfigError};
+
+// 宿主文件（Markdown、脚本等）中嵌入的配置块描述，例如：
+//   ---yaml   front matter，以 "---" 或 "---yaml" 开始、"---" 结束
+//   +++toml   Hugo 风格的 TOML front matter
+//   ```json   Markdown 代码块，必须带语言标记，以 "```" 结束
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedSpec {
+    pub delimiter: String,
+    pub config_type: ConfigType,
+}
+
+// 配置块在宿主文件中的位置（字节偏移，不含分隔行）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbeddedBlock {
+    pub start: usize,
+    pub end: usize,
+    pub content: String,
+}
+
+impl EmbeddedSpec {
+    pub fn parse(spec: &str) -> Result<Self, ConfigError> {
+        let spec = spec.trim();
+        let split = spec
+            .find(|c: char| c.is_ascii_alphanumeric())
+            .ok_or_else(|| ConfigError::InvalidEmbeddedSpec(spec.to_string()))?;
+        let (delimiter, format) = spec.split_at(split);
+        let config_type = match format.to_lowercase().as_str() {
+            "yml" => ConfigType::Yaml,
+            format => ConfigType::from(format),
+        };
+        if delimiter.is_empty() || config_type == ConfigType::Unknown {
+            return Err(ConfigError::InvalidEmbeddedSpec(spec.to_string()));
+        }
+        Ok(Self {
+            delimiter: delimiter.to_string(),
+            config_type,
+        })
+    }
+
+    fn is_code_fence(&self) -> bool {
+        self.delimiter.starts_with("```") || self.delimiter.starts_with("~~~")
+    }
+
+    fn is_opening(&self, line: &str, tag: &str) -> bool {
+        let line = line.trim();
+        match line.strip_prefix(self.delimiter.as_str()) {
+            Some(rest) if rest.trim().eq_ignore_ascii_case(tag) => true,
+            // front matter 允许不带格式标记的分隔行
+            Some("") => !self.is_code_fence(),
+            _ => false,
+        }
+    }
+
+    fn tag(&self) -> &'static str {
+        match self.config_type {
+            ConfigType::Yaml => "yaml",
+            ConfigType::Json => "json",
+            ConfigType::Json5 => "json5",
+            ConfigType::Toml => "toml",
+            ConfigType::Unknown => "",
+        }
+    }
+}
+
+pub struct EmbeddedConfigService;
+
+impl EmbeddedConfigService {
+    // 找到宿主文件中第一个匹配的配置块
+    pub fn extract(host: &str, spec: &EmbeddedSpec) -> Result<EmbeddedBlock, ConfigError> {
+        let mut offset = 0;
+        let mut start = None;
+        for line in host.split_inclusive('\n') {
+            let line_end = offset + line.len();
+            match start {
+                None => {
+                    let matched = spec.is_opening(line, spec.tag())
+                        || (spec.config_type == ConfigType::Yaml && spec.is_opening(line, "yml"));
+                    if matched {
+                        start = Some(line_end);
+                    }
+                }
+                Some(start) => {
+                    if line.trim() == spec.delimiter {
+                        return Ok(EmbeddedBlock {
+                            start,
+                            end: offset,
+                            content: host[start..offset].to_string(),
+                        });
+                    }
+                }
+            }
+            offset = line_end;
+        }
+        Err(ConfigError::EmbeddedBlockNotFound(format!(
+            "{}{}",
+            spec.delimiter,
+            spec.tag()
+        )))
+    }
+
+    // 只替换配置块内容，分隔行和块外的内容保持原样
+    pub fn replace(host: &str, block: &EmbeddedBlock, content: &str) -> String {
+        let mut result = String::with_capacity(host.len() + content.len());
+        result.push_str(&host[..block.start]);
+        result.push_str(content);
+        if !content.is_empty() && !content.ends_with('\n') {
+            result.push('\n');
+        }
+        result.push_str(&host[block.end..]);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::configuration::Config;
+
+    fn front_matter() -> EmbeddedSpec {
+        EmbeddedSpec::parse("---yaml").unwrap()
+    }
+
+    #[test]
+    fn extracts_front_matter_and_keeps_body_on_replace() {
+        let host = "---\ntitle: Hello\n---\n# Heading\n\nSome *markdown* body: not yaml [\n";
+        let block = EmbeddedConfigService::extract(host, &front_matter()).unwrap();
+        assert_eq!(block.content, "title: Hello\n");
+
+        let config = Config::from("post.md".to_string(), block.content.clone(), ConfigType::Yaml).unwrap();
+        assert!(config.get("title").is_some());
+
+        let replaced = EmbeddedConfigService::replace(host, &block, "title: Bye");
+        assert_eq!(replaced, "---\ntitle: Bye\n---\n# Heading\n\nSome *markdown* body: not yaml [\n");
+    }
+
+    #[test]
+    fn missing_closing_delimiter_is_not_found() {
+        let host = "---yaml\ntitle: Hello\n# Heading\n";
+        assert!(matches!(
+            EmbeddedConfigService::extract(host, &front_matter()),
+            Err(ConfigError::EmbeddedBlockNotFound(block)) if block == "---yaml"
+        ));
+    }
+
+    #[test]
+    fn empty_front_matter_is_an_empty_config() {
+        let host = "---\n---\nbody\n";
+        let block = EmbeddedConfigService::extract(host, &front_matter()).unwrap();
+        assert_eq!((block.start, block.end), (4, 4));
+        assert_eq!(block.content, "");
+        let config = Config::from("post.md".to_string(), block.content.clone(), ConfigType::Yaml).unwrap();
+        assert!(config.config.is_empty());
+        assert_eq!(EmbeddedConfigService::replace(host, &block, "a: 1"), "---\na: 1\n---\nbody\n");
+    }
+
+    #[test]
+    fn non_yaml_front_matter_fails_to_parse() {
+        let host = "---\n: : [not yaml\n---\nbody\n";
+        let block = EmbeddedConfigService::extract(host, &front_matter()).unwrap();
+        assert!(matches!(
+            Config::from("post.md".to_string(), block.content, ConfigType::Yaml),
+            Err(ConfigError::ParseConfigError)
+        ));
+    }
+
+    #[test]
+    fn code_fence_requires_language_tag() {
+        let spec = EmbeddedSpec::parse("```json").unwrap();
+        let host = "```\nnot config\n```\n```json\n{\"a\": 1}\n```\n";
+        assert_eq!(EmbeddedConfigService::extract(host, &spec).unwrap().content, "{\"a\": 1}\n");
+        assert!(matches!(EmbeddedSpec::parse("---ini"), Err(ConfigError::InvalidEmbeddedSpec(_))));
+    }
+}
@@ -11,18 +11,30 @@ impl EnvOverrideService {
     }
 
     pub fn get_env_override_config(config: &mut Config) -> Result<Config, ConfigError> {
-        let envs = Self::get_envs();
+        Self::apply_overrides(config, Self::get_envs())?;
+        Ok(config.clone())
+    }
+
+    // 用给定的变量（已去掉 APP_ 前缀）覆盖配置，不读取进程环境
+    pub fn apply_overrides(
+        config: &mut Config,
+        envs: HashMap<String, String>,
+    ) -> Result<(), ConfigError> {
         for (key, value) in envs {
             // 将环境变量键转换为路径 (例如: DATABASE_HOST -> database.host)
             let path = Self::env_key_to_path(&key)?;
             let config_value = ConfigValue::from_string(value);
             Self::set_by_path_recursive(&mut config.config, &path, config_value)?;
         }
-        Ok(config.clone())
+        Ok(())
     }
 
     pub fn get_envs() -> HashMap<String, String> {
-        std::env::vars()
+        Self::filter_envs(std::env::vars())
+    }
+
+    pub fn filter_envs(vars: impl IntoIterator<Item = (String, String)>) -> HashMap<String, String> {
+        vars.into_iter()
             .filter(|(key, _)| key.starts_with("APP_"))
             .map(|(key, value)| (key.to_string().replace("APP_", ""), value.to_string()))
             .collect::<HashMap<String, String>>()
@@ -0,0 +1,163 @@
+use std::collections::HashMap;
+
+use rand::{Rng, SeedableRng, rngs::StdRng, seq::SliceRandom};
+use serde_json::Number;
+
+use crate::domain::{
+    entities::configuration::{Config, ConfigValue},
+    value_objects::{config_format::ConfigType, config_path::ConfigPath},
+};
+
+const SECTIONS: [&str; 16] = [
+    "server", "database", "cache", "queue", "auth", "logging", "metrics", "features", "storage",
+    "mail", "search", "payments", "gateway", "scheduler", "tracing", "replica",
+];
+const SERVICES: [&str; 10] = [
+    "api", "billing", "orders", "users", "inventory", "notifier", "reports", "catalog", "ledger",
+    "profile",
+];
+const ENVIRONMENTS: [&str; 4] = ["dev", "staging", "prod", "canary"];
+const REGIONS: [&str; 5] = ["us-east-1", "us-west-2", "eu-west-1", "ap-south-1", "ap-northeast-1"];
+const CHILDREN: [&str; 5] = ["pool", "tls", "retry", "limits", "backoff"];
+const LOG_LEVELS: [&str; 5] = ["trace", "debug", "info", "warn", "error"];
+
+// 生成带有端口、主机、特性开关和密钥占位符的合成配置树
+pub struct FixtureGenerator {
+    rng: StdRng,
+    depth: usize,
+}
+
+impl FixtureGenerator {
+    pub fn new(depth: usize, seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            rng,
+            depth: depth.max(1),
+        }
+    }
+
+    pub fn generate(&mut self, path: String, config_type: ConfigType) -> Config {
+        let mut config = HashMap::new();
+        let service = self.pick(&SERVICES);
+        config.insert("service".to_string(), ConfigValue::String(service.to_string()));
+        config.insert(
+            "environment".to_string(),
+            ConfigValue::String(self.pick(&ENVIRONMENTS).to_string()),
+        );
+        config.insert(
+            "region".to_string(),
+            ConfigValue::String(self.pick(&REGIONS).to_string()),
+        );
+        config.insert(
+            "version".to_string(),
+            ConfigValue::String(format!(
+                "{}.{}.{}",
+                self.rng.gen_range(0..4),
+                self.rng.gen_range(0..20),
+                self.rng.gen_range(0..50)
+            )),
+        );
+
+        let count = self.rng.gen_range(3..=6);
+        let mut sections = SECTIONS.to_vec();
+        sections.shuffle(&mut self.rng);
+        for section in sections.into_iter().take(count) {
+            let value = self.section(section, 1);
+            config.insert(section.to_string(), value);
+        }
+
+        Config {
+            path: ConfigPath::new(path).unwrap(),
+            config,
+            config_type,
+        }
+    }
+
+    fn section(&mut self, name: &str, level: usize) -> ConfigValue {
+        let mut object = HashMap::new();
+        if name == "features" {
+            for flag in ["new_checkout", "dark_mode", "beta_search", "fast_path", "audit_v2"] {
+                object.insert(flag.to_string(), ConfigValue::Boolean(self.rng.gen_bool(0.5)));
+            }
+            return ConfigValue::Object(object);
+        }
+
+        object.insert("enabled".to_string(), ConfigValue::Boolean(self.rng.gen_bool(0.8)));
+        object.insert("host".to_string(), ConfigValue::String(self.host(name)));
+        object.insert("port".to_string(), self.number(1024, 65535));
+        object.insert("timeout_ms".to_string(), self.number(100, 30_000));
+        match name {
+            "database" | "replica" | "cache" | "queue" => {
+                object.insert("max_connections".to_string(), self.number(4, 512));
+                object.insert("username".to_string(), ConfigValue::String(format!("{}_svc", name)));
+                object.insert("password".to_string(), self.secret(name, "password"));
+            }
+            "auth" | "payments" | "mail" | "gateway" => {
+                object.insert("api_key".to_string(), self.secret(name, "api_key"));
+                object.insert("retries".to_string(), self.number(0, 10));
+            }
+            "logging" | "tracing" | "metrics" => {
+                object.insert(
+                    "level".to_string(),
+                    ConfigValue::String(self.pick(&LOG_LEVELS).to_string()),
+                );
+                object.insert("sample_rate".to_string(), self.ratio());
+            }
+            _ => {
+                object.insert("workers".to_string(), self.number(1, 64));
+            }
+        }
+
+        if level < self.depth {
+            let replicas = self.rng.gen_range(1..=3);
+            let endpoints = (0..replicas)
+                .map(|i| {
+                    let mut endpoint = HashMap::new();
+                    endpoint.insert(
+                        "host".to_string(),
+                        ConfigValue::String(format!("{}-{}.{}", name, i, self.domain())),
+                    );
+                    endpoint.insert("port".to_string(), self.number(1024, 65535));
+                    endpoint.insert("weight".to_string(), self.number(1, 100));
+                    ConfigValue::Object(endpoint)
+                })
+                .collect();
+            object.insert("endpoints".to_string(), ConfigValue::Array(endpoints));
+            let child = self.pick(&CHILDREN);
+            object.insert(child.to_string(), self.section(child, level + 1));
+        }
+        ConfigValue::Object(object)
+    }
+
+    fn host(&mut self, name: &str) -> String {
+        format!("{}-{}.{}", name, self.rng.gen_range(1..10), self.domain())
+    }
+
+    fn domain(&mut self) -> String {
+        format!("{}.internal.example.com", self.pick(&REGIONS))
+    }
+
+    fn secret(&self, section: &str, key: &str) -> ConfigValue {
+        ConfigValue::String(format!(
+            "${{SECRET_{}_{}}}",
+            section.to_uppercase(),
+            key.to_uppercase()
+        ))
+    }
+
+    fn number(&mut self, min: u64, max: u64) -> ConfigValue {
+        ConfigValue::Number(Number::from(self.rng.gen_range(min..=max)))
+    }
+
+    fn ratio(&mut self) -> ConfigValue {
+        let value = (self.rng.gen_range(0.0..1.0_f64) * 100.0).round() / 100.0;
+        ConfigValue::Number(Number::from_f64(value).unwrap_or(Number::from(0)))
+    }
+
+    fn pick(&mut self, items: &[&'static str]) -> &'static str {
+        items.choose(&mut self.rng).unwrap()
+    }
+}
@@ -24,6 +24,8 @@ impl FormatConverterService {
             config_type = ConfigType::Toml;
         } else if path.ends_with(".json") {
             config_type = ConfigType::Json;
+        } else if path.ends_with(".json5") {
+            config_type = ConfigType::Json5;
         } else if path.ends_with(".yaml") || path.ends_with(".yml") {
             config_type = ConfigType::Yaml;
         }
@@ -38,6 +40,21 @@ impl FormatConverterService {
         Ok(conf)
     }
 
+    // 没有扩展名且无法从首行识别时（例如单行 JSON），依次尝试 JSON、TOML、YAML
+    pub fn validate_config_or_guess(&self) -> Result<Config, ConfigError> {
+        match self.validate_config() {
+            Err(ConfigError::UnknownConfigType) => {
+                let path = self.config_path.as_string();
+                let content = delete_ignore_line(&self.content);
+                [ConfigType::Json, ConfigType::Toml, ConfigType::Yaml]
+                    .into_iter()
+                    .find_map(|config_type| Config::from(path.clone(), content.clone(), config_type).ok())
+                    .ok_or(ConfigError::UnknownConfigType)
+            }
+            result => result,
+        }
+    }
+
     fn detect_format(content: &str) -> Result<ConfigType, ConfigError> {
         let mut config_type = ConfigType::Unknown;
         if content.is_empty() {
@@ -46,7 +63,9 @@ impl FormatConverterService {
         if config_type == ConfigType::Unknown {
             let mut lines = content.lines();
             if let Some(line) = lines.next() {
-                if (line.starts_with("[")
+                if line.trim().starts_with("---") {
+                    config_type = ConfigType::Yaml;
+                } else if (line.starts_with("[")
                     && line.ends_with("]")
                     && !line.contains(":")
                     && !line.contains("{")
@@ -71,6 +90,7 @@ impl FormatConverterService {
         match config_type {
             ConfigType::Toml => {}
             ConfigType::Json => {}
+            ConfigType::Json5 => {}
             ConfigType::Yaml => {}
             ConfigType::Unknown => {
                 return Err(ConfigError::UnknownConfigType);
@@ -80,3 +100,61 @@ impl FormatConverterService {
         Ok(config_type)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::configuration::{ConfigValue, YAML_DOCUMENTS_KEY};
+
+    fn convert(path: &str, content: &str) -> Result<Config, ConfigError> {
+        FormatConverterService::new(ConfigPath::new(path.to_string()).unwrap(), content.to_string())
+            .validate_config()
+    }
+
+    #[test]
+    fn json5_accepts_comments_and_trailing_commas() {
+        let content = "{\n  // 服务端口\n  port: 8080,\n  /* 主机 */ host: 'localhost',\n  tags: ['a', 'b',],\n}";
+        let config = convert("server.json5", content).unwrap();
+        assert_eq!(config.config_type, ConfigType::Json5);
+        assert_eq!(config.get("port").unwrap().as_number(), Some(8080.0));
+        assert_eq!(config.get("host").unwrap().as_string().map(String::as_str), Some("localhost"));
+        assert_eq!(config.get("tags").unwrap().len(), Some(2));
+    }
+
+    #[test]
+    fn plain_json_rejects_json5_syntax() {
+        let result = convert("server.json", "{\n  \"port\": 8080,\n}");
+        assert!(matches!(result, Err(ConfigError::ParseConfigError)));
+    }
+
+    #[test]
+    fn multi_document_yaml_is_exposed_as_documents() {
+        let config = convert("app.yaml", "---\nname: first\n---\nname: second\n").unwrap();
+        let documents = config.get(YAML_DOCUMENTS_KEY).unwrap();
+        assert_eq!(documents.len(), Some(2));
+
+        let second = config.clone().select_document(1).unwrap();
+        assert_eq!(second.get("name"), Some(ConfigValue::String("second".to_string())));
+        assert!(matches!(
+            config.select_document(2),
+            Err(ConfigError::DocumentNotFound { index: 2, count: 2 })
+        ));
+    }
+
+    #[test]
+    fn single_document_yaml_is_not_wrapped() {
+        let config = convert("app.yaml", "---\nname: only\n").unwrap();
+        assert_eq!(config.get(YAML_DOCUMENTS_KEY), None);
+        assert!(config.clone().select_document(0).is_ok());
+        assert!(matches!(
+            config.select_document(1),
+            Err(ConfigError::DocumentNotFound { index: 1, count: 1 })
+        ));
+    }
+
+    #[test]
+    fn multi_document_yaml_rejects_malformed_document() {
+        let result = convert("app.yaml", "---\nname: first\n---\nname: [unclosed\n");
+        assert!(matches!(result, Err(ConfigError::ParseConfigError)));
+    }
+}
@@ -1,4 +1,14 @@
+pub mod config_assertion;
 pub mod config_merger;
 pub mod env_override;
 pub mod format_converter;
-pub mod config_validation;
\ No newline at end of file
+pub mod config_validation;
+pub mod config_formatter;
+pub mod config_stats;
+pub mod embedded_config;
+pub mod fixture_generator;
+pub mod schema_drift;
+pub mod script_rule;
+pub mod config_diff;
+pub mod config_lint;
+pub mod config_patch;
@@ -0,0 +1,283 @@
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::domain::entities::configuration::Config;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingField {
+    pub path: String,
+    pub required: bool,
+}
+
+// 值的类型与 schema 声明的 type 不一致，例如对象改成了字符串
+#[derive(Debug, Clone, Serialize)]
+pub struct TypeMismatch {
+    pub path: String,
+    pub expected: String,
+    pub actual: String,
+}
+
+// 配置与 schema 之间的差异
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct DriftReport {
+    pub unused_keys: Vec<String>,
+    pub missing_fields: Vec<MissingField>,
+    pub type_mismatches: Vec<TypeMismatch>,
+}
+
+impl DriftReport {
+    pub fn has_drift(&self) -> bool {
+        !self.unused_keys.is_empty()
+            || !self.missing_fields.is_empty()
+            || !self.type_mismatches.is_empty()
+    }
+}
+
+// 对比 JSON Schema（包括由结构体生成的 schema）与配置的键
+pub struct SchemaDriftService;
+
+impl SchemaDriftService {
+    pub fn compare(schema: &Value, config: &Config) -> DriftReport {
+        let mut report = DriftReport::default();
+        Self::compare_value(schema, schema, &config.to_serde_value(), "", &mut report);
+        report.unused_keys.sort();
+        report.unused_keys.dedup();
+        report.missing_fields.sort_by(|a, b| a.path.cmp(&b.path));
+        report.missing_fields.dedup_by(|a, b| a.path == b.path);
+        report.type_mismatches.sort_by(|a, b| a.path.cmp(&b.path));
+        report
+    }
+
+    fn compare_value(
+        root: &Value,
+        schema: &Value,
+        value: &Value,
+        path: &str,
+        report: &mut DriftReport,
+    ) {
+        let schema = Self::resolve(root, schema);
+        // 类型已经不同时不再比较其下的键
+        let types = Self::types(schema);
+        let actual = Self::type_name(value);
+        let accepted = types
+            .iter()
+            .any(|expected| *expected == actual || (*expected == "number" && actual == "integer"));
+        if !types.is_empty() && !accepted {
+            report.type_mismatches.push(TypeMismatch {
+                path: path.to_string(),
+                expected: types.join(" | "),
+                actual: actual.to_string(),
+            });
+            return;
+        }
+        match value {
+            Value::Object(object) => {
+                let properties = Self::properties(root, schema);
+                let additional = schema.get("additionalProperties");
+                for (key, child) in object {
+                    let child_path = Self::join(path, key);
+                    match properties.iter().find(|(name, _)| name == key) {
+                        Some((_, child_schema)) => {
+                            Self::compare_value(root, child_schema, child, &child_path, report)
+                        }
+                        // 声明为 map 的对象允许任意键，继续按值的 schema 比较
+                        None => match additional {
+                            Some(value_schema @ Value::Object(_)) => Self::compare_value(
+                                root,
+                                value_schema,
+                                child,
+                                &child_path,
+                                report,
+                            ),
+                            Some(Value::Bool(true)) => {}
+                            _ if properties.is_empty() && !Self::is_object_schema(schema) => {}
+                            _ => report.unused_keys.push(child_path),
+                        },
+                    }
+                }
+                let required = Self::required(root, schema);
+                for (name, _) in properties.iter() {
+                    if !object.contains_key(name) {
+                        report.missing_fields.push(MissingField {
+                            path: Self::join(path, name),
+                            required: required.contains(name),
+                        });
+                    }
+                }
+            }
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    let item_path = format!("{}[]", path);
+                    for item in items {
+                        Self::compare_value(root, item_schema, item, &item_path, report);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 展开 $ref，以及 Option<T> 生成的 anyOf/oneOf 包装
+    fn resolve<'a>(root: &'a Value, schema: &'a Value) -> &'a Value {
+        if let Some(Value::String(reference)) = schema.get("$ref") {
+            let pointer = reference.trim_start_matches('#');
+            if let Some(target) = root.pointer(pointer) {
+                return Self::resolve(root, target);
+            }
+        }
+        for key in ["anyOf", "oneOf"] {
+            if let Some(Value::Array(variants)) = schema.get(key)
+                && let Some(variant) = variants
+                    .iter()
+                    .map(|v| Self::resolve(root, v))
+                    .find(|v| v.get("type") != Some(&Value::String("null".to_string())))
+            {
+                return variant;
+            }
+        }
+        schema
+    }
+
+    // allOf 中的属性合并到一起
+    fn properties(root: &Value, schema: &Value) -> Vec<(String, Value)> {
+        let mut properties: Vec<(String, Value)> = schema
+            .get("properties")
+            .and_then(|p| p.as_object())
+            .map(|p| p.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+            .unwrap_or_default();
+        if let Some(Value::Array(parts)) = schema.get("allOf") {
+            for part in parts {
+                properties.extend(Self::properties(root, Self::resolve(root, part)));
+            }
+        }
+        properties
+    }
+
+    fn required(root: &Value, schema: &Value) -> Vec<String> {
+        let mut required: Vec<String> = schema
+            .get("required")
+            .and_then(|r| r.as_array())
+            .map(|r| r.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+            .unwrap_or_default();
+        if let Some(Value::Array(parts)) = schema.get("allOf") {
+            for part in parts {
+                required.extend(Self::required(root, Self::resolve(root, part)));
+            }
+        }
+        required
+    }
+
+    fn is_object_schema(schema: &Value) -> bool {
+        Self::types(schema).contains(&"object")
+    }
+
+    // schema 中 type 声明的类型，未声明时为空
+    fn types(schema: &Value) -> Vec<&str> {
+        match schema.get("type") {
+            Some(Value::String(t)) => vec![t.as_str()],
+            Some(Value::Array(types)) => types.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    // 与 JSON Schema 的类型名一致，整数单独区分
+    fn type_name(value: &Value) -> &'static str {
+        match value {
+            Value::Null => "null",
+            Value::Bool(_) => "boolean",
+            Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
+        }
+    }
+
+    fn join(parent: &str, key: &str) -> String {
+        if parent.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}.{}", parent, key)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::domain::entities::configuration::ConfigValue;
+
+    fn compare(config: Value) -> DriftReport {
+        let schema = json!({
+            "type": "object",
+            "required": ["database"],
+            "properties": {
+                "database": {
+                    "type": "object",
+                    "required": ["host"],
+                    "properties": {
+                        "host": {"type": "string"},
+                        "port": {"type": "integer"},
+                        "timeout": {"type": ["number", "string"]}
+                    }
+                },
+                "debug": {"type": "boolean"}
+            }
+        });
+        let mut parsed = Config::new();
+        for (key, value) in config.as_object().unwrap() {
+            parsed.set(key, ConfigValue::from_serde_json(value.clone()).unwrap()).unwrap();
+        }
+        SchemaDriftService::compare(&schema, &parsed)
+    }
+
+    #[test]
+    fn config_matching_the_schema_has_no_drift() {
+        let report = compare(json!({
+            "database": {"host": "db", "port": 5432, "timeout": 1.5},
+            "debug": false
+        }));
+        assert!(!report.has_drift(), "{:?}", report);
+    }
+
+    #[test]
+    fn missing_keys_are_reported_with_required_flag() {
+        let report = compare(json!({"database": {"port": 5432, "timeout": "30s"}}));
+        let missing: Vec<(&str, bool)> =
+            report.missing_fields.iter().map(|f| (f.path.as_str(), f.required)).collect();
+        assert_eq!(missing, [("database.host", true), ("debug", false)]);
+        assert!(report.unused_keys.is_empty());
+    }
+
+    #[test]
+    fn extra_keys_are_reported_as_unused() {
+        let report = compare(json!({
+            "database": {"host": "db", "port": 5432, "timeout": 1, "pool": 4},
+            "debug": false,
+            "legacy": true
+        }));
+        assert_eq!(report.unused_keys, ["database.pool", "legacy"]);
+        assert!(report.missing_fields.is_empty());
+    }
+
+    #[test]
+    fn type_changes_are_reported_without_descending() {
+        let report = compare(json!({
+            "database": "postgres://db:5432",
+            "debug": "false"
+        }));
+        let mismatches: Vec<(&str, &str, &str)> = report
+            .type_mismatches
+            .iter()
+            .map(|m| (m.path.as_str(), m.expected.as_str(), m.actual.as_str()))
+            .collect();
+        assert_eq!(mismatches, [("database", "object", "string"), ("debug", "boolean", "string")]);
+        assert!(report.missing_fields.is_empty());
+
+        let report = compare(json!({"database": {"host": "db", "port": 54.32}, "debug": true}));
+        assert_eq!(report.type_mismatches[0].path, "database.port");
+        assert_eq!(report.type_mismatches[0].actual, "number");
+    }
+}
@@ -0,0 +1,207 @@
+use std::{path::Path, sync::Arc};
+
+use rhai::{AST, Dynamic, Engine, Scope};
+use tracing::debug;
+
+use crate::{
+    domain::entities::{configuration::Config, validation_rule::ValidationRule},
+    shared::error::{ConfigError, ValidationError},
+};
+
+// 单个脚本最多执行的操作数，防止死循环拖住校验
+const MAX_SCRIPT_OPERATIONS: u64 = 1_000_000;
+
+// 以 Rhai 脚本实现的自定义规则，脚本通过变量 `config` 读取整份配置。
+// 返回值约定：() / true / 空数组表示通过；false、字符串、{ field, message }
+// 或它们组成的数组表示失败；脚本中 throw 的内容同样视为失败信息
+pub struct ScriptRuleService;
+
+impl ScriptRuleService {
+    // 加载目录下所有 *.rhai 脚本，按文件名排序
+    pub fn load_dir(dir: &Path) -> Result<Vec<Box<ValidationRule>>, ConfigError> {
+        let mut paths: Vec<_> = std::fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "rhai"))
+            .collect();
+        paths.sort();
+
+        let engine = Arc::new(Self::engine());
+        paths
+            .iter()
+            .map(|path| {
+                let name = path
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let source = std::fs::read_to_string(path)?;
+                debug!("load script rule: {}", path.display());
+                Self::compile_with(engine.clone(), &name, &source)
+            })
+            .collect()
+    }
+
+    pub fn compile(name: &str, source: &str) -> Result<Box<ValidationRule>, ConfigError> {
+        Self::compile_with(Arc::new(Self::engine()), name, source)
+    }
+
+    fn compile_with(
+        engine: Arc<Engine>,
+        name: &str,
+        source: &str,
+    ) -> Result<Box<ValidationRule>, ConfigError> {
+        let ast = engine
+            .compile(source)
+            .map_err(|e| ConfigError::InvalidScriptRule(format!("{}: {}", name, e)))?;
+        let name = name.to_string();
+        Ok(Box::new(move |config: &Config| {
+            let messages = Self::run(&engine, &ast, config);
+            if messages.is_empty() {
+                Ok(())
+            } else {
+                Err(ValidationError::ScriptRuleViolation {
+                    rule: name.clone(),
+                    message: messages.join("; "),
+                })
+            }
+        }))
+    }
+
+    fn engine() -> Engine {
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_SCRIPT_OPERATIONS);
+        engine
+    }
+
+    fn run(engine: &Engine, ast: &AST, config: &Config) -> Vec<String> {
+        let config = match rhai::serde::to_dynamic(config.to_serde_value()) {
+            Ok(config) => config,
+            Err(e) => return vec![format!("script error: {}", e)],
+        };
+        let mut scope = Scope::new();
+        scope.push_constant("config", config);
+        match engine.eval_ast_with_scope::<Dynamic>(&mut scope, ast) {
+            Ok(result) => Self::messages(result),
+            Err(e) => match *e {
+                rhai::EvalAltResult::ErrorRuntime(value, _) => Self::messages(value),
+                e => vec![format!("script error: {}", e)],
+            },
+        }
+    }
+
+    fn messages(result: Dynamic) -> Vec<String> {
+        if result.is_unit() {
+            return vec![];
+        }
+        if let Ok(passed) = result.as_bool() {
+            return if passed {
+                vec![]
+            } else {
+                vec!["rule failed".to_string()]
+            };
+        }
+        if result.is_array() {
+            return result
+                .cast::<rhai::Array>()
+                .into_iter()
+                .flat_map(Self::messages)
+                .collect();
+        }
+        if result.is_map() {
+            let map = result.cast::<rhai::Map>();
+            let message = map
+                .get("message")
+                .map(|m| m.to_string())
+                .unwrap_or_else(|| "rule failed".to_string());
+            return match map.get("field") {
+                Some(field) => vec![format!("{}: {}", field, message)],
+                None => vec![message],
+            };
+        }
+        vec![result.to_string()]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        services::format_converter::FormatConverterService, value_objects::config_path::ConfigPath,
+    };
+
+    fn config(content: &str) -> Config {
+        FormatConverterService::new(ConfigPath::new("app.json").unwrap(), content.to_string())
+            .validate_config()
+            .unwrap()
+    }
+
+    // 规则的失败信息，通过时为 None
+    fn run(source: &str, content: &str) -> Option<String> {
+        let rule = ScriptRuleService::compile("rule", source).unwrap();
+        match rule(&config(content)) {
+            Ok(()) => None,
+            Err(ValidationError::ScriptRuleViolation { rule, message }) => {
+                assert_eq!(rule, "rule");
+                Some(message)
+            }
+            Err(e) => panic!("unexpected error {:?}", e),
+        }
+    }
+
+    fn temp_dir(name: &str) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("config-manager-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn script_results_map_to_messages() {
+        let port = r#"{"port": 80}"#;
+        assert_eq!(run("()", port), None);
+        assert_eq!(run("config.port > 0", port), None);
+        assert_eq!(run("[]", port), None);
+        assert_eq!(run("config.port > 1024", port).as_deref(), Some("rule failed"));
+        assert_eq!(run(r#""port too low""#, port).as_deref(), Some("port too low"));
+        assert_eq!(
+            run(r#"[#{ field: "port", message: "privileged" }, "second"]"#, port).as_deref(),
+            Some("port: privileged; second")
+        );
+        assert_eq!(run(r#"if config.port < 1024 { throw "privileged port" }"#, port).as_deref(), Some("privileged port"));
+    }
+
+    #[test]
+    fn script_errors_fail_the_rule() {
+        let message = run("config.missing.field", "{}").unwrap();
+        assert!(message.starts_with("script error"), "{}", message);
+        let message = run("loop {}", "{}").unwrap();
+        assert!(message.starts_with("script error"), "{}", message);
+    }
+
+    #[test]
+    fn invalid_scripts_are_rejected_on_compile() {
+        let error = ScriptRuleService::compile("broken", "let x = ;").err().unwrap();
+        assert!(matches!(&error, ConfigError::InvalidScriptRule(message) if message.starts_with("broken: ")), "{:?}", error);
+    }
+
+    #[test]
+    fn load_dir_loads_rhai_files_in_order() {
+        let dir = temp_dir("rules-d");
+        std::fs::write(dir.join("b_port.rhai"), r#"if config.port < 1024 { "port" }"#).unwrap();
+        std::fs::write(dir.join("a_host.rhai"), r#"if config.host == () { "host" }"#).unwrap();
+        std::fs::write(dir.join("notes.txt"), "not a rule").unwrap();
+        let rules = ScriptRuleService::load_dir(&dir).unwrap();
+        assert_eq!(rules.len(), 2);
+        let config = config(r#"{"port": 80}"#);
+        let failures: Vec<String> = rules
+            .iter()
+            .filter_map(|rule| rule(&config).err())
+            .map(|e| e.to_string())
+            .collect();
+        assert_eq!(failures, ["custom rule a_host: host", "custom rule b_port: port"]);
+
+        std::fs::write(dir.join("c_broken.rhai"), "fn (").unwrap();
+        let error = ScriptRuleService::load_dir(&dir).err().unwrap();
+        assert!(matches!(&error, ConfigError::InvalidScriptRule(message) if message.starts_with("c_broken: ")), "{:?}", error);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -4,19 +4,20 @@ use std::fmt::Display;
 pub enum ConfigType {
     Yaml,
     Json,
+    Json5,
     Toml,
     Unknown,
 }
 
 impl From<&str> for ConfigType {
     fn from(s: &str) -> Self {
-        let format = match s {
+        match s {
             "json" => ConfigType::Json,
+            "json5" => ConfigType::Json5,
             "yaml" => ConfigType::Yaml,
             "toml" => ConfigType::Toml,
             _ => ConfigType::Unknown,
-        };
-        format
+        }
     }
 }
 
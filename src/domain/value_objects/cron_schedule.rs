@@ -0,0 +1,188 @@
+use chrono::{DateTime, Datelike, Duration, TimeZone, Timelike, Utc};
+
+use crate::{domain::value_objects::units::parse_duration, shared::error::ConfigError};
+
+// 定时任务的执行计划：5 段 cron 表达式（分 时 日 月 周，按 UTC），每段支持 *、*/n、a-b、a-b/n
+// 和逗号分隔的列表，周日为 0 或 7；也可以写成 @hourly、@daily、@weekly 或 @every <时长>
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CronSchedule {
+    Every(Duration),
+    Cron(CronFields),
+}
+
+// 每段允许的取值，按位存储
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronFields {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    // 日和周都不是 * 时满足其一即可，与 crontab 一致
+    any_day: bool,
+}
+
+// 找不到下一次执行时间时放弃搜索的范围，例如 2 月 30 日永远不会到来
+const SEARCH_LIMIT_DAYS: i64 = 366 * 5;
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, ConfigError> {
+        let invalid = || ConfigError::InvalidSchedule(expression.to_string());
+        let expression = expression.trim();
+        let expression = match expression {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            _ => expression,
+        };
+        if let Some(interval) = expression.strip_prefix("@every") {
+            let interval = parse_duration(interval).filter(|d| !d.is_zero()).ok_or_else(invalid)?;
+            return Ok(Self::Every(Duration::from_std(interval).map_err(|_| invalid())?));
+        }
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(invalid());
+        };
+        let weekdays = Self::field(weekday, 0, 7).ok_or_else(invalid)?;
+        Ok(Self::Cron(CronFields {
+            minutes: Self::field(minute, 0, 59).ok_or_else(invalid)?,
+            hours: Self::field(hour, 0, 23).ok_or_else(invalid)?,
+            days: Self::field(day, 1, 31).ok_or_else(invalid)?,
+            months: Self::field(month, 1, 12).ok_or_else(invalid)?,
+            // 7 与 0 都表示周日
+            weekdays: (weekdays | (weekdays >> 7)) & 0x7f,
+            any_day: day != "*" && weekday != "*",
+        }))
+    }
+
+    // 严格晚于 after 的下一次执行时间
+    pub fn next_after(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let fields = match self {
+            Self::Every(interval) => return after.checked_add_signed(*interval),
+            Self::Cron(fields) => fields,
+        };
+        let limit = after + Duration::days(SEARCH_LIMIT_DAYS);
+        let mut next = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        while next <= limit {
+            if !Self::contains(fields.months, next.month()) {
+                let (year, month) = match next.month() {
+                    12 => (next.year() + 1, 1),
+                    month => (next.year(), month + 1),
+                };
+                next = Utc.with_ymd_and_hms(year, month, 1, 0, 0, 0).single()?;
+            } else if !fields.day_matches(next) {
+                next = next.date_naive().succ_opt()?.and_hms_opt(0, 0, 0)?.and_utc();
+            } else if !Self::contains(fields.hours, next.hour()) {
+                next = next.with_minute(0)? + Duration::hours(1);
+            } else if !Self::contains(fields.minutes, next.minute()) {
+                next += Duration::minutes(1);
+            } else {
+                return Some(next);
+            }
+        }
+        None
+    }
+
+    fn contains(bits: u64, value: u32) -> bool {
+        bits & (1 << value) != 0
+    }
+
+    fn field(field: &str, min: u32, max: u32) -> Option<u64> {
+        let mut bits = 0u64;
+        for part in field.split(',') {
+            let (range, step) = match part.split_once('/') {
+                Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0)?),
+                None => (part, 1),
+            };
+            let (start, end) = match range {
+                "*" => (min, max),
+                range => match range.split_once('-') {
+                    Some((start, end)) => (start.parse().ok()?, end.parse().ok()?),
+                    // "5/15" 表示从 5 开始每 15 个
+                    None if part.contains('/') => (range.parse().ok()?, max),
+                    None => {
+                        let value = range.parse().ok()?;
+                        (value, value)
+                    }
+                },
+            };
+            if start < min || end > max || start > end {
+                return None;
+            }
+            for value in (start..=end).step_by(step as usize) {
+                bits |= 1 << value;
+            }
+        }
+        Some(bits)
+    }
+}
+
+impl CronFields {
+    fn day_matches(&self, at: DateTime<Utc>) -> bool {
+        let day = CronSchedule::contains(self.days, at.day());
+        let weekday = CronSchedule::contains(self.weekdays, at.weekday().num_days_from_sunday());
+        if self.any_day { day || weekday } else { day && weekday }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2026-01-01 是周四
+    fn next(expression: &str, after: &str) -> Option<String> {
+        let after = DateTime::parse_from_rfc3339(after).unwrap().with_timezone(&Utc);
+        let next = CronSchedule::parse(expression).unwrap().next_after(after)?;
+        Some(next.format("%Y-%m-%dT%H:%M").to_string())
+    }
+
+    #[test]
+    fn steps_ranges_and_lists() {
+        let at = |expression| next(expression, "2026-01-01T10:07:30Z").unwrap();
+        assert_eq!(at("*/15 * * * *"), "2026-01-01T10:15");
+        assert_eq!(at("5/20 * * * *"), "2026-01-01T10:25");
+        assert_eq!(at("0 9-17/4 * * *"), "2026-01-01T13:00");
+        assert_eq!(at("0,30 8 * * 1,3"), "2026-01-05T08:00");
+        assert_eq!(at("0 0 * * 7"), "2026-01-04T00:00");
+        // 日和周都指定时满足其一即可
+        assert_eq!(at("0 0 13 * 5"), "2026-01-02T00:00");
+        assert_eq!(at("@hourly"), "2026-01-01T11:00");
+        assert_eq!(at("@every 90s"), "2026-01-01T10:09");
+        // 结果严格晚于 after
+        assert_eq!(next("0 9-17/4 * * *", "2026-01-01T17:00:00Z").unwrap(), "2026-01-02T09:00");
+    }
+
+    #[test]
+    fn rolls_over_days_months_and_years() {
+        assert_eq!(next("0 0 1 * *", "2026-01-31T12:00:00Z").unwrap(), "2026-02-01T00:00");
+        assert_eq!(next("30 23 31 12 *", "2026-12-31T23:30:00Z").unwrap(), "2027-12-31T23:30");
+        // 没有 31 日的月份被跳过
+        assert_eq!(next("0 0 31 * *", "2026-01-31T00:00:00Z").unwrap(), "2026-03-31T00:00");
+        assert_eq!(next("0 0 29 2 *", "2026-03-01T00:00:00Z").unwrap(), "2028-02-29T00:00");
+        assert_eq!(next("0 0 30 2 *", "2026-01-01T00:00:00Z"), None);
+    }
+
+    #[test]
+    fn rejects_out_of_range_and_malformed_fields() {
+        for expression in [
+            "60 * * * *",
+            "* 24 * * *",
+            "* * 0 * *",
+            "* * * 13 *",
+            "* * * * 8",
+            "5-1 * * * *",
+            "*/0 * * * *",
+            "a * * * *",
+            "* * * *",
+            "* * * * * *",
+            "@every 0s",
+            "@yearly",
+        ] {
+            assert!(
+                matches!(CronSchedule::parse(expression), Err(ConfigError::InvalidSchedule(_))),
+                "{}",
+                expression
+            );
+        }
+    }
+}
@@ -0,0 +1,159 @@
+use crate::shared::error::ConfigError;
+
+// 点分路径模式：`*` 匹配单个段（也可出现在段内，如 db_*），`**` 匹配任意多段；
+// 键名本身含有 `.`、`*` 或 `\` 时用 `\.`、`\*`、`\\` 转义
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyPattern {
+    segments: Vec<Segment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    // `**`
+    Any,
+    // 以未转义的 `*` 分隔的字面部分，只有一个部分时按字面匹配
+    Glob(Vec<String>),
+}
+
+impl KeyPattern {
+    pub fn parse(pattern: &str) -> Result<Self, ConfigError> {
+        let pattern = pattern.trim();
+        let invalid = || ConfigError::InvalidKeyPattern(pattern.to_string());
+        let mut segments = Vec::new();
+        let mut parts = vec![String::new()];
+        let mut chars = pattern.chars();
+        loop {
+            match chars.next() {
+                Some('\\') => match chars.next() {
+                    Some(c @ ('.' | '*' | '\\')) => parts.last_mut().unwrap().push(c),
+                    _ => return Err(invalid()),
+                },
+                Some('*') => parts.push(String::new()),
+                Some(c) if c != '.' => parts.last_mut().unwrap().push(c),
+                end => {
+                    let segment = match std::mem::replace(&mut parts, vec![String::new()]) {
+                        parts if parts.len() == 1 && parts[0].is_empty() => return Err(invalid()),
+                        parts if parts.len() == 3 && parts.iter().all(|p| p.is_empty()) => {
+                            Segment::Any
+                        }
+                        parts => Segment::Glob(parts),
+                    };
+                    segments.push(segment);
+                    if end.is_none() {
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(Self { segments })
+    }
+
+    pub fn parse_list(patterns: &[String]) -> Result<Vec<Self>, ConfigError> {
+        patterns.iter().map(|p| Self::parse(p)).collect()
+    }
+
+    // 路径完整匹配模式
+    pub fn matches(&self, path: &[&str]) -> bool {
+        Self::match_from(&self.segments, path, false)
+    }
+
+    // 路径是某个可能匹配的路径的前缀（其子孙可能被匹配）
+    pub fn matches_prefix(&self, path: &[&str]) -> bool {
+        Self::match_from(&self.segments, path, true)
+    }
+
+    fn match_from(pattern: &[Segment], path: &[&str], prefix: bool) -> bool {
+        match (pattern.first(), path.first()) {
+            (None, None) => true,
+            (None, Some(_)) => false,
+            (Some(_), None) => prefix || pattern.iter().all(|s| *s == Segment::Any),
+            (Some(Segment::Any), Some(_)) => {
+                Self::match_from(&pattern[1..], path, prefix)
+                    || Self::match_from(pattern, &path[1..], prefix)
+            }
+            (Some(Segment::Glob(parts)), Some(key)) => {
+                Self::match_segment(parts, key) && Self::match_from(&pattern[1..], &path[1..], prefix)
+            }
+        }
+    }
+
+    // 段内通配：parts 之间可以是任意字符
+    fn match_segment(parts: &[String], key: &str) -> bool {
+        if parts.len() == 1 {
+            return parts[0] == key;
+        }
+        let (first, last) = (&parts[0], &parts[parts.len() - 1]);
+        if !key.starts_with(first.as_str())
+            || key.len() < first.len() + last.len()
+            || !key.ends_with(last.as_str())
+        {
+            return false;
+        }
+        let mut rest = &key[first.len()..key.len() - last.len()];
+        for part in &parts[1..parts.len() - 1] {
+            match rest.find(part.as_str()) {
+                Some(index) => rest = &rest[index + part.len()..],
+                None => return false,
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        let path: Vec<&str> = path.split('.').collect();
+        KeyPattern::parse(pattern).unwrap().matches(&path)
+    }
+
+    #[test]
+    fn wildcards_match_nested_keys() {
+        assert!(matches("database.*", "database.host"));
+        assert!(!matches("database.*", "database.pool.size"));
+        assert!(matches("database.**", "database.pool.size"));
+        assert!(matches("**.port", "services.api.port"));
+        assert!(matches("**.port", "port"));
+        assert!(matches("services.*.db_*", "services.api.db_host"));
+        assert!(matches("*.*_url", "cache.redis_url"));
+        assert!(matches("a*b*c", "aXbYc"));
+    }
+
+    #[test]
+    fn non_matching_paths() {
+        assert!(!matches("database.host", "database.port"));
+        assert!(!matches("database.host", "database"));
+        assert!(!matches("db_*", "cache_host"));
+        assert!(!matches("**.port", "services.api.host"));
+        assert!(!matches("a*b*c", "acb"));
+        // 前缀匹配：子孙可能被匹配的父对象
+        let pattern = KeyPattern::parse("services.*.port").unwrap();
+        assert!(pattern.matches_prefix(&["services", "api"]));
+        assert!(!pattern.matches_prefix(&["database"]));
+    }
+
+    #[test]
+    fn escaped_dots_and_stars_are_literal() {
+        let pattern = KeyPattern::parse("hosts.db\\.internal").unwrap();
+        assert!(pattern.matches(&["hosts", "db.internal"]));
+        assert!(!pattern.matches(&["hosts", "db", "internal"]));
+        let pattern = KeyPattern::parse("glob.\\*").unwrap();
+        assert!(pattern.matches(&["glob", "*"]));
+        assert!(!pattern.matches(&["glob", "anything"]));
+        let pattern = KeyPattern::parse("path.c:\\\\*").unwrap();
+        assert!(pattern.matches(&["path", "c:\\windows"]));
+    }
+
+    #[test]
+    fn malformed_patterns_are_rejected() {
+        for pattern in ["", "a..b", ".a", "a.", "a.\\x", "a\\"] {
+            assert!(
+                matches!(KeyPattern::parse(pattern), Err(ConfigError::InvalidKeyPattern(_))),
+                "{}",
+                pattern
+            );
+        }
+    }
+}
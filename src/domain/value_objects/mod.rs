@@ -1,2 +1,7 @@
 pub mod config_format;
-pub mod config_path;
\ No newline at end of file
+pub mod config_path;
+pub mod cron_schedule;
+pub mod key_pattern;
+pub mod namespace;
+pub mod read_consistency;
+pub mod units;
@@ -0,0 +1,49 @@
+use serde::Serialize;
+
+use crate::shared::error::ConfigError;
+
+// 命名空间对应配置目录下的同名子目录，其中的配置以 "<namespace>/<file>" 为名称，
+// 与顶层配置共用缓存、事件日志和订阅
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Namespace(String);
+
+impl Namespace {
+    // 名称即目录名：不能为空、不能以 "." 开头，只允许字母、数字、"-"、"_" 和 "."
+    pub fn new(name: impl Into<String>) -> Result<Self, ConfigError> {
+        let name = name.into();
+        let valid = !name.is_empty()
+            && !name.starts_with('.')
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if valid {
+            Ok(Self(name))
+        } else {
+            Err(ConfigError::InvalidNamespace(name))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    // 命名空间内配置的名称
+    pub fn key(&self, file: &str) -> String {
+        format!("{}/{}", self.0, file)
+    }
+
+    // 订阅整个命名空间时使用的目标
+    pub fn target(&self) -> String {
+        format!("{}/", self.0)
+    }
+
+    // 配置名称去掉命名空间前缀后的文件名，不属于该命名空间时返回 None
+    pub fn file<'a>(&self, key: &'a str) -> Option<&'a str> {
+        key.strip_prefix(self.0.as_str())?.strip_prefix('/')
+    }
+}
+
+// 订阅目标为配置名称，或以 "/" 结尾表示该命名空间下的所有配置
+pub fn covers(target: &str, key: &str) -> bool {
+    target == key || (target.ends_with('/') && key.starts_with(target))
+}
@@ -0,0 +1,54 @@
+use std::fmt::Display;
+
+use crate::shared::error::ConfigError;
+
+// 客户端通过请求头选择读一致性级别，例如 `X-Consistency: bounded-staleness=250`
+pub const CONSISTENCY_HEADER: &str = "x-consistency";
+
+// 读请求的一致性级别。目前只有单节点部署，本节点即 leader，读取天然线性一致；
+// 其余级别依赖集群模式（ReadIndex / 租约、副本延迟），尚未实现，请求时明确拒绝
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReadConsistency {
+    #[default]
+    Linearizable,
+    LeaderLocal,
+    AnyReplica,
+    BoundedStaleness { max_lag_ms: u64 },
+}
+
+impl ReadConsistency {
+    pub fn parse(value: &str) -> Result<Self, ConfigError> {
+        let value = value.trim().to_lowercase();
+        match value.as_str() {
+            "linearizable" => Ok(ReadConsistency::Linearizable),
+            "leader-local" => Ok(ReadConsistency::LeaderLocal),
+            "any-replica" => Ok(ReadConsistency::AnyReplica),
+            _ => value
+                .strip_prefix("bounded-staleness=")
+                .and_then(|ms| ms.trim_end_matches("ms").parse().ok())
+                .map(|max_lag_ms| ReadConsistency::BoundedStaleness { max_lag_ms })
+                .ok_or(ConfigError::InvalidConsistency(value)),
+        }
+    }
+
+    // 单节点只能提供默认的线性一致读
+    pub fn supported(self) -> Result<Self, ConfigError> {
+        match self {
+            ReadConsistency::Linearizable => Ok(self),
+            _ => Err(ConfigError::ConsistencyNotSupported(self.to_string())),
+        }
+    }
+}
+
+impl Display for ReadConsistency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ReadConsistency::Linearizable => write!(f, "linearizable"),
+            ReadConsistency::LeaderLocal => write!(f, "leader-local"),
+            ReadConsistency::AnyReplica => write!(f, "any-replica"),
+            ReadConsistency::BoundedStaleness { max_lag_ms } => {
+                write!(f, "bounded-staleness={}", max_lag_ms)
+            }
+        }
+    }
+}
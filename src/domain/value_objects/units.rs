@@ -0,0 +1,98 @@
+use std::time::Duration;
+
+// 时长：数字加单位，可以串联，例如 `30s`、`5m`、`1h30m`、`250ms`；不带单位的数字按秒处理
+pub fn parse_duration(value: &str) -> Option<Duration> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    if let Ok(seconds) = value.parse::<f64>() {
+        return Duration::try_from_secs_f64(seconds).ok();
+    }
+
+    let mut total = Duration::ZERO;
+    let mut rest = value;
+    while !rest.is_empty() {
+        let number_len = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let unit_len = rest[number_len..]
+            .find(|c: char| c.is_ascii_digit() || c == '.')
+            .unwrap_or(rest.len() - number_len);
+        let number: f64 = rest[..number_len].parse().ok()?;
+        let unit_seconds = match rest[number_len..number_len + unit_len].trim() {
+            "ns" => 1e-9,
+            "us" | "µs" => 1e-6,
+            "ms" => 1e-3,
+            "s" | "sec" => 1.0,
+            "m" | "min" => 60.0,
+            "h" => 3600.0,
+            "d" => 86400.0,
+            _ => return None,
+        };
+        total = total.checked_add(Duration::try_from_secs_f64(number * unit_seconds).ok()?)?;
+        rest = &rest[number_len + unit_len..];
+    }
+    Some(total)
+}
+
+// 字节大小：KB/MB/GB/TB 按 1000 进位，KiB/MiB/GiB/TiB 按 1024 进位；不带单位按字节处理
+pub fn parse_byte_size(value: &str) -> Option<u64> {
+    let value = value.trim();
+    let number_len = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let number: f64 = value[..number_len].parse().ok()?;
+    let multiplier: u64 = match value[number_len..].trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" => 1000,
+        "m" | "mb" => 1000_u64.pow(2),
+        "g" | "gb" => 1000_u64.pow(3),
+        "t" | "tb" => 1000_u64.pow(4),
+        "ki" | "kib" => 1 << 10,
+        "mi" | "mib" => 1 << 20,
+        "gi" | "gib" => 1 << 30,
+        "ti" | "tib" => 1 << 40,
+        _ => return None,
+    };
+    let bytes = number * multiplier as f64;
+    (bytes.fract() == 0.0 && bytes <= u64::MAX as f64).then_some(bytes as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn durations_with_units() {
+        assert_eq!(parse_duration("30s"), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("5m"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_duration("1h30m"), Some(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("250ms"), Some(Duration::from_millis(250)));
+        assert_eq!(parse_duration(" 1.5 "), Some(Duration::from_millis(1500)));
+        assert_eq!(parse_duration("2d"), Some(Duration::from_secs(172800)));
+    }
+
+    #[test]
+    fn invalid_durations() {
+        for value in ["", "s", "5 minutes", "5x", "-5", "-5s", "1h-30m"] {
+            assert_eq!(parse_duration(value), None, "{:?}", value);
+        }
+    }
+
+    #[test]
+    fn byte_sizes_with_units() {
+        assert_eq!(parse_byte_size("512"), Some(512));
+        assert_eq!(parse_byte_size("512MB"), Some(512_000_000));
+        assert_eq!(parse_byte_size("1GiB"), Some(1 << 30));
+        assert_eq!(parse_byte_size("1.5 kb"), Some(1500));
+        assert_eq!(parse_byte_size("2Ki"), Some(2048));
+    }
+
+    #[test]
+    fn invalid_byte_sizes() {
+        for value in ["", "MB", "12 bytes", "1.5B", "-1KB", "1e3"] {
+            assert_eq!(parse_byte_size(value), None, "{:?}", value);
+        }
+    }
+}
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use crate::{
+    domain::repositories::audit_sink::AuditSink,
+    infrastructure::{
+        audit::{
+            file_audit_sink::FileAuditSink, http_audit_sink::HttpAuditSink,
+            syslog_audit_sink::SyslogAuditSink,
+        },
+        serializers::audit_record_serializer::AuditRecordFormat,
+    },
+    shared::config::AuditSettings,
+};
+
+pub struct AuditSinkFactory;
+
+impl AuditSinkFactory {
+    pub fn create(settings: &AuditSettings) -> Arc<dyn AuditSink> {
+        match settings {
+            AuditSettings::Jsonl { path } => {
+                Arc::new(FileAuditSink::new(path, AuditRecordFormat::JsonLines))
+            }
+            AuditSettings::Csv { path } => Arc::new(FileAuditSink::new(path, AuditRecordFormat::Csv)),
+            AuditSettings::Syslog { address, app_name } => {
+                Arc::new(SyslogAuditSink::new(address.clone(), app_name.clone()))
+            }
+            AuditSettings::Http { url } => Arc::new(HttpAuditSink::new(url.clone())),
+        }
+    }
+}
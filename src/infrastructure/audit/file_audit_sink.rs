@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+
+use crate::{
+    domain::{entities::audit::AuditRecord, repositories::audit_sink::AuditSink},
+    infrastructure::serializers::audit_record_serializer::{
+        AuditRecordFormat, AuditRecordSerializer, CSV_HEADER,
+    },
+    shared::error::ConfigError,
+};
+
+// 追加写入本地文件，每条记录一行（JSON Lines 或 CSV）
+pub struct FileAuditSink {
+    path: PathBuf,
+    format: AuditRecordFormat,
+    lock: Mutex<()>,
+}
+
+impl FileAuditSink {
+    pub fn new(path: impl Into<PathBuf>, format: AuditRecordFormat) -> Self {
+        Self {
+            path: path.into(),
+            format,
+            lock: Mutex::new(()),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for FileAuditSink {
+    async fn write(&self, record: &AuditRecord) -> Result<(), ConfigError> {
+        let line = AuditRecordSerializer::serialize(record, self.format)?;
+        let _guard = self.lock.lock().await;
+
+        if let Some(parent) = self.path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let is_new = tokio::fs::metadata(&self.path)
+            .await
+            .map(|m| m.len() == 0)
+            .unwrap_or(true);
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        if is_new && self.format == AuditRecordFormat::Csv {
+            file.write_all(format!("{}\n", CSV_HEADER).as_bytes()).await?;
+        }
+        file.write_all(format!("{}\n", line).as_bytes()).await?;
+        file.flush().await?;
+        Ok(())
+    }
+
+    // 只有 JSON Lines 格式支持回读，无法解析的行被跳过
+    async fn read(&self) -> Option<Result<Vec<AuditRecord>, ConfigError>> {
+        if self.format != AuditRecordFormat::JsonLines {
+            return None;
+        }
+        let _guard = self.lock.lock().await;
+        let content = match tokio::fs::read_to_string(&self.path).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Some(Ok(Vec::new())),
+            Err(e) => return Some(Err(e.into())),
+        };
+        Some(Ok(content
+            .lines()
+            .filter_map(|line| AuditRecordSerializer::deserialize(line).ok())
+            .collect()))
+    }
+}
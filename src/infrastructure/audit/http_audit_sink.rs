@@ -0,0 +1,47 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::{
+    domain::{entities::audit::AuditRecord, repositories::audit_sink::AuditSink},
+    shared::error::ConfigError,
+};
+
+// 以 JSON POST 的方式发送到外部采集端（如 SIEM 的 HTTP 接口）
+pub struct HttpAuditSink {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl HttpAuditSink {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for HttpAuditSink {
+    async fn write(&self, record: &AuditRecord) -> Result<(), ConfigError> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(record)
+            .send()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(ConfigError::RemoteRequestFailed(format!(
+                "{} returned {}",
+                self.url,
+                response.status()
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,4 @@
+pub mod audit_sink_factory;
+pub mod file_audit_sink;
+pub mod http_audit_sink;
+pub mod syslog_audit_sink;
@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use tokio::net::UdpSocket;
+
+use crate::{
+    domain::{entities::audit::AuditRecord, repositories::audit_sink::AuditSink},
+    infrastructure::serializers::audit_record_serializer::{
+        AuditRecordFormat, AuditRecordSerializer,
+    },
+    shared::error::ConfigError,
+};
+
+// local0.info
+const SYSLOG_PRIORITY: u8 = 16 * 8 + 6;
+
+// 通过 UDP 发送 RFC 5424 格式的 syslog 消息，消息体为 JSON
+pub struct SyslogAuditSink {
+    address: String,
+    app_name: String,
+    hostname: String,
+}
+
+impl SyslogAuditSink {
+    pub fn new(address: String, app_name: String) -> Self {
+        let hostname = std::fs::read_to_string("/etc/hostname")
+            .map(|h| h.trim().to_string())
+            .ok()
+            .filter(|h| !h.is_empty())
+            .unwrap_or_else(|| "-".to_string());
+        Self {
+            address,
+            app_name,
+            hostname,
+        }
+    }
+}
+
+#[async_trait]
+impl AuditSink for SyslogAuditSink {
+    async fn write(&self, record: &AuditRecord) -> Result<(), ConfigError> {
+        let body = AuditRecordSerializer::serialize(record, AuditRecordFormat::JsonLines)?;
+        let message = format!(
+            "<{}>1 {} {} {} {} audit - {}",
+            SYSLOG_PRIORITY,
+            record.timestamp.to_rfc3339(),
+            self.hostname,
+            self.app_name,
+            std::process::id(),
+            body
+        );
+        let socket = UdpSocket::bind("0.0.0.0:0").await?;
+        socket.send_to(message.as_bytes(), &self.address).await?;
+        Ok(())
+    }
+}
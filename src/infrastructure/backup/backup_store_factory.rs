@@ -0,0 +1,37 @@
+use std::sync::Arc;
+
+use crate::{
+    domain::{
+        entities::backup::BackupPlan,
+        repositories::backup_store::BackupStore,
+        value_objects::{cron_schedule::CronSchedule, namespace::Namespace},
+    },
+    infrastructure::backup::{
+        directory_backup_store::DirectoryBackupStore, s3_backup_store::S3BackupStore,
+    },
+    shared::{
+        config::{BackupSettings, BackupStoreSettings},
+        error::ConfigError,
+    },
+};
+
+pub struct BackupStoreFactory;
+
+impl BackupStoreFactory {
+    pub fn create(settings: &BackupStoreSettings) -> Arc<dyn BackupStore> {
+        match settings {
+            BackupStoreSettings::Dir { path } => Arc::new(DirectoryBackupStore::new(path)),
+            BackupStoreSettings::S3(s3) => Arc::new(S3BackupStore::new(s3)),
+        }
+    }
+
+    // 设置文件中的计划和命名空间在启动时校验
+    pub fn plan(settings: &BackupSettings) -> Result<BackupPlan, ConfigError> {
+        Ok(BackupPlan {
+            schedule: CronSchedule::parse(&settings.schedule)?,
+            store: Self::create(&settings.store),
+            namespace: settings.namespace.clone().map(Namespace::new).transpose()?,
+            retention: settings.retention.clone(),
+        })
+    }
+}
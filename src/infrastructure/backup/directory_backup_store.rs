@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+
+use crate::{
+    domain::{entities::backup::BackupInfo, repositories::backup_store::BackupStore},
+    shared::error::BackupError,
+};
+
+// 备份保存为本地目录中的文件，目录不存在时在首次备份时创建
+pub struct DirectoryBackupStore {
+    dir: PathBuf,
+}
+
+impl DirectoryBackupStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    // 名称来自列举结果或请求参数，不允许带路径
+    fn path(&self, name: &str) -> Result<PathBuf, BackupError> {
+        match BackupInfo::parse(name, 0) {
+            Some(_) if !name.contains(['/', '\\']) => Ok(self.dir.join(name)),
+            _ => Err(BackupError::NotFound {
+                name: name.to_string(),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl BackupStore for DirectoryBackupStore {
+    fn id(&self) -> String {
+        format!("dir:{}", self.dir.display())
+    }
+
+    async fn put(&self, name: &str, content: Vec<u8>) -> Result<(), BackupError> {
+        let path = self.path(name)?;
+        tokio::fs::create_dir_all(&self.dir).await?;
+        // 先写临时文件再改名，列举时不会看到写了一半的备份
+        let partial = self.dir.join(format!(".{}.partial", name));
+        tokio::fs::write(&partial, content).await?;
+        tokio::fs::rename(&partial, &path).await?;
+        Ok(())
+    }
+
+    async fn get(&self, name: &str) -> Result<Vec<u8>, BackupError> {
+        match tokio::fs::read(self.path(name)?).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Err(BackupError::NotFound {
+                name: name.to_string(),
+            }),
+            result => Ok(result?),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<BackupInfo>, BackupError> {
+        let mut entries = match tokio::fs::read_dir(&self.dir).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            result => result?,
+        };
+        let mut backups = Vec::new();
+        while let Some(entry) = entries.next_entry().await? {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let metadata = entry.metadata().await?;
+            if metadata.is_file()
+                && let Some(backup) = BackupInfo::parse(&name, metadata.len())
+            {
+                backups.push(backup);
+            }
+        }
+        backups.sort_by_key(|backup| backup.created_at);
+        Ok(backups)
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), BackupError> {
+        tokio::fs::remove_file(self.path(name)?).await?;
+        Ok(())
+    }
+}
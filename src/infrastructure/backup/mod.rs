@@ -0,0 +1,3 @@
+pub mod backup_store_factory;
+pub mod directory_backup_store;
+pub mod s3_backup_store;
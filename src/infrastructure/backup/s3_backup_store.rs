@@ -0,0 +1,79 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{entities::backup::BackupInfo, repositories::backup_store::BackupStore},
+    infrastructure::s3::s3_client::S3Client,
+    shared::{config::S3Settings, error::BackupError},
+};
+
+// 备份保存为 S3 对象，对象名为 <prefix><备份名称>
+pub struct S3BackupStore {
+    client: S3Client,
+    prefix: String,
+}
+
+impl S3BackupStore {
+    pub fn new(settings: &S3Settings) -> Self {
+        Self {
+            client: S3Client::new(settings),
+            prefix: settings.prefix.clone(),
+        }
+    }
+
+    fn key(&self, name: &str) -> String {
+        format!("{}{}", self.prefix, name)
+    }
+}
+
+#[async_trait]
+impl BackupStore for S3BackupStore {
+    fn id(&self) -> String {
+        format!("s3://{}/{}", self.client.bucket(), self.prefix)
+    }
+
+    async fn put(&self, name: &str, content: Vec<u8>) -> Result<(), BackupError> {
+        self.client
+            .put_object(&self.key(name), content)
+            .await
+            .map(|_| ())
+            .map_err(BackupError::Storage)
+    }
+
+    async fn get(&self, name: &str) -> Result<Vec<u8>, BackupError> {
+        self.client
+            .get_object(&self.key(name))
+            .await
+            .map_err(BackupError::Storage)?
+            .ok_or_else(|| BackupError::NotFound {
+                name: name.to_string(),
+            })
+    }
+
+    // 只列出前缀下一层中符合备份命名的对象
+    async fn list(&self) -> Result<Vec<BackupInfo>, BackupError> {
+        let objects = self
+            .client
+            .list_objects(&self.prefix)
+            .await
+            .map_err(BackupError::Storage)?;
+        let mut backups: Vec<BackupInfo> = objects
+            .iter()
+            .filter_map(|object| {
+                let name = object.key.strip_prefix(&self.prefix)?;
+                if name.contains('/') {
+                    return None;
+                }
+                BackupInfo::parse(name, object.size)
+            })
+            .collect();
+        backups.sort_by_key(|backup| backup.created_at);
+        Ok(backups)
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), BackupError> {
+        self.client
+            .delete_object(&self.key(name))
+            .await
+            .map_err(BackupError::Storage)
+    }
+}
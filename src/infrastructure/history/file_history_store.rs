@@ -0,0 +1,104 @@
+use std::path::{Path, PathBuf};
+
+use chrono::{DateTime, Utc};
+
+use crate::{
+    domain::{entities::config_version::ConfigVersion, repositories::history_store::HistoryStore},
+    shared::{
+        error::ConfigError,
+        utils::{atomic_write, sha256_hex},
+    },
+};
+
+// 历史目录位于配置目录下；每个配置一个 JSON Lines 文件，扩展名不是配置格式，不会被当作配置加载
+pub const HISTORY_DIR: &str = ".history";
+
+// 按配置保存最近 limit 个版本，limit 为 0 时不记录
+#[derive(Debug, Clone)]
+pub struct FileHistoryStore {
+    dir: PathBuf,
+    limit: usize,
+}
+
+impl FileHistoryStore {
+    pub fn new(config_path: &str, limit: usize) -> Self {
+        Self {
+            dir: Path::new(config_path).join(HISTORY_DIR),
+            limit,
+        }
+    }
+
+    fn history_file(&self, file: &str) -> PathBuf {
+        self.dir.join(format!("{}.jsonl", file))
+    }
+}
+
+impl HistoryStore for FileHistoryStore {
+    fn list(&self, file: &str) -> Result<Vec<ConfigVersion>, ConfigError> {
+        let path = self.history_file(file);
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        std::fs::read_to_string(&path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line).map_err(|e| ConfigError::InvalidHistory {
+                    path: path.display().to_string(),
+                    error: e.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    fn record(
+        &self,
+        file: &str,
+        content: String,
+        saved_at: DateTime<Utc>,
+    ) -> Result<Option<u64>, ConfigError> {
+        if self.limit == 0 {
+            return Ok(None);
+        }
+        let mut versions = self.list(file)?;
+        let sha256 = sha256_hex(content.as_bytes());
+        if versions.last().is_some_and(|last| last.sha256 == sha256) {
+            return Ok(None);
+        }
+        let version = versions.last().map_or(1, |last| last.version + 1);
+        versions.push(ConfigVersion {
+            version,
+            saved_at,
+            size: content.len(),
+            sha256,
+            content,
+        });
+        let skip = versions.len().saturating_sub(self.limit);
+
+        let mut lines = String::new();
+        for version in versions.iter().skip(skip) {
+            let line = serde_json::to_string(version).map_err(|_| ConfigError::ParseConfigError)?;
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+        // 命名空间内的配置按子目录存放
+        let history_file = self.history_file(file);
+        std::fs::create_dir_all(history_file.parent().unwrap_or(&self.dir))?;
+        atomic_write(history_file, lines)?;
+        Ok(Some(version))
+    }
+
+    // 不记录历史时不读取文件
+    fn record_file(
+        &self,
+        file: &str,
+        path: &Path,
+        saved_at: DateTime<Utc>,
+    ) -> Result<Option<u64>, ConfigError> {
+        if self.limit == 0 {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(path)?;
+        self.record_content(file, content, saved_at)
+    }
+}
@@ -0,0 +1,77 @@
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+// 超过该数量时清理已经攒满的桶，避免大量一次性客户端占用内存
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+// 按客户端的令牌桶限流：每秒补充 rate 个令牌，最多积攒 burst 个。
+// 自带锁，不经过 AppState，被限流的请求不会再去争用全局状态
+#[derive(Debug)]
+pub struct RateLimiter {
+    rate: f64,
+    burst: f64,
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl RateLimiter {
+    // 未指定 burst 时允许积攒一秒的请求量
+    pub fn new(rate: f64, burst: Option<u32>) -> Self {
+        let burst = burst.map(f64::from).unwrap_or(rate.ceil()).max(1.0);
+        Self {
+            rate,
+            burst,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // 设置热更新时限额不变则沿用原来的限流器，已消耗的令牌不会被重置
+    pub fn same_limits(&self, other: &RateLimiter) -> bool {
+        self.rate == other.rate && self.burst == other.burst
+    }
+
+    // 携带 API key 时按 key 计数，否则按客户端 IP
+    pub fn client_key(api_key: Option<&str>, address: Option<SocketAddr>) -> String {
+        match (api_key, address) {
+            (Some(api_key), _) => format!("key:{}", api_key),
+            (None, Some(address)) => format!("ip:{}", address.ip()),
+            (None, None) => "unknown".to_string(),
+        }
+    }
+
+    // 取走一个令牌；桶已空时返回下一个令牌可用前需要等待的时长
+    pub fn check(&self, client: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() > MAX_TRACKED_CLIENTS {
+            buckets.retain(|_, bucket| self.refill(bucket, now) < self.burst);
+        }
+        let bucket = buckets.entry(client.to_string()).or_insert(Bucket {
+            tokens: self.burst,
+            updated: now,
+        });
+        let tokens = self.refill(bucket, now);
+        bucket.tokens = tokens;
+        bucket.updated = now;
+        if tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - tokens) / self.rate))
+        }
+    }
+
+    fn refill(&self, bucket: &Bucket, now: Instant) -> f64 {
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        (bucket.tokens + elapsed * self.rate).min(self.burst)
+    }
+}
@@ -1,6 +1,6 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, DurationRound, Utc};
 use serde::{Deserialize, Serialize};
 use tokio::{
     fs::{File, OpenOptions},
@@ -14,25 +14,74 @@ pub struct Log {
     pub timestamp: DateTime<Utc>,
 }
 
+// 日志格式：text 为 [时间]:[级别]:消息，json 每行一个 JSON 对象，便于日志采集
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+// 按时间切分日志文件的周期（UTC 整点 / 零点）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum RotationPeriod {
+    Hourly,
+    Daily,
+}
+
+impl RotationPeriod {
+    fn next(self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let period = match self {
+            RotationPeriod::Hourly => Duration::hours(1),
+            RotationPeriod::Daily => Duration::days(1),
+        };
+        now.duration_trunc(period).unwrap_or(now) + period
+    }
+}
+
+// 文件超过 max_size 字节或到达 period 边界时切分，切分出的文件名为 <file>.<时间>，
+// 只保留最近 keep 个
+#[derive(Debug, Clone, Default)]
+pub struct LogRotation {
+    pub max_size: Option<u64>,
+    pub period: Option<RotationPeriod>,
+    pub keep: usize,
+}
+
 pub struct LogConfig {
     pub file: String,
     pub level: String,
+    pub format: LogFormat,
+    pub rotation: LogRotation,
 }
 
 pub struct LogManager {
     pub config: LogConfig,
     pub writer: BufWriter<File>,
+    // 当前文件已写入的字节数与下一次按时间切分的时刻
+    size: u64,
+    next_rotation: Option<DateTime<Utc>>,
 }
 
 impl LogManager {
-    pub async fn new(config: LogConfig) -> Self {
-        let file_path = Path::new(&config.file);
-        if !file_path.exists() {
-            File::create(file_path).await.unwrap();
+    pub async fn new(config: LogConfig) -> std::io::Result<Self> {
+        let (writer, size) = Self::open(Path::new(&config.file)).await?;
+        let next_rotation = config.rotation.period.map(|period| period.next(Utc::now()));
+        Ok(Self {
+            config,
+            writer,
+            size,
+            next_rotation,
+        })
+    }
+
+    async fn open(file_path: &Path) -> std::io::Result<(BufWriter<File>, u64)> {
+        if let Some(dir) = file_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(dir).await?;
         }
-        let file = OpenOptions::new().append(true).open(file_path).await.unwrap();
-        let writer = BufWriter::new(file);
-        Self { config, writer }
+        let file = OpenOptions::new().create(true).append(true).open(file_path).await?;
+        let size = file.metadata().await.map(|meta| meta.len()).unwrap_or_default();
+        Ok((BufWriter::new(file), size))
     }
 
     pub async fn log_info(&mut self, message: String) {
@@ -89,16 +138,74 @@ impl LogManager {
             {
                 self.write_log(log).await;
             }
-        } else if self.config.level == "warn" {
-            if log.level == "warn" || log.level == "error" {
-                self.write_log(log).await;
-            }
+        } else if self.config.level == "warn"
+            && (log.level == "warn" || log.level == "error")
+        {
+            self.write_log(log).await;
+        }
+    }
+
+    pub async fn flush(&mut self) {
+        if let Err(e) = self.writer.flush().await {
+            tracing::error!("flush log file failed: {}", e);
         }
     }
 
     pub async fn write_log(&mut self, log: Log) {
-        let log_str = format!("[{}]:[{}]:{}\n", log.timestamp.format("%Y-%m-%d %H:%M:%S"), log.level.to_uppercase(), log.message);
+        let log_str = match self.config.format {
+            LogFormat::Text => format!("[{}]:[{}]:{}\n", log.timestamp.format("%Y-%m-%d %H:%M:%S"), log.level.to_uppercase(), log.message),
+            LogFormat::Json => format!("{}\n", serde_json::to_string(&log).unwrap()),
+        };
+        let oversized = self
+            .config
+            .rotation
+            .max_size
+            .is_some_and(|max_size| self.size > 0 && self.size + log_str.len() as u64 > max_size);
+        let due = self.next_rotation.is_some_and(|next| log.timestamp >= next);
+        if (oversized || due)
+            && let Err(e) = self.rotate().await
+        {
+            tracing::error!("rotate log file {} failed: {}", self.config.file, e);
+        }
         self.writer.write_all(log_str.as_bytes()).await.unwrap();
         self.writer.flush().await.unwrap();
+        self.size += log_str.len() as u64;
+    }
+
+    // 当前文件改名为 <file>.<时间> 后重新打开，再删除超出保留数量的旧文件；
+    // 新文件打不开时改回原名，继续使用原来的句柄
+    async fn rotate(&mut self) -> std::io::Result<()> {
+        self.writer.flush().await?;
+        let file_path = PathBuf::from(&self.config.file);
+        let rotated = format!("{}.{}", self.config.file, Utc::now().format("%Y%m%dT%H%M%S%3f"));
+        tokio::fs::rename(&file_path, &rotated).await?;
+        match Self::open(&file_path).await {
+            Ok((writer, size)) => (self.writer, self.size) = (writer, size),
+            Err(e) => {
+                let _ = tokio::fs::rename(&rotated, &file_path).await;
+                return Err(e);
+            }
+        }
+        self.next_rotation = self.config.rotation.period.map(|period| period.next(Utc::now()));
+
+        let dir = match file_path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        let prefix = format!("{}.", file_path.file_name().unwrap_or_default().to_string_lossy());
+        let mut rotated_files = Vec::new();
+        let mut entries = tokio::fs::read_dir(&dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                rotated_files.push(entry.path());
+            }
+        }
+        // 时间后缀按字典序即按时间排序
+        rotated_files.sort();
+        let excess = rotated_files.len().saturating_sub(self.config.rotation.keep);
+        for path in rotated_files.into_iter().take(excess) {
+            tokio::fs::remove_file(path).await?;
+        }
+        Ok(())
     }
 }
@@ -1,5 +1,13 @@
+pub mod audit;
+pub mod backup;
+pub mod history;
+pub mod limits;
 pub mod logging;
 pub mod notification;
+pub mod privilege;
 pub mod repositories;
+pub mod s3;
+pub mod schedules;
 pub mod serializers;
-pub mod watchers;
\ No newline at end of file
+pub mod sources;
+pub mod watchers;
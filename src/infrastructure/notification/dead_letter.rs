@@ -0,0 +1,73 @@
+use std::collections::VecDeque;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::domain::events::config_changed::ConfigUpdate;
+
+pub const DEFAULT_DEAD_LETTER_CAPACITY: usize = 1000;
+
+// 投递目标：通知 sink（id 带 webhook、kafka 等类型前缀）或订阅者连接
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "id", rename_all = "snake_case")]
+pub enum DeliveryTarget {
+    Sink(String),
+    Subscriber(String),
+}
+
+impl std::fmt::Display for DeliveryTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeliveryTarget::Sink(id) => write!(f, "{}", id),
+            DeliveryTarget::Subscriber(id) => write!(f, "subscriber:{}", id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub id: String,
+    pub target: DeliveryTarget,
+    pub update: ConfigUpdate,
+    pub attempts: u32,
+    pub last_error: String,
+    pub failed_at: DateTime<Utc>,
+}
+
+// 重试耗尽的通知，容量满时淘汰最旧的记录
+#[derive(Debug)]
+pub struct DeadLetterQueue {
+    capacity: usize,
+    entries: VecDeque<DeadLetter>,
+}
+
+impl Default for DeadLetterQueue {
+    fn default() -> Self {
+        Self::new(DEFAULT_DEAD_LETTER_CAPACITY)
+    }
+}
+
+impl DeadLetterQueue {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: VecDeque::new(),
+        }
+    }
+
+    pub fn push(&mut self, letter: DeadLetter) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(letter);
+    }
+
+    pub fn list(&self) -> Vec<DeadLetter> {
+        self.entries.iter().cloned().collect()
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<DeadLetter> {
+        let index = self.entries.iter().position(|l| l.id == id)?;
+        self.entries.remove(index)
+    }
+}
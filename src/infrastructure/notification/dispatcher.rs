@@ -0,0 +1,107 @@
+use std::sync::{Arc, Weak};
+
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tracing::info;
+
+use crate::{
+    domain::{
+        events::config_changed::{ConfigChange, ConfigUpdate},
+        repositories::notification_sink::NotificationSink,
+    },
+    infrastructure::notification::{dead_letter::DeliveryTarget, subscriber_sink::SubscriberSink},
+    shared::{app_state::AppState, error::ConfigError},
+};
+
+// 将更新放入订阅者和各通知 sink（webhook、消息总线）的投递队列，失败的投递进入死信队列；
+// 返回订阅者数量
+pub fn dispatch(app_state: &Arc<AppState>, change: ConfigChange) -> usize {
+    app_state.sync_ttls(&change);
+    let (update, count) = app_state.publish(change);
+
+    for queue in sink_queues(app_state) {
+        let _ = queue.send(update.clone());
+    }
+    count
+}
+
+// 每个 sink 一个长期运行的投递任务，同一 sink 按发布顺序收到更新，慢的 sink 不会拖住其他 sink
+fn sink_queues(app_state: &Arc<AppState>) -> &Vec<UnboundedSender<ConfigUpdate>> {
+    app_state.sink_queues.get_or_init(|| {
+        let subscribers: Arc<dyn NotificationSink> = Arc::new(SubscriberSink::new(app_state));
+        std::iter::once(subscribers)
+            .chain(app_state.notification_sinks.iter().cloned())
+            .map(|sink| {
+                let (sender, receiver) = mpsc::unbounded_channel();
+                tokio::spawn(deliver(Arc::downgrade(app_state), sink, receiver));
+                sender
+            })
+            .collect()
+    })
+}
+
+async fn deliver(
+    app_state: Weak<AppState>,
+    sink: Arc<dyn NotificationSink>,
+    mut updates: UnboundedReceiver<ConfigUpdate>,
+) {
+    while let Some(update) = updates.recv().await {
+        if let Err((attempts, error)) = sink.publish(&update).await {
+            info!(
+                "{} failed after {} attempts: {}",
+                sink.id(),
+                attempts,
+                error
+            );
+            let Some(app_state) = app_state.upgrade() else {
+                return;
+            };
+            app_state.dead_letter(DeliveryTarget::Sink(sink.id()), update, attempts, error);
+        }
+    }
+}
+
+// 重新投递死信；失败时放回队列并累计尝试次数
+pub async fn replay(app_state: &Arc<AppState>, id: &str) -> Result<(), ConfigError> {
+    let letter = app_state
+        .dead_letters
+        .lock()
+        .unwrap()
+        .remove(id)
+        .ok_or(ConfigError::KeyNotFound)?;
+
+    let result = match &letter.target {
+        DeliveryTarget::Sink(id) => {
+            let sink = app_state
+                .notification_sinks
+                .iter()
+                .find(|sink| sink.id() == *id);
+            match sink {
+                Some(sink) => sink.publish(&letter.update).await,
+                None => Err((1, format!("{} is no longer configured", id))),
+            }
+        }
+        DeliveryTarget::Subscriber(client_id) => {
+            match app_state.subscriber(client_id) {
+                Some(sender) => sender
+                    .send(letter.update.clone())
+                    .map_err(|_| (1, "subscriber channel closed".to_string())),
+                None => Err((1, format!("subscriber {} is not connected", client_id))),
+            }
+        }
+    };
+
+    match result {
+        Ok(()) => {
+            info!("dead letter {} replayed to {}", letter.id, letter.target);
+            Ok(())
+        }
+        Err((attempts, error)) => {
+            let mut letter = letter;
+            letter.attempts += attempts;
+            letter.last_error = error.clone();
+            letter.failed_at = app_state.clock.now();
+            app_state.dead_letters.lock().unwrap().push(letter);
+            Err(ConfigError::RemoteRequestFailed(error))
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::{collections::BTreeMap, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use rskafka::{
+    client::{
+        ClientBuilder,
+        partition::{Compression, PartitionClient, UnknownTopicHandling},
+    },
+    record::Record,
+};
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::domain::{
+    events::config_changed::ConfigUpdate, repositories::notification_sink::NotificationSink,
+};
+
+const KAFKA_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 把更新（JSON）写入 Kafka topic 的指定分区，以配置名称为消息 key；
+// 分区客户端复用，写入失败时重新连接一次
+pub struct KafkaSink {
+    brokers: Vec<String>,
+    topic: String,
+    partition: i32,
+    client: Mutex<Option<Arc<PartitionClient>>>,
+}
+
+impl KafkaSink {
+    pub fn new(brokers: Vec<String>, topic: String, partition: i32) -> Self {
+        Self {
+            brokers,
+            topic,
+            partition,
+            client: Mutex::new(None),
+        }
+    }
+
+    async fn connect(&self) -> rskafka::client::error::Result<Arc<PartitionClient>> {
+        let client = ClientBuilder::new(self.brokers.clone()).build().await?;
+        let partition = client
+            .partition_client(
+                self.topic.clone(),
+                self.partition,
+                UnknownTopicHandling::Error,
+            )
+            .await?;
+        Ok(Arc::new(partition))
+    }
+}
+
+#[async_trait]
+impl NotificationSink for KafkaSink {
+    fn id(&self) -> String {
+        format!(
+            "kafka:{}/{}/{}",
+            self.brokers.join(","),
+            self.topic,
+            self.partition
+        )
+    }
+
+    async fn publish(&self, update: &ConfigUpdate) -> Result<(), (u32, String)> {
+        let payload = serde_json::to_vec(update).map_err(|e| (1, e.to_string()))?;
+        let mut client = self.client.lock().await;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let record = Record {
+                key: Some(update.file.clone().into_bytes()),
+                value: Some(payload.clone()),
+                headers: BTreeMap::new(),
+                timestamp: update.timestamp,
+            };
+            // rskafka 在 broker 不可用时会持续退避重试，用超时限制单次投递的时长
+            let result = tokio::time::timeout(KAFKA_TIMEOUT, async {
+                let partition = match client.take() {
+                    Some(partition) => partition,
+                    None => self.connect().await.map_err(|e| e.to_string())?,
+                };
+                partition
+                    .produce(vec![record], Compression::NoCompression)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok::<_, String>(partition)
+            })
+            .await
+            .unwrap_or_else(|_| Err("timed out".to_string()));
+            match result {
+                Ok(partition) => {
+                    *client = Some(partition);
+                    return Ok(());
+                }
+                Err(e) if attempts < 2 => debug!("kafka publish to {} failed: {}", self.topic, e),
+                Err(e) => return Err((attempts, e)),
+            }
+        }
+    }
+}
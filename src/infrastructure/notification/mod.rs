@@ -0,0 +1,9 @@
+pub mod dead_letter;
+pub mod dispatcher;
+pub mod kafka_sink;
+pub mod nats_sink;
+pub mod notification_sink_factory;
+pub mod redis_sink;
+pub mod subscriber_sink;
+pub mod subscriber_quota;
+pub mod webhook;
@@ -0,0 +1,111 @@
+use std::{io, time::Duration};
+
+use async_trait::async_trait;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    sync::Mutex,
+};
+use tracing::debug;
+
+use crate::domain::{
+    events::config_changed::ConfigUpdate, repositories::notification_sink::NotificationSink,
+};
+
+const NATS_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 通过 NATS 文本协议把更新（JSON）发布到 subject；连接复用，断开后重连一次
+pub struct NatsSink {
+    address: String,
+    subject: String,
+    connection: Mutex<Option<BufReader<TcpStream>>>,
+}
+
+impl NatsSink {
+    pub fn new(address: String, subject: String) -> Self {
+        Self {
+            address,
+            subject,
+            connection: Mutex::new(None),
+        }
+    }
+
+    // 服务端先发送 INFO，客户端以 CONNECT 回应；关闭 verbose，发布成功时不回复 +OK
+    async fn connect(&self) -> io::Result<BufReader<TcpStream>> {
+        let mut stream = BufReader::new(TcpStream::connect(&self.address).await?);
+        let mut line = String::new();
+        stream.read_line(&mut line).await?;
+        if !line.starts_with("INFO") {
+            return Err(io::Error::other(format!(
+                "unexpected greeting: {}",
+                line.trim()
+            )));
+        }
+        let connect =
+            "CONNECT {\"verbose\":false,\"pedantic\":false,\"name\":\"config-manager\"}\r\n";
+        stream.get_mut().write_all(connect.as_bytes()).await?;
+        Ok(stream)
+    }
+
+    // PUB 之后发送 PING，收到 PONG 说明服务端已处理完这条消息
+    async fn send(
+        stream: &mut BufReader<TcpStream>,
+        subject: &str,
+        payload: &[u8],
+    ) -> io::Result<()> {
+        let mut frame = format!("PUB {} {}\r\n", subject, payload.len()).into_bytes();
+        frame.extend_from_slice(payload);
+        frame.extend_from_slice(b"\r\nPING\r\n");
+        stream.get_mut().write_all(&frame).await?;
+        loop {
+            let mut line = String::new();
+            if stream.read_line(&mut line).await? == 0 {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            match line.trim_end() {
+                "PONG" => return Ok(()),
+                "PING" => stream.get_mut().write_all(b"PONG\r\n").await?,
+                error if error.starts_with("-ERR") => {
+                    return Err(io::Error::other(error.to_string()));
+                }
+                // +OK 或服务端更新的 INFO
+                _ => {}
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for NatsSink {
+    fn id(&self) -> String {
+        format!("nats:{}/{}", self.address, self.subject)
+    }
+
+    async fn publish(&self, update: &ConfigUpdate) -> Result<(), (u32, String)> {
+        let payload = serde_json::to_vec(update).map_err(|e| (1, e.to_string()))?;
+        let mut connection = self.connection.lock().await;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let result = tokio::time::timeout(NATS_TIMEOUT, async {
+                let mut stream = match connection.take() {
+                    Some(stream) => stream,
+                    None => self.connect().await?,
+                };
+                Self::send(&mut stream, &self.subject, &payload).await?;
+                Ok::<_, io::Error>(stream)
+            })
+            .await
+            .unwrap_or_else(|_| Err(io::ErrorKind::TimedOut.into()));
+            match result {
+                Ok(stream) => {
+                    *connection = Some(stream);
+                    return Ok(());
+                }
+                // 复用的连接可能已被服务端关闭，重新连接后再试一次
+                Err(e) if attempts < 2 => debug!("nats publish to {} failed: {}", self.address, e),
+                Err(e) => return Err((attempts, e.to_string())),
+            }
+        }
+    }
+}
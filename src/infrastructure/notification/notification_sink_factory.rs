@@ -0,0 +1,35 @@
+use std::sync::Arc;
+
+use crate::{
+    domain::repositories::notification_sink::NotificationSink,
+    infrastructure::notification::{
+        kafka_sink::KafkaSink, nats_sink::NatsSink, redis_sink::RedisSink,
+    },
+    shared::config::NotificationSettings,
+};
+
+pub struct NotificationSinkFactory;
+
+impl NotificationSinkFactory {
+    pub fn create(settings: &NotificationSettings) -> Arc<dyn NotificationSink> {
+        match settings {
+            NotificationSettings::Kafka {
+                brokers,
+                topic,
+                partition,
+            } => Arc::new(KafkaSink::new(brokers.clone(), topic.clone(), *partition)),
+            NotificationSettings::Nats { address, subject } => {
+                Arc::new(NatsSink::new(address.clone(), subject.clone()))
+            }
+            NotificationSettings::Redis {
+                address,
+                channel,
+                password,
+            } => Arc::new(RedisSink::new(
+                address.clone(),
+                channel.clone(),
+                password.clone(),
+            )),
+        }
+    }
+}
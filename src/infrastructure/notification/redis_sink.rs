@@ -0,0 +1,97 @@
+use std::{io, time::Duration};
+
+use async_trait::async_trait;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+    sync::Mutex,
+};
+use tracing::debug;
+
+use crate::domain::{
+    events::config_changed::ConfigUpdate, repositories::notification_sink::NotificationSink,
+};
+
+const REDIS_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 通过 RESP 协议的 PUBLISH 把更新（JSON）发布到 Redis channel；连接复用，断开后重连一次
+pub struct RedisSink {
+    address: String,
+    channel: String,
+    password: Option<String>,
+    connection: Mutex<Option<BufReader<TcpStream>>>,
+}
+
+impl RedisSink {
+    pub fn new(address: String, channel: String, password: Option<String>) -> Self {
+        Self {
+            address,
+            channel,
+            password,
+            connection: Mutex::new(None),
+        }
+    }
+
+    async fn connect(&self) -> io::Result<BufReader<TcpStream>> {
+        let mut stream = BufReader::new(TcpStream::connect(&self.address).await?);
+        if let Some(password) = &self.password {
+            Self::command(&mut stream, &[b"AUTH", password.as_bytes()]).await?;
+        }
+        Ok(stream)
+    }
+
+    // 以 RESP 数组发送命令并读取单行回复（+OK 或 :<订阅者数>），错误回复以 "-" 开头
+    async fn command(stream: &mut BufReader<TcpStream>, args: &[&[u8]]) -> io::Result<String> {
+        let mut frame = format!("*{}\r\n", args.len()).into_bytes();
+        for arg in args {
+            frame.extend_from_slice(format!("${}\r\n", arg.len()).as_bytes());
+            frame.extend_from_slice(arg);
+            frame.extend_from_slice(b"\r\n");
+        }
+        stream.get_mut().write_all(&frame).await?;
+        let mut line = String::new();
+        if stream.read_line(&mut line).await? == 0 {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        match line.trim_end() {
+            error if error.starts_with('-') => Err(io::Error::other(error[1..].to_string())),
+            reply => Ok(reply.to_string()),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for RedisSink {
+    fn id(&self) -> String {
+        format!("redis:{}/{}", self.address, self.channel)
+    }
+
+    async fn publish(&self, update: &ConfigUpdate) -> Result<(), (u32, String)> {
+        let payload = serde_json::to_vec(update).map_err(|e| (1, e.to_string()))?;
+        let mut connection = self.connection.lock().await;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let result = tokio::time::timeout(REDIS_TIMEOUT, async {
+                let mut stream = match connection.take() {
+                    Some(stream) => stream,
+                    None => self.connect().await?,
+                };
+                let args: [&[u8]; 3] = [b"PUBLISH", self.channel.as_bytes(), &payload];
+                Self::command(&mut stream, &args).await?;
+                Ok::<_, io::Error>(stream)
+            })
+            .await
+            .unwrap_or_else(|_| Err(io::ErrorKind::TimedOut.into()));
+            match result {
+                Ok(stream) => {
+                    *connection = Some(stream);
+                    return Ok(());
+                }
+                // 复用的连接可能已被服务端关闭，重新连接后再试一次
+                Err(e) if attempts < 2 => debug!("redis publish to {} failed: {}", self.address, e),
+                Err(e) => return Err((attempts, e.to_string())),
+            }
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+// 超出带宽上限时的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum QuotaAction {
+    Throttle,
+    Disconnect,
+}
+
+// 单个订阅者的带宽与单条消息大小限制
+#[derive(Debug, Clone)]
+pub struct BandwidthQuota {
+    pub bytes_per_sec: Option<u64>,
+    pub max_payload: Option<u64>,
+    pub action: QuotaAction,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum QuotaDecision {
+    Allow,
+    Delay(std::time::Duration),
+    Disconnect(String),
+}
+
+// 订阅者推送统计，按 1 秒窗口计算带宽；一个连接的所有订阅共用同一份配额
+#[derive(Debug, Clone, Serialize)]
+pub struct SubscriberUsage {
+    // 订阅的文件 -> 最后一次拿到该文件最新配置的时间
+    pub files: BTreeMap<String, Option<DateTime<Utc>>>,
+    pub transport: String,
+    pub bytes_pushed: u64,
+    pub messages_pushed: u64,
+    pub throttled: u64,
+    pub connected_at: DateTime<Utc>,
+    #[serde(skip)]
+    window_start: DateTime<Utc>,
+    #[serde(skip)]
+    window_bytes: u64,
+}
+
+impl SubscriberUsage {
+    pub fn new(transport: &str, now: DateTime<Utc>) -> Self {
+        Self {
+            files: BTreeMap::new(),
+            transport: transport.to_string(),
+            bytes_pushed: 0,
+            messages_pushed: 0,
+            throttled: 0,
+            connected_at: now,
+            window_start: now,
+            window_bytes: 0,
+        }
+    }
+
+    // 记录一次推送并给出配额判定；被延迟的消息计入下一个窗口
+    pub fn account(
+        &mut self,
+        bytes: u64,
+        quota: &BandwidthQuota,
+        now: DateTime<Utc>,
+    ) -> QuotaDecision {
+        if let Some(max_payload) = quota.max_payload
+            && bytes > max_payload
+        {
+            return QuotaDecision::Disconnect(format!(
+                "payload of {} bytes exceeds limit of {} bytes",
+                bytes, max_payload
+            ));
+        }
+
+        let window = Duration::seconds(1);
+        if now >= self.window_start + window {
+            self.window_start = now;
+            self.window_bytes = 0;
+        }
+
+        let mut decision = QuotaDecision::Allow;
+        if let Some(bytes_per_sec) = quota.bytes_per_sec
+            && self.window_bytes > 0
+            && self.window_bytes + bytes > bytes_per_sec
+        {
+            match quota.action {
+                QuotaAction::Disconnect => {
+                    return QuotaDecision::Disconnect(format!(
+                        "bandwidth limit of {} bytes/s exceeded",
+                        bytes_per_sec
+                    ));
+                }
+                QuotaAction::Throttle => {
+                    self.window_start += window;
+                    self.window_bytes = 0;
+                    self.throttled += 1;
+                    let wait = (self.window_start - now).to_std().unwrap_or_default();
+                    decision = QuotaDecision::Delay(wait);
+                }
+            }
+        }
+
+        self.window_bytes += bytes;
+        self.bytes_pushed += bytes;
+        self.messages_pushed += 1;
+        decision
+    }
+}
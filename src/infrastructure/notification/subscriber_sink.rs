@@ -0,0 +1,50 @@
+use std::sync::{Arc, Weak};
+
+use async_trait::async_trait;
+use tracing::debug;
+
+use crate::{
+    domain::{events::config_changed::ConfigUpdate, repositories::notification_sink::NotificationSink},
+    infrastructure::notification::dead_letter::DeliveryTarget,
+    shared::app_state::AppState,
+};
+
+pub const SUBSCRIBER_SINK_ID: &str = "subscribers";
+
+// 把更新写入订阅了该文件的 TCP/WebSocket 连接；通道已关闭的订阅者单独进入死信队列
+pub struct SubscriberSink {
+    app_state: Weak<AppState>,
+}
+
+impl SubscriberSink {
+    pub fn new(app_state: &Arc<AppState>) -> Self {
+        Self {
+            app_state: Arc::downgrade(app_state),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for SubscriberSink {
+    fn id(&self) -> String {
+        SUBSCRIBER_SINK_ID.to_string()
+    }
+
+    async fn publish(&self, update: &ConfigUpdate) -> Result<(), (u32, String)> {
+        let Some(app_state) = self.app_state.upgrade() else {
+            return Err((1, "server is shutting down".to_string()));
+        };
+        for (client_id, sender, client_update) in app_state.subscribers_for(update) {
+            if let Err(e) = sender.send(client_update) {
+                debug!("send config to client {} failed, maybe client is closed", client_id);
+                app_state.dead_letter(
+                    DeliveryTarget::Subscriber(client_id),
+                    e.0,
+                    1,
+                    "subscriber channel closed".to_string(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
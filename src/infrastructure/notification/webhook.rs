@@ -0,0 +1,60 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use tracing::debug;
+
+use crate::domain::{
+    events::config_changed::ConfigUpdate, repositories::notification_sink::NotificationSink,
+};
+
+// 以 JSON POST 的方式投递配置更新，失败时指数退避重试
+#[derive(Debug, Clone)]
+pub struct WebhookNotifier {
+    client: reqwest::Client,
+    pub url: String,
+    pub max_retries: u32,
+    pub backoff: Duration,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String, max_retries: u32) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .unwrap_or_default(),
+            url,
+            max_retries,
+            backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationSink for WebhookNotifier {
+    fn id(&self) -> String {
+        format!("webhook:{}", self.url)
+    }
+
+    async fn publish(&self, update: &ConfigUpdate) -> Result<(), (u32, String)> {
+        let mut attempts = 0;
+        let mut delay = self.backoff;
+        loop {
+            attempts += 1;
+            let error = match self.client.post(&self.url).json(update).send().await {
+                Ok(response) if response.status().is_success() => return Ok(()),
+                Ok(response) => format!("HTTP {}", response.status()),
+                Err(e) => e.to_string(),
+            };
+            debug!(
+                "webhook {} attempt {} failed: {}",
+                self.url, attempts, error
+            );
+            if attempts > self.max_retries {
+                return Err((attempts, error));
+            }
+            tokio::time::sleep(delay).await;
+            delay *= 2;
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod privilege_drop;
@@ -0,0 +1,123 @@
+use tracing::info;
+
+use crate::shared::error::ConfigError;
+
+// serve 在绑定端口、准备好配置目录之后放弃的权限
+#[derive(Debug, Clone, Default)]
+pub struct PrivilegeDrop {
+    pub user: Option<String>,
+    pub group: Option<String>,
+    pub chroot: Option<String>,
+}
+
+impl PrivilegeDrop {
+    pub fn is_empty(&self) -> bool {
+        self.user.is_none() && self.group.is_none() && self.chroot.is_none()
+    }
+
+    // chroot 之后仍按宿主机路径重新打开文件的功能无法继续工作，启动时直接拒绝，而不是运行中才失败
+    pub fn reject_host_paths(&self, features: &[&str]) -> Result<(), ConfigError> {
+        match &self.chroot {
+            Some(root) if !features.is_empty() => Err(ConfigError::PrivilegeDropFailed(format!(
+                "--chroot {} cannot be combined with {}, which reopen files by their host path",
+                root,
+                features.join(", ")
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    // 返回 chroot 之后配置目录的新路径；未配置 chroot 时返回 None
+    #[cfg(unix)]
+    pub fn apply(&self, config_path: &str) -> Result<Option<String>, ConfigError> {
+        use std::path::Path;
+
+        use nix::unistd::{self, Gid, Group, Uid, User};
+
+        if self.is_empty() {
+            return Ok(None);
+        }
+        let failed = |step: &str, e: nix::Error| {
+            ConfigError::PrivilegeDropFailed(format!("{}: {}", step, e))
+        };
+
+        // 用户和组必须在 chroot 之前解析，chroot 之后通常读不到 /etc/passwd
+        let user = match &self.user {
+            Some(user) => Some(match user.parse::<u32>() {
+                Ok(uid) => User::from_uid(Uid::from_raw(uid)),
+                Err(_) => User::from_name(user),
+            }
+            .map_err(|e| failed("lookup user", e))?
+            .ok_or_else(|| ConfigError::PrivilegeDropFailed(format!("unknown user {}", user)))?),
+            None => None,
+        };
+        let gid = match &self.group {
+            Some(group) => match group.parse::<u32>() {
+                Ok(gid) => Gid::from_raw(gid),
+                Err(_) => Group::from_name(group)
+                    .map_err(|e| failed("lookup group", e))?
+                    .ok_or_else(|| {
+                        ConfigError::PrivilegeDropFailed(format!("unknown group {}", group))
+                    })?
+                    .gid,
+            },
+            // 只指定用户时使用该用户的主组
+            None => match &user {
+                Some(user) => user.gid,
+                None => Gid::effective(),
+            },
+        };
+
+        let mut new_config_path = None;
+        if let Some(root) = &self.chroot {
+            let root = std::fs::canonicalize(root)?;
+            let config_path = std::fs::canonicalize(config_path)?;
+            let relative = config_path.strip_prefix(&root).map_err(|_| {
+                ConfigError::PrivilegeDropFailed(format!(
+                    "config path {} is outside chroot {}",
+                    config_path.display(),
+                    root.display()
+                ))
+            })?;
+            unistd::chroot(&root).map_err(|e| failed("chroot", e))?;
+            unistd::chdir("/").map_err(|e| failed("chdir", e))?;
+            let inside = Path::new("/").join(relative);
+            info!("chroot to {}, config path is now {}", root.display(), inside.display());
+            new_config_path = Some(inside.to_string_lossy().to_string());
+        }
+
+        // 先丢弃附加组和组，再切换用户，否则切换用户后无权修改组
+        if Uid::effective().is_root() {
+            unistd::setgroups(&[gid]).map_err(|e| failed("setgroups", e))?;
+        }
+        unistd::setgid(gid).map_err(|e| failed("setgid", e))?;
+        if let Some(user) = &user {
+            unistd::setuid(user.uid).map_err(|e| failed("setuid", e))?;
+            if unistd::setuid(Uid::from_raw(0)).is_ok() && !user.uid.is_root() {
+                return Err(ConfigError::PrivilegeDropFailed(
+                    "still able to regain root after setuid".to_string(),
+                ));
+            }
+        }
+        #[cfg(target_os = "linux")]
+        nix::sys::prctl::set_no_new_privs().map_err(|e| failed("set no_new_privs", e))?;
+
+        info!(
+            "privileges dropped, running as uid {} gid {}",
+            Uid::effective(),
+            Gid::effective()
+        );
+        Ok(new_config_path)
+    }
+
+    #[cfg(not(unix))]
+    pub fn apply(&self, _config_path: &str) -> Result<Option<String>, ConfigError> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        Err(ConfigError::PrivilegeDropFailed(
+            "dropping privileges is only supported on unix".to_string(),
+        ))
+    }
+}
+
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use crate::{
+    domain::repositories::{
+        audit_sink::AuditSink, config_store::ConfigStore, history_store::HistoryStore,
+    },
+    infrastructure::repositories::{
+        consul_config_repository::ConsulConfigRepository,
+        etcd_config_repository::EtcdConfigRepository, s3_config_repository::S3ConfigRepository,
+        sqlite_config_repository::SqliteConfigRepository,
+    },
+    shared::{config::StorageBackend, error::ConfigError},
+};
+#[cfg(feature = "k8s-sync")]
+use crate::infrastructure::repositories::kubernetes_config_repository::KubernetesConfigRepository;
+
+// serve --storage 选择的后端；SQLite 同时保存历史版本和审计记录
+pub struct StorageBackends {
+    pub store: Arc<dyn ConfigStore>,
+    pub history: Option<Arc<dyn HistoryStore>>,
+    pub audit: Option<Arc<dyn AuditSink>>,
+}
+
+pub struct ConfigStoreFactory;
+
+impl ConfigStoreFactory {
+    // --storage file 时配置目录就是存储本身，返回 None
+    pub fn create(
+        backend: &StorageBackend,
+        history_limit: usize,
+    ) -> Result<Option<StorageBackends>, ConfigError> {
+        let store: Arc<dyn ConfigStore> = match backend {
+            StorageBackend::File => return Ok(None),
+            StorageBackend::S3(s3) => Arc::new(S3ConfigRepository::new(s3)),
+            StorageBackend::Etcd(etcd) => Arc::new(EtcdConfigRepository::new(etcd)),
+            StorageBackend::Consul(consul) => Arc::new(ConsulConfigRepository::new(consul)),
+            #[cfg(feature = "k8s-sync")]
+            StorageBackend::Kubernetes(namespace) => Arc::new(KubernetesConfigRepository::new(namespace)?),
+            StorageBackend::Sqlite(path) => {
+                let sqlite = Arc::new(SqliteConfigRepository::open(path, history_limit)?);
+                return Ok(Some(StorageBackends {
+                    store: sqlite.clone(),
+                    history: Some(sqlite.clone()),
+                    audit: Some(sqlite),
+                }));
+            }
+        };
+        Ok(Some(StorageBackends {
+            store,
+            history: None,
+            audit: None,
+        }))
+    }
+}
@@ -0,0 +1,166 @@
+use std::{sync::Mutex, time::Duration};
+
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::Value;
+
+use crate::{
+    domain::repositories::config_store::{ConfigStore, StoredConfig},
+    shared::{
+        config::KvSettings,
+        error::ConfigError,
+        utils::{is_config_file, is_valid_config_name},
+    },
+};
+
+const CONSUL_TIMEOUT: Duration = Duration::from_secs(30);
+// blocking query 的最长等待时间，请求超时需要比它长
+const CONSUL_WAIT_SECS: u64 = 60;
+
+// Consul KV 中 <prefix><配置名称> 下的配置，revision 为 ModifyIndex；
+// 用 blocking query 等待前缀下的修改，token 从 CONSUL_HTTP_TOKEN 环境变量读取
+pub struct ConsulConfigRepository {
+    client: reqwest::Client,
+    endpoint: String,
+    prefix: String,
+    token: Option<String>,
+    // 最近一次列举时的 X-Consul-Index，blocking query 从这里开始等待
+    index: Mutex<u64>,
+}
+
+impl ConsulConfigRepository {
+    pub fn new(settings: &KvSettings) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: settings.endpoint.trim_end_matches('/').to_string(),
+            prefix: settings.prefix.clone(),
+            token: std::env::var("CONSUL_HTTP_TOKEN").ok().filter(|token| !token.is_empty()),
+            index: Mutex::new(0),
+        }
+    }
+
+    fn url(&self, key: &str) -> String {
+        format!("{}/v1/kv/{}", self.endpoint, key)
+    }
+
+    // 键不存在时 Consul 返回 404，同样带有 X-Consul-Index，由调用方处理
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        timeout: Duration,
+    ) -> Result<reqwest::Response, ConfigError> {
+        let request = match &self.token {
+            Some(token) => request.header("x-consul-token", token),
+            None => request,
+        };
+        let response = request
+            .timeout(timeout)
+            .send()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?;
+        match response.status() {
+            status if status.is_success() || status == reqwest::StatusCode::NOT_FOUND => Ok(response),
+            status => Err(ConfigError::RemoteRequestFailed(format!(
+                "{}: {}",
+                status,
+                response.text().await.unwrap_or_default()
+            ))),
+        }
+    }
+
+    // 记录响应中的 X-Consul-Index；索引变小说明 Consul 重建过，按 0 重新开始
+    fn update_index(&self, response: &reqwest::Response) {
+        let Some(index) = response
+            .headers()
+            .get("x-consul-index")
+            .and_then(|index| index.to_str().ok())
+            .and_then(|index| index.parse::<u64>().ok())
+        else {
+            return;
+        };
+        let mut current = self.index.lock().unwrap();
+        *current = if index < *current { 0 } else { index };
+    }
+}
+
+#[async_trait]
+impl ConfigStore for ConsulConfigRepository {
+    fn id(&self) -> String {
+        format!("consul://{}/{}", self.endpoint.trim_start_matches("http://"), self.prefix)
+    }
+
+    async fn list(&self) -> Result<Vec<StoredConfig>, ConfigError> {
+        let request = self.client.get(self.url(&self.prefix)).query(&[("recurse", "true")]);
+        let response = self.send(request, CONSUL_TIMEOUT).await?;
+        self.update_index(&response);
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(vec![]);
+        }
+        let entries: Vec<Value> = response
+            .json()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?;
+        Ok(entries
+            .iter()
+            .filter_map(|entry| {
+                let name = entry["Key"].as_str()?.strip_prefix(&self.prefix)?;
+                (is_valid_config_name(name) && is_config_file(name)).then(|| StoredConfig {
+                    name: name.to_string(),
+                    revision: entry["ModifyIndex"].to_string(),
+                })
+            })
+            .collect())
+    }
+
+    async fn get_content(&self, name: &str) -> Result<Option<String>, ConfigError> {
+        let request = self.client.get(self.url(&format!("{}{}", self.prefix, name)));
+        let response = self.send(request, CONSUL_TIMEOUT).await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let entries: Vec<Value> = response
+            .json()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?;
+        let Some(value) = entries.first().map(|entry| entry["Value"].as_str().unwrap_or_default())
+        else {
+            return Ok(None);
+        };
+        let content = base64::engine::general_purpose::STANDARD
+            .decode(value)
+            .map_err(|_| ConfigError::ParseConfigError)?;
+        String::from_utf8(content)
+            .map(Some)
+            .map_err(|_| ConfigError::ParseConfigError)
+    }
+
+    // Consul 的 PUT 不返回 ModifyIndex，下次列举时再取得
+    async fn save_content(&self, name: &str, content: &str) -> Result<Option<String>, ConfigError> {
+        let request = self
+            .client
+            .put(self.url(&format!("{}{}", self.prefix, name)))
+            .body(content.to_string());
+        self.send(request, CONSUL_TIMEOUT).await?;
+        Ok(None)
+    }
+
+    async fn delete_content(&self, name: &str) -> Result<(), ConfigError> {
+        let request = self.client.delete(self.url(&format!("{}{}", self.prefix, name)));
+        self.send(request, CONSUL_TIMEOUT).await?;
+        Ok(())
+    }
+
+    // 前缀下任一键被修改（或等待超时）时返回
+    async fn changed(&self, _interval: Duration) -> Result<(), ConfigError> {
+        let index = *self.index.lock().unwrap();
+        let request = self.client.get(self.url(&self.prefix)).query(&[
+            ("recurse", "true".to_string()),
+            ("index", index.to_string()),
+            ("wait", format!("{}s", CONSUL_WAIT_SECS)),
+        ]);
+        let timeout = Duration::from_secs(CONSUL_WAIT_SECS) + CONSUL_TIMEOUT;
+        let response = self.send(request, timeout).await?;
+        self.update_index(&response);
+        Ok(())
+    }
+}
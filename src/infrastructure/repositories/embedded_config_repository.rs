@@ -0,0 +1,55 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{
+        entities::configuration::Config,
+        repositories::configuration_repository::ConfigurationRepository,
+        services::embedded_config::{EmbeddedConfigService, EmbeddedSpec},
+    },
+    shared::{
+        error::ConfigError,
+        utils::{atomic_write_async, delete_ignore_line, read_file_async},
+    },
+};
+
+// 读写嵌入在其他文件（Markdown、脚本等）中的配置块，块外内容不做改动
+pub struct EmbeddedConfigRepository {
+    pub spec: EmbeddedSpec,
+}
+
+impl EmbeddedConfigRepository {
+    pub fn new(spec: EmbeddedSpec) -> Self {
+        Self { spec }
+    }
+}
+
+#[async_trait]
+impl ConfigurationRepository for EmbeddedConfigRepository {
+    async fn get(&self, name: &str) -> Result<Config, ConfigError> {
+        let host = read_file_async(name).await?;
+        let block = EmbeddedConfigService::extract(&host, &self.spec)?;
+        Config::from(
+            name.to_string(),
+            delete_ignore_line(&block.content),
+            self.spec.config_type.clone(),
+        )
+    }
+
+    async fn list(&self) -> Result<Vec<String>, ConfigError> {
+        Err(ConfigError::NowRepositoryConfigNotSupportFunction)
+    }
+
+    async fn save(&self, name: &str, config: Config) -> Result<(), ConfigError> {
+        let host = read_file_async(name).await?;
+        let block = EmbeddedConfigService::extract(&host, &self.spec)?;
+        let content = config.serialize_to(&self.spec.config_type)?;
+        atomic_write_async(name, EmbeddedConfigService::replace(&host, &block, &content))
+            .await
+            .map_err(ConfigError::IoError)?;
+        Ok(())
+    }
+
+    async fn delete(&self, _name: &str) -> Result<(), ConfigError> {
+        Err(ConfigError::NowRepositoryConfigNotSupportFunction)
+    }
+}
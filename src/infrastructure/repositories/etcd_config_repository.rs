@@ -0,0 +1,187 @@
+use std::{sync::Mutex, time::Duration};
+
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::{Value, json};
+
+use crate::{
+    domain::repositories::config_store::{ConfigStore, StoredConfig},
+    shared::{
+        config::KvSettings,
+        error::ConfigError,
+        utils::{is_config_file, is_valid_config_name},
+    },
+};
+
+const ETCD_TIMEOUT: Duration = Duration::from_secs(30);
+// watch 连接保持的最长时间，到期后重新列举一次再建立新的 watch
+const ETCD_WATCH_TIMEOUT: Duration = Duration::from_secs(300);
+
+// etcd v3 中 <prefix><配置名称> 下的配置，通过 gRPC gateway 的 JSON 接口（/v3/kv、/v3/watch）访问；
+// revision 为键的 mod_revision
+pub struct EtcdConfigRepository {
+    client: reqwest::Client,
+    endpoint: String,
+    prefix: String,
+    // 最近一次列举时的集群 revision，watch 从下一个 revision 开始
+    revision: Mutex<i64>,
+}
+
+impl EtcdConfigRepository {
+    pub fn new(settings: &KvSettings) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            endpoint: settings.endpoint.trim_end_matches('/').to_string(),
+            prefix: settings.prefix.clone(),
+            revision: Mutex::new(0),
+        }
+    }
+
+    fn encode(value: &[u8]) -> String {
+        base64::engine::general_purpose::STANDARD.encode(value)
+    }
+
+    fn decode(value: &Value) -> Option<Vec<u8>> {
+        base64::engine::general_purpose::STANDARD
+            .decode(value.as_str().unwrap_or_default())
+            .ok()
+    }
+
+    // 前缀范围的 [key, range_end)：最后一个字节加一；前缀为空时 "\0" 表示全部键
+    fn range(&self) -> Value {
+        let mut range_end = self.prefix.as_bytes().to_vec();
+        match range_end.last_mut() {
+            Some(last) => *last += 1,
+            None => range_end.push(0),
+        }
+        let key = match self.prefix.as_str() {
+            "" => vec![0],
+            prefix => prefix.as_bytes().to_vec(),
+        };
+        json!({"key": Self::encode(&key), "range_end": Self::encode(&range_end)})
+    }
+
+    fn key(&self, name: &str) -> String {
+        Self::encode(format!("{}{}", self.prefix, name).as_bytes())
+    }
+
+    async fn post(&self, path: &str, body: Value) -> Result<Value, ConfigError> {
+        let response = self
+            .client
+            .post(format!("{}{}", self.endpoint, path))
+            .timeout(ETCD_TIMEOUT)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(ConfigError::RemoteRequestFailed(format!(
+                "{}: {}",
+                status,
+                response.text().await.unwrap_or_default()
+            )));
+        }
+        response
+            .json()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))
+    }
+
+    // gateway 把 int64 编码为字符串
+    fn int(value: &Value) -> i64 {
+        match value {
+            Value::String(value) => value.parse().unwrap_or_default(),
+            value => value.as_i64().unwrap_or_default(),
+        }
+    }
+}
+
+#[async_trait]
+impl ConfigStore for EtcdConfigRepository {
+    fn id(&self) -> String {
+        format!("etcd://{}/{}", self.endpoint.trim_start_matches("http://"), self.prefix)
+    }
+
+    async fn list(&self) -> Result<Vec<StoredConfig>, ConfigError> {
+        let response = self.post("/v3/kv/range", self.range()).await?;
+        *self.revision.lock().unwrap() = Self::int(&response["header"]["revision"]);
+        let kvs = response["kvs"].as_array().cloned().unwrap_or_default();
+        Ok(kvs
+            .iter()
+            .filter_map(|kv| {
+                let key = String::from_utf8(Self::decode(&kv["key"])?).ok()?;
+                let name = key.strip_prefix(&self.prefix)?;
+                (is_valid_config_name(name) && is_config_file(name)).then(|| StoredConfig {
+                    name: name.to_string(),
+                    revision: Self::int(&kv["mod_revision"]).to_string(),
+                })
+            })
+            .collect())
+    }
+
+    async fn get_content(&self, name: &str) -> Result<Option<String>, ConfigError> {
+        let response = self.post("/v3/kv/range", json!({"key": self.key(name)})).await?;
+        let Some(kv) = response["kvs"].as_array().and_then(|kvs| kvs.first()) else {
+            return Ok(None);
+        };
+        let content = Self::decode(&kv["value"]).ok_or(ConfigError::ParseConfigError)?;
+        String::from_utf8(content)
+            .map(Some)
+            .map_err(|_| ConfigError::ParseConfigError)
+    }
+
+    // 写入后集群的 revision 即该键的 mod_revision
+    async fn save_content(&self, name: &str, content: &str) -> Result<Option<String>, ConfigError> {
+        let body = json!({"key": self.key(name), "value": Self::encode(content.as_bytes())});
+        let response = self.post("/v3/kv/put", body).await?;
+        Ok(Some(Self::int(&response["header"]["revision"]).to_string()))
+    }
+
+    async fn delete_content(&self, name: &str) -> Result<(), ConfigError> {
+        self.post("/v3/kv/deleterange", json!({"key": self.key(name)})).await?;
+        Ok(())
+    }
+
+    // 从上次列举之后的 revision 开始 watch 前缀，收到第一批事件时返回；
+    // watch 被服务端取消（例如 revision 已被压缩）或连接到期时同样返回，由调用方重新列举
+    async fn changed(&self, _interval: Duration) -> Result<(), ConfigError> {
+        let mut request = self.range();
+        request["start_revision"] = json!((*self.revision.lock().unwrap() + 1).to_string());
+        let mut response = self
+            .client
+            .post(format!("{}/v3/watch", self.endpoint))
+            .json(&json!({"create_request": request}))
+            .send()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(ConfigError::RemoteRequestFailed(response.status().to_string()));
+        }
+        let watch = async {
+            let mut buffer = String::new();
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?
+            {
+                buffer.push_str(&String::from_utf8_lossy(&chunk));
+                while let Some(end) = buffer.find('\n') {
+                    let line: String = buffer.drain(..=end).collect();
+                    let Ok(message) = serde_json::from_str::<Value>(line.trim()) else {
+                        continue;
+                    };
+                    let result = &message["result"];
+                    let events = result["events"].as_array().is_some_and(|events| !events.is_empty());
+                    if events || result["canceled"] == true || message.get("error").is_some() {
+                        return Ok(());
+                    }
+                }
+            }
+            Ok(())
+        };
+        tokio::time::timeout(ETCD_WATCH_TIMEOUT, watch)
+            .await
+            .unwrap_or(Ok(()))
+    }
+}
@@ -1,15 +1,33 @@
+use std::path::{Component, Path, PathBuf};
+
 use async_trait::async_trait;
+use tokio::sync::mpsc;
 
 use crate::{
     domain::{
-        entities::configuration::Config,
-        repositories::configuration_repository::ConfigurationRepository,
+        entities::{
+            config_version::{ConfigVersion, ConfigVersionInfo},
+            configuration::Config,
+        },
+        repositories::{
+            configuration_repository::{ConfigWatch, ConfigurationRepository},
+            history_store::HistoryStore,
+        },
         services::format_converter::FormatConverterService,
-        value_objects::{config_format::ConfigType, config_path::ConfigPath},
+        value_objects::config_path::ConfigPath,
+    },
+    infrastructure::{
+        history::file_history_store::FileHistoryStore, watchers::config_watcher::ConfigWatcher,
+    },
+    shared::{
+        error::ConfigError,
+        utils::{atomic_write_async, is_config_file, is_valid_config_name},
     },
-    shared::{error::ConfigError, utils::read_file},
 };
 
+// 以目录为根的文件仓储，配置名称是相对根目录的路径；serve 以配置目录为根，
+// CLI 以当前目录为根，命令行中的文件路径即配置名称
+#[derive(Debug, Clone)]
 pub struct FileConfigRepository {
     pub config_path: String,
 }
@@ -19,56 +37,159 @@ impl FileConfigRepository {
         Self { config_path }
     }
 
-    pub fn save(&self, config: Config, path: &str) -> Result<(), ConfigError> {
-        // 转换为serde_json::Value以避免类型标签
-        let serde_value = config.to_serde_value();
+    // CLI 使用：相对路径按当前目录解析，绝对路径原样使用
+    pub fn current_dir() -> Self {
+        Self::new(".".to_string())
+    }
 
-        let converted_content = match config.config_type {
-            ConfigType::Json => serde_json::to_string_pretty(&serde_value)
-                .map_err(|_| ConfigError::ParseConfigError)?,
-            ConfigType::Yaml => {
-                serde_yaml::to_string(&serde_value).map_err(|_| ConfigError::ParseConfigError)?
-            }
-            ConfigType::Toml => {
-                // TOML需要特殊处理，因为它不支持所有JSON类型
-                toml::to_string_pretty(&serde_value).map_err(|_| ConfigError::ParseConfigError)?
+    // 原样写入文本内容，用于恢复历史版本时保留原有格式和注释
+    pub async fn save_content(&self, content: &str, name: &str) -> Result<(), ConfigError> {
+        let save_path = self.path(name);
+        // 子目录中的配置，目录不存在时先创建
+        if let Some(dir) = save_path.parent() {
+            tokio::fs::create_dir_all(dir).await.map_err(ConfigError::IoError)?;
+        }
+        atomic_write_async(save_path, content).await.map_err(ConfigError::IoError)?;
+        Ok(())
+    }
+
+    // 配置名称对应的文件路径
+    pub fn path(&self, name: &str) -> PathBuf {
+        Path::new(&self.config_path).join(name)
+    }
+
+    // 递归列出配置目录下的配置文件，返回 (配置名称, 文件路径)，跳过隐藏文件和目录
+    pub fn files(&self) -> std::io::Result<Vec<(String, PathBuf)>> {
+        let mut configs = Vec::new();
+        Self::collect(Path::new(&self.config_path), "", &mut configs)?;
+        configs.sort();
+        Ok(configs)
+    }
+
+    fn collect(
+        dir: &Path,
+        prefix: &str,
+        configs: &mut Vec<(String, PathBuf)>,
+    ) -> std::io::Result<()> {
+        for entry in std::fs::read_dir(dir)? {
+            let entry = entry?;
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            if file_name.starts_with('.') {
+                continue;
             }
-            ConfigType::Unknown => {
-                return Err(ConfigError::UnknownConfigType);
+            let (name, path) = (format!("{}{}", prefix, file_name), entry.path());
+            // 不跟随指向目录的符号链接，避免目录环
+            if entry.file_type()?.is_dir() {
+                Self::collect(&path, &format!("{}/", name), configs)?;
+            } else if path.is_file() && is_config_file(&name) {
+                configs.push((name, path));
             }
-        };
-
-        // 写入目标文件
-        let save_path = self.get_config_save_path(path);
-        std::fs::write(save_path, converted_content).unwrap();
+        }
         Ok(())
     }
 
-    pub fn get_config_save_path(&self, config_name: &str) -> String {
-        format!("{}/{}", self.config_path, config_name)
+    // 同 files，使用 tokio::fs 逐层读取目录，供 serve 启动和重建时使用
+    pub async fn files_async(&self) -> std::io::Result<Vec<(String, PathBuf)>> {
+        let mut configs = Vec::new();
+        let mut dirs = vec![(PathBuf::from(&self.config_path), String::new())];
+        while let Some((dir, prefix)) = dirs.pop() {
+            let mut entries = tokio::fs::read_dir(&dir).await?;
+            while let Some(entry) = entries.next_entry().await? {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                if file_name.starts_with('.') {
+                    continue;
+                }
+                let (name, path) = (format!("{}{}", prefix, file_name), entry.path());
+                if entry.file_type().await?.is_dir() {
+                    dirs.push((path, format!("{}/", name)));
+                } else if tokio::fs::metadata(&path).await.is_ok_and(|meta| meta.is_file())
+                    && is_config_file(&name)
+                {
+                    configs.push((name, path));
+                }
+            }
+        }
+        configs.sort();
+        Ok(configs)
+    }
+
+    // 配置目录下文件对应的配置名称，即以 "/" 分隔的相对路径；
+    // 不在配置目录下或位于隐藏目录中时返回 None
+    pub fn config_name(&self, file_path: &Path) -> Option<String> {
+        let root = Path::new(&self.config_path);
+        let relative = match file_path.strip_prefix(root) {
+            Ok(relative) => relative.to_path_buf(),
+            // 监听器上报的路径可能与配置目录的写法不同（相对路径、符号链接），解析后再比较；
+            // 文件可能已被删除，只解析所在目录
+            Err(_) => {
+                let root = std::fs::canonicalize(root).ok()?;
+                let dir = std::fs::canonicalize(file_path.parent()?).ok()?;
+                dir.strip_prefix(root).ok()?.join(file_path.file_name()?)
+            }
+        };
+        let segments = relative
+            .components()
+            .map(|component| match component {
+                Component::Normal(segment) => Some(segment.to_string_lossy().to_string()),
+                _ => None,
+            })
+            .collect::<Option<Vec<_>>>()?;
+        let name = segments.join("/");
+        is_valid_config_name(&name).then_some(name)
     }
 }
 
 #[async_trait]
 impl ConfigurationRepository for FileConfigRepository {
-    async fn save(&self, config: Config, path: &str) -> Result<(), ConfigError> {
-        self.save(config, path)
+    async fn get(&self, name: &str) -> Result<Config, ConfigError> {
+        let content = tokio::fs::read_to_string(self.path(name)).await?;
+        FormatConverterService::new(ConfigPath::new(name.to_string())?, content).validate_config()
     }
 
-    async fn get(&self, path: String) -> Result<Config, ConfigError> {
-        let content = read_file(&path)?;
-        let config = FormatConverterService::new(ConfigPath::new(path).unwrap(), content)
-            .validate_config()?;
-        Ok(config)
+    async fn list(&self) -> Result<Vec<String>, ConfigError> {
+        let files = self.files_async().await?;
+        Ok(files.into_iter().map(|(name, _)| name).collect())
     }
 
-    async fn get_all(&self) -> Result<Vec<Config>, ConfigError> {
-        Err(ConfigError::NowRepositoryConfigNotSupportFunction)
+    async fn save(&self, name: &str, config: Config) -> Result<(), ConfigError> {
+        let content = config.serialize_to(&config.config_type)?;
+        self.save_content(&content, name).await
     }
-    async fn delete(&self, _path: String) -> Result<(), ConfigError> {
-        Err(ConfigError::NowRepositoryConfigNotSupportFunction)
+
+    async fn delete(&self, name: &str) -> Result<(), ConfigError> {
+        match tokio::fs::remove_file(self.path(name)).await {
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                Err(ConfigError::ConfigNotFound(name.to_string()))
+            }
+            result => result.map_err(ConfigError::IoError),
+        }
     }
-    async fn update(&self, _config: Config, _path: String) -> Result<(), ConfigError> {
-        Err(ConfigError::NowRepositoryConfigNotSupportFunction)
+
+    // 递归监听根目录，根目录之外和隐藏目录中的文件不产出
+    async fn watch(&self) -> Result<ConfigWatch, ConfigError> {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let repository = self.clone();
+        let mut watcher = ConfigWatcher::new(move |file_path| {
+            if let Some(name) = repository.config_name(&file_path) {
+                let _ = sender.send(name);
+            }
+        })?;
+        watcher.watch(Path::new(&self.config_path), true)?;
+        Ok(ConfigWatch::new(receiver, watcher))
+    }
+
+    // 读取 serve 在根目录 .history/ 中记录的历史，只读，不受保留数量影响
+    async fn history(&self, name: &str) -> Result<Vec<ConfigVersionInfo>, ConfigError> {
+        let versions = FileHistoryStore::new(&self.config_path, 0).list(name)?;
+        Ok(versions.iter().map(ConfigVersionInfo::from).collect())
+    }
+
+    async fn version(&self, name: &str, version: u64) -> Result<ConfigVersion, ConfigError> {
+        FileHistoryStore::new(&self.config_path, 0)
+            .get(name, version)?
+            .ok_or_else(|| ConfigError::VersionNotFound {
+                file: name.to_string(),
+                version,
+            })
     }
 }
@@ -0,0 +1,136 @@
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use crate::{
+    domain::{
+        entities::{
+            config_version::{ConfigVersion, ConfigVersionInfo},
+            configuration::{Config, ConfigValue},
+        },
+        repositories::configuration_repository::ConfigurationRepository,
+        value_objects::{config_format::ConfigType, config_path::ConfigPath},
+    },
+    shared::{app_state::RestResponse, error::ConfigError},
+};
+
+// 通过运行中 serve --http 实例的 REST API 读写配置
+pub struct HttpConfigRepository {
+    pub base_url: String,
+    client: reqwest::Client,
+    // 服务端启用 API key 鉴权时以 Bearer 方式携带
+    api_key: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RemoteConfig {
+    path: ConfigPath,
+    #[serde(rename = "type")]
+    config_type: ConfigType,
+    config: serde_json::Value,
+}
+
+// GET /api/configs 列表项，这里只需要名称
+#[derive(Debug, Deserialize)]
+struct RemoteSummary {
+    name: String,
+}
+
+impl HttpConfigRepository {
+    pub fn new(base_url: String) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client: reqwest::Client::new(),
+            api_key: None,
+        }
+    }
+
+    pub fn with_api_key(mut self, api_key: Option<String>) -> Self {
+        self.api_key = api_key;
+        self
+    }
+
+    fn request(&self, method: reqwest::Method, url: String) -> reqwest::RequestBuilder {
+        let request = self.client.request(method, url);
+        match &self.api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
+        }
+    }
+
+    fn config_url(&self, name: &str) -> String {
+        format!("{}/api/configs/{}", self.base_url, name)
+    }
+
+    async fn send<T: serde::de::DeserializeOwned>(
+        request: reqwest::RequestBuilder,
+    ) -> Result<T, ConfigError> {
+        let response = request
+            .send()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?;
+        // 失败响应的 data 可能是其他类型（例如附加规则的错误列表），先按任意 JSON 解析
+        let body: RestResponse<serde_json::Value> = response
+            .json()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?;
+        if !body.success {
+            if body.code == 404 {
+                return Err(ConfigError::KeyNotFound);
+            }
+            return Err(ConfigError::RemoteRequestFailed(body.message));
+        }
+        let data = body
+            .data
+            .ok_or_else(|| ConfigError::RemoteRequestFailed("empty response".to_string()))?;
+        serde_json::from_value(data).map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl ConfigurationRepository for HttpConfigRepository {
+    async fn get(&self, name: &str) -> Result<Config, ConfigError> {
+        let remote: RemoteConfig = Self::send(self.request(reqwest::Method::GET, self.config_url(name))).await?;
+        Ok(Config {
+            path: remote.path,
+            config: ConfigValue::from_serde_json(remote.config)?.into_object()?,
+            config_type: remote.config_type,
+        })
+    }
+
+    async fn list(&self) -> Result<Vec<String>, ConfigError> {
+        let url = format!("{}/api/configs", self.base_url);
+        let summaries: Vec<RemoteSummary> =
+            Self::send(self.request(reqwest::Method::GET, url)).await?;
+        Ok(summaries.into_iter().map(|summary| summary.name).collect())
+    }
+
+    async fn save(&self, name: &str, config: Config) -> Result<(), ConfigError> {
+        let content = config.serialize_to(&config.config_type)?;
+        let request = self.request(reqwest::Method::PUT, self.config_url(name));
+        let _: String = Self::send(request.body(content)).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), ConfigError> {
+        let _: String = Self::send(self.request(reqwest::Method::DELETE, self.config_url(name))).await?;
+        Ok(())
+    }
+    async fn history(&self, name: &str) -> Result<Vec<ConfigVersionInfo>, ConfigError> {
+        let url = format!("{}/history", self.config_url(name));
+        match Self::send(self.request(reqwest::Method::GET, url)).await {
+            Err(ConfigError::KeyNotFound) => Err(ConfigError::ConfigNotFound(name.to_string())),
+            result => result,
+        }
+    }
+
+    async fn version(&self, name: &str, version: u64) -> Result<ConfigVersion, ConfigError> {
+        let url = format!("{}/versions/{}", self.config_url(name), version);
+        match Self::send(self.request(reqwest::Method::GET, url)).await {
+            Err(ConfigError::KeyNotFound) => Err(ConfigError::VersionNotFound {
+                file: name.to_string(),
+                version,
+            }),
+            result => result,
+        }
+    }
+}
@@ -0,0 +1,297 @@
+use std::{
+    sync::{
+        Mutex,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::{Value, json};
+
+use crate::{
+    domain::repositories::config_store::{ConfigStore, StoredConfig},
+    shared::{
+        error::ConfigError,
+        utils::{is_config_file, is_valid_config_name, sha256_hex},
+    },
+};
+
+const KUBE_TIMEOUT: Duration = Duration::from_secs(30);
+// watch 请求让 API server 在这段时间后结束连接，之后重新列举一次再建立新的 watch
+const KUBE_WATCH_SECS: u64 = 300;
+const SERVICE_ACCOUNT_DIR: &str = "/var/run/secrets/kubernetes.io/serviceaccount";
+// 集群外运行时指定 API 地址，例如 kubectl proxy 的 http://127.0.0.1:8001
+const KUBE_API_URL_ENV: &str = "KUBERNETES_API_URL";
+// Secret 中的配置以此为名称前缀，只读
+const SECRETS_PREFIX: &str = "secrets/";
+
+// 命名空间中 ConfigMap 与 Secret 的配置文件键：ConfigMap cm 中的键 app.yaml 对应配置 cm/app.yaml，
+// Secret s 中的键对应 secrets/s/app.yaml；revision 为内容哈希。修改写回为 ConfigMap 的 merge patch，
+// ConfigMap 不存在时创建，Secret 不写回
+pub struct KubernetesConfigRepository {
+    client: reqwest::Client,
+    endpoint: String,
+    namespace: String,
+    // 集群内运行时使用 service account 的 token，kubelet 会定期轮换文件，每次请求重新读取
+    token_path: Option<String>,
+    // 最近一次列举时 ConfigMap 与 Secret 列表的 resourceVersion，watch 从这里开始
+    versions: Mutex<(String, String)>,
+    // 没有 Secret 的 list 权限时只同步 ConfigMap
+    secrets_readable: AtomicBool,
+}
+
+impl KubernetesConfigRepository {
+    pub fn new(namespace: &str) -> Result<Self, ConfigError> {
+        let in_cluster = || -> Result<(String, reqwest::Client), ConfigError> {
+            let host = std::env::var("KUBERNETES_SERVICE_HOST").map_err(|_| {
+                ConfigError::InvalidServerSettings(format!(
+                    "not running in a cluster, set {} to reach the Kubernetes API",
+                    KUBE_API_URL_ENV
+                ))
+            })?;
+            let port = std::env::var("KUBERNETES_SERVICE_PORT").unwrap_or_else(|_| "443".to_string());
+            let ca = std::fs::read(format!("{}/ca.crt", SERVICE_ACCOUNT_DIR))?;
+            let ca = reqwest::Certificate::from_pem(&ca)
+                .map_err(|e| ConfigError::InvalidServerSettings(e.to_string()))?;
+            let client = reqwest::Client::builder()
+                .add_root_certificate(ca)
+                .build()
+                .map_err(|e| ConfigError::InvalidServerSettings(e.to_string()))?;
+            Ok((format!("https://{}:{}", host, port), client))
+        };
+        let (endpoint, client) = match std::env::var(KUBE_API_URL_ENV) {
+            Ok(url) => (url, reqwest::Client::new()),
+            Err(_) => in_cluster()?,
+        };
+        let token_path = format!("{}/token", SERVICE_ACCOUNT_DIR);
+        Ok(Self {
+            client,
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            namespace: namespace.to_string(),
+            token_path: std::path::Path::new(&token_path).exists().then_some(token_path),
+            versions: Mutex::new((String::new(), String::new())),
+            secrets_readable: AtomicBool::new(true),
+        })
+    }
+
+    fn url(&self, resource: &str, name: Option<&str>) -> String {
+        let base = format!("{}/api/v1/namespaces/{}/{}", self.endpoint, self.namespace, resource);
+        match name {
+            Some(name) => format!("{}/{}", base, name),
+            None => base,
+        }
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, ConfigError> {
+        let token = match &self.token_path {
+            Some(path) => tokio::fs::read_to_string(path).await.ok(),
+            None => None,
+        };
+        let request = match token {
+            Some(token) => request.bearer_auth(token.trim()),
+            None => request,
+        };
+        request
+            .send()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))
+    }
+
+    // 2xx 时解析 JSON，404 时返回 None
+    async fn json(&self, request: reqwest::RequestBuilder) -> Result<Option<Value>, ConfigError> {
+        let response = self.send(request.timeout(KUBE_TIMEOUT)).await?;
+        match response.status() {
+            reqwest::StatusCode::NOT_FOUND => Ok(None),
+            status if status.is_success() => response
+                .json()
+                .await
+                .map(Some)
+                .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string())),
+            status => Err(ConfigError::RemoteRequestFailed(format!(
+                "{}: {}",
+                status,
+                response.text().await.unwrap_or_default()
+            ))),
+        }
+    }
+
+    // 配置名称对应的 (资源类型, 对象名, 键)；ConfigMap 与 Secret 的键中不能有 "/"
+    fn locate(name: &str) -> Option<(&'static str, &str, &str)> {
+        let (resource, rest) = match name.strip_prefix(SECRETS_PREFIX) {
+            Some(rest) => ("secrets", rest),
+            None => ("configmaps", name),
+        };
+        let (object, key) = rest.split_once('/')?;
+        (!key.contains('/')).then_some((resource, object, key))
+    }
+
+    // 对象中键对应的文本内容，Secret 的 data 为 base64
+    fn value(resource: &str, object: &Value, key: &str) -> Option<String> {
+        let value = object["data"][key].as_str()?;
+        match resource {
+            "secrets" => base64::engine::general_purpose::STANDARD
+                .decode(value)
+                .ok()
+                .and_then(|value| String::from_utf8(value).ok()),
+            _ => Some(value.to_string()),
+        }
+    }
+
+    // 列举一种资源中的配置，返回列表的 resourceVersion；无权限（403）时返回 None
+    async fn list_resource(
+        &self,
+        resource: &'static str,
+        configs: &mut Vec<StoredConfig>,
+    ) -> Result<Option<String>, ConfigError> {
+        let response = self.send(self.client.get(self.url(resource, None)).timeout(KUBE_TIMEOUT)).await?;
+        if response.status() == reqwest::StatusCode::FORBIDDEN && resource == "secrets" {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(ConfigError::RemoteRequestFailed(format!(
+                "list {}: {}",
+                resource,
+                response.status()
+            )));
+        }
+        let list: Value = response
+            .json()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?;
+        let prefix = if resource == "secrets" { SECRETS_PREFIX } else { "" };
+        for object in list["items"].as_array().into_iter().flatten() {
+            let Some(object_name) = object["metadata"]["name"].as_str() else {
+                continue;
+            };
+            let Some(data) = object["data"].as_object() else {
+                continue;
+            };
+            for key in data.keys() {
+                let name = format!("{}{}/{}", prefix, object_name, key);
+                if !(is_valid_config_name(&name) && is_config_file(&name)) {
+                    continue;
+                }
+                let Some(value) = Self::value(resource, object, key) else {
+                    continue;
+                };
+                configs.push(StoredConfig {
+                    name,
+                    revision: sha256_hex(value.as_bytes()),
+                });
+            }
+        }
+        Ok(Some(list["metadata"]["resourceVersion"].as_str().unwrap_or_default().to_string()))
+    }
+
+    // 从 resource_version 开始 watch 一种资源，收到第一个事件（包括 410 Gone 等错误事件）或连接结束时返回
+    async fn watch_resource(&self, resource: &str, resource_version: String) -> Result<(), ConfigError> {
+        let request = self.client.get(self.url(resource, None)).query(&[
+            ("watch", "1".to_string()),
+            ("resourceVersion", resource_version),
+            ("timeoutSeconds", KUBE_WATCH_SECS.to_string()),
+            ("allowWatchBookmarks", "false".to_string()),
+        ]);
+        let mut response = self
+            .send(request.timeout(Duration::from_secs(KUBE_WATCH_SECS) + KUBE_TIMEOUT))
+            .await?;
+        if !response.status().is_success() {
+            return Err(ConfigError::RemoteRequestFailed(format!(
+                "watch {}: {}",
+                resource,
+                response.status()
+            )));
+        }
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?
+        {
+            if chunk.contains(&b'\n') {
+                return Ok(());
+            }
+        }
+        Ok(())
+    }
+
+    async fn patch(&self, object: &str, patch: Value) -> Result<Option<Value>, ConfigError> {
+        let request = self
+            .client
+            .patch(self.url("configmaps", Some(object)))
+            .header("content-type", "application/merge-patch+json")
+            .body(patch.to_string());
+        self.json(request).await
+    }
+}
+
+#[async_trait]
+impl ConfigStore for KubernetesConfigRepository {
+    fn id(&self) -> String {
+        format!("k8s://{}", self.namespace)
+    }
+
+    async fn list(&self) -> Result<Vec<StoredConfig>, ConfigError> {
+        let mut configs = Vec::new();
+        let configmaps = self.list_resource("configmaps", &mut configs).await?.unwrap_or_default();
+        let secrets = self.list_resource("secrets", &mut configs).await?;
+        self.secrets_readable.store(secrets.is_some(), Ordering::Relaxed);
+        *self.versions.lock().unwrap() = (configmaps, secrets.unwrap_or_default());
+        Ok(configs)
+    }
+
+    async fn get_content(&self, name: &str) -> Result<Option<String>, ConfigError> {
+        let Some((resource, object, key)) = Self::locate(name) else {
+            return Ok(None);
+        };
+        let object_json = self.json(self.client.get(self.url(resource, Some(object)))).await?;
+        Ok(object_json.and_then(|object_json| Self::value(resource, &object_json, key)))
+    }
+
+    async fn save_content(&self, name: &str, content: &str) -> Result<Option<String>, ConfigError> {
+        let (object, key) = match Self::locate(name) {
+            Some(("configmaps", object, key)) => (object, key),
+            _ => {
+                return Err(ConfigError::InvalidConfigPath(format!(
+                    "{} cannot be written to a ConfigMap, expected <configmap>/<key>",
+                    name
+                )));
+            }
+        };
+        let data = json!({ key: content });
+        if self.patch(object, json!({"data": data})).await?.is_none() {
+            let configmap = json!({
+                "apiVersion": "v1",
+                "kind": "ConfigMap",
+                "metadata": {"name": object, "namespace": self.namespace},
+                "data": data,
+            });
+            let request = self.client.post(self.url("configmaps", None)).json(&configmap);
+            self.json(request).await?;
+        }
+        Ok(Some(sha256_hex(content.as_bytes())))
+    }
+
+    // 只删除 ConfigMap 中的键，对象本身保留
+    async fn delete_content(&self, name: &str) -> Result<(), ConfigError> {
+        if let Some(("configmaps", object, key)) = Self::locate(name) {
+            self.patch(object, json!({"data": { key: null }})).await?;
+        }
+        Ok(())
+    }
+
+    async fn changed(&self, interval: Duration) -> Result<(), ConfigError> {
+        let (configmaps, secrets) = self.versions.lock().unwrap().clone();
+        if configmaps.is_empty() {
+            tokio::time::sleep(interval).await;
+            return Ok(());
+        }
+        if !self.secrets_readable.load(Ordering::Relaxed) {
+            return self.watch_resource("configmaps", configmaps).await;
+        }
+        tokio::select! {
+            result = self.watch_resource("configmaps", configmaps) => result,
+            result = self.watch_resource("secrets", secrets) => result,
+        }
+    }
+}
@@ -2,7 +2,10 @@ use async_trait::async_trait;
 
 use crate::{
     domain::{
-        entities::{configuration::Config, template::TemplateType},
+        entities::{
+            configuration::Config,
+            template::{TemplateMetadata, TemplateType},
+        },
         repositories::template_repository::TemplateRepository,
         value_objects::config_format::ConfigType,
     },
@@ -11,6 +14,12 @@ use crate::{
 
 pub struct MemoryTemplateRepository;
 
+impl Default for MemoryTemplateRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MemoryTemplateRepository {
     pub fn new() -> Self {
         Self {}
@@ -73,17 +82,19 @@ impl TemplateRepository for MemoryTemplateRepository {
             });
         }
 
-        let config =
+        let mut config =
             Config::get_default_config(template.clone(), format.clone()).map_err(|_| {
                 TemplateError::UnsupportedFormat {
                     format: "not a valid config file".to_string(),
                 }
             })?;
+        // 记录模板来源，供 template upgrade 使用
+        TemplateMetadata::new(template.clone(), &config).annotate(&mut config);
         config.show(".", 5);
         let serde_value = config.to_serde_value();
 
         let converted_content = match format {
-            ConfigType::Json => serde_json::to_string_pretty(&serde_value)
+            ConfigType::Json | ConfigType::Json5 => serde_json::to_string_pretty(&serde_value)
                 .map_err(|_| TemplateError::ParseTemplateError)?,
             ConfigType::Yaml => serde_yaml::to_string(&serde_value)
                 .map_err(|_| TemplateError::ParseTemplateError)?,
@@ -99,6 +110,7 @@ impl TemplateRepository for MemoryTemplateRepository {
         println!("🔧 generate config file: {}", converted_content);
         let format_ext = match format {
             ConfigType::Json => "json",
+            ConfigType::Json5 => "json5",
             ConfigType::Yaml => "yaml",
             ConfigType::Toml => "toml",
             ConfigType::Unknown => "txt",
@@ -106,7 +118,7 @@ impl TemplateRepository for MemoryTemplateRepository {
         let output = format!("{}-config.{}", template, format_ext);
         println!("📝 output file name: {}", output);
         // 写入目标文件
-        std::fs::write(&output, converted_content).map_err(|e| TemplateError::IoError(e))?;
+        std::fs::write(&output, converted_content).map_err(TemplateError::IoError)?;
 
         println!("✅ template file generated: {}", output);
 
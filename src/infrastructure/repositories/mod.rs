@@ -1,2 +1,12 @@
+pub mod config_store_factory;
+pub mod consul_config_repository;
+pub mod embedded_config_repository;
+pub mod etcd_config_repository;
 pub mod file_config_repository;
-pub mod memory_template_repository;
\ No newline at end of file
+pub mod http_config_repository;
+#[cfg(feature = "k8s-sync")]
+pub mod kubernetes_config_repository;
+pub mod memory_template_repository;
+pub mod s3_config_repository;
+pub mod sqlite_config_repository;
+pub mod source_config_repository;
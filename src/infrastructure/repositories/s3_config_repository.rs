@@ -0,0 +1,116 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{
+        entities::configuration::Config,
+        repositories::{
+            config_store::{ConfigStore, StoredConfig},
+            configuration_repository::ConfigurationRepository,
+        },
+        services::format_converter::FormatConverterService, value_objects::config_path::ConfigPath,
+    },
+    infrastructure::s3::s3_client::S3Client,
+    shared::{
+        config::S3Settings,
+        error::ConfigError,
+        utils::{is_config_file, is_valid_config_name},
+    },
+};
+
+// 配置保存在 S3 兼容对象存储中，对象名为 <prefix><配置名称>；
+// 命名空间 team-a 中的配置即 <prefix>team-a/ 下的对象
+pub struct S3ConfigRepository {
+    client: S3Client,
+    prefix: String,
+}
+
+impl S3ConfigRepository {
+    pub fn new(settings: &S3Settings) -> Self {
+        Self {
+            client: S3Client::new(settings),
+            prefix: settings.prefix.clone(),
+        }
+    }
+
+    fn key(&self, name: &str) -> String {
+        format!("{}{}", self.prefix, name)
+    }
+}
+
+// revision 为对象的 ETag
+#[async_trait]
+impl ConfigStore for S3ConfigRepository {
+    fn id(&self) -> String {
+        format!("s3://{}/{}", self.client.bucket(), self.prefix)
+    }
+
+    // 前缀下的全部配置；扩展名不是配置格式或位于隐藏目录中的对象跳过
+    async fn list(&self) -> Result<Vec<StoredConfig>, ConfigError> {
+        let objects = self
+            .client
+            .list_objects(&self.prefix)
+            .await
+            .map_err(ConfigError::RemoteRequestFailed)?;
+        Ok(objects
+            .into_iter()
+            .filter_map(|object| {
+                let name = object.key.strip_prefix(&self.prefix)?;
+                (is_valid_config_name(name) && is_config_file(name)).then(|| StoredConfig {
+                    name: name.to_string(),
+                    revision: object.etag,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_content(&self, name: &str) -> Result<Option<String>, ConfigError> {
+        let content = self
+            .client
+            .get_object(&self.key(name))
+            .await
+            .map_err(ConfigError::RemoteRequestFailed)?;
+        content
+            .map(|content| String::from_utf8(content).map_err(|_| ConfigError::ParseConfigError))
+            .transpose()
+    }
+
+    async fn save_content(&self, name: &str, content: &str) -> Result<Option<String>, ConfigError> {
+        self.client
+            .put_object(&self.key(name), content.as_bytes().to_vec())
+            .await
+            .map_err(ConfigError::RemoteRequestFailed)
+    }
+
+    async fn delete_content(&self, name: &str) -> Result<(), ConfigError> {
+        self.client
+            .delete_object(&self.key(name))
+            .await
+            .map_err(ConfigError::RemoteRequestFailed)
+    }
+}
+
+#[async_trait]
+impl ConfigurationRepository for S3ConfigRepository {
+    async fn get(&self, name: &str) -> Result<Config, ConfigError> {
+        let content = self
+            .get_content(name)
+            .await?
+            .ok_or_else(|| ConfigError::ConfigNotFound(name.to_string()))?;
+        FormatConverterService::new(ConfigPath::new(name.to_string())?, content).validate_config()
+    }
+
+    async fn list(&self) -> Result<Vec<String>, ConfigError> {
+        let stored = ConfigStore::list(self).await?;
+        Ok(stored.into_iter().map(|stored| stored.name).collect())
+    }
+
+    async fn save(&self, name: &str, config: Config) -> Result<(), ConfigError> {
+        let content = config.serialize_to(&config.config_type)?;
+        self.save_content(name, &content).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), ConfigError> {
+        self.delete_content(name).await
+    }
+}
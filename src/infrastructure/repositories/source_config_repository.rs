@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{
+        entities::configuration::Config,
+        repositories::{
+            config_source::ConfigSource, configuration_repository::ConfigurationRepository,
+        },
+    },
+    shared::error::ConfigError,
+};
+
+// 只读仓储：show、stats、diff 等命令通过它读取 URL 或标准输入中的配置
+pub struct SourceConfigRepository {
+    source: Box<dyn ConfigSource>,
+}
+
+impl SourceConfigRepository {
+    pub fn new(source: Box<dyn ConfigSource>) -> Self {
+        Self { source }
+    }
+}
+
+#[async_trait]
+impl ConfigurationRepository for SourceConfigRepository {
+    async fn get(&self, _name: &str) -> Result<Config, ConfigError> {
+        self.source.load().await
+    }
+
+    async fn list(&self) -> Result<Vec<String>, ConfigError> {
+        Err(ConfigError::NowRepositoryConfigNotSupportFunction)
+    }
+
+    async fn save(&self, _name: &str, _config: Config) -> Result<(), ConfigError> {
+        Err(ConfigError::NowRepositoryConfigNotSupportFunction)
+    }
+
+    async fn delete(&self, _name: &str) -> Result<(), ConfigError> {
+        Err(ConfigError::NowRepositoryConfigNotSupportFunction)
+    }
+}
@@ -0,0 +1,319 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::{
+    domain::{
+        entities::{audit::AuditRecord, config_version::ConfigVersion, configuration::Config},
+        repositories::{
+            audit_sink::AuditSink,
+            config_store::{ConfigStore, StoredConfig},
+            configuration_repository::ConfigurationRepository,
+            history_store::HistoryStore,
+        },
+        services::format_converter::FormatConverterService,
+        value_objects::config_path::ConfigPath,
+    },
+    shared::{error::ConfigError, utils::sha256_hex},
+};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS configs (
+    name TEXT PRIMARY KEY,
+    content TEXT NOT NULL,
+    sha256 TEXT NOT NULL,
+    updated_at TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS versions (
+    name TEXT NOT NULL,
+    version INTEGER NOT NULL,
+    saved_at TEXT NOT NULL,
+    size INTEGER NOT NULL,
+    sha256 TEXT NOT NULL,
+    content TEXT NOT NULL,
+    PRIMARY KEY (name, version)
+);
+CREATE TABLE IF NOT EXISTS audit (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    timestamp TEXT NOT NULL,
+    config TEXT NOT NULL,
+    record TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS audit_config ON audit (config, id);
+CREATE TABLE IF NOT EXISTS rules (
+    name TEXT PRIMARY KEY,
+    content TEXT NOT NULL
+);
+";
+
+// 配置、历史版本、审计记录和附加规则保存在同一个 SQLite 数据库中；
+// 写入配置与记录版本在同一个事务内完成，revision 为内容的 sha256
+pub struct SqliteConfigRepository {
+    path: PathBuf,
+    connection: Mutex<Connection>,
+    // 每个配置保留的版本数，0 表示不记录
+    history_limit: usize,
+}
+
+impl SqliteConfigRepository {
+    pub fn open(path: &Path, history_limit: usize) -> Result<Self, ConfigError> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+        let connection = Connection::open(path).map_err(db_error)?;
+        // 其他进程同时写入时等待锁释放，而不是立即失败
+        connection
+            .busy_timeout(std::time::Duration::from_secs(5))
+            .map_err(db_error)?;
+        connection
+            .pragma_update(None, "journal_mode", "WAL")
+            .map_err(db_error)?;
+        connection.execute_batch(SCHEMA).map_err(db_error)?;
+        Ok(Self {
+            path: path.to_path_buf(),
+            connection: Mutex::new(connection),
+            history_limit,
+        })
+    }
+
+    // 在同一个事务中追加版本并清理超出数量的旧版本
+    fn record_version(
+        transaction: &rusqlite::Transaction,
+        file: &str,
+        content: &str,
+        saved_at: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Option<u64>, rusqlite::Error> {
+        if limit == 0 {
+            return Ok(None);
+        }
+        let sha256 = sha256_hex(content.as_bytes());
+        let latest: Option<(i64, String)> = transaction
+            .query_row(
+                "SELECT version, sha256 FROM versions WHERE name = ?1 ORDER BY version DESC LIMIT 1",
+                params![file],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        if latest.as_ref().is_some_and(|(_, latest)| *latest == sha256) {
+            return Ok(None);
+        }
+        let version = latest.map_or(1, |(version, _)| version + 1);
+        let size = content.len() as i64;
+        transaction.execute(
+            "INSERT INTO versions (name, version, saved_at, size, sha256, content)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![file, version, saved_at, size, sha256, content],
+        )?;
+        transaction.execute(
+            "DELETE FROM versions WHERE name = ?1 AND version <= ?2",
+            params![file, version - limit as i64],
+        )?;
+        Ok(Some(version as u64))
+    }
+
+    fn version_from_row(row: &rusqlite::Row) -> Result<ConfigVersion, rusqlite::Error> {
+        Ok(ConfigVersion {
+            version: row.get::<_, i64>(0)? as u64,
+            saved_at: row.get(1)?,
+            size: row.get::<_, i64>(2)? as usize,
+            sha256: row.get(3)?,
+            content: row.get(4)?,
+        })
+    }
+}
+
+fn db_error(e: rusqlite::Error) -> ConfigError {
+    ConfigError::DatabaseError(e.to_string())
+}
+
+#[async_trait]
+impl ConfigStore for SqliteConfigRepository {
+    fn id(&self) -> String {
+        format!("sqlite:{}", self.path.display())
+    }
+
+    async fn list(&self) -> Result<Vec<StoredConfig>, ConfigError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare("SELECT name, sha256 FROM configs ORDER BY name")
+            .map_err(db_error)?;
+        let configs = statement
+            .query_map([], |row| {
+                Ok(StoredConfig {
+                    name: row.get(0)?,
+                    revision: row.get(1)?,
+                })
+            })
+            .map_err(db_error)?;
+        configs.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    async fn get_content(&self, name: &str) -> Result<Option<String>, ConfigError> {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .query_row("SELECT content FROM configs WHERE name = ?1", params![name], |row| {
+                row.get(0)
+            })
+            .optional()
+            .map_err(db_error)
+    }
+
+    async fn save_content(&self, name: &str, content: &str) -> Result<Option<String>, ConfigError> {
+        let now = Utc::now();
+        let sha256 = sha256_hex(content.as_bytes());
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction().map_err(db_error)?;
+        transaction
+            .execute(
+                "INSERT INTO configs (name, content, sha256, updated_at) VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (name) DO UPDATE SET
+                     content = excluded.content, sha256 = excluded.sha256, updated_at = excluded.updated_at",
+                params![name, content, sha256, now],
+            )
+            .map_err(db_error)?;
+        Self::record_version(&transaction, name, content, now, self.history_limit).map_err(db_error)?;
+        transaction.commit().map_err(db_error)?;
+        Ok(Some(sha256))
+    }
+
+    // 历史版本保留，配置重新创建后版本号继续递增
+    async fn delete_content(&self, name: &str) -> Result<(), ConfigError> {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute("DELETE FROM configs WHERE name = ?1", params![name])
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn list_rules(&self) -> Result<Vec<(String, String)>, ConfigError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare("SELECT name, content FROM rules ORDER BY name")
+            .map_err(db_error)?;
+        let rules = statement
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(db_error)?;
+        rules.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    async fn save_rules(&self, name: &str, content: &str) -> Result<(), ConfigError> {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "INSERT INTO rules (name, content) VALUES (?1, ?2)
+                 ON CONFLICT (name) DO UPDATE SET content = excluded.content",
+                params![name, content],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+}
+
+impl HistoryStore for SqliteConfigRepository {
+    fn list(&self, file: &str) -> Result<Vec<ConfigVersion>, ConfigError> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare(
+                "SELECT version, saved_at, size, sha256, content FROM versions
+                 WHERE name = ?1 ORDER BY version",
+            )
+            .map_err(db_error)?;
+        let versions = statement
+            .query_map(params![file], Self::version_from_row)
+            .map_err(db_error)?;
+        versions.collect::<Result<_, _>>().map_err(db_error)
+    }
+
+    fn get(&self, file: &str, version: u64) -> Result<Option<ConfigVersion>, ConfigError> {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .query_row(
+                "SELECT version, saved_at, size, sha256, content FROM versions
+                 WHERE name = ?1 AND version = ?2",
+                params![file, version as i64],
+                Self::version_from_row,
+            )
+            .optional()
+            .map_err(db_error)
+    }
+
+    fn record(
+        &self,
+        file: &str,
+        content: String,
+        saved_at: DateTime<Utc>,
+    ) -> Result<Option<u64>, ConfigError> {
+        let mut connection = self.connection.lock().unwrap();
+        let transaction = connection.transaction().map_err(db_error)?;
+        let version = Self::record_version(&transaction, file, &content, saved_at, self.history_limit)
+            .map_err(db_error)?;
+        transaction.commit().map_err(db_error)?;
+        Ok(version)
+    }
+}
+
+#[async_trait]
+impl AuditSink for SqliteConfigRepository {
+    async fn write(&self, record: &AuditRecord) -> Result<(), ConfigError> {
+        let json = serde_json::to_string(record).map_err(|_| ConfigError::ParseConfigError)?;
+        let connection = self.connection.lock().unwrap();
+        connection
+            .execute(
+                "INSERT INTO audit (timestamp, config, record) VALUES (?1, ?2, ?3)",
+                params![record.timestamp, record.config, json],
+            )
+            .map_err(db_error)?;
+        Ok(())
+    }
+
+    async fn read(&self) -> Option<Result<Vec<AuditRecord>, ConfigError>> {
+        let connection = self.connection.lock().unwrap();
+        let records = connection
+            .prepare("SELECT record FROM audit ORDER BY id")
+            .and_then(|mut statement| {
+                statement
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .map_err(db_error)
+            .map(|records| {
+                records
+                    .iter()
+                    .filter_map(|record| serde_json::from_str(record).ok())
+                    .collect()
+            });
+        Some(records)
+    }
+}
+
+#[async_trait]
+impl ConfigurationRepository for SqliteConfigRepository {
+    async fn get(&self, name: &str) -> Result<Config, ConfigError> {
+        let content = self
+            .get_content(name)
+            .await?
+            .ok_or_else(|| ConfigError::ConfigNotFound(name.to_string()))?;
+        FormatConverterService::new(ConfigPath::new(name.to_string())?, content).validate_config()
+    }
+
+    async fn list(&self) -> Result<Vec<String>, ConfigError> {
+        let stored = ConfigStore::list(self).await?;
+        Ok(stored.into_iter().map(|stored| stored.name).collect())
+    }
+
+    async fn save(&self, name: &str, config: Config) -> Result<(), ConfigError> {
+        let content = config.serialize_to(&config.config_type)?;
+        self.save_content(name, &content).await?;
+        Ok(())
+    }
+
+    async fn delete(&self, name: &str) -> Result<(), ConfigError> {
+        self.delete_content(name).await
+    }
+}
@@ -0,0 +1,282 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use hmac::{Hmac, KeyInit, Mac};
+use sha2::Sha256;
+
+use crate::shared::{
+    config::S3Settings,
+    utils::{sha256_hex, to_hex},
+};
+
+const S3_TIMEOUT: Duration = Duration::from_secs(30);
+const DEFAULT_REGION: &str = "us-east-1";
+
+// 最小的 S3 客户端：以路径方式访问 bucket，用 SigV4 签名，只支持对象的读写、删除和列举
+pub struct S3Client {
+    http: reqwest::Client,
+    endpoint: String,
+    bucket: String,
+    region: String,
+    access_key: String,
+    secret_key: String,
+    session_token: Option<String>,
+}
+
+// 列举结果中的一个对象，key 为完整对象名；etag 在内容变化时改变
+#[derive(Debug, Clone)]
+pub struct S3Object {
+    pub key: String,
+    pub size: u64,
+    pub etag: String,
+}
+
+impl S3Client {
+    pub fn new(settings: &S3Settings) -> Self {
+        let env = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let region = settings
+            .region
+            .clone()
+            .or_else(|| env("AWS_REGION"))
+            .unwrap_or_else(|| DEFAULT_REGION.to_string());
+        let endpoint = settings
+            .endpoint
+            .clone()
+            .or_else(|| env("AWS_ENDPOINT_URL"))
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", region));
+        Self {
+            http: reqwest::Client::builder()
+                .timeout(S3_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            bucket: settings.bucket.clone(),
+            region,
+            access_key: settings
+                .access_key
+                .clone()
+                .or_else(|| env("AWS_ACCESS_KEY_ID"))
+                .unwrap_or_default(),
+            secret_key: settings
+                .secret_key
+                .clone()
+                .or_else(|| env("AWS_SECRET_ACCESS_KEY"))
+                .unwrap_or_default(),
+            session_token: env("AWS_SESSION_TOKEN"),
+        }
+    }
+
+    pub fn bucket(&self) -> &str {
+        &self.bucket
+    }
+
+    // 返回新对象的 ETag（服务端未返回时为 None）
+    pub async fn put_object(&self, key: &str, content: Vec<u8>) -> Result<Option<String>, String> {
+        let (etag, _) = self.send(reqwest::Method::PUT, key, &[], content).await?;
+        Ok(etag)
+    }
+
+    // 对象不存在时返回 None
+    pub async fn get_object(&self, key: &str) -> Result<Option<Vec<u8>>, String> {
+        match self.send(reqwest::Method::GET, key, &[], vec![]).await {
+            Ok((_, body)) => Ok(Some(body)),
+            Err(e) if e.starts_with("404") => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    pub async fn delete_object(&self, key: &str) -> Result<(), String> {
+        self.send(reqwest::Method::DELETE, key, &[], vec![]).await?;
+        Ok(())
+    }
+
+    // ListObjectsV2，自动翻页
+    pub async fn list_objects(&self, prefix: &str) -> Result<Vec<S3Object>, String> {
+        let mut objects = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2".to_string()), ("prefix", prefix.to_string())];
+            if let Some(token) = &token {
+                query.push(("continuation-token", token.clone()));
+            }
+            let (_, body) = self.send(reqwest::Method::GET, "", &query, vec![]).await?;
+            let body = String::from_utf8_lossy(&body);
+            for contents in Self::elements(&body, "Contents") {
+                let key = Self::elements(contents, "Key").next().map(Self::unescape);
+                let size = Self::elements(contents, "Size")
+                    .next()
+                    .and_then(|size| size.parse().ok());
+                let etag = Self::elements(contents, "ETag")
+                    .next()
+                    .map(Self::unescape)
+                    .unwrap_or_default();
+                if let (Some(key), Some(size)) = (key, size) {
+                    objects.push(S3Object { key, size, etag });
+                }
+            }
+            token = match Self::elements(&body, "IsTruncated").next() {
+                Some("true") => Self::elements(&body, "NextContinuationToken")
+                    .next()
+                    .map(Self::unescape),
+                _ => None,
+            };
+            if token.is_none() {
+                return Ok(objects);
+            }
+        }
+    }
+
+    // 返回响应的 ETag 和内容；失败时错误以状态码开头，例如 "404 Not Found: ..."
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        key: &str,
+        query: &[(&str, String)],
+        body: Vec<u8>,
+    ) -> Result<(Option<String>, Vec<u8>), String> {
+        let path = match key {
+            "" => format!("/{}", Self::encode(&self.bucket, false)),
+            key => format!(
+                "/{}/{}",
+                Self::encode(&self.bucket, false),
+                Self::encode(key, true)
+            ),
+        };
+        let mut query: Vec<(String, String)> = query
+            .iter()
+            .map(|(k, v)| (Self::encode(k, false), Self::encode(v, false)))
+            .collect();
+        query.sort();
+        let query = query
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join("&");
+        let url = match query.as_str() {
+            "" => format!("{}{}", self.endpoint, path),
+            query => format!("{}{}?{}", self.endpoint, path, query),
+        };
+        let host = url::Url::parse(&url)
+            .ok()
+            .and_then(|url| {
+                let host = url.host_str()?.to_string();
+                Some(match url.port() {
+                    Some(port) => format!("{}:{}", host, port),
+                    None => host,
+                })
+            })
+            .ok_or_else(|| format!("invalid S3 endpoint {}", self.endpoint))?;
+
+        let now = Utc::now();
+        let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let date = now.format("%Y%m%d").to_string();
+        let payload_hash = sha256_hex(&body);
+        let mut headers = vec![
+            ("host", host),
+            ("x-amz-content-sha256", payload_hash.clone()),
+            ("x-amz-date", amz_date.clone()),
+        ];
+        if let Some(token) = &self.session_token {
+            headers.push(("x-amz-security-token", token.clone()));
+        }
+        let signed_headers = headers
+            .iter()
+            .map(|(name, _)| *name)
+            .collect::<Vec<_>>()
+            .join(";");
+        let canonical_headers: String = headers
+            .iter()
+            .map(|(name, value)| format!("{}:{}\n", name, value.trim()))
+            .collect();
+        let canonical_request = format!(
+            "{}\n{}\n{}\n{}\n{}\n{}",
+            method, path, query, canonical_headers, signed_headers, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            amz_date,
+            scope,
+            sha256_hex(canonical_request.as_bytes())
+        );
+        let key = [date.as_str(), &self.region, "s3", "aws4_request"]
+            .iter()
+            .fold(format!("AWS4{}", self.secret_key).into_bytes(), |key, part| {
+                Self::hmac(&key, part.as_bytes())
+            });
+        let signature = to_hex(&Self::hmac(&key, string_to_sign.as_bytes()));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+            self.access_key, scope, signed_headers, signature
+        );
+
+        let mut request = self
+            .http
+            .request(method, &url)
+            .header("authorization", authorization)
+            .body(body);
+        for (name, value) in headers.into_iter().filter(|(name, _)| *name != "host") {
+            request = request.header(name, value);
+        }
+        let response = request.send().await.map_err(|e| e.to_string())?;
+        let status = response.status();
+        let etag = response
+            .headers()
+            .get("etag")
+            .and_then(|etag| etag.to_str().ok())
+            .map(|etag| etag.to_string());
+        let body = response.bytes().await.map_err(|e| e.to_string())?;
+        if status.is_success() {
+            Ok((etag, body.to_vec()))
+        } else {
+            let message = Self::elements(&String::from_utf8_lossy(&body), "Message")
+                .next()
+                .map(Self::unescape)
+                .unwrap_or_default();
+            Err(format!("{}: {}", status, message))
+        }
+    }
+
+    fn hmac(key: &[u8], data: &[u8]) -> Vec<u8> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("hmac accepts any key length");
+        mac.update(data);
+        mac.finalize().into_bytes().to_vec()
+    }
+
+    // SigV4 的 URI 编码：只保留非保留字符，对象名中的 "/" 不编码
+    fn encode(value: &str, keep_slash: bool) -> String {
+        value
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                    (b as char).to_string()
+                }
+                b'/' if keep_slash => "/".to_string(),
+                b => format!("%{:02X}", b),
+            })
+            .collect()
+    }
+
+    // S3 响应是结构固定的 XML，按标签取出内容即可
+    fn elements<'a>(xml: &'a str, tag: &str) -> impl Iterator<Item = &'a str> {
+        let open = format!("<{}>", tag);
+        let close = format!("</{}>", tag);
+        let mut rest = xml;
+        std::iter::from_fn(move || {
+            let start = rest.find(&open)? + open.len();
+            let end = start + rest[start..].find(&close)?;
+            let element = &rest[start..end];
+            rest = &rest[end + close.len()..];
+            Some(element)
+        })
+    }
+
+    fn unescape(value: &str) -> String {
+        value
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&")
+    }
+}
@@ -0,0 +1,57 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    domain::entities::scheduled_change::ScheduledChange,
+    shared::{error::ConfigError, utils::atomic_write},
+};
+
+// 等待生效的修改保存在配置目录下，扩展名不是配置格式，不会被当作配置加载
+pub const SCHEDULES_FILE: &str = ".schedules.jsonl";
+
+// 每行一个修改，每次变化整体重写，服务重启后恢复
+#[derive(Debug, Clone)]
+pub struct FileScheduleStore {
+    path: PathBuf,
+}
+
+impl FileScheduleStore {
+    pub fn new(config_path: &str) -> Self {
+        Self {
+            path: Path::new(config_path).join(SCHEDULES_FILE),
+        }
+    }
+
+    pub fn load(&self) -> Result<Vec<ScheduledChange>, ConfigError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        std::fs::read_to_string(&self.path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line).map_err(|e| ConfigError::InvalidScheduledChanges {
+                    path: self.path.display().to_string(),
+                    error: e.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    // 没有等待生效的修改时删除文件
+    pub fn save(&self, changes: &[ScheduledChange]) -> Result<(), ConfigError> {
+        if changes.is_empty() {
+            if self.path.exists() {
+                std::fs::remove_file(&self.path)?;
+            }
+            return Ok(());
+        }
+        let mut lines = String::new();
+        for change in changes {
+            let line = serde_json::to_string(change).map_err(|_| ConfigError::ParseConfigError)?;
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+        atomic_write(&self.path, lines)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,57 @@
+use std::path::{Path, PathBuf};
+
+use crate::{
+    domain::entities::config_ttl::ConfigTtl,
+    shared::{error::ConfigError, utils::atomic_write},
+};
+
+// 未到期的过期规则保存在配置目录下，与定时修改一样不会被当作配置加载
+pub const TTLS_FILE: &str = ".ttls.jsonl";
+
+// 每行一条规则，重启后按原到期时间恢复
+#[derive(Debug, Clone)]
+pub struct FileTtlStore {
+    path: PathBuf,
+}
+
+impl FileTtlStore {
+    pub fn new(config_path: &str) -> Self {
+        Self {
+            path: Path::new(config_path).join(TTLS_FILE),
+        }
+    }
+
+    pub fn load(&self) -> Result<Vec<ConfigTtl>, ConfigError> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+        std::fs::read_to_string(&self.path)?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                serde_json::from_str(line).map_err(|e| ConfigError::InvalidTtls {
+                    path: self.path.display().to_string(),
+                    error: e.to_string(),
+                })
+            })
+            .collect()
+    }
+
+    // 没有规则时删除文件
+    pub fn save(&self, ttls: &[ConfigTtl]) -> Result<(), ConfigError> {
+        if ttls.is_empty() {
+            if self.path.exists() {
+                std::fs::remove_file(&self.path)?;
+            }
+            return Ok(());
+        }
+        let mut lines = String::new();
+        for ttl in ttls {
+            let line = serde_json::to_string(ttl).map_err(|_| ConfigError::ParseConfigError)?;
+            lines.push_str(&line);
+            lines.push('\n');
+        }
+        atomic_write(&self.path, lines)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,2 @@
+pub mod file_schedule_store;
+pub mod file_ttl_store;
@@ -0,0 +1,54 @@
+use crate::{domain::entities::audit::AuditRecord, shared::error::ConfigError};
+
+pub const CSV_HEADER: &str = "id,timestamp,action,config,source,actor,detail,changes";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditRecordFormat {
+    JsonLines,
+    Csv,
+}
+
+pub struct AuditRecordSerializer;
+
+impl AuditRecordSerializer {
+    // 解析 JSON Lines 中的一行
+    pub fn deserialize(line: &str) -> Result<AuditRecord, ConfigError> {
+        serde_json::from_str(line).map_err(|_| ConfigError::ParseConfigError)
+    }
+
+    // 序列化为单行文本（不含换行符）
+    pub fn serialize(record: &AuditRecord, format: AuditRecordFormat) -> Result<String, ConfigError> {
+        match format {
+            AuditRecordFormat::JsonLines => {
+                serde_json::to_string(record).map_err(|_| ConfigError::ParseConfigError)
+            }
+            // changes 列为 JSON 数组，没有差异时留空
+            AuditRecordFormat::Csv => Ok([
+                record.id.clone(),
+                record.timestamp.to_rfc3339(),
+                record.action.to_string(),
+                record.config.clone(),
+                record.source.clone(),
+                record.actor.clone().unwrap_or_default(),
+                record.detail.clone().unwrap_or_default(),
+                if record.changes.is_empty() {
+                    String::new()
+                } else {
+                    serde_json::to_string(&record.changes).map_err(|_| ConfigError::ParseConfigError)?
+                },
+            ]
+            .iter()
+            .map(|field| Self::csv_field(field))
+            .collect::<Vec<_>>()
+            .join(",")),
+        }
+    }
+
+    fn csv_field(field: &str) -> String {
+        if field.contains([',', '"', '\n', '\r']) {
+            format!("\"{}\"", field.replace('"', "\"\""))
+        } else {
+            field.to_string()
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod audit_record_serializer;
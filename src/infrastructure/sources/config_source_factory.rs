@@ -0,0 +1,28 @@
+use crate::{
+    domain::repositories::config_source::ConfigSource,
+    infrastructure::sources::{
+        file_config_source::FileConfigSource,
+        http_config_source::HttpConfigSource,
+        stdin_config_source::{STDIN_SOURCE, StdinConfigSource},
+    },
+    shared::utils::is_url,
+};
+
+pub struct ConfigSourceFactory;
+
+impl ConfigSourceFactory {
+    // "-" 表示标准输入，http:// 与 https:// 开头的为远程地址，其余按本地文件处理
+    pub fn create(spec: &str) -> Box<dyn ConfigSource> {
+        if spec == STDIN_SOURCE {
+            Box::new(StdinConfigSource)
+        } else if is_url(spec) {
+            Box::new(HttpConfigSource::new(spec))
+        } else {
+            Box::new(FileConfigSource::new(spec))
+        }
+    }
+
+    pub fn is_local(spec: &str) -> bool {
+        spec != STDIN_SOURCE && !is_url(spec)
+    }
+}
@@ -0,0 +1,34 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{
+        entities::configuration::Config, repositories::config_source::ConfigSource,
+        services::format_converter::FormatConverterService,
+        value_objects::config_path::ConfigPath,
+    },
+    shared::error::ConfigError,
+};
+
+pub struct FileConfigSource {
+    path: String,
+}
+
+impl FileConfigSource {
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: path.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl ConfigSource for FileConfigSource {
+    fn name(&self) -> &str {
+        &self.path
+    }
+
+    async fn load(&self) -> Result<Config, ConfigError> {
+        let content = tokio::fs::read_to_string(&self.path).await?;
+        FormatConverterService::new(ConfigPath::new(self.path.clone())?, content).validate_config()
+    }
+}
@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use reqwest::header::CONTENT_TYPE;
+
+use crate::{
+    domain::{
+        entities::configuration::{Config, ConfigValue},
+        repositories::config_source::ConfigSource,
+        services::format_converter::FormatConverterService,
+        value_objects::{config_format::ConfigType, config_path::ConfigPath},
+    },
+    shared::{error::ConfigError, utils::delete_ignore_line},
+};
+
+// 通过 HTTP(S) 获取配置，例如对象存储中的文件。格式优先按 Content-Type 判断，
+// 其次按 URL 路径的扩展名，最后按内容识别。
+// 另一个 config-manager 实例的 /api/configs/<name> 响应会被解包为其中的配置
+pub struct HttpConfigSource {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl HttpConfigSource {
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn content_type_format(content_type: &str) -> ConfigType {
+        let mime = content_type.split(';').next().unwrap_or_default().trim().to_lowercase();
+        if mime.ends_with("json5") {
+            ConfigType::Json5
+        } else if mime.ends_with("json") {
+            ConfigType::Json
+        } else if mime.ends_with("yaml") || mime.ends_with("yml") {
+            ConfigType::Yaml
+        } else if mime.ends_with("toml") {
+            ConfigType::Toml
+        } else {
+            ConfigType::Unknown
+        }
+    }
+
+    // config-manager 的 REST 响应：{ success, code, message, data: { type, config } }
+    fn unwrap_rest_response(&self, value: &serde_json::Value) -> Option<Result<Config, ConfigError>> {
+        let object = value.as_object()?;
+        let success = object.get("success")?.as_bool()?;
+        if !success {
+            if object.get("code").and_then(|c| c.as_u64()) == Some(404) {
+                return Some(Err(ConfigError::ConfigNotFound(self.url.clone())));
+            }
+            let message = object.get("message").and_then(|m| m.as_str()).unwrap_or_default();
+            return Some(Err(ConfigError::RemoteRequestFailed(format!(
+                "GET {}: {}",
+                self.url, message
+            ))));
+        }
+        let data = object.get("data")?.as_object()?;
+        let config_type: ConfigType = serde_json::from_value(data.get("type")?.clone()).ok()?;
+        let config = data.get("config")?.clone();
+        Some(
+            ConfigValue::from_serde_json(config)
+                .and_then(ConfigValue::into_object)
+                .map(|config| Config {
+                    path: ConfigPath::new(self.url.clone()).unwrap(),
+                    config,
+                    config_type,
+                }),
+        )
+    }
+}
+
+#[async_trait]
+impl ConfigSource for HttpConfigSource {
+    fn name(&self) -> &str {
+        &self.url
+    }
+
+    async fn load(&self) -> Result<Config, ConfigError> {
+        let response = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(ConfigError::ConfigNotFound(self.url.clone()));
+        }
+        if !status.is_success() {
+            return Err(ConfigError::RemoteRequestFailed(format!(
+                "GET {}: HTTP {}",
+                self.url, status
+            )));
+        }
+        let format = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .map(Self::content_type_format)
+            .unwrap_or(ConfigType::Unknown);
+        let content = response
+            .text()
+            .await
+            .map_err(|e| ConfigError::RemoteRequestFailed(e.to_string()))?;
+
+        if format == ConfigType::Json
+            && let Ok(value) = serde_json::from_str::<serde_json::Value>(&content)
+            && let Some(config) = self.unwrap_rest_response(&value)
+        {
+            return config;
+        }
+        if format != ConfigType::Unknown {
+            return Config::from(self.url.clone(), delete_ignore_line(&content), format);
+        }
+        // 去掉查询参数后按路径扩展名识别
+        let path = url::Url::parse(&self.url)
+            .map(|url| url.path().to_string())
+            .unwrap_or_else(|_| self.url.clone());
+        let mut config =
+            FormatConverterService::new(ConfigPath::new(path)?, content).validate_config_or_guess()?;
+        config.path = ConfigPath::new(self.url.clone())?;
+        Ok(config)
+    }
+}
@@ -0,0 +1,4 @@
+pub mod config_source_factory;
+pub mod file_config_source;
+pub mod http_config_source;
+pub mod stdin_config_source;
@@ -0,0 +1,29 @@
+use async_trait::async_trait;
+use tokio::io::AsyncReadExt;
+
+use crate::{
+    domain::{
+        entities::configuration::Config, repositories::config_source::ConfigSource,
+        services::format_converter::FormatConverterService,
+        value_objects::config_path::ConfigPath,
+    },
+    shared::error::ConfigError,
+};
+
+pub const STDIN_SOURCE: &str = "-";
+
+// 从标准输入读取整份配置，格式由内容识别
+pub struct StdinConfigSource;
+
+#[async_trait]
+impl ConfigSource for StdinConfigSource {
+    fn name(&self) -> &str {
+        STDIN_SOURCE
+    }
+
+    async fn load(&self) -> Result<Config, ConfigError> {
+        let mut content = String::new();
+        tokio::io::stdin().read_to_string(&mut content).await?;
+        FormatConverterService::new(ConfigPath::new(STDIN_SOURCE)?, content).validate_config_or_guess()
+    }
+}
@@ -0,0 +1,304 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        mpsc::{self, RecvTimeoutError},
+    },
+    time::{Duration, Instant},
+};
+
+use notify::{
+    Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher, event::ModifyKind,
+};
+use serde::Serialize;
+use tracing::debug;
+
+use crate::{
+    domain::{
+        entities::{configuration::Config, validation_rule::Validation},
+        services::{env_override::EnvOverrideService, format_converter::FormatConverterService},
+        value_objects::config_path::ConfigPath,
+    },
+    infrastructure::repositories::file_config_repository::FileConfigRepository,
+    shared::{
+        error::ConfigError,
+        utils::{is_atomic_write_temp, is_config_file},
+    },
+};
+
+pub const DEFAULT_WATCH_POLL_INTERVAL_MS: u64 = 1000;
+
+// 配置文件监听器：过滤临时文件和非配置文件，只把新建、修改或删除的配置文件路径交给回调
+pub struct ConfigWatcher {
+    watcher: Box<dyn Watcher + Send>,
+    health: Arc<WatcherHealth>,
+}
+
+// 监听方式：inotify 使用系统的文件通知（macOS 上为 FSEvents）；NFS 和部分 Docker 挂载卷上
+// 收不到通知，改用 poll 定时扫描，按内容哈希判断文件是否修改
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, clap::ValueEnum)]
+#[serde(rename_all = "snake_case")]
+pub enum WatchMode {
+    #[default]
+    Inotify,
+    Poll,
+}
+
+// 监听方式、poll 模式的扫描间隔和按文件去抖的窗口（为 0 时不合并）
+#[derive(Debug, Clone, Copy)]
+pub struct WatchOptions {
+    pub mode: WatchMode,
+    pub poll_interval: Duration,
+    pub debounce: Duration,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            mode: WatchMode::default(),
+            poll_interval: Duration::from_millis(DEFAULT_WATCH_POLL_INTERVAL_MS),
+            debounce: Duration::ZERO,
+        }
+    }
+}
+
+// 监听器运行状态，由 notify 的回调线程更新，供健康检查读取
+#[derive(Debug, Default)]
+pub struct WatcherHealth {
+    mode: WatchMode,
+    watching: AtomicBool,
+    events: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WatcherReport {
+    pub mode: WatchMode,
+    pub alive: bool,
+    pub events: u64,
+    pub last_error: Option<String>,
+}
+
+impl WatcherHealth {
+    // 已开始监听且最近一次回调没有出错
+    pub fn report(&self) -> WatcherReport {
+        let last_error = self.last_error.lock().unwrap().clone();
+        WatcherReport {
+            mode: self.mode,
+            alive: self.watching.load(Ordering::Relaxed) && last_error.is_none(),
+            events: self.events.load(Ordering::Relaxed),
+            last_error,
+        }
+    }
+
+    fn record(&self, result: &notify::Result<Event>) {
+        let mut last_error = self.last_error.lock().unwrap();
+        match result {
+            Ok(_) => {
+                self.events.fetch_add(1, Ordering::Relaxed);
+                *last_error = None;
+            }
+            Err(e) => *last_error = Some(e.to_string()),
+        }
+    }
+}
+
+impl ConfigWatcher {
+    pub fn new<F>(on_change: F) -> Result<Self, ConfigError>
+    where
+        F: Fn(PathBuf) + Send + 'static,
+    {
+        Self::with_options(WatchOptions::default(), on_change)
+    }
+
+    // 按文件去抖：同一文件在 debounce 内的多次事件合并，最后一次事件之后安静 debounce 才回调
+    // 一次。回调在单独的线程中执行，监听器释放后先处理完未到期的文件再退出
+    pub fn with_options<F>(options: WatchOptions, on_change: F) -> Result<Self, ConfigError>
+    where
+        F: Fn(PathBuf) + Send + 'static,
+    {
+        let window = options.debounce;
+        if window.is_zero() {
+            return Self::start(options, on_change);
+        }
+        let (tx, rx) = mpsc::channel::<PathBuf>();
+        std::thread::spawn(move || {
+            let mut deadlines: HashMap<PathBuf, Instant> = HashMap::new();
+            loop {
+                let received = match deadlines.values().min() {
+                    Some(deadline) => {
+                        rx.recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                    }
+                    None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                match received {
+                    Ok(path) => {
+                        deadlines.insert(path, Instant::now() + window);
+                    }
+                    Err(RecvTimeoutError::Timeout) => {
+                        let now = Instant::now();
+                        let due: Vec<PathBuf> = deadlines
+                            .iter()
+                            .filter(|(_, deadline)| **deadline <= now)
+                            .map(|(path, _)| path.clone())
+                            .collect();
+                        for path in due {
+                            deadlines.remove(&path);
+                            on_change(path);
+                        }
+                    }
+                    Err(RecvTimeoutError::Disconnected) => {
+                        deadlines.into_keys().for_each(&on_change);
+                        return;
+                    }
+                }
+            }
+        });
+        Self::start(options, move |file_path| {
+            if tx.send(file_path).is_err() {
+                debug!("debounce thread exited");
+            }
+        })
+    }
+
+    fn start<F>(options: WatchOptions, on_change: F) -> Result<Self, ConfigError>
+    where
+        F: Fn(PathBuf) + Send + 'static,
+    {
+        let health = Arc::new(WatcherHealth {
+            mode: options.mode,
+            ..Default::default()
+        });
+        let callback_health = health.clone();
+        let handler = move |result: notify::Result<Event>| {
+            callback_health.record(&result);
+            let event = match result {
+                Ok(event) => event,
+                Err(e) => {
+                    debug!("file watch error: {}", e);
+                    return;
+                }
+            };
+            if !(event.kind.is_create() || event.kind.is_modify() || event.kind.is_remove())
+                || event.paths.contains(&PathBuf::from("target"))
+            {
+                return;
+            }
+            debug!("config file event: {:?}", event);
+            let added = matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(ModifyKind::Name(_))
+            );
+            // 新建、修改、删除和重命名（两端路径）都交给回调，回调按文件是否还存在区分
+            for path in event.paths.iter() {
+                // 新建或移入的子目录：递归监听生效之前可能已写入文件，逐个交给回调
+                if added && path.is_dir() {
+                    let repository = FileConfigRepository::new(path.to_string_lossy().to_string());
+                    for (_, file_path) in repository.files().unwrap_or_default() {
+                        if Self::is_watched_file(&file_path) {
+                            on_change(file_path);
+                        }
+                    }
+                } else if Self::is_watched_file(path) {
+                    on_change(path.clone());
+                }
+            }
+        };
+        let watcher: Box<dyn Watcher + Send> = match options.mode {
+            WatchMode::Inotify => Box::new(
+                RecommendedWatcher::new(handler, notify::Config::default())
+                    .map_err(|e| ConfigError::WatchError(e.to_string()))?,
+            ),
+            // 只比较修改时间在挂载卷上不可靠，按内容哈希判断
+            WatchMode::Poll => {
+                let config = notify::Config::default()
+                    .with_poll_interval(options.poll_interval)
+                    .with_compare_contents(true);
+                Box::new(
+                    PollWatcher::new(handler, config)
+                        .map_err(|e| ConfigError::WatchError(e.to_string()))?,
+                )
+            }
+        };
+        Ok(Self { watcher, health })
+    }
+
+    pub fn health(&self) -> Arc<WatcherHealth> {
+        self.health.clone()
+    }
+
+    pub fn watch(&mut self, path: &Path, recursive: bool) -> Result<(), ConfigError> {
+        let mode = if recursive {
+            RecursiveMode::Recursive
+        } else {
+            RecursiveMode::NonRecursive
+        };
+        self.watcher
+            .watch(path, mode)
+            .map_err(|e| ConfigError::WatchError(format!("{}: {}", path.display(), e)))?;
+        self.health.watching.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    // 读取并解析变更后的文件，返回 (文件名, 填充默认值后的配置, 应用环境变量覆盖后的 JSON)
+    pub fn load(
+        file_path: &Path,
+        validation: Option<&Validation>,
+    ) -> Result<(String, Config, String), ConfigError> {
+        let file_name = file_path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .ok_or(ConfigError::InvalidPath)?;
+        Self::load_as(file_path, file_name, validation)
+    }
+
+    // 同 load，但以给定的配置名称（例如带命名空间前缀）代替文件名
+    pub fn load_as(
+        file_path: &Path,
+        name: String,
+        validation: Option<&Validation>,
+    ) -> Result<(String, Config, String), ConfigError> {
+        let content = std::fs::read_to_string(file_path)?;
+        Self::parse_as(content, name, validation)
+    }
+
+    // 同 load_as，解析已经读出的文件内容
+    pub fn parse_as(
+        content: String,
+        name: String,
+        validation: Option<&Validation>,
+    ) -> Result<(String, Config, String), ConfigError> {
+        let mut config = FormatConverterService::new(ConfigPath::new(name.clone())?, content)
+            .validate_config()?;
+        if let Some(validation) = validation {
+            validation.apply_defaults(&mut config)?;
+        }
+        let released = EnvOverrideService::apply_env_override(&mut config)?;
+        let config_str =
+            serde_json::to_string(&released.to_serde_value()).unwrap_or_else(|_| "{}".to_string());
+        Ok((name, config, config_str))
+    }
+
+    fn is_watched_file(file_path: &Path) -> bool {
+        let Some(file_name) = file_path.file_name() else {
+            return false;
+        };
+        let file_name = file_name.to_string_lossy();
+        // 包括 atomic_write 自己的临时文件，重命名为目标文件时才会触发重新加载
+        if file_name.starts_with('.')
+            || file_name.ends_with(".tmp")
+            || file_name.ends_with('~')
+            || is_atomic_write_temp(&file_name)
+        {
+            debug!("ignore temporary file: {}", file_name);
+            return false;
+        }
+        if !is_config_file(&file_name) {
+            debug!("ignore non-config file: {}", file_name);
+            return false;
+        }
+        true
+    }
+}
@@ -0,0 +1,2 @@
+pub mod config_watcher;
+pub mod reload_handle;
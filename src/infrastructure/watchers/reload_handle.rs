@@ -0,0 +1,144 @@
+use std::{
+    collections::HashMap,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::broadcast::{self, error::RecvError};
+use tracing::debug;
+
+use crate::{
+    domain::{
+        entities::configuration::Config, events::config_changed::ChangeEvent,
+        value_objects::key_pattern::KeyPattern,
+    },
+    infrastructure::{
+        repositories::file_config_repository::FileConfigRepository,
+        watchers::config_watcher::ConfigWatcher,
+    },
+    shared::error::ConfigError,
+};
+
+const CHANGE_CHANNEL_CAPACITY: usize = 1024;
+
+// 订阅关心的文件和键；都为空时接收所有变更。键模式匹配变更键本身或其任一父键
+#[derive(Debug, Clone, Default)]
+pub struct ReloadInterest {
+    pub files: Vec<String>,
+    pub keys: Vec<KeyPattern>,
+}
+
+impl ReloadInterest {
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    pub fn file(mut self, file: &str) -> Self {
+        self.files.push(file.to_string());
+        self
+    }
+
+    pub fn key(mut self, pattern: KeyPattern) -> Self {
+        self.keys.push(pattern);
+        self
+    }
+
+    pub fn matches(&self, event: &ChangeEvent) -> bool {
+        if !self.files.is_empty() && !self.files.contains(&event.file) {
+            return false;
+        }
+        if self.keys.is_empty() {
+            return true;
+        }
+        let path: Vec<&str> = event.key.split('.').collect();
+        self.keys
+            .iter()
+            .any(|pattern| (1..=path.len()).any(|n| pattern.matches(&path[..n])))
+    }
+}
+
+// 嵌入库的应用使用：监听配置目录，按键推送新旧值，不必自行包装 notify
+pub struct ReloadHandle {
+    configs: Arc<Mutex<HashMap<String, Config>>>,
+    sender: broadcast::Sender<ChangeEvent>,
+    _watcher: ConfigWatcher,
+}
+
+impl ReloadHandle {
+    pub fn new(config_path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let config_path = config_path.as_ref();
+        // 子目录中的配置以相对配置目录的路径为名称
+        let repository = FileConfigRepository::new(config_path.to_string_lossy().to_string());
+        let mut configs = HashMap::new();
+        for (name, path) in repository.files()? {
+            let (name, config, _) = ConfigWatcher::load_as(&path, name, None)?;
+            configs.insert(name, config);
+        }
+        let configs = Arc::new(Mutex::new(configs));
+        let (sender, _) = broadcast::channel(CHANGE_CHANNEL_CAPACITY);
+
+        let configs_for_watcher = configs.clone();
+        let sender_for_watcher = sender.clone();
+        let mut watcher = ConfigWatcher::new(move |file_path| {
+            let Some(name) = repository.config_name(&file_path) else {
+                return;
+            };
+            let (file_name, config, _) = match ConfigWatcher::load_as(&file_path, name, None) {
+                Ok(loaded) => loaded,
+                Err(e) => {
+                    // 解析失败时保留旧配置，等待下一次修改
+                    debug!("config reload failed: {:?} - {}", file_path, e);
+                    return;
+                }
+            };
+            let mut configs = configs_for_watcher.lock().unwrap();
+            let events = ChangeEvent::diff(
+                &file_name,
+                configs.get(&file_name),
+                &config,
+                chrono::Utc::now(),
+            );
+            configs.insert(file_name, config);
+            for event in events {
+                // 没有订阅者时发送失败，忽略即可
+                let _ = sender_for_watcher.send(event);
+            }
+        })?;
+        watcher.watch(config_path, true)?;
+
+        Ok(Self {
+            configs,
+            sender,
+            _watcher: watcher,
+        })
+    }
+
+    // 当前已加载的配置
+    pub fn current(&self, file: &str) -> Option<Config> {
+        self.configs.lock().unwrap().get(file).cloned()
+    }
+
+    pub fn subscribe(&self, interest: ReloadInterest) -> ReloadSubscription {
+        ReloadSubscription {
+            interest,
+            receiver: self.sender.subscribe(),
+        }
+    }
+}
+
+pub struct ReloadSubscription {
+    interest: ReloadInterest,
+    receiver: broadcast::Receiver<ChangeEvent>,
+}
+
+impl ReloadSubscription {
+    // 等待下一个匹配的变更；消费过慢时返回 RecvError::Lagged，之后可继续接收
+    pub async fn recv(&mut self) -> Result<ChangeEvent, RecvError> {
+        loop {
+            let event = self.receiver.recv().await?;
+            if self.interest.matches(&event) {
+                return Ok(event);
+            }
+        }
+    }
+}
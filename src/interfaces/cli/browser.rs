@@ -0,0 +1,356 @@
+use std::{collections::HashSet, io::Write};
+
+use ratatui::{
+    DefaultTerminal, Frame,
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Paragraph},
+};
+
+use crate::domain::entities::configuration::{Config, ConfigValue};
+
+// 树中可见的一行
+struct TreeRow {
+    path: String,
+    key: String,
+    depth: usize,
+    is_last: bool,
+    value: ConfigValue,
+}
+
+enum Mode {
+    Normal,
+    Search,
+}
+
+pub struct ConfigBrowser {
+    config: Config,
+    title: String,
+    expanded: HashSet<String>,
+    state: ListState,
+    mode: Mode,
+    query: String,
+    matches: Vec<String>,
+    status: String,
+}
+
+impl ConfigBrowser {
+    pub fn new(config: Config, title: String) -> Self {
+        let mut state = ListState::default();
+        state.select(Some(0));
+        Self {
+            config,
+            title,
+            expanded: HashSet::new(),
+            state,
+            mode: Mode::Normal,
+            query: String::new(),
+            matches: vec![],
+            status: "↑/↓ move  →/← expand/collapse  / search  n next  y copy path  q quit"
+                .to_string(),
+        }
+    }
+
+    pub fn run(mut self) -> std::io::Result<()> {
+        let mut terminal = ratatui::init();
+        let result = self.event_loop(&mut terminal);
+        ratatui::restore();
+        result
+    }
+
+    fn event_loop(&mut self, terminal: &mut DefaultTerminal) -> std::io::Result<()> {
+        loop {
+            let rows = self.visible_rows();
+            terminal.draw(|frame| self.draw(frame, &rows))?;
+
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+
+            match self.mode {
+                Mode::Search => match key.code {
+                    KeyCode::Enter => {
+                        self.mode = Mode::Normal;
+                        self.search();
+                    }
+                    KeyCode::Esc => self.mode = Mode::Normal,
+                    KeyCode::Backspace => {
+                        self.query.pop();
+                    }
+                    KeyCode::Char(c) => self.query.push(c),
+                    _ => {}
+                },
+                Mode::Normal => match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Down | KeyCode::Char('j') => self.move_selection(&rows, 1),
+                    KeyCode::Up | KeyCode::Char('k') => self.move_selection(&rows, -1),
+                    KeyCode::Right | KeyCode::Char('l') => {
+                        if let Some(row) = self.selected(&rows) {
+                            self.expanded.insert(row.path.clone());
+                        }
+                    }
+                    KeyCode::Left | KeyCode::Char('h') => {
+                        if let Some(row) = self.selected(&rows) {
+                            self.expanded.remove(&row.path);
+                        }
+                    }
+                    KeyCode::Enter | KeyCode::Char(' ') => {
+                        if let Some(row) = self.selected(&rows) {
+                            let path = row.path.clone();
+                            if !self.expanded.remove(&path) {
+                                self.expanded.insert(path);
+                            }
+                        }
+                    }
+                    KeyCode::Char('/') => {
+                        self.mode = Mode::Search;
+                        self.query.clear();
+                    }
+                    KeyCode::Char('n') => self.next_match(),
+                    KeyCode::Char('y') | KeyCode::Char('c') => {
+                        if let Some(row) = self.selected(&rows) {
+                            let path = row.path.clone();
+                            self.copy_to_clipboard(&path)?;
+                            self.status = format!("copied: {}", path);
+                        }
+                    }
+                    _ => {}
+                },
+            }
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame, rows: &[TreeRow]) {
+        let [tree_area, status_area] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+
+        let items: Vec<ListItem> = rows
+            .iter()
+            .map(|row| {
+                let mut prefix = Config::get_tree_prefix(row.depth, row.is_last);
+                let marker = match &row.value {
+                    ConfigValue::Object(_) | ConfigValue::Array(_) => {
+                        if self.expanded.contains(&row.path) {
+                            "▾ "
+                        } else {
+                            "▸ "
+                        }
+                    }
+                    _ => "",
+                };
+                prefix.push_str(marker);
+                let value_color = match &row.value {
+                    ConfigValue::Boolean(_) => Color::Magenta,
+                    ConfigValue::Null => Color::Red,
+                    _ => Color::Green,
+                };
+                let highlight = !self.matches.is_empty() && self.matches.contains(&row.path);
+                let key_style = if highlight {
+                    Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::Blue)
+                };
+                ListItem::new(Line::from(vec![
+                    Span::raw(prefix),
+                    Span::styled(row.key.clone(), key_style),
+                    Span::raw(": "),
+                    Span::styled(row.value.summary(), Style::default().fg(value_color)),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!(" {} ({}) ", self.title, self.config.config_type)),
+            )
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, tree_area, &mut self.state);
+
+        let status = match self.mode {
+            Mode::Search => format!("/{}", self.query),
+            Mode::Normal => self.status.clone(),
+        };
+        frame.render_widget(Paragraph::new(status), status_area);
+    }
+
+    fn visible_rows(&self) -> Vec<TreeRow> {
+        let mut rows = Vec::new();
+        Self::collect_rows(&self.config.config, "", 0, &self.expanded, &mut rows);
+        rows
+    }
+
+    fn collect_rows(
+        object: &std::collections::HashMap<String, ConfigValue>,
+        parent: &str,
+        depth: usize,
+        expanded: &HashSet<String>,
+        rows: &mut Vec<TreeRow>,
+    ) {
+        let mut keys: Vec<&String> = object.keys().collect();
+        keys.sort();
+        let children: Vec<(String, &ConfigValue)> =
+            keys.into_iter().map(|k| (k.clone(), &object[k])).collect();
+        Self::collect_children(children, parent, depth, expanded, rows);
+    }
+
+    fn collect_children(
+        children: Vec<(String, &ConfigValue)>,
+        parent: &str,
+        depth: usize,
+        expanded: &HashSet<String>,
+        rows: &mut Vec<TreeRow>,
+    ) {
+        let count = children.len();
+        for (index, (key, value)) in children.into_iter().enumerate() {
+            let path = Self::join_path(parent, &key);
+            rows.push(TreeRow {
+                path: path.clone(),
+                key,
+                depth,
+                is_last: index == count - 1,
+                value: value.clone(),
+            });
+            if !expanded.contains(&path) {
+                continue;
+            }
+            match value {
+                ConfigValue::Object(obj) => {
+                    Self::collect_rows(obj, &path, depth + 1, expanded, rows);
+                }
+                ConfigValue::Array(arr) => {
+                    let items = arr
+                        .iter()
+                        .enumerate()
+                        .map(|(i, v)| (format!("[{}]", i), v))
+                        .collect();
+                    Self::collect_children(items, &path, depth + 1, expanded, rows);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn join_path(parent: &str, key: &str) -> String {
+        if parent.is_empty() {
+            key.to_string()
+        } else if key.starts_with('[') {
+            format!("{}{}", parent, key)
+        } else {
+            format!("{}.{}", parent, key)
+        }
+    }
+
+    fn selected<'a>(&self, rows: &'a [TreeRow]) -> Option<&'a TreeRow> {
+        self.state.selected().and_then(|i| rows.get(i))
+    }
+
+    fn move_selection(&mut self, rows: &[TreeRow], delta: isize) {
+        if rows.is_empty() {
+            return;
+        }
+        let current = self.state.selected().unwrap_or(0) as isize;
+        let next = (current + delta).clamp(0, rows.len() as isize - 1);
+        self.state.select(Some(next as usize));
+    }
+
+    // 搜索匹配键路径或标量值的节点，并展开其所有祖先
+    fn search(&mut self) {
+        self.matches.clear();
+        if self.query.is_empty() {
+            return;
+        }
+        let query = self.query.to_lowercase();
+        let mut every_path = Vec::new();
+        Self::collect_all(&self.config.config, "", &mut every_path);
+        for (path, value) in every_path {
+            let scalar = match &value {
+                ConfigValue::Object(_) | ConfigValue::Array(_) => String::new(),
+                other => other.summary().to_lowercase(),
+            };
+            if path.to_lowercase().contains(&query) || scalar.contains(&query) {
+                self.matches.push(path);
+            }
+        }
+        let ancestors: Vec<String> = self
+            .matches
+            .iter()
+            .flat_map(|path| Self::ancestors(path))
+            .collect();
+        self.expanded.extend(ancestors);
+        self.status = format!("{} matches for '{}'", self.matches.len(), self.query);
+        self.next_match();
+    }
+
+    fn next_match(&mut self) {
+        if self.matches.is_empty() {
+            return;
+        }
+        let rows = self.visible_rows();
+        let current = self.state.selected().unwrap_or(0);
+        let positions: Vec<usize> = rows
+            .iter()
+            .enumerate()
+            .filter(|(_, row)| self.matches.contains(&row.path))
+            .map(|(i, _)| i)
+            .collect();
+        let next = positions
+            .iter()
+            .find(|&&i| i > current)
+            .or(positions.first())
+            .copied();
+        if let Some(next) = next {
+            self.state.select(Some(next));
+        }
+    }
+
+    fn collect_all(
+        object: &std::collections::HashMap<String, ConfigValue>,
+        parent: &str,
+        out: &mut Vec<(String, ConfigValue)>,
+    ) {
+        for (key, value) in object {
+            let path = Self::join_path(parent, key);
+            Self::collect_value(&path, value, out);
+        }
+    }
+
+    fn collect_value(path: &str, value: &ConfigValue, out: &mut Vec<(String, ConfigValue)>) {
+        out.push((path.to_string(), value.clone()));
+        match value {
+            ConfigValue::Object(obj) => Self::collect_all(obj, path, out),
+            ConfigValue::Array(arr) => {
+                for (i, item) in arr.iter().enumerate() {
+                    Self::collect_value(&format!("{}[{}]", path, i), item, out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // a.b[0].c -> [a, a.b, a.b[0]]
+    fn ancestors(path: &str) -> Vec<String> {
+        let mut result = Vec::new();
+        for (i, c) in path.char_indices() {
+            if (c == '.' || c == '[') && i > 0 {
+                result.push(path[..i].to_string());
+            }
+        }
+        result
+    }
+
+    // 通过 OSC 52 转义序列写入终端剪贴板
+    fn copy_to_clipboard(&self, text: &str) -> std::io::Result<()> {
+        use base64::Engine;
+        let encoded = base64::engine::general_purpose::STANDARD.encode(text);
+        let mut stdout = std::io::stdout();
+        write!(stdout, "\x1b]52;c;{}\x07", encoded)?;
+        stdout.flush()
+    }
+}
@@ -1,35 +1,255 @@
+use crate::domain::entities::access_policy::Permission;
+use crate::infrastructure::logging::log_manager::{LogFormat, RotationPeriod};
+use crate::infrastructure::notification::subscriber_quota::QuotaAction;
+use crate::infrastructure::watchers::config_watcher::WatchMode;
+use crate::interfaces::cli::diff_renderer::DiffFormat;
+use crate::interfaces::cli::validation_report::ValidationReportFormat;
+
 #[derive(Debug, clap::Parser)]
 pub struct Command {
+    // 远程服务地址或 contexts.yaml 中的上下文名称
+    #[clap(long, global = true)]
+    pub server: Option<String>,
+    // 读写嵌入在其他文件中的配置块，例如 '---yaml'、'+++toml'、'```json'
+    #[clap(long, global = true, allow_hyphen_values = true)]
+    pub embedded: Option<String>,
+    #[clap(long, global = true, value_enum, default_value = "text")]
+    pub error_format: ErrorFormat,
+    #[clap(flatten)]
+    pub log: LogArgs,
     #[clap(subcommand)]
     pub subcommand: Subcommand,
 }
 
+// 运行日志（标准输出）与日志文件的格式、过滤和切分
+#[derive(Debug, clap::Args)]
+pub struct LogArgs {
+    #[clap(long, global = true, value_enum, default_value = "text")]
+    pub log_format: LogFormat,
+    // EnvFilter 语法，可以按模块指定级别，例如 "info,config_manager::interfaces::http=debug"；
+    // 未指定时读取 RUST_LOG，默认 debug
+    #[clap(long, global = true)]
+    pub log_filter: Option<String>,
+    // 未指定时写入状态目录：$XDG_STATE_HOME/config-manager/，默认 ~/.local/state/config-manager/
+    #[clap(long, global = true)]
+    pub log_file: Option<String>,
+    // 日志文件超过该字节数时切分
+    #[clap(long, global = true)]
+    pub log_max_size: Option<u64>,
+    // 按小时或按天切分日志文件
+    #[clap(long, global = true, value_enum)]
+    pub log_rotate: Option<RotationPeriod>,
+    // 保留的切分文件数
+    #[clap(long, global = true, default_value = "5")]
+    pub log_keep: usize,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum ErrorFormat {
+    Text,
+    Json,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum ReportFormat {
+    Text,
+    Json,
+}
+
 #[derive(Debug, clap::Subcommand)]
 pub enum Subcommand {
     #[clap(name = "validate")]
     Validate {
-        file: String,
+        // 本地路径、目录、glob、http(s):// 地址或 "-"（标准输入）
+        #[clap(required = true)]
+        files: Vec<String>,
         #[clap(short, long, default_value = "")]
         validate_file: String,
+        #[clap(short, long)]
+        watch: bool,
+        // 警告也视为校验失败
+        #[clap(long)]
+        strict: bool,
+        // 校验通过后用规则中的 defaults 填充缺失的键，写入 --output
+        #[clap(long, requires = "output")]
+        apply_defaults: bool,
+        #[clap(short, long)]
+        output: Option<String>,
+        // CI 报告格式；未指定 --report-file 时报告代替文本摘要输出到标准输出
+        #[clap(long, value_enum)]
+        report: Option<ValidationReportFormat>,
+        #[clap(long, requires = "report")]
+        report_file: Option<String>,
+    },
+
+    // 一次完成解析、lint、校验规则和 schema 检查，由仓库根目录的 configmanager.toml 配置；
+    // 适合作为 pre-commit 钩子，传入的文件覆盖项目中的 files
+    #[clap(name = "check")]
+    Check {
+        files: Vec<String>,
+        // 项目文件路径，默认从当前目录向上查找 configmanager.toml
+        #[clap(long)]
+        project: Option<String>,
+        #[clap(long)]
+        strict: bool,
+        #[clap(long, value_enum, default_value = "text")]
+        format: ReportFormat,
+    },
+
+    // 运行断言文件，测试基础配置、profile 覆盖和环境变量叠加后的结果；默认读取项目根目录下的 tests/
+    #[clap(name = "test")]
+    Test {
+        files: Vec<String>,
+        #[clap(long, value_enum, default_value = "text")]
+        format: ReportFormat,
+    },
+
+    // 对比配置与 JSON Schema（可由应用结构体生成），报告多余的键和缺失的字段
+    #[clap(name = "drift")]
+    Drift {
+        #[clap(required = true)]
+        files: Vec<String>,
+        #[clap(short, long)]
+        schema: String,
+        #[clap(long, value_enum, default_value = "text")]
+        format: ReportFormat,
+    },
+
+    // 对比两份配置，可输出为终端 diff、并排表格、HTML 报告或 JSON
+    #[clap(name = "diff")]
+    Diff {
+        left: String,
+        right: String,
+        #[clap(long, value_enum, default_value = "unified")]
+        format: DiffFormat,
+        #[clap(short, long)]
+        output: Option<String>,
     },
 
     #[clap(name = "show")]
     Show {
+        // 本地路径、http(s):// 地址或 "-"（标准输入）
         file: String,
         #[clap(short, long, default_value = "")]
         get: String,
         #[clap(short, long, default_value = "5")]
         deepth: usize,
+        #[clap(short, long)]
+        watch: bool,
+        #[clap(long)]
+        doc_index: Option<usize>,
+    },
+
+    #[clap(name = "stats")]
+    Stats { file: String },
+
+    #[clap(name = "browse")]
+    Browse { file: String },
+
+    #[clap(name = "get")]
+    Get {
+        file: String,
+        key: String,
+        #[clap(long)]
+        doc_index: Option<usize>,
+    },
+
+    #[clap(name = "set")]
+    Set {
+        file: String,
+        key: String,
+        value: String,
+    },
+
+    // 配置的历史版本：本地读取当前目录 .history/ 中 serve 记录的版本，--server 时读取服务端
+    #[clap(name = "history")]
+    History {
+        file: String,
+        // 输出该版本的原始内容
+        #[clap(long)]
+        version: Option<u64>,
+    },
+
+    // 按校验文件中的 deprecated_fields 把废弃键改写为替代键
+    #[clap(name = "migrate")]
+    Migrate {
+        #[clap(required = true)]
+        files: Vec<String>,
+        #[clap(short, long)]
+        validate_file: String,
+        #[clap(long)]
+        dry_run: bool,
+    },
+
+    #[clap(name = "refactor")]
+    Refactor {
+        #[clap(subcommand)]
+        action: RefactorAction,
     },
 
     #[clap(name = "convert")]
-    Convert { input: String, output: String },
+    Convert {
+        input: String,
+        output: String,
+        #[clap(long, value_delimiter = ',')]
+        only: Vec<String>,
+        #[clap(long, value_delimiter = ',')]
+        exclude: Vec<String>,
+        #[clap(long)]
+        doc_index: Option<usize>,
+    },
+
+    #[clap(name = "format")]
+    Format {
+        file: String,
+        #[clap(long, default_value = "false")]
+        sort_keys: bool,
+        #[clap(long, default_value = "2")]
+        indent: usize,
+    },
 
-    #[clap(name = "template")]
+    #[clap(name = "template", args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
     Template {
-        template: String,
+        #[clap(required = true)]
+        template: Option<String>,
         #[clap(short, long, default_value = "toml")]
         format: String,
+        #[clap(subcommand)]
+        action: Option<TemplateAction>,
+    },
+
+    #[clap(name = "bundle")]
+    Bundle {
+        #[clap(subcommand)]
+        action: BundleAction,
+    },
+
+    #[clap(name = "fixtures")]
+    Fixtures {
+        #[clap(subcommand)]
+        action: FixturesAction,
+    },
+
+    #[clap(name = "shell")]
+    Shell {
+        #[clap(short = 'H', long, default_value = "127.0.0.1")]
+        host: String,
+        #[clap(short, long, default_value = "8080")]
+        port: u16,
+    },
+
+    #[clap(name = "preflight")]
+    Preflight {
+        #[clap(short, long, default_value = "8080")]
+        port: u16,
+        #[clap(short = 'H', long, default_value = "0.0.0.0")]
+        host: String,
+        #[clap(short, long, default_value = ".")]
+        config_path: String,
+        #[clap(long)]
+        tls_cert: Option<String>,
+        #[clap(long, default_value = "14")]
+        tls_expiry_days: i64,
     },
 
     #[clap(name = "serve")]
@@ -42,6 +262,231 @@ pub enum Subcommand {
         config_path: String,
         #[clap(long, default_value = "false")]
         http: bool,
+        #[clap(long, default_value = "300")]
+        resume_window: u64,
+        #[clap(long)]
+        max_bytes_per_sec: Option<u64>,
+        #[clap(long)]
+        max_payload: Option<u64>,
+        #[clap(long, value_enum, default_value = "throttle")]
+        quota_action: QuotaAction,
+        #[clap(long)]
+        webhook: Vec<String>,
+        #[clap(long, default_value = "3")]
+        webhook_retries: u32,
+        #[clap(long)]
+        settings: Option<String>,
+        #[clap(flatten)]
+        load: Box<LoadArgs>,
+        // 消费者需在配置修改后多少秒内拿到新配置，超出则告警
+        #[clap(long, default_value = "300")]
+        freshness_slo: u64,
+        #[clap(flatten)]
+        bind: Box<BindArgs>,
+        // 加载配置时使用的校验规则文件，缺失的键按其中的 defaults 填充
+        #[clap(short, long)]
+        validate_file: Option<String>,
+        // HTTP 的 PUT / PATCH 必须携带 If-Match，缺失时返回 428
+        #[clap(long)]
+        require_if_match: bool,
+        // 每个配置在 .history/ 中保留的历史版本数，0 表示不记录
+        #[clap(long, default_value = "20")]
+        history_limit: usize,
+        // 按配置名授权的访问策略文件，调用方身份为 API key 名称
+        #[clap(long)]
+        policy: Option<String>,
+        #[clap(flatten)]
+        limits: Box<RequestLimitArgs>,
+    },
+}
+
+// serve 的监听地址，以及绑定后的降权
+#[derive(Debug, clap::Args)]
+pub struct BindArgs {
+    // 监听该路径的 Unix socket 而不是 TCP 端口，便于作为 sidecar 部署并以文件权限控制访问
+    #[clap(long)]
+    pub uds: Option<String>,
+    // 绑定端口后切换到的用户 / 组（名称或 id）
+    #[clap(long)]
+    pub user: Option<String>,
+    #[clap(long)]
+    pub group: Option<String>,
+    // 绑定端口后 chroot 到该目录，配置目录必须位于其中；不能与 --settings、文件审计 sink 和日志切分同时使用
+    #[clap(long)]
+    pub chroot: Option<String>,
+}
+
+// serve 加载、缓存和监听配置目录的方式
+#[derive(Debug, clap::Args)]
+pub struct LoadArgs {
+    #[clap(long)]
+    pub memory_budget: Option<usize>,
+    #[clap(long, default_value = "8")]
+    pub startup_workers: usize,
+    // 启动时不解析配置，首次访问时才加载；配合 --memory-budget 淘汰长时间未访问的配置
+    #[clap(long)]
+    pub lazy_load: bool,
+    // 文件监听器的去抖窗口（毫秒）：同一文件在窗口内的多次修改合并为一次重新加载，0 表示不合并
+    #[clap(long, default_value = "200")]
+    pub watch_debounce: u64,
+    // 文件监听方式：NFS 或收不到文件通知的挂载卷上使用 poll
+    #[clap(long, value_enum, default_value = "inotify")]
+    pub watch_mode: WatchMode,
+    // poll 模式扫描配置目录的间隔（毫秒）
+    #[clap(long, default_value = "1000")]
+    pub watch_poll_interval: u64,
+    // 配置的存储位置：file 只使用配置目录；s3://bucket/prefix 启动时从 bucket 拉取配置，
+    // 之后配置目录与 bucket 双向同步，每个命名空间对应 prefix 下的一个子前缀；
+    // sqlite:path.db 同样双向同步，历史版本、审计记录和附加规则也保存在数据库中；
+    // etcd://host:2379/prefix、consul://host:8500/prefix 通过原生 watch 镜像已有 KV 中的配置；
+    // k8s://namespace（需要 k8s-sync feature）同步 ConfigMap 与 Secret，修改写回 ConfigMap
+    #[clap(long, default_value = "file")]
+    pub storage: String,
+    // 检查存储中配置是否被修改的间隔（毫秒），etcd、Consul 和 Kubernetes 使用 watch，不按间隔轮询
+    #[clap(long, default_value = "5000")]
+    pub storage_poll_interval: u64,
+    // 只把存储中的配置同步到配置目录，接口写入和本地修改不写回存储
+    #[clap(long)]
+    pub storage_read_only: bool,
+}
+
+// serve 的请求限流、大小限制与 TCP 推送连接保活
+#[derive(Debug, clap::Args)]
+pub struct RequestLimitArgs {
+    // 每个客户端（API key 或 IP）每秒允许的请求数，超出时返回 429
+    #[clap(long)]
+    pub rate_limit: Option<f64>,
+    // 令牌桶容量，默认等于一秒的请求数
+    #[clap(long)]
+    pub rate_burst: Option<u32>,
+    // HTTP 请求体和 TCP 单行命令的最大字节数，超出时返回 413
+    #[clap(long, default_value = "2097152")]
+    pub max_body_size: usize,
+    // TCP 推送连接（LISTEN / SUBSCRIBE）的心跳间隔秒数，0 表示不发送 ping
+    #[clap(long, default_value = "30")]
+    pub tcp_heartbeat: u64,
+    // TCP 推送连接超过该秒数没有收到客户端任何数据（如回复的 pong）时断开，0 表示不限制
+    #[clap(long, default_value = "90")]
+    pub tcp_idle_timeout: u64,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum RefactorAction {
+    #[clap(name = "rename")]
+    Rename {
+        #[clap(long)]
+        from: String,
+        #[clap(long)]
+        to: String,
+        file: String,
+        #[clap(long)]
+        update_references: bool,
+    },
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum FixturesAction {
+    #[clap(name = "generate")]
+    Generate {
+        #[clap(long, default_value = "100")]
+        files: usize,
+        #[clap(long, default_value = "4")]
+        depth: usize,
+        #[clap(long, default_value = "yaml")]
+        format: String,
+        #[clap(short, long, default_value = "fixtures")]
+        output: String,
+        #[clap(long)]
+        seed: Option<u64>,
+    },
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum TemplateAction {
+    // 按当前模板默认值升级由旧版本模板生成的配置
+    #[clap(name = "upgrade")]
+    Upgrade {
+        #[clap(required = true)]
+        files: Vec<String>,
+        #[clap(long)]
+        dry_run: bool,
+        #[clap(long, value_enum, default_value = "prompt")]
+        on_conflict: ConflictStrategy,
+    },
+}
+
+// 用户修改过的键在模板升级时如何处理
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum ConflictStrategy {
+    Prompt,
+    Keep,
+    Take,
+}
+
+#[derive(Debug, clap::Subcommand)]
+pub enum BundleAction {
+    #[clap(name = "create")]
+    Create {
+        #[clap(short, long)]
+        configs: String,
+        #[clap(long)]
+        schemas: Option<String>,
+        #[clap(long)]
+        policies: Option<String>,
+        #[clap(short, long)]
+        output: String,
+        #[clap(long)]
+        key_file: Option<String>,
+    },
+
+    #[clap(name = "verify")]
+    Verify {
+        bundle: String,
+        #[clap(long)]
+        key_file: Option<String>,
+        // 没有 --key-file 时接受未签名（或不校验签名）的 bundle
+        #[clap(long)]
+        allow_unsigned: bool,
+    },
+
+    #[clap(name = "apply")]
+    Apply {
+        bundle: String,
+        #[clap(short, long)]
+        target: String,
+        #[clap(long)]
+        key_file: Option<String>,
+        // 没有 --key-file 时接受未签名（或不校验签名）的 bundle
+        #[clap(long)]
+        allow_unsigned: bool,
+    },
+
+    // 导出 serve 配置目录（可限定命名空间）及其附加规则，用于备份和跨环境迁移
+    #[clap(name = "export")]
+    Export {
+        #[clap(short, long, default_value = ".")]
+        config_path: String,
+        #[clap(short, long)]
+        namespace: Option<String>,
+        #[clap(short, long)]
+        output: String,
+        #[clap(long)]
+        key_file: Option<String>,
+    },
+
+    // 校验后导入 serve 配置目录；未指定命名空间时使用导出时的命名空间
+    #[clap(name = "import")]
+    Import {
+        bundle: String,
+        #[clap(short, long, default_value = ".")]
+        config_path: String,
+        #[clap(short, long)]
+        namespace: Option<String>,
+        #[clap(long)]
+        key_file: Option<String>,
+        // 没有 --key-file 时接受未签名（或不校验签名）的 bundle
+        #[clap(long)]
+        allow_unsigned: bool,
     },
 }
 
@@ -56,11 +501,70 @@ pub enum CliCommand {
     List,
 
     Listen { path: String },
+
+    // 只推送指定键路径的变化；未指定键路径时推送整个文件的更新
+    Subscribe { path: String, keys: Vec<String> },
+
+    // 按 rules/ 目录下的规则文件校验已加载的配置，未指定时使用服务端的全局规则
+    Validate { path: String, rules: Option<String> },
+
+    History { path: String },
+
+    Hello { version: Option<u32> },
+
+    Begin,
+
+    Set { file: String, key: String, value: String },
+
+    Commit,
+
+    Abort,
+
+    Auth { key: String },
 }
 
 impl CliCommand {
-    pub fn from_str(s: &str) -> Option<Self> {
-        let parts: Vec<&str> = s.trim().split_whitespace().collect();
+    // 命令访问的配置，用于命名空间路由检查
+    pub fn target(&self) -> Option<&str> {
+        match self {
+            Self::Add { path }
+            | Self::Remove { path }
+            | Self::Get { path }
+            | Self::Listen { path }
+            | Self::Subscribe { path, .. }
+            | Self::Validate { path, .. }
+            | Self::History { path } => Some(path),
+            Self::Set { file, .. } => Some(file),
+            Self::List
+            | Self::Hello { .. }
+            | Self::Begin
+            | Self::Commit
+            | Self::Abort
+            | Self::Auth { .. } => None,
+        }
+    }
+
+    // 访问 target 所需的权限，与 HTTP 接口一致：删除需要 admin
+    pub fn permission(&self) -> Option<Permission> {
+        match self {
+            Self::Get { .. }
+            | Self::Listen { .. }
+            | Self::Subscribe { .. }
+            | Self::Validate { .. }
+            | Self::History { .. } => Some(Permission::Read),
+            Self::Add { .. } | Self::Set { .. } => Some(Permission::Write),
+            Self::Remove { .. } => Some(Permission::Admin),
+            Self::List
+            | Self::Hello { .. }
+            | Self::Begin
+            | Self::Commit
+            | Self::Abort
+            | Self::Auth { .. } => None,
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let parts: Vec<&str> = s.split_whitespace().collect();
         if parts.is_empty() {
             return None;
         }
@@ -105,6 +609,41 @@ impl CliCommand {
                     None
                 }
             }
+            "subscribe" => parts.get(1).map(|path| Self::Subscribe {
+                path: path.to_string(),
+                keys: parts[2..].iter().map(|key| key.to_string()).collect(),
+            }),
+            "validate" => parts.get(1).map(|path| Self::Validate {
+                path: path.to_string(),
+                rules: parts.get(2).map(|rules| rules.to_string()),
+            }),
+            "history" => parts.get(1).map(|path| Self::History {
+                path: path.to_string(),
+            }),
+            "begin" => Some(Self::Begin),
+            "set" => {
+                if parts.len() >= 4 {
+                    Some(Self::Set {
+                        file: parts[1].to_string(),
+                        key: parts[2].to_string(),
+                        value: parts[3..].join(" "),
+                    })
+                } else {
+                    None
+                }
+            }
+            "commit" => Some(Self::Commit),
+            "abort" => Some(Self::Abort),
+            "auth" => parts.get(1).map(|key| Self::Auth {
+                key: key.to_string(),
+            }),
+            "hello" => match parts.get(1) {
+                Some(version) => version
+                    .parse()
+                    .ok()
+                    .map(|version| Self::Hello { version: Some(version) }),
+                None => Some(Self::Hello { version: None }),
+            },
             _ => None,
         }
     }
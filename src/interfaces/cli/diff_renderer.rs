@@ -0,0 +1,313 @@
+use colored::{Color, Colorize};
+
+use crate::domain::{
+    entities::configuration::ConfigValue,
+    services::config_diff::{ConfigDiff, DiffEntry, DiffKind},
+};
+
+// 并排表格中单元格的最大宽度，超出部分截断
+const MAX_CELL_WIDTH: usize = 40;
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum DiffFormat {
+    Unified,
+    SideBySide,
+    Html,
+    Json,
+}
+
+impl DiffFormat {
+    pub fn renderer(&self) -> Box<dyn DiffRenderer> {
+        match self {
+            DiffFormat::Unified => Box::new(UnifiedRenderer),
+            DiffFormat::SideBySide => Box::new(SideBySideRenderer),
+            DiffFormat::Html => Box::new(HtmlRenderer),
+            DiffFormat::Json => Box::new(JsonRenderer),
+        }
+    }
+}
+
+pub trait DiffRenderer {
+    fn render(&self, diff: &ConfigDiff) -> String;
+}
+
+fn display(value: &Option<ConfigValue>) -> String {
+    match value {
+        Some(value) => value.to_serde_value().to_string(),
+        None => String::new(),
+    }
+}
+
+fn summary(diff: &ConfigDiff) -> String {
+    format!(
+        "{} added, {} removed, {} changed",
+        diff.count(DiffKind::Added),
+        diff.count(DiffKind::Removed),
+        diff.count(DiffKind::Changed)
+    )
+}
+
+// 终端彩色统一格式：- 旧值 / + 新值
+pub struct UnifiedRenderer;
+
+impl DiffRenderer for UnifiedRenderer {
+    fn render(&self, diff: &ConfigDiff) -> String {
+        let mut lines = vec![
+            format!("--- {}", diff.left).bold().to_string(),
+            format!("+++ {}", diff.right).bold().to_string(),
+        ];
+        for entry in diff.entries.iter() {
+            if entry.old.is_some() {
+                lines.push(
+                    format!("- {}: {}", entry.key, display(&entry.old))
+                        .color(Color::Red)
+                        .to_string(),
+                );
+            }
+            if entry.new.is_some() {
+                lines.push(
+                    format!("+ {}: {}", entry.key, display(&entry.new))
+                        .color(Color::Green)
+                        .to_string(),
+                );
+            }
+        }
+        lines.push(summary(diff));
+        lines.join("\n")
+    }
+}
+
+// 并排表格：键 | 左侧值 | 右侧值
+pub struct SideBySideRenderer;
+
+impl SideBySideRenderer {
+    fn cell(value: &str) -> String {
+        if value.chars().count() > MAX_CELL_WIDTH {
+            let truncated: String = value.chars().take(MAX_CELL_WIDTH - 3).collect();
+            format!("{}...", truncated)
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+impl DiffRenderer for SideBySideRenderer {
+    fn render(&self, diff: &ConfigDiff) -> String {
+        let rows: Vec<(&DiffEntry, String, String)> = diff
+            .entries
+            .iter()
+            .map(|entry| {
+                (
+                    entry,
+                    Self::cell(&display(&entry.old)),
+                    Self::cell(&display(&entry.new)),
+                )
+            })
+            .collect();
+        let key_width = rows.iter().map(|r| r.0.key.len()).chain([3]).max().unwrap_or(3);
+        let left_width = rows
+            .iter()
+            .map(|r| r.1.chars().count())
+            .chain([diff.left.chars().count().min(MAX_CELL_WIDTH)])
+            .max()
+            .unwrap_or(0);
+
+        let mut lines = vec![
+            format!(
+                "  {:<key_width$}  {:<left_width$}  {}",
+                "KEY",
+                Self::cell(&diff.left),
+                Self::cell(&diff.right)
+            )
+            .bold()
+            .to_string(),
+        ];
+        for (entry, left, right) in rows {
+            let (marker, color) = match entry.kind {
+                DiffKind::Added => ("+", Color::Green),
+                DiffKind::Removed => ("-", Color::Red),
+                DiffKind::Changed => ("~", Color::Yellow),
+            };
+            lines.push(
+                format!("{} {:<key_width$}  {:<left_width$}  {}", marker, entry.key, left, right)
+                    .color(color)
+                    .to_string(),
+            );
+        }
+        lines.push(summary(diff));
+        lines.join("\n")
+    }
+}
+
+// 独立的 HTML 报告，可直接附加到变更单
+pub struct HtmlRenderer;
+
+impl HtmlRenderer {
+    fn escape(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+}
+
+impl DiffRenderer for HtmlRenderer {
+    fn render(&self, diff: &ConfigDiff) -> String {
+        let title = format!(
+            "Config diff: {} → {}",
+            Self::escape(&diff.left),
+            Self::escape(&diff.right)
+        );
+        let rows: String = diff
+            .entries
+            .iter()
+            .map(|entry| {
+                let kind = match entry.kind {
+                    DiffKind::Added => "added",
+                    DiffKind::Removed => "removed",
+                    DiffKind::Changed => "changed",
+                };
+                format!(
+                    "      <tr class=\"{kind}\"><td>{kind}</td><td><code>{}</code></td><td><code>{}</code></td><td><code>{}</code></td></tr>\n",
+                    Self::escape(&entry.key),
+                    Self::escape(&display(&entry.old)),
+                    Self::escape(&display(&entry.new)),
+                )
+            })
+            .collect();
+        format!(
+            r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <title>{title}</title>
+  <style>
+    body {{ font-family: sans-serif; margin: 2em; }}
+    table {{ border-collapse: collapse; width: 100%; }}
+    th, td {{ border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }}
+    code {{ white-space: pre-wrap; word-break: break-all; }}
+    tr.added {{ background: #e6ffed; }}
+    tr.removed {{ background: #ffeef0; }}
+    tr.changed {{ background: #fff8c5; }}
+  </style>
+</head>
+<body>
+  <h1>{title}</h1>
+  <p>{summary}</p>
+  <table>
+    <thead>
+      <tr><th>Change</th><th>Key</th><th>{left}</th><th>{right}</th></tr>
+    </thead>
+    <tbody>
+{rows}    </tbody>
+  </table>
+</body>
+</html>
+"#,
+            summary = summary(diff),
+            left = Self::escape(&diff.left),
+            right = Self::escape(&diff.right),
+        )
+    }
+}
+
+pub struct JsonRenderer;
+
+impl DiffRenderer for JsonRenderer {
+    fn render(&self, diff: &ConfigDiff) -> String {
+        serde_json::to_string_pretty(diff).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn diff() -> ConfigDiff {
+        let value = |value: serde_json::Value| Some(ConfigValue::from_serde_json(value).unwrap());
+        ConfigDiff {
+            left: "old.json".to_string(),
+            right: "new.json".to_string(),
+            entries: vec![
+                DiffEntry {
+                    key: "debug".to_string(),
+                    kind: DiffKind::Removed,
+                    old: value(false.into()),
+                    new: None,
+                },
+                DiffEntry {
+                    key: "db.host".to_string(),
+                    kind: DiffKind::Changed,
+                    old: value("<a>".into()),
+                    new: value("b".into()),
+                },
+                DiffEntry {
+                    key: "user".to_string(),
+                    kind: DiffKind::Added,
+                    old: None,
+                    new: value("app".into()),
+                },
+            ],
+        }
+    }
+
+    fn render(format: DiffFormat) -> String {
+        colored::control::set_override(false);
+        format.renderer().render(&diff())
+    }
+
+    #[test]
+    fn unified_lists_old_and_new_values() {
+        assert_eq!(
+            render(DiffFormat::Unified),
+            [
+                "--- old.json",
+                "+++ new.json",
+                "- debug: false",
+                "- db.host: \"<a>\"",
+                "+ db.host: \"b\"",
+                "+ user: \"app\"",
+                "1 added, 1 removed, 1 changed",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn side_by_side_aligns_columns() {
+        assert_eq!(
+            render(DiffFormat::SideBySide),
+            [
+                "  KEY      old.json  new.json",
+                "- debug    false     ",
+                "~ db.host  \"<a>\"     \"b\"",
+                "+ user               \"app\"",
+                "1 added, 1 removed, 1 changed",
+            ]
+            .join("\n")
+        );
+        let long = "x".repeat(MAX_CELL_WIDTH + 1);
+        assert_eq!(SideBySideRenderer::cell(&long).chars().count(), MAX_CELL_WIDTH);
+    }
+
+    #[test]
+    fn html_escapes_keys_and_values() {
+        let html = render(DiffFormat::Html);
+        assert!(html.contains("<title>Config diff: old.json → new.json</title>"));
+        assert!(html.contains(
+            "<tr class=\"changed\"><td>changed</td><td><code>db.host</code></td><td><code>&quot;&lt;a&gt;&quot;</code></td><td><code>&quot;b&quot;</code></td></tr>"
+        ));
+        assert!(html.contains("<p>1 added, 1 removed, 1 changed</p>"));
+    }
+
+    #[test]
+    fn json_serializes_entries() {
+        let json: serde_json::Value = serde_json::from_str(&render(DiffFormat::Json)).unwrap();
+        assert_eq!(json["left"], "old.json");
+        assert_eq!(
+            json["entries"][1],
+            serde_json::json!({"key": "db.host", "kind": "changed", "old": "<a>", "new": "b"})
+        );
+        assert_eq!(json["entries"][2]["old"], serde_json::Value::Null);
+    }
+}
@@ -1 +1,6 @@
-pub mod command;
\ No newline at end of file
+pub mod browser;
+pub mod command;
+pub mod diff_renderer;
+pub mod shell;
+pub mod validation_report;
+pub mod watch;
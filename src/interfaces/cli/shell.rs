@@ -0,0 +1,262 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpStream,
+    sync::{Arc, Mutex},
+};
+
+use colored::{Color, Colorize};
+use rustyline::{
+    Context, ExternalPrinter, Helper, Highlighter, Hinter, Validator,
+    completion::{Completer, Pair},
+    error::ReadlineError,
+    history::DefaultHistory,
+};
+use tracing::debug;
+
+use crate::interfaces::cli::command::CliCommand;
+
+const COMMANDS: [&str; 15] = [
+    "add", "remove", "get", "list", "listen", "subscribe", "validate", "history", "begin", "set",
+    "commit", "abort", "hello", "help", "exit",
+];
+
+// 基于 TCP 协议的请求/响应连接：响应格式为 "<长度>\n<内容>"
+struct TcpProtocolClient {
+    reader: BufReader<TcpStream>,
+}
+
+impl TcpProtocolClient {
+    fn connect(addr: &str) -> std::io::Result<Self> {
+        Ok(Self {
+            reader: BufReader::new(TcpStream::connect(addr)?),
+        })
+    }
+
+    fn request(&mut self, line: &str) -> std::io::Result<String> {
+        self.send(line)?;
+        self.read_frame()
+    }
+
+    fn send(&mut self, line: &str) -> std::io::Result<()> {
+        let stream = self.reader.get_mut();
+        stream.write_all(format!("{}\n", line).as_bytes())?;
+        stream.flush()
+    }
+
+    fn read_frame(&mut self) -> std::io::Result<String> {
+        let mut header = String::new();
+        if self.reader.read_line(&mut header)? == 0 {
+            return Err(std::io::ErrorKind::UnexpectedEof.into());
+        }
+        let len: usize = header.trim().parse().map_err(|_| {
+            std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("invalid frame header: {}", header.trim()),
+            )
+        })?;
+        let mut body = vec![0; len];
+        self.reader.read_exact(&mut body)?;
+        Ok(String::from_utf8_lossy(&body).to_string())
+    }
+}
+
+#[derive(Helper, Hinter, Highlighter, Validator)]
+struct ShellHelper {
+    config_names: Arc<Mutex<Vec<String>>>,
+}
+
+impl Completer for ShellHelper {
+    type Candidate = Pair;
+
+    // 第一个词补全命令，之后补全已加载的配置名
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let line = &line[..pos];
+        let start = line.rfind(char::is_whitespace).map(|i| i + 1).unwrap_or(0);
+        let word = &line[start..];
+        let candidates: Vec<String> = if start == 0 {
+            COMMANDS.iter().map(|c| c.to_string()).collect()
+        } else {
+            self.config_names.lock().unwrap().clone()
+        };
+        let pairs = candidates
+            .into_iter()
+            .filter(|c| c.starts_with(word))
+            .map(|c| Pair {
+                display: c.clone(),
+                replacement: c,
+            })
+            .collect();
+        Ok((start, pairs))
+    }
+}
+
+pub struct ConfigShell {
+    addr: String,
+}
+
+impl ConfigShell {
+    pub fn new(host: String, port: u16) -> Self {
+        Self {
+            addr: format!("{}:{}", host, port),
+        }
+    }
+
+    pub fn run(self) -> anyhow::Result<()> {
+        let mut client = TcpProtocolClient::connect(&self.addr)?;
+        let config_names = Arc::new(Mutex::new(Vec::new()));
+        Self::refresh_names(&mut client, &config_names);
+
+        let mut editor = rustyline::Editor::<ShellHelper, DefaultHistory>::new()?;
+        editor.set_helper(Some(ShellHelper {
+            config_names: config_names.clone(),
+        }));
+        let history = Self::history_path();
+        if let Some(history) = &history {
+            let _ = editor.load_history(history);
+        }
+
+        println!(
+            "connected to {}, type 'help' for commands",
+            self.addr.color(Color::Green)
+        );
+        let prompt = format!("{}> ", self.addr);
+        loop {
+            let line = match editor.readline(&prompt) {
+                Ok(line) => line,
+                Err(ReadlineError::Interrupted) => continue,
+                Err(ReadlineError::Eof) => break,
+                Err(e) => return Err(e.into()),
+            };
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let _ = editor.add_history_entry(line);
+
+            let request = Self::normalize(line);
+            match request.split_whitespace().next() {
+                Some("exit") | Some("quit") => break,
+                Some("help") => {
+                    Self::print_help();
+                    continue;
+                }
+                _ => {}
+            }
+            match CliCommand::parse(&request) {
+                Some(CliCommand::Listen { path }) | Some(CliCommand::Subscribe { path, .. }) => {
+                    // LISTEN、SUBSCRIBE 会独占连接，因此为推送单独建立连接，REPL 继续可用
+                    // 非终端环境下没有外部打印器，直接写 stdout
+                    let printer: Box<dyn FnMut(String) -> bool + Send> =
+                        match editor.create_external_printer() {
+                            Ok(mut printer) => Box::new(move |m| printer.print(m).is_ok()),
+                            Err(_) => Box::new(|m| {
+                                println!("{}", m);
+                                true
+                            }),
+                        };
+                    self.spawn_listener(path, &request, printer)?;
+                }
+                Some(command) => {
+                    let response = client.request(&request)?;
+                    Self::print_response(&response);
+                    if matches!(command, CliCommand::Add { .. } | CliCommand::Remove { .. }) {
+                        Self::refresh_names(&mut client, &config_names);
+                    }
+                }
+                None => println!("{} {}", "unknown command:".color(Color::Red), line),
+            }
+        }
+
+        if let Some(history) = &history {
+            let _ = editor.save_history(history);
+        }
+        Ok(())
+    }
+
+    fn spawn_listener(
+        &self,
+        path: String,
+        request: &str,
+        mut printer: Box<dyn FnMut(String) -> bool + Send>,
+    ) -> std::io::Result<()> {
+        let mut listener = TcpProtocolClient::connect(&self.addr)?;
+        let initial = listener.request(request)?;
+        println!("listening {}: {}", path.color(Color::Cyan), initial.trim());
+        std::thread::spawn(move || {
+            while let Ok(frame) = listener.read_frame() {
+                // 回复心跳，否则服务端会把连接当作空闲连接断开
+                if frame.trim() == "ping" {
+                    if listener.send("pong").is_err() {
+                        break;
+                    }
+                    continue;
+                }
+                let message = format!("[{}] {}", path.color(Color::Cyan), Self::pretty(&frame));
+                if !printer(message) {
+                    break;
+                }
+            }
+            debug!("listener for {} closed", path);
+        });
+        Ok(())
+    }
+
+    fn refresh_names(client: &mut TcpProtocolClient, config_names: &Arc<Mutex<Vec<String>>>) {
+        if let Ok(response) = client.request("list") {
+            let names = response
+                .lines()
+                .filter_map(|l| l.trim().strip_prefix("- "))
+                .map(|l| l.to_string())
+                .collect();
+            *config_names.lock().unwrap() = names;
+        }
+    }
+
+    // 命令名大小写不敏感，参数保持原样
+    fn normalize(line: &str) -> String {
+        match line.split_once(char::is_whitespace) {
+            Some((command, rest)) => format!("{} {}", command.to_lowercase(), rest.trim()),
+            None => line.to_lowercase(),
+        }
+    }
+
+    fn print_response(response: &str) {
+        println!("{}", Self::pretty(response));
+    }
+
+    fn pretty(body: &str) -> String {
+        match serde_json::from_str::<serde_json::Value>(body.trim()) {
+            Ok(value) => serde_json::to_string_pretty(&value).unwrap_or_else(|_| body.to_string()),
+            Err(_) => body.trim_end().to_string(),
+        }
+    }
+
+    fn print_help() {
+        println!("  add <path>      load a config file into the server");
+        println!("  remove <path>   unload a config and delete its file");
+        println!("  get <name>      show a loaded config");
+        println!("  list            list loaded configs");
+        println!("  listen <name>   print pushed updates for a config");
+        println!("  subscribe <name> [key...]  print pushed changes of the given keys");
+        println!("  validate <name> [rules]    validate a loaded config against a rules file");
+        println!("  history <name>  list saved versions of a config");
+        println!("  begin           start a transaction");
+        println!("  set <name> <key> <value>  stage a change (applied directly outside a transaction)");
+        println!("  commit          apply all staged changes atomically");
+        println!("  abort           discard staged changes");
+        println!("  hello [version] show protocol version and server capabilities");
+        println!("  exit            leave the shell");
+    }
+
+    fn history_path() -> Option<std::path::PathBuf> {
+        let home = std::env::var("HOME").ok()?;
+        let dir = std::path::Path::new(&home).join(".config-manager");
+        std::fs::create_dir_all(&dir).ok()?;
+        Some(dir.join("shell_history"))
+    }
+}
@@ -0,0 +1,289 @@
+use serde_json::json;
+
+use crate::{
+    application::services::validation_service::FileValidationReport,
+    domain::entities::validation_rule::Severity,
+    infrastructure::sources::config_source_factory::ConfigSourceFactory,
+};
+
+// 供 CI 使用的校验报告格式：GitLab 读取 JUnit，GitHub code scanning 读取 SARIF
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum ValidationReportFormat {
+    Json,
+    Junit,
+    Sarif,
+}
+
+impl ValidationReportFormat {
+    pub fn renderer(&self) -> Box<dyn ValidationReportRenderer> {
+        match self {
+            ValidationReportFormat::Json => Box::new(JsonReportRenderer),
+            ValidationReportFormat::Junit => Box::new(JunitReportRenderer),
+            ValidationReportFormat::Sarif => Box::new(SarifReportRenderer),
+        }
+    }
+}
+
+pub trait ValidationReportRenderer {
+    fn render(&self, reports: &[FileValidationReport]) -> String;
+}
+
+// 单条问题：解析失败记为 ParseError，其余来自 ValidationResult
+struct Issue {
+    severity: Severity,
+    rule: &'static str,
+    field: Option<String>,
+    message: String,
+    line: Option<usize>,
+}
+
+fn issues(report: &FileValidationReport) -> Vec<Issue> {
+    if let Some(e) = &report.parse_error {
+        return vec![Issue {
+            severity: Severity::Error,
+            rule: "ParseError",
+            field: None,
+            message: e.to_string(),
+            line: None,
+        }];
+    }
+    let Some(result) = &report.result else {
+        return vec![];
+    };
+    let content = ConfigSourceFactory::is_local(&report.file)
+        .then(|| std::fs::read_to_string(&report.file).ok())
+        .flatten();
+    [
+        (Severity::Error, &result.errors),
+        (Severity::Warning, &result.warnings),
+        (Severity::Info, &result.infos),
+    ]
+    .into_iter()
+    .flat_map(|(severity, errors)| errors.iter().map(move |e| (severity, e)))
+    .map(|(severity, e)| Issue {
+        severity,
+        rule: e.kind(),
+        field: e.field().map(str::to_string),
+        message: e.to_string(),
+        line: e
+            .field()
+            .zip(content.as_deref())
+            .and_then(|(field, content)| locate_key(content, field)),
+    })
+    .collect()
+}
+
+// 尽力定位键所在的行（从 1 开始）：按层级依次查找 `key:`、`"key":`、`key =` 或 TOML 表头，
+// 找不到更深层的键时退回到最近一层
+fn locate_key(content: &str, key: &str) -> Option<usize> {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut found = None;
+    let mut cursor = 0;
+    for segment in key.split('.') {
+        let segment = segment.split('[').next().unwrap_or(segment);
+        if segment.is_empty() {
+            continue;
+        }
+        let position = lines[cursor..].iter().position(|line| {
+            let line = line.trim_start();
+            let rest = [format!("\"{}\"", segment), format!("'{}'", segment), segment.to_string()]
+                .iter()
+                .find_map(|name| line.strip_prefix(name.as_str()))
+                .map(str::trim_start);
+            let is_key = rest.is_some_and(|rest| rest.starts_with(':') || rest.starts_with('='));
+            let is_table = line.starts_with('[')
+                && line
+                    .trim_matches(|c| c == '[' || c == ']')
+                    .rsplit('.')
+                    .next()
+                    .is_some_and(|last| last.trim() == segment);
+            is_key || is_table
+        });
+        match position {
+            Some(position) => {
+                cursor += position;
+                found = Some(cursor + 1);
+            }
+            None => break,
+        }
+    }
+    found
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+pub struct JsonReportRenderer;
+
+impl ValidationReportRenderer for JsonReportRenderer {
+    fn render(&self, reports: &[FileValidationReport]) -> String {
+        let failed = reports.iter().filter(|r| !r.is_valid()).count();
+        let warned = reports.iter().filter(|r| r.is_valid() && r.has_warnings()).count();
+        let files: Vec<_> = reports
+            .iter()
+            .map(|report| {
+                let issues: Vec<_> = issues(report)
+                    .into_iter()
+                    .map(|issue| {
+                        json!({
+                            "severity": issue.severity,
+                            "rule": issue.rule,
+                            "field": issue.field,
+                            "message": issue.message,
+                            "line": issue.line,
+                        })
+                    })
+                    .collect();
+                json!({
+                    "file": report.file,
+                    "format": report.config_type.as_ref().map(|t| t.to_string()),
+                    "valid": report.is_valid(),
+                    "issues": issues,
+                })
+            })
+            .collect();
+        let output = json!({
+            "summary": {
+                "total": reports.len(),
+                "passed": reports.len() - failed - warned,
+                "warnings": warned,
+                "failed": failed,
+            },
+            "files": files,
+        });
+        serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
+    }
+}
+
+// 每个文件一个 testsuite，每条错误一个失败的 testcase；没有错误的文件记一个通过的 testcase
+pub struct JunitReportRenderer;
+
+impl ValidationReportRenderer for JunitReportRenderer {
+    fn render(&self, reports: &[FileValidationReport]) -> String {
+        let mut suites = String::new();
+        let (mut total_tests, mut total_failures, mut total_errors) = (0, 0, 0);
+        for report in reports {
+            let issues = issues(report);
+            let mut cases = String::new();
+            let (mut tests, mut failures, mut errors) = (0, 0, 0);
+            let mut notes = Vec::new();
+            for issue in issues.iter() {
+                if issue.severity != Severity::Error {
+                    notes.push(format!("{}: {}", issue.severity, issue.message));
+                    continue;
+                }
+                tests += 1;
+                let name = issue.field.clone().unwrap_or_else(|| issue.rule.to_string());
+                let location = issue
+                    .line
+                    .map(|line| format!("{}:{}", report.file, line))
+                    .unwrap_or_else(|| report.file.clone());
+                // 解析失败用 <error>，校验不通过用 <failure>
+                let tag = if issue.rule == "ParseError" {
+                    errors += 1;
+                    "error"
+                } else {
+                    failures += 1;
+                    "failure"
+                };
+                cases.push_str(&format!(
+                    "    <testcase classname=\"{file}\" name=\"{name}\" file=\"{file}\">\n      <{tag} type=\"{rule}\" message=\"{message}\">{location}: {message}</{tag}>\n    </testcase>\n",
+                    file = escape_xml(&report.file),
+                    name = escape_xml(&name),
+                    rule = issue.rule,
+                    message = escape_xml(&issue.message),
+                    location = escape_xml(&location),
+                ));
+            }
+            if tests == 0 {
+                tests = 1;
+                cases.push_str(&format!(
+                    "    <testcase classname=\"{file}\" name=\"valid\" file=\"{file}\"/>\n",
+                    file = escape_xml(&report.file)
+                ));
+            }
+            if !notes.is_empty() {
+                cases.push_str(&format!(
+                    "    <system-out>{}</system-out>\n",
+                    escape_xml(&notes.join("\n"))
+                ));
+            }
+            suites.push_str(&format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n{}  </testsuite>\n",
+                escape_xml(&report.file),
+                tests,
+                failures,
+                errors,
+                cases
+            ));
+            total_tests += tests;
+            total_failures += failures;
+            total_errors += errors;
+        }
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"config-manager validate\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n{}</testsuites>\n",
+            total_tests, total_failures, total_errors, suites
+        )
+    }
+}
+
+// SARIF 2.1.0：物理位置指向文件（能定位时带行号），逻辑位置记录出错的键
+pub struct SarifReportRenderer;
+
+impl ValidationReportRenderer for SarifReportRenderer {
+    fn render(&self, reports: &[FileValidationReport]) -> String {
+        let mut rules: Vec<&'static str> = Vec::new();
+        let mut results = Vec::new();
+        for report in reports {
+            for issue in issues(report) {
+                if !rules.contains(&issue.rule) {
+                    rules.push(issue.rule);
+                }
+                let level = match issue.severity {
+                    Severity::Error => "error",
+                    Severity::Warning => "warning",
+                    Severity::Info => "note",
+                };
+                let mut physical = json!({ "artifactLocation": { "uri": report.file } });
+                if let Some(line) = issue.line {
+                    physical["region"] = json!({ "startLine": line });
+                }
+                let mut location = json!({ "physicalLocation": physical });
+                if let Some(field) = &issue.field {
+                    location["logicalLocations"] =
+                        json!([{ "fullyQualifiedName": field, "kind": "member" }]);
+                }
+                results.push(json!({
+                    "ruleId": issue.rule,
+                    "level": level,
+                    "message": { "text": issue.message },
+                    "locations": [location],
+                }));
+            }
+        }
+        let rules: Vec<_> = rules
+            .into_iter()
+            .map(|rule| json!({ "id": rule, "name": rule }))
+            .collect();
+        let output = json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "config-manager",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules,
+                    }
+                },
+                "results": results,
+            }],
+        });
+        serde_json::to_string_pretty(&output).unwrap_or_else(|_| "{}".to_string())
+    }
+}
@@ -0,0 +1,56 @@
+use std::{collections::HashSet, path::PathBuf, time::Duration};
+
+use colored::{Color, Colorize};
+
+use crate::{infrastructure::watchers::config_watcher::ConfigWatcher, shared::error::ConfigError};
+
+// 监听给定文件，每次变更后重新执行 run，类似 cargo watch
+pub async fn watch_files<F>(files: &[String], mut run: F) -> Result<(), ConfigError>
+where
+    F: AsyncFnMut(),
+{
+    let targets: HashSet<PathBuf> = files
+        .iter()
+        .map(std::fs::canonicalize)
+        .collect::<Result<_, _>>()?;
+
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<PathBuf>();
+    let mut watcher = ConfigWatcher::new(move |path| {
+        let _ = tx.send(path);
+    })?;
+    // 监听所在目录而不是文件本身，编辑器保存时常常会替换文件
+    let dirs: HashSet<PathBuf> = targets
+        .iter()
+        .filter_map(|f| f.parent().map(|p| p.to_path_buf()))
+        .collect();
+    for dir in dirs.iter() {
+        watcher.watch(dir, false)?;
+    }
+
+    run().await;
+    loop {
+        println!("{}", "watching for changes... (Ctrl-C to exit)".color(Color::BrightBlack));
+        let mut changed = None;
+        while let Some(path) = rx.recv().await {
+            let path = std::fs::canonicalize(&path).unwrap_or(path);
+            if targets.contains(&path) {
+                changed = Some(path);
+                break;
+            }
+        }
+        let Some(changed) = changed else {
+            return Ok(());
+        };
+        // 合并短时间内的连续事件
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        while rx.try_recv().is_ok() {}
+
+        print!("\x1b[2J\x1b[H");
+        println!(
+            "[{}] change detected: {}",
+            chrono::Local::now().format("%H:%M:%S"),
+            changed.display()
+        );
+        run().await;
+    }
+}
@@ -0,0 +1,91 @@
+use std::time::Duration;
+
+use axum::{
+    Router,
+    http::{HeaderName, HeaderValue, Method, header},
+};
+use tower_http::{
+    cors::{AllowOrigin, CorsLayer},
+    set_header::SetResponseHeaderLayer,
+};
+
+use crate::shared::{
+    config::{CorsSettings, SecurityHeaderSettings},
+    error::ConfigError,
+};
+
+// 按服务端设置构造 CORS 层，来源、方法或头名称无效时拒绝启动
+pub fn cors_layer(settings: &CorsSettings) -> Result<CorsLayer, ConfigError> {
+    let origins = if settings.allowed_origins.iter().any(|origin| origin == "*") {
+        AllowOrigin::any()
+    } else {
+        AllowOrigin::list(
+            settings
+                .allowed_origins
+                .iter()
+                .map(|origin| parse(origin, "origin", |o| HeaderValue::from_str(o).ok()))
+                .collect::<Result<Vec<_>, _>>()?,
+        )
+    };
+    let methods = settings
+        .allowed_methods
+        .iter()
+        .map(|method| parse(method, "method", |m| Method::from_bytes(m.as_bytes()).ok()))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(methods)
+        .allow_headers(header_names(&settings.allowed_headers)?)
+        .expose_headers(header_names(&settings.expose_headers)?)
+        .max_age(Duration::from_secs(settings.max_age)))
+}
+
+// 只在处理函数没有设置同名头时补上安全头
+pub fn with_security_headers<S>(
+    router: Router<S>,
+    settings: &SecurityHeaderSettings,
+) -> Result<Router<S>, ConfigError>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    if !settings.enabled {
+        return Ok(router);
+    }
+    let mut headers = vec![
+        (header::X_CONTENT_TYPE_OPTIONS, "nosniff".to_string()),
+        (header::X_FRAME_OPTIONS, settings.frame_options.clone()),
+        (
+            header::CONTENT_SECURITY_POLICY,
+            settings.content_security_policy.clone(),
+        ),
+        (header::REFERRER_POLICY, settings.referrer_policy.clone()),
+    ];
+    if let Some(max_age) = settings.hsts_max_age {
+        headers.push((
+            header::STRICT_TRANSPORT_SECURITY,
+            format!("max-age={}", max_age),
+        ));
+    }
+    headers
+        .into_iter()
+        .try_fold(router, |router, (name, value)| {
+            let value = parse(&value, name.as_str(), |v| HeaderValue::from_str(v).ok())?;
+            Ok(router.layer(SetResponseHeaderLayer::if_not_present(name, value)))
+        })
+}
+
+fn header_names(names: &[String]) -> Result<Vec<HeaderName>, ConfigError> {
+    names
+        .iter()
+        .map(|name| {
+            parse(name, "header", |n| {
+                HeaderName::from_bytes(n.as_bytes()).ok()
+            })
+        })
+        .collect()
+}
+
+fn parse<T>(value: &str, what: &str, f: impl Fn(&str) -> Option<T>) -> Result<T, ConfigError> {
+    f(value)
+        .ok_or_else(|| ConfigError::InvalidServerSettings(format!("invalid {} {:?}", what, value)))
+}
@@ -0,0 +1,70 @@
+use std::{sync::Arc, time::Duration};
+
+use tracing::debug;
+
+use crate::{
+    domain::events::config_changed::ConfigUpdate,
+    shared::app_state::{AppState, wait_for_shutdown},
+};
+
+pub const DEFAULT_WATCH_TIMEOUT_SECS: u64 = 30;
+// 挂起太久的请求容易被中间代理断开，客户端指定的等待时间不超过该值
+pub const MAX_WATCH_TIMEOUT_SECS: u64 = 300;
+
+pub enum WatchOutcome {
+    Changed,
+    TimedOut,
+    ShuttingDown,
+}
+
+// 长轮询期间注册为订阅者，请求结束（包括客户端提前断开）时注销
+struct Waiter {
+    state: Arc<AppState>,
+    client_id: String,
+}
+
+impl Drop for Waiter {
+    fn drop(&mut self) {
+        self.state.remove_subscriber(&self.client_id);
+    }
+}
+
+// 等待配置版本大于 version；未指定 version 时等待下一次更新。
+// version 比当前最新序号还大时说明来自服务重启之前，直接返回让客户端重新读取
+pub async fn wait_for_change(
+    state: &Arc<AppState>,
+    file: &str,
+    version: Option<u64>,
+    timeout: Duration,
+) -> WatchOutcome {
+    let (tx, mut updates) = tokio::sync::mpsc::unbounded_channel::<ConfigUpdate>();
+    let client_id = state.id_generator.next_id("poll");
+    {
+        let mut subscriptions = state.subscriptions.lock().unwrap();
+        let (current, latest) =
+            (subscriptions.event_log.version(file), subscriptions.event_log.latest_seq());
+        if version.is_some_and(|version| current > version || version > latest) {
+            return WatchOutcome::Changed;
+        }
+        subscriptions.add(&client_id, tx, "long_poll", state.clock.now());
+        subscriptions.watch(&client_id, file);
+    }
+    let _waiter = Waiter {
+        state: state.clone(),
+        client_id: client_id.clone(),
+    };
+    // 客户端持有的就是当前版本
+    state.mark_synced(&client_id, file);
+    debug!("long poll {} waiting for {} (version {:?})", client_id, file, version);
+
+    let mut shutdown = state.shutdown_receiver();
+    tokio::select! {
+        biased;
+        _ = wait_for_shutdown(&mut shutdown) => WatchOutcome::ShuttingDown,
+        update = updates.recv() => match update {
+            Some(_) => WatchOutcome::Changed,
+            None => WatchOutcome::ShuttingDown,
+        },
+        _ = tokio::time::sleep(timeout) => WatchOutcome::TimedOut,
+    }
+}
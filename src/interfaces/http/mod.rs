@@ -1 +1,4 @@
-pub mod server;
\ No newline at end of file
+pub mod layers;
+pub mod long_poll;
+pub mod server;
+pub mod sse;
\ No newline at end of file
@@ -1,32 +1,98 @@
 use std::{
-    collections::HashMap,
-    path::{Path, PathBuf},
-    sync::{Arc, Mutex},
+    net::SocketAddr,
+    path::Path,
+    sync::Arc,
 };
 
-use axum::{Router, extract::State, routing::get};
-use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use axum::{Router, ServiceExt, extract::State, routing::get};
+use tower::Layer;
 use tracing::{debug, info};
 
 use crate::{
+    application::{
+        dtos::{
+            audit_query::AuditQuery,
+            backup_request::RestoreBackupRequest,
+            bundle_query::BundleQuery,
+            capabilities::Capabilities,
+            config_query::ConfigQuery,
+            config_status::ConfigStatus,
+            config_summary::{ConfigSummary, CreateConfigRequest, NamespaceSummary},
+            config_transaction::{ConfigTransaction, TransactionRequest, TransactionResult},
+            lock_request::LockRequest,
+            log_level_request::LogLevelRequest,
+            rebuild_status::RebuildStatus,
+            rollout_request::RolloutRequest,
+            schedule_request::ScheduleRequest,
+            ttl_request::TtlRequest,
+            validate_query::ValidateQuery,
+        },
+        services::{
+            attached_rules_service::AttachedRulesService, audit_service::AuditService,
+            authorization_service::AuthorizationService, backup_service::BackupService,
+            storage_sync_service::StorageSyncService,
+            bundle_service::BundleService, freshness_service::FreshnessService,
+            health_service::HealthService, introspection_service::IntrospectionService,
+            lock_service::LockService,
+            rebuild_service::RebuildService, rollout_service::RolloutService,
+            schedule_service::ScheduleService, settings_service::SettingsService,
+            startup_service::StartupService,
+            transaction_service::TransactionService, ttl_service::TtlService,
+            watch_service::WatchService,
+        },
+    },
     domain::{
-        services::{env_override::EnvOverrideService, format_converter::FormatConverterService},
-        value_objects::config_path::ConfigPath,
+        entities::{
+            access_policy::Permission,
+            audit::{AuditAction, AuditActor, AuditRecord},
+            config_lock::ConfigLock,
+            config_ttl::ConfigTtl,
+            config_version::{ConfigVersion, ConfigVersionInfo},
+            configuration::ConfigValue,
+            rollout::Rollout,
+            scheduled_change::ScheduledChange,
+            validation_rule::ValidationResult,
+        },
+        events::config_changed::{ChangeKind, ConfigChange},
+        services::{
+            config_patch::{ConfigPatch, ConfigPatchService},
+            env_override::EnvOverrideService,
+            format_converter::FormatConverterService,
+        },
+        value_objects::{
+            config_format::ConfigType,
+            config_path::ConfigPath,
+            read_consistency::{CONSISTENCY_HEADER, ReadConsistency},
+        },
     },
     infrastructure::{
+        limits::rate_limiter::RateLimiter,
         logging::log_manager::LogManager,
+        notification::dispatcher::{dispatch, replay},
         repositories::file_config_repository::FileConfigRepository,
     },
+    interfaces::http::{
+        layers::{cors_layer, with_security_headers},
+        long_poll::{
+            DEFAULT_WATCH_TIMEOUT_SECS, MAX_WATCH_TIMEOUT_SECS, WatchOutcome, wait_for_change,
+        },
+        sse::handle_http_config_events,
+    },
     shared::{
-        app_state::{AppState, RestResponse},
-        utils::read_file,
+        app_state::{AppState, RestResponse, drain_subscribers, wait_for_shutdown},
+        config::{ApiScope, Interface},
+        error::{BackupError, ConfigError, ErrorCategory},
+        listener::ServeListener,
+        utils::{is_config_file, is_valid_config_name, log_filter, set_log_filter, shutdown_signal},
     },
 };
 
 pub struct HttpServer {
     pub port: u16,
     pub host: String,
-    pub app_state: Arc<Mutex<AppState>>,
+    // 设置时监听该 Unix socket，不再监听 TCP 端口
+    pub uds: Option<String>,
+    pub app_state: Arc<AppState>,
     pub log_manager: LogManager,
 }
 
@@ -34,222 +100,606 @@ impl HttpServer {
     pub fn new(
         port: u16,
         host: String,
-        app_state: Arc<Mutex<AppState>>,
+        app_state: Arc<AppState>,
         log_manager: LogManager,
     ) -> Self {
         Self {
             port,
             host,
+            uds: None,
             app_state,
             log_manager,
         }
     }
 
+    pub fn with_uds(mut self, uds: Option<String>) -> Self {
+        self.uds = uds;
+        self
+    }
+
     pub async fn start(mut self) -> anyhow::Result<()> {
         info!(
             "check config path: {}",
-            self.app_state.lock().unwrap().config_path
+            self.app_state.config_path()
         );
-        if !Path::new(&self.app_state.lock().unwrap().config_path).exists() {
+        if !tokio::fs::try_exists(self.app_state.config_path()).await.unwrap_or(false) {
             info!("config path not found, create it");
-            std::fs::create_dir_all(self.app_state.lock().unwrap().config_path.clone())?;
+            tokio::fs::create_dir_all(self.app_state.config_path()).await?;
         }
+        let listener = ServeListener::bind(&self.host, self.port, self.uds.as_deref()).await?;
+        // 端口已绑定、配置目录已就绪，此后不再需要高权限
+        self.app_state.drop_privileges()?;
         info!(
             "load config from path: {}",
-            self.app_state.lock().unwrap().config_path
+            self.app_state.config_path()
         );
 
-        // 先获取配置路径，避免在循环中持有锁
-        let config_path = self.app_state.lock().unwrap().config_path.clone();
-
-        // 收集所有配置文件到临时 HashMap
-        let mut configs_to_load = HashMap::new();
-
-        // 遍历文件夹中的文件
-        for entry in std::fs::read_dir(config_path)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_file() {
-                info!("load config file: {}", path.to_string_lossy().to_string());
-                let content = read_file(path.to_string_lossy().to_string().as_str())?;
-                info!("content: {}", content);
-                let config = FormatConverterService::new(
-                    ConfigPath::new(path.to_string_lossy().to_string()).unwrap(),
-                    content,
-                )
-                .validate_config()?;
-                info!("config: {:?}", config);
-
-                // 添加到临时 HashMap，不需要获取锁
-                configs_to_load.insert(entry.file_name().to_string_lossy().to_string(), config);
-            }
-        }
-
-        // 批量插入所有配置，只获取一次锁
-        {
-            let mut app_state_guard = self.app_state.lock().unwrap();
-            for (key, config) in configs_to_load {
-                app_state_guard.config_map.insert(key, config);
-            }
-        } // 锁在这里被释放
+        // 后台并发加载配置，加载进度可通过 /api/admin/startup/status 查询；加载完成后开始应用定时修改
+        ScheduleService::restore(&self.app_state);
+        TtlService::restore(&self.app_state);
+        let app_state_for_startup = self.app_state.clone();
+        tokio::spawn(async move {
+            StartupService::load(&app_state_for_startup).await;
+            tokio::spawn(TtlService::run(app_state_for_startup.clone()));
+            ScheduleService::run(app_state_for_startup).await;
+        });
+        tokio::spawn(FreshnessService::monitor(self.app_state.clone()));
+        tokio::spawn(BackupService::schedule(self.app_state.clone()));
+        tokio::spawn(StorageSyncService::run(self.app_state.clone()));
 
-        info!(
-            "config loaded finished: {} files",
-            self.app_state.lock().unwrap().config_map.len()
-        );
-        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<(String, String)>();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<ConfigChange>();
         let app_state_for_notify = self.app_state.clone();
-        tokio::spawn(async move {
-            while let Some((file_name, config_str)) = rx.recv().await {
-                let notify_senders: Vec<tokio::sync::mpsc::UnboundedSender<String>> = {
-                    let app_state_guard = app_state_for_notify.lock().unwrap();
-                    app_state_guard
-                        .notify_map
-                        .iter()
-                        .filter(|(_, (watched_file, _))| *watched_file == file_name)
-                        .map(|(_, (_, sender))| sender.clone())
-                        .collect()
+        let mut shutdown = self.app_state.shutdown_receiver();
+        let notify_task = tokio::spawn(async move {
+            loop {
+                let change = tokio::select! {
+                    received = rx.recv() => match received {
+                        Some(received) => received,
+                        None => break,
+                    },
+                    _ = wait_for_shutdown(&mut shutdown) => break,
+                };
+                let (file_name, bytes) = (change.file.clone(), change.config.len());
+                // 监听器发现文件被删除时记为 delete
+                let action = match change.kind {
+                    ChangeKind::Deleted => AuditAction::Delete,
+                    ChangeKind::Created | ChangeKind::Updated => AuditAction::Reload,
                 };
+                StorageSyncService::notify(&app_state_for_notify, &change);
+                let sender_count = dispatch(&app_state_for_notify, change);
+                app_state_for_notify.audit(
+                    action,
+                    &file_name,
+                    "file_watcher",
+                    None,
+                );
                 self.log_manager
                     .log_info(format!(
-                        "config file: {} updated, notify {} clients, config: {}",
+                        "config file: {} updated, notify {} clients, {} bytes",
                         file_name,
-                        notify_senders.len(),
-                        config_str
+                        sender_count,
+                        bytes
                     ))
                     .await;
-                let sender_count = notify_senders.len();
-                for sender in notify_senders {
-                    if let Err(_) = sender.send(config_str.clone()) {
-                        debug!("send config to client failed, maybe client is closed");
-                    }
-                }
                 debug!("send {} config to {} clients", sender_count, file_name);
             }
+            self.log_manager
+                .log_info("server shutting down".to_string())
+                .await;
+            self.log_manager.flush().await;
         });
         // HTTP 版本的文件监听器
-        let app_state_for_watcher = self.app_state.clone();
-        let mut watcher = RecommendedWatcher::new(
-            move |result: notify::Result<Event>| {
-                let event = match result {
-                    Ok(event) => event,
-                    Err(e) => {
-                        debug!("file watch error: {}", e);
-                        return;
-                    }
-                };
+        let _watcher = WatchService::watch(&self.app_state, tx)?;
 
-                if event.kind.is_modify() && !event.paths.contains(&PathBuf::from("target")) {
-                    debug!("config file modified event: {:?}", event);
-                    if let Some(file_path) = event.paths.last() {
-                        if let Some(file_name_os) = file_path.file_name() {
-                            let file_name = file_name_os.to_string_lossy().to_string();
-                            debug!("file_name: {:?}", file_name);
-
-                            // 过滤临时文件和非配置文件
-                            if file_name.starts_with('.')
-                                || file_name.ends_with(".tmp")
-                                || file_name.ends_with("~")
-                            {
-                                debug!("ignore temporary file: {}", file_name);
-                                return;
-                            }
-
-                            // 只处理配置文件类型
-                            if !file_name.ends_with(".toml")
-                                && !file_name.ends_with(".json")
-                                && !file_name.ends_with(".yaml")
-                                && !file_name.ends_with(".yml")
-                            {
-                                debug!("ignore non-config file: {}", file_name);
-                                return;
-                            }
-
-                            match std::fs::read_to_string(file_path) {
-                                Ok(content) => {
-                                    match FormatConverterService::new(
-                                        ConfigPath::new(file_name.clone()).unwrap(),
-                                        content,
-                                    )
-                                    .validate_config()
-                                    {
-                                        Ok(mut validated_config) => {
-                                            match EnvOverrideService::apply_env_override(
-                                                &mut validated_config,
-                                            ) {
-                                                Ok(config_for_notify) => {
-                                                    let config_str = serde_json::to_string(
-                                                        &config_for_notify.to_serde_value(),
-                                                    )
-                                                    .unwrap_or_else(|_| "{}".to_string());
-
-                                                    app_state_for_watcher
-                                                        .lock()
-                                                        .unwrap()
-                                                        .config_map
-                                                        .insert(
-                                                            file_name.clone(),
-                                                            validated_config,
-                                                        );
-
-                                                    // 通过通道发送通知请求
-                                                    if let Err(_) =
-                                                        tx.send((file_name.clone(), config_str))
-                                                    {
-                                                        debug!("notify channel is closed");
-                                                    }
-
-                                                    info!("config watcher event: {:?}", event);
-                                                }
-                                                Err(e) => {
-                                                    debug!(
-                                                        "config release failed: {} - {}",
-                                                        file_name, e
-                                                    );
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            debug!("config validate failed: {} - {}", file_name, e);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    debug!("read file failed: {} - {}", file_name, e);
-                                }
-                            }
-                        }
-                    }
-                }
-            },
-            notify::Config::default(),
-        )?;
-        watcher.watch(
-            Path::new(&self.app_state.lock().unwrap().config_path),
-            RecursiveMode::Recursive,
-        )?;
-        info!("config watcher init finished");
+        let guard = RequestGuard::new(&self.app_state);
 
-        let app = Router::new()
-            .route("/", get(handle_http_root))
-            .route("/api/configs", get(handle_http_list_configs))
+        // 单个配置的路由，按访问策略检查调用方对该配置的权限
+        let configs = Router::new()
             .route(
                 "/api/configs/{path}",
                 get(handle_http_get_config)
                     .put(handle_http_update_config)
-                    .delete(handle_http_delete_config),
+                    .patch(handle_http_patch_config)
+                    .delete(handle_http_delete_config)
+                    .layer(axum::middleware::from_fn_with_state(
+                        self.app_state.clone(),
+                        attach_etag,
+                    )),
+            )
+            .route("/api/configs/{path}/events", get(handle_http_config_events))
+            .route("/api/configs/{path}/history", get(handle_http_config_history))
+            .route("/api/configs/{path}/status", get(handle_http_config_status))
+            .route("/api/configs/{path}/rollout", get(handle_http_get_rollout))
+            .route(
+                "/api/configs/{path}/rollout/canary",
+                axum::routing::post(handle_http_start_rollout),
+            )
+            .route(
+                "/api/configs/{path}/rollout/promote",
+                axum::routing::post(handle_http_promote_rollout),
+            )
+            .route(
+                "/api/configs/{path}/rollout/rollback",
+                axum::routing::post(handle_http_rollback_rollout),
+            )
+            .route(
+                "/api/configs/{path}/schedule",
+                axum::routing::post(handle_http_schedule_change),
+            )
+            .route("/api/configs/{path}/pending", get(handle_http_pending_changes))
+            .route(
+                "/api/configs/{path}/ttl",
+                get(handle_http_list_ttls).post(handle_http_set_ttl),
+            )
+            .route(
+                "/api/configs/{path}/versions/{version}",
+                get(handle_http_config_version),
+            )
+            .route(
+                "/api/configs/{path}/rollback/{version}",
+                axum::routing::post(handle_http_rollback_config).layer(
+                    axum::middleware::from_fn_with_state(self.app_state.clone(), attach_etag),
+                ),
+            )
+            .route(
+                "/api/configs/{path}/keys/{key}",
+                get(handle_http_get_config_key)
+                    .patch(handle_http_patch_config_key)
+                    .layer(axum::middleware::from_fn_with_state(
+                        self.app_state.clone(),
+                        attach_etag,
+                    )),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                self.app_state.clone(),
+                reject_locked,
+            ))
+            .route_layer(axum::middleware::from_fn_with_state(
+                self.app_state.clone(),
+                authorize_config,
+            ));
+
+        // 配置锁不受锁本身限制；解锁需要 admin 权限
+        let locks = Router::new()
+            .route(
+                "/api/configs/{path}/lock",
+                get(handle_http_get_lock)
+                    .post(handle_http_lock_config)
+                    .delete(handle_http_unlock_config),
+            )
+            .route_layer(axum::middleware::from_fn_with_state(
+                self.app_state.clone(),
+                authorize_config,
+            ));
+
+        // 服务端管理接口，需要 admin 权限
+        let admin = Router::new()
+            .route("/api/subscribers", get(handle_http_list_subscribers))
+            .route("/api/audit", get(handle_http_list_audit))
+            .route(
+                "/api/admin/rebuild",
+                axum::routing::post(handle_http_rebuild),
+            )
+            .route("/api/admin/rebuild/status", get(handle_http_rebuild_status))
+            .route("/api/admin/reload", axum::routing::post(handle_http_reload))
+            .route("/api/admin/state", get(handle_http_server_state))
+            .route("/api/admin/locks", get(handle_http_list_locks))
+            .route(
+                "/api/admin/freeze",
+                get(handle_http_get_freeze)
+                    .post(handle_http_freeze)
+                    .delete(handle_http_unfreeze),
+            )
+            .route("/api/admin/startup/status", get(handle_http_startup_status))
+            .route("/api/admin/cache", get(handle_http_cache_metrics))
+            .route("/api/admin/freshness", get(handle_http_freshness))
+            .route(
+                "/api/admin/log-level",
+                get(handle_http_log_level).put(handle_http_set_log_level),
+            )
+            .route(
+                "/api/admin/bundle",
+                get(handle_http_export_bundle).post(handle_http_import_bundle),
+            )
+            .route(
+                "/api/backups",
+                get(handle_http_list_backups).post(handle_http_create_backup),
+            )
+            .route(
+                "/api/backups/restore",
+                axum::routing::post(handle_http_restore_backup),
+            )
+            .route("/api/admin/dead-letters", get(handle_http_list_dead_letters))
+            .route(
+                "/api/admin/dead-letters/{id}",
+                axum::routing::delete(handle_http_delete_dead_letter),
+            )
+            .route(
+                "/api/admin/dead-letters/{id}/replay",
+                axum::routing::post(handle_http_replay_dead_letter),
             )
+            .route_layer(axum::middleware::from_fn_with_state(
+                self.app_state.clone(),
+                authorize_admin,
+            ));
+
+        let app = Router::new()
+            .route("/", get(handle_http_root))
+            .route(
+                "/api/configs",
+                get(handle_http_list_configs).post(handle_http_create_config),
+            )
+            .route("/api/namespaces", get(handle_http_list_namespaces))
+            .route(
+                "/api/namespaces/{namespace}/configs",
+                get(handle_http_list_namespace_configs),
+            )
+            .route("/api/validate", axum::routing::post(handle_http_validate))
+            .route("/api/capabilities", get(handle_http_capabilities))
+            .route(
+                "/api/transactions",
+                axum::routing::post(handle_http_commit_transaction),
+            )
+            .merge(configs)
+            .merge(locks)
+            .merge(admin)
             .route(
                 "/ws/listen",
                 get(crate::interfaces::websocket::server::handle_websocket_upgrade),
             ) // 🔌 WebSocket 路由
+            .layer(axum::middleware::from_fn_with_state(
+                guard.clone(),
+                limit_requests,
+            ))
+            .layer(axum::extract::DefaultBodyLimit::max(guard.max_body_size))
+            .layer(axum::middleware::from_fn_with_state(guard, authenticate))
+            // 探针不携带 API key，也不参与限流
+            .route("/healthz", get(handle_http_healthz))
+            .route("/readyz", get(handle_http_readyz))
             .with_state(self.app_state.clone()); // 🔑 关键：将状态附加到路由
 
-        let addr = (self.host.clone(), self.port);
-        let listener = tokio::net::TcpListener::bind(&addr).await?;
-        info!("HTTP server listening on {}:{}", self.host, self.port);
-        axum::serve(listener, app).await?;
+        // CORS 放在最外层，浏览器的预检请求不需要携带 API key
+        let app = with_security_headers(app, &self.app_state.security_headers)?;
+        let app = match &self.app_state.cors {
+            Some(cors) => app.layer(cors_layer(cors)?),
+            None => app,
+        };
+        // 配置路径的改写需要在路由匹配之前完成，因此包在整个 Router 之外
+        let app =
+            axum::middleware::map_request_with_state(self.app_state.clone(), config_path_alias)
+                .layer(app);
+
+        info!(
+            "HTTP server listening on {}",
+            ServeListener::describe(&self.host, self.port, self.uds.as_deref())
+        );
+        // 收到信号后停止接受新连接，通知订阅者并等待其断开，最后写完日志再退出
+        let app_state_for_shutdown = self.app_state.clone();
+        let shutdown = async move {
+            shutdown_signal().await;
+            app_state_for_shutdown.begin_shutdown();
+        };
+        match listener {
+            ServeListener::Tcp(listener) => {
+                axum::serve(
+                    listener,
+                    app.into_make_service_with_connect_info::<SocketAddr>(),
+                )
+                .with_graceful_shutdown(shutdown)
+                .await?
+            }
+            // Unix socket 的对端没有网络地址，审计和限流按 API key 区分调用方
+            #[cfg(unix)]
+            ServeListener::Unix(listener) => {
+                axum::serve(listener, app.into_make_service())
+                    .with_graceful_shutdown(shutdown)
+                    .await?
+            }
+        }
+        if let Some(uds) = &self.uds {
+            let _ = std::fs::remove_file(uds);
+        }
+        drain_subscribers(&self.app_state).await;
+        let _ = notify_task.await;
+        info!("HTTP server shut down");
+        Ok(())
+    }
+}
+
+// 配置了 API key 时校验请求携带的密钥和权限范围，并把操作者交给后续的审计记录
+// 鉴权和限流在每个请求上执行，读取当前生效的设置，设置文件修改后对之后的请求生效
+#[derive(Clone)]
+struct RequestGuard {
+    app_state: Arc<AppState>,
+    max_body_size: usize,
+}
+
+impl RequestGuard {
+    fn new(app_state: &Arc<AppState>) -> Self {
+        Self {
+            app_state: app_state.clone(),
+            max_body_size: app_state.max_body_size,
+        }
+    }
+}
+
+async fn authenticate(
+    State(guard): State<RequestGuard>,
+    mut request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let mut actor = AuditActor::new("http_api", peer_address(&request));
+    let settings = guard.app_state.settings();
+    let api_keys = &settings.api_keys;
+    if api_keys.enabled() {
+        let Some(token) = api_token(request.headers()) else {
+            return unauthorized("Missing API key");
+        };
+        let Some(key) = api_keys.find(token) else {
+            return unauthorized("Invalid API key");
+        };
+        let scope = required_scope(request.method(), request.uri().path());
+        if !key.allows(scope) {
+            return RestResponse::<String>::error(
+                403,
+                format!("API key '{}' lacks the {} scope", key.name, scope),
+            )
+            .into_response();
+        }
+        actor = actor.with_api_key(&key.name);
+    }
+    request.extensions_mut().insert(actor);
+    next.run(request).await
+}
+
+// 监听 Unix socket 时请求没有对端地址
+fn peer_address(request: &axum::extract::Request) -> Option<SocketAddr> {
+    request
+        .extensions()
+        .get::<axum::extract::ConnectInfo<SocketAddr>>()
+        .map(|axum::extract::ConnectInfo(address)| *address)
+}
+
+// 声明的请求体超出上限时返回 413；未声明长度的请求体由 DefaultBodyLimit 在读取时截断
+async fn limit_requests(
+    State(guard): State<RequestGuard>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let content_length = request
+        .headers()
+        .get(axum::http::header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<usize>().ok());
+    if content_length.is_some_and(|length| length > guard.max_body_size) {
+        return RestResponse::<String>::error(
+            413,
+            format!("Request body exceeds {} bytes", guard.max_body_size),
+        )
+        .into_response();
+    }
+    if let Some(rate_limiter) = &guard.app_state.settings().rate_limiter {
+        let client = RateLimiter::client_key(actor.api_key.as_deref(), peer_address(&request));
+        if let Err(wait) = rate_limiter.check(&client) {
+            let mut response =
+                RestResponse::<String>::error(429, "Too many requests".to_string()).into_response();
+            response.headers_mut().insert(
+                axum::http::header::RETRY_AFTER,
+                axum::http::HeaderValue::from(wait.as_secs_f64().ceil() as u64),
+            );
+            return response;
+        }
+    }
+    next.run(request).await
+}
+
+fn api_token(headers: &axum::http::HeaderMap) -> Option<&str> {
+    let bearer = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    bearer.or_else(|| headers.get("x-api-key").and_then(|value| value.to_str().ok()))
+}
+
+// 只读请求需要 read，其余需要 write；/api/validate 不保存内容，按只读处理
+fn required_scope(method: &axum::http::Method, path: &str) -> ApiScope {
+    if method == axum::http::Method::GET
+        || method == axum::http::Method::HEAD
+        || path == "/api/validate"
+    {
+        ApiScope::Read
+    } else {
+        ApiScope::Write
+    }
+}
+
+fn unauthorized(message: &str) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let mut response = RestResponse::<String>::error(401, message.to_string()).into_response();
+    response.headers_mut().insert(
+        axum::http::header::WWW_AUTHENTICATE,
+        axum::http::HeaderValue::from_static("Bearer"),
+    );
+    response
+}
+
+// 读取需要 read，删除需要 admin，其余修改需要 write
+async fn authorize_config(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::extract::Path(params): axum::extract::Path<std::collections::HashMap<String, String>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let permission = match *request.method() {
+        axum::http::Method::GET | axum::http::Method::HEAD => Permission::Read,
+        axum::http::Method::DELETE => Permission::Admin,
+        _ => Permission::Write,
+    };
+    let path = params.get("path").map(String::as_str).unwrap_or_default();
+    let authorized =
+        AuthorizationService::authorize(&state, &actor, permission, path);
+    match authorized {
+        Ok(()) => next.run(request).await,
+        Err(e) => RestResponse::<String>::error(403, e.to_string()).into_response(),
+    }
+}
+
+// 配置被锁定或全局冻结时拒绝修改请求，返回 423
+async fn reject_locked(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(params): axum::extract::Path<std::collections::HashMap<String, String>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    if matches!(*request.method(), axum::http::Method::GET | axum::http::Method::HEAD) {
+        return next.run(request).await;
+    }
+    let path = params.get("path").map(String::as_str).unwrap_or_default();
+    match state.check_unlocked(path) {
+        Ok(()) => next.run(request).await,
+        Err(e) => RestResponse::<String>::error(423, e.to_string()).into_response(),
+    }
+}
+
+// 配置名称可以包含 "/"（子目录中的配置，例如 services/api/app.yaml），而 {path} 只匹配一段：
+// 路由之前把名称中的 "/" 编码为 %2F，名称到第一个带配置文件扩展名的段为止，例如
+// /api/configs/services/api/app.yaml/history 改写为 /api/configs/services%2Fapi%2Fapp.yaml/history。
+// /api/namespaces/{namespace}/configs/{path}... 是 /api/configs/{namespace}/{path}... 的别名，
+// /api/namespaces/{namespace}/events 订阅整个命名空间，对应 /api/configs/{namespace}%2F/events；
+// 改写后子目录和命名空间内的配置复用单个配置的全部接口、鉴权和 ETag
+async fn config_path_alias(
+    State(state): State<Arc<AppState>>,
+    mut request: axum::extract::Request,
+) -> Result<axum::extract::Request, axum::response::Response> {
+    use axum::response::IntoResponse;
+
+    let uri = request.uri();
+    let path = match uri
+        .path()
+        .strip_prefix("/api/namespaces/")
+        .and_then(|rest| rest.split_once('/'))
+    {
+        Some((namespace, rest)) => {
+            let path = match rest.strip_prefix("configs/") {
+                Some(path) => format!("/api/configs/{}/{}", namespace, path),
+                None if rest == "events" => format!("/api/configs/{}%2F/events", namespace),
+                None => return Ok(request),
+            };
+            if state.namespace(namespace).is_none() {
+                let message = format!("Namespace '{}' not found", namespace);
+                return Err(RestResponse::<String>::error(404, message).into_response());
+            }
+            path
+        }
+        None => uri.path().to_string(),
+    };
+    let Some(rest) = path.strip_prefix("/api/configs/") else {
+        return Ok(request);
+    };
+    let segments: Vec<&str> = rest.split('/').collect();
+    let end = segments
+        .iter()
+        .position(|segment| is_config_file(segment))
+        .unwrap_or_default();
+    let mut rewritten = format!("/api/configs/{}", segments[..=end].join("%2F"));
+    for segment in &segments[end + 1..] {
+        rewritten.push('/');
+        rewritten.push_str(segment);
+    }
+    if let Some(query) = uri.query() {
+        rewritten.push('?');
+        rewritten.push_str(query);
+    }
+    match rewritten.parse() {
+        Ok(uri) => {
+            *request.uri_mut() = uri;
+            Ok(request)
+        }
+        Err(_) => Err(axum::http::StatusCode::BAD_REQUEST.into_response()),
+    }
+}
+
+async fn authorize_admin(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let authorized = AuthorizationService::authorize_admin(&state, &actor);
+    match authorized {
+        Ok(()) => next.run(request).await,
+        Err(e) => RestResponse::<String>::error(403, e.to_string()).into_response(),
+    }
+}
+
+// 在响应中附带配置当前的 ETag；412 响应也带上，便于客户端重新读取后重试
+async fn attach_etag(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(params): axum::extract::Path<std::collections::HashMap<String, String>>,
+    request: axum::extract::Request,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    let mut response = next.run(request).await;
+    let Some(path) = params.get("path") else {
+        return response;
+    };
+    let etag = state
+        .route(Interface::Http, path)
+        .ok()
+        .and_then(|_| state.config_map.hash(path))
+        .map(|hash| etag(&hash));
+    if let Some(value) = etag.and_then(|etag| axum::http::HeaderValue::from_str(&etag).ok()) {
+        response.headers_mut().insert(axum::http::header::ETAG, value);
+    }
+    response
+}
+
+fn etag(hash: &str) -> String {
+    format!("\"{}\"", hash)
+}
+
+// If-Match 与当前 ETag 都不匹配时返回 412；服务要求 If-Match 而请求未携带时返回 428
+fn check_if_match(
+    app_state: &AppState,
+    path: &str,
+    headers: &axum::http::HeaderMap,
+) -> Result<(), axum::Json<RestResponse<serde_json::Value>>> {
+    let Some(if_match) = headers.get(axum::http::header::IF_MATCH) else {
+        if app_state.require_if_match {
+            return Err(RestResponse::error(
+                428,
+                format!("If-Match header is required to modify '{}'", path),
+            ));
+        }
+        return Ok(());
+    };
+    let current = app_state.config_map.hash(path).map(|hash| etag(&hash));
+    let matched = if_match.to_str().is_ok_and(|if_match| {
+        if_match.split(',').map(str::trim).any(|tag| match &current {
+            Some(current) => tag == "*" || tag == current,
+            None => false,
+        })
+    });
+    if matched {
         Ok(())
+    } else {
+        Err(RestResponse::error(
+            412,
+            format!("Config '{}' has been modified, ETag does not match If-Match", path),
+        ))
     }
 }
 
@@ -257,71 +707,1259 @@ async fn handle_http_root() -> axum::Json<RestResponse<String>> {
     RestResponse::success("🔧 ConfigMaster HTTP API Server".to_string())
 }
 
+// 探针依据 HTTP 状态码判断，不健康时返回 503
+async fn handle_http_healthz(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    let report = HealthService::check(&state);
+    if report.healthy {
+        (axum::http::StatusCode::OK, RestResponse::success(report))
+    } else {
+        (
+            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            RestResponse::rejected(503, "Unhealthy".to_string(), report),
+        )
+    }
+}
+
+async fn handle_http_readyz(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    let report = HealthService::check(&state);
+    if report.ready {
+        (axum::http::StatusCode::OK, RestResponse::success(report))
+    } else {
+        (
+            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            RestResponse::rejected(503, "Not ready".to_string(), report),
+        )
+    }
+}
+
 async fn handle_http_list_configs(
-    State(state): State<Arc<Mutex<AppState>>>,
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
 ) -> impl axum::response::IntoResponse {
-    let configs: Vec<String> = {
-        let app_state = state.lock().unwrap();
-        app_state.config_map.keys().cloned().collect()
-    };
+    RestResponse::success(readable_summaries(&state, &actor))
+}
+
+// 声明的命名空间及其中调用方可读的配置数量
+async fn handle_http_list_namespaces(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+) -> impl axum::response::IntoResponse {
+    let configs = readable_summaries(&state, &actor);
+    let namespaces: Vec<NamespaceSummary> = state
+        .settings()
+        .namespaces
+        .iter()
+        .map(|namespace| NamespaceSummary {
+            name: namespace.as_str().to_string(),
+            configs: configs
+                .iter()
+                .filter(|summary| namespace.file(&summary.name).is_some())
+                .count(),
+        })
+        .collect();
+    RestResponse::success(namespaces)
+}
 
+// 命名空间内的配置列表，名称不带命名空间前缀
+async fn handle_http_list_namespace_configs(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::extract::Path(namespace): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    let Some(namespace) = state.namespace(&namespace) else {
+        return RestResponse::<Vec<ConfigSummary>>::error(
+            404,
+            format!("Namespace '{}' not found", namespace),
+        );
+    };
+    let configs = readable_summaries(&state, &actor)
+        .into_iter()
+        .filter_map(|mut summary| {
+            summary.name = namespace.file(&summary.name)?.to_string();
+            Some(summary)
+        })
+        .collect();
     RestResponse::success(configs)
 }
 
-async fn handle_http_get_config(
-    State(state): State<Arc<Mutex<AppState>>>,
-    axum::extract::Path(path): axum::extract::Path<String>,
+fn readable_summaries(state: &AppState, actor: &AuditActor) -> Vec<ConfigSummary> {
+    let mut configs = state.config_summaries(Interface::Http);
+    configs.retain(|summary| {
+        AuthorizationService::authorize(state, actor, Permission::Read, &summary.name).is_ok()
+    });
+    configs
+}
+
+// 新建配置：name 没有配置文件扩展名时按 format 补全，已存在时返回 409；
+// name 可以是子目录中的相对路径，例如 services/api/app.yaml
+async fn handle_http_create_config(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::Json(request): axum::Json<CreateConfigRequest>,
 ) -> impl axum::response::IntoResponse {
-    let config_result = {
-        let app_state = state.lock().unwrap();
-        app_state.config_map.get(&path).cloned()
+    let name = request.name.trim();
+    if !is_valid_config_name(name) {
+        return RestResponse::<serde_json::Value>::error(
+            400,
+            format!("Invalid config name '{}'", request.name),
+        );
+    }
+    let path = match request.format.as_deref().map(str::to_lowercase) {
+        Some(format) => {
+            let format = if format == "yml" { "yaml".to_string() } else { format };
+            if ConfigType::from(format.as_str()) == ConfigType::Unknown {
+                return RestResponse::<serde_json::Value>::error(
+                    400,
+                    format!("Unsupported format '{}'", format),
+                );
+            }
+            if is_config_file(name) {
+                name.to_string()
+            } else {
+                format!("{}.{}", name, format)
+            }
+        }
+        None if is_config_file(name) => name.to_string(),
+        None => {
+            return RestResponse::<serde_json::Value>::error(
+                400,
+                format!("Cannot infer format of '{}', specify format", name),
+            );
+        }
     };
 
-    match config_result {
-        Some(mut config) => match EnvOverrideService::apply_env_override(&mut config) {
-            Ok(released_config) => RestResponse::success(serde_json::json!({
-                "path": released_config.path,
-                "type": released_config.config_type,
-                "config": released_config.to_serde_value()
-            })),
-            Err(e) => RestResponse::<serde_json::Value>::error(
+    let config = match FormatConverterService::new(ConfigPath::new(path.clone()).unwrap(), request.content)
+        .validate_config()
+    {
+        Ok(config) => config,
+        Err(e) => {
+            return RestResponse::<serde_json::Value>::error(
                 400,
-                format!("Failed to process config: {}", e),
-            ),
-        },
-        None => {
-            RestResponse::<serde_json::Value>::error(404, format!("Config '{}' not found", path))
+                format!("Failed to create config: {}", e),
+            );
         }
+    };
+
+    let _writes = state.lock_writes().await;
+    if let Err(e) = state.route(Interface::Http, &path).and_then(|_| {
+        AuthorizationService::authorize(&state, &actor, Permission::Write, &path)
+    }) {
+        return RestResponse::<serde_json::Value>::error(403, e.to_string());
+    }
+    if let Err(e) = state.check_unlocked(&path) {
+        return RestResponse::<serde_json::Value>::error(423, e.to_string());
+    }
+    if state.config_map.contains_key(&path)
+        || tokio::fs::try_exists(Path::new(&state.config_path()).join(&path))
+            .await
+            .unwrap_or(false)
+    {
+        return RestResponse::<serde_json::Value>::error(
+            409,
+            format!("Config '{}' already exists", path),
+        );
+    }
+    let mut loaded = config.clone();
+    if let Some(validation) = state.validation.clone()
+        && let Err(e) = validation.apply_defaults(&mut loaded)
+    {
+        return RestResponse::<serde_json::Value>::error(
+            400,
+            format!("Failed to create config: {}", e),
+        );
+    }
+    if let Err(e) = AttachedRulesService::check(&state.config_path(), &path, &loaded) {
+        return match e {
+            ConfigError::AttachedRulesViolation { ref errors, .. } => {
+                RestResponse::rejected(409, e.to_string(), serde_json::json!(errors))
+            }
+            e => RestResponse::<serde_json::Value>::error(
+                500,
+                format!("Failed to load attached rules: {}", e),
+            ),
+        };
+    }
+    if let Err(e) = state.repository().save(&path, config).await {
+        return RestResponse::<serde_json::Value>::error(
+            500,
+            format!("Failed to create config: {}", e),
+        );
     }
+    AuditService::record_change(&state, AuditAction::Create, &path, &actor, None, Some(&loaded), None);
+    state.store_write(path.clone(), loaded, &actor.source);
+    RestResponse::success(serde_json::json!(format!("Config '{}' created successfully", path)))
 }
 
-async fn handle_http_update_config(
-    State(state): State<Arc<Mutex<AppState>>>,
-    axum::extract::Path(path): axum::extract::Path<String>,
+async fn handle_http_capabilities() -> impl axum::response::IntoResponse {
+    RestResponse::success(Capabilities::default())
+}
+
+// 只校验请求体，不保存；rules 指定 rules/ 下的规则文件，未指定时使用服务端的校验规则
+async fn handle_http_validate(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(query): axum::extract::Query<ValidateQuery>,
     body: String,
 ) -> impl axum::response::IntoResponse {
-    match FormatConverterService::new(ConfigPath::new(path.clone()).unwrap(), body)
-        .validate_config()
+    let name = match query.format.as_deref().map(str::to_lowercase) {
+        Some(format) => {
+            let format = if format == "yml" { "yaml".to_string() } else { format };
+            if ConfigType::from(format.as_str()) == ConfigType::Unknown {
+                return RestResponse::<ValidationResult>::error(
+                    400,
+                    format!("Unsupported format '{}'", format),
+                );
+            }
+            format!("payload.{}", format)
+        }
+        None => "payload".to_string(),
+    };
+    let config = match ConfigPath::new(name)
+        .and_then(|path| FormatConverterService::new(path, body).validate_config_or_guess())
     {
-        Ok(config) => {
-            let mut app_state = state.lock().unwrap();
-            app_state.config_map.insert(path.clone(), config.clone());
-            FileConfigRepository::new(app_state.config_path.clone())
-                .save(config, &path)
-                .unwrap();
-            RestResponse::success(format!("Config '{}' updated successfully", path))
+        Ok(config) => config,
+        Err(e) => {
+            return RestResponse::<ValidationResult>::error(
+                400,
+                format!("Failed to parse config: {}", e),
+            );
         }
-        Err(e) => RestResponse::<String>::error(400, format!("Failed to update config: {}", e)),
+    };
+
+    match AttachedRulesService::validate(&state, &config, query.rules.as_deref()) {
+        Ok(result) => RestResponse::success(result),
+        Err(e @ ConfigError::ConfigNotFound(_)) => {
+            RestResponse::<ValidationResult>::error(404, format!("Rules not found: {}", e))
+        }
+        Err(e) => RestResponse::<ValidationResult>::error(400, format!("Invalid rules: {}", e)),
     }
 }
 
-async fn handle_http_delete_config(
-    State(state): State<Arc<Mutex<AppState>>>,
-    axum::extract::Path(path): axum::extract::Path<String>,
+// 原子提交跨文件的多个键修改
+async fn handle_http_commit_transaction(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::Json(request): axum::Json<TransactionRequest>,
 ) -> impl axum::response::IntoResponse {
-    let removed = {
-        let mut app_state = state.lock().unwrap();
-        app_state.config_map.remove(&path).is_some()
+    let mut transaction = ConfigTransaction::default();
+    for change in request.changes {
+        if let Err(e) = state.route(Interface::Http, &change.file) {
+            return RestResponse::<TransactionResult>::error(404, format!("Transaction failed: {}", e));
+        }
+        let authorized = AuthorizationService::authorize(
+            &state,
+            &actor,
+            Permission::Write,
+            &change.file,
+        );
+        if let Err(e) = authorized {
+            return RestResponse::<TransactionResult>::error(403, format!("Transaction failed: {}", e));
+        }
+        match ConfigValue::from_serde_json(change.value) {
+            Ok(value) => transaction.stage(change.file, change.key, value),
+            Err(e) => {
+                return RestResponse::<TransactionResult>::error(
+                    400,
+                    format!("Invalid value for {}: {}", change.key, e),
+                );
+            }
+        }
+    }
+    match TransactionService::commit(&state, transaction, &actor).await {
+        Ok(result) => RestResponse::success(result),
+        Err(e @ ConfigError::ConfigNotFound(_)) => {
+            RestResponse::<TransactionResult>::error(404, format!("Transaction failed: {}", e))
+        }
+        Err(e @ ConfigError::AttachedRulesViolation { .. }) => {
+            RestResponse::<TransactionResult>::error(409, format!("Transaction failed: {}", e))
+        }
+        Err(e @ (ConfigError::ConfigLocked { .. } | ConfigError::ConfigsFrozen { .. })) => {
+            RestResponse::<TransactionResult>::error(423, format!("Transaction failed: {}", e))
+        }
+        Err(e) => {
+            RestResponse::<TransactionResult>::error(400, format!("Transaction failed: {}", e))
+        }
+    }
+}
+
+// 当前订阅者的推送统计
+async fn handle_http_list_subscribers(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    let subscribers: Vec<serde_json::Value> = state
+        .subscriptions
+        .lock()
+        .unwrap()
+        .subscriber_usage
+        .iter()
+        .map(|(client_id, usage)| {
+            let mut value = serde_json::to_value(usage).unwrap_or_default();
+            value["client_id"] = serde_json::Value::String(client_id.clone());
+            value
+        })
+        .collect();
+    RestResponse::success(serde_json::json!({
+        "quota": {
+            "bytes_per_sec": state.quota.bytes_per_sec,
+            "max_payload": state.quota.max_payload,
+            "action": state.quota.action,
+        },
+        "subscribers": subscribers,
+    }))
+}
+
+// 丢弃内存中的 config_map 并从配置目录重建，不断开订阅者
+async fn handle_http_rebuild(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    if RebuildService::start(&state) {
+        RestResponse::success("Rebuild started".to_string())
+    } else {
+        RestResponse::<String>::error(409, "Rebuild already running".to_string())
+    }
+}
+
+// 全局冻结与全部配置锁
+async fn handle_http_list_locks(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    let now = state.clock.now();
+    let mut locks = state.locks.lock().unwrap();
+    RestResponse::success(serde_json::json!({
+        "freeze": locks.frozen(now),
+        "configs": locks.list(now),
+    }))
+}
+
+async fn handle_http_get_freeze(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    match state.locks.lock().unwrap().frozen(state.clock.now()) {
+        Some(lock) => RestResponse::success(lock),
+        None => RestResponse::<ConfigLock>::error(404, "Config changes are not frozen".to_string()),
+    }
+}
+
+// 冻结期间所有配置的修改都被拒绝
+async fn handle_http_freeze(
+    State(state): State<Arc<AppState>>,
+    axum::Json(request): axum::Json<LockRequest>,
+) -> impl axum::response::IntoResponse {
+    match LockService::lock(&state, None, request).await {
+        Ok(lock) => RestResponse::success(lock),
+        Err(e) => RestResponse::<ConfigLock>::error(400, format!("Freeze failed: {}", e)),
+    }
+}
+
+async fn handle_http_unfreeze(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    match LockService::unlock(&state, None).await {
+        Ok(lock) => RestResponse::success(lock),
+        Err(_) => RestResponse::<ConfigLock>::error(404, "Config changes are not frozen".to_string()),
+    }
+}
+
+// 同步重新扫描配置目录，返回重建结果
+async fn handle_http_reload(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    match RebuildService::reload(&state).await {
+        Some(status) => RestResponse::success(status),
+        None => RestResponse::<RebuildStatus>::error(409, "Rebuild already running".to_string()),
+    }
+}
+
+// 已加载的配置、订阅者、监听器与缓存的运行状态
+async fn handle_http_server_state(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    RestResponse::success(IntrospectionService::state(&state))
+}
+
+// 导出全部配置（或一个命名空间）为 bundle，响应体为 tar.gz
+async fn handle_http_export_bundle(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(query): axum::extract::Query<BundleQuery>,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    let namespace = match query.namespace.as_deref() {
+        Some(name) => match state.namespace(name) {
+            Some(namespace) => Some(namespace),
+            None => {
+                return RestResponse::<String>::error(404, format!("Namespace '{}' not found", name))
+                    .into_response();
+            }
+        },
+        None => None,
+    };
+    let mut bundle = Vec::new();
+    match BundleService::export(&state.config_path(), namespace.as_ref(), &mut bundle, None) {
+        Ok(_) => (
+            [(axum::http::header::CONTENT_TYPE, "application/gzip")],
+            bundle,
+        )
+            .into_response(),
+        Err(e) => RestResponse::<String>::error(500, format!("Failed to export bundle: {}", e))
+            .into_response(),
+    }
+}
+
+// 导入请求体中的 bundle：校验失败时返回 422 并在 data 中列出错误，全部通过才写入，
+// 导入的配置按更新处理，记录审计并推送给订阅者
+async fn handle_http_import_bundle(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::extract::Query(query): axum::extract::Query<BundleQuery>,
+    body: axum::body::Bytes,
+) -> impl axum::response::IntoResponse {
+    let verification = match BundleService::verify_archive(body.as_ref(), None, true) {
+        Ok(verification) => verification,
+        Err(e) => {
+            return RestResponse::<serde_json::Value>::error(400, format!("Invalid bundle: {}", e));
+        }
+    };
+    if !verification.is_valid() {
+        return RestResponse::rejected(
+            422,
+            "Bundle failed validation".to_string(),
+            serde_json::json!(BackupService::errors(&verification)),
+        );
+    }
+    let namespace = match query.namespace.or_else(|| verification.manifest.namespace.clone()) {
+        Some(name) => match state.namespace(&name) {
+            Some(namespace) => Some(namespace),
+            None => {
+                return RestResponse::<serde_json::Value>::error(
+                    404,
+                    format!("Namespace '{}' not found", name),
+                );
+            }
+        },
+        None => None,
+    };
+    match BackupService::import(&state, &verification, namespace.as_ref(), &actor, "bundle import")
+        .await
+    {
+        Ok(imported) => RestResponse::success(serde_json::json!(imported)),
+        Err(e) => RestResponse::<serde_json::Value>::error(
+            500,
+            format!("Failed to import bundle: {}", e),
+        ),
+    }
+}
+
+async fn handle_http_list_backups(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    match BackupService::list(&state).await {
+        Ok(backups) => RestResponse::success(serde_json::json!(backups)),
+        Err(e) => backup_error(e),
+    }
+}
+
+// 立即备份一次，不影响定时备份的计划
+async fn handle_http_create_backup(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    match BackupService::backup(&state).await {
+        Ok(backup) => RestResponse::success(serde_json::json!(backup)),
+        Err(e) => backup_error(e),
+    }
+}
+
+async fn handle_http_restore_backup(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::Json(request): axum::Json<RestoreBackupRequest>,
+) -> impl axum::response::IntoResponse {
+    match BackupService::restore(&state, request.backup.as_deref(), &actor).await {
+        Ok(restored) => RestResponse::success(serde_json::json!(restored)),
+        Err(e) => backup_error(e),
+    }
+}
+
+// 未配置备份或备份不存在时返回 404，备份未通过校验时返回 422 并在 data 中列出错误
+fn backup_error(e: BackupError) -> axum::Json<RestResponse<serde_json::Value>> {
+    match e {
+        BackupError::NotConfigured | BackupError::NotFound { .. } => {
+            RestResponse::error(404, e.to_string())
+        }
+        BackupError::Invalid { ref errors, .. } => {
+            RestResponse::rejected(422, e.to_string(), serde_json::json!(errors))
+        }
+        e => RestResponse::error(500, format!("Backup failed: {}", e)),
+    }
+}
+
+// 当前生效的日志过滤规则（EnvFilter 语法）
+async fn handle_http_log_level() -> impl axum::response::IntoResponse {
+    RestResponse::success(serde_json::json!({"filter": log_filter()}))
+}
+
+// 运行期间修改日志级别，直到重启或设置文件重新加载
+async fn handle_http_set_log_level(
+    axum::Json(request): axum::Json<LogLevelRequest>,
+) -> impl axum::response::IntoResponse {
+    let filter = SettingsService::log_filter(request.level, &request.modules);
+    match set_log_filter(&filter) {
+        Ok(()) => {
+            info!("log filter changed to {}", filter);
+            RestResponse::success(serde_json::json!({"filter": log_filter()}))
+        }
+        Err(e) => RestResponse::<serde_json::Value>::error(400, e.to_string()),
+    }
+}
+
+// 各配置的修改/拉取时间，以及超出新鲜度 SLO 的消费者
+async fn handle_http_freshness(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    let report = state.freshness_report();
+    RestResponse::success(report)
+}
+
+async fn handle_http_rebuild_status(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    let status = state.rebuild_status.lock().unwrap().clone();
+    RestResponse::success(status)
+}
+
+// 解析缓存的命中率与淘汰统计
+async fn handle_http_cache_metrics(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    let metrics = state.config_map.metrics();
+    RestResponse::success(metrics)
+}
+
+async fn handle_http_startup_status(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    let status = state.startup_status.lock().unwrap().clone();
+    RestResponse::success(status)
+}
+
+async fn handle_http_list_audit(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Query(query): axum::extract::Query<AuditQuery>,
+) -> impl axum::response::IntoResponse {
+    match AuditService::read(&state).await {
+        Ok(records) => {
+            let records =
+                AuditService::filter(&state, Interface::Http, records, &query);
+            RestResponse::success(records)
+        }
+        Err(ConfigError::AuditNotReadable) => RestResponse::<Vec<AuditRecord>>::error(
+            501,
+            ConfigError::AuditNotReadable.to_string(),
+        ),
+        Err(e) => {
+            RestResponse::<Vec<AuditRecord>>::error(500, format!("Read audit log failed: {}", e))
+        }
+    }
+}
+
+async fn handle_http_list_dead_letters(
+    State(state): State<Arc<AppState>>,
+) -> impl axum::response::IntoResponse {
+    let dead_letters = state.dead_letters.lock().unwrap().list();
+    RestResponse::success(dead_letters)
+}
+
+async fn handle_http_replay_dead_letter(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    match replay(&state, &id).await {
+        Ok(()) => RestResponse::success(format!("Dead letter '{}' replayed", id)),
+        Err(ConfigError::KeyNotFound) => {
+            RestResponse::<String>::error(404, format!("Dead letter '{}' not found", id))
+        }
+        Err(e) => RestResponse::<String>::error(502, format!("Replay failed: {}", e)),
+    }
+}
+
+async fn handle_http_delete_dead_letter(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(id): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    let removed = state.dead_letters.lock().unwrap().remove(&id);
+    match removed {
+        Some(_) => RestResponse::success(format!("Dead letter '{}' deleted", id)),
+        None => RestResponse::<String>::error(404, format!("Dead letter '{}' not found", id)),
+    }
+}
+
+// watch=true 时为长轮询：阻塞到配置版本大于 version 后返回新配置，超时返回 304
+async fn handle_http_get_config(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+    axum::extract::Query(query): axum::extract::Query<ConfigQuery>,
+    headers: axum::http::HeaderMap,
+) -> axum::response::Response {
+    use axum::response::IntoResponse;
+
+    // 配置不存在时不等待，由读取返回错误
+    if query.watch
+        && state.route(Interface::Http, &path).is_ok()
+        && state.config_map.contains_key(&path)
+    {
+        let timeout = query
+            .timeout
+            .unwrap_or(DEFAULT_WATCH_TIMEOUT_SECS)
+            .min(MAX_WATCH_TIMEOUT_SECS);
+        let timeout = std::time::Duration::from_secs(timeout);
+        match wait_for_change(&state, &path, query.version, timeout).await {
+            WatchOutcome::Changed => {}
+            WatchOutcome::TimedOut => return axum::http::StatusCode::NOT_MODIFIED.into_response(),
+            WatchOutcome::ShuttingDown => {
+                return RestResponse::<serde_json::Value>::error(
+                    503,
+                    "Server is shutting down".to_string(),
+                )
+                .into_response();
+            }
+        }
+    }
+    read_config(&state, &path, &headers).into_response()
+}
+
+fn read_config(
+    state: &AppState,
+    path: &str,
+    headers: &axum::http::HeaderMap,
+) -> axum::Json<RestResponse<serde_json::Value>> {
+    let consistency = match headers.get(CONSISTENCY_HEADER) {
+        Some(value) => match value.to_str().map(|value| ReadConsistency::parse(value)?.supported()) {
+            Ok(Ok(consistency)) => consistency,
+            Ok(Err(e)) => return RestResponse::<serde_json::Value>::error(400, e.to_string()),
+            Err(_) => {
+                return RestResponse::<serde_json::Value>::error(
+                    400,
+                    format!("invalid {} header", CONSISTENCY_HEADER),
+                );
+            }
+        },
+        None => ReadConsistency::default(),
+    };
+    // 先取版本再取内容：内容只会比版本新，客户端用该版本长轮询不会错过更新
+    let (version, config_result) = {
+        if state.route(Interface::Http, path).is_err() {
+            return RestResponse::<serde_json::Value>::error(
+                404,
+                format!("Config '{}' not found", path),
+            );
+        }
+        let version = state.config_version(path);
+        let config = state.config_map.get(path);
+        if config.is_some() {
+            state.record_fetch(path);
+        }
+        (version, config)
+    };
+
+    match config_result {
+        Some(mut config) => match EnvOverrideService::apply_env_override(&mut config) {
+            Ok(released_config) => RestResponse::success(serde_json::json!({
+                "path": released_config.path,
+                "type": released_config.config_type,
+                "config": released_config.to_serde_value(),
+                "version": version,
+                "consistency": consistency.to_string()
+            })),
+            Err(e) => RestResponse::<serde_json::Value>::error(
+                400,
+                format!("Failed to process config: {}", e),
+            ),
+        },
+        None => {
+            RestResponse::<serde_json::Value>::error(404, format!("Config '{}' not found", path))
+        }
+    }
+}
+
+// 读取单个点分路径上的值，与整份读取一样应用环境变量覆盖
+async fn handle_http_get_config_key(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path((path, key)): axum::extract::Path<(String, String)>,
+) -> impl axum::response::IntoResponse {
+    let config = {
+        if state.route(Interface::Http, &path).is_err() {
+            return RestResponse::<serde_json::Value>::error(
+                404,
+                format!("Config '{}' not found", path),
+            );
+        }
+        let config = state.config_map.get(&path);
+        if config.is_some() {
+            state.record_fetch(&path);
+        }
+        config
+    };
+    let Some(mut config) = config else {
+        return RestResponse::<serde_json::Value>::error(404, format!("Config '{}' not found", path));
+    };
+    let config = match EnvOverrideService::apply_env_override(&mut config) {
+        Ok(config) => config,
+        Err(e) => {
+            return RestResponse::<serde_json::Value>::error(
+                400,
+                format!("Failed to process config: {}", e),
+            );
+        }
+    };
+    match config.get(&key) {
+        Some(value) => RestResponse::success(serde_json::json!({
+            "path": path,
+            "key": key,
+            "value": value.to_serde_value(),
+        })),
+        None => RestResponse::<serde_json::Value>::error(
+            404,
+            format!("Key '{}' not found in '{}'", key, path),
+        ),
+    }
+}
+
+// 修改单个点分路径上的值，请求体为 JSON 值；文件按原格式重新序列化，由文件监听器通知订阅者
+async fn handle_http_patch_config_key(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::extract::Path((path, key)): axum::extract::Path<(String, String)>,
+    headers: axum::http::HeaderMap,
+    body: String,
+) -> impl axum::response::IntoResponse {
+    let value = match serde_json::from_str(&body)
+        .map_err(|e| e.to_string())
+        .and_then(|value| ConfigValue::from_serde_json(value).map_err(|e| e.to_string()))
+    {
+        Ok(value) => value,
+        Err(e) => {
+            return RestResponse::<serde_json::Value>::error(
+                400,
+                format!("Invalid JSON value: {}", e),
+            );
+        }
+    };
+
+    let _writes = state.lock_writes().await;
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<serde_json::Value>::error(404, format!("Config '{}' not found", path));
+    }
+    let Some(before) = state.config_map.get(&path) else {
+        return RestResponse::<serde_json::Value>::error(404, format!("Config '{}' not found", path));
+    };
+    let mut config = before.clone();
+    if let Err(response) = check_if_match(&state, &path, &headers) {
+        return response;
+    }
+    if let Err(e) = config.set(&key, value) {
+        return RestResponse::<serde_json::Value>::error(
+            400,
+            format!("Failed to update key '{}': {}", key, e),
+        );
+    }
+    if let Err(e) = AttachedRulesService::check(&state.config_path(), &path, &config) {
+        return match e {
+            ConfigError::AttachedRulesViolation { ref errors, .. } => {
+                RestResponse::rejected(409, e.to_string(), serde_json::json!(errors))
+            }
+            e => RestResponse::<serde_json::Value>::error(
+                500,
+                format!("Failed to load attached rules: {}", e),
+            ),
+        };
+    }
+    if let Err(e) = state.repository().save(&path, config.clone()).await {
+        return RestResponse::<serde_json::Value>::error(
+            500,
+            format!("Failed to update key '{}': {}", key, e),
+        );
+    }
+    AuditService::record_change(
+        &state,
+        AuditAction::Update,
+        &path,
+        &actor,
+        Some(&before),
+        Some(&config),
+        Some(format!("key {}", key)),
+    );
+    state.store_write(path.clone(), config, &actor.source);
+    RestResponse::success(serde_json::json!(format!("Key '{}' in '{}' updated successfully", key, path)))
+}
+
+async fn handle_http_update_config(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+    headers: axum::http::HeaderMap,
+    body: String,
+) -> impl axum::response::IntoResponse {
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<serde_json::Value>::error(404, format!("Config '{}' not found", path));
+    }
+    match FormatConverterService::new(ConfigPath::new(path.clone()).unwrap(), body)
+        .validate_config()
+    {
+        Ok(config) => {
+            let _writes = state.lock_writes().await;
+            if let Err(response) = check_if_match(&state, &path, &headers) {
+                return response;
+            }
+            // 文件中保存原始内容，内存中的配置填充默认值
+            let mut loaded = config.clone();
+            if let Some(validation) = state.validation.clone()
+                && let Err(e) = validation.apply_defaults(&mut loaded)
+            {
+                return RestResponse::<serde_json::Value>::error(400, format!("Failed to update config: {}", e));
+            }
+            // 违反附加规则时返回 409，data 中列出全部错误
+            if let Err(e) = AttachedRulesService::check(&state.config_path(), &path, &loaded) {
+                return match e {
+                    ConfigError::AttachedRulesViolation { ref errors, .. } => {
+                        RestResponse::rejected(409, e.to_string(), serde_json::json!(errors))
+                    }
+                    e => RestResponse::<serde_json::Value>::error(
+                        500,
+                        format!("Failed to load attached rules: {}", e),
+                    ),
+                };
+            }
+            if let Err(e) = state.repository().save(&path, config).await {
+                return RestResponse::<serde_json::Value>::error(
+                    500,
+                    format!("Failed to update config: {}", e),
+                );
+            }
+            let before = state.config_map.get(&path);
+            let action = if before.is_some() {
+                AuditAction::Update
+            } else {
+                AuditAction::Create
+            };
+            AuditService::record_change(&state, action, &path, &actor, before.as_ref(), Some(&loaded), None);
+            state.store_write(path.clone(), loaded, &actor.source);
+            RestResponse::success(serde_json::json!(format!("Config '{}' updated successfully", path)))
+        }
+        Err(e) => RestResponse::<serde_json::Value>::error(400, format!("Failed to update config: {}", e)),
+    }
+}
+
+// 按 Content-Type 应用 JSON Patch 或 JSON Merge Patch；补丁作用于当前内存中的配置，
+// 全部操作成功才落盘，避免整份覆盖其他人的并发修改
+async fn handle_http_patch_config(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+    headers: axum::http::HeaderMap,
+    body: String,
+) -> impl axum::response::IntoResponse {
+    let content_type = headers
+        .get(axum::http::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_lowercase())
+        .unwrap_or_default();
+    let patch = match ConfigPatch::parse(&content_type, &body) {
+        Some(Ok(patch)) => patch,
+        Some(Err(e)) => return RestResponse::<serde_json::Value>::error(400, e.to_string()),
+        None => {
+            return RestResponse::<serde_json::Value>::error(
+                415,
+                format!(
+                    "Unsupported patch content type, expected {} or {}",
+                    ConfigPatch::JSON_PATCH,
+                    ConfigPatch::MERGE_PATCH
+                ),
+            );
+        }
+    };
+
+    let _writes = state.lock_writes().await;
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<serde_json::Value>::error(404, format!("Config '{}' not found", path));
+    }
+    let Some(config) = state.config_map.get(&path) else {
+        return RestResponse::<serde_json::Value>::error(404, format!("Config '{}' not found", path));
+    };
+    if let Err(response) = check_if_match(&state, &path, &headers) {
+        return response;
+    }
+    let patched = ConfigPatchService::apply(&config, &patch);
+    let patched = match patched {
+        Ok(patched) => patched,
+        Err(e @ ConfigError::PatchConflict(_)) => {
+            return RestResponse::<serde_json::Value>::error(409, e.to_string());
+        }
+        Err(e) => return RestResponse::<serde_json::Value>::error(400, e.to_string()),
+    };
+    if let Err(e) = AttachedRulesService::check(&state.config_path(), &path, &patched) {
+        return match e {
+            ConfigError::AttachedRulesViolation { ref errors, .. } => {
+                RestResponse::rejected(409, e.to_string(), serde_json::json!(errors))
+            }
+            e => RestResponse::<serde_json::Value>::error(
+                500,
+                format!("Failed to load attached rules: {}", e),
+            ),
+        };
+    }
+    if let Err(e) = state.repository().save(&path, patched.clone()).await {
+        return RestResponse::<serde_json::Value>::error(
+            500,
+            format!("Failed to patch config: {}", e),
+        );
+    }
+    AuditService::record_change(
+        &state,
+        AuditAction::Update,
+        &path,
+        &actor,
+        Some(&config),
+        Some(&patched),
+        Some(patch.describe()),
+    );
+    state.store_write(path.clone(), patched, &actor.source);
+    RestResponse::success(serde_json::json!(format!("Config '{}' patched successfully", path)))
+}
+
+// 配置的加载状态：解析失败的文件不在列表中，通过这里查看失败原因
+async fn handle_http_config_status(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    let status = state
+        .route(Interface::Http, &path)
+        .ok()
+        .and_then(|_| state.config_status(&path));
+    match status {
+        Some(status) => RestResponse::success(status),
+        None => RestResponse::<ConfigStatus>::error(404, format!("Config '{}' not found", path)),
+    }
+}
+
+// 历史版本列表（不含内容），按版本号升序
+async fn handle_http_config_history(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    let history = {
+        if state.route(Interface::Http, &path).is_err() {
+            return RestResponse::<Vec<ConfigVersionInfo>>::error(
+                404,
+                format!("Config '{}' not found", path),
+            );
+        }
+        state.history()
+    };
+    match history.list(&path) {
+        Ok(versions) => RestResponse::success(versions.iter().map(ConfigVersionInfo::from).collect()),
+        Err(e) => RestResponse::<Vec<ConfigVersionInfo>>::error(
+            500,
+            format!("Failed to read history: {}", e),
+        ),
+    }
+}
+
+async fn handle_http_config_version(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path((path, version)): axum::extract::Path<(String, u64)>,
+) -> impl axum::response::IntoResponse {
+    let history = {
+        if state.route(Interface::Http, &path).is_err() {
+            return RestResponse::<ConfigVersion>::error(
+                404,
+                format!("Config '{}' not found", path),
+            );
+        }
+        state.history()
+    };
+    match history.get(&path, version) {
+        Ok(Some(version)) => RestResponse::success(version),
+        Ok(None) => RestResponse::<ConfigVersion>::error(
+            404,
+            format!("Version {} of '{}' not found", version, path),
+        ),
+        Err(e) => RestResponse::<ConfigVersion>::error(
+            500,
+            format!("Failed to read history: {}", e),
+        ),
+    }
+}
+
+// 恢复历史版本：原样写回当时的文件内容，文件监听器随后记录为新版本并通知订阅者
+async fn handle_http_rollback_config(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::extract::Path((path, version)): axum::extract::Path<(String, u64)>,
+    headers: axum::http::HeaderMap,
+) -> impl axum::response::IntoResponse {
+    let _writes = state.lock_writes().await;
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<serde_json::Value>::error(404, format!("Config '{}' not found", path));
+    }
+    if let Err(response) = check_if_match(&state, &path, &headers) {
+        return response;
+    }
+    let target = match state.history().get(&path, version) {
+        Ok(Some(target)) => target,
+        Ok(None) => {
+            return RestResponse::<serde_json::Value>::error(
+                404,
+                format!("Version {} of '{}' not found", version, path),
+            );
+        }
+        Err(e) => {
+            return RestResponse::<serde_json::Value>::error(
+                500,
+                format!("Failed to read history: {}", e),
+            );
+        }
+    };
+    let mut loaded = match FormatConverterService::new(
+        ConfigPath::new(path.clone()).unwrap(),
+        target.content.clone(),
+    )
+    .validate_config()
+    {
+        Ok(config) => config,
+        Err(e) => {
+            return RestResponse::<serde_json::Value>::error(
+                500,
+                format!("Version {} of '{}' cannot be parsed: {}", version, path, e),
+            );
+        }
+    };
+    if let Some(validation) = state.validation.clone()
+        && let Err(e) = validation.apply_defaults(&mut loaded)
+    {
+        return RestResponse::<serde_json::Value>::error(400, format!("Failed to roll back: {}", e));
+    }
+    if let Err(e) = AttachedRulesService::check(&state.config_path(), &path, &loaded) {
+        return match e {
+            ConfigError::AttachedRulesViolation { ref errors, .. } => {
+                RestResponse::rejected(409, e.to_string(), serde_json::json!(errors))
+            }
+            e => RestResponse::<serde_json::Value>::error(
+                500,
+                format!("Failed to load attached rules: {}", e),
+            ),
+        };
+    }
+    if let Err(e) = FileConfigRepository::new(state.config_path())
+        .save_content(&target.content, &path)
+        .await
+    {
+        return RestResponse::<serde_json::Value>::error(500, format!("Failed to roll back: {}", e));
+    }
+    let before = state.config_map.get(&path);
+    AuditService::record_change(
+        &state,
+        AuditAction::Update,
+        &path,
+        &actor,
+        before.as_ref(),
+        Some(&loaded),
+        Some(format!("rollback to version {}", version)),
+    );
+    state.store_write(path.clone(), loaded, &actor.source);
+    RestResponse::success(serde_json::json!(format!(
+        "Config '{}' rolled back to version {}",
+        path, version
+    )))
+}
+
+async fn handle_http_get_rollout(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<Rollout>::error(404, format!("Config '{}' not found", path));
+    }
+    match state.rollout(&path) {
+        Some(rollout) => RestResponse::success(rollout),
+        None => rollout_error(ConfigError::RolloutNotFound(path)),
+    }
+}
+
+// 新内容只推给被选中的订阅者，文件保持不变
+async fn handle_http_start_rollout(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+    axum::Json(request): axum::Json<RolloutRequest>,
+) -> impl axum::response::IntoResponse {
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<Rollout>::error(404, format!("Config '{}' not found", path));
+    }
+    match RolloutService::start(&state, &path, request, &actor) {
+        Ok(rollout) => RestResponse::success(rollout),
+        Err(e) => rollout_error(e),
+    }
+}
+
+async fn handle_http_promote_rollout(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<Rollout>::error(404, format!("Config '{}' not found", path));
+    }
+    match RolloutService::promote(&state, &path, &actor).await {
+        Ok(rollout) => RestResponse::success(rollout),
+        Err(e) => rollout_error(e),
+    }
+}
+
+async fn handle_http_rollback_rollout(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<Rollout>::error(404, format!("Config '{}' not found", path));
+    }
+    match RolloutService::rollback(&state, &path, &actor) {
+        Ok(rollout) => RestResponse::success(rollout),
+        Err(e) => rollout_error(e),
+    }
+}
+
+fn rollout_error(e: ConfigError) -> axum::Json<RestResponse<Rollout>> {
+    let code = match e {
+        ConfigError::RolloutInProgress(_) | ConfigError::AttachedRulesViolation { .. } => 409,
+        ConfigError::IoError(_) => 500,
+        _ => match e.category() {
+            ErrorCategory::NotFound => 404,
+            _ => 400,
+        },
+    };
+    RestResponse::<Rollout>::error(code, format!("Rollout failed: {}", e))
+}
+
+// 到达 apply_at 时整份替换配置内容
+async fn handle_http_schedule_change(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+    axum::Json(request): axum::Json<ScheduleRequest>,
+) -> impl axum::response::IntoResponse {
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<ScheduledChange>::error(404, format!("Config '{}' not found", path));
+    }
+    match ScheduleService::schedule(&state, &path, request, &actor) {
+        Ok(change) => RestResponse::success(change),
+        Err(e) => {
+            let code = match e {
+                ConfigError::AttachedRulesViolation { .. } => 409,
+                ConfigError::IoError(_) => 500,
+                _ => 400,
+            };
+            RestResponse::<ScheduledChange>::error(code, format!("Failed to schedule change: {}", e))
+        }
+    }
+}
+
+// 按生效时间排序
+async fn handle_http_pending_changes(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<Vec<ScheduledChange>>::error(404, format!("Config '{}' not found", path));
+    }
+    RestResponse::success(ScheduleService::pending(&state, &path))
+}
+
+async fn handle_http_list_ttls(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<Vec<ConfigTtl>>::error(404, format!("Config '{}' not found", path));
+    }
+    RestResponse::success(TtlService::list(&state, &path))
+}
+
+// 键或整个配置在 ttl_secs 后删除或恢复为 default
+async fn handle_http_set_ttl(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+    axum::Json(request): axum::Json<TtlRequest>,
+) -> impl axum::response::IntoResponse {
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<ConfigTtl>::error(404, format!("Config '{}' not found", path));
+    }
+    match TtlService::set(&state, &path, request) {
+        Ok(ttl) => RestResponse::success(ttl),
+        Err(e) => {
+            let code = match e.category() {
+                ErrorCategory::NotFound => 404,
+                _ => 400,
+            };
+            RestResponse::<ConfigTtl>::error(code, format!("Failed to set ttl: {}", e))
+        }
+    }
+}
+
+async fn handle_http_get_lock(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    match state.locks.lock().unwrap().get(&path, state.clock.now()) {
+        Some(lock) => RestResponse::success(lock),
+        None => RestResponse::<ConfigLock>::error(404, ConfigError::NotLocked(path).to_string()),
+    }
+}
+
+// 名称以 "/" 结尾时锁定整个命名空间
+async fn handle_http_lock_config(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+    axum::Json(request): axum::Json<LockRequest>,
+) -> impl axum::response::IntoResponse {
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<ConfigLock>::error(404, format!("Config '{}' not found", path));
+    }
+    match LockService::lock(&state, Some(&path), request).await {
+        Ok(lock) => RestResponse::success(lock),
+        Err(e) => RestResponse::<ConfigLock>::error(400, format!("Lock failed: {}", e)),
+    }
+}
+
+async fn handle_http_unlock_config(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    match LockService::unlock(&state, Some(&path)).await {
+        Ok(lock) => RestResponse::success(lock),
+        Err(e) => RestResponse::<ConfigLock>::error(404, e.to_string()),
+    }
+}
+
+async fn handle_http_delete_config(
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+) -> impl axum::response::IntoResponse {
+    let removed = {
+        let _writes = state.lock_writes().await;
+        let removed = match state.route(Interface::Http, &path) {
+            Ok(_) => state.config_map.remove(&path),
+            Err(_) => None,
+        };
+        if let Some(removed) = &removed {
+            AuditService::record_change(&state, AuditAction::Delete, &path, &actor, Some(removed), None, None);
+        }
+        removed.is_some()
     };
 
     if removed {
@@ -0,0 +1,233 @@
+use std::{convert::Infallible, sync::Arc};
+
+use axum::{
+    extract::State,
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse},
+    },
+};
+use futures_util::stream::{self, Stream, StreamExt};
+use tokio::sync::{mpsc::UnboundedReceiver, watch};
+use tracing::{debug, info};
+
+use crate::{
+    domain::{
+        entities::configuration::Config, events::config_changed::ConfigUpdate,
+        services::env_override::EnvOverrideService, value_objects::namespace::covers,
+    },
+    infrastructure::notification::subscriber_quota::QuotaDecision,
+    shared::{
+        app_state::{AppState, RestResponse, wait_for_shutdown},
+        config::Interface,
+    },
+};
+
+// SSE 请求头：客户端重连时带上最后收到的事件 id（即事件序号）
+const LAST_EVENT_ID: &str = "last-event-id";
+
+// 一个 SSE 连接的订阅，与 WebSocket、TCP 订阅者共用同一套推送通道；连接断开时注销
+struct SseSubscription {
+    state: Arc<AppState>,
+    client_id: String,
+    file: String,
+    updates: UnboundedReceiver<ConfigUpdate>,
+    shutdown: watch::Receiver<bool>,
+    closed: bool,
+}
+
+impl Drop for SseSubscription {
+    fn drop(&mut self) {
+        self.state.remove_subscriber(&self.client_id);
+        info!("SSE client {} disconnected", self.client_id);
+    }
+}
+
+// 首个事件为 initial（当前配置），之后每次更新一个 update 事件；
+// 带 Last-Event-ID 重连且错过的更新仍在事件日志中时，改为补发这些更新。
+// path 以 "/" 结尾时订阅整个命名空间，initial 事件包含命名空间内的全部配置
+pub async fn handle_http_config_events(
+    State(state): State<Arc<AppState>>,
+    axum::extract::Path(path): axum::extract::Path<String>,
+    headers: axum::http::HeaderMap,
+) -> Response {
+    if state.route(Interface::Http, &path).is_err() {
+        return RestResponse::<String>::error(404, format!("Config '{}' not found", path))
+            .into_response();
+    }
+    let namespace = path.strip_suffix('/');
+    if let Some(namespace) = namespace
+        && state.namespace(namespace).is_none()
+    {
+        return RestResponse::<String>::error(404, format!("Namespace '{}' not found", namespace))
+            .into_response();
+    }
+    let last_event_id = headers
+        .get(LAST_EVENT_ID)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok());
+
+    // 在订阅锁内读取快照（或错过的更新）并注册，之后发布的更新都会进入推送通道
+    let (tx, updates) = tokio::sync::mpsc::unbounded_channel::<ConfigUpdate>();
+    let client_id = state.id_generator.next_id("sse");
+    let (seq, missed, configs) = {
+        let mut subscriptions = state.subscriptions.lock().unwrap();
+        let configs: Vec<(String, Config)> = match namespace {
+            Some(_) => state
+                .visible_configs(Interface::Http)
+                .into_iter()
+                .filter(|key| covers(&path, key))
+                .filter_map(|key| Some((key.clone(), state.config_map.get(&key)?)))
+                .collect(),
+            None => match state.config_map.get(&path) {
+                Some(config) => vec![(path.clone(), config)],
+                None => {
+                    return RestResponse::<String>::error(
+                        404,
+                        format!("Config '{}' not found", path),
+                    )
+                    .into_response();
+                }
+            },
+        };
+        let missed = last_event_id.and_then(|seq| subscriptions.event_log.since(&path, seq));
+        subscriptions.add(&client_id, tx, "sse", state.clock.now());
+        subscriptions.watch(&client_id, &path);
+        (subscriptions.event_log.latest_seq(), missed, configs)
+    };
+    let subscription = SseSubscription {
+        state: state.clone(),
+        client_id: client_id.clone(),
+        file: path.clone(),
+        updates,
+        shutdown: state.shutdown_receiver(),
+        closed: false,
+    };
+
+    let first_events = match missed {
+        Some(missed) => {
+            info!(
+                "SSE client {} resumed, {} missed updates",
+                client_id,
+                missed.len()
+            );
+            missed.iter().map(update_event).collect()
+        }
+        None => {
+            let mut released = serde_json::Map::new();
+            for (key, mut config) in configs {
+                match EnvOverrideService::apply_env_override(&mut config) {
+                    Ok(config) => {
+                        let value = serde_json::json!({
+                            "hash": state.config_map.hash(&key),
+                            "config": config.to_serde_value()
+                        });
+                        released.insert(key, value);
+                    }
+                    Err(e) => {
+                        return RestResponse::<String>::error(
+                            500,
+                            format!("Failed to process config: {}", e),
+                        )
+                        .into_response();
+                    }
+                }
+            }
+            let data = match namespace {
+                Some(namespace) => serde_json::json!({
+                    "namespace": namespace,
+                    "seq": seq,
+                    "configs": released
+                }),
+                None => {
+                    let mut data = released.remove(&path).unwrap_or_default();
+                    data["file"] = serde_json::json!(path);
+                    data["seq"] = serde_json::json!(seq);
+                    data
+                }
+            };
+            vec![
+                Event::default()
+                    .event("initial")
+                    .id(seq.to_string())
+                    .data(data.to_string()),
+            ]
+        }
+    };
+    state.mark_synced(&client_id, &path);
+    info!("SSE client {} start watching file {}", client_id, path);
+
+    let events = stream::iter(first_events)
+        .map(Ok)
+        .chain(updates_stream(subscription));
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+fn updates_stream(subscription: SseSubscription) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold(subscription, |mut subscription| async move {
+        if subscription.closed {
+            return None;
+        }
+        let update = tokio::select! {
+            biased;
+            // 服务关闭：发送 shutdown 事件后结束响应
+            _ = wait_for_shutdown(&mut subscription.shutdown) => {
+                subscription.closed = true;
+                let event = Event::default().event("shutdown").data("server shutting down");
+                return Some((Ok(event), subscription));
+            }
+            update = subscription.updates.recv() => update?,
+        };
+
+        // 带宽配额：限速时等待下一个窗口，超限时发送 error 事件后结束响应
+        let bytes = update.config.len() as u64;
+        match subscription
+            .state
+            .account_push(&subscription.client_id, bytes)
+        {
+            QuotaDecision::Allow => {}
+            QuotaDecision::Delay(wait) => {
+                debug!(
+                    "throttle SSE client {} for {:?}",
+                    subscription.client_id, wait
+                );
+                tokio::time::sleep(wait).await;
+            }
+            QuotaDecision::Disconnect(reason) => {
+                info!(
+                    "SSE client {} exceeded quota: {}",
+                    subscription.client_id, reason
+                );
+                subscription.closed = true;
+                let message = format!("subscriber quota exceeded: {}", reason);
+                return Some((
+                    Ok(Event::default().event("error").data(message)),
+                    subscription,
+                ));
+            }
+        }
+        subscription
+            .state
+            .mark_synced(&subscription.client_id, &subscription.file);
+        Some((Ok(update_event(&update)), subscription))
+    })
+}
+
+fn update_event(update: &ConfigUpdate) -> Event {
+    let config: serde_json::Value = serde_json::from_str(&update.config).unwrap_or_default();
+    let data = serde_json::json!({
+        "file": update.file,
+        "seq": update.seq,
+        "config": config,
+        "patch": update.patch,
+        "hash": update.hash,
+        "source": update.source,
+        "timestamp": update.timestamp.to_rfc3339()
+    });
+    Event::default()
+        .event(update.kind.message_type())
+        .id(update.seq.to_string())
+        .data(data.to_string())
+}
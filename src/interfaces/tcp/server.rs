@@ -1,117 +1,163 @@
-use std::{
-    collections::HashMap,
-    path::{Path, PathBuf},
-    sync::{Arc, Mutex},
-};
+use std::{net::SocketAddr, path::Path, sync::Arc};
 
-use notify::{Event, RecursiveMode, Watcher};
 use tokio::{
-    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
-    net::{TcpListener, TcpStream},
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader},
+    sync::mpsc::UnboundedReceiver,
 };
 use tracing::{debug, info};
 
 use crate::{
+    application::{
+        dtos::{capabilities::Capabilities, config_transaction::ConfigTransaction},
+        services::{
+            attached_rules_service::AttachedRulesService, audit_service::AuditService,
+            authorization_service::AuthorizationService, backup_service::BackupService,
+            storage_sync_service::StorageSyncService,
+            freshness_service::FreshnessService,
+            schedule_service::ScheduleService, startup_service::StartupService,
+            transaction_service::TransactionService, ttl_service::TtlService,
+            watch_service::WatchService,
+        },
+    },
     domain::{
+        entities::{
+            audit::{AuditAction, AuditActor},
+            config_version::ConfigVersionInfo,
+            configuration::ConfigValue,
+            key_subscriptions::KeySubscriptions,
+        },
+        events::config_changed::{ChangeKind, ConfigChange, ConfigUpdate},
         services::{env_override::EnvOverrideService, format_converter::FormatConverterService},
         value_objects::config_path::ConfigPath,
     },
     infrastructure::{
+        limits::rate_limiter::RateLimiter,
         logging::log_manager::LogManager,
-        repositories::file_config_repository::FileConfigRepository,
+        notification::{dispatcher::dispatch, subscriber_quota::QuotaDecision},
     },
     interfaces::cli::command::CliCommand,
-    shared::{app_state::AppState, utils::read_file},
+    shared::{
+        app_state::{AppState, drain_subscribers, wait_for_shutdown},
+        config::Interface,
+        listener::ServeListener,
+        utils::{read_file_async, shutdown_signal},
+    },
 };
 
-async fn handle_client(stream: TcpStream, app_state: Arc<Mutex<AppState>>) -> anyhow::Result<()> {
-    let stream_addr = stream.local_addr().unwrap();
+// 连接可能来自 TCP 端口或 Unix socket，后者没有对端地址
+async fn handle_client<S>(
+    stream: S,
+    address: Option<SocketAddr>,
+    app_state: Arc<AppState>,
+) -> anyhow::Result<()>
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let connection_id = app_state.id_generator.next_id("tcp");
+    let max_body_size = app_state.max_body_size;
+    // AUTH 之后以对应 API key 的身份授权和审计
+    let mut actor = AuditActor::new("tcp_client", address);
 
     let mut reader = BufReader::new(stream);
+    // BEGIN 之后暂存的修改，COMMIT 时一次性提交
+    let mut transaction: Option<ConfigTransaction> = None;
 
     loop {
         let mut line = String::new();
 
-        match reader.read_line(&mut line).await {
+        // 单行命令最多读取 max_body_size 字节，超出时回复错误并断开，剩余内容不再解析
+        match (&mut reader).take(max_body_size as u64 + 1).read_line(&mut line).await {
             Ok(0) => {
                 debug!("client closed connection");
                 break;
             }
+            Ok(_) if line.len() > max_body_size => {
+                info!("tcp request exceeds {} bytes, closing connection", max_body_size);
+                let message = format!("request exceeds {} bytes\n", max_body_size);
+                let response = format!("{}\n{}", message.len(), message);
+                let _ = reader.get_mut().write_all(response.as_bytes()).await;
+                break;
+            }
             Ok(_) => {
                 let request = line.trim();
                 debug!("received request: {}", request);
-                let command = CliCommand::from_str(request);
+                let command = CliCommand::parse(request);
+                let client = RateLimiter::client_key(actor.api_key.as_deref(), address);
+                let throttled = app_state
+                    .settings()
+                    .rate_limiter
+                    .as_ref()
+                    .and_then(|rate_limiter| rate_limiter.check(&client).err());
                 let mut response = String::new();
                 debug!("command: {:?}", command);
 
+                // 命名空间路由：未对 TCP 开放的配置按不存在处理
+                let hidden = command.as_ref().and_then(|c| c.target()).is_some_and(|target| {
+                    app_state.route(Interface::Tcp, target).is_err()
+                });
+                let denied = command
+                    .as_ref()
+                    .and_then(|c| Some((c.permission()?, c.target()?)))
+                    .and_then(|(permission, target)| {
+                        AuthorizationService::authorize(&app_state, &actor, permission, target)
+                            .err()
+                    })
+                    .map(|e| format!("access denied: {}\n", e));
+
                 match command {
+                    _ if throttled.is_some() => {
+                        let wait = throttled.unwrap_or_default();
+                        response = format!(
+                            "rate limit exceeded, retry after {} ms\n",
+                            wait.as_millis()
+                        );
+                    }
+                    Some(command) if hidden => {
+                        response =
+                            format!("config not found: {}\n", command.target().unwrap_or_default());
+                    }
+                    Some(_) if denied.is_some() => {
+                        response = denied.unwrap_or_default();
+                    }
+                    // 配置被锁定或全局冻结时拒绝修改
+                    Some(CliCommand::Add { path } | CliCommand::Remove { path })
+                        if app_state.check_unlocked(&path).is_err() =>
+                    {
+                        if let Err(e) = app_state.check_unlocked(&path) {
+                            response = format!("{}\n", e);
+                        }
+                    }
                     Some(CliCommand::Add { path }) => {
                         debug!("add: {}", path);
-                        match read_file(&path) {
-                            Ok(content) => {
-                                match FormatConverterService::new(
-                                    ConfigPath::new(path.clone()).unwrap(),
-                                    content,
-                                )
-                                .validate_config()
-                                {
-                                    Ok(mut config) => {
-                                        match EnvOverrideService::apply_env_override(&mut config) {
-                                            Ok(config) => {
-                                                app_state
-                                                    .lock()
-                                                    .unwrap()
-                                                    .config_map
-                                                    .insert(path.clone(), config.clone());
-
-                                                // 现在可以安全地使用 await
-                                                match FileConfigRepository::new(
-                                                    app_state.lock().unwrap().config_path.clone(),
-                                                )
-                                                .save(config.clone(), &path)
-                                                {
-                                                    Ok(_) => {
-                                                        let config_str = serde_json::to_string(&config)
-                                                    .unwrap_or_else(|_| {
-                                                        "add config success, but serialize failed"
-                                                            .to_string()
-                                                    });
-                                                        response =
-                                                            format!("add result: {}\n", config_str);
-                                                    }
-                                                    Err(e) => {
-                                                        response = format!(
-                                                            "write config file failed: {}\n",
-                                                            e
-                                                        );
-                                                    }
-                                                }
-                                            }
-                                            Err(e) => {
-                                                response = format!("env override failed: {}\n", e);
-                                            }
-                                        }
-                                    }
-                                    Err(e) => {
-                                        response = format!("config validate failed: {}\n", e);
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                response = format!("read file failed: {}\n", e);
-                            }
-                        }
+                        response = match add(&app_state, &actor, &path).await {
+                            Ok(response) | Err(response) => response,
+                        };
                     }
                     Some(CliCommand::Remove { path }) => {
                         debug!("remove: {}", path);
-                        let removed =
-                            { app_state.lock().unwrap().config_map.remove(&path).is_some() }; // MutexGuard 在这里被释放
+                        let removed = {
+                            let _writes = app_state.lock_writes().await;
+                            let removed = app_state.config_map.remove(&path);
+                            if let Some(removed) = &removed {
+                                AuditService::record_change(
+                                    &app_state,
+                                    AuditAction::Delete,
+                                    &path,
+                                    &actor,
+                                    Some(removed),
+                                    None,
+                                    None,
+                                );
+                            }
+                            removed
+                        }; // 写锁在这里被释放
 
-                        if removed {
-                            let removed_path = Path::new(&app_state.lock().unwrap().config_path)
+                        if removed.is_some() {
+                            let removed_path = Path::new(&app_state.config_path())
                                 .join(path.clone());
 
                             // 删除文件
+
                             match tokio::fs::remove_file(&removed_path).await {
                                 Ok(_) => {
                                     response = format!("removed config: {}\n", path);
@@ -130,7 +176,7 @@ async fn handle_client(stream: TcpStream, app_state: Arc<Mutex<AppState>>) -> an
                     Some(CliCommand::Get { path }) => {
                         debug!("get: {}", path);
                         let config_str = {
-                            match app_state.lock().unwrap().config_map.get(&path) {
+                            match app_state.config_map.get(&path) {
                                 Some(config) => match serde_json::to_string(&config) {
                                     Ok(config_str) => Some(config_str),
                                     Err(e) => {
@@ -143,25 +189,31 @@ async fn handle_client(stream: TcpStream, app_state: Arc<Mutex<AppState>>) -> an
                                     None
                                 }
                             }
-                        }; // MutexGuard 在这里被释放
+                        };
 
                         if let Some(config_str) = config_str {
+                            app_state.record_fetch(&path);
                             response = format!("{}\n", config_str);
                         }
                     }
                     Some(CliCommand::List) => {
                         debug!("list");
                         let list_response = {
-                            if app_state.lock().unwrap().config_map.is_empty() {
+                            let keys = AuthorizationService::readable(
+                                &app_state,
+                                &actor,
+                                app_state.visible_configs(Interface::Tcp),
+                            );
+                            if keys.is_empty() {
                                 "no config file loaded".to_string()
                             } else {
                                 let mut list_response = String::from("loaded config files:\n");
-                                for (key, _) in app_state.lock().unwrap().config_map.iter() {
+                                for key in keys {
                                     list_response.push_str(&format!("  - {}\n", key));
                                 }
                                 list_response
                             }
-                        }; // MutexGuard 在这里被释放
+                        };
 
                         response = list_response;
                     }
@@ -170,12 +222,11 @@ async fn handle_client(stream: TcpStream, app_state: Arc<Mutex<AppState>>) -> an
                         debug!("listen: {}", path);
 
                         // 发送初始响应
-                        let initial_config = match app_state.lock().unwrap().config_map.get(&path) {
-                            Some(config) => {
-                                let mut config_clone = config.clone();
+                        let initial_config = match app_state.config_map.get(&path) {
+                            Some(mut config) => {
                                 format!(
                                     "{:?}",
-                                    EnvOverrideService::apply_env_override(&mut config_clone)
+                                    EnvOverrideService::apply_env_override(&mut config)
                                         .unwrap()
                                         .config
                                 )
@@ -184,7 +235,7 @@ async fn handle_client(stream: TcpStream, app_state: Arc<Mutex<AppState>>) -> an
                         };
 
                         let mut stream = reader.into_inner();
-                        let response_bytes_len = initial_config.as_bytes().len();
+                        let response_bytes_len = initial_config.len();
                         let initial_response =
                             format!("{}\n{}", response_bytes_len, initial_config);
 
@@ -198,37 +249,166 @@ async fn handle_client(stream: TcpStream, app_state: Arc<Mutex<AppState>>) -> an
                         }
 
                         // 创建通知通道
-                        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+                        let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<ConfigUpdate>();
 
                         // 将监听信息存储到 notify_map
-                        app_state
-                            .lock()
-                            .unwrap()
-                            .notify_map
-                            .insert(stream_addr.to_string(), (path.clone(), tx));
+                        app_state.add_subscriber(&connection_id, &path, tx, "tcp");
+                        app_state.mark_synced(&connection_id, &path);
 
-                        debug!("client {} start listen file {}", stream_addr, path);
+                        debug!("client {} start listen file {}", connection_id, path);
 
                         // 启动异步推送任务
-                        tokio::spawn(async move {
-                            while let Some(config_data) = rx.recv().await {
-                                let response_len = config_data.as_bytes().len();
-                                let push_response = format!("{}\n{}", response_len, config_data);
+                        spawn_push(stream, app_state, connection_id, rx, |update| {
+                            vec![update.config.clone()]
+                        });
 
-                                if let Err(e) = stream.write_all(push_response.as_bytes()).await {
-                                    debug!("push data failed: {}", e);
-                                    break;
-                                }
-                                if let Err(e) = stream.flush().await {
-                                    debug!("flush stream failed: {}", e);
+                        // 跳出循环，该连接现在专门用于推送
+                        return Ok(());
+                    }
+
+                    Some(CliCommand::Subscribe { path, keys }) => {
+                        debug!("subscribe: {} {:?}", path, keys);
+                        match subscribe(&app_state, &connection_id, &path, &keys) {
+                            Ok((initial, mut filter, rx)) => {
+                                let mut stream = reader.into_inner();
+                                let initial = format!("{}\n{}", initial.len(), initial);
+                                if let Err(e) = stream.write_all(initial.as_bytes()).await {
+                                    debug!("send initial response failed: {}", e);
+                                    app_state.remove_subscriber(&connection_id);
                                     break;
                                 }
-                                debug!("push config update success");
+                                info!("client {} subscribed to {} {:?}", connection_id, path, keys);
+
+                                // 键路径只在对应子树变化时推送
+                                spawn_push(stream, app_state, connection_id, rx, move |update| {
+                                    filter
+                                        .changes(update)
+                                        .into_iter()
+                                        .map(|change| change.message(update).to_string())
+                                        .collect()
+                                });
+                                return Ok(());
                             }
+                            Err(e) => response = format!("{}\n", e),
+                        }
+                    }
+
+                    Some(CliCommand::Validate { path, rules }) => {
+                        debug!("validate: {} {:?}", path, rules);
+                        let result = app_state.config_map.get(&path).map(|config| {
+                            AttachedRulesService::validate(&app_state, &config, rules.as_deref())
                         });
+                        response = match result {
+                            Some(Ok(result)) => format!(
+                                "{}\n",
+                                serde_json::to_string(&result).unwrap_or_default()
+                            ),
+                            Some(Err(e)) => format!("load rules failed: {}\n", e),
+                            None => format!("config not found: {}\n", path),
+                        };
+                    }
 
-                        // 跳出循环，该连接现在专门用于推送
-                        return Ok(());
+                    Some(CliCommand::History { path }) => {
+                        debug!("history: {}", path);
+                        response = match app_state.history().list(&path) {
+                            Ok(versions) => {
+                                let versions: Vec<ConfigVersionInfo> =
+                                    versions.iter().map(ConfigVersionInfo::from).collect();
+                                format!(
+                                    "{}\n",
+                                    serde_json::to_string(&versions).unwrap_or_default()
+                                )
+                            }
+                            Err(e) => format!("read history failed: {}\n", e),
+                        };
+                    }
+
+                    Some(CliCommand::Begin) => {
+                        debug!("begin");
+                        response = if transaction.is_some() {
+                            "transaction already in progress\n".to_string()
+                        } else {
+                            transaction = Some(ConfigTransaction::default());
+                            "transaction started\n".to_string()
+                        };
+                    }
+
+                    Some(CliCommand::Set { file, key, value }) => {
+                        debug!("set: {} {} {}", file, key, value);
+                        let value = ConfigValue::from_string(value);
+                        match transaction.as_mut() {
+                            Some(transaction) => {
+                                transaction.stage(file, key, value);
+                                response =
+                                    format!("staged {} changes\n", transaction.changes.len());
+                            }
+                            // 不在事务中时立即提交单个修改
+                            None => {
+                                let mut single = ConfigTransaction::default();
+                                single.stage(file.clone(), key.clone(), value);
+                                response = match TransactionService::commit(
+                                    &app_state,
+                                    single,
+                                    &actor,
+                                )
+                                .await
+                                {
+                                    Ok(_) => format!("set {} in {}\n", key, file),
+                                    Err(e) => format!("set failed: {}\n", e),
+                                };
+                            }
+                        }
+                    }
+
+                    Some(CliCommand::Commit) => {
+                        debug!("commit");
+                        response = match transaction.take() {
+                            Some(staged) => {
+                                match TransactionService::commit(&app_state, staged, &actor).await {
+                                    Ok(result) => format!(
+                                        "committed {} changes to {} files\n",
+                                        result.changes,
+                                        result.files.len()
+                                    ),
+                                    Err(e) => format!("commit failed, nothing applied: {}\n", e),
+                                }
+                            }
+                            None => "no transaction in progress\n".to_string(),
+                        };
+                    }
+
+                    Some(CliCommand::Abort) => {
+                        debug!("abort");
+                        response = match transaction.take() {
+                            Some(staged) => {
+                                format!("aborted {} staged changes\n", staged.changes.len())
+                            }
+                            None => "no transaction in progress\n".to_string(),
+                        };
+                    }
+
+                    Some(CliCommand::Auth { key }) => {
+                        debug!("auth");
+                        let api_key = app_state.settings().api_keys.find(&key).cloned();
+                        response = match api_key {
+                            Some(api_key) => {
+                                actor = AuditActor::new("tcp_client", address)
+                                    .with_api_key(&api_key.name);
+                                format!("authenticated as {}\n", api_key.name)
+                            }
+                            None => "invalid api key\n".to_string(),
+                        };
+                    }
+
+                    Some(CliCommand::Hello { version }) => {
+                        debug!("hello: {:?}", version);
+                        response = match Capabilities::negotiate(version) {
+                            Ok(capabilities) => format!(
+                                "{}\n",
+                                serde_json::to_string(&capabilities).unwrap_or_default()
+                            ),
+                            Err(e) => format!("{}\n", e),
+                        };
                     }
 
                     None => {
@@ -239,7 +419,7 @@ async fn handle_client(stream: TcpStream, app_state: Arc<Mutex<AppState>>) -> an
 
                 // 发送响应
                 let mut stream = reader.into_inner();
-                let response_bytes_len = response.as_bytes().len();
+                let response_bytes_len = response.len();
                 let response = format!("{}\n{}", response_bytes_len, response);
                 if let Err(e) = stream.write_all(response.as_bytes()).await {
                     debug!("send response failed: {}", e);
@@ -265,10 +445,228 @@ async fn handle_client(stream: TcpStream, app_state: Arc<Mutex<AppState>>) -> an
     Ok(())
 }
 
+// 读取文件并加载为配置；与 HTTP 更新一致，内存中的配置填充默认值并通过附加规则校验后才写入
+async fn add(app_state: &Arc<AppState>, actor: &AuditActor, path: &str) -> Result<String, String> {
+    let content = read_file_async(path)
+        .await
+        .map_err(|e| format!("read file failed: {}\n", e))?;
+    let mut config = FormatConverterService::new(ConfigPath::new(path).unwrap(), content)
+        .validate_config()
+        .map_err(|e| format!("config validate failed: {}\n", e))?;
+    let config = EnvOverrideService::apply_env_override(&mut config)
+        .map_err(|e| format!("env override failed: {}\n", e))?;
+
+    let _writes = app_state.lock_writes().await;
+    let mut loaded = config.clone();
+    if let Some(validation) = app_state.validation.clone() {
+        validation
+            .apply_defaults(&mut loaded)
+            .map_err(|e| format!("config validate failed: {}\n", e))?;
+    }
+    AttachedRulesService::check(&app_state.config_path(), path, &loaded)
+        .map_err(|e| format!("config validate failed: {}\n", e))?;
+
+    // 文件写入成功后才更新内存，写入失败时内存与文件保持一致
+    app_state
+        .repository()
+        .save(path, config)
+        .await
+        .map_err(|e| format!("write config file failed: {}\n", e))?;
+    let before = app_state.config_map.get(path);
+    app_state.store_write(path.to_string(), loaded.clone(), &actor.source);
+    AuditService::record_change(
+        app_state,
+        AuditAction::Create,
+        path,
+        actor,
+        before.as_ref(),
+        Some(&loaded),
+        None,
+    );
+    let config_str = serde_json::to_string(&loaded)
+        .unwrap_or_else(|_| "add config success, but serialize failed".to_string());
+    Ok(format!("add result: {}\n", config_str))
+}
+
+// 在订阅锁内登记并读取快照，之后发布的更新都会进入推送通道；未指定键路径时订阅整个文件
+fn subscribe(
+    app_state: &AppState,
+    connection_id: &str,
+    path: &str,
+    keys: &[String],
+) -> Result<(String, KeySubscriptions, UnboundedReceiver<ConfigUpdate>), String> {
+    let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<ConfigUpdate>();
+    let (seq, mut config) = {
+        let mut subscriptions = app_state.subscriptions.lock().unwrap();
+        let config = app_state
+            .config_map
+            .get(path)
+            .ok_or_else(|| format!("config not found: {}", path))?;
+        subscriptions.add(connection_id, tx, "tcp", app_state.clock.now());
+        let seq = subscriptions.event_log.latest_seq();
+        subscriptions.watch(connection_id, path);
+        (seq, config)
+    };
+    let released = match EnvOverrideService::apply_env_override(&mut config) {
+        Ok(released) => released,
+        Err(e) => {
+            app_state.remove_subscriber(connection_id);
+            return Err(format!("env override failed: {}", e));
+        }
+    };
+
+    let mut filter = KeySubscriptions::default();
+    let initial = if keys.is_empty() {
+        filter.subscribe(path, None, seq, None);
+        serde_json::json!({
+            "type": "subscribed",
+            "file": path,
+            "seq": seq,
+            "config": released.to_serde_value()
+        })
+    } else {
+        let mut values = serde_json::Map::new();
+        for key in keys {
+            let value = released.get(key);
+            values.insert(
+                key.clone(),
+                value.as_ref().map(ConfigValue::to_serde_value).unwrap_or_default(),
+            );
+            filter.subscribe(path, Some(key.clone()), seq, value);
+        }
+        serde_json::json!({
+            "type": "subscribed",
+            "file": path,
+            "seq": seq,
+            "values": values
+        })
+    };
+    app_state.mark_synced(connection_id, path);
+    Ok((initial.to_string(), filter, rx))
+}
+
+// 连接专门用于推送：render 把一次更新转换为要发送的帧。定期发送 ping 帧，
+// 客户端发来的任何一行（如 pong）都视为存活；客户端断开、空闲超时、服务关闭或推送失败时断开并注销
+fn spawn_push<S>(
+    stream: S,
+    app_state: Arc<AppState>,
+    connection_id: String,
+    mut rx: UnboundedReceiver<ConfigUpdate>,
+    mut render: impl FnMut(&ConfigUpdate) -> Vec<String> + Send + 'static,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let mut shutdown = app_state.shutdown_receiver();
+    let (heartbeat, idle_timeout) = (app_state.tcp_heartbeat, app_state.tcp_idle_timeout);
+    tokio::spawn(async move {
+        let (reader, mut stream) = tokio::io::split(stream);
+        let mut lines = BufReader::new(reader).lines();
+        let mut last_seen = tokio::time::Instant::now();
+        let mut next_ping = last_seen + heartbeat.unwrap_or_default();
+
+        'push: loop {
+            let idle_deadline = last_seen + idle_timeout.unwrap_or_default();
+            let update = tokio::select! {
+                biased;
+                // 服务关闭：通知客户端后断开连接
+                _ = wait_for_shutdown(&mut shutdown) => {
+                    let message = "server shutting down\n";
+                    let notice = format!("{}\n{}", message.len(), message);
+                    let _ = stream.write_all(notice.as_bytes()).await;
+                    let _ = stream.shutdown().await;
+                    break;
+                }
+                line = lines.next_line() => match line {
+                    Ok(Some(_)) => {
+                        last_seen = tokio::time::Instant::now();
+                        continue;
+                    }
+                    Ok(None) | Err(_) => {
+                        info!("client {} disconnected", connection_id);
+                        break;
+                    }
+                },
+                update = rx.recv() => match update {
+                    Some(update) => update,
+                    None => break,
+                },
+                _ = tokio::time::sleep_until(idle_deadline), if idle_timeout.is_some() => {
+                    info!("client {} idle, closing connection", connection_id);
+                    let _ = stream.shutdown().await;
+                    break;
+                }
+                _ = tokio::time::sleep_until(next_ping), if heartbeat.is_some() => {
+                    let message = "ping\n";
+                    let ping = format!("{}\n{}", message.len(), message);
+                    if let Err(e) = stream.write_all(ping.as_bytes()).await {
+                        debug!("send ping to client {} failed: {}", connection_id, e);
+                        break;
+                    }
+                    next_ping = tokio::time::Instant::now() + heartbeat.unwrap_or_default();
+                    continue;
+                }
+            };
+            for config_data in render(&update) {
+                let response_len = config_data.len();
+                let push_response = format!("{}\n{}", response_len, config_data);
+
+                let decision =
+                    app_state.account_push(&connection_id, push_response.len() as u64);
+                match decision {
+                    QuotaDecision::Allow => {}
+                    QuotaDecision::Delay(wait) => {
+                        debug!("throttle client {} for {:?}", connection_id, wait);
+                        tokio::time::sleep(wait).await;
+                    }
+                    QuotaDecision::Disconnect(reason) => {
+                        info!("client {} exceeded quota: {}", connection_id, reason);
+                        break 'push;
+                    }
+                }
+
+                if let Err(e) = stream.write_all(push_response.as_bytes()).await {
+                    debug!("push data failed: {}", e);
+                    break 'push;
+                }
+                if let Err(e) = stream.flush().await {
+                    debug!("flush stream failed: {}", e);
+                    break 'push;
+                }
+            }
+            app_state.mark_synced(&connection_id, &update.file);
+            debug!("push config update success");
+        }
+        app_state.remove_subscriber(&connection_id);
+    });
+}
+
+// 接受一个连接并交给 handle_client 处理
+async fn accept(listener: &ServeListener, app_state: &Arc<AppState>) -> std::io::Result<()> {
+    let app_state = app_state.clone();
+    match listener {
+        ServeListener::Tcp(listener) => {
+            let (stream, address) = listener.accept().await?;
+            tokio::spawn(async move {
+                let _ = handle_client(stream, Some(address), app_state).await;
+            });
+        }
+        #[cfg(unix)]
+        ServeListener::Unix(listener) => {
+            let (stream, _) = listener.accept().await?;
+            tokio::spawn(async move {
+                let _ = handle_client(stream, None, app_state).await;
+            });
+        }
+    }
+    Ok(())
+}
+
 pub struct TcpServer {
     pub port: u16,
     pub host: String,
-    pub app_state: Arc<Mutex<AppState>>,
+    // 设置时监听该 Unix socket，不再监听 TCP 端口
+    pub uds: Option<String>,
+    pub app_state: Arc<AppState>,
     pub log_manager: LogManager,
 }
 
@@ -276,220 +674,123 @@ impl TcpServer {
     pub fn new(
         port: u16,
         host: String,
-        app_state: Arc<Mutex<AppState>>,
+        app_state: Arc<AppState>,
         log_manager: LogManager,
     ) -> Self {
         Self {
             port,
             host,
+            uds: None,
             app_state,
             log_manager,
         }
     }
 
+    pub fn with_uds(mut self, uds: Option<String>) -> Self {
+        self.uds = uds;
+        self
+    }
+
     pub async fn start(mut self) -> anyhow::Result<()> {
         debug!(
             "serve port: {} host: {} config path: {}",
             self.port,
             self.host,
-            self.app_state.lock().unwrap().config_path
+            self.app_state.config_path()
         );
 
         info!(
             "check config path: {}",
-            self.app_state.lock().unwrap().config_path
+            self.app_state.config_path()
         );
-        if !Path::new(&self.app_state.lock().unwrap().config_path).exists() {
+        if !tokio::fs::try_exists(self.app_state.config_path()).await.unwrap_or(false) {
             info!("config path not found, create it");
-            std::fs::create_dir_all(self.app_state.lock().unwrap().config_path.clone())?;
+            tokio::fs::create_dir_all(self.app_state.config_path()).await?;
         }
+        let listener = ServeListener::bind(&self.host, self.port, self.uds.as_deref()).await?;
+        // 端口已绑定、配置目录已就绪，此后不再需要高权限
+        self.app_state.drop_privileges()?;
         info!(
             "load config from path: {}",
-            self.app_state.lock().unwrap().config_path
+            self.app_state.config_path()
         );
 
-        // 先获取配置路径，避免在循环中持有锁
-        let config_path = self.app_state.lock().unwrap().config_path.clone();
-
-        // 收集所有配置文件到临时 HashMap
-        let mut configs_to_load = HashMap::new();
-
-        // 遍历文件夹中的文件
-        for entry in std::fs::read_dir(config_path)? {
-            let entry = entry?;
-            let path = entry.path();
-            if path.is_file() {
-                info!("load config file: {}", path.to_string_lossy().to_string());
-                let content = read_file(path.to_string_lossy().to_string().as_str())?;
-                info!("content: {}", content);
-                let config = FormatConverterService::new(
-                    ConfigPath::new(path.to_string_lossy().to_string()).unwrap(),
-                    content,
-                )
-                .validate_config()?;
-                info!("config: {:?}", config);
-
-                // 添加到临时 HashMap，不需要获取锁
-                configs_to_load.insert(entry.file_name().to_string_lossy().to_string(), config);
-            }
-        }
-
-        // 批量插入所有配置，只获取一次锁
-        {
-            let mut app_state_guard = self.app_state.lock().unwrap();
-            for (key, config) in configs_to_load {
-                app_state_guard.config_map.insert(key, config);
-            }
-        } // 锁在这里被释放
-
-        info!(
-            "config loaded finished: {} files",
-            self.app_state.lock().unwrap().config_map.len()
-        );
+        ScheduleService::restore(&self.app_state);
+        TtlService::restore(&self.app_state);
+        StartupService::load(&self.app_state).await;
+        tokio::spawn(ScheduleService::run(self.app_state.clone()));
+        tokio::spawn(TtlService::run(self.app_state.clone()));
+        tokio::spawn(FreshnessService::monitor(self.app_state.clone()));
+        tokio::spawn(BackupService::schedule(self.app_state.clone()));
+        tokio::spawn(StorageSyncService::run(self.app_state.clone()));
 
         // 创建通道用于异步通知
-        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<(String, String)>();
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<ConfigChange>();
 
         // 启动异步任务处理通知
         let app_state_for_notify = self.app_state.clone();
-        tokio::spawn(async move {
-            while let Some((file_name, config_str)) = rx.recv().await {
-                let notify_senders: Vec<tokio::sync::mpsc::UnboundedSender<String>> = {
-                    let app_state_guard = app_state_for_notify.lock().unwrap();
-                    app_state_guard
-                        .notify_map
-                        .iter()
-                        .filter(|(_, (watched_file, _))| *watched_file == file_name)
-                        .map(|(_, (_, sender))| sender.clone())
-                        .collect()
+        let mut shutdown = self.app_state.shutdown_receiver();
+        let notify_task = tokio::spawn(async move {
+            loop {
+                let change = tokio::select! {
+                    received = rx.recv() => match received {
+                        Some(received) => received,
+                        None => break,
+                    },
+                    _ = wait_for_shutdown(&mut shutdown) => break,
+                };
+                let (file_name, bytes) = (change.file.clone(), change.config.len());
+                // 监听器发现文件被删除时记为 delete
+                let action = match change.kind {
+                    ChangeKind::Deleted => AuditAction::Delete,
+                    ChangeKind::Created | ChangeKind::Updated => AuditAction::Reload,
                 };
+                StorageSyncService::notify(&app_state_for_notify, &change);
+                let sender_count = dispatch(&app_state_for_notify, change);
+                app_state_for_notify.audit(
+                    action,
+                    &file_name,
+                    "file_watcher",
+                    None,
+                );
                 self.log_manager
                     .log_info(format!(
-                        "config file: {} updated, notify {} clients, config: {}",
+                        "config file: {} updated, notify {} clients, {} bytes",
                         file_name,
-                        notify_senders.len(),
-                        config_str
+                        sender_count,
+                        bytes
                     ))
                     .await;
-                let sender_count = notify_senders.len();
-                for sender in notify_senders {
-                    if let Err(_) = sender.send(config_str.clone()) {
-                        debug!("send config to client failed, maybe client is closed");
-                    }
-                }
                 debug!("send {} config to {} clients", sender_count, file_name);
             }
+            self.log_manager
+                .log_info("server shutting down".to_string())
+                .await;
+            self.log_manager.flush().await;
         });
 
-        let app_state_for_watcher = self.app_state.clone();
-        let mut watcher = notify::RecommendedWatcher::new(
-            move |result: notify::Result<Event>| {
-                let event = match result {
-                    Ok(event) => event,
-                    Err(e) => {
-                        debug!("file watch error: {}", e);
-                        return;
-                    }
-                };
-
-                if event.kind.is_modify() && !event.paths.contains(&PathBuf::from("target")) {
-                    debug!("event: {:?}", event);
-                    if let Some(file_path) = event.paths.last() {
-                        if let Some(file_name_os) = file_path.file_name() {
-                            let file_name = file_name_os.to_string_lossy().to_string();
-                            debug!("file_name: {:?}", file_name);
-
-                            // 过滤临时文件和非配置文件
-                            if file_name.starts_with('.')
-                                || file_name.ends_with(".tmp")
-                                || file_name.ends_with("~")
-                            {
-                                debug!("ignore temporary file: {}", file_name);
-                                return;
-                            }
-
-                            // 只处理配置文件类型
-                            if !file_name.ends_with(".toml")
-                                && !file_name.ends_with(".json")
-                                && !file_name.ends_with(".yaml")
-                                && !file_name.ends_with(".yml")
-                            {
-                                debug!("ignore non-config file: {}", file_name);
-                                return;
-                            }
-
-                            match std::fs::read_to_string(file_path) {
-                                Ok(content) => {
-                                    match FormatConverterService::new(
-                                        ConfigPath::new(file_name.clone()).unwrap(),
-                                        content,
-                                    )
-                                    .validate_config()
-                                    {
-                                        Ok(mut validated_config) => {
-                                            match EnvOverrideService::apply_env_override(
-                                                &mut validated_config,
-                                            ) {
-                                                Ok(config_for_notify) => {
-                                                    let config_str = serde_json::to_string(
-                                                        &config_for_notify.to_serde_value(),
-                                                    )
-                                                    .unwrap_or_else(|_| "{}".to_string());
-
-                                                    app_state_for_watcher
-                                                        .lock()
-                                                        .unwrap()
-                                                        .config_map
-                                                        .insert(
-                                                            file_name.clone(),
-                                                            validated_config,
-                                                        );
-
-                                                    // 通过通道发送通知请求
-                                                    if let Err(_) =
-                                                        tx.send((file_name.clone(), config_str))
-                                                    {
-                                                        debug!("notify channel is closed");
-                                                    }
-
-                                                    info!("config watcher event: {:?}", event);
-                                                }
-                                                Err(e) => {
-                                                    debug!(
-                                                        "config release failed: {} - {}",
-                                                        file_name, e
-                                                    );
-                                                }
-                                            }
-                                        }
-                                        Err(e) => {
-                                            debug!("config validate failed: {} - {}", file_name, e);
-                                        }
-                                    }
-                                }
-                                Err(e) => {
-                                    debug!("read file failed: {} - {}", file_name, e);
-                                }
-                            }
-                        }
-                    }
-                }
-            },
-            notify::Config::default(),
-        )?;
-        watcher.watch(
-            Path::new(&self.app_state.lock().unwrap().config_path),
-            RecursiveMode::Recursive,
-        )?;
-        info!("config watcher init finished");
-        let listener = TcpListener::bind((self.host.clone(), self.port)).await?;
-        info!("server init finished");
+        let _watcher = WatchService::watch(&self.app_state, tx)?;
+        info!(
+            "server init finished, listening on {}",
+            ServeListener::describe(&self.host, self.port, self.uds.as_deref())
+        );
+        // 收到信号后停止接受新连接，通知 LISTEN 客户端并等待其断开，最后写完日志再退出
+        let signal = shutdown_signal();
+        tokio::pin!(signal);
         loop {
-            let (stream, _) = listener.accept().await?;
-            let app_state_cloned = self.app_state.clone();
-            tokio::spawn(async move {
-                let _ = handle_client(stream, app_state_cloned).await;
-            });
+            tokio::select! {
+                accepted = accept(&listener, &self.app_state) => accepted?,
+                _ = &mut signal => break,
+            }
+        }
+        drop(listener);
+        if let Some(uds) = &self.uds {
+            let _ = std::fs::remove_file(uds);
         }
+        self.app_state.begin_shutdown();
+        drain_subscribers(&self.app_state).await;
+        let _ = notify_task.await;
+        info!("TCP server shut down");
+        Ok(())
     }
 }
@@ -1,33 +1,77 @@
-use std::sync::{Arc, Mutex};
+use std::{collections::BTreeMap, sync::Arc};
 
 use axum::extract::{ws::{Message, WebSocket}, Query, State, WebSocketUpgrade};
-use chrono::Utc;
-use futures_util::{SinkExt, StreamExt};
+use futures_util::{SinkExt, StreamExt, stream::SplitSink};
 use tracing::{debug, info};
 
-use crate::{application::dtos::ws_query::WsQuery, domain::services::env_override::EnvOverrideService, shared::app_state::AppState};
+use crate::{application::{dtos::{capabilities::{Capabilities, STRUCTURED_CONFIG_VERSION}, ws_message::WsClientMessage, ws_query::WsQuery}, services::authorization_service::AuthorizationService}, domain::{entities::{access_policy::Permission, audit::AuditActor, key_subscriptions::{KeySubscriptions, SubscriptionChange}}, events::config_changed::ConfigUpdate, services::env_override::EnvOverrideService}, infrastructure::notification::subscriber_quota::QuotaDecision, shared::{app_state::{AppState, Subscriptions, wait_for_shutdown}, config::Interface}};
+
+// 接收端交给发送任务的消息：直接发送的文本，或需要在发送任务内处理的订阅请求
+enum Outgoing {
+    Text(String),
+    Request(WsClientMessage),
+}
 
 // 🔌 WebSocket 升级处理
 pub async fn handle_websocket_upgrade(
-    State(state): State<Arc<Mutex<AppState>>>,
+    State(state): State<Arc<AppState>>,
+    axum::Extension(actor): axum::Extension<AuditActor>,
     query: Result<Query<WsQuery>, axum::extract::rejection::QueryRejection>,
     ws: WebSocketUpgrade,
 ) -> axum::response::Response {
     match query {
         Ok(Query(query)) => {
-            info!("WebSocket upgrade request success - file: {}", query.file);
-            
-            // 检查文件是否存在于配置映射中
-            let file_exists = {
-                let app_state = state.lock().unwrap();
-                app_state.config_map.contains_key(&query.file)
-            };
-            
-            if !file_exists {
-                info!("warning: request file {} not in config map", query.file);
+            info!(
+                "WebSocket upgrade request success - file: {}",
+                query.file.as_deref().unwrap_or("-")
+            );
+
+            // 检查文件是否存在于配置映射中，未对 WebSocket 开放的命名空间直接拒绝
+            // 未指定文件时连接后通过 subscribe 消息订阅，逐个检查
+            if let Some(file) = &query.file {
+                let file_exists = state.config_map.contains_key(file);
+                let routed = state.route(Interface::Ws, file);
+                let authorized =
+                    AuthorizationService::authorize(&state, &actor, Permission::Read, file);
+                if routed.is_err() {
+                    return axum::response::Response::builder()
+                        .status(404)
+                        .body(format!("config file {} not found", file).into())
+                        .unwrap();
+                }
+                if let Err(e) = authorized {
+                    return axum::response::Response::builder()
+                        .status(403)
+                        .body(e.to_string().into())
+                        .unwrap();
+                }
+
+                if !file_exists {
+                    info!("warning: request file {} not in config map", file);
+                }
             }
-            
-            ws.on_upgrade(move |socket| handle_websocket_connection(socket, state, query.file))
+
+            let labels = match query.labels() {
+                Ok(labels) => labels,
+                Err(e) => {
+                    return axum::response::Response::builder()
+                        .status(400)
+                        .body(e.into())
+                        .unwrap();
+                }
+            };
+
+            ws.on_upgrade(move |socket| {
+                handle_websocket_connection(
+                    socket,
+                    state,
+                    actor,
+                    query.file,
+                    query.resume,
+                    query.protocol,
+                    labels,
+                )
+            })
         }
         Err(e) => {
             info!("WebSocket query parameters parse failed: {}", e);
@@ -42,107 +86,181 @@ pub async fn handle_websocket_upgrade(
 // 🔌 WebSocket 连接处理
 async fn handle_websocket_connection(
     mut socket: WebSocket,
-    state: Arc<Mutex<AppState>>,
-    file_name: String,
+    state: Arc<AppState>,
+    actor: AuditActor,
+    file_name: Option<String>,
+    resume: Option<String>,
+    protocol: Option<u32>,
+    labels: BTreeMap<String, String>,
 ) {
-    info!("new WebSocket connection, watching file: {}", file_name);
-
-    // 生成唯一的客户端ID
-    let client_id = format!(
-        "ws_{}_{}",
-        std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis(),
-        rand::random::<u32>()
+    info!(
+        "new WebSocket connection, watching file: {}",
+        file_name.as_deref().unwrap_or("-")
     );
 
-    // 发送初始配置
-    let initial_config = {
-        let app_state = state.lock().unwrap();
-        match app_state.config_map.get(&file_name) {
-            Some(config) => {
-                let mut config_clone = config.clone();
-                match EnvOverrideService::apply_env_override(&mut config_clone) {
-                    Ok(released_config) => serde_json::to_string(&serde_json::json!({
-                        "type": "initial",
-                        "file": file_name,
-                        "config": released_config.to_serde_value()
-                    }))
-                    .unwrap_or_else(|_| "{}".to_string()),
-                    Err(e) => serde_json::json!({
-                        "type": "error",
-                        "message": format!("Failed to process config: {}", e)
-                    })
-                    .to_string(),
-                }
+    // 协议版本不兼容时直接关闭连接
+    let capabilities = match Capabilities::negotiate(protocol) {
+        Ok(capabilities) => capabilities,
+        Err(e) => {
+            info!("WebSocket protocol negotiation failed: {}", e);
+            let message = serde_json::json!({ "type": "error", "message": e }).to_string();
+            let _ = socket.send(Message::Text(message.into())).await;
+            let _ = socket.send(Message::Close(None)).await;
+            return;
+        }
+    };
+
+    // 创建通知通道
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<ConfigUpdate>();
+
+    // 在订阅锁内完成会话恢复（或初始配置）与订阅注册，避免漏掉中间发布的更新
+    let clock = state.clock.clone();
+    let client_id = state.id_generator.next_id("ws");
+    let mut ws_subscriptions = KeySubscriptions::default();
+    let (resume_token, first_message) = {
+        let mut subscriptions = state.subscriptions.lock().unwrap();
+        subscriptions.add(&client_id, tx, "ws", clock.now());
+        subscriptions.label(&client_id, labels);
+        match &file_name {
+            Some(file_name) => {
+                let (token, message) = open_session(
+                    &state,
+                    &mut subscriptions,
+                    &client_id,
+                    file_name,
+                    resume.as_deref(),
+                    &capabilities,
+                );
+                // 序号在登记之前读取，加入金丝雀发布时推送的版本不会被过滤
+                let seq = subscriptions.event_log.latest_seq();
+                subscriptions.watch(&client_id, file_name);
+                ws_subscriptions.subscribe(file_name, None, seq, None);
+                (Some(token), message)
+            }
+            None => {
+                let message = serde_json::json!({
+                    "type": "connected",
+                    "capabilities": capabilities
+                })
+                .to_string();
+                (None, message)
             }
-            None => serde_json::json!({
-                "type": "error",
-                "message": format!("config file {} not found", file_name)
-            })
-            .to_string(),
         }
     };
 
-    // 发送初始配置
-    if let Err(e) = socket.send(Message::Text(initial_config.into())).await {
+    let decision = state.account_push(&client_id, first_message.len() as u64);
+    let sent = match decision {
+        QuotaDecision::Disconnect(reason) => {
+            info!("WebSocket client {} exceeded quota: {}", client_id, reason);
+            let _ = socket.send(Message::Text(quota_error(&reason).into())).await;
+            Err(reason)
+        }
+        _ => socket
+            .send(Message::Text(first_message.into()))
+            .await
+            .map_err(|e| e.to_string()),
+    };
+    if let Err(e) = sent {
         debug!("send initial config failed: {}", e);
+        state.remove_subscriber(&client_id);
+        if let Some(token) = &resume_token {
+            state.suspend_resume_session(token);
+        }
         return;
     }
 
-    // 创建通知通道
-    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<String>();
-
-    // 将 WebSocket 连接注册到通知系统
-    {
-        let mut app_state = state.lock().unwrap();
-        app_state
-            .notify_map
-            .insert(client_id.clone(), (file_name.clone(), tx));
+    if let Some(file_name) = &file_name {
+        state.mark_synced(&client_id, file_name);
+        info!("WebSocket client {} start watching file {}", client_id, file_name);
     }
 
-    info!("WebSocket client {} start watching file {}", client_id, file_name);
-
     // 分别处理发送和接收
     let (mut sender, mut receiver) = socket.split();
 
     // 创建一个通道用于从接收端向发送端传递消息
-    let (internal_tx, mut internal_rx) = tokio::sync::mpsc::unbounded_channel::<String>();
+    let (internal_tx, mut internal_rx) = tokio::sync::mpsc::unbounded_channel::<Outgoing>();
 
-    // 启动发送任务，处理配置更新推送和内部消息
+    // 启动发送任务，处理配置更新推送、订阅请求和内部消息
+    // 订阅请求与更新推送在同一个任务内按顺序处理，新订阅登记之后发布的更新不会被过滤掉
     let client_id_for_send = client_id.clone();
-    let file_name_for_send = file_name.clone();
-    let send_task = tokio::spawn(async move {
-        loop {
+    let state_for_send = state.clone();
+    let token_for_send = resume_token.clone();
+    let protocol_version = capabilities.protocol_version;
+    let mut shutdown = state.shutdown_receiver();
+    let mut shutdown_for_send = shutdown.clone();
+    let mut send_task = tokio::spawn(async move {
+        'send: loop {
+            // 先检查关闭：接收端注销订阅后 rx 也会结束，不能抢在关闭通知之前退出
             tokio::select! {
-                // 处理配置更新推送
-                config_data = rx.recv() => {
-                    if let Some(config_data) = config_data {
-                        let message = serde_json::json!({
-                            "type": "update",
-                            "file": file_name_for_send,
-                            "config": config_data,
-                            "timestamp": Utc::now().to_rfc3339()
-                        }).to_string();
-
-                        if let Err(e) = sender.send(Message::Text(message.into())).await {
-                            debug!("push config update failed: {}", e);
-                            break;
-                        }
-                        debug!("push config update to WebSocket client {} success", client_id_for_send);
-                    } else {
+                biased;
+                // 服务关闭：通知客户端后关闭连接
+                _ = wait_for_shutdown(&mut shutdown_for_send) => {
+                    let message = serde_json::json!({
+                        "type": "shutdown",
+                        "message": "server shutting down"
+                    })
+                    .to_string();
+                    let _ = sender.send(Message::Text(message.into())).await;
+                    let _ = sender.send(Message::Close(None)).await;
+                    break;
+                }
+                // 处理配置更新推送，键路径订阅只在子树变化时推送
+                update = rx.recv() => {
+                    let Some(update) = update else {
                         break;
+                    };
+                    for change in ws_subscriptions.changes(&update) {
+                        let message = update_message(&update, change, protocol_version);
+                        if !push(&mut sender, &state_for_send, &client_id_for_send, message).await {
+                            break 'send;
+                        }
+                    }
+                    if let Some(token) = &token_for_send {
+                        state_for_send.acknowledge(token, update.seq);
                     }
+                    state_for_send.mark_synced(&client_id_for_send, &update.file);
+                    debug!("push config update to WebSocket client {} success", client_id_for_send);
                 }
-                // 处理内部消息（如pong响应）
+                // 处理订阅请求和内部消息（如pong响应）
                 internal_msg = internal_rx.recv() => {
-                    if let Some(msg) = internal_msg {
-                        if let Err(e) = sender.send(Message::Text(msg.into())).await {
-                            debug!("send internal message failed: {}", e);
-                            break;
+                    let message = match internal_msg {
+                        Some(Outgoing::Text(message)) => message,
+                        Some(Outgoing::Request(WsClientMessage::Subscribe { file, key })) => {
+                            let subscribed = subscribe(
+                                &state_for_send,
+                                &actor,
+                                &client_id_for_send,
+                                &mut ws_subscriptions,
+                                &file,
+                                key.clone(),
+                            );
+                            match subscribed {
+                                Ok(message) => {
+                                    if !push(&mut sender, &state_for_send, &client_id_for_send, message).await {
+                                        break;
+                                    }
+                                    state_for_send.mark_synced(&client_id_for_send, &file);
+                                    info!("WebSocket client {} subscribed to {}", client_id_for_send, file);
+                                    continue;
+                                }
+                                Err(e) => subscription_error(&file, key.as_deref(), &e),
+                            }
                         }
-                    } else {
+                        Some(Outgoing::Request(WsClientMessage::Unsubscribe { file, key })) => {
+                            if ws_subscriptions.unsubscribe(&file, key.as_deref()) {
+                                state_for_send.subscriptions.lock().unwrap().unwatch(&client_id_for_send, &file);
+                            }
+                            serde_json::json!({
+                                "type": "unsubscribed",
+                                "file": file,
+                                "key": key
+                            })
+                            .to_string()
+                        }
+                        None => break,
+                    };
+                    if let Err(e) = sender.send(Message::Text(message.into())).await {
+                        debug!("send internal message failed: {}", e);
                         break;
                     }
                 }
@@ -150,24 +268,44 @@ async fn handle_websocket_connection(
         }
     });
 
-    // 处理客户端消息（保持连接活跃）
-    while let Some(msg) = receiver.next().await {
+    // 处理客户端消息（保持连接活跃），服务关闭时不再等待客户端
+    loop {
+        let msg = tokio::select! {
+            msg = receiver.next() => match msg {
+                Some(msg) => msg,
+                None => break,
+            },
+            _ = wait_for_shutdown(&mut shutdown) => break,
+        };
         match msg {
             Ok(Message::Text(text)) => {
                 let text_str = text.to_string();
                 debug!("receive WebSocket message: {}", text_str);
-                // 处理ping消息
-                if text_str == "ping" {
+                // 处理ping消息，其余文本按订阅请求解析
+                let outgoing = if text_str == "ping" {
                     let pong = serde_json::json!({
                         "type": "pong",
-                        "timestamp": Utc::now().to_rfc3339()
+                        "timestamp": clock.now().to_rfc3339()
                     })
                     .to_string();
-
-                    if let Err(_) = internal_tx.send(pong) {
-                        debug!("send pong to internal channel failed");
-                        break;
+                    Outgoing::Text(pong)
+                } else {
+                    match serde_json::from_str::<WsClientMessage>(&text_str) {
+                        Ok(request) => Outgoing::Request(request),
+                        Err(e) => {
+                            let message = serde_json::json!({
+                                "type": "error",
+                                "message": format!("invalid message: {}", e)
+                            })
+                            .to_string();
+                            Outgoing::Text(message)
+                        }
                     }
+                };
+
+                if internal_tx.send(outgoing).is_err() {
+                    debug!("send message to internal channel failed");
+                    break;
                 }
             }
             Ok(Message::Close(_)) => {
@@ -184,14 +322,233 @@ async fn handle_websocket_connection(
         }
     }
 
-    // 清理：从通知映射中移除该客户端
-    {
-        let mut app_state = state.lock().unwrap();
-        app_state.notify_map.remove(&client_id);
+    // 清理：从通知映射中移除该客户端，保留恢复令牌直到窗口过期
+    state.remove_subscriber(&client_id);
+    if let Some(token) = &resume_token {
+        state.suspend_resume_session(token);
     }
 
-    // 取消发送任务
-    send_task.abort();
+    // 关闭时等待发送任务送出关闭通知，否则直接取消
+    if *shutdown.borrow() {
+        let _ = (&mut send_task).await;
+    } else {
+        send_task.abort();
+    }
 
     info!("WebSocket client {} disconnected", client_id);
-}
\ No newline at end of file
+}
+
+// 恢复会话并返回错过的更新，令牌无效时签发新令牌并发送完整配置；返回 (恢复令牌, 首条消息)
+fn open_session(
+    state: &AppState,
+    subscriptions: &mut Subscriptions,
+    client_id: &str,
+    file_name: &str,
+    resume: Option<&str>,
+    capabilities: &Capabilities,
+) -> (String, String) {
+    let resumed = resume.and_then(|token| {
+        state
+            .resume_session(subscriptions, token, file_name)
+            .map(|missed| (token.to_string(), missed))
+    });
+    match resumed {
+        Some((token, missed)) => {
+            info!(
+                "WebSocket client {} resumed session, {} missed updates",
+                client_id,
+                missed.len()
+            );
+            let message = serde_json::json!({
+                "type": "resumed",
+                "file": file_name,
+                "resume_token": token,
+                "capabilities": capabilities,
+                "missed": missed.iter().map(|update| serde_json::json!({
+                    "seq": update.seq,
+                    "config": config_payload(&update.config, capabilities.protocol_version),
+                    "patch": update.patch,
+                    "hash": update.hash,
+                    "source": update.source,
+                    "timestamp": update.timestamp.to_rfc3339()
+                })).collect::<Vec<_>>()
+            })
+            .to_string();
+            (token, message)
+        }
+        None => {
+            if resume.is_some() {
+                debug!("resume token invalid or expired, send full config");
+            }
+            let token = state.open_resume_session(subscriptions, file_name);
+            let message = initial_message(state, subscriptions, file_name, &token, capabilities);
+            (token, message)
+        }
+    }
+}
+
+// 在订阅锁内登记文件并读取快照，之后发布的更新都会进入该连接的推送通道
+fn subscribe(
+    state: &AppState,
+    actor: &AuditActor,
+    client_id: &str,
+    ws_subscriptions: &mut KeySubscriptions,
+    file: &str,
+    key: Option<String>,
+) -> Result<String, String> {
+    state
+        .route(Interface::Ws, file)
+        .map_err(|_| format!("config file {} not found", file))?;
+    AuthorizationService::authorize(state, actor, Permission::Read, file)
+        .map_err(|e| e.to_string())?;
+
+    let (seq, mut config) = {
+        let mut subscriptions = state.subscriptions.lock().unwrap();
+        let config = state
+            .config_map
+            .get(file)
+            .ok_or_else(|| format!("config file {} not found", file))?;
+        let seq = subscriptions.event_log.latest_seq();
+        subscriptions.watch(client_id, file);
+        (seq, config)
+    };
+    let released = EnvOverrideService::apply_env_override(&mut config);
+    let released = match released {
+        Ok(released) => released,
+        Err(e) => {
+            // 快照失败时撤销登记，已有的其他订阅不受影响
+            if !ws_subscriptions.is_watching(file) {
+                state.subscriptions.lock().unwrap().unwatch(client_id, file);
+            }
+            return Err(format!("Failed to process config: {}", e));
+        }
+    };
+
+    let message = match &key {
+        Some(key) => {
+            let value = released.get(key);
+            let message = serde_json::json!({
+                "type": "subscribed",
+                "file": file,
+                "key": key,
+                "seq": seq,
+                "value": value.as_ref().map(|value| value.to_serde_value())
+            });
+            ws_subscriptions.subscribe(file, Some(key.clone()), seq, value);
+            message
+        }
+        None => {
+            let message = serde_json::json!({
+                "type": "subscribed",
+                "file": file,
+                "seq": seq,
+                "config": released.to_serde_value()
+            });
+            ws_subscriptions.subscribe(file, None, seq, None);
+            message
+        }
+    };
+    Ok(message.to_string())
+}
+
+fn update_message(
+    update: &ConfigUpdate,
+    change: SubscriptionChange,
+    protocol_version: u32,
+) -> String {
+    let mut message = change.message(update);
+    if let SubscriptionChange::File = change
+        && protocol_version < STRUCTURED_CONFIG_VERSION
+    {
+        message["config"] = config_payload(&update.config, protocol_version);
+    }
+    message.to_string()
+}
+
+// 协议 1 的客户端仍按 JSON 字符串接收 config
+fn config_payload(config: &str, protocol_version: u32) -> serde_json::Value {
+    if protocol_version >= STRUCTURED_CONFIG_VERSION {
+        serde_json::from_str(config).unwrap_or_default()
+    } else {
+        serde_json::Value::String(config.to_string())
+    }
+}
+
+// 带宽配额：限速时等待下一个窗口，超限断开时通知客户端；返回 false 表示连接需要关闭
+async fn push(
+    sender: &mut SplitSink<WebSocket, Message>,
+    state: &AppState,
+    client_id: &str,
+    message: String,
+) -> bool {
+    match state.account_push(client_id, message.len() as u64) {
+        QuotaDecision::Allow => {}
+        QuotaDecision::Delay(wait) => {
+            debug!("throttle WebSocket client {} for {:?}", client_id, wait);
+            tokio::time::sleep(wait).await;
+        }
+        QuotaDecision::Disconnect(reason) => {
+            info!("WebSocket client {} exceeded quota: {}", client_id, reason);
+            let _ = sender.send(Message::Text(quota_error(&reason).into())).await;
+            let _ = sender.send(Message::Close(None)).await;
+            return false;
+        }
+    }
+    if let Err(e) = sender.send(Message::Text(message.into())).await {
+        debug!("push config update failed: {}", e);
+        return false;
+    }
+    true
+}
+
+fn subscription_error(file: &str, key: Option<&str>, message: &str) -> String {
+    serde_json::json!({
+        "type": "error",
+        "file": file,
+        "key": key,
+        "message": message
+    })
+    .to_string()
+}
+
+fn quota_error(reason: &str) -> String {
+    serde_json::json!({
+        "type": "error",
+        "message": format!("subscriber quota exceeded: {}", reason)
+    })
+    .to_string()
+}
+
+fn initial_message(
+    app_state: &AppState,
+    subscriptions: &Subscriptions,
+    file_name: &str,
+    resume_token: &str,
+    capabilities: &Capabilities,
+) -> String {
+    match app_state.config_map.get(file_name) {
+        Some(mut config) => {
+            match EnvOverrideService::apply_env_override(&mut config) {
+                Ok(released_config) => serde_json::to_string(&serde_json::json!({
+                    "type": "initial",
+                    "file": file_name,
+                    "resume_token": resume_token,
+                    "capabilities": capabilities,
+                    "seq": subscriptions.event_log.latest_seq(),
+                    "config": released_config.to_serde_value()
+                }))
+                .unwrap_or_else(|_| "{}".to_string()),
+                Err(e) => serde_json::json!({
+                    "type": "error",
+                    "message": format!("Failed to process config: {}", e)
+                })
+                .to_string(),
+            }
+        }
+        None => serde_json::json!({
+            "type": "error",
+            "message": format!("config file {} not found", file_name)
+        })
+        .to_string(),
+    }
+}
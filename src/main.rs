@@ -1,121 +1,757 @@
 use anyhow::Result;
+use std::process::ExitCode;
 use clap::Parser;
-use colored::{Color, Colorize};
-use config_manager::domain::value_objects::config_path::ConfigPath;
+use std::sync::Arc;
 use config_manager::infrastructure::repositories::file_config_repository::FileConfigRepository;
-use config_manager::interfaces::cli::command::{Command, Subcommand};
+use config_manager::interfaces::cli::validation_report::ValidationReportFormat;
+use config_manager::interfaces::cli::command::{
+    BundleAction, Command, ConflictStrategy, ErrorFormat, FixturesAction, RefactorAction,
+    ReportFormat, Subcommand, TemplateAction,
+};
 
-use config_manager::application::services::configuration_service::ConfigurationService;
+use config_manager::application::services::bundle_service::BundleService;
+use config_manager::application::services::check_service::CheckService;
+use config_manager::application::services::config_test_service::ConfigTestService;
+use config_manager::application::services::configuration_service::{
+    ConfigurationService, TemplateConflict,
+};
+use config_manager::application::services::drift_service::DriftService;
+use config_manager::application::services::fixture_service::{FixtureOptions, FixtureService};
+use config_manager::application::services::preflight_service::{
+    PreflightOptions, PreflightService,
+};
 use config_manager::application::services::template_service::TemplateService;
-use config_manager::application::services::validation_service::ValidationService;
+use config_manager::application::services::validation_service::{
+    FileValidationReport, ValidationService,
+};
+use config_manager::domain::entities::configuration::ConfigValue;
 use config_manager::domain::entities::template::TemplateType;
-use config_manager::domain::services::config_validation::ConfigValidationService;
-use config_manager::domain::services::format_converter::FormatConverterService;
-use config_manager::infrastructure::logging::log_manager::{LogConfig, LogManager};
+use config_manager::domain::services::config_diff::ConfigDiffService;
+use config_manager::domain::services::config_formatter::FormatOptions;
+use config_manager::domain::value_objects::key_pattern::KeyPattern;
+use config_manager::domain::value_objects::namespace::Namespace;
+use config_manager::infrastructure::logging::log_manager::{LogConfig, LogManager, LogRotation};
 use config_manager::infrastructure::repositories::memory_template_repository::MemoryTemplateRepository;
+use config_manager::infrastructure::watchers::config_watcher::WatchOptions;
+use config_manager::interfaces::cli::browser::ConfigBrowser;
+use config_manager::interfaces::cli::shell::ConfigShell;
+use config_manager::interfaces::cli::watch::watch_files;
 use config_manager::interfaces::http::server::HttpServer;
 use config_manager::interfaces::tcp::server::TcpServer;
-use config_manager::shared::utils::{init_tracing, read_file};
+use config_manager::domain::repositories::configuration_repository::ConfigurationRepository;
+use config_manager::infrastructure::repositories::http_config_repository::HttpConfigRepository;
+use config_manager::infrastructure::repositories::embedded_config_repository::EmbeddedConfigRepository;
+use config_manager::infrastructure::repositories::source_config_repository::SourceConfigRepository;
+use config_manager::infrastructure::sources::config_source_factory::ConfigSourceFactory;
+use config_manager::domain::services::embedded_config::EmbeddedSpec;
+use config_manager::shared::error::{
+    BundleError, ConfigError, ErrorCategory, TemplateError, ValidationError,
+};
+use config_manager::shared::utils::{default_log_file, expand_config_paths, init_tracing, resolve_server};
 use tracing::debug;
 
 #[tokio::main]
-async fn main() -> Result<()> {
-    init_tracing();
+async fn main() -> ExitCode {
+    let command = Command::parse();
+    let error_format = command.error_format;
+    if let Err(e) = init_tracing(command.log.log_format, command.log.log_filter.as_deref()) {
+        return report_error(&anyhow::Error::from(e), error_format);
+    }
+
+    match run(command).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => report_error(&e, error_format),
+    }
+}
+
+async fn run(command: Command) -> Result<()> {
     let log_manager = LogManager::new(LogConfig {
-        file: "test.log".to_string(),
+        file: command.log.log_file.clone().unwrap_or_else(default_log_file),
         level: "info".to_string(),
+        format: command.log.log_format,
+        rotation: LogRotation {
+            max_size: command.log.log_max_size,
+            period: command.log.log_rotate,
+            keep: command.log.log_keep,
+        },
     })
-    .await;
-
-    let command = Command::parse();
+    .await
+    .map_err(ConfigError::IoError)?;
 
     match command.subcommand {
         Subcommand::Validate {
-            file,
+            files,
             validate_file,
+            watch,
+            strict,
+            apply_defaults,
+            output,
+            report,
+            report_file,
         } => {
-            if validate_file.is_empty() {
-                debug!("validate: {}", file);
-                let content = read_file(&file)?;
-                let config = FormatConverterService::new(ConfigPath::new(file).unwrap(), content)
-                    .validate_config()?;
+            if apply_defaults
+                && (watch
+                    || command.server.is_some()
+                    || command.embedded.is_some()
+                    || files.len() != 1
+                    || !ConfigSourceFactory::is_local(&files[0]))
+            {
+                anyhow::bail!("--apply-defaults requires a single local config file");
+            }
+            if apply_defaults && validate_file.is_empty() {
+                anyhow::bail!("--apply-defaults requires a rules file (-v)");
+            }
+            let validation = if validate_file.is_empty() {
+                None
+            } else {
+                debug!("validate rules: {}", validate_file);
+                Some(Arc::new(ValidationService::load_validation_file(
+                    &validate_file,
+                )?))
+            };
+
+            if watch {
+                if command.server.is_some()
+                    || command.embedded.is_some()
+                    || !files.iter().all(|file| ConfigSourceFactory::is_local(file))
+                {
+                    anyhow::bail!("--watch is only supported for local config files");
+                }
+                let files = expand_config_paths(&files)?;
+                if files.is_empty() {
+                    return Err(ConfigError::NoFilesMatched.into());
+                }
+                watch_files(&files, async || {
+                    let reports =
+                        ValidationService::validate_files(files.clone(), validation.clone()).await;
+                    let reports = apply_strict(reports, strict);
+                    if let Err(e) = emit_validation_reports(&reports, report, report_file.as_deref()) {
+                        eprintln!("Error: {}", e);
+                    }
+                })
+                .await?;
+                return Ok(());
+            }
+
+            let reports = match (&command.server, &command.embedded) {
+                (None, None) => {
+                    let files = expand_config_paths(&files)?;
+                    if files.is_empty() {
+                        return Err(ConfigError::NoFilesMatched.into());
+                    }
+                    ValidationService::validate_files(files, validation.clone()).await
+                }
+                _ => {
+                    let repository = config_repository(&command.server, &command.embedded, "")?;
+                    ValidationService::validate_from_repository(
+                        repository.as_ref(),
+                        files.clone(),
+                        validation.as_deref(),
+                    )
+                    .await
+                }
+            };
+            let reports = apply_strict(reports, strict);
+            emit_validation_reports(&reports, report, report_file.as_deref())?;
+
+            let failed = reports.iter().filter(|r| !r.is_valid()).count();
+            if failed > 0 {
+                return Err(ConfigError::ValidationFailed {
+                    failed,
+                    total: reports.len(),
+                }
+                .into());
+            }
+
+            if let (true, Some(validation), Some(output)) = (apply_defaults, &validation, &output) {
+                let applied =
+                    ValidationService::write_with_defaults(&files[0], validation, output)?;
                 println!(
-                    "config validate success, file format is {}",
-                    (config.config_type).to_string().color(Color::Green)
+                    "{} defaults applied ({}), written to {}",
+                    applied.len(),
+                    applied.join(", "),
+                    output
                 );
-            } else {
-                debug!("validate: {}", validate_file);
-                let validation_content = read_file(&validate_file)?;
-                let validation_config = FormatConverterService::new(
-                    ConfigPath::new(validate_file).unwrap(),
-                    validation_content,
-                )
-                .validate_config()?;
-                let validation = ValidationService::get_validation_by_config(&validation_config)?;
-                let content = read_file(&file)?;
-                let config =
-                    FormatConverterService::new(ConfigPath::new(file.clone()).unwrap(), content)
-                        .validate_config()?;
-                let config_type = config.config_type.clone();
-                debug!("config: {:?}", config);
-                let validation_result =
-                    ConfigValidationService::validate_with_rules(validation, config);
-                if !validation_result.is_valid {
-                    println!(
-                        "{} config validate failed: {:?}",
-                        file.color(Color::Red),
-                        validation_result.errors
-                    );
+            }
+        }
+        Subcommand::Diff {
+            left,
+            right,
+            format,
+            output,
+        } => {
+            let left_config = config_repository(&command.server, &command.embedded, &left)?
+                .get(&left)
+                .await?;
+            let right_config = config_repository(&command.server, &command.embedded, &right)?
+                .get(&right)
+                .await?;
+            let diff = ConfigDiffService::diff(&left, &left_config, &right, &right_config);
+            let rendered = format.renderer().render(&diff);
+            match output {
+                Some(output) => {
+                    std::fs::write(&output, rendered).map_err(ConfigError::IoError)?;
+                    println!("diff written to {}", output);
+                }
+                None => println!("{}", rendered),
+            }
+        }
+        Subcommand::Check {
+            files,
+            project,
+            strict,
+            format,
+        } => {
+            let (root, settings) = CheckService::load_project(project.as_deref())?;
+            let options = CheckService::options(&root, &settings, strict)?;
+            let files = CheckService::select_files(&root, &settings, &files)?;
+            let reports = CheckService::check_files(&files, &options);
+            match format {
+                ReportFormat::Text => CheckService::print_summary(&reports),
+                ReportFormat::Json => {
+                    println!("{}", serde_json::to_string_pretty(&CheckService::to_json(&reports))?)
+                }
+            }
+            let failed = reports.iter().filter(|r| !r.is_passed()).count();
+            if failed > 0 {
+                return Err(ConfigError::CheckFailed {
+                    failed,
+                    total: reports.len(),
+                }
+                .into());
+            }
+        }
+        Subcommand::Test { files, format } => {
+            let root = ConfigTestService::root();
+            let files = ConfigTestService::test_files(&root, &files)?;
+            if files.is_empty() {
+                return Err(ConfigError::NoFilesMatched.into());
+            }
+            let reports = ConfigTestService::run_files(&root, &files);
+            match format {
+                ReportFormat::Text => ConfigTestService::print_summary(&reports),
+                ReportFormat::Json => println!(
+                    "{}",
+                    serde_json::to_string_pretty(&ConfigTestService::to_json(&reports))?
+                ),
+            }
+            let failed: usize = reports.iter().map(|r| r.failed()).sum();
+            if failed > 0 {
+                return Err(ConfigError::TestsFailed {
+                    failed,
+                    total: reports.iter().map(|r| r.total()).sum(),
+                }
+                .into());
+            }
+        }
+        Subcommand::Drift {
+            files,
+            schema,
+            format,
+        } => {
+            let schema = DriftService::load_schema(&schema)?;
+            let files = expand_config_paths(&files)?;
+            if files.is_empty() {
+                return Err(ConfigError::NoFilesMatched.into());
+            }
+            let reports = DriftService::check_files(&schema, &files);
+            match format {
+                ReportFormat::Text => DriftService::print_summary(&reports),
+                ReportFormat::Json => println!("{}", serde_json::to_string_pretty(&reports)?),
+            }
+            let drifted = reports.iter().filter(|r| !r.is_clean()).count();
+            if drifted > 0 {
+                return Err(ConfigError::SchemaDrift {
+                    drifted,
+                    total: reports.len(),
+                }
+                .into());
+            }
+        }
+        Subcommand::Show {
+            file,
+            get,
+            deepth,
+            watch,
+            doc_index,
+        } => {
+            let service = ConfigurationService::new(config_repository(&command.server, &command.embedded, &file)?);
+            let show = async || {
+                if get.is_empty() {
+                    service
+                        .display_configuration(file.clone(), deepth, doc_index)
+                        .await
                 } else {
-                    println!(
-                        "{} config validate success, file format is {}",
-                        file.color(Color::Green),
-                        config_type.to_string().color(Color::Green)
-                    );
+                    service
+                        .get_configuration_value(file.clone(), get.clone(), doc_index)
+                        .await
+                }
+            };
+            if watch {
+                if command.server.is_some() || !ConfigSourceFactory::is_local(&file) {
+                    anyhow::bail!("--watch is only supported for local files");
                 }
+                watch_files(std::slice::from_ref(&file), async || {
+                    if let Err(e) = show().await {
+                        eprintln!("Error: {}", e);
+                    }
+                })
+                .await?;
+            } else {
+                show().await?;
             }
         }
-        Subcommand::Show { file, get, deepth } => {
-            if get.is_empty() {
-                ConfigurationService::new(Box::new(FileConfigRepository::new(file.clone())))
-                    .display_configuration(file, deepth)
-                    .await?;
+        Subcommand::Stats { file } => {
+            ConfigurationService::new(config_repository(&command.server, &command.embedded, &file)?)
+                .display_statistics(file)
+                .await?;
+        }
+        Subcommand::Browse { file } => {
+            let config = config_repository(&command.server, &command.embedded, &file)?
+                .get(&file)
+                .await?;
+            ConfigBrowser::new(config, file).run()?;
+        }
+        Subcommand::Get { file, key, doc_index } => {
+            ConfigurationService::new(config_repository(&command.server, &command.embedded, &file)?)
+                .get_configuration_value(file, key, doc_index)
+                .await?;
+        }
+        Subcommand::History { file, version } => {
+            ConfigurationService::new(config_repository(&command.server, &command.embedded, &file)?)
+                .display_history(file, version)
+                .await?;
+        }
+        Subcommand::Set { file, key, value } => {
+            debug!("set: {} {}={}", file, key, value);
+            ConfigurationService::new(config_repository(&command.server, &command.embedded, &file)?)
+                .set_configuration_value(file, key, value)
+                .await?;
+        }
+        Subcommand::Migrate {
+            files,
+            validate_file,
+            dry_run,
+        } => {
+            let validation = ValidationService::load_validation_file(&validate_file)?;
+            let files = match (&command.server, &command.embedded) {
+                (None, None) => expand_config_paths(&files)?,
+                _ => files,
+            };
+            if files.is_empty() {
+                return Err(ConfigError::NoFilesMatched.into());
+            }
+            let mut migrated = 0;
+            for file in files {
+                debug!("migrate: {}", file);
+                migrated += ConfigurationService::new(config_repository(
+                    &command.server,
+                    &command.embedded,
+                    &file,
+                )?)
+                .migrate_deprecated_keys(file, &validation.deprecated_fields, dry_run)
+                .await?;
+            }
+            if dry_run {
+                println!("\n{} keys would be migrated (dry run)", migrated);
             } else {
-                ConfigurationService::new(Box::new(FileConfigRepository::new(file.clone())))
-                    .get_configuration_value(file, get)
-                    .await?;
+                println!("\n{} keys migrated", migrated);
             }
         }
-        Subcommand::Convert { input, output } => {
+        Subcommand::Refactor { action } => match action {
+            RefactorAction::Rename {
+                from,
+                to,
+                file,
+                update_references,
+            } => {
+                debug!("rename: {} -> {} in {}", from, to, file);
+                ConfigurationService::new(config_repository(&command.server, &command.embedded, &file)?)
+                    .rename_key(file, from, to, update_references)
+                    .await?;
+            }
+        },
+        Subcommand::Convert {
+            input,
+            output,
+            only,
+            exclude,
+            doc_index,
+        } => {
             debug!("convert: {} -> {}", input, output);
-            ConfigurationService::new(Box::new(FileConfigRepository::new(input.clone())))
-                .convert_configuration(input, output)
+            let only = KeyPattern::parse_list(&only)?;
+            let exclude = KeyPattern::parse_list(&exclude)?;
+            ConfigurationService::new(Box::new(FileConfigRepository::current_dir()))
+                .convert_configuration(input, output, &only, &exclude, doc_index)
+                .await?;
+        }
+        Subcommand::Format {
+            file,
+            sort_keys,
+            indent,
+        } => {
+            debug!("format: {}", file);
+            ConfigurationService::new(Box::new(FileConfigRepository::current_dir()))
+                .format_configuration(file, FormatOptions { sort_keys, indent })
                 .await?;
         }
-        Subcommand::Template { template, format } => {
+        Subcommand::Template {
+            action:
+                Some(TemplateAction::Upgrade {
+                    files,
+                    dry_run,
+                    on_conflict,
+                }),
+            ..
+        } => {
+            let files = match (&command.server, &command.embedded) {
+                (None, None) => expand_config_paths(&files)?,
+                _ => files,
+            };
+            if files.is_empty() {
+                return Err(ConfigError::NoFilesMatched.into());
+            }
+            let mut upgraded = 0;
+            for file in files {
+                debug!("template upgrade: {}", file);
+                upgraded += ConfigurationService::new(config_repository(
+                    &command.server,
+                    &command.embedded,
+                    &file,
+                )?)
+                .upgrade_template(file, dry_run, |conflict| resolve_conflict(conflict, on_conflict))
+                .await?;
+            }
+            if dry_run {
+                println!("\n{} keys would be upgraded (dry run)", upgraded);
+            } else {
+                println!("\n{} keys upgraded", upgraded);
+            }
+        }
+        Subcommand::Template {
+            template, format, ..
+        } => {
+            let template = template.unwrap_or_default();
             debug!("template: {} {}", template, format);
             TemplateService::new(Box::new(MemoryTemplateRepository::new()))
                 .write_template(TemplateType::from(template), format)
                 .await?;
         }
+        Subcommand::Fixtures { action } => match action {
+            FixturesAction::Generate {
+                files,
+                depth,
+                format,
+                output,
+                seed,
+            } => {
+                let written = FixtureService::generate(&FixtureOptions {
+                    output: output.clone(),
+                    files,
+                    depth,
+                    format,
+                    seed,
+                })?;
+                println!("✅ generated {} fixture files in {}", written.len(), output);
+            }
+        },
+        Subcommand::Bundle { action } => match action {
+            BundleAction::Create {
+                configs,
+                schemas,
+                policies,
+                output,
+                key_file,
+            } => {
+                let key = key_file.as_deref().map(BundleService::read_key_file).transpose()?;
+                let manifest = BundleService::create(
+                    &configs,
+                    schemas.as_deref(),
+                    policies.as_deref(),
+                    &output,
+                    key.as_deref(),
+                )?;
+                println!(
+                    "✅ bundle created: {} ({} files, {})",
+                    output,
+                    manifest.entries.len(),
+                    if key.is_some() { "signed" } else { "unsigned" }
+                );
+            }
+            BundleAction::Verify {
+                bundle,
+                key_file,
+                allow_unsigned,
+            } => {
+                let key = key_file.as_deref().map(BundleService::read_key_file).transpose()?;
+                let verification = BundleService::verify(&bundle, key.as_deref(), allow_unsigned)?;
+                ValidationService::print_summary(&verification.reports);
+                if !verification.is_valid() {
+                    return Err(ConfigError::ValidationFailed {
+                        failed: verification.reports.iter().filter(|r| !r.is_valid()).count(),
+                        total: verification.reports.len(),
+                    }
+                    .into());
+                }
+                println!(
+                    "✅ bundle verified: {} ({} files, {})",
+                    bundle,
+                    verification.manifest.entries.len(),
+                    if verification.signed { "signature ok" } else { "signature not checked" }
+                );
+            }
+            BundleAction::Apply {
+                bundle,
+                target,
+                key_file,
+                allow_unsigned,
+            } => {
+                let key = key_file.as_deref().map(BundleService::read_key_file).transpose()?;
+                let verification = BundleService::verify(&bundle, key.as_deref(), allow_unsigned)?;
+                ValidationService::print_summary(&verification.reports);
+                if !verification.is_valid() {
+                    return Err(ConfigError::ValidationFailed {
+                        failed: verification.reports.iter().filter(|r| !r.is_valid()).count(),
+                        total: verification.reports.len(),
+                    }
+                    .into());
+                }
+                let written = BundleService::apply(&verification, &target)?;
+                println!("✅ bundle applied: {} files written to {}", written, target);
+            }
+            BundleAction::Export {
+                config_path,
+                namespace,
+                output,
+                key_file,
+            } => {
+                let key = key_file.as_deref().map(BundleService::read_key_file).transpose()?;
+                let namespace = namespace.map(Namespace::new).transpose()?;
+                let manifest = BundleService::export(
+                    &config_path,
+                    namespace.as_ref(),
+                    std::fs::File::create(&output)?,
+                    key.as_deref(),
+                )?;
+                println!(
+                    "✅ bundle exported: {} ({} configs, {} files, {})",
+                    output,
+                    manifest.configs().count(),
+                    manifest.entries.len(),
+                    if key.is_some() { "signed" } else { "unsigned" }
+                );
+            }
+            BundleAction::Import {
+                bundle,
+                config_path,
+                namespace,
+                key_file,
+                allow_unsigned,
+            } => {
+                let key = key_file.as_deref().map(BundleService::read_key_file).transpose()?;
+                let verification = BundleService::verify(&bundle, key.as_deref(), allow_unsigned)?;
+                ValidationService::print_summary(&verification.reports);
+                if !verification.is_valid() {
+                    return Err(ConfigError::ValidationFailed {
+                        failed: verification.reports.iter().filter(|r| !r.is_valid()).count(),
+                        total: verification.reports.len(),
+                    }
+                    .into());
+                }
+                let namespace = namespace
+                    .or_else(|| verification.manifest.namespace.clone())
+                    .map(Namespace::new)
+                    .transpose()?;
+                let imported = BundleService::import(&verification, &config_path, namespace.as_ref())?;
+                println!("✅ bundle imported: {} configs written to {}", imported.len(), config_path);
+            }
+        },
+        Subcommand::Shell { host, port } => {
+            ConfigShell::new(host, port).run()?;
+        }
+        Subcommand::Preflight {
+            port,
+            host,
+            config_path,
+            tls_cert,
+            tls_expiry_days,
+        } => {
+            let checks = PreflightService::run(&PreflightOptions {
+                host,
+                port,
+                config_path,
+                tls_cert,
+                tls_expiry_days,
+            });
+            PreflightService::print_summary(&checks);
+            let failed = checks.iter().filter(|c| !c.passed).count();
+            if failed > 0 {
+                return Err(ConfigError::PreflightFailed { failed }.into());
+            }
+        }
         Subcommand::Serve {
             port,
             host,
             config_path,
             http,
+            resume_window,
+            max_bytes_per_sec,
+            max_payload,
+            quota_action,
+            webhook,
+            webhook_retries,
+            settings,
+            load,
+            freshness_slo,
+            bind,
+            validate_file,
+            require_if_match,
+            history_limit,
+            policy,
+            limits,
         } => {
+            use config_manager::infrastructure::audit::audit_sink_factory::AuditSinkFactory;
+            use config_manager::shared::config::{AuditSettings, RateLimitSettings, ServerSettings};
+            use config_manager::infrastructure::backup::backup_store_factory::BackupStoreFactory;
+            use config_manager::infrastructure::notification::subscriber_quota::BandwidthQuota;
+            use config_manager::infrastructure::notification::{
+                notification_sink_factory::NotificationSinkFactory, webhook::WebhookNotifier,
+            };
+            use config_manager::infrastructure::privilege::privilege_drop::PrivilegeDrop;
+            use config_manager::application::services::authorization_service::AuthorizationService;
+            use config_manager::application::services::settings_service::SettingsService;
+            use config_manager::application::services::storage_sync_service::{
+                StorageSync, StorageSyncService,
+            };
+            use config_manager::infrastructure::repositories::config_store_factory::ConfigStoreFactory;
             use config_manager::shared::app_state::AppState;
-            use std::sync::{Arc, Mutex};
+            use config_manager::shared::config::StorageBackend;
 
-            let app_state = AppState::new(port, host.clone(), config_path);
-            let app_state = Arc::new(Mutex::new(app_state));
+            let app_state = AppState::new(port, host.clone(), config_path)
+                .with_resume_window(chrono::Duration::seconds(resume_window as i64))
+                .with_memory_budget(load.memory_budget)
+                .with_startup_workers(load.startup_workers)
+                .with_lazy_load(load.lazy_load)
+                .with_watch(WatchOptions {
+                    mode: load.watch_mode,
+                    poll_interval: std::time::Duration::from_millis(load.watch_poll_interval),
+                    debounce: std::time::Duration::from_millis(load.watch_debounce),
+                })
+                .with_freshness_slo(chrono::Duration::seconds(freshness_slo as i64))
+                .with_require_if_match(require_if_match)
+                .with_history_limit(history_limit)
+                .with_max_body_size(limits.max_body_size)
+                .with_tcp_keepalive(limits.tcp_heartbeat, limits.tcp_idle_timeout)
+                .with_privilege_drop(PrivilegeDrop {
+                    user: bind.user,
+                    group: bind.group,
+                    chroot: bind.chroot,
+                })
+                .with_quota(BandwidthQuota {
+                    bytes_per_sec: max_bytes_per_sec,
+                    max_payload,
+                    action: quota_action,
+                });
+            let app_state = webhook.into_iter().fold(app_state, |app_state, url| {
+                let webhook = WebhookNotifier::new(url, webhook_retries);
+                app_state.with_notification_sink(Arc::new(webhook))
+            });
+            let server_settings = match &settings {
+                Some(path) => ServerSettings::load(path)?,
+                None => ServerSettings::default(),
+            };
+            let mut host_paths = Vec::new();
+            if settings.is_some() {
+                host_paths.push("--settings hot reload");
+            }
+            if matches!(server_settings.audit, Some(AuditSettings::Jsonl { .. } | AuditSettings::Csv { .. })) {
+                host_paths.push("a file audit sink");
+            }
+            let rotation = &log_manager.config.rotation;
+            if rotation.max_size.is_some() || rotation.period.is_some() {
+                host_paths.push("log rotation");
+            }
+            app_state.privilege_drop.reject_host_paths(&host_paths)?;
+            let app_state = match &server_settings.audit {
+                Some(audit) => app_state.with_audit_sink(AuditSinkFactory::create(audit)),
+                None => app_state,
+            };
+            let app_state = server_settings
+                .notifications
+                .iter()
+                .fold(app_state, |app_state, sink| {
+                    app_state.with_notification_sink(NotificationSinkFactory::create(sink))
+                });
+            let app_state = match &server_settings.backup {
+                Some(backup) => app_state.with_backup(BackupStoreFactory::plan(backup)?),
+                None => app_state,
+            };
+            let storage_poll_interval = std::time::Duration::from_millis(load.storage_poll_interval);
+            // 数据库同时保存历史版本，设置文件未配置审计 sink 时审计记录也写入数据库
+            let app_state = match ConfigStoreFactory::create(&StorageBackend::parse(&load.storage)?, history_limit)? {
+                None => app_state,
+                Some(backends) => {
+                    let storage = StorageSync::new(backends.store, storage_poll_interval, load.storage_read_only);
+                    let app_state = app_state.with_storage(storage);
+                    let app_state = match backends.history {
+                        Some(history) => app_state.with_history_store(history),
+                        None => app_state,
+                    };
+                    match (backends.audit, &server_settings.audit) {
+                        (Some(audit), None) => app_state.with_audit_sink(audit),
+                        _ => app_state,
+                    }
+                }
+            };
+            let rate_limit = match limits.rate_limit {
+                Some(rate) if rate <= 0.0 => anyhow::bail!("--rate-limit must be positive"),
+                Some(rate) => Some(RateLimitSettings {
+                    rate,
+                    burst: limits.rate_burst,
+                }),
+                None => None,
+            };
+            let live = SettingsService::live(&server_settings, rate_limit, None)?;
+            SettingsService::apply_log_level(&server_settings)?;
+            // 命名空间子目录不存在时创建，文件监听器需要监听这些目录
+            for namespace in live.namespaces.iter() {
+                let dir = std::path::Path::new(&app_state.config_path()).join(namespace.as_str());
+                tokio::fs::create_dir_all(dir).await.map_err(ConfigError::IoError)?;
+            }
+            let app_state = app_state
+                .with_settings(live)
+                .with_cors(server_settings.cors.clone())
+                .with_security_headers(server_settings.security_headers.clone());
+            let app_state = match &policy {
+                Some(path) => app_state.with_policy(AuthorizationService::load_policy(path)?),
+                None => app_state,
+            };
+            let app_state = match &validate_file {
+                Some(path) => app_state.with_validation(ValidationService::load_validation_file(path)?),
+                None => app_state,
+            };
+            let app_state = Arc::new(app_state);
+            // 配置保存在 bucket 中时先拉取到配置目录，启动加载看到的就是 bucket 中的配置
+            if app_state.storage.is_some() {
+                tokio::fs::create_dir_all(app_state.config_path())
+                    .await
+                    .map_err(ConfigError::IoError)?;
+                StorageSyncService::pull(&app_state).await?;
+            }
+            // 设置文件修改后热更新，服务运行期间保持监听
+            let _settings_watcher = match &settings {
+                Some(path) => Some(SettingsService::watch(&app_state, path, rate_limit)?),
+                None => None,
+            };
             if http {
                 // HTTP 模式需要先创建 AppState
                 HttpServer::new(port, host, app_state, log_manager)
+                    .with_uds(bind.uds)
                     .start()
                     .await?;
             } else {
                 TcpServer::new(port, host, app_state, log_manager)
+                    .with_uds(bind.uds)
                     .start()
                     .await?;
             }
@@ -123,3 +759,148 @@ async fn main() -> Result<()> {
     }
     Ok(())
 }
+
+// 用户修改过的键：按 --on-conflict 处理，prompt 时在终端询问
+fn resolve_conflict(conflict: &TemplateConflict, strategy: ConflictStrategy) -> bool {
+    match strategy {
+        ConflictStrategy::Keep => false,
+        ConflictStrategy::Take => true,
+        ConflictStrategy::Prompt => {
+            let show = |value: &Option<ConfigValue>| match value {
+                Some(value) => value.to_serde_value().to_string(),
+                None => "(unset)".to_string(),
+            };
+            print!(
+                "{}: yours {}, template {} -> {}. Take template value? [y/N] ",
+                conflict.key,
+                show(&conflict.current),
+                show(&conflict.old_default),
+                show(&conflict.new_default)
+            );
+            let _ = std::io::Write::flush(&mut std::io::stdout());
+            let mut answer = String::new();
+            std::io::stdin().read_line(&mut answer).is_ok()
+                && matches!(answer.trim().to_lowercase().as_str(), "y" | "yes")
+        }
+    }
+}
+
+// 未指定 --report 时输出文本摘要；指定 --report-file 时写入报告并同时输出摘要
+fn emit_validation_reports(
+    reports: &[FileValidationReport],
+    format: Option<ValidationReportFormat>,
+    report_file: Option<&str>,
+) -> Result<()> {
+    let Some(format) = format else {
+        ValidationService::print_summary(reports);
+        return Ok(());
+    };
+    let rendered = format.renderer().render(reports);
+    match report_file {
+        Some(path) => {
+            std::fs::write(path, rendered).map_err(ConfigError::IoError)?;
+            ValidationService::print_summary(reports);
+            println!("report written to {}", path);
+        }
+        None => println!("{}", rendered),
+    }
+    Ok(())
+}
+
+fn apply_strict(reports: Vec<FileValidationReport>, strict: bool) -> Vec<FileValidationReport> {
+    if strict {
+        reports.into_iter().map(FileValidationReport::strict).collect()
+    } else {
+        reports
+    }
+}
+
+// 远程服务启用 API key 鉴权时，CLI 从该环境变量读取密钥
+const API_KEY_ENV: &str = "CONFIG_MANAGER_API_KEY";
+
+// 根据 --server 选择本地文件仓储或远程 HTTP 仓储，--embedded 时读写宿主文件中的配置块；
+// 文件参数为 URL 或 "-" 时从对应来源只读加载
+fn config_repository(
+    server: &Option<String>,
+    embedded: &Option<String>,
+    file: &str,
+) -> Result<Box<dyn ConfigurationRepository>> {
+    match (server, embedded) {
+        (Some(_), Some(_)) => anyhow::bail!("--embedded is only supported for local files"),
+        (Some(server), None) => Ok(Box::new(
+            HttpConfigRepository::new(resolve_server(server)?)
+                .with_api_key(std::env::var(API_KEY_ENV).ok()),
+        )),
+        (None, Some(spec)) => Ok(Box::new(EmbeddedConfigRepository::new(EmbeddedSpec::parse(
+            spec,
+        )?))),
+        (None, None) if !ConfigSourceFactory::is_local(file) => Ok(Box::new(
+            SourceConfigRepository::new(ConfigSourceFactory::create(file)),
+        )),
+        (None, None) => Ok(Box::new(FileConfigRepository::current_dir())),
+    }
+}
+
+// 退出码约定：0 成功，1 内部错误，2 参数错误（clap），3 解析错误，4 校验失败，
+// 5 IO 错误，6 未找到，7 远程请求失败，8 完整性校验失败
+fn exit_code(category: ErrorCategory) -> u8 {
+    match category {
+        ErrorCategory::Internal => 1,
+        ErrorCategory::Parse => 3,
+        ErrorCategory::Validation => 4,
+        ErrorCategory::Io => 5,
+        ErrorCategory::NotFound => 6,
+        ErrorCategory::Remote => 7,
+        ErrorCategory::Integrity => 8,
+    }
+}
+
+fn error_category(error: &anyhow::Error) -> ErrorCategory {
+    if let Some(e) = error.downcast_ref::<ConfigError>() {
+        e.category()
+    } else if let Some(e) = error.downcast_ref::<ValidationError>() {
+        e.category()
+    } else if let Some(e) = error.downcast_ref::<TemplateError>() {
+        e.category()
+    } else if let Some(e) = error.downcast_ref::<BundleError>() {
+        e.category()
+    } else if error.downcast_ref::<std::io::Error>().is_some() {
+        ErrorCategory::Io
+    } else {
+        ErrorCategory::Internal
+    }
+}
+
+fn error_kind(error: &anyhow::Error) -> &'static str {
+    if let Some(e) = error.downcast_ref::<ConfigError>() {
+        e.kind()
+    } else if let Some(e) = error.downcast_ref::<ValidationError>() {
+        e.kind()
+    } else if let Some(e) = error.downcast_ref::<TemplateError>() {
+        e.kind()
+    } else if let Some(e) = error.downcast_ref::<BundleError>() {
+        e.kind()
+    } else {
+        "Error"
+    }
+}
+
+fn report_error(error: &anyhow::Error, format: ErrorFormat) -> ExitCode {
+    let category = error_category(error);
+    let code = exit_code(category);
+    match format {
+        ErrorFormat::Text => eprintln!("Error: {:#}", error),
+        ErrorFormat::Json => {
+            let output = serde_json::json!({
+                "error": {
+                    "kind": error_kind(error),
+                    "category": category,
+                    "code": code,
+                    "message": format!("{:#}", error),
+                }
+            });
+            eprintln!("{}", output);
+        }
+    }
+    ExitCode::from(code)
+}
@@ -2,17 +2,202 @@ use axum::{
     body::Body,
     response::{IntoResponse, Response},
 };
+use chrono::{DateTime, Duration, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use tokio::sync::mpsc::UnboundedSender;
-use crate::domain::entities::configuration::ConfigMap;
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    path::Path,
+    sync::{Arc, Mutex, OnceLock, RwLock},
+};
+use tokio::sync::{mpsc::UnboundedSender, watch};
+use crate::{
+    application::{
+        dtos::{
+            config_status::{ConfigLoadState, ConfigStatus, LoadFailure},
+            config_summary::ConfigSummary,
+            rebuild_status::RebuildStatus,
+            startup_status::StartupStatus,
+        },
+        services::{startup_service::DEFAULT_STARTUP_WORKERS, storage_sync_service::StorageSync},
+    },
+    domain::{
+        entities::{
+            access_policy::AccessPolicy,
+            audit::{AuditAction, AuditRecord},
+            backup::BackupPlan,
+            config_lock::ConfigLocks,
+            config_map::{ConfigMap, ConfigMetadata},
+            config_ttl::{ConfigTtl, ConfigTtls},
+            configuration::Config,
+            freshness::{
+                DEFAULT_FRESHNESS_SLO_SECS, FreshnessReport, FreshnessTracker, StaleConsumer,
+            },
+            rollout::Rollout,
+            scheduled_change::ScheduledChanges,
+            validation_rule::Validation,
+        },
+        repositories::{
+            audit_sink::AuditSink, configuration_repository::ConfigurationRepository,
+            history_store::HistoryStore, notification_sink::NotificationSink,
+        },
+        events::{
+            config_changed::{ChangeKind, ConfigChange, ConfigUpdate},
+            event_log::EventLog,
+        },
+        services::{config_patch::ConfigPatchService, env_override::EnvOverrideService},
+        value_objects::namespace::{Namespace, covers},
+    },
+    infrastructure::{
+        history::file_history_store::FileHistoryStore,
+        repositories::file_config_repository::FileConfigRepository,
+        limits::rate_limiter::RateLimiter,
+        notification::{
+            dead_letter::{DeadLetter, DeadLetterQueue, DeliveryTarget},
+            subscriber_quota::{BandwidthQuota, QuotaAction, QuotaDecision, SubscriberUsage},
+        },
+        privilege::privilege_drop::PrivilegeDrop,
+        watchers::config_watcher::{WatchOptions, WatcherHealth},
+    },
+    shared::{
+        clock::{Clock, IdGenerator, RandomIdGenerator, SystemClock},
+        config::{ApiKeys, CorsSettings, Interface, NamespaceRouting, SecurityHeaderSettings},
+        error::ConfigError,
+        utils::is_valid_config_name,
+    },
+};
 
+pub const DEFAULT_RESUME_WINDOW_SECS: u64 = 300;
+pub const DEFAULT_HISTORY_LIMIT: usize = 20;
+pub const DEFAULT_MAX_BODY_SIZE: usize = 2 * 1024 * 1024;
+pub const DEFAULT_TCP_HEARTBEAT_SECS: u64 = 30;
+pub const DEFAULT_TCP_IDLE_TIMEOUT_SECS: u64 = 90;
+pub const DEFAULT_WATCH_DEBOUNCE_MS: u64 = 200;
+// 关闭时等待订阅者收到关闭通知的最长时间
+pub const SHUTDOWN_DRAIN_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+// 服务端共享状态，以 Arc<AppState> 在各接口间共享：设置项在启动前构造完成后只读，
+// 运行期变化的部分各自加锁，读取配置不会与推送、审计等操作争用同一把锁
 pub struct AppState {
+    // 内部按分片加锁，读取之间互不阻塞
     pub config_map: ConfigMap,
     pub port: u16,
     pub host: String,
-    pub config_path: String,
+    // 降权时可能改为 chroot 内的路径，通过 config_path() 读取
+    config_path: RwLock<String>,
+    // 订阅者、事件日志与恢复会话
+    pub subscriptions: Mutex<Subscriptions>,
+    pub clock: Arc<dyn Clock>,
+    // 服务启动时间，用于计算运行时长
+    pub started_at: DateTime<Utc>,
+    pub id_generator: Arc<dyn IdGenerator>,
+    pub resume_window: Duration,
+    pub quota: BandwidthQuota,
+    // webhook 与消息总线，按启动参数和设置文件创建
+    pub notification_sinks: Vec<Arc<dyn NotificationSink>>,
+    // 每个 sink（包括订阅者）一个投递队列，首次分发时启动对应的投递任务
+    pub sink_queues: OnceLock<Vec<UnboundedSender<ConfigUpdate>>>,
+    pub dead_letters: Mutex<DeadLetterQueue>,
+    // 设置文件中配置了 backup 时按计划备份配置目录
+    pub backup: Option<Arc<BackupPlan>>,
+    // --storage 指定存储时配置目录与存储双向同步
+    pub storage: Option<Arc<StorageSync>>,
+    // 历史版本的存放位置，未指定时保存在配置目录的 .history 中
+    pub history_store: Option<Arc<dyn HistoryStore>>,
+    pub audit_sink: Option<Arc<dyn AuditSink>>,
+    pub rebuild_status: Mutex<RebuildStatus>,
+    pub startup_workers: usize,
+    // 启动时只登记配置文件，首次访问时才解析
+    pub lazy_load: bool,
+    // 文件监听方式与合并同一文件连续修改的窗口
+    pub watch: WatchOptions,
+    pub startup_status: Mutex<StartupStatus>,
+    // 按配置名称记录最近一次加载失败，成功加载或文件删除后清除
+    load_failures: Mutex<HashMap<String, LoadFailure>>,
+    pub freshness: Mutex<FreshnessTracker>,
+    pub freshness_slo: Duration,
+    // 路由、命名空间、API key 与限流，设置文件修改时整体替换
+    settings: RwLock<Arc<LiveSettings>>,
+    pub cors: Option<CorsSettings>,
+    pub security_headers: SecurityHeaderSettings,
+    pub privilege_drop: PrivilegeDrop,
+    // 服务端加载配置时使用的校验规则（目前用于填充默认值）
+    pub validation: Option<Arc<Validation>>,
+    // 按配置名授权的访问策略，未加载时不限制
+    pub policy: Option<Arc<AccessPolicy>>,
+    // HTTP 写请求是否必须携带 If-Match
+    pub require_if_match: bool,
+    // 每个配置保留的历史版本数，0 表示不记录
+    pub history_limit: usize,
+    pub max_body_size: usize,
+    // TCP 推送连接的心跳间隔和空闲超时，None 表示不启用
+    pub tcp_heartbeat: Option<std::time::Duration>,
+    pub tcp_idle_timeout: Option<std::time::Duration>,
+    // 服务启动监听后设置，供健康检查读取
+    pub watcher_health: OnceLock<Arc<WatcherHealth>>,
+    // 收到 SIGINT/SIGTERM 后置为 true，推送任务据此通知订阅者并断开
+    pub shutdown: watch::Sender<bool>,
+    // 串行化配置修改，If-Match 检查、写盘和更新缓存在同一临界区内完成；读取不经过这把锁
+    writes: tokio::sync::Mutex<()>,
+    // 接口已写盘、等待文件监听器重新加载的修改，按文件名索引
+    pending_writes: Mutex<HashMap<String, PendingWrite>>,
+    // 配置锁与全局冻结，期间接口写入和文件的外部修改都被拒绝
+    pub locks: Mutex<ConfigLocks>,
+    // 等待生效的修改；提交新的修改时唤醒调度任务
+    pub schedules: Mutex<ScheduledChanges>,
+    pub schedules_changed: tokio::sync::Notify,
+    // 配置与键的过期规则；规则变化时唤醒过期任务
+    pub ttls: Mutex<ConfigTtls>,
+    pub ttls_changed: tokio::sync::Notify,
+}
+
+// 接口写入的来源、写入前的版本和写入的内容
+// 懒加载模式下写入前的版本可能已被淘汰，此时 previous 为 None 而 existed 为 true
+struct PendingWrite {
+    source: String,
+    previous: Option<Config>,
+    existed: bool,
+    written: serde_json::Value,
+}
+
+// 运行期间可以替换的服务端设置，来自 --settings 文件和命令行的限流参数
+#[derive(Debug, Clone, Default)]
+pub struct LiveSettings {
+    pub routing: NamespaceRouting,
+    // 声明的命名空间，其中的配置名称带 "<namespace>/" 前缀
+    pub namespaces: Vec<Namespace>,
+    pub api_keys: ApiKeys,
+    // 按客户端限流，未配置时不限制
+    pub rate_limiter: Option<Arc<RateLimiter>>,
+}
+
+// 订阅相关状态共用一把锁：订阅注册与事件发布互斥，新订阅者不会漏掉并发发布的更新
+#[derive(Default)]
+pub struct Subscriptions {
     pub notify_map: NotifyMap,
+    pub subscriber_usage: HashMap<String, SubscriberUsage>,
+    pub event_log: EventLog,
+    pub resume_sessions: HashMap<String, ResumeSession>,
+    // 按文件名索引的进行中的金丝雀发布
+    pub rollouts: HashMap<String, Rollout>,
+    // 被新版本结束的金丝雀发布，按结束它的事件序号索引，订阅者投递时取出
+    pub superseded: HashMap<u64, Rollout>,
+}
+
+// 一个订阅者：订阅的文件、订阅时声明的标签（金丝雀按标签选择）与通知发送器
+pub struct Subscriber {
+    pub files: BTreeSet<String>,
+    pub labels: BTreeMap<String, String>,
+    pub sender: UnboundedSender<ConfigUpdate>,
+    // 注册时事件日志的最新序号，此前的更新已包含在订阅快照中
+    pub since: u64,
+}
+
+// WebSocket 会话恢复信息：记录最后送达的事件序号
+#[derive(Debug, Clone)]
+pub struct ResumeSession {
+    pub file: String,
+    pub last_seq: u64,
+    pub disconnected_at: Option<DateTime<Utc>>,
 }
 
 impl AppState {
@@ -21,14 +206,897 @@ impl AppState {
             config_map: ConfigMap::new(),
             port,
             host,
-            config_path,
-            notify_map: NotifyMap::new(),
+            config_path: RwLock::new(config_path),
+            subscriptions: Mutex::new(Subscriptions::default()),
+            clock: Arc::new(SystemClock),
+            started_at: SystemClock.now(),
+            id_generator: Arc::new(RandomIdGenerator),
+            resume_window: Duration::seconds(DEFAULT_RESUME_WINDOW_SECS as i64),
+            quota: BandwidthQuota {
+                bytes_per_sec: None,
+                max_payload: None,
+                action: QuotaAction::Throttle,
+            },
+            notification_sinks: Vec::new(),
+            sink_queues: OnceLock::new(),
+            backup: None,
+            storage: None,
+            history_store: None,
+            dead_letters: Mutex::new(DeadLetterQueue::default()),
+            audit_sink: None,
+            rebuild_status: Mutex::new(RebuildStatus::default()),
+            startup_workers: DEFAULT_STARTUP_WORKERS,
+            lazy_load: false,
+            watch: WatchOptions {
+                debounce: std::time::Duration::from_millis(DEFAULT_WATCH_DEBOUNCE_MS),
+                ..WatchOptions::default()
+            },
+            startup_status: Mutex::new(StartupStatus::default()),
+            load_failures: Mutex::new(HashMap::new()),
+            freshness: Mutex::new(FreshnessTracker::default()),
+            freshness_slo: Duration::seconds(DEFAULT_FRESHNESS_SLO_SECS as i64),
+            settings: RwLock::new(Arc::new(LiveSettings::default())),
+            cors: None,
+            security_headers: SecurityHeaderSettings::default(),
+            privilege_drop: PrivilegeDrop::default(),
+            validation: None,
+            policy: None,
+            require_if_match: false,
+            history_limit: DEFAULT_HISTORY_LIMIT,
+            max_body_size: DEFAULT_MAX_BODY_SIZE,
+            tcp_heartbeat: Some(std::time::Duration::from_secs(DEFAULT_TCP_HEARTBEAT_SECS)),
+            tcp_idle_timeout: Some(std::time::Duration::from_secs(DEFAULT_TCP_IDLE_TIMEOUT_SECS)),
+            watcher_health: OnceLock::new(),
+            shutdown: watch::Sender::new(false),
+            writes: tokio::sync::Mutex::new(()),
+            pending_writes: Mutex::new(HashMap::new()),
+            locks: Mutex::new(ConfigLocks::default()),
+            schedules: Mutex::new(ScheduledChanges::default()),
+            schedules_changed: tokio::sync::Notify::new(),
+            ttls: Mutex::new(ConfigTtls::default()),
+            ttls_changed: tokio::sync::Notify::new(),
         }
     }
+
+    pub fn config_path(&self) -> String {
+        self.config_path.read().unwrap().clone()
+    }
+
+    // 当前生效的设置；请求处理期间持有同一份快照，不受并发替换影响
+    pub fn settings(&self) -> Arc<LiveSettings> {
+        self.settings.read().unwrap().clone()
+    }
+
+    pub fn replace_settings(&self, settings: LiveSettings) {
+        *self.settings.write().unwrap() = Arc::new(settings);
+    }
+
+    // 配置被锁定或全局冻结时返回原因
+    pub fn check_unlocked(&self, key: &str) -> Result<(), ConfigError> {
+        self.locks.lock().unwrap().check(key, self.clock.now())
+    }
+
+    // 修改配置前获取，持有期间可以 await 写盘
+    pub async fn lock_writes(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.writes.lock().await
+    }
+
+    // 同 lock_writes，供文件监听器等运行时之外的线程使用
+    pub fn blocking_lock_writes(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.writes.blocking_lock()
+    }
+
+    pub fn with_clock(mut self, clock: Arc<dyn Clock>) -> Self {
+        self.started_at = clock.now();
+        self.clock = clock;
+        self
+    }
+
+    pub fn with_id_generator(mut self, id_generator: Arc<dyn IdGenerator>) -> Self {
+        self.id_generator = id_generator;
+        self
+    }
+
+    pub fn with_resume_window(mut self, resume_window: Duration) -> Self {
+        self.resume_window = resume_window;
+        self
+    }
+
+    pub fn with_memory_budget(mut self, memory_budget: Option<usize>) -> Self {
+        self.config_map = std::mem::take(&mut self.config_map).with_memory_budget(memory_budget);
+        self
+    }
+
+    pub fn with_startup_workers(mut self, startup_workers: usize) -> Self {
+        self.startup_workers = startup_workers;
+        self
+    }
+
+    pub fn with_lazy_load(mut self, lazy_load: bool) -> Self {
+        self.lazy_load = lazy_load;
+        self
+    }
+
+    pub fn with_watch(mut self, watch: WatchOptions) -> Self {
+        self.watch = watch;
+        self
+    }
+
+    pub fn with_freshness_slo(mut self, freshness_slo: Duration) -> Self {
+        self.freshness_slo = freshness_slo;
+        self
+    }
+
+    pub fn with_settings(self, settings: LiveSettings) -> Self {
+        self.replace_settings(settings);
+        self
+    }
+
+    pub fn with_cors(mut self, cors: Option<CorsSettings>) -> Self {
+        self.cors = cors;
+        self
+    }
+
+    pub fn with_security_headers(mut self, security_headers: SecurityHeaderSettings) -> Self {
+        self.security_headers = security_headers;
+        self
+    }
+
+    pub fn with_privilege_drop(mut self, privilege_drop: PrivilegeDrop) -> Self {
+        self.privilege_drop = privilege_drop;
+        self
+    }
+
+    pub fn with_require_if_match(mut self, require_if_match: bool) -> Self {
+        self.require_if_match = require_if_match;
+        self
+    }
+
+    pub fn with_history_limit(mut self, history_limit: usize) -> Self {
+        self.history_limit = history_limit;
+        self
+    }
+
+    pub fn with_max_body_size(mut self, max_body_size: usize) -> Self {
+        self.max_body_size = max_body_size;
+        self
+    }
+
+    // 秒数为 0 表示不启用
+    pub fn with_tcp_keepalive(mut self, heartbeat_secs: u64, idle_timeout_secs: u64) -> Self {
+        let enabled = |secs| (secs > 0).then(|| std::time::Duration::from_secs(secs));
+        self.tcp_heartbeat = enabled(heartbeat_secs);
+        self.tcp_idle_timeout = enabled(idle_timeout_secs);
+        self
+    }
+
+    pub fn with_validation(mut self, validation: Validation) -> Self {
+        self.validation = Some(Arc::new(validation));
+        self
+    }
+
+    pub fn with_policy(mut self, policy: AccessPolicy) -> Self {
+        self.policy = Some(Arc::new(policy));
+        self
+    }
+
+    pub fn with_quota(mut self, quota: BandwidthQuota) -> Self {
+        self.quota = quota;
+        self
+    }
+
+    pub fn with_backup(mut self, plan: BackupPlan) -> Self {
+        self.backup = Some(Arc::new(plan));
+        self
+    }
+
+    pub fn with_storage(mut self, storage: StorageSync) -> Self {
+        self.storage = Some(Arc::new(storage));
+        self
+    }
+
+    pub fn with_history_store(mut self, history_store: Arc<dyn HistoryStore>) -> Self {
+        self.history_store = Some(history_store);
+        self
+    }
+
+    pub fn with_notification_sink(mut self, sink: Arc<dyn NotificationSink>) -> Self {
+        self.notification_sinks.push(sink);
+        self
+    }
+
+    pub fn with_audit_sink(mut self, audit_sink: Arc<dyn AuditSink>) -> Self {
+        self.audit_sink = Some(audit_sink);
+        self
+    }
+
+    // 不带差异的审计记录，例如文件监听器的重新加载；客户端的修改由 AuditService 记录
+    pub fn audit(&self, action: AuditAction, config: &str, source: &str, detail: Option<String>) {
+        self.write_audit(AuditRecord {
+            id: self.id_generator.next_id("audit"),
+            timestamp: self.clock.now(),
+            action,
+            config: config.to_string(),
+            source: source.to_string(),
+            actor: None,
+            detail,
+            changes: Vec::new(),
+        });
+    }
+
+    // 异步写入审计记录，写入失败只记录日志，不影响请求本身
+    pub fn write_audit(&self, record: AuditRecord) {
+        let Some(sink) = self.audit_sink.clone() else {
+            return;
+        };
+        tokio::spawn(async move {
+            if let Err(e) = sink.write(&record).await {
+                tracing::warn!("write audit record {} failed: {}", record.id, e);
+            }
+        });
+    }
+
+    pub fn dead_letter(
+        &self,
+        target: DeliveryTarget,
+        update: ConfigUpdate,
+        attempts: u32,
+        last_error: String,
+    ) {
+        let letter = DeadLetter {
+            id: self.id_generator.next_id("dl"),
+            target,
+            update,
+            attempts,
+            last_error,
+            failed_at: self.clock.now(),
+        };
+        self.dead_letters.lock().unwrap().push(letter);
+    }
+
+    pub fn add_subscriber(
+        &self,
+        client_id: &str,
+        file: &str,
+        sender: UnboundedSender<ConfigUpdate>,
+        transport: &str,
+    ) {
+        let now = self.clock.now();
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        subscriptions.add(client_id, sender, transport, now);
+        subscriptions.watch(client_id, file);
+    }
+
+    pub fn remove_subscriber(&self, client_id: &str) {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        subscriptions.notify_map.remove(client_id);
+        subscriptions.subscriber_usage.remove(client_id);
+        for rollout in subscriptions.rollouts.values_mut() {
+            rollout.clients.remove(client_id);
+        }
+    }
+
+    pub fn subscriber(&self, client_id: &str) -> Option<UnboundedSender<ConfigUpdate>> {
+        let subscriptions = self.subscriptions.lock().unwrap();
+        subscriptions.notify_map.get(client_id).map(|subscriber| subscriber.sender.clone())
+    }
+
+    pub fn rollout(&self, file: &str) -> Option<Rollout> {
+        self.subscriptions.lock().unwrap().rollouts.get(file).cloned()
+    }
+
+    // 开始金丝雀发布：把新内容推给当前订阅该文件且被目标选中的订阅者，返回这些订阅者
+    pub fn start_rollout(&self, mut rollout: Rollout) -> Result<Rollout, ConfigError> {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        if subscriptions.rollouts.contains_key(&rollout.file) {
+            return Err(ConfigError::RolloutInProgress(rollout.file));
+        }
+        let targets: Vec<(String, UnboundedSender<ConfigUpdate>)> = subscriptions
+            .notify_map
+            .iter()
+            .filter(|(client_id, subscriber)| {
+                subscriber.files.iter().any(|target| covers(target, &rollout.file))
+                    && rollout.target.matches(client_id, &subscriber.labels)
+            })
+            .map(|(client_id, subscriber)| (client_id.clone(), subscriber.sender.clone()))
+            .collect();
+        let update = rollout.canary_update(subscriptions.event_log.next_seq());
+        for (client_id, sender) in targets {
+            if sender.send(update.clone()).is_ok() {
+                rollout.clients.insert(client_id);
+            }
+        }
+        subscriptions.rollouts.insert(rollout.file.clone(), rollout.clone());
+        Ok(rollout)
+    }
+
+    // 结束金丝雀发布，仍在线的金丝雀订阅者收到稳定版本
+    pub fn rollback_rollout(&self, file: &str) -> Result<Rollout, ConfigError> {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let rollout = subscriptions
+            .rollouts
+            .remove(file)
+            .ok_or_else(|| ConfigError::RolloutNotFound(file.to_string()))?;
+        let update = rollout.rollback_update(subscriptions.event_log.next_seq(), self.clock.now());
+        for client_id in rollout.clients.iter() {
+            if let Some(subscriber) = subscriptions.notify_map.get(client_id) {
+                let _ = subscriber.sender.send(update.clone());
+            }
+        }
+        Ok(rollout)
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.subscriptions.lock().unwrap().notify_map.len()
+    }
+
+    pub fn begin_shutdown(&self) {
+        self.shutdown.send_replace(true);
+    }
+
+    pub fn shutdown_receiver(&self) -> watch::Receiver<bool> {
+        self.shutdown.subscribe()
+    }
+
+    // 端口绑定之后调用；chroot 后配置目录改为 chroot 内的路径
+    pub fn drop_privileges(&self) -> Result<(), ConfigError> {
+        let mut current = self.config_path.write().unwrap();
+        if let Some(config_path) = self.privilege_drop.apply(&current)? {
+            *current = config_path;
+        }
+        Ok(())
+    }
+
+    // 所有接口访问配置前统一检查配置名称和命名空间路由；以 "/" 结尾的名称表示整个命名空间。
+    // 名称不合法（例如包含 ".."）时不能映射到配置目录下的文件
+    pub fn route(&self, interface: Interface, file: &str) -> Result<(), ConfigError> {
+        if !is_valid_config_name(file.strip_suffix('/').unwrap_or(file)) {
+            return Err(ConfigError::InvalidConfigPath(file.to_string()));
+        }
+        if self.settings().routing.allows(interface, file) {
+            Ok(())
+        } else {
+            tracing::debug!("config {} is not exposed over {}", file, interface);
+            Err(ConfigError::NamespaceNotExposed {
+                file: file.to_string(),
+                interface: interface.to_string(),
+            })
+        }
+    }
+
+    pub fn namespace(&self, name: &str) -> Option<Namespace> {
+        let settings = self.settings();
+        settings.namespaces.iter().find(|namespace| namespace.as_str() == name).cloned()
+    }
+
+    // 配置目录下文件对应的配置名称（相对路径），隐藏目录中的文件返回 None
+    pub fn config_key(&self, file_path: &Path) -> Option<String> {
+        FileConfigRepository::new(self.config_path()).config_name(file_path)
+    }
+
+    // 接口写入都落到配置目录，由文件监听器推送；--storage 时再由同步任务写回存储
+    pub fn repository(&self) -> Arc<dyn ConfigurationRepository> {
+        Arc::new(FileConfigRepository::new(self.config_path()))
+    }
+
+    pub fn history(&self) -> Arc<dyn HistoryStore> {
+        match &self.history_store {
+            Some(history_store) => history_store.clone(),
+            None => Arc::new(FileHistoryStore::new(&self.config_path(), self.history_limit)),
+        }
+    }
+
+    pub fn record_load_failure(&self, key: &str, error: String) {
+        let failure = LoadFailure {
+            error,
+            failed_at: self.clock.now(),
+        };
+        self.load_failures.lock().unwrap().insert(key.to_string(), failure);
+    }
+
+    pub fn clear_load_failure(&self, key: &str) {
+        self.load_failures.lock().unwrap().remove(key);
+    }
+
+    // 配置的加载状态，既不在缓存中也没有加载失败记录时返回 None
+    pub fn config_status(&self, key: &str) -> Option<ConfigStatus> {
+        let failure = self.load_failures.lock().unwrap().get(key).cloned();
+        let state = match (self.config_map.contains_key(key), &failure) {
+            (false, None) => return None,
+            (false, Some(_)) => ConfigLoadState::Failed,
+            (true, Some(_)) => ConfigLoadState::Stale,
+            (true, None) if self.config_map.is_cold(key) => ConfigLoadState::Unloaded,
+            (true, None) => ConfigLoadState::Loaded,
+        };
+        Some(ConfigStatus {
+            name: key.to_string(),
+            state,
+            failure,
+        })
+    }
+
+    // 当前接口可见的配置列表
+    pub fn visible_configs(&self, interface: Interface) -> Vec<String> {
+        let settings = self.settings();
+        self.config_map
+            .keys()
+            .into_iter()
+            .filter(|key| settings.routing.allows(interface, key))
+            .collect()
+    }
+
+    // 带元数据的可见配置列表，按名称排序
+    pub fn config_summaries(&self, interface: Interface) -> Vec<ConfigSummary> {
+        let mut summaries: Vec<ConfigSummary> = self
+            .visible_configs(interface)
+            .into_iter()
+            .filter_map(|name| {
+                let metadata = self.config_map.metadata(&name)?;
+                Some(ConfigSummary { name, metadata })
+            })
+            .collect();
+        summaries.sort_by(|a, b| a.name.cmp(&b.name));
+        summaries
+    }
+
+    // 写入缓存并记录元数据，返回此前是否已存在；大小和修改时间取自配置目录下的文件，
+    // 文件不存在时（例如事务尚未落盘）按序列化结果和当前时间估计
+    pub fn store_config(&self, key: String, config: Config) -> bool {
+        let file = std::path::Path::new(&self.config_path()).join(&key);
+        let (size, modified_at) = match std::fs::metadata(&file) {
+            Ok(meta) => (
+                meta.len(),
+                meta.modified()
+                    .map(DateTime::<Utc>::from)
+                    .unwrap_or_else(|_| self.clock.now()),
+            ),
+            Err(_) => (
+                config
+                    .serialize_to(&config.config_type)
+                    .map(|content| content.len() as u64)
+                    .unwrap_or_default(),
+                self.clock.now(),
+            ),
+        };
+        let metadata = ConfigMetadata::new(&config, size, modified_at);
+        self.config_map.insert(key, config, metadata)
+    }
+
+    // 接口写入：更新缓存并记下来源和写入前的版本，文件监听器重新加载该文件时据此生成推送
+    // 监听器处理前的多次写入保留最早的版本，订阅者收到的补丁基于他们手中的版本
+    pub fn store_write(&self, key: String, config: Config, source: &str) -> bool {
+        let written = config.to_serde_value();
+        {
+            let mut pending_writes = self.pending_writes.lock().unwrap();
+            match pending_writes.get_mut(&key) {
+                Some(pending) => {
+                    pending.source = source.to_string();
+                    pending.written = written;
+                }
+                None => {
+                    let pending = PendingWrite {
+                        source: source.to_string(),
+                        previous: self.config_map.peek(&key),
+                        existed: self.config_map.contains_key(&key),
+                        written,
+                    };
+                    pending_writes.insert(key.clone(), pending);
+                }
+            }
+        }
+        self.store_config(key, config)
+    }
+
+    // 接口写入了该文件、还在等待文件监听器重新加载
+    pub fn has_pending_write(&self, key: &str) -> bool {
+        self.pending_writes.lock().unwrap().contains_key(key)
+    }
+
+    // 文件监听器加载到新版本：内容与接口写入的一致时沿用接口记录的来源，否则视为外部修改
+    // 没有待推送的接口写入且内容哈希未变（例如只改了空白或注释）时返回 None，不产生推送
+    pub fn reload_config(
+        &self,
+        key: String,
+        config: Config,
+        config_str: String,
+    ) -> Option<ConfigChange> {
+        let pending = self.pending_writes.lock().unwrap().remove(&key);
+        // 懒加载模式下只比较最后一次加载时的哈希，不从文件重新加载旧版本（文件已是新内容）
+        if pending.is_none()
+            && self.config_map.cached_hash(&key) == Some(ConfigMap::content_hash(&config))
+        {
+            self.store_config(key, config);
+            return None;
+        }
+        let (source, previous, existed) = match pending {
+            Some(pending) if pending.written == config.to_serde_value() => {
+                (pending.source, pending.previous, pending.existed)
+            }
+            Some(pending) => ("file_watcher".to_string(), pending.previous, pending.existed),
+            None => (
+                "file_watcher".to_string(),
+                self.config_map.peek(&key),
+                self.config_map.contains_key(&key),
+            ),
+        };
+        let evicted = previous.is_none() && existed;
+        self.store_config(key.clone(), config);
+        let mut change = self.config_change(key, previous, config_str, &source);
+        // 旧版本已被淘汰时无法计算差异，推送替换整个配置的补丁
+        if evicted {
+            let new = serde_json::from_str(&change.config).unwrap_or_default();
+            change.kind = ChangeKind::Updated;
+            change.patch = ConfigPatchService::diff(&serde_json::Value::Null, &new);
+        }
+        Some(change)
+    }
+
+    // 文件监听器发现配置文件已被删除或移走：移出缓存并丢弃待推送的接口写入，
+    // 缓存中本来就没有时返回 None；懒加载模式下未加载的配置没有旧版本，补丁替换整个配置
+    pub fn remove_config(&self, key: &str) -> Option<ConfigChange> {
+        self.pending_writes.lock().unwrap().remove(key);
+        self.clear_load_failure(key);
+        if !self.config_map.contains_key(key) {
+            return None;
+        }
+        let old = match self.config_map.remove(key) {
+            Some(mut previous) => EnvOverrideService::apply_env_override(&mut previous)
+                .map(|released| released.to_serde_value())
+                .unwrap_or_else(|_| previous.to_serde_value()),
+            None => serde_json::Value::Null,
+        };
+        Some(ConfigChange {
+            file: key.to_string(),
+            kind: ChangeKind::Deleted,
+            config: "null".to_string(),
+            patch: ConfigPatchService::diff(&old, &serde_json::json!({})),
+            hash: None,
+            source: "file_watcher".to_string(),
+        })
+    }
+
+    // 根据订阅者手中的版本生成补丁；推送内容应用了环境变量覆盖，旧版本也按同样方式处理
+    pub fn config_change(
+        &self,
+        key: String,
+        previous: Option<Config>,
+        config_str: String,
+        source: &str,
+    ) -> ConfigChange {
+        // 缓存中原本没有的配置视为新建
+        let kind = match previous {
+            Some(_) => ChangeKind::Updated,
+            None => ChangeKind::Created,
+        };
+        let old = match previous {
+            Some(mut previous) => EnvOverrideService::apply_env_override(&mut previous)
+                .map(|released| released.to_serde_value())
+                .unwrap_or_else(|_| previous.to_serde_value()),
+            None => serde_json::json!({}),
+        };
+        let new = serde_json::from_str(&config_str).unwrap_or_default();
+        ConfigChange {
+            patch: ConfigPatchService::diff(&old, &new),
+            hash: self.config_map.hash(&key),
+            file: key,
+            kind,
+            config: config_str,
+            source: source.to_string(),
+        }
+    }
+
+    // 订阅者成功收到文件的最新配置（初始、恢复或推送）
+    pub fn mark_synced(&self, client_id: &str, file: &str) {
+        let now = self.clock.now();
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        if let Some(synced) = subscriptions
+            .subscriber_usage
+            .get_mut(client_id)
+            .and_then(|usage| usage.files.get_mut(file))
+        {
+            *synced = Some(now);
+        }
+    }
+
+    // 配置当前版本，即最近一次推送该文件更新的事件序号
+    pub fn config_version(&self, file: &str) -> u64 {
+        self.subscriptions.lock().unwrap().event_log.version(file)
+    }
+
+    // 轮询方式（HTTP GET / TCP get）拉取了配置
+    pub fn record_fetch(&self, file: &str) {
+        let now = self.clock.now();
+        self.freshness.lock().unwrap().fetched(file, now);
+    }
+
+    // 找出在 SLO 窗口内没有拿到最新配置的订阅者和轮询方
+    pub fn freshness_report(&self) -> FreshnessReport {
+        let now = self.clock.now();
+        // 与 publish 相同的加锁顺序：先订阅状态，后新鲜度
+        let subscriptions = self.subscriptions.lock().unwrap();
+        let tracker = self.freshness.lock().unwrap();
+        let mut stale: Vec<StaleConsumer> = subscriptions
+            .subscriber_usage
+            .iter()
+            .flat_map(|(client_id, usage)| {
+                usage.files.iter().map(move |(file, synced)| (client_id, usage, file, *synced))
+            })
+            .filter_map(|(client_id, usage, file, synced)| {
+                let (last_modified, lag_secs) =
+                    tracker.staleness(file, synced, now, self.freshness_slo)?;
+                Some(StaleConsumer {
+                    consumer: client_id.clone(),
+                    transport: usage.transport.clone(),
+                    file: file.clone(),
+                    last_modified,
+                    last_synced: synced,
+                    lag_secs,
+                })
+            })
+            .collect();
+
+        let configs = tracker.configs();
+        for (file, freshness) in configs.iter() {
+            // 从未被拉取过的配置没有轮询方，不计入
+            let Some(fetched) = freshness.last_fetched else {
+                continue;
+            };
+            if let Some((last_modified, lag_secs)) =
+                tracker.staleness(file, Some(fetched), now, self.freshness_slo)
+            {
+                stale.push(StaleConsumer {
+                    consumer: "pollers".to_string(),
+                    transport: "poll".to_string(),
+                    file: file.clone(),
+                    last_modified,
+                    last_synced: Some(fetched),
+                    lag_secs,
+                });
+            }
+        }
+        stale.sort_by(|a, b| b.lag_secs.cmp(&a.lag_secs).then(a.consumer.cmp(&b.consumer)));
+
+        FreshnessReport {
+            slo_secs: self.freshness_slo.num_seconds(),
+            checked_at: now,
+            configs,
+            stale,
+        }
+    }
+
+    // 推送前记账，超出配额时返回延迟或断开
+    pub fn account_push(&self, client_id: &str, bytes: u64) -> QuotaDecision {
+        let now = self.clock.now();
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        match subscriptions.subscriber_usage.get_mut(client_id) {
+            Some(usage) => usage.account(bytes, &self.quota, now),
+            None => QuotaDecision::Allow,
+        }
+    }
+
+    // 按修改后配置中的 $ttl 声明同步过期规则，配置被删除时规则随之移除
+    pub fn sync_ttls(&self, change: &ConfigChange) {
+        let now = self.clock.now();
+        let changed = match change.kind {
+            ChangeKind::Deleted => self.ttls.lock().unwrap().remove_file(&change.file),
+            ChangeKind::Created | ChangeKind::Updated => {
+                let config = serde_json::from_str(&change.config).unwrap_or_default();
+                match ConfigTtl::declared(&change.file, &config, now) {
+                    Ok(declared) => self.ttls.lock().unwrap().sync_declared(&change.file, declared),
+                    Err(e) => {
+                        tracing::warn!("ignore ttl declaration: {}", e);
+                        false
+                    }
+                }
+            }
+        };
+        if changed {
+            self.ttls_changed.notify_one();
+        }
+    }
+
+    // 写入事件日志，返回更新和订阅该文件的订阅者数量；
+    // 文件有进行中的金丝雀发布时发布随之结束，留待订阅者投递时使用
+    pub fn publish(&self, change: ConfigChange) -> (ConfigUpdate, usize) {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let update = subscriptions.event_log.record(change, self.clock.now());
+        self.freshness.lock().unwrap().modified(&update.file, update.timestamp);
+        if let Some(rollout) = subscriptions.rollouts.remove(&update.file) {
+            subscriptions.superseded.insert(update.seq, rollout);
+        }
+        let count = subscriptions
+            .notify_map
+            .values()
+            .filter(|subscriber| {
+                subscriber.files.iter().any(|target| covers(target, &update.file))
+            })
+            .count();
+        (update, count)
+    }
+
+    // 返回应收到该更新的 (客户端ID, 通知发送器, 推送给该订阅者的更新)；
+    // 更新之后才注册的订阅者已从快照中拿到该版本，金丝雀订阅者的补丁基于金丝雀版本
+    pub fn subscribers_for(
+        &self,
+        update: &ConfigUpdate,
+    ) -> Vec<(String, UnboundedSender<ConfigUpdate>, ConfigUpdate)> {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let rollout = subscriptions.superseded.remove(&update.seq);
+        let canary = rollout.as_ref().map(|rollout| rollout.superseded_update(update));
+        subscriptions
+            .notify_map
+            .iter()
+            .filter(|(_, subscriber)| {
+                subscriber.since < update.seq
+                    && subscriber.files.iter().any(|target| covers(target, &update.file))
+            })
+            .map(|(client_id, subscriber)| {
+                let update = match (&rollout, &canary) {
+                    (Some(rollout), Some(canary)) if rollout.clients.contains(client_id) => {
+                        canary.clone()
+                    }
+                    _ => update.clone(),
+                };
+                (client_id.clone(), subscriber.sender.clone(), update)
+            })
+            .collect()
+    }
+
+    // 签发新的恢复令牌，从当前最新事件开始计算
+    pub fn open_resume_session(&self, subscriptions: &mut Subscriptions, file: &str) -> String {
+        let token = self.id_generator.next_id("resume");
+        subscriptions.open_resume_session(&token, file);
+        token
+    }
+
+    // 令牌有效且在恢复窗口内时返回断线期间错过的更新
+    pub fn resume_session(
+        &self,
+        subscriptions: &mut Subscriptions,
+        token: &str,
+        file: &str,
+    ) -> Option<Vec<ConfigUpdate>> {
+        subscriptions.prune_resume_sessions(self.clock.now() - self.resume_window);
+        subscriptions.resume_session(token, file)
+    }
+
+    pub fn acknowledge(&self, token: &str, seq: u64) {
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        if let Some(session) = subscriptions.resume_sessions.get_mut(token) {
+            session.last_seq = session.last_seq.max(seq);
+        }
+    }
+
+    pub fn suspend_resume_session(&self, token: &str) {
+        let now = self.clock.now();
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        if let Some(session) = subscriptions.resume_sessions.get_mut(token) {
+            session.disconnected_at = Some(now);
+        }
+        subscriptions.prune_resume_sessions(now - self.resume_window);
+    }
+}
+
+impl Subscriptions {
+    // 注册订阅者，之后通过 watch 添加要接收更新的文件
+    pub fn add(
+        &mut self,
+        client_id: &str,
+        sender: UnboundedSender<ConfigUpdate>,
+        transport: &str,
+        now: DateTime<Utc>,
+    ) {
+        let subscriber = Subscriber {
+            files: BTreeSet::new(),
+            labels: BTreeMap::new(),
+            sender,
+            since: self.event_log.latest_seq(),
+        };
+        self.notify_map.insert(client_id.to_string(), subscriber);
+        let usage = SubscriberUsage::new(transport, now);
+        self.subscriber_usage.insert(client_id.to_string(), usage);
+    }
+
+    // 订阅前声明的标签
+    pub fn label(&mut self, client_id: &str, labels: BTreeMap<String, String>) {
+        if let Some(subscriber) = self.notify_map.get_mut(client_id) {
+            subscriber.labels = labels;
+        }
+    }
+
+    // 订阅的文件有进行中的金丝雀发布且订阅者被选中时加入发布，快照之后紧接着收到金丝雀版本
+    pub fn watch(&mut self, client_id: &str, file: &str) {
+        let Some(subscriber) = self.notify_map.get_mut(client_id) else {
+            return;
+        };
+        subscriber.files.insert(file.to_string());
+        if let Some(usage) = self.subscriber_usage.get_mut(client_id) {
+            usage.files.entry(file.to_string()).or_default();
+        }
+        let subscriber = &self.notify_map[client_id];
+        let joined: Vec<String> = self
+            .rollouts
+            .values()
+            .filter(|rollout| {
+                covers(file, &rollout.file)
+                    && !rollout.clients.contains(client_id)
+                    && rollout.target.matches(client_id, &subscriber.labels)
+            })
+            .map(|rollout| rollout.file.clone())
+            .collect();
+        for file in joined {
+            let seq = self.event_log.next_seq();
+            let rollout = self.rollouts.get_mut(&file).unwrap();
+            if subscriber.sender.send(rollout.canary_update(seq)).is_ok() {
+                rollout.clients.insert(client_id.to_string());
+            }
+        }
+    }
+
+    pub fn unwatch(&mut self, client_id: &str, file: &str) {
+        if let Some(subscriber) = self.notify_map.get_mut(client_id) {
+            subscriber.files.remove(file);
+        }
+        if let Some(usage) = self.subscriber_usage.get_mut(client_id) {
+            usage.files.remove(file);
+        }
+    }
+
+    fn open_resume_session(&mut self, token: &str, file: &str) {
+        self.resume_sessions.insert(
+            token.to_string(),
+            ResumeSession {
+                file: file.to_string(),
+                last_seq: self.event_log.latest_seq(),
+                disconnected_at: None,
+            },
+        );
+    }
+
+    fn resume_session(&mut self, token: &str, file: &str) -> Option<Vec<ConfigUpdate>> {
+        let session = self.resume_sessions.get_mut(token)?;
+        if session.file != file || session.disconnected_at.is_none() {
+            return None;
+        }
+        let missed = self.event_log.since(file, session.last_seq)?;
+        session.disconnected_at = None;
+        if let Some(last) = missed.last() {
+            session.last_seq = last.seq;
+        }
+        Some(missed)
+    }
+
+    fn prune_resume_sessions(&mut self, deadline: DateTime<Utc>) {
+        self.resume_sessions.retain(|_, session| match session.disconnected_at {
+            Some(at) => at >= deadline,
+            None => true,
+        });
+    }
+}
+
+// 服务开始关闭时返回；不持有 watch 的读锁，可在 select! 分支中继续 await
+pub async fn wait_for_shutdown(shutdown: &mut watch::Receiver<bool>) {
+    let _ = shutdown.wait_for(|stopping| *stopping).await;
+}
+
+// 等待推送任务把关闭通知发给所有订阅者并注销，超时后不再等待
+pub async fn drain_subscribers(app_state: &AppState) {
+    let deadline = tokio::time::Instant::now() + SHUTDOWN_DRAIN_TIMEOUT;
+    loop {
+        let remaining = app_state.subscriber_count();
+        if remaining == 0 {
+            return;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            tracing::warn!("{} subscribers not drained before shutdown", remaining);
+            return;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
 }
 
-// 存储监听者信息：客户端ID -> (文件路径, 通知发送器)
-type NotifyMap = HashMap<String, (String, UnboundedSender<String>)>;
+// 存储监听者信息：客户端ID -> 订阅者
+type NotifyMap = HashMap<String, Subscriber>;
 
 // 🌐 HTTP 响应统一格式
 #[derive(Debug, Serialize, Deserialize)]
@@ -59,6 +1127,16 @@ impl<T: Serialize> RestResponse<T> {
         };
         axum::Json(response)
     }
+
+    // 请求被拒绝，同时在 data 中返回原因明细
+    pub fn rejected(code: u16, message: String, data: T) -> axum::Json<Self> {
+        axum::Json(Self {
+            success: false,
+            code,
+            message,
+            data: Some(data),
+        })
+    }
 }
 
 impl<T: Serialize> RestResponse<T> {
@@ -70,10 +1148,9 @@ impl<T: Serialize> RestResponse<T> {
 impl<T: Serialize> IntoResponse for RestResponse<T> {
     fn into_response(self) -> Response {
         let json = self.to_json();
-        let response = Response::builder()
+        Response::builder()
             .header("Content-Type", "application/json")
             .body(Body::from(json))
-            .unwrap();
-        response
+            .unwrap()
     }
 }
@@ -0,0 +1,90 @@
+use std::sync::{
+    Mutex,
+    atomic::{AtomicU64, Ordering},
+};
+
+use chrono::{DateTime, Duration, Utc};
+
+// 时间来源抽象，测试中可以替换为固定时钟
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+// ID 生成抽象，用于客户端 ID、事件 ID 等
+pub trait IdGenerator: Send + Sync {
+    fn next_id(&self, prefix: &str) -> String;
+}
+
+#[derive(Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+// 手动推进的时钟
+#[derive(Debug)]
+pub struct FixedClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl FixedClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Mutex::new(now),
+        }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now += duration;
+    }
+}
+
+impl Clock for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+}
+
+// 时间戳 + 随机数，保证跨进程唯一
+#[derive(Debug, Default)]
+pub struct RandomIdGenerator;
+
+impl IdGenerator for RandomIdGenerator {
+    fn next_id(&self, prefix: &str) -> String {
+        format!(
+            "{}_{}_{}",
+            prefix,
+            Utc::now().timestamp_millis(),
+            rand::random::<u32>()
+        )
+    }
+}
+
+// 递增序号，输出可预测
+#[derive(Debug, Default)]
+pub struct SequentialIdGenerator {
+    counter: AtomicU64,
+}
+
+impl SequentialIdGenerator {
+    pub fn new() -> Self {
+        Self {
+            counter: AtomicU64::new(0),
+        }
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn next_id(&self, prefix: &str) -> String {
+        let id = self.counter.fetch_add(1, Ordering::SeqCst) + 1;
+        format!("{}_{}", prefix, id)
+    }
+}
@@ -0,0 +1,591 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        entities::backup::RetentionPolicy,
+        services::{
+            config_formatter::FormatOptions,
+            config_lint::{KeyCase, LintRules},
+        },
+    },
+    shared::error::ConfigError,
+};
+
+// serve 的服务端设置文件（YAML 或 JSON）
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ServerSettings {
+    #[serde(default)]
+    pub audit: Option<AuditSettings>,
+    #[serde(default)]
+    pub routing: Vec<NamespaceRoute>,
+    // 多租户命名空间，每个对应配置目录下的同名子目录，例如：
+    //   namespaces: [team-a, team-b]
+    #[serde(default)]
+    pub namespaces: Vec<String>,
+    #[serde(default)]
+    pub api_keys: Vec<ApiKey>,
+    #[serde(default)]
+    pub cors: Option<CorsSettings>,
+    #[serde(default)]
+    pub security_headers: SecurityHeaderSettings,
+    // 未配置时使用启动时的 --log-filter
+    #[serde(default)]
+    pub log_level: Option<LogLevel>,
+    // 按模块（tracing target）覆盖日志级别，例如：
+    //   log_modules:
+    //     config_manager::infrastructure::notification: debug
+    //     hyper: warn
+    #[serde(default)]
+    pub log_modules: BTreeMap<String, LogLevel>,
+    // 覆盖命令行的 --rate-limit / --rate-burst
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitSettings>,
+    #[serde(default)]
+    pub notifications: Vec<NotificationSettings>,
+    #[serde(default)]
+    pub backup: Option<BackupSettings>,
+}
+
+// 定时备份，存储位置由 store 字段选择，例如：
+//   backup:
+//     schedule: "0 3 * * *"      # cron 表达式（UTC）或 @every 6h
+//     namespace: team-a          # 可选，只备份该命名空间
+//     retention: { keep_last: 10, keep_daily: 7 }
+//     store: dir
+//     path: /var/backups/config-manager
+// 备份到 S3（或兼容 S3 的对象存储）时：
+//     store: s3
+//     bucket: config-backups
+//     prefix: prod/
+//     endpoint: http://minio:9000   # 可选，默认 AWS
+// 备份格式与 bundle export 相同
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackupSettings {
+    pub schedule: String,
+    #[serde(default)]
+    pub namespace: Option<String>,
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+    #[serde(flatten)]
+    pub store: BackupStoreSettings,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "store", rename_all = "snake_case")]
+pub enum BackupStoreSettings {
+    Dir { path: String },
+    S3(S3Settings),
+}
+
+// S3 兼容对象存储的位置与凭证；未配置的凭证、region 和 endpoint 从 AWS_ACCESS_KEY_ID、
+// AWS_SECRET_ACCESS_KEY、AWS_SESSION_TOKEN、AWS_REGION、AWS_ENDPOINT_URL 环境变量读取
+#[derive(Debug, Clone, Deserialize)]
+pub struct S3Settings {
+    pub bucket: String,
+    // 对象名前缀，例如 "prod/"
+    #[serde(default)]
+    pub prefix: String,
+    #[serde(default)]
+    pub region: Option<String>,
+    // 以路径方式访问 bucket（<endpoint>/<bucket>/<key>），未配置时为 AWS 的区域端点
+    #[serde(default)]
+    pub endpoint: Option<String>,
+    #[serde(default)]
+    pub access_key: Option<String>,
+    #[serde(default)]
+    pub secret_key: Option<String>,
+}
+
+// serve --storage：配置保存的位置。file 为配置目录本身；s3://bucket/prefix 时配置目录作为
+// bucket 的本地副本，两边的修改互相同步，连接参数与凭证从 AWS_* 环境变量读取；
+// sqlite:path.db 时配置、历史版本、审计记录和附加规则保存在 SQLite 数据库中；
+// etcd://host:2379/prefix、consul://host:8500/prefix 镜像已有 KV 中 prefix 下的配置；
+// k8s://namespace（k8s-sync feature）同步命名空间中的 ConfigMap 与 Secret
+#[derive(Debug, Clone)]
+pub enum StorageBackend {
+    File,
+    S3(S3Settings),
+    Sqlite(PathBuf),
+    Etcd(KvSettings),
+    Consul(KvSettings),
+    #[cfg(feature = "k8s-sync")]
+    Kubernetes(String),
+}
+
+// etcd 或 Consul 的 HTTP 地址与键前缀；prefix 非空时以 "/" 结尾
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KvSettings {
+    pub endpoint: String,
+    pub prefix: String,
+}
+
+impl StorageBackend {
+    pub fn parse(spec: &str) -> Result<Self, ConfigError> {
+        if spec == "file" {
+            return Ok(Self::File);
+        }
+        let invalid = || {
+            ConfigError::InvalidServerSettings(format!(
+                "invalid storage {}, expected file, s3://bucket/prefix, sqlite:path.db, \
+                 etcd://host:port/prefix, consul://host:port/prefix or k8s://namespace",
+                spec
+            ))
+        };
+        if let Some(path) = spec.strip_prefix("sqlite:") {
+            return match path {
+                "" => Err(invalid()),
+                path => Ok(Self::Sqlite(PathBuf::from(path))),
+            };
+        }
+        let (scheme, location) = spec.split_once("://").ok_or_else(invalid)?;
+        let (host, prefix) = location.split_once('/').unwrap_or((location, ""));
+        if host.is_empty() {
+            return Err(invalid());
+        }
+        let prefix = match prefix.trim_matches('/') {
+            "" => String::new(),
+            prefix => format!("{}/", prefix),
+        };
+        let kv = || KvSettings {
+            endpoint: format!("http://{}", host),
+            prefix: prefix.clone(),
+        };
+        match scheme {
+            "s3" => Ok(Self::S3(S3Settings {
+                bucket: host.to_string(),
+                prefix: prefix.clone(),
+                region: None,
+                endpoint: None,
+                access_key: None,
+                secret_key: None,
+            })),
+            "etcd" => Ok(Self::Etcd(kv())),
+            "consul" => Ok(Self::Consul(kv())),
+            #[cfg(feature = "k8s-sync")]
+            "k8s" if prefix.is_empty() => Ok(Self::Kubernetes(host.to_string())),
+            #[cfg(not(feature = "k8s-sync"))]
+            "k8s" => Err(ConfigError::InvalidServerSettings(
+                "k8s:// storage requires building with the k8s-sync feature".to_string(),
+            )),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+// 变更事件发布到的消息总线，由 sink 字段选择，可以同时配置多个，例如：
+//   notifications:
+//     - sink: kafka
+//       brokers: ["kafka-1:9092", "kafka-2:9092"]
+//       topic: config-changes
+//     - sink: nats
+//       address: nats:4222
+//       subject: config.changes
+//     - sink: redis
+//       address: redis:6379
+//       channel: config-changes
+// 消息内容与 webhook 相同，为 JSON 格式的配置更新
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "sink", rename_all = "snake_case")]
+pub enum NotificationSettings {
+    Kafka {
+        brokers: Vec<String>,
+        topic: String,
+        #[serde(default)]
+        partition: i32,
+    },
+    Nats {
+        address: String,
+        subject: String,
+    },
+    Redis {
+        address: String,
+        channel: String,
+        #[serde(default)]
+        password: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LogLevel::Trace => "trace",
+            LogLevel::Debug => "debug",
+            LogLevel::Info => "info",
+            LogLevel::Warn => "warn",
+            LogLevel::Error => "error",
+        }
+    }
+}
+
+// 每个客户端（API key 或 IP）每秒允许的请求数与令牌桶容量，例如：
+//   rate_limit:
+//     rate: 50
+//     burst: 100
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitSettings {
+    pub rate: f64,
+    #[serde(default)]
+    pub burst: Option<u32>,
+}
+
+// 对外提供配置的接口
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Interface {
+    Http,
+    Ws,
+    Tcp,
+}
+
+impl Display for Interface {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interface::Http => write!(f, "http"),
+            Interface::Ws => write!(f, "ws"),
+            Interface::Tcp => write!(f, "tcp"),
+        }
+    }
+}
+
+// 命名空间只在指定接口上可见，例如：
+//   routing:
+//     - namespace: secrets
+//       interfaces: [tcp]
+//     - namespace: public
+//       interfaces: [http, ws]
+// 命名空间匹配同名配置、"<namespace>.<ext>" 以及 "<namespace>/" 下的配置
+#[derive(Debug, Clone, Deserialize)]
+pub struct NamespaceRoute {
+    pub namespace: String,
+    pub interfaces: Vec<Interface>,
+}
+
+impl NamespaceRoute {
+    fn matches(&self, key: &str) -> bool {
+        match key.strip_prefix(self.namespace.as_str()) {
+            Some(rest) => rest.is_empty() || rest.starts_with('/') || rest.starts_with('.'),
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct NamespaceRouting {
+    routes: Vec<NamespaceRoute>,
+}
+
+impl NamespaceRouting {
+    pub fn new(routes: Vec<NamespaceRoute>) -> Self {
+        Self { routes }
+    }
+
+    // 最长匹配的命名空间决定可见接口，未声明的配置对所有接口可见
+    pub fn allows(&self, interface: Interface, key: &str) -> bool {
+        self.routes
+            .iter()
+            .filter(|route| route.matches(key))
+            .max_by_key(|route| route.namespace.len())
+            .is_none_or(|route| route.interfaces.contains(&interface))
+    }
+}
+
+// HTTP API 的静态密钥，请求通过 "Authorization: Bearer <key>" 或 "X-Api-Key: <key>" 携带，例如：
+//   api_keys:
+//     - name: dashboard
+//       key: r3ad-only
+//       scopes: [read]
+//     - name: deploy
+//       key: s3cr3t
+//       scopes: [read, write]
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiKey {
+    pub name: String,
+    pub key: String,
+    pub scopes: Vec<ApiScope>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiScope {
+    Read,
+    Write,
+}
+
+impl Display for ApiScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApiScope::Read => write!(f, "read"),
+            ApiScope::Write => write!(f, "write"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ApiKeys {
+    keys: Vec<ApiKey>,
+}
+
+impl ApiKeys {
+    pub fn new(keys: Vec<ApiKey>) -> Self {
+        Self { keys }
+    }
+
+    // 未配置任何密钥时不启用鉴权
+    pub fn enabled(&self) -> bool {
+        !self.keys.is_empty()
+    }
+
+    pub fn find(&self, token: &str) -> Option<&ApiKey> {
+        self.keys
+            .iter()
+            .find(|key| constant_time_eq(key.key.as_bytes(), token.as_bytes()))
+    }
+}
+
+impl ApiKey {
+    pub fn allows(&self, scope: ApiScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+}
+
+// 比较耗时与内容无关，避免通过响应时间逐字节猜出密钥
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+// 浏览器跨域访问 HTTP API，例如：
+//   cors:
+//     allowed_origins: ["https://dashboard.example.com"]
+//     allowed_methods: [GET, PUT]
+//     allowed_headers: [authorization, content-type]
+//     max_age: 600
+// allowed_origins 为 ["*"] 时允许任意来源；未配置 cors 时不返回任何 CORS 头
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CorsSettings {
+    pub allowed_origins: Vec<String>,
+    #[serde(default = "default_cors_methods")]
+    pub allowed_methods: Vec<String>,
+    #[serde(default = "default_cors_headers")]
+    pub allowed_headers: Vec<String>,
+    #[serde(default = "default_cors_expose_headers")]
+    pub expose_headers: Vec<String>,
+    #[serde(default = "default_cors_max_age")]
+    pub max_age: u64,
+}
+
+fn default_cors_methods() -> Vec<String> {
+    ["GET", "HEAD", "POST", "PUT", "PATCH", "DELETE"].map(String::from).to_vec()
+}
+
+fn default_cors_headers() -> Vec<String> {
+    ["authorization", "content-type", "if-match", "x-api-key"].map(String::from).to_vec()
+}
+
+fn default_cors_expose_headers() -> Vec<String> {
+    vec!["etag".to_string()]
+}
+
+fn default_cors_max_age() -> u64 {
+    600
+}
+
+// 附加在每个 HTTP 响应上的安全头，已由处理函数设置的头不会被覆盖；
+// 只有在 TLS 终止于本服务或其前置代理时才应配置 hsts_max_age
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SecurityHeaderSettings {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_content_security_policy")]
+    pub content_security_policy: String,
+    #[serde(default = "default_frame_options")]
+    pub frame_options: String,
+    #[serde(default = "default_referrer_policy")]
+    pub referrer_policy: String,
+    #[serde(default)]
+    pub hsts_max_age: Option<u64>,
+}
+
+impl Default for SecurityHeaderSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            content_security_policy: default_content_security_policy(),
+            frame_options: default_frame_options(),
+            referrer_policy: default_referrer_policy(),
+            hsts_max_age: None,
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_content_security_policy() -> String {
+    "default-src 'none'; frame-ancestors 'none'".to_string()
+}
+
+fn default_frame_options() -> String {
+    "DENY".to_string()
+}
+
+fn default_referrer_policy() -> String {
+    "no-referrer".to_string()
+}
+
+// 审计记录输出方式，由 sink 字段选择
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "sink", rename_all = "snake_case")]
+pub enum AuditSettings {
+    Jsonl {
+        path: String,
+    },
+    Csv {
+        path: String,
+    },
+    Syslog {
+        address: String,
+        #[serde(default = "default_app_name")]
+        app_name: String,
+    },
+    Http {
+        url: String,
+    },
+}
+
+fn default_app_name() -> String {
+    "config-manager".to_string()
+}
+
+impl ServerSettings {
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path)?;
+        serde_yaml::from_str(&content).map_err(|_| ConfigError::ParseConfigError)
+    }
+}
+
+pub const PROJECT_FILE: &str = "configmanager.toml";
+
+// 仓库根目录下的 configmanager.toml，配置 check 命令，例如：
+//   files = ["config"]
+//   exclude = ["config/generated/**"]
+//   rules = "validation.yaml"
+//   schema = "schema.json"
+//   strict = false
+//
+//   [lint]
+//   format = true
+//   key_case = "snake_case"
+//   max_depth = 6
+// 路径均相对于该文件所在目录
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ProjectSettings {
+    #[serde(default = "default_project_files")]
+    pub files: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    pub rules: Option<String>,
+    pub schema: Option<String>,
+    #[serde(default)]
+    pub strict: bool,
+    #[serde(default)]
+    pub lint: LintSettings,
+}
+
+impl Default for ProjectSettings {
+    fn default() -> Self {
+        Self {
+            files: default_project_files(),
+            exclude: Vec::new(),
+            rules: None,
+            schema: None,
+            strict: false,
+            lint: LintSettings::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LintSettings {
+    // 文件内容必须与 format 命令的输出一致
+    #[serde(default)]
+    pub format: bool,
+    #[serde(default)]
+    pub sort_keys: bool,
+    #[serde(default = "default_indent")]
+    pub indent: usize,
+    pub key_case: Option<KeyCase>,
+    pub max_depth: Option<usize>,
+}
+
+impl Default for LintSettings {
+    fn default() -> Self {
+        Self {
+            format: false,
+            sort_keys: false,
+            indent: default_indent(),
+            key_case: None,
+            max_depth: None,
+        }
+    }
+}
+
+impl LintSettings {
+    pub fn rules(&self) -> LintRules {
+        LintRules {
+            format: self.format.then_some(FormatOptions {
+                sort_keys: self.sort_keys,
+                indent: self.indent,
+            }),
+            key_case: self.key_case,
+            max_depth: self.max_depth,
+        }
+    }
+}
+
+fn default_project_files() -> Vec<String> {
+    vec![".".to_string()]
+}
+
+fn default_indent() -> usize {
+    2
+}
+
+impl ProjectSettings {
+    pub fn load(path: &Path) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path)?;
+        toml::from_str(&content).map_err(|e| ConfigError::InvalidProjectFile {
+            path: path.display().to_string(),
+            error: e.message().to_string(),
+        })
+    }
+
+    // 从 dir 向上查找最近的 configmanager.toml
+    pub fn discover(dir: &Path) -> Option<PathBuf> {
+        let dir = std::path::absolute(dir).ok()?;
+        dir.ancestors()
+            .map(|dir| dir.join(PROJECT_FILE))
+            .find(|path| path.is_file())
+    }
+}
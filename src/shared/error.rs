@@ -1,4 +1,18 @@
+use serde::Serialize;
 use thiserror::Error;
+
+// 错误类别，用于 CLI 退出码和结构化错误输出
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    Internal,
+    Parse,
+    Validation,
+    Io,
+    NotFound,
+    Remote,
+    Integrity,
+}
 #[derive(Debug, Error)]
 pub enum ConfigError {
     #[error("config parse error")]
@@ -11,7 +25,7 @@ pub enum ConfigError {
     InvalidFileExtension,
     #[error("empty path")]
     EmptyPath,
-    #[error("unsupported config type, we only support json, yaml, toml")]
+    #[error("unsupported config type, we only support json, json5, yaml, toml")]
     UnknownConfigType,
     #[error("empty content")]
     EmptyContent,
@@ -19,6 +33,14 @@ pub enum ConfigError {
     UnsupportedFormat { format: String },
     #[error("key not found")]
     KeyNotFound,
+    #[error("config not found: {0}")]
+    ConfigNotFound(String),
+    #[error("config {file} is not exposed over {interface}")]
+    NamespaceNotExposed { file: String, interface: String },
+    #[error("invalid namespace: {0}")]
+    InvalidNamespace(String),
+    #[error("key already exists: {0}")]
+    KeyAlreadyExists(String),
     #[error("unsupported template type")]
     UnsupportedTemplateType,
     #[error("invalid path")]
@@ -29,6 +51,260 @@ pub enum ConfigError {
     NowRepositoryConfigNotSupportFunction,
     #[error("invalid config path: {0}")]
     InvalidConfigPath(String),
+    #[error("invalid glob pattern: {0}")]
+    InvalidGlobPattern(String),
+    #[error("invalid key pattern: {0}")]
+    InvalidKeyPattern(String),
+    #[error("invalid embedded block spec: {0}, expected e.g. ---yaml, +++toml or ```json")]
+    InvalidEmbeddedSpec(String),
+    #[error("{0} was not generated from a template")]
+    TemplateMetadataMissing(String),
+    #[error("embedded config block {0} not found")]
+    EmbeddedBlockNotFound(String),
+    #[error("invalid severity: {0}, expected error, warning or info")]
+    InvalidSeverity(String),
+    #[error("invalid cross field rule: {0}")]
+    InvalidCrossFieldRule(String),
+    #[error("invalid read consistency {0}, expected linearizable, leader-local, any-replica or bounded-staleness=<ms>")]
+    InvalidConsistency(String),
+    #[error("read consistency {0} requires cluster mode, which is not supported; only linearizable reads are served")]
+    ConsistencyNotSupported(String),
+    #[error("invalid rules at {path}: {error}")]
+    InvalidRules { path: String, error: String },
+    #[error("invalid assertion {0:?}, expected '<path> <op> <value>', '<path> exists' or '<path> missing'")]
+    InvalidAssertion(String),
+    #[error("invalid test file {path}: {error}")]
+    InvalidTestFile { path: String, error: String },
+    #[error("invalid project file {path}: {error}")]
+    InvalidProjectFile { path: String, error: String },
+    #[error("config {file} violates attached rules: {}", errors.join("; "))]
+    AttachedRulesViolation { file: String, errors: Vec<String> },
+    #[error("audit log is not configured with a readable sink, use the jsonl sink")]
+    AuditNotReadable,
+    #[error("version {version} of {file} not found")]
+    VersionNotFound { file: String, version: u64 },
+    #[error("invalid history file {path}: {error}")]
+    InvalidHistory { path: String, error: String },
+    #[error("invalid server settings: {0}")]
+    InvalidServerSettings(String),
+    #[error("invalid log filter {0}")]
+    InvalidLogFilter(String),
+    #[error("invalid schedule {0:?}, expected a 5-field cron expression, @hourly, @daily, @weekly or @every <duration>")]
+    InvalidSchedule(String),
+    #[error("invalid access policy {path}: {error}")]
+    InvalidPolicy { path: String, error: String },
+    #[error("{subject} has no {permission} permission on {config}")]
+    AccessDenied {
+        subject: String,
+        permission: String,
+        config: String,
+    },
+    #[error("invalid patch: {0}")]
+    InvalidPatch(String),
+    #[error("patch cannot be applied: {0}")]
+    PatchConflict(String),
+    #[error("invalid script rule: {0}")]
+    InvalidScriptRule(String),
+    #[error("invalid regex pattern for field {field}: {error}")]
+    InvalidRegexPattern { field: String, error: String },
+    #[error("unknown server context: {0}")]
+    UnknownServerContext(String),
+    #[error("remote request failed: {0}")]
+    RemoteRequestFailed(String),
+    #[error("no config files matched")]
+    NoFilesMatched,
+    #[error("{failed} of {total} files failed validation")]
+    ValidationFailed { failed: usize, total: usize },
+    #[error("{drifted} of {total} files drifted from schema")]
+    SchemaDrift { drifted: usize, total: usize },
+    #[error("{failed} of {total} config tests failed")]
+    TestsFailed { failed: usize, total: usize },
+    #[error("{failed} of {total} files failed check")]
+    CheckFailed { failed: usize, total: usize },
+    #[error("{failed} preflight checks failed")]
+    PreflightFailed { failed: usize },
+    #[error("document index {index} out of range, file has {count} documents")]
+    DocumentNotFound { index: usize, count: usize },
+    #[error("failed to drop privileges: {0}")]
+    PrivilegeDropFailed(String),
+    #[error("file watch error: {0}")]
+    WatchError(String),
+    #[error("database error: {0}")]
+    DatabaseError(String),
+    #[error("invalid rollout target: {0}")]
+    InvalidRolloutTarget(String),
+    #[error("config {0} already has a rollout in progress")]
+    RolloutInProgress(String),
+    #[error("no rollout in progress for {0}")]
+    RolloutNotFound(String),
+    #[error("config {config} is locked by {owner}: {reason}")]
+    ConfigLocked {
+        config: String,
+        owner: String,
+        reason: String,
+    },
+    #[error("config changes are frozen by {owner}: {reason}")]
+    ConfigsFrozen { owner: String, reason: String },
+    #[error("{0} is not locked")]
+    NotLocked(String),
+    #[error("invalid lock: {0}")]
+    InvalidLock(String),
+    #[error("invalid scheduled change: {0}")]
+    InvalidScheduledChange(String),
+    #[error("invalid scheduled changes file {path}: {error}")]
+    InvalidScheduledChanges { path: String, error: String },
+    #[error("invalid ttl: {0}")]
+    InvalidTtl(String),
+    #[error("invalid ttl file {path}: {error}")]
+    InvalidTtls { path: String, error: String },
+}
+
+impl ConfigError {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            ConfigError::ParseConfigError
+            | ConfigError::EmptyLine
+            | ConfigError::InvalidFileExtension
+            | ConfigError::UnknownConfigType
+            | ConfigError::EmptyContent
+            | ConfigError::UnsupportedFormat { .. }
+            | ConfigError::InvalidEnvVar { .. }
+            | ConfigError::InvalidRegexPattern { .. }
+            | ConfigError::InvalidCrossFieldRule(_)
+            | ConfigError::InvalidScriptRule(_)
+            | ConfigError::InvalidRules { .. }
+            | ConfigError::InvalidProjectFile { .. }
+            | ConfigError::InvalidAssertion(_)
+            | ConfigError::InvalidPatch(_)
+            | ConfigError::InvalidHistory { .. }
+            | ConfigError::InvalidPolicy { .. }
+            | ConfigError::InvalidServerSettings(_)
+            | ConfigError::InvalidLogFilter(_)
+            | ConfigError::InvalidSchedule(_)
+            | ConfigError::InvalidTestFile { .. }
+            | ConfigError::InvalidConsistency(_)
+            | ConfigError::ConsistencyNotSupported(_)
+            | ConfigError::InvalidSeverity(_)
+            | ConfigError::InvalidEmbeddedSpec(_)
+            | ConfigError::InvalidRolloutTarget(_)
+            | ConfigError::InvalidLock(_)
+            | ConfigError::InvalidScheduledChange(_)
+            | ConfigError::InvalidScheduledChanges { .. }
+            | ConfigError::InvalidTtl(_)
+            | ConfigError::InvalidTtls { .. } => ErrorCategory::Parse,
+            ConfigError::IoError(_) | ConfigError::WatchError(_) | ConfigError::DatabaseError(_) => {
+                ErrorCategory::Io
+            }
+            ConfigError::KeyNotFound
+            | ConfigError::NoFilesMatched
+            | ConfigError::DocumentNotFound { .. }
+            | ConfigError::EmbeddedBlockNotFound(_)
+            | ConfigError::TemplateMetadataMissing(_)
+            | ConfigError::NamespaceNotExposed { .. }
+            | ConfigError::RolloutNotFound(_)
+            | ConfigError::NotLocked(_)
+            | ConfigError::VersionNotFound { .. }
+            | ConfigError::ConfigNotFound(_) => ErrorCategory::NotFound,
+            ConfigError::ValidationFailed { .. }
+            | ConfigError::PreflightFailed { .. }
+            | ConfigError::CheckFailed { .. }
+            | ConfigError::TestsFailed { .. }
+            | ConfigError::SchemaDrift { .. }
+            | ConfigError::PatchConflict(_)
+            | ConfigError::RolloutInProgress(_)
+            | ConfigError::ConfigLocked { .. }
+            | ConfigError::ConfigsFrozen { .. }
+            | ConfigError::AccessDenied { .. }
+            | ConfigError::AttachedRulesViolation { .. } => ErrorCategory::Validation,
+            ConfigError::UnknownServerContext(_) | ConfigError::RemoteRequestFailed(_) => {
+                ErrorCategory::Remote
+            }
+            ConfigError::EmptyPath
+            | ConfigError::UnsupportedTemplateType
+            | ConfigError::InvalidPath
+            | ConfigError::NowRepositoryConfigNotSupportFunction
+            | ConfigError::AuditNotReadable
+            | ConfigError::PrivilegeDropFailed(_)
+            | ConfigError::InvalidConfigPath(_)
+            | ConfigError::InvalidNamespace(_)
+            | ConfigError::InvalidGlobPattern(_)
+            | ConfigError::InvalidKeyPattern(_) => ErrorCategory::Internal,
+            ConfigError::KeyAlreadyExists(_) => ErrorCategory::Validation,
+        }
+    }
+
+    // 结构化错误输出中的 kind，取变体名
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ConfigError::ParseConfigError => "ParseConfigError",
+            ConfigError::IoError(_) => "IoError",
+            ConfigError::EmptyLine => "EmptyLine",
+            ConfigError::InvalidFileExtension => "InvalidFileExtension",
+            ConfigError::EmptyPath => "EmptyPath",
+            ConfigError::UnknownConfigType => "UnknownConfigType",
+            ConfigError::EmptyContent => "EmptyContent",
+            ConfigError::UnsupportedFormat { .. } => "UnsupportedFormat",
+            ConfigError::KeyNotFound => "KeyNotFound",
+            ConfigError::ConfigNotFound(_) => "ConfigNotFound",
+            ConfigError::NamespaceNotExposed { .. } => "NamespaceNotExposed",
+            ConfigError::InvalidNamespace(_) => "InvalidNamespace",
+            ConfigError::KeyAlreadyExists(_) => "KeyAlreadyExists",
+            ConfigError::UnsupportedTemplateType => "UnsupportedTemplateType",
+            ConfigError::InvalidPath => "InvalidPath",
+            ConfigError::InvalidEnvVar { .. } => "InvalidEnvVar",
+            ConfigError::NowRepositoryConfigNotSupportFunction => "NowRepositoryConfigNotSupportFunction",
+            ConfigError::InvalidConfigPath(_) => "InvalidConfigPath",
+            ConfigError::InvalidGlobPattern(_) => "InvalidGlobPattern",
+            ConfigError::InvalidKeyPattern(_) => "InvalidKeyPattern",
+            ConfigError::InvalidEmbeddedSpec(_) => "InvalidEmbeddedSpec",
+            ConfigError::TemplateMetadataMissing(_) => "TemplateMetadataMissing",
+            ConfigError::EmbeddedBlockNotFound(_) => "EmbeddedBlockNotFound",
+            ConfigError::InvalidSeverity(_) => "InvalidSeverity",
+            ConfigError::InvalidCrossFieldRule(_) => "InvalidCrossFieldRule",
+            ConfigError::InvalidConsistency(_) => "InvalidConsistency",
+            ConfigError::ConsistencyNotSupported(_) => "ConsistencyNotSupported",
+            ConfigError::InvalidRules { .. } => "InvalidRules",
+            ConfigError::InvalidAssertion(_) => "InvalidAssertion",
+            ConfigError::InvalidTestFile { .. } => "InvalidTestFile",
+            ConfigError::InvalidProjectFile { .. } => "InvalidProjectFile",
+            ConfigError::AttachedRulesViolation { .. } => "AttachedRulesViolation",
+            ConfigError::AuditNotReadable => "AuditNotReadable",
+            ConfigError::VersionNotFound { .. } => "VersionNotFound",
+            ConfigError::InvalidHistory { .. } => "InvalidHistory",
+            ConfigError::InvalidServerSettings(_) => "InvalidServerSettings",
+            ConfigError::InvalidLogFilter(_) => "InvalidLogFilter",
+            ConfigError::InvalidSchedule(_) => "InvalidSchedule",
+            ConfigError::InvalidPolicy { .. } => "InvalidPolicy",
+            ConfigError::AccessDenied { .. } => "AccessDenied",
+            ConfigError::InvalidPatch(_) => "InvalidPatch",
+            ConfigError::PatchConflict(_) => "PatchConflict",
+            ConfigError::InvalidScriptRule(_) => "InvalidScriptRule",
+            ConfigError::InvalidRegexPattern { .. } => "InvalidRegexPattern",
+            ConfigError::UnknownServerContext(_) => "UnknownServerContext",
+            ConfigError::RemoteRequestFailed(_) => "RemoteRequestFailed",
+            ConfigError::NoFilesMatched => "NoFilesMatched",
+            ConfigError::ValidationFailed { .. } => "ValidationFailed",
+            ConfigError::SchemaDrift { .. } => "SchemaDrift",
+            ConfigError::TestsFailed { .. } => "TestsFailed",
+            ConfigError::CheckFailed { .. } => "CheckFailed",
+            ConfigError::PreflightFailed { .. } => "PreflightFailed",
+            ConfigError::DocumentNotFound { .. } => "DocumentNotFound",
+            ConfigError::PrivilegeDropFailed(_) => "PrivilegeDropFailed",
+            ConfigError::WatchError(_) => "WatchError",
+            ConfigError::DatabaseError(_) => "DatabaseError",
+            ConfigError::InvalidRolloutTarget(_) => "InvalidRolloutTarget",
+            ConfigError::RolloutInProgress(_) => "RolloutInProgress",
+            ConfigError::RolloutNotFound(_) => "RolloutNotFound",
+            ConfigError::ConfigLocked { .. } => "ConfigLocked",
+            ConfigError::ConfigsFrozen { .. } => "ConfigsFrozen",
+            ConfigError::NotLocked(_) => "NotLocked",
+            ConfigError::InvalidLock(_) => "InvalidLock",
+            ConfigError::InvalidScheduledChange(_) => "InvalidScheduledChange",
+            ConfigError::InvalidScheduledChanges { .. } => "InvalidScheduledChanges",
+            ConfigError::InvalidTtl(_) => "InvalidTtl",
+            ConfigError::InvalidTtls { .. } => "InvalidTtls",
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -45,6 +321,90 @@ pub enum ValidationError {
     CustomRuleViolation { field: String, rule: String },
     #[error("field {field} is not defined")]
     UndefinedField { field: String },
+    #[error("field {field} value {value:?} does not match pattern {pattern}")]
+    PatternMismatch {
+        field: String,
+        pattern: String,
+        value: String,
+    },
+    #[error("field {field} has {actual} items, expected {expected}")]
+    ItemCount {
+        field: String,
+        expected: String,
+        actual: usize,
+    },
+    #[error("field {field} value {value} is a duplicate")]
+    DuplicateItem { field: String, value: String },
+    #[error("field {field} value {value} is not allowed, expected one of: {allowed}")]
+    NotAllowed {
+        field: String,
+        value: String,
+        allowed: String,
+    },
+    #[error("field {field} path {path} does not exist")]
+    PathNotFound { field: String, path: String },
+    #[error("custom rule {rule}: {message}")]
+    ScriptRuleViolation { rule: String, message: String },
+    #[error("field {field} is deprecated{hint}")]
+    DeprecatedField { field: String, hint: String },
+    #[error("field {field} is required when {condition}")]
+    ConditionalRequired { field: String, condition: String },
+    #[error("fields {fields} are mutually exclusive")]
+    MutuallyExclusive { fields: String },
+    #[error("field {left} ({left_value}) must be {op} field {right} ({right_value})")]
+    ComparisonFailed {
+        left: String,
+        op: String,
+        right: String,
+        left_value: f64,
+        right_value: f64,
+    },
+}
+
+impl ValidationError {
+    pub fn category(&self) -> ErrorCategory {
+        ErrorCategory::Validation
+    }
+
+    // 报告中的规则标识
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ValidationError::RequiredField { .. } => "RequiredField",
+            ValidationError::TypeMismatch { .. } => "TypeMismatch",
+            ValidationError::CustomRuleViolation { .. } => "CustomRuleViolation",
+            ValidationError::UndefinedField { .. } => "UndefinedField",
+            ValidationError::PatternMismatch { .. } => "PatternMismatch",
+            ValidationError::ItemCount { .. } => "ItemCount",
+            ValidationError::DuplicateItem { .. } => "DuplicateItem",
+            ValidationError::NotAllowed { .. } => "NotAllowed",
+            ValidationError::PathNotFound { .. } => "PathNotFound",
+            ValidationError::ScriptRuleViolation { .. } => "ScriptRuleViolation",
+            ValidationError::DeprecatedField { .. } => "DeprecatedField",
+            ValidationError::ConditionalRequired { .. } => "ConditionalRequired",
+            ValidationError::MutuallyExclusive { .. } => "MutuallyExclusive",
+            ValidationError::ComparisonFailed { .. } => "ComparisonFailed",
+        }
+    }
+
+    // 出错的键；脚本规则和互斥规则不对应单个键
+    pub fn field(&self) -> Option<&str> {
+        match self {
+            ValidationError::RequiredField { field }
+            | ValidationError::TypeMismatch { field, .. }
+            | ValidationError::CustomRuleViolation { field, .. }
+            | ValidationError::UndefinedField { field }
+            | ValidationError::PatternMismatch { field, .. }
+            | ValidationError::ItemCount { field, .. }
+            | ValidationError::DuplicateItem { field, .. }
+            | ValidationError::NotAllowed { field, .. }
+            | ValidationError::PathNotFound { field, .. }
+            | ValidationError::DeprecatedField { field, .. }
+            | ValidationError::ConditionalRequired { field, .. } => Some(field),
+            ValidationError::ComparisonFailed { left, .. } => Some(left),
+            ValidationError::ScriptRuleViolation { .. }
+            | ValidationError::MutuallyExclusive { .. } => None,
+        }
+    }
 }
 
 
@@ -62,4 +422,114 @@ pub enum TemplateError {
     UnknownConfigType,
     #[error("io error")]
     IoError(#[from] std::io::Error),
-}
\ No newline at end of file
+}
+
+impl TemplateError {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            TemplateError::IoError(_) => ErrorCategory::Io,
+            TemplateError::TemplateNotFound => ErrorCategory::NotFound,
+            TemplateError::ParseTemplateError
+            | TemplateError::UnsupportedFormat { .. }
+            | TemplateError::UnknownConfigType => ErrorCategory::Parse,
+            TemplateError::NowRepositoryTemplateNotSupportFunction => ErrorCategory::Internal,
+        }
+    }
+
+    // 结构化错误输出中的 kind，取变体名
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TemplateError::ParseTemplateError => "ParseTemplateError",
+            TemplateError::TemplateNotFound => "TemplateNotFound",
+            TemplateError::NowRepositoryTemplateNotSupportFunction => "NowRepositoryTemplateNotSupportFunction",
+            TemplateError::UnsupportedFormat { .. } => "UnsupportedFormat",
+            TemplateError::UnknownConfigType => "UnknownConfigType",
+            TemplateError::IoError(_) => "IoError",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum BundleError {
+    #[error("io error")]
+    IoError(#[from] std::io::Error),
+    #[error("bundle manifest missing")]
+    ManifestMissing,
+    #[error("invalid bundle manifest")]
+    InvalidManifest,
+    #[error("invalid signing key")]
+    InvalidKey,
+    #[error("bundle is not signed")]
+    SignatureMissing,
+    #[error("bundle signature mismatch")]
+    SignatureMismatch,
+    #[error("no signing key given, pass --key-file or --allow-unsigned")]
+    KeyRequired,
+    #[error("hash mismatch for {path}")]
+    HashMismatch { path: String },
+    #[error("file {path} listed in manifest is missing from bundle")]
+    MissingEntry { path: String },
+    #[error("file {path} is not listed in manifest")]
+    UnexpectedEntry { path: String },
+    #[error("bundle entry {path} points outside the target directory")]
+    InvalidEntryPath { path: String },
+    #[error("config error: {0}")]
+    Config(#[from] ConfigError),
+}
+
+impl BundleError {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            BundleError::IoError(_) => ErrorCategory::Io,
+            BundleError::Config(e) => e.category(),
+            _ => ErrorCategory::Integrity,
+        }
+    }
+
+    // 结构化错误输出中的 kind，取变体名
+    pub fn kind(&self) -> &'static str {
+        match self {
+            BundleError::IoError(_) => "IoError",
+            BundleError::ManifestMissing => "ManifestMissing",
+            BundleError::InvalidManifest => "InvalidManifest",
+            BundleError::InvalidKey => "InvalidKey",
+            BundleError::SignatureMissing => "SignatureMissing",
+            BundleError::SignatureMismatch => "SignatureMismatch",
+            BundleError::KeyRequired => "KeyRequired",
+            BundleError::HashMismatch { .. } => "HashMismatch",
+            BundleError::MissingEntry { .. } => "MissingEntry",
+            BundleError::UnexpectedEntry { .. } => "UnexpectedEntry",
+            BundleError::InvalidEntryPath { .. } => "InvalidEntryPath",
+            BundleError::Config(e) => e.kind(),
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum BackupError {
+    #[error("io error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("backups are not configured")]
+    NotConfigured,
+    #[error("backup not found: {name}")]
+    NotFound { name: String },
+    // 对象存储请求失败，message 中带上状态码或连接错误
+    #[error("backup storage error: {0}")]
+    Storage(String),
+    #[error("backup {name} failed validation: {errors:?}")]
+    Invalid { name: String, errors: Vec<String> },
+    #[error("bundle error: {0}")]
+    Bundle(#[from] BundleError),
+}
+
+impl BackupError {
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            BackupError::IoError(_) => ErrorCategory::Io,
+            BackupError::NotConfigured | BackupError::NotFound { .. } => ErrorCategory::NotFound,
+            BackupError::Storage(_) => ErrorCategory::Remote,
+            BackupError::Invalid { .. } => ErrorCategory::Validation,
+            BackupError::Bundle(e) => e.category(),
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::io;
+
+use tokio::net::TcpListener;
+#[cfg(unix)]
+use tokio::net::UnixListener;
+
+// serve 模式的监听地址：TCP 端口，或 --uds 指定的 Unix socket（以文件权限控制访问）
+pub enum ServeListener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl ServeListener {
+    pub async fn bind(host: &str, port: u16, uds: Option<&str>) -> io::Result<Self> {
+        match uds {
+            Some(path) => Self::bind_unix(path),
+            None => Ok(Self::Tcp(TcpListener::bind((host, port)).await?)),
+        }
+    }
+
+    // 上次运行遗留的 socket 文件先删除，同名的其他文件不覆盖
+    #[cfg(unix)]
+    fn bind_unix(path: &str) -> io::Result<Self> {
+        use std::os::unix::fs::FileTypeExt;
+
+        if let Ok(metadata) = std::fs::symlink_metadata(path)
+            && metadata.file_type().is_socket()
+        {
+            std::fs::remove_file(path)?;
+        }
+        Ok(Self::Unix(UnixListener::bind(path)?))
+    }
+
+    #[cfg(not(unix))]
+    fn bind_unix(_path: &str) -> io::Result<Self> {
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "unix domain sockets are not supported on this platform",
+        ))
+    }
+
+    pub fn describe(host: &str, port: u16, uds: Option<&str>) -> String {
+        match uds {
+            Some(path) => format!("unix:{}", path),
+            None => format!("{}:{}", host, port),
+        }
+    }
+}
@@ -1,4 +1,6 @@
 pub mod error;
 pub mod config;
+pub mod listener;
 pub mod utils;
-pub mod app_state;
\ No newline at end of file
+pub mod app_state;
+pub mod clock;
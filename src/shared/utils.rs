@@ -1,13 +1,97 @@
-use tracing_subscriber::fmt;
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{
+        OnceLock,
+        atomic::{AtomicU64, Ordering},
+    },
+};
 
-use crate::shared::error::ConfigError;
+use tokio::io::AsyncWriteExt;
 
-pub fn init_tracing() {
-    let subscriber = fmt::Subscriber::builder()
-        .with_max_level(tracing::Level::DEBUG)
-        .finish();
+use tracing_subscriber::{EnvFilter, Registry, fmt, layer::SubscriberExt, reload};
+
+use crate::{infrastructure::logging::log_manager::LogFormat, shared::error::ConfigError};
+
+// 日志过滤可以在运行期间修改（serve 的设置文件热更新、PUT /api/admin/log-level）
+static LOG_FILTER: OnceLock<reload::Handle<EnvFilter, Registry>> = OnceLock::new();
+// 启动时的过滤规则，设置文件未指定日志级别时恢复为它
+static DEFAULT_LOG_FILTER: OnceLock<String> = OnceLock::new();
+
+// filter 为 EnvFilter 语法，例如 "info,config_manager::infrastructure::notification=debug"；
+// 未指定时读取 RUST_LOG，都没有时为 debug
+pub fn init_tracing(format: LogFormat, filter: Option<&str>) -> Result<(), ConfigError> {
+    let directives = match filter {
+        Some(filter) => filter.to_string(),
+        None => std::env::var(EnvFilter::DEFAULT_ENV).unwrap_or_else(|_| "debug".to_string()),
+    };
+    let (filter, handle) = reload::Layer::new(parse_log_filter(&directives)?);
+    let (text, json) = match format {
+        LogFormat::Text => (Some(fmt::layer()), None),
+        LogFormat::Json => (None, Some(fmt::layer().json())),
+    };
+    let subscriber = tracing_subscriber::registry().with(filter).with(text).with(json);
 
     tracing::subscriber::set_global_default(subscriber).expect("Failed to set default subscriber");
+    let _ = LOG_FILTER.set(handle);
+    let _ = DEFAULT_LOG_FILTER.set(directives);
+    Ok(())
+}
+
+fn parse_log_filter(directives: &str) -> Result<EnvFilter, ConfigError> {
+    EnvFilter::builder()
+        .parse(directives)
+        .map_err(|e| ConfigError::InvalidLogFilter(format!("{}: {}", directives, e)))
+}
+
+pub fn default_log_filter() -> String {
+    DEFAULT_LOG_FILTER.get().cloned().unwrap_or_else(|| "debug".to_string())
+}
+
+pub fn log_filter() -> String {
+    LOG_FILTER
+        .get()
+        .and_then(|handle| handle.with_current(|filter| filter.to_string()).ok())
+        .unwrap_or_else(default_log_filter)
+}
+
+pub fn set_log_filter(directives: &str) -> Result<(), ConfigError> {
+    let filter = parse_log_filter(directives)?;
+    if let Some(handle) = LOG_FILTER.get()
+        && let Err(e) = handle.modify(|current| *current = filter)
+    {
+        tracing::warn!("update log filter failed: {}", e);
+    }
+    Ok(())
+}
+
+// 等待 SIGINT（Ctrl-C）或 SIGTERM，serve 模式据此开始优雅关闭
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            tracing::error!("listen for ctrl-c failed: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                tracing::error!("listen for SIGTERM failed: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => tracing::info!("received SIGINT, shutting down"),
+        _ = terminate => tracing::info!("received SIGTERM, shutting down"),
+    }
 }
 
 pub fn read_file(path: &str) -> Result<String, ConfigError> {
@@ -15,11 +99,95 @@ pub fn read_file(path: &str) -> Result<String, ConfigError> {
     Ok(content)
 }
 
+// 同 read_file，供 serve 的异步路径使用，读取期间不占用运行时线程
+pub async fn read_file_async(path: &str) -> Result<String, ConfigError> {
+    let content = tokio::fs::read_to_string(path).await.map_err(ConfigError::IoError)?;
+    Ok(content)
+}
+
+// atomic_write 使用的临时文件后缀，临时文件以 "." 开头并与目标文件位于同一目录
+pub const ATOMIC_WRITE_SUFFIX: &str = ".cm-tmp";
+
+static ATOMIC_WRITE_SEQ: AtomicU64 = AtomicU64::new(0);
+
+pub fn is_atomic_write_temp(file_name: &str) -> bool {
+    file_name.starts_with('.') && file_name.ends_with(ATOMIC_WRITE_SUFFIX)
+}
+
+// 目标文件所在目录和同目录下的临时文件路径
+fn atomic_write_paths(path: &Path) -> std::io::Result<(&Path, PathBuf)> {
+    let file_name = path.file_name().ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidInput, "path has no file name")
+    })?;
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let temp = dir.join(format!(
+        ".{}.{}-{}{}",
+        file_name.to_string_lossy(),
+        std::process::id(),
+        ATOMIC_WRITE_SEQ.fetch_add(1, Ordering::Relaxed),
+        ATOMIC_WRITE_SUFFIX
+    ));
+    Ok((dir, temp))
+}
+
+// 原子写入：先写同目录下的临时文件并 fsync，再重命名覆盖目标文件并 fsync 所在目录。
+// 中途崩溃时目标文件保持旧内容，文件监听器也不会读到写了一半的文件；已有文件的权限保持不变
+pub fn atomic_write(path: impl AsRef<Path>, content: impl AsRef<[u8]>) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let (dir, temp) = atomic_write_paths(path)?;
+    let written = (|| {
+        let mut file = std::fs::File::create(&temp)?;
+        file.write_all(content.as_ref())?;
+        if let Ok(metadata) = std::fs::metadata(path) {
+            file.set_permissions(metadata.permissions())?;
+        }
+        file.sync_all()?;
+        std::fs::rename(&temp, path)
+    })();
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    written?;
+    // 目录 fsync 之后重命名才算落盘；非 unix 平台不能以文件方式打开目录
+    #[cfg(unix)]
+    std::fs::File::open(dir)?.sync_all()?;
+    Ok(())
+}
+
+// 同 atomic_write，使用 tokio::fs，供接口的写请求使用
+pub async fn atomic_write_async(
+    path: impl AsRef<Path>,
+    content: impl AsRef<[u8]>,
+) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let (dir, temp) = atomic_write_paths(path)?;
+    let written = async {
+        let mut file = tokio::fs::File::create(&temp).await?;
+        file.write_all(content.as_ref()).await?;
+        if let Ok(metadata) = tokio::fs::metadata(path).await {
+            file.set_permissions(metadata.permissions()).await?;
+        }
+        file.sync_all().await?;
+        tokio::fs::rename(&temp, path).await
+    }
+    .await;
+    if written.is_err() {
+        let _ = tokio::fs::remove_file(&temp).await;
+    }
+    written?;
+    #[cfg(unix)]
+    tokio::fs::File::open(dir).await?.sync_all().await?;
+    Ok(())
+}
+
 pub fn delete_ignore_line(content: &str) -> String {
     content
         .lines()
         .filter(|line| {
-            if line.contains("#") || line.is_empty() || line.trim().starts_with("---") {
+            if line.contains("#") || line.is_empty() {
                 return false;
             }
             true
@@ -27,3 +195,125 @@ pub fn delete_ignore_line(content: &str) -> String {
         .collect::<Vec<&str>>()
         .join("\n")
 }
+
+pub fn is_config_file(path: &str) -> bool {
+    let path = path.to_lowercase();
+    path.ends_with(".toml")
+        || path.ends_with(".json")
+        || path.ends_with(".json5")
+        || path.ends_with(".yaml")
+        || path.ends_with(".yml")
+}
+
+// serve 模式的配置名称是相对配置目录的路径，以 "/" 分隔；
+// 不允许空段、以 "." 开头的段（隐藏文件和 ".."）以及反斜杠
+pub fn is_valid_config_name(name: &str) -> bool {
+    !name.contains('\\')
+        && name
+            .split('/')
+            .all(|segment| !segment.is_empty() && !segment.starts_with('.'))
+}
+
+// 路径在当前目录之下时转为相对路径，报告中的文件名更短
+pub fn relative_to_cwd(path: &std::path::Path) -> std::path::PathBuf {
+    let cwd = std::env::current_dir().ok();
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    match cwd.as_deref().and_then(|cwd| path.strip_prefix(cwd).ok()) {
+        Some(relative) if relative.as_os_str().is_empty() => std::path::PathBuf::from("."),
+        Some(relative) => relative.to_path_buf(),
+        None => path,
+    }
+}
+
+// 将文件、目录和 glob 模式展开为配置文件列表（保持输入顺序并去重）
+pub fn expand_config_paths(inputs: &[String]) -> Result<Vec<String>, ConfigError> {
+    let mut files: Vec<String> = Vec::new();
+    for input in inputs {
+        let path = std::path::Path::new(input);
+        // 远程地址和标准输入原样保留，不做 glob 展开
+        let matched: Vec<String> = if input == "-" || is_url(input) {
+            vec![input.clone()]
+        } else if path.is_dir() {
+            let pattern = format!("{}/**/*", input.trim_end_matches('/'));
+            glob_config_files(&pattern)?
+        } else if input.contains(['*', '?', '[']) {
+            glob_config_files(input)?
+        } else {
+            vec![input.clone()]
+        };
+        for file in matched {
+            if !files.contains(&file) {
+                files.push(file);
+            }
+        }
+    }
+    Ok(files)
+}
+
+fn glob_config_files(pattern: &str) -> Result<Vec<String>, ConfigError> {
+    let paths =
+        glob::glob(pattern).map_err(|e| ConfigError::InvalidGlobPattern(e.to_string()))?;
+    let mut files: Vec<String> = paths
+        .filter_map(|entry| entry.ok())
+        .filter(|path| path.is_file())
+        .map(|path| path.to_string_lossy().to_string())
+        .filter(|path| is_config_file(path))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+// 解析 --server 参数：URL 直接使用，否则从 ~/.config-manager/contexts.yaml 中按名称查找
+pub fn is_url(input: &str) -> bool {
+    input.starts_with("http://") || input.starts_with("https://")
+}
+
+pub fn resolve_server(server: &str) -> Result<String, ConfigError> {
+    if is_url(server) {
+        return Ok(server.to_string());
+    }
+
+    let home = std::env::var("HOME").map_err(|_| ConfigError::UnknownServerContext(server.to_string()))?;
+    let contexts_path = std::path::Path::new(&home).join(".config-manager/contexts.yaml");
+    let content = std::fs::read_to_string(contexts_path)
+        .map_err(|_| ConfigError::UnknownServerContext(server.to_string()))?;
+    let contexts: std::collections::HashMap<String, String> =
+        serde_yaml::from_str(&content).map_err(|_| ConfigError::ParseConfigError)?;
+    contexts
+        .get(server)
+        .cloned()
+        .ok_or_else(|| ConfigError::UnknownServerContext(server.to_string()))
+}
+
+// 默认的运行日志文件，放在状态目录而不是当前目录；没有 HOME 时退回临时目录
+pub fn default_log_file() -> String {
+    let state_dir = std::env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| std::env::var_os("HOME").map(|home| Path::new(&home).join(".local/state")))
+        .unwrap_or_else(std::env::temp_dir);
+    state_dir
+        .join("config-manager/config-manager.log")
+        .to_string_lossy()
+        .to_string()
+}
+
+pub fn sha256_hex(data: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    to_hex(&Sha256::digest(data))
+}
+
+pub fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+// 十六进制字符串解码，长度为奇数或含非十六进制字符时返回 None
+pub fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| hex.get(i..i + 2).and_then(|byte| u8::from_str_radix(byte, 16).ok()))
+        .collect()
+}
@@ -0,0 +1,1206 @@
+// 端到端测试脚手架：在临时目录里以随机端口启动 serve 进程（TCP 与 HTTP/WS），
+// 再通过 CLI 二进制、库里的 HTTP 客户端以及原始 TCP / WebSocket 协议驱动它们
+use std::{
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{
+        OnceLock,
+        atomic::{AtomicUsize, Ordering},
+    },
+    time::{Duration, Instant},
+};
+
+use axum::{Json, Router, routing::post};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::Value;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpStream, UnixStream},
+    sync::mpsc,
+};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite::Message};
+
+pub const TIMEOUT: Duration = Duration::from_secs(10);
+
+static WORKSPACE_SEQ: AtomicUsize = AtomicUsize::new(0);
+
+// 被测二进制只构建一次；测试进程里的 CARGO 指向当前使用的 cargo
+pub fn binary() -> &'static Path {
+    static BINARY: OnceLock<PathBuf> = OnceLock::new();
+    BINARY.get_or_init(|| {
+        let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+        let status = Command::new(cargo)
+            .args(["build", "--quiet", "-p", "config-manager", "--bin", "config-manager"])
+            .args(["--features", "k8s-sync"])
+            .current_dir(workspace_root())
+            .status()
+            .expect("run cargo build");
+        assert!(status.success(), "build config-manager failed");
+        let target_dir = std::env::var("CARGO_TARGET_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| workspace_root().join("target"));
+        target_dir
+            .join("debug")
+            .join(format!("config-manager{}", std::env::consts::EXE_SUFFIX))
+    })
+}
+
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../..")
+}
+
+// 空闲端口：绑定 0 端口拿到系统分配的端口后立即释放
+pub fn free_port() -> u16 {
+    TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .map(|addr| addr.port())
+        .expect("allocate port")
+}
+
+// 轮询直到 f 返回 Some，超时则 panic
+pub async fn eventually<T>(what: &str, mut f: impl AsyncFnMut() -> Option<T>) -> T {
+    let deadline = Instant::now() + TIMEOUT;
+    loop {
+        if let Some(value) = f().await {
+            return value;
+        }
+        assert!(Instant::now() < deadline, "timed out waiting for {}", what);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+// 每个测试独占的临时目录：config/ 存放配置，其余为审计日志、服务日志等
+pub struct Workspace {
+    pub root: PathBuf,
+}
+
+impl Workspace {
+    pub fn new(name: &str) -> Self {
+        let root = std::env::temp_dir().join(format!(
+            "config-manager-e2e-{}-{}-{}",
+            name,
+            std::process::id(),
+            WORKSPACE_SEQ.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&root);
+        std::fs::create_dir_all(root.join("config")).expect("create workspace");
+        Self { root }
+    }
+
+    pub fn config_dir(&self) -> PathBuf {
+        self.root.join("config")
+    }
+
+    // 运行日志默认写入的状态目录，避免测试写到用户目录
+    pub fn state_dir(&self) -> PathBuf {
+        self.root.join("state")
+    }
+
+    pub fn app_log(&self) -> Option<String> {
+        std::fs::read_to_string(self.state_dir().join("config-manager/config-manager.log")).ok()
+    }
+
+    // file 可以带子目录，例如命名空间内的 "team-a/app.json"
+    pub fn write(&self, file: &str, content: &str) {
+        let path = self.config_dir().join(file);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).expect("create config dir");
+        }
+        std::fs::write(path, content).expect("write config");
+    }
+
+    pub fn read(&self, file: &str) -> Option<String> {
+        std::fs::read_to_string(self.config_dir().join(file)).ok()
+    }
+
+    // 写入配置目录之外的服务端文件，例如访问策略
+    pub fn write_server_file(&self, file: &str, content: &str) -> PathBuf {
+        let path = self.root.join(file);
+        std::fs::write(&path, content).expect("write server file");
+        path
+    }
+
+    pub fn audit_log(&self) -> PathBuf {
+        self.root.join("audit.jsonl")
+    }
+
+    // 写入把审计记录输出到 audit_log() 的服务端设置文件
+    pub fn audit_settings(&self) -> PathBuf {
+        let path = self.root.join("settings.yaml");
+        let content = format!("audit:\n  sink: jsonl\n  path: {}\n", self.audit_log().display());
+        std::fs::write(&path, content).expect("write settings");
+        path
+    }
+
+    pub fn audit_records(&self) -> Vec<Value> {
+        std::fs::read_to_string(self.audit_log())
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect()
+    }
+
+    // 在配置目录下运行 CLI
+    pub fn cli(&self, args: &[&str]) -> CliOutput {
+        let output = Command::new(binary())
+            .args(args)
+            .current_dir(self.config_dir())
+            .env("XDG_STATE_HOME", self.state_dir())
+            .env("NO_COLOR", "1")
+            .env("RUST_LOG", "error")
+            .output()
+            .expect("run config-manager");
+        CliOutput {
+            success: output.status.success(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        }
+    }
+}
+
+impl Drop for Workspace {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.root);
+    }
+}
+
+#[derive(Debug)]
+pub struct CliOutput {
+    pub success: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    Tcp,
+    Http,
+}
+
+// 后台运行的 serve 进程，drop 时终止；输出写入工作目录下的 serve-<port>.log
+pub struct Server {
+    pub port: u16,
+    log: PathBuf,
+    child: Child,
+}
+
+impl Server {
+    pub async fn start(workspace: &Workspace, mode: Mode, extra_args: &[String]) -> Self {
+        Self::start_on(workspace, mode, free_port(), extra_args).await
+    }
+
+    // 在指定端口启动，用于模拟服务重启后客户端重连
+    pub async fn start_on(
+        workspace: &Workspace,
+        mode: Mode,
+        port: u16,
+        extra_args: &[String],
+    ) -> Self {
+        let mut server = Self::spawn(workspace, mode, port, extra_args, &[]);
+        server.wait_ready().await;
+        server
+    }
+
+    // 带额外环境变量启动，例如 --storage s3:// 需要的 AWS_* 连接参数
+    pub async fn start_with_env(
+        workspace: &Workspace,
+        mode: Mode,
+        extra_args: &[String],
+        env: &[(&str, &str)],
+    ) -> Self {
+        let mut server = Self::spawn(workspace, mode, free_port(), extra_args, env);
+        server.wait_ready().await;
+        server
+    }
+
+    // 以 --uds 启动，只监听 Unix socket
+    pub async fn start_unix(workspace: &Workspace, mode: Mode, socket: &Path) -> Self {
+        let args = ["--uds".to_string(), socket.display().to_string()];
+        let mut server = Self::spawn(workspace, mode, free_port(), &args, &[]);
+        let deadline = Instant::now() + TIMEOUT;
+        while UnixStream::connect(socket).await.is_err() {
+            if let Ok(Some(status)) = server.child.try_wait() {
+                panic!("server exited with {}:\n{}", status, server.log());
+            }
+            assert!(Instant::now() < deadline, "server not ready:\n{}", server.log());
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        server
+    }
+
+    fn spawn(
+        workspace: &Workspace,
+        mode: Mode,
+        port: u16,
+        extra_args: &[String],
+        env: &[(&str, &str)],
+    ) -> Self {
+        let log = workspace.root.join(format!("serve-{}.log", port));
+        let log_file = std::fs::File::create(&log).expect("create server log");
+        let mut command = Command::new(binary());
+        command
+            .args(["serve", "-H", "127.0.0.1", "-p", &port.to_string()])
+            .arg("-c")
+            .arg(workspace.config_dir())
+            .args(extra_args)
+            .env("XDG_STATE_HOME", workspace.state_dir())
+            .envs(env.iter().copied())
+            .current_dir(workspace.config_dir())
+            .stdout(Stdio::from(log_file.try_clone().expect("clone log handle")))
+            .stderr(Stdio::from(log_file));
+        if mode == Mode::Http {
+            command.arg("--http");
+        }
+        let child = command.spawn().expect("spawn server");
+        Self { port, log, child }
+    }
+
+    async fn wait_ready(&mut self) {
+        let deadline = Instant::now() + TIMEOUT;
+        while TcpStream::connect(("127.0.0.1", self.port)).await.is_err() {
+            if let Ok(Some(status)) = self.child.try_wait() {
+                panic!("server exited with {}:\n{}", status, self.log());
+            }
+            assert!(Instant::now() < deadline, "server not ready:\n{}", self.log());
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    pub fn log(&self) -> String {
+        std::fs::read_to_string(&self.log).unwrap_or_default()
+    }
+
+    pub fn http_url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    pub async fn get_json(&self, path: &str) -> Value {
+        reqwest::get(format!("{}{}", self.http_url(), path))
+            .await
+            .expect("send request")
+            .json()
+            .await
+            .expect("decode response")
+    }
+
+    pub async fn get_with_header(&self, path: &str, name: &str, value: &str) -> Value {
+        reqwest::Client::new()
+            .get(format!("{}{}", self.http_url(), path))
+            .header(name, value)
+            .send()
+            .await
+            .expect("send request")
+            .json()
+            .await
+            .expect("decode response")
+    }
+
+    pub async fn post_json(&self, path: &str, body: &Value) -> Value {
+        reqwest::Client::new()
+            .post(format!("{}{}", self.http_url(), path))
+            .json(body)
+            .send()
+            .await
+            .expect("send request")
+            .json()
+            .await
+            .expect("decode response")
+    }
+
+    pub async fn put_json(&self, path: &str, body: &Value) -> Value {
+        reqwest::Client::new()
+            .put(format!("{}{}", self.http_url(), path))
+            .json(body)
+            .send()
+            .await
+            .expect("send request")
+            .json()
+            .await
+            .expect("decode response")
+    }
+
+    // 长轮询读取配置，返回状态码和响应体（304 没有响应体）
+    pub async fn watch(&self, file: &str, version: u64, timeout_secs: u64) -> (u16, Option<Value>) {
+        let url = format!(
+            "{}/api/configs/{}?watch=true&version={}&timeout={}",
+            self.http_url(),
+            file,
+            version,
+            timeout_secs
+        );
+        let response = reqwest::get(url).await.expect("send request");
+        let status = response.status().as_u16();
+        let body = response.bytes().await.expect("read response");
+        (status, serde_json::from_slice(&body).ok())
+    }
+
+    pub async fn put(&self, path: &str, body: &str) -> Value {
+        reqwest::Client::new()
+            .put(format!("{}{}", self.http_url(), path))
+            .body(body.to_string())
+            .send()
+            .await
+            .expect("send request")
+            .json()
+            .await
+            .expect("decode response")
+    }
+
+    pub async fn etag(&self, path: &str) -> Option<String> {
+        let response = reqwest::get(format!("{}{}", self.http_url(), path))
+            .await
+            .expect("send request");
+        let etag = response.headers().get("etag")?;
+        Some(etag.to_str().expect("etag header").to_string())
+    }
+
+    pub async fn put_if_match(&self, path: &str, if_match: &str, body: &str) -> Value {
+        reqwest::Client::new()
+            .put(format!("{}{}", self.http_url(), path))
+            .header("if-match", if_match)
+            .body(body.to_string())
+            .send()
+            .await
+            .expect("send request")
+            .json()
+            .await
+            .expect("decode response")
+    }
+
+    pub async fn patch(&self, path: &str, content_type: &str, body: &str) -> Value {
+        reqwest::Client::new()
+            .patch(format!("{}{}", self.http_url(), path))
+            .header("content-type", content_type)
+            .body(body.to_string())
+            .send()
+            .await
+            .expect("send request")
+            .json()
+            .await
+            .expect("decode response")
+    }
+
+    pub async fn send_with_key(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        api_key: &str,
+        body: &str,
+    ) -> Value {
+        reqwest::Client::new()
+            .request(method, format!("{}{}", self.http_url(), path))
+            .bearer_auth(api_key)
+            .body(body.to_string())
+            .send()
+            .await
+            .expect("send request")
+            .json()
+            .await
+            .expect("decode response")
+    }
+
+    // 行协议：发送一条命令，响应为 "<字节数>\n<内容>"
+    pub async fn tcp_request(&self, command: &str) -> String {
+        let stream = TcpStream::connect(("127.0.0.1", self.port))
+            .await
+            .expect("connect tcp server");
+        let mut stream = BufReader::new(stream);
+        stream
+            .get_mut()
+            .write_all(format!("{}\n", command).as_bytes())
+            .await
+            .expect("send tcp command");
+        read_frame(&mut stream).await
+    }
+
+    // 发送 LISTEN 并读掉初始配置，之后的推送用 read_frame 读取
+    pub async fn tcp_listen(&self, file: &str) -> BufReader<TcpStream> {
+        let stream = TcpStream::connect(("127.0.0.1", self.port))
+            .await
+            .expect("connect tcp server");
+        let mut stream = BufReader::new(stream);
+        stream
+            .get_mut()
+            .write_all(format!("listen {}\n", file).as_bytes())
+            .await
+            .expect("send tcp command");
+        read_frame(&mut stream).await;
+        stream
+    }
+
+    // 发送 SUBSCRIBE，返回连接和初始快照，之后的推送用 read_frame 读取
+    pub async fn tcp_subscribe(&self, file: &str, keys: &[&str]) -> (BufReader<TcpStream>, Value) {
+        let stream = TcpStream::connect(("127.0.0.1", self.port))
+            .await
+            .expect("connect tcp server");
+        let mut stream = BufReader::new(stream);
+        stream
+            .get_mut()
+            .write_all(format!("subscribe {} {}\n", file, keys.join(" ")).as_bytes())
+            .await
+            .expect("send tcp command");
+        let initial = read_frame(&mut stream).await;
+        let initial = serde_json::from_str(&initial).expect("decode subscribe response");
+        (stream, initial)
+    }
+
+    // 发送 SIGTERM 并等待进程退出
+    pub async fn terminate(&mut self) -> ExitStatus {
+        let status = Command::new("kill")
+            .args(["-TERM", &self.child.id().to_string()])
+            .status()
+            .expect("send SIGTERM");
+        assert!(status.success(), "kill failed");
+        let deadline = Instant::now() + TIMEOUT;
+        loop {
+            if let Some(status) = self.child.try_wait().expect("wait server") {
+                return status;
+            }
+            assert!(Instant::now() < deadline, "server did not exit:\n{}", self.log());
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    }
+
+    pub async fn listen(&self, file: &str) -> WsListener {
+        let url = format!("ws://127.0.0.1:{}/ws/listen?file={}", self.port, file);
+        let (stream, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .expect("connect websocket");
+        WsListener { stream }
+    }
+
+    // 声明标签后订阅，金丝雀发布可以按标签选择订阅者
+    pub async fn listen_with_labels(&self, file: &str, labels: &str) -> WsListener {
+        let url = format!(
+            "ws://127.0.0.1:{}/ws/listen?file={}&labels={}",
+            self.port, file, labels
+        );
+        let (stream, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .expect("connect websocket");
+        WsListener { stream }
+    }
+
+    // 订阅配置的 SSE 事件流；last_event_id 为重连时最后收到的事件序号
+    pub async fn events(&self, file: &str, last_event_id: Option<u64>) -> SseListener {
+        self.sse(&format!("/api/configs/{}/events", file), last_event_id).await
+    }
+
+    // 订阅整个命名空间的 SSE 事件流
+    pub async fn namespace_events(&self, namespace: &str) -> SseListener {
+        self.sse(&format!("/api/namespaces/{}/events", namespace), None).await
+    }
+
+    async fn sse(&self, path: &str, last_event_id: Option<u64>) -> SseListener {
+        let mut request = reqwest::Client::new().get(format!("{}{}", self.http_url(), path));
+        if let Some(id) = last_event_id {
+            request = request.header("last-event-id", id.to_string());
+        }
+        let response = request.send().await.expect("connect sse");
+        SseListener {
+            response,
+            buffer: String::new(),
+        }
+    }
+
+    // 不指定文件建立连接，之后通过 subscribe 消息订阅
+    pub async fn websocket(&self) -> WsListener {
+        let url = format!("ws://127.0.0.1:{}/ws/listen", self.port);
+        let (stream, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .expect("connect websocket");
+        WsListener { stream }
+    }
+}
+
+// 读取一条 "<字节数>\n<内容>" 格式的响应
+pub async fn read_frame<S: AsyncRead + Unpin>(stream: &mut BufReader<S>) -> String {
+    tokio::time::timeout(TIMEOUT, async {
+        let mut length = String::new();
+        stream.read_line(&mut length).await?;
+        let length: usize = length.trim().parse().map_err(std::io::Error::other)?;
+        let mut body = vec![0; length];
+        stream.read_exact(&mut body).await?;
+        Ok::<_, std::io::Error>(String::from_utf8_lossy(&body).trim_end().to_string())
+    })
+    .await
+    .expect("tcp response timed out")
+    .expect("read tcp response")
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+pub struct WsListener {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+}
+
+impl WsListener {
+    // 下一条指定类型的 JSON 消息，跳过其他类型
+    pub async fn next_of(&mut self, message_type: &str) -> Value {
+        tokio::time::timeout(TIMEOUT, async {
+            loop {
+                match self.stream.next().await {
+                    Some(Ok(Message::Text(text))) => {
+                        let message: Value = serde_json::from_str(&text).expect("decode ws message");
+                        if message["type"] == message_type {
+                            return message;
+                        }
+                    }
+                    Some(Ok(_)) => {}
+                    other => panic!("websocket closed: {:?}", other),
+                }
+            }
+        })
+        .await
+        .unwrap_or_else(|_| panic!("timed out waiting for {} message", message_type))
+    }
+
+    pub async fn send(&mut self, message: Value) {
+        self.stream
+            .send(Message::Text(message.to_string()))
+            .await
+            .expect("send ws message");
+    }
+
+    pub async fn close(mut self) {
+        let _ = self.stream.send(Message::Close(None)).await;
+    }
+}
+
+pub struct SseListener {
+    response: reqwest::Response,
+    buffer: String,
+}
+
+impl SseListener {
+    // 下一个事件的 (事件名, JSON 数据)，跳过保活注释
+    pub async fn next_event(&mut self) -> (String, Value) {
+        tokio::time::timeout(TIMEOUT, async {
+            loop {
+                if let Some(end) = self.buffer.find("\n\n") {
+                    let block: String = self.buffer.drain(..end + 2).collect();
+                    let (mut event, mut data) = (String::new(), String::new());
+                    for line in block.lines() {
+                        if let Some(value) = line.strip_prefix("event:") {
+                            event = value.trim().to_string();
+                        } else if let Some(value) = line.strip_prefix("data:") {
+                            data.push_str(value.trim());
+                        }
+                    }
+                    if !event.is_empty() {
+                        let data = serde_json::from_str(&data).unwrap_or(Value::String(data));
+                        return (event, data);
+                    }
+                    continue;
+                }
+                let chunk = self.response.chunk().await.expect("read sse");
+                let chunk = chunk.unwrap_or_else(|| panic!("sse stream closed"));
+                self.buffer.push_str(&String::from_utf8_lossy(&chunk));
+            }
+        })
+        .await
+        .expect("timed out waiting for sse event")
+    }
+
+    // 下一个指定名称的事件，跳过其他事件
+    pub async fn next_of(&mut self, event: &str) -> Value {
+        loop {
+            let (name, data) = self.next_event().await;
+            if name == event {
+                return data;
+            }
+        }
+    }
+}
+
+// 接收 webhook 推送的本地 HTTP 服务
+pub struct WebhookReceiver {
+    pub url: String,
+    receiver: mpsc::UnboundedReceiver<Value>,
+}
+
+impl WebhookReceiver {
+    pub async fn start() -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind webhook receiver");
+        let url = format!("http://{}/hook", listener.local_addr().expect("local addr"));
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let app = Router::new().route(
+            "/hook",
+            post(move |Json(body): Json<Value>| async move {
+                let _ = sender.send(body);
+            }),
+        );
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        Self { url, receiver }
+    }
+
+    pub async fn recv(&mut self) -> Value {
+        tokio::time::timeout(TIMEOUT, self.receiver.recv())
+            .await
+            .expect("timed out waiting for webhook")
+            .expect("webhook receiver closed")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Broker {
+    Nats,
+    Redis,
+}
+
+// 模拟 NATS / Redis 服务端，只实现发布所需的协议，收到的消息按 (subject 或 channel, JSON 内容) 转发
+pub struct BrokerReceiver {
+    pub address: String,
+    receiver: mpsc::UnboundedReceiver<(String, Value)>,
+}
+
+impl BrokerReceiver {
+    pub async fn start(broker: Broker) -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind broker receiver");
+        let address = listener.local_addr().expect("local addr").to_string();
+        let (sender, receiver) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let sender = sender.clone();
+                tokio::spawn(async move {
+                    let mut stream = BufReader::new(stream);
+                    let _ = match broker {
+                        Broker::Nats => Self::serve_nats(&mut stream, &sender).await,
+                        Broker::Redis => Self::serve_redis(&mut stream, &sender).await,
+                    };
+                });
+            }
+        });
+        Self { address, receiver }
+    }
+
+    async fn serve_nats(
+        stream: &mut BufReader<tokio::net::TcpStream>,
+        sender: &mpsc::UnboundedSender<(String, Value)>,
+    ) -> std::io::Result<()> {
+        stream.get_mut().write_all(b"INFO {\"server_id\":\"e2e\"}\r\n").await?;
+        loop {
+            let mut line = String::new();
+            if stream.read_line(&mut line).await? == 0 {
+                return Ok(());
+            }
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            match parts.as_slice() {
+                ["PING"] => stream.get_mut().write_all(b"PONG\r\n").await?,
+                ["PUB", subject, size] => {
+                    let mut payload = vec![0; size.parse::<usize>().unwrap() + 2];
+                    stream.read_exact(&mut payload).await?;
+                    let message = serde_json::from_slice(&payload[..payload.len() - 2]).unwrap();
+                    let _ = sender.send((subject.to_string(), message));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    async fn serve_redis(
+        stream: &mut BufReader<tokio::net::TcpStream>,
+        sender: &mpsc::UnboundedSender<(String, Value)>,
+    ) -> std::io::Result<()> {
+        loop {
+            let mut line = String::new();
+            if stream.read_line(&mut line).await? == 0 {
+                return Ok(());
+            }
+            let count: usize = line.trim().trim_start_matches('*').parse().unwrap();
+            let mut args = Vec::new();
+            for _ in 0..count {
+                let mut header = String::new();
+                stream.read_line(&mut header).await?;
+                let size: usize = header.trim().trim_start_matches('$').parse().unwrap();
+                let mut arg = vec![0; size + 2];
+                stream.read_exact(&mut arg).await?;
+                arg.truncate(size);
+                args.push(arg);
+            }
+            if args[0] == b"PUBLISH" {
+                let channel = String::from_utf8_lossy(&args[1]).to_string();
+                let _ = sender.send((channel, serde_json::from_slice(&args[2]).unwrap()));
+                stream.get_mut().write_all(b":1\r\n").await?;
+            } else {
+                stream.get_mut().write_all(b"+OK\r\n").await?;
+            }
+        }
+    }
+
+    pub async fn recv(&mut self) -> (String, Value) {
+        tokio::time::timeout(TIMEOUT, self.receiver.recv())
+            .await
+            .expect("timed out waiting for broker message")
+            .expect("broker receiver closed")
+    }
+}
+
+// 模拟 S3：以路径方式访问 bucket，只实现对象的读写、删除和 ListObjectsV2（不翻页），
+// 请求必须带 SigV4 格式的 Authorization；对象保存在内存中，可直接读取
+#[derive(Clone)]
+pub struct S3Server {
+    pub endpoint: String,
+    objects: std::sync::Arc<std::sync::Mutex<std::collections::BTreeMap<String, Vec<u8>>>>,
+}
+
+impl S3Server {
+    pub async fn start() -> Self {
+        use axum::{
+            body::Bytes,
+            extract::{Path as UrlPath, Query, State},
+            http::{HeaderMap, Method, StatusCode},
+            routing::any,
+        };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind s3 server");
+        let server = Self {
+            endpoint: format!("http://{}", listener.local_addr().expect("local addr")),
+            objects: Default::default(),
+        };
+        let signed = |headers: &HeaderMap| {
+            headers
+                .get("authorization")
+                .and_then(|value| value.to_str().ok())
+                .is_some_and(|value| value.starts_with("AWS4-HMAC-SHA256 Credential=test/"))
+        };
+        let list = move |State(server): State<S3Server>,
+                         UrlPath(bucket): UrlPath<String>,
+                         Query(query): Query<std::collections::HashMap<String, String>>,
+                         headers: HeaderMap| async move {
+            if !signed(&headers) {
+                return (StatusCode::FORBIDDEN, String::new());
+            }
+            let prefix = format!("{}/{}", bucket, query.get("prefix").cloned().unwrap_or_default());
+            let contents: String = server
+                .objects
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(key, _)| key.starts_with(&prefix))
+                .map(|(key, content)| {
+                    format!(
+                        "<Contents><Key>{}</Key><Size>{}</Size><ETag>{}</ETag></Contents>",
+                        &key[bucket.len() + 1..],
+                        content.len(),
+                        Self::etag(content).replace('"', "&quot;")
+                    )
+                })
+                .collect();
+            let body = format!(
+                "<ListBucketResult><IsTruncated>false</IsTruncated>{}</ListBucketResult>",
+                contents
+            );
+            (StatusCode::OK, body)
+        };
+        let object = move |State(server): State<S3Server>,
+                           method: Method,
+                           UrlPath((bucket, key)): UrlPath<(String, String)>,
+                           headers: HeaderMap,
+                           body: Bytes| async move {
+            let no_etag = HeaderMap::new();
+            if !signed(&headers) {
+                return (StatusCode::FORBIDDEN, no_etag, vec![]);
+            }
+            let key = format!("{}/{}", bucket, key);
+            let mut objects = server.objects.lock().unwrap();
+            let with_etag = |content: &[u8]| {
+                let mut headers = HeaderMap::new();
+                headers.insert("etag", Self::etag(content).parse().unwrap());
+                headers
+            };
+            match method {
+                Method::PUT => {
+                    let etag = with_etag(&body);
+                    objects.insert(key, body.to_vec());
+                    (StatusCode::OK, etag, vec![])
+                }
+                Method::DELETE => {
+                    objects.remove(&key);
+                    (StatusCode::NO_CONTENT, no_etag, vec![])
+                }
+                _ => match objects.get(&key) {
+                    Some(content) => (StatusCode::OK, with_etag(content), content.clone()),
+                    None => (
+                        StatusCode::NOT_FOUND,
+                        no_etag,
+                        b"<Error><Message>The specified key does not exist.</Message></Error>"
+                            .to_vec(),
+                    ),
+                },
+            }
+        };
+        let app = Router::new()
+            .route("/{bucket}", axum::routing::get(list))
+            .route("/{bucket}/{*key}", any(object))
+            .with_state(server.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        server
+    }
+
+    // 直接修改 bucket 中的对象，模拟其他实例或运维人员的写入；content 为 None 时删除
+    pub fn set(&self, bucket: &str, key: &str, content: Option<&str>) {
+        let key = format!("{}/{}", bucket, key);
+        let mut objects = self.objects.lock().unwrap();
+        match content {
+            Some(content) => objects.insert(key, content.as_bytes().to_vec()),
+            None => objects.remove(&key),
+        };
+    }
+
+    pub fn get(&self, bucket: &str, key: &str) -> Option<String> {
+        let key = format!("{}/{}", bucket, key);
+        let objects = self.objects.lock().unwrap();
+        objects.get(&key).map(|content| String::from_utf8_lossy(content).to_string())
+    }
+
+    // 与内容一一对应的带引号 ETag
+    fn etag(content: &[u8]) -> String {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        content.hash(&mut hasher);
+        format!("\"{:016x}\"", hasher.finish())
+    }
+
+    // bucket 中的对象名（不含 bucket）
+    pub fn keys(&self, bucket: &str) -> Vec<String> {
+        let prefix = format!("{}/", bucket);
+        self.objects
+            .lock()
+            .unwrap()
+            .keys()
+            .filter_map(|key| key.strip_prefix(&prefix).map(|key| key.to_string()))
+            .collect()
+    }
+}
+
+// 键 -> (内容, 修改时的 revision)
+type KvEntries = std::sync::Arc<std::sync::Mutex<std::collections::BTreeMap<String, (Vec<u8>, u64)>>>;
+
+// 模拟 etcd（v3 JSON gateway）或 Consul KV：键值保存在内存中，每次写入递增全局 revision，
+// watch（etcd 的 /v3/watch 流、Consul 的 blocking query）在 revision 变化时返回
+#[derive(Clone)]
+pub struct KvServer {
+    pub endpoint: String,
+    entries: KvEntries,
+    revision: std::sync::Arc<tokio::sync::watch::Sender<u64>>,
+}
+
+impl KvServer {
+    async fn bind(app: impl FnOnce(Self) -> Router) -> Self {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind kv server");
+        let server = Self {
+            endpoint: listener.local_addr().expect("local addr").to_string(),
+            entries: Default::default(),
+            revision: std::sync::Arc::new(tokio::sync::watch::channel(1).0),
+        };
+        let app = app(server.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        server
+    }
+
+    pub async fn etcd() -> Self {
+        use axum::{body::Body, extract::State};
+        use base64::{Engine, engine::general_purpose::STANDARD};
+
+        let decode = |value: &Value| {
+            String::from_utf8(STANDARD.decode(value.as_str().unwrap_or_default()).unwrap()).unwrap()
+        };
+        let range = move |State(server): State<KvServer>, Json(request): Json<Value>| async move {
+            let (key, range_end) = (decode(&request["key"]), decode(&request["range_end"]));
+            let kvs: Vec<Value> = server
+                .entries
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(k, _)| match range_end.as_str() {
+                    "" => **k == key,
+                    end => k.as_str() >= key.as_str() && k.as_str() < end,
+                })
+                .map(|(k, (value, revision))| {
+                    let (key, value) = (STANDARD.encode(k), STANDARD.encode(value));
+                    serde_json::json!({"key": key, "value": value, "mod_revision": revision.to_string()})
+                })
+                .collect();
+            Json(serde_json::json!({"header": server.header(), "kvs": kvs}))
+        };
+        let put = move |State(server): State<KvServer>, Json(request): Json<Value>| async move {
+            let value = STANDARD.decode(request["value"].as_str().unwrap_or_default()).unwrap();
+            server.write(&decode(&request["key"]), Some(value));
+            Json(serde_json::json!({"header": server.header()}))
+        };
+        let delete = move |State(server): State<KvServer>, Json(request): Json<Value>| async move {
+            server.write(&decode(&request["key"]), None);
+            Json(serde_json::json!({"header": server.header()}))
+        };
+        // 先返回 created，start_revision 之后有修改时返回一批事件，之后保持连接
+        let watch = |State(server): State<KvServer>, Json(request): Json<Value>| async move {
+            let start: u64 = request["create_request"]["start_revision"]
+                .as_str()
+                .and_then(|revision| revision.parse().ok())
+                .unwrap_or_default();
+            let created = serde_json::json!({"result": {"header": server.header(), "created": true}});
+            let mut revisions = server.revision.subscribe();
+            let events = async move {
+                let _ = revisions.wait_for(|revision| *revision >= start).await;
+                let events = serde_json::json!({"result": {"header": server.header(), "events": [{"type": "PUT"}]}});
+                Ok::<_, std::io::Error>(format!("{}\n", events))
+            };
+            let stream = futures_util::stream::once(async move { Ok(format!("{}\n", created)) })
+                .chain(futures_util::stream::once(events))
+                .chain(futures_util::stream::pending());
+            Body::from_stream(stream)
+        };
+        Self::bind(|server| {
+            Router::new()
+                .route("/v3/kv/range", post(range))
+                .route("/v3/kv/put", post(put))
+                .route("/v3/kv/deleterange", post(delete))
+                .route("/v3/watch", post(watch))
+                .with_state(server)
+        })
+        .await
+    }
+
+    pub async fn consul() -> Self {
+        use axum::{
+            body::Bytes,
+            extract::{Path as UrlPath, Query, State},
+            http::{Method, StatusCode},
+            response::IntoResponse,
+            routing::any,
+        };
+        use base64::{Engine, engine::general_purpose::STANDARD};
+
+        let kv = |State(server): State<KvServer>,
+                  method: Method,
+                  UrlPath(key): UrlPath<String>,
+                  Query(query): Query<std::collections::HashMap<String, String>>,
+                  body: Bytes| async move {
+            match method {
+                Method::PUT => {
+                    server.write(&key, Some(body.to_vec()));
+                    return (StatusCode::OK, [("x-consul-index", server.index())], "true".to_string())
+                        .into_response();
+                }
+                Method::DELETE => {
+                    server.write(&key, None);
+                    return (StatusCode::OK, [("x-consul-index", server.index())], "true".to_string())
+                        .into_response();
+                }
+                _ => {}
+            }
+            // blocking query：index 不小于当前 revision 时等到下一次修改或超时
+            if let Some(index) = query.get("index").and_then(|index| index.parse::<u64>().ok()) {
+                let mut revisions = server.revision.subscribe();
+                let _ = tokio::time::timeout(
+                    Duration::from_secs(5),
+                    revisions.wait_for(|revision| *revision > index),
+                )
+                .await;
+            }
+            let recurse = query.contains_key("recurse");
+            let entries: Vec<Value> = server
+                .entries
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(k, _)| if recurse { k.starts_with(&key) } else { **k == key })
+                .map(|(k, (value, revision))| {
+                    serde_json::json!({"Key": k, "Value": STANDARD.encode(value), "ModifyIndex": revision})
+                })
+                .collect();
+            let status = if entries.is_empty() { StatusCode::NOT_FOUND } else { StatusCode::OK };
+            (status, [("x-consul-index", server.index())], Json(entries)).into_response()
+        };
+        Self::bind(|server| {
+            Router::new()
+                .route("/v1/kv/{*key}", any(kv))
+                .with_state(server)
+        })
+        .await
+    }
+
+    fn header(&self) -> Value {
+        serde_json::json!({"revision": self.index()})
+    }
+
+    fn index(&self) -> String {
+        self.revision.borrow().to_string()
+    }
+
+    fn write(&self, key: &str, value: Option<Vec<u8>>) {
+        let mut entries = self.entries.lock().unwrap();
+        self.revision.send_modify(|revision| *revision += 1);
+        let revision = *self.revision.borrow();
+        match value {
+            Some(value) => entries.insert(key.to_string(), (value, revision)),
+            None => entries.remove(key),
+        };
+    }
+
+    // 直接修改键值，模拟其他系统的写入；content 为 None 时删除
+    pub fn set(&self, key: &str, content: Option<&str>) {
+        self.write(key, content.map(|content| content.as_bytes().to_vec()));
+    }
+
+    pub fn get(&self, key: &str) -> Option<String> {
+        let entries = self.entries.lock().unwrap();
+        entries.get(key).map(|(value, _)| String::from_utf8_lossy(value).to_string())
+    }
+}
+
+// (资源类型, 对象名) -> 键 -> 内容
+type KubeObjects = std::sync::Arc<
+    std::sync::Mutex<std::collections::BTreeMap<(String, String), std::collections::BTreeMap<String, String>>>,
+>;
+
+// 模拟 Kubernetes API 中的 ConfigMap 与 Secret（不区分命名空间）：对象保存在内存中，每次写入递增
+// resourceVersion，watch 在之后有修改时返回一个事件，之后保持连接
+#[derive(Clone)]
+pub struct KubeServer {
+    pub url: String,
+    objects: KubeObjects,
+    revision: std::sync::Arc<tokio::sync::watch::Sender<u64>>,
+}
+
+impl KubeServer {
+    pub async fn start() -> Self {
+        use axum::{
+            body::{Body, Bytes},
+            extract::{Path as UrlPath, Query, State},
+            http::{Method, StatusCode},
+            response::IntoResponse,
+            routing::any,
+        };
+
+        let collection = |State(server): State<KubeServer>,
+                          method: Method,
+                          UrlPath((_, resource)): UrlPath<(String, String)>,
+                          Query(query): Query<std::collections::HashMap<String, String>>,
+                          body: Bytes| async move {
+            if method == Method::POST {
+                let object: Value = serde_json::from_slice(&body).unwrap();
+                let name = object["metadata"]["name"].as_str().unwrap();
+                server.patch(&resource, name, &object["data"], true);
+                return (StatusCode::CREATED, Json(server.object(&resource, name).unwrap()))
+                    .into_response();
+            }
+            if query.contains_key("watch") {
+                let start: u64 = query["resourceVersion"].parse().unwrap_or_default();
+                let mut revisions = server.revision.subscribe();
+                let event = async move {
+                    let _ = revisions.wait_for(|revision| *revision > start).await;
+                    Ok::<_, std::io::Error>("{\"type\": \"MODIFIED\", \"object\": {}}\n".to_string())
+                };
+                let stream = futures_util::stream::once(event).chain(futures_util::stream::pending());
+                return Body::from_stream(stream).into_response();
+            }
+            let names: Vec<String> = server
+                .objects
+                .lock()
+                .unwrap()
+                .keys()
+                .filter(|(kind, _)| *kind == resource)
+                .map(|(_, name)| name.clone())
+                .collect();
+            let items: Vec<Value> =
+                names.iter().filter_map(|name| server.object(&resource, name)).collect();
+            let version = server.revision.borrow().to_string();
+            Json(serde_json::json!({"metadata": {"resourceVersion": version}, "items": items}))
+                .into_response()
+        };
+        let object = |State(server): State<KubeServer>,
+                      method: Method,
+                      UrlPath((_, resource, name)): UrlPath<(String, String, String)>,
+                      body: Bytes| async move {
+            if method == Method::PATCH {
+                let patch: Value = serde_json::from_slice(&body).unwrap();
+                if !server.patch(&resource, &name, &patch["data"], false) {
+                    return StatusCode::NOT_FOUND.into_response();
+                }
+            }
+            match server.object(&resource, &name) {
+                Some(object) => Json(object).into_response(),
+                None => StatusCode::NOT_FOUND.into_response(),
+            }
+        };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind kube server");
+        let server = Self {
+            url: format!("http://{}", listener.local_addr().expect("local addr")),
+            objects: Default::default(),
+            revision: std::sync::Arc::new(tokio::sync::watch::channel(1).0),
+        };
+        let app = Router::new()
+            .route("/api/v1/namespaces/{namespace}/{resource}", any(collection))
+            .route("/api/v1/namespaces/{namespace}/{resource}/{name}", any(object))
+            .with_state(server.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        server
+    }
+
+    // Secret 的内容以 base64 返回
+    fn object(&self, resource: &str, name: &str) -> Option<Value> {
+        use base64::{Engine, engine::general_purpose::STANDARD};
+
+        let objects = self.objects.lock().unwrap();
+        let data = objects.get(&(resource.to_string(), name.to_string()))?;
+        let data: serde_json::Map<String, Value> = data
+            .iter()
+            .map(|(key, value)| {
+                let value = match resource {
+                    "secrets" => STANDARD.encode(value),
+                    _ => value.clone(),
+                };
+                (key.clone(), Value::String(value))
+            })
+            .collect();
+        let version = self.revision.borrow().to_string();
+        Some(serde_json::json!({"metadata": {"name": name, "resourceVersion": version}, "data": data}))
+    }
+
+    // merge patch：值为 null 的键被删除；对象不存在且不允许创建时返回 false
+    fn patch(&self, resource: &str, name: &str, data: &Value, create: bool) -> bool {
+        let mut objects = self.objects.lock().unwrap();
+        let id = (resource.to_string(), name.to_string());
+        if !create && !objects.contains_key(&id) {
+            return false;
+        }
+        let object = objects.entry(id).or_default();
+        for (key, value) in data.as_object().into_iter().flatten() {
+            match value.as_str() {
+                Some(value) => object.insert(key.clone(), value.to_string()),
+                None => object.remove(key),
+            };
+        }
+        self.revision.send_modify(|revision| *revision += 1);
+        true
+    }
+
+    // 直接修改对象中的键，模拟 kubectl 的修改；content 为 None 时删除
+    pub fn set(&self, resource: &str, name: &str, key: &str, content: Option<&str>) {
+        self.patch(resource, name, &serde_json::json!({ key: content }), true);
+    }
+
+    pub fn get(&self, resource: &str, name: &str, key: &str) -> Option<String> {
+        let objects = self.objects.lock().unwrap();
+        objects.get(&(resource.to_string(), name.to_string()))?.get(key).cloned()
+    }
+}
@@ -0,0 +1,2520 @@
+use config_manager::{
+    domain::{
+        repositories::configuration_repository::ConfigurationRepository,
+        services::format_converter::FormatConverterService,
+        value_objects::config_path::ConfigPath,
+    },
+    infrastructure::repositories::{
+        file_config_repository::FileConfigRepository, http_config_repository::HttpConfigRepository,
+    },
+    shared::error::ConfigError,
+};
+use config_manager_client::{
+    BindError, ChangeKind, ClientError, ConfigBind, ConfigClient, ConfigUpdate,
+};
+use e2e::{
+    Broker, BrokerReceiver, KubeServer, KvServer, Mode, S3Server, Server, WebhookReceiver, Workspace, eventually,
+};
+use reqwest::Method;
+use serde_json::{Value, json};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+const APP_JSON: &str = r#"{"database": {"host": "localhost", "port": 5432}, "debug": false}"#;
+
+fn data(response: &Value) -> &Value {
+    assert_eq!(response["success"], true, "request failed: {}", response);
+    &response["data"]
+}
+
+// CLI 通过 HTTP 修改配置后，WebSocket 订阅者、webhook、TCP 客户端和审计日志都能看到这次变更
+#[tokio::test]
+async fn cli_update_reaches_every_interface() {
+    let workspace = Workspace::new("update");
+    workspace.write("app.json", APP_JSON);
+    let mut webhook = WebhookReceiver::start().await;
+    let settings = workspace.audit_settings();
+    let http = Server::start(
+        &workspace,
+        Mode::Http,
+        &[
+            "--webhook".to_string(),
+            webhook.url.clone(),
+            "--settings".to_string(),
+            settings.display().to_string(),
+        ],
+    )
+    .await;
+    let tcp = Server::start(&workspace, Mode::Tcp, &[]).await;
+
+    let mut listener = http.listen("app.json").await;
+    let initial = listener.next_of("initial").await;
+    assert_eq!(initial["config"]["database"]["port"], 5432);
+
+    let output = workspace.cli(&[
+        "--server",
+        &http.http_url(),
+        "set",
+        "app.json",
+        "database.port",
+        "6543",
+    ]);
+    assert!(output.success, "cli set failed: {}", output.stderr);
+
+    let update = listener.next_of("update").await;
+    assert_eq!(update["file"], "app.json");
+    assert_eq!(update["config"]["database"]["port"], 6543);
+    assert_eq!(
+        update["patch"],
+        json!([{"op": "replace", "path": "/database/port", "value": 6543}])
+    );
+    assert_eq!(update["source"], "http_api");
+    let etag = http.etag("/api/configs/app.json").await.expect("etag header");
+    assert_eq!(etag, format!("\"{}\"", update["hash"].as_str().unwrap()));
+    listener.close().await;
+
+    let notification = webhook.recv().await;
+    assert_eq!(notification["file"], "app.json");
+    assert!(notification["config"].as_str().unwrap().contains("6543"));
+
+    let reply = eventually("tcp server to reload app.json", async || {
+        let reply = tcp.tcp_request("get app.json").await;
+        reply.contains("6543").then_some(reply)
+    })
+    .await;
+    assert!(reply.contains("localhost"), "unexpected tcp reply: {}", reply);
+
+    let output = workspace.cli(&[
+        "--server",
+        &http.http_url(),
+        "show",
+        "app.json",
+        "-g",
+        "database.port",
+    ]);
+    assert!(output.success, "cli show failed: {}", output.stderr);
+    assert!(output.stdout.contains("6543"), "unexpected output: {}", output.stdout);
+
+    let record = eventually("audit record for the update", async || {
+        workspace
+            .audit_records()
+            .into_iter()
+            .find(|record| record["action"] == "update" && record["config"] == "app.json")
+    })
+    .await;
+    assert_eq!(record["source"], "http_api");
+}
+
+// 库中的 HTTP 客户端新建配置，服务端落盘并对 TCP 客户端可见
+#[tokio::test]
+async fn library_client_creates_config() {
+    let workspace = Workspace::new("client");
+    workspace.write("app.json", APP_JSON);
+    let settings = workspace.audit_settings();
+    let http = Server::start(
+        &workspace,
+        Mode::Http,
+        &["--settings".to_string(), settings.display().to_string()],
+    )
+    .await;
+    let tcp = Server::start(&workspace, Mode::Tcp, &[]).await;
+
+    let client = HttpConfigRepository::new(http.http_url());
+    let config = FormatConverterService::new(
+        ConfigPath::new("service.json".to_string()).unwrap(),
+        r#"{"name": "billing", "replicas": 3}"#.to_string(),
+    )
+    .validate_config()
+    .unwrap();
+    client.save("service.json", config).await.unwrap();
+
+    let fetched = client.get("service.json").await.unwrap();
+    assert_eq!(fetched.to_serde_value(), json!({"name": "billing", "replicas": 3}));
+    let saved: Value = serde_json::from_str(&workspace.read("service.json").unwrap()).unwrap();
+    assert_eq!(saved["replicas"], 3);
+    assert!(client.list().await.unwrap().contains(&"service.json".to_string()));
+
+    eventually("tcp server to load service.json", async || {
+        tcp.tcp_request("get service.json")
+            .await
+            .contains("billing")
+            .then_some(())
+    })
+    .await;
+
+    let record = eventually("audit record for the create", async || {
+        workspace
+            .audit_records()
+            .into_iter()
+            .find(|record| record["config"] == "service.json" && record["source"] == "http_api")
+    })
+    .await;
+    assert_eq!(record["action"], "create");
+}
+
+// 事务中任一文件不存在时整体回滚，已有配置在内存和磁盘上都保持不变
+#[tokio::test]
+async fn failed_transaction_rolls_back() {
+    let workspace = Workspace::new("transaction");
+    workspace.write("app.json", APP_JSON);
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let response = http
+        .post_json(
+            "/api/transactions",
+            &json!({
+                "changes": [
+                    { "file": "app.json", "key": "database.port", "value": 7000 },
+                    { "file": "missing.json", "key": "enabled", "value": true },
+                ]
+            }),
+        )
+        .await;
+    assert_eq!(response["success"], false);
+    assert_eq!(response["code"], 404);
+
+    let current = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&current)["config"]["database"]["port"], 5432);
+    assert_eq!(workspace.read("app.json").unwrap(), APP_JSON);
+
+    let response = http
+        .post_json(
+            "/api/transactions",
+            &json!({
+                "changes": [
+                    { "file": "app.json", "key": "database.port", "value": 7000 },
+                    { "file": "app.json", "key": "debug", "value": true },
+                ]
+            }),
+        )
+        .await;
+    assert_eq!(data(&response)["changes"], 2);
+    let current = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&current)["config"]["database"]["port"], 7000);
+    assert_eq!(data(&current)["config"]["debug"], true);
+}
+
+// CLI 转换出的新格式文件被运行中的服务加载，内容与源文件一致
+#[tokio::test]
+async fn converted_config_is_served() {
+    let workspace = Workspace::new("convert");
+    workspace.write("app.json", APP_JSON);
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let output = workspace.cli(&["convert", "app.json", "app.yaml"]);
+    assert!(output.success, "cli convert failed: {}", output.stderr);
+    assert!(workspace.read("app.yaml").unwrap().contains("host: localhost"));
+
+    let converted = eventually("http server to load app.yaml", async || {
+        let response = http.get_json("/api/configs/app.yaml").await;
+        (response["success"] == true).then(|| response["data"]["config"].clone())
+    })
+    .await;
+    let original = http.get_json("/api/configs/app.json").await;
+    assert_eq!(converted, data(&original)["config"]);
+}
+
+// --error-format json 输出错误变体名、类别和退出码
+#[tokio::test]
+async fn json_errors_report_kind_and_category() {
+    let workspace = Workspace::new("error-format");
+    workspace.write("broken.json", "{");
+
+    let output = workspace.cli(&["--error-format", "json", "show", "broken.json"]);
+    assert!(!output.success);
+    let error: Value = serde_json::from_str(output.stderr.lines().last().unwrap()).unwrap();
+    assert_eq!(error["error"]["kind"], "ParseConfigError");
+    assert_eq!(error["error"]["category"], "parse");
+    assert_eq!(error["error"]["code"], 3);
+}
+
+// validate 直接校验另一个实例通过 HTTP 提供的配置
+#[tokio::test]
+async fn validate_fetches_config_over_http() {
+    let workspace = Workspace::new("remote-validate");
+    workspace.write("app.json", APP_JSON);
+    std::fs::write(
+        workspace.root.join("rules.yaml"),
+        "field_types:\n  database.port: { type: integer, max: 1024 }\n",
+    )
+    .unwrap();
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+    let url = format!("{}/api/configs/app.json", http.http_url());
+    let rules = workspace.root.join("rules.yaml").display().to_string();
+
+    let output = workspace.cli(&["validate", &url, "-v", &rules]);
+    assert!(!output.success);
+    assert!(output.stdout.contains("database.port"), "unexpected output: {}", output.stdout);
+
+    let missing = format!("{}/api/configs/missing.json", http.http_url());
+    let output = workspace.cli(&["validate", &missing]);
+    assert!(!output.success);
+    assert!(output.stdout.contains("not found"), "unexpected output: {}", output.stdout);
+}
+
+// rules/ 下的附加规则同时约束 HTTP 写入和磁盘上的修改，违反时继续提供上一个有效版本
+#[tokio::test]
+async fn attached_rules_reject_invalid_changes() {
+    let workspace = Workspace::new("attached-rules");
+    workspace.write("app.json", APP_JSON);
+    std::fs::create_dir_all(workspace.root.join("rules")).unwrap();
+    std::fs::write(
+        workspace.root.join("rules/app.json.rules.yaml"),
+        "field_types:\n  database.port: { type: integer, max: 9999 }\n",
+    )
+    .unwrap();
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let response = http
+        .put("/api/configs/app.json", r#"{"database": {"host": "localhost", "port": 65000}}"#)
+        .await;
+    assert_eq!(response["success"], false);
+    assert_eq!(response["code"], 409);
+    assert!(response["data"][0].as_str().unwrap().contains("database.port"));
+    assert_eq!(workspace.read("app.json").unwrap(), APP_JSON);
+
+    workspace.write("app.json", r#"{"database": {"host": "db", "port": 70000}}"#);
+    eventually("watcher to reject the change", async || {
+        http.log().contains("config reload rejected").then_some(())
+    })
+    .await;
+    let current = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&current)["config"]["database"]["host"], "localhost");
+
+    let response = http
+        .put("/api/configs/app.json", r#"{"database": {"host": "localhost", "port": 6000}}"#)
+        .await;
+    assert_eq!(data(&response), "Config 'app.json' updated successfully");
+}
+
+// 校验接口只返回结果，不修改已提供的配置
+#[tokio::test]
+async fn validate_endpoint_does_not_store_payload() {
+    let workspace = Workspace::new("validate-endpoint");
+    workspace.write("app.json", APP_JSON);
+    std::fs::create_dir_all(workspace.root.join("rules")).unwrap();
+    std::fs::write(
+        workspace.root.join("rules/app.rules.yaml"),
+        "field_types:\n  database.port: { type: integer, max: 9999 }\n",
+    )
+    .unwrap();
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let payload = json!({"database": {"host": "localhost", "port": 65000}});
+    let response = http.post_json("/api/validate?rules=app.rules.yaml", &payload).await;
+    assert_eq!(data(&response)["is_valid"], false);
+    assert!(data(&response)["errors"][0].as_str().unwrap().contains("database.port"));
+
+    let response = http.post_json("/api/validate?rules=missing.yaml", &payload).await;
+    assert_eq!(response["code"], 404);
+
+    let current = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&current)["config"]["database"]["port"], 5432);
+    assert_eq!(workspace.read("app.json").unwrap(), APP_JSON);
+}
+
+// POST 新建的配置写入磁盘，并带着元数据出现在列表中
+#[tokio::test]
+async fn created_config_is_listed_with_metadata() {
+    let workspace = Workspace::new("create-config");
+    workspace.write("app.json", APP_JSON);
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let request =
+        json!({"name": "service", "content": "name: billing\nreplicas: 3\n", "format": "yaml"});
+    let response = http.post_json("/api/configs", &request).await;
+    assert_eq!(data(&response), "Config 'service.yaml' created successfully");
+    assert!(workspace.read("service.yaml").unwrap().contains("billing"));
+
+    let response = http.post_json("/api/configs", &request).await;
+    assert_eq!(response["code"], 409);
+
+    let list = http.get_json("/api/configs").await;
+    let configs = data(&list).as_array().unwrap();
+    assert_eq!(configs.len(), 2);
+    assert_eq!(configs[0]["name"], "app.json");
+    assert_eq!(configs[0]["format"], "Json");
+    assert_eq!(configs[0]["size"], APP_JSON.len());
+    assert_eq!(configs[0]["key_count"], 3);
+    assert_eq!(configs[1]["name"], "service.yaml");
+    assert_eq!(configs[1]["key_count"], 2);
+    assert!(configs[1]["modified_at"].is_string());
+}
+
+// 按点分路径读写单个值，修改后文件保持原格式并推送给订阅者
+#[tokio::test]
+async fn single_key_patch_is_pushed() {
+    let workspace = Workspace::new("patch-key");
+    workspace.write("app.yaml", "database:\n  host: localhost\n  port: 5432\n");
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let response = http.get_json("/api/configs/app.yaml/keys/database.port").await;
+    assert_eq!(data(&response)["value"], 5432);
+    let response = http.get_json("/api/configs/app.yaml/keys/database.user").await;
+    assert_eq!(response["code"], 404);
+
+    let mut listener = http.listen("app.yaml").await;
+    listener.next_of("initial").await;
+    let response =
+        http.patch("/api/configs/app.yaml/keys/database.port", "application/json", "6543").await;
+    assert_eq!(data(&response), "Key 'database.port' in 'app.yaml' updated successfully");
+
+    let update = listener.next_of("update").await;
+    assert_eq!(update["file"], "app.yaml");
+    assert_eq!(update["config"]["database"]["port"], 6543);
+    listener.close().await;
+
+    let saved = workspace.read("app.yaml").unwrap();
+    assert!(saved.contains("port: 6543"), "unexpected file: {}", saved);
+    let response = http.get_json("/api/configs/app.yaml/keys/database").await;
+    assert_eq!(data(&response)["value"], json!({"host": "localhost", "port": 6543}));
+}
+
+// 一个连接订阅多个文件，键路径订阅只在对应子树变化时收到更新
+#[tokio::test]
+async fn websocket_subscribes_to_files_and_key_paths() {
+    let workspace = Workspace::new("ws-subscribe");
+    workspace.write("app.yaml", "database:\n  port: 5432\nlog:\n  level: info\n");
+    workspace.write("cache.json", r#"{"ttl": 60}"#);
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let mut listener = http.websocket().await;
+    listener.next_of("connected").await;
+    listener.send(json!({"type": "subscribe", "file": "app.yaml", "key": "database.port"})).await;
+    let subscribed = listener.next_of("subscribed").await;
+    assert_eq!(subscribed["value"], 5432);
+    listener.send(json!({"type": "subscribe", "file": "cache.json"})).await;
+    let subscribed = listener.next_of("subscribed").await;
+    assert_eq!(subscribed["config"], json!({"ttl": 60}));
+    listener.send(json!({"type": "subscribe", "file": "missing.yaml"})).await;
+    let error = listener.next_of("error").await;
+    assert_eq!(error["file"], "missing.yaml");
+
+    // 修改订阅子树之外的键不推送
+    http.patch("/api/configs/app.yaml/keys/log.level", "application/json", r#""debug""#).await;
+    http.patch("/api/configs/app.yaml/keys/database.port", "application/json", "6543").await;
+    let update = listener.next_of("update").await;
+    assert_eq!(update["file"], "app.yaml");
+    assert_eq!(update["key"], "database.port");
+    assert_eq!(update["value"], 6543);
+
+    workspace.write("cache.json", r#"{"ttl": 120}"#);
+    let update = listener.next_of("update").await;
+    assert_eq!(update["file"], "cache.json");
+    assert_eq!(update["config"], json!({"ttl": 120}));
+    assert_eq!(update["patch"], json!([{"op": "replace", "path": "/ttl", "value": 120}]));
+    assert_eq!(update["source"], "file_watcher");
+
+    listener.send(json!({"type": "unsubscribe", "file": "app.yaml"})).await;
+    listener.next_of("unsubscribed").await;
+    http.patch("/api/configs/app.yaml/keys/database.port", "application/json", "7654").await;
+    workspace.write("cache.json", r#"{"ttl": 180}"#);
+    let update = listener.next_of("update").await;
+    assert_eq!(update["file"], "cache.json");
+    listener.close().await;
+}
+
+// SSE 推送当前配置和之后的更新，带 Last-Event-ID 重连时补发断线期间的更新
+#[tokio::test]
+async fn sse_streams_updates_and_resumes() {
+    let workspace = Workspace::new("sse");
+    workspace.write("app.yaml", "database:\n  port: 5432\n");
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let mut events = http.events("app.yaml", None).await;
+    let initial = events.next_of("initial").await;
+    assert_eq!(initial["config"]["database"]["port"], 5432);
+
+    http.patch("/api/configs/app.yaml/keys/database.port", "application/json", "6543").await;
+    let update = events.next_of("update").await;
+    assert_eq!(update["config"]["database"]["port"], 6543);
+    assert_eq!(update["source"], "http_api");
+    let last_seq = update["seq"].as_u64().unwrap();
+    drop(events);
+
+    http.patch("/api/configs/app.yaml/keys/database.port", "application/json", "7000").await;
+    let mut events = http.events("app.yaml", Some(last_seq)).await;
+    loop {
+        let (event, data) = events.next_event().await;
+        assert_eq!(event, "update", "resumed stream should not start over: {}", data);
+        if data["config"]["database"]["port"] == 7000 {
+            break;
+        }
+    }
+}
+
+// 长轮询：版本没有变化时超时返回 304，配置更新后立即返回新版本
+#[tokio::test]
+async fn long_poll_returns_when_version_changes() {
+    let workspace = Workspace::new("long-poll");
+    workspace.write("app.yaml", "database:\n  port: 5432\n");
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let current = http.get_json("/api/configs/app.yaml").await;
+    let version = current["data"]["version"].as_u64().unwrap();
+    let (status, body) = http.watch("app.yaml", version, 1).await;
+    assert_eq!(status, 304);
+    assert!(body.is_none());
+
+    let (watched, _) = tokio::join!(http.watch("app.yaml", version, 30), async {
+        http.patch("/api/configs/app.yaml/keys/database.port", "application/json", "6543").await
+    });
+    let (status, body) = watched;
+    assert_eq!(status, 200);
+    let body = body.unwrap();
+    assert_eq!(body["data"]["config"]["database"]["port"], 6543);
+    let changed = body["data"]["version"].as_u64().unwrap();
+    assert!(changed > version);
+
+    // 已是旧版本时不等待；一次写入可能触发多次修改事件，版本只会更大
+    let (status, body) = http.watch("app.yaml", version, 30).await;
+    assert_eq!(status, 200);
+    assert!(body.unwrap()["data"]["version"].as_u64().unwrap() >= changed);
+}
+
+// 补丁作用于当前版本，测试操作失败时整个补丁都不生效
+#[tokio::test]
+async fn json_and_merge_patches_apply_atomically() {
+    let workspace = Workspace::new("patch-config");
+    workspace.write("app.json", APP_JSON);
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let patch = r#"[
+        {"op": "replace", "path": "/database/port", "value": 6543},
+        {"op": "test", "path": "/database/host", "value": "db.internal"}
+    ]"#;
+    let response = http.patch("/api/configs/app.json", "application/json-patch+json", patch).await;
+    assert_eq!(response["code"], 409);
+    assert_eq!(workspace.read("app.json").unwrap(), APP_JSON);
+
+    let patch = r#"[
+        {"op": "test", "path": "/database/host", "value": "localhost"},
+        {"op": "replace", "path": "/database/port", "value": 6543}
+    ]"#;
+    let response = http.patch("/api/configs/app.json", "application/json-patch+json", patch).await;
+    assert_eq!(data(&response), "Config 'app.json' patched successfully");
+
+    let patch = r#"{"debug": null, "cache": {"ttl": 60}}"#;
+    let response = http.patch("/api/configs/app.json", "application/merge-patch+json", patch).await;
+    assert_eq!(data(&response), "Config 'app.json' patched successfully");
+
+    let current = http.get_json("/api/configs/app.json").await;
+    assert_eq!(
+        data(&current)["config"],
+        json!({"database": {"host": "localhost", "port": 6543}, "cache": {"ttl": 60}})
+    );
+    let saved: Value = serde_json::from_str(&workspace.read("app.json").unwrap()).unwrap();
+    assert_eq!(saved, data(&current)["config"]);
+
+    let response = http.patch("/api/configs/app.json", "text/plain", "{}").await;
+    assert_eq!(response["code"], 415);
+}
+
+// 两个编辑者基于同一版本修改，后提交的一方收到 412 而不是覆盖前者
+#[tokio::test]
+async fn stale_if_match_is_rejected() {
+    let workspace = Workspace::new("etag");
+    workspace.write("app.json", APP_JSON);
+    let http = Server::start(&workspace, Mode::Http, &["--require-if-match".to_string()]).await;
+
+    let response = http.put("/api/configs/app.json", r#"{"debug": true}"#).await;
+    assert_eq!(response["code"], 428);
+
+    let etag = http.etag("/api/configs/app.json").await.expect("etag header");
+    let first = r#"{"database": {"host": "localhost", "port": 6543}, "debug": false}"#;
+    let response = http.put_if_match("/api/configs/app.json", &etag, first).await;
+    assert_eq!(data(&response), "Config 'app.json' updated successfully");
+
+    let second = r#"{"database": {"host": "localhost", "port": 5432}, "debug": true}"#;
+    let response = http.put_if_match("/api/configs/app.json", &etag, second).await;
+    assert_eq!(response["code"], 412);
+
+    let current = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&current)["config"]["database"]["port"], 6543);
+    let etag = http.etag("/api/configs/app.json").await.expect("etag header");
+    let response = http.put_if_match("/api/configs/app.json", &etag, second).await;
+    assert_eq!(data(&response), "Config 'app.json' updated successfully");
+}
+
+// X-Consistency 只接受默认的 linearizable 并在响应中回显，其余级别和无法识别的级别返回 400
+#[tokio::test]
+async fn read_consistency_rejects_levels_that_need_a_cluster() {
+    let workspace = Workspace::new("consistency");
+    workspace.write("app.json", APP_JSON);
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&response)["consistency"], "linearizable");
+    let response = http.get_with_header("/api/configs/app.json", "x-consistency", "Linearizable").await;
+    assert_eq!(data(&response)["consistency"], "linearizable");
+    assert_eq!(data(&response)["config"]["database"]["port"], 5432);
+
+    // 其余级别需要集群模式，单节点明确拒绝而不是按线性一致读返回
+    for header in ["leader-local", "Any-Replica", "bounded-staleness=250ms"] {
+        let response = http.get_with_header("/api/configs/app.json", "x-consistency", header).await;
+        assert_eq!(response["code"], 400, "{}", header);
+        assert!(response["message"].as_str().unwrap().contains("cluster mode"), "{}", header);
+    }
+
+    for header in ["eventual", "bounded-staleness=soon"] {
+        let response = http.get_with_header("/api/configs/app.json", "x-consistency", header).await;
+        assert_eq!(response["code"], 400, "{}", header);
+        assert!(response["message"].as_str().unwrap().contains("invalid read consistency"));
+    }
+}
+
+// 文件监听器记录每次修改，回滚后原样恢复当时的文件内容
+#[tokio::test]
+async fn history_records_changes_and_rolls_back() {
+    let workspace = Workspace::new("history");
+    workspace.write("app.yaml", "# primary database\nport: 5432\n");
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    workspace.write("app.yaml", "# primary database\nport: 6543\n");
+    let history = eventually("history to record the change", async || {
+        let response = http.get_json("/api/configs/app.yaml/history").await;
+        let versions = data(&response).as_array().unwrap().clone();
+        let latest = versions.last()?["version"].as_u64()?;
+        let version = http.get_json(&format!("/api/configs/app.yaml/versions/{}", latest)).await;
+        let content = data(&version)["content"].as_str()?.to_string();
+        content.contains("6543").then_some(versions)
+    })
+    .await;
+    assert_eq!(history[0]["version"], 1);
+    assert!(history[0].get("content").is_none());
+
+    let version = http.get_json("/api/configs/app.yaml/versions/1").await;
+    assert_eq!(data(&version)["content"], "# primary database\nport: 5432\n");
+
+    // CLI 通过 --server 读取同一份历史，本地读取配置目录下的 .history/
+    let server = http.http_url();
+    let output = workspace.cli(&["--server", &server, "history", "app.yaml"]);
+    assert!(output.success, "cli history failed: {}", output.stderr);
+    assert!(output.stdout.contains("v1"), "unexpected output: {}", output.stdout);
+    for args in [vec!["--server", &server], vec![]] {
+        let output = workspace.cli(&[args.as_slice(), &["history", "app.yaml", "--version", "1"]].concat());
+        assert!(output.success, "cli history failed: {}", output.stderr);
+        assert_eq!(output.stdout, "# primary database\nport: 5432\n");
+    }
+    let output = workspace.cli(&["--server", &server, "history", "app.yaml", "--version", "9"]);
+    assert!(!output.success);
+    assert!(output.stderr.contains("version 9 of app.yaml not found"), "unexpected error: {}", output.stderr);
+    let output = workspace.cli(&["--server", &server, "get", "app.yaml", "port"]);
+    assert!(output.success, "cli get failed: {}", output.stderr);
+    assert!(output.stdout.contains("6543"), "unexpected output: {}", output.stdout);
+
+    let response = http.post_json("/api/configs/app.yaml/rollback/1", &json!(null)).await;
+    assert_eq!(data(&response), "Config 'app.yaml' rolled back to version 1");
+    assert_eq!(workspace.read("app.yaml").unwrap(), "# primary database\nport: 5432\n");
+    let current = http.get_json("/api/configs/app.yaml").await;
+    assert_eq!(data(&current)["config"]["port"], 5432);
+
+    let response = http.post_json("/api/configs/app.yaml/rollback/9", &json!(null)).await;
+    assert_eq!(response["code"], 404);
+}
+
+// 通过 --server 重命名键时，改名记入历史并受附加规则约束；目标键已存在时拒绝
+#[tokio::test]
+async fn refactor_rename_through_server_keeps_history_and_rules() {
+    let workspace = Workspace::new("refactor-rename");
+    workspace.write("app.json", APP_JSON);
+    std::fs::create_dir_all(workspace.root.join("rules")).unwrap();
+    std::fs::write(
+        workspace.root.join("rules/app.json.rules.yaml"),
+        "required_fields: [database.host]\n",
+    )
+    .unwrap();
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+    let server = http.http_url();
+    let rename = |from: &str, to: &str| {
+        workspace.cli(&["--server", &server, "refactor", "rename", "--from", from, "--to", to, "app.json"])
+    };
+
+    let output = rename("database.port", "database.primary_port");
+    assert!(output.success, "rename failed: {}", output.stderr);
+    let current = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&current)["config"]["database"]["primary_port"], 5432);
+    assert!(data(&current)["config"]["database"].get("port").is_none());
+    let history = eventually("history to record the rename", async || {
+        let response = http.get_json("/api/configs/app.json/history").await;
+        let versions = data(&response).as_array().unwrap().clone();
+        let latest = versions.last()?["version"].as_u64()?;
+        let version = http.get_json(&format!("/api/configs/app.json/versions/{}", latest)).await;
+        let content = data(&version)["content"].as_str()?.to_string();
+        content.contains("primary_port").then_some(versions)
+    })
+    .await;
+    let first = history[0]["version"].as_u64().unwrap();
+    let version = http.get_json(&format!("/api/configs/app.json/versions/{}", first)).await;
+    assert!(data(&version)["content"].as_str().unwrap().contains("\"port\""));
+
+    // 附加规则要求的键不能被改名移走
+    let output = rename("database.host", "database.hostname");
+    assert!(!output.success);
+    assert!(output.stderr.contains("database.host"), "unexpected error: {}", output.stderr);
+    let current = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&current)["config"]["database"]["host"], "localhost");
+
+    let output = rename("database.primary_port", "database.host");
+    assert!(!output.success);
+    assert!(output.stderr.contains("key already exists: database.host"), "unexpected error: {}", output.stderr);
+    let current = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&current)["config"]["database"]["primary_port"], 5432);
+}
+
+// TCP 客户端的 SET 同样受附加规则约束，并能校验配置、查看历史和按键路径订阅
+#[tokio::test]
+async fn tcp_commands_match_http() {
+    let workspace = Workspace::new("tcp-commands");
+    workspace.write("app.json", APP_JSON);
+    std::fs::create_dir_all(workspace.root.join("rules")).unwrap();
+    std::fs::write(
+        workspace.root.join("rules/app.json.rules.yaml"),
+        "field_types:\n  database.port: { type: integer, max: 9999 }\n",
+    )
+    .unwrap();
+    std::fs::write(
+        workspace.root.join("rules/strict.yaml"),
+        "field_types:\n  database.port: { type: integer, max: 1000 }\n",
+    )
+    .unwrap();
+    let tcp = Server::start(&workspace, Mode::Tcp, &[]).await;
+
+    let reply = tcp.tcp_request("validate app.json strict.yaml").await;
+    let result: Value = serde_json::from_str(&reply).unwrap();
+    assert_eq!(result["is_valid"], false);
+    assert!(result["errors"][0].as_str().unwrap().contains("database.port"));
+
+    let (mut subscriber, initial) = tcp.tcp_subscribe("app.json", &["database.port"]).await;
+    assert_eq!(initial["values"]["database.port"], 5432);
+
+    let reply = tcp.tcp_request("set app.json database.port 65000").await;
+    assert!(reply.starts_with("set failed"), "{}", reply);
+    assert_eq!(workspace.read("app.json").unwrap(), APP_JSON);
+
+    // 只修改其他键时不推送，下一次推送就是端口的变化
+    let reply = tcp.tcp_request("set app.json database.host db").await;
+    assert_eq!(reply, "set database.host in app.json");
+    // 等文件监听器处理完这次写入，否则两次写入会在去抖窗口内合并为一个版本
+    eventually("host change to be loaded", async || {
+        let reply = tcp.tcp_request("history app.json").await;
+        let versions: Vec<Value> = serde_json::from_str(&reply).ok()?;
+        (versions.len() >= 2).then_some(())
+    })
+    .await;
+    tcp.tcp_request("set app.json database.port 6543").await;
+    let update: Value = serde_json::from_str(&e2e::read_frame(&mut subscriber).await).unwrap();
+    assert_eq!(update["key"], "database.port");
+    assert_eq!(update["value"], 6543);
+    assert_eq!(update["source"], "tcp_client");
+
+    let history = eventually("history to record the change", async || {
+        let reply = tcp.tcp_request("history app.json").await;
+        let versions: Vec<Value> = serde_json::from_str(&reply).ok()?;
+        (versions.len() >= 3).then_some(versions)
+    })
+    .await;
+    assert_eq!(history[0]["version"], 1);
+
+    // ADD 重新加载文件时同样检查附加规则，违反时内存中的配置保持不变
+    workspace.write("app.json", &APP_JSON.replace("5432", "65000"));
+    let reply = tcp.tcp_request("add app.json").await;
+    assert!(reply.starts_with("config validate failed"), "{}", reply);
+    assert!(reply.contains("database.port"), "{}", reply);
+    let reply = tcp.tcp_request("get app.json").await;
+    assert!(reply.contains("6543"), "{}", reply);
+}
+
+// LISTEN 连接定期收到 ping：回复 pong 的连接一直保留，不回复的连接空闲超时后被断开
+#[tokio::test]
+async fn tcp_listeners_heartbeat_and_idle_timeout() {
+    let workspace = Workspace::new("tcp-heartbeat");
+    workspace.write("app.json", APP_JSON);
+    let args = ["--tcp-heartbeat", "1", "--tcp-idle-timeout", "3"].map(String::from);
+    let tcp = Server::start(&workspace, Mode::Tcp, &args).await;
+
+    let mut idle = tcp.tcp_listen("app.json").await;
+    let mut alive = tcp.tcp_listen("app.json").await;
+    for _ in 0..4 {
+        assert_eq!(e2e::read_frame(&mut alive).await, "ping");
+        alive.get_mut().write_all(b"pong\n").await.unwrap();
+    }
+    let mut received = Vec::new();
+    tokio::time::timeout(std::time::Duration::from_secs(10), idle.read_to_end(&mut received))
+        .await
+        .expect("idle listener to be closed")
+        .unwrap();
+    assert!(String::from_utf8_lossy(&received).contains("ping"));
+
+    workspace.write("app.json", r#"{"database": {"host": "db", "port": 5432}, "debug": false}"#);
+    let update = loop {
+        let frame = e2e::read_frame(&mut alive).await;
+        if frame != "ping" {
+            break frame;
+        }
+        alive.get_mut().write_all(b"pong\n").await.unwrap();
+    };
+    assert!(update.contains("\"db\""), "{}", update);
+
+    // 客户端关闭连接后，即使没有推送也会注销订阅
+    drop(alive);
+    eventually("server to notice the closed listener", async || {
+        tcp.log().contains("disconnected").then_some(())
+    })
+    .await;
+}
+
+// --uds 时 HTTP 与 TCP 服务都只监听 Unix socket
+#[tokio::test]
+async fn serve_listens_on_unix_socket() {
+    let workspace = Workspace::new("uds");
+    workspace.write("app.json", APP_JSON);
+
+    let socket = workspace.root.join("http.sock");
+    let http = Server::start_unix(&workspace, Mode::Http, &socket).await;
+    let mut stream = tokio::net::UnixStream::connect(&socket).await.unwrap();
+    let request = "GET /api/configs/app.json HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n";
+    stream.write_all(format!("{}\r\n", request).as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    assert!(response.starts_with("HTTP/1.1 200"), "{}", response);
+    assert!(response.contains("5432"));
+    assert!(tokio::net::TcpStream::connect(("127.0.0.1", http.port)).await.is_err());
+
+    let socket = workspace.root.join("tcp.sock");
+    let _tcp = Server::start_unix(&workspace, Mode::Tcp, &socket).await;
+    let stream = tokio::net::UnixStream::connect(&socket).await.unwrap();
+    let mut stream = tokio::io::BufReader::new(stream);
+    stream.get_mut().write_all(b"get app.json\n").await.unwrap();
+    assert!(e2e::read_frame(&mut stream).await.contains("5432"));
+}
+
+// chroot 之后按宿主机路径重新打开文件的功能无法工作，serve 在绑定端口前直接拒绝
+#[tokio::test]
+async fn serve_rejects_chroot_with_host_paths() {
+    let workspace = Workspace::new("chroot");
+    workspace.write("app.json", APP_JSON);
+    let root = workspace.root.display().to_string();
+    let config_dir = workspace.config_dir().display().to_string();
+    let port = e2e::free_port().to_string();
+    let settings = workspace.audit_settings().display().to_string();
+
+    let output = workspace.cli(&[
+        "serve", "-p", &port, "-c", &config_dir, "--chroot", &root, "--settings", &settings,
+        "--log-rotate", "daily",
+    ]);
+    assert!(!output.success);
+    for feature in ["--settings hot reload", "a file audit sink", "log rotation"] {
+        assert!(output.stderr.contains(feature), "{}: {}", feature, output.stderr);
+    }
+}
+
+// HTTP 修改的审计记录带有客户端地址和键级差异，并能按配置名查询
+#[tokio::test]
+async fn audit_records_actor_and_diff() {
+    let workspace = Workspace::new("audit-query");
+    workspace.write("app.json", APP_JSON);
+    workspace.write("other.json", r#"{"enabled": true}"#);
+    let settings = workspace.audit_settings();
+    let http = Server::start(
+        &workspace,
+        Mode::Http,
+        &["--settings".to_string(), settings.display().to_string()],
+    )
+    .await;
+
+    let body = r#"{"database": {"host": "localhost", "port": 6543}, "debug": false}"#;
+    let response = http.put("/api/configs/app.json", body).await;
+    assert_eq!(data(&response), "Config 'app.json' updated successfully");
+    let response = http.put("/api/configs/other.json", r#"{"enabled": false}"#).await;
+    assert_eq!(data(&response), "Config 'other.json' updated successfully");
+
+    let record = eventually("audit record for the update", async || {
+        let response = http.get_json("/api/audit?config=app.json").await;
+        data(&response)
+            .as_array()?
+            .iter()
+            .find(|record| record["source"] == "http_api")
+            .cloned()
+    })
+    .await;
+    assert_eq!(record["action"], "update");
+    assert!(record["actor"].as_str().unwrap().starts_with("127.0.0.1:"));
+    assert_eq!(
+        record["changes"],
+        json!([{"key": "database.port", "kind": "changed", "old": 5432, "new": 6543}])
+    );
+
+    let response = http.get_json("/api/audit?since=2100-01-01T00:00:00Z").await;
+    assert_eq!(data(&response), &json!([]));
+}
+
+// 启用 API key 后未携带密钥的请求被拒绝，只读密钥不能修改配置
+#[tokio::test]
+async fn api_keys_enforce_scopes() {
+    let workspace = Workspace::new("api-keys");
+    workspace.write("app.json", APP_JSON);
+    let settings = workspace.audit_settings();
+    let mut content = std::fs::read_to_string(&settings).unwrap();
+    content.push_str(
+        "api_keys:\n  - name: dashboard\n    key: read-key\n    scopes: [read]\n  \
+         - name: deploy\n    key: write-key\n    scopes: [read, write]\n",
+    );
+    std::fs::write(&settings, content).unwrap();
+    let http = Server::start(
+        &workspace,
+        Mode::Http,
+        &["--settings".to_string(), settings.display().to_string()],
+    )
+    .await;
+
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(response["code"], 401);
+    let response = http
+        .send_with_key(Method::GET, "/api/configs/app.json", "wrong-key", "")
+        .await;
+    assert_eq!(response["code"], 401);
+    let response = http
+        .send_with_key(Method::GET, "/api/configs/app.json", "read-key", "")
+        .await;
+    assert_eq!(data(&response)["config"]["database"]["port"], 5432);
+
+    let body = r#"{"database": {"host": "localhost", "port": 6543}, "debug": false}"#;
+    let response = http
+        .send_with_key(Method::PUT, "/api/configs/app.json", "read-key", body)
+        .await;
+    assert_eq!(response["code"], 403);
+    let response = http
+        .send_with_key(Method::DELETE, "/api/configs/app.json", "read-key", "")
+        .await;
+    assert_eq!(response["code"], 403);
+    assert!(workspace.read("app.json").is_some());
+
+    let response = http
+        .send_with_key(Method::PUT, "/api/configs/app.json", "write-key", body)
+        .await;
+    assert_eq!(data(&response), "Config 'app.json' updated successfully");
+    let record = eventually("audit record for the update", async || {
+        workspace
+            .audit_records()
+            .into_iter()
+            .find(|record| record["source"] == "http_api")
+    })
+    .await;
+    assert_eq!(record["actor"], "deploy");
+}
+
+// 同一份访问策略同时约束 HTTP 和 TCP：只能访问角色模式匹配的配置
+#[tokio::test]
+async fn policy_limits_access_per_namespace() {
+    let workspace = Workspace::new("policy");
+    workspace.write("team-a-app.json", r#"{"replicas": 1}"#);
+    workspace.write("team-b-app.json", r#"{"replicas": 1}"#);
+    let settings = workspace.write_server_file(
+        "settings.yaml",
+        "api_keys:\n  - name: alice\n    key: alice-key\n    scopes: [read, write]\n",
+    );
+    let policy = workspace.write_server_file(
+        "policy.yaml",
+        "roles:\n  team-a-editor:\n    - configs: \"team-a-*\"\n      permissions: [write]\n\
+         bindings:\n  alice: [team-a-editor]\n  anonymous: [team-a-editor]\n",
+    );
+    let args = [
+        "--settings".to_string(),
+        settings.display().to_string(),
+        "--policy".to_string(),
+        policy.display().to_string(),
+    ];
+    let http = Server::start(&workspace, Mode::Http, &args).await;
+    let tcp = Server::start(&workspace, Mode::Tcp, &args).await;
+
+    let response = http.send_with_key(Method::GET, "/api/configs", "alice-key", "").await;
+    let names: Vec<&str> = data(&response)
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|summary| summary["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["team-a-app.json"]);
+
+    let response = http
+        .send_with_key(Method::PUT, "/api/configs/team-b-app.json", "alice-key", "{}")
+        .await;
+    assert_eq!(response["code"], 403);
+    let response = http
+        .send_with_key(Method::DELETE, "/api/configs/team-a-app.json", "alice-key", "")
+        .await;
+    assert_eq!(response["code"], 403);
+    let body = r#"{"replicas": 2}"#;
+    let response = http
+        .send_with_key(Method::PUT, "/api/configs/team-a-app.json", "alice-key", body)
+        .await;
+    assert_eq!(data(&response), "Config 'team-a-app.json' updated successfully");
+    let response = http.send_with_key(Method::GET, "/api/admin/cache", "alice-key", "").await;
+    assert_eq!(response["code"], 403);
+
+    assert!(tcp.tcp_request("get team-b-app.json").await.starts_with("access denied"));
+    assert_eq!(
+        tcp.tcp_request("set team-b-app.json replicas 3").await,
+        "access denied: anonymous has no write permission on team-b-app.json"
+    );
+    assert!(tcp.tcp_request("get team-a-app.json").await.contains("replicas"));
+}
+
+// 浏览器预检请求无需 API key 即可通过，普通响应带有 CORS 和安全头
+#[tokio::test]
+async fn cors_preflight_and_security_headers() {
+    let workspace = Workspace::new("cors");
+    workspace.write("app.json", APP_JSON);
+    let settings = workspace.write_server_file(
+        "settings.yaml",
+        "api_keys:\n  - name: dashboard\n    key: read-key\n    scopes: [read]\n\
+         cors:\n  allowed_origins: [\"https://dashboard.example.com\"]\n",
+    );
+    let http = Server::start(
+        &workspace,
+        Mode::Http,
+        &["--settings".to_string(), settings.display().to_string()],
+    )
+    .await;
+    let url = format!("{}/api/configs/app.json", http.http_url());
+    let client = reqwest::Client::new();
+
+    let preflight = client
+        .request(Method::OPTIONS, &url)
+        .header("origin", "https://dashboard.example.com")
+        .header("access-control-request-method", "PUT")
+        .header("access-control-request-headers", "authorization, if-match")
+        .send()
+        .await
+        .unwrap();
+    let headers = preflight.headers();
+    assert_eq!(headers["access-control-allow-origin"], "https://dashboard.example.com");
+    assert!(headers["access-control-allow-methods"].to_str().unwrap().contains("PUT"));
+
+    let response = client
+        .get(&url)
+        .header("origin", "https://dashboard.example.com")
+        .bearer_auth("read-key")
+        .send()
+        .await
+        .unwrap();
+    let headers = response.headers();
+    assert_eq!(headers["access-control-allow-origin"], "https://dashboard.example.com");
+    assert_eq!(headers["access-control-expose-headers"], "etag");
+    assert_eq!(headers["x-content-type-options"], "nosniff");
+    assert_eq!(headers["x-frame-options"], "DENY");
+    assert!(headers.get("strict-transport-security").is_none());
+
+    let response = client
+        .get(&url)
+        .header("origin", "https://elsewhere.example.com")
+        .bearer_auth("read-key")
+        .send()
+        .await
+        .unwrap();
+    assert!(response.headers().get("access-control-allow-origin").is_none());
+}
+
+// 超出令牌桶的请求收到 429，超大的请求体收到 413；TCP 使用同样的限制
+#[tokio::test]
+async fn rate_and_size_limits_are_enforced() {
+    let workspace = Workspace::new("limits");
+    workspace.write("app.json", APP_JSON);
+    let args = [
+        "--rate-limit".to_string(),
+        "0.5".to_string(),
+        "--rate-burst".to_string(),
+        "2".to_string(),
+        "--max-body-size".to_string(),
+        "128".to_string(),
+    ];
+    let http = Server::start(&workspace, Mode::Http, &args).await;
+    let tcp = Server::start(&workspace, Mode::Tcp, &args).await;
+
+    let large = format!(r#"{{"debug": "{}"}}"#, "x".repeat(256));
+    let response = http.put("/api/configs/app.json", &large).await;
+    assert_eq!(response["code"], 413);
+    assert_eq!(workspace.read("app.json").unwrap(), APP_JSON);
+
+    for _ in 0..2 {
+        let response = http.get_json("/api/configs/app.json").await;
+        assert_eq!(data(&response)["config"]["debug"], false);
+    }
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(response["code"], 429);
+
+    assert!(tcp.tcp_request("get app.json").await.contains("database"));
+    assert!(tcp.tcp_request("get app.json").await.contains("database"));
+    assert!(tcp.tcp_request("get app.json").await.starts_with("rate limit exceeded"));
+}
+
+// 探针无需 API key；启动加载完成后 readyz 返回 200，配置目录不可访问时 healthz 返回 503
+#[tokio::test]
+async fn health_and_readiness_probes() {
+    let workspace = Workspace::new("probes");
+    workspace.write("app.json", APP_JSON);
+    let settings = workspace.write_server_file(
+        "settings.yaml",
+        "api_keys:\n  - name: dashboard\n    key: read-key\n    scopes: [read]\n",
+    );
+    let http = Server::start(
+        &workspace,
+        Mode::Http,
+        &["--settings".to_string(), settings.display().to_string()],
+    )
+    .await;
+    let probe = async |path: &str| {
+        let response = reqwest::get(format!("{}{}", http.http_url(), path)).await.unwrap();
+        (response.status().as_u16(), response.json::<Value>().await.unwrap())
+    };
+
+    let report = eventually("server to become ready", async || {
+        let (status, body) = probe("/readyz").await;
+        (status == 200).then_some(body)
+    })
+    .await;
+    assert_eq!(data(&report)["configs"], 1);
+    assert_eq!(data(&report)["watcher"]["alive"], true);
+    let (status, _) = probe("/healthz").await;
+    assert_eq!(status, 200);
+
+    std::fs::rename(workspace.config_dir(), workspace.root.join("moved")).unwrap();
+    let (status, body) = probe("/healthz").await;
+    assert_eq!(status, 503);
+    assert_eq!(body["data"]["config_dir"]["accessible"], false);
+    let (status, _) = probe("/readyz").await;
+    assert_eq!(status, 503);
+}
+
+// SIGTERM 时服务端通知 WebSocket 和 LISTEN 订阅者、写完日志后以 0 退出
+#[tokio::test]
+async fn sigterm_notifies_subscribers_and_exits_cleanly() {
+    let workspace = Workspace::new("shutdown");
+    workspace.write("app.json", APP_JSON);
+
+    let mut http = Server::start(&workspace, Mode::Http, &[]).await;
+    eventually("config to load", async || {
+        let response = http.get_json("/api/configs").await;
+        (response["data"].as_array()?.len() == 1).then_some(())
+    })
+    .await;
+    let mut listener = http.listen("app.json").await;
+    listener.next_of("initial").await;
+    let status = http.terminate().await;
+    assert_eq!(listener.next_of("shutdown").await["message"], "server shutting down");
+    assert!(status.success(), "http server exited with {}:\n{}", status, http.log());
+    assert!(workspace.app_log().unwrap().contains("server shutting down"));
+
+    let mut tcp = Server::start(&workspace, Mode::Tcp, &[]).await;
+    let mut stream = tcp.tcp_listen("app.json").await;
+    let status = tcp.terminate().await;
+    assert_eq!(e2e::read_frame(&mut stream).await, "server shutting down");
+    assert!(status.success(), "tcp server exited with {}:\n{}", status, tcp.log());
+}
+
+// 命名空间：各子目录的同名配置互相隔离，命名空间订阅只收到本命名空间的更新
+#[tokio::test]
+async fn namespaces_isolate_configs_and_notifications() {
+    let workspace = Workspace::new("namespaces");
+    workspace.write("app.json", APP_JSON);
+    workspace.write("team-a/app.json", r#"{"database": {"port": 1111}}"#);
+    workspace.write("team-b/app.json", r#"{"database": {"port": 2222}}"#);
+    let settings = workspace.write_server_file("settings.yaml", "namespaces: [team-a, team-b]\n");
+    let http = Server::start(
+        &workspace,
+        Mode::Http,
+        &["--settings".to_string(), settings.display().to_string()],
+    )
+    .await;
+
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["port"], 5432);
+    let response = http.get_json("/api/namespaces/team-a/configs/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["port"], 1111);
+    let response = http.get_json("/api/namespaces/team-b/configs/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["port"], 2222);
+    let response = http.get_json("/api/namespaces/team-c/configs/app.json").await;
+    assert_eq!(response["code"], 404);
+
+    let response = http.get_json("/api/namespaces").await;
+    assert_eq!(
+        data(&response),
+        &json!([{"name": "team-a", "configs": 1}, {"name": "team-b", "configs": 1}])
+    );
+    let response = http.get_json("/api/namespaces/team-a/configs").await;
+    assert_eq!(data(&response)[0]["name"], "app.json");
+
+    let mut events = http.namespace_events("team-a").await;
+    let initial = events.next_of("initial").await;
+    assert_eq!(initial["configs"]["team-a/app.json"]["config"]["database"]["port"], 1111);
+
+    // 另一个命名空间的修改不会推送给 team-a 的订阅者
+    workspace.write("team-b/app.json", r#"{"database": {"port": 2223}}"#);
+    eventually("team-b reload", async || {
+        let response = http.get_json("/api/namespaces/team-b/configs/app.json").await;
+        (response["data"]["config"]["database"]["port"] == 2223).then_some(())
+    })
+    .await;
+    let response = http
+        .put("/api/namespaces/team-a/configs/app.json", r#"{"database": {"port": 1112}}"#)
+        .await;
+    assert_eq!(data(&response), "Config 'team-a/app.json' updated successfully");
+    let update = events.next_of("update").await;
+    assert_eq!(update["file"], "team-a/app.json");
+    assert_eq!(update["config"]["database"]["port"], 1112);
+    assert!(workspace.read("team-a/app.json").unwrap().contains("1112"));
+    assert!(workspace.read("app.json").unwrap().contains("5432"));
+}
+
+// bundle export/import 在环境之间迁移一个命名空间的配置和附加规则，HTTP 导入的修改推送给订阅者
+#[tokio::test]
+async fn bundles_move_namespaces_between_environments() {
+    let source = Workspace::new("bundle-source");
+    source.write("app.json", APP_JSON);
+    source.write("team-a/app.json", r#"{"database": {"port": 1111}}"#);
+    source.write("team-a/db/cache.yaml", "ttl: 60\n");
+    std::fs::create_dir_all(source.root.join("rules/team-a")).unwrap();
+    std::fs::write(
+        source.root.join("rules/team-a/app.json.rules.yaml"),
+        "field_types:\n  database.port: { type: integer, max: 9999 }\n",
+    )
+    .unwrap();
+
+    let output = source.cli(&["bundle", "export", "-n", "team-a", "-o", "../team-a.tar.gz"]);
+    assert!(output.success, "{:?}", output);
+    assert!(output.stdout.contains("2 configs, 3 files"), "{}", output.stdout);
+
+    // 导入时沿用导出时的命名空间，附加规则写入目标的 rules/ 目录
+    let target = Workspace::new("bundle-target");
+    let bundle = source.root.join("team-a.tar.gz").display().to_string();
+    let output = target.cli(&["bundle", "import", &bundle]);
+    assert!(!output.success, "unsigned bundle accepted without --allow-unsigned");
+    let output = target.cli(&["bundle", "import", &bundle, "--allow-unsigned"]);
+    assert!(output.success, "{:?}", output);
+    assert!(target.read("team-a/app.json").unwrap().contains("1111"));
+    assert_eq!(target.read("team-a/db/cache.yaml").unwrap(), "ttl: 60\n");
+    assert!(target.read("app.json").is_none());
+    assert!(target.root.join("rules/team-a/app.json.rules.yaml").is_file());
+
+    let settings = "namespaces: [team-a]\n";
+    let source_settings = source.write_server_file("settings.yaml", settings);
+    let source_http = Server::start(
+        &source,
+        Mode::Http,
+        &["--settings".to_string(), source_settings.display().to_string()],
+    )
+    .await;
+    let target_settings = target.write_server_file("settings.yaml", settings);
+    let target_http = Server::start(
+        &target,
+        Mode::Http,
+        &["--settings".to_string(), target_settings.display().to_string()],
+    )
+    .await;
+
+    let response = source_http
+        .put("/api/namespaces/team-a/configs/app.json", r#"{"database": {"port": 1112}}"#)
+        .await;
+    data(&response);
+    let response = reqwest::get(format!("{}/api/admin/bundle?namespace=team-b", source_http.http_url()))
+        .await
+        .unwrap();
+    let body: Value = response.json().await.unwrap();
+    assert_eq!(body["code"], 404);
+    let response = reqwest::get(format!("{}/api/admin/bundle?namespace=team-a", source_http.http_url()))
+        .await
+        .unwrap();
+    assert_eq!(response.headers()["content-type"], "application/gzip");
+    let bundle = response.bytes().await.unwrap();
+
+    let mut listener = target_http.listen("team-a/app.json").await;
+    listener.next_of("initial").await;
+    let import = |bundle: Vec<u8>| {
+        let url = format!("{}/api/admin/bundle", target_http.http_url());
+        async move {
+            reqwest::Client::new()
+                .post(url)
+                .body(bundle)
+                .send()
+                .await
+                .unwrap()
+                .json::<Value>()
+                .await
+                .unwrap()
+        }
+    };
+    let response = import(bundle.to_vec()).await;
+    assert_eq!(data(&response), &json!(["team-a/app.json", "team-a/db/cache.yaml"]));
+    let update = listener.next_of("update").await;
+    assert_eq!(update["config"]["database"]["port"], 1112);
+    assert!(target.read("team-a/app.json").unwrap().contains("1112"));
+
+    // 违反附加规则的 bundle 整体拒绝，不写入任何文件
+    source.write("team-a/app.json", r#"{"database": {"port": 65000}}"#);
+    let output = source.cli(&["bundle", "export", "-n", "team-a", "-o", "../invalid.tar.gz"]);
+    assert!(output.success, "{:?}", output);
+    let response = import(std::fs::read(source.root.join("invalid.tar.gz")).unwrap()).await;
+    assert_eq!(response["code"], 422);
+    assert!(response["data"][0].as_str().unwrap().contains("database.port"));
+    assert!(target.read("team-a/app.json").unwrap().contains("1112"));
+    let response = import(b"not a bundle".to_vec()).await;
+    assert_eq!(response["code"], 400);
+}
+
+// 定时备份按保留策略清理旧备份，丢失配置后可以从本地目录或 S3 中的备份恢复
+#[tokio::test]
+async fn scheduled_backups_rotate_and_restore() {
+    let workspace = Workspace::new("backups");
+    workspace.write("app.json", APP_JSON);
+    let backups = workspace.root.join("backups");
+    let settings = workspace.write_server_file(
+        "settings.yaml",
+        &format!(
+            "backup:\n  schedule: \"@every 300ms\"\n  retention: {{ keep_last: 2 }}\n  store: dir\n  path: {}\n",
+            backups.display()
+        ),
+    );
+    let mut http = Server::start(
+        &workspace,
+        Mode::Http,
+        &["--settings".to_string(), settings.display().to_string()],
+    )
+    .await;
+    eventually("old backups to expire", async || {
+        http.log().contains("expired and was deleted").then_some(())
+    })
+    .await;
+    let response = http.get_json("/api/backups").await;
+    let listed = data(&response).as_array().unwrap().len();
+    assert!((2..=3).contains(&listed), "{}", response);
+    let files = std::fs::read_dir(&backups).unwrap().count();
+    assert!(files <= 3, "{} backups left", files);
+    http.terminate().await;
+
+    let s3 = S3Server::start().await;
+    let settings = workspace.write_server_file(
+        "settings.yaml",
+        &format!(
+            "backup:\n  schedule: \"0 0 1 1 *\"\n  store: s3\n  bucket: backups\n  prefix: prod/\n  endpoint: {}\n  region: eu-west-1\n  access_key: test\n  secret_key: secret\n",
+            s3.endpoint
+        ),
+    );
+    let http = Server::start(
+        &workspace,
+        Mode::Http,
+        &["--settings".to_string(), settings.display().to_string()],
+    )
+    .await;
+    let response = http.post_json("/api/backups/restore", &json!({})).await;
+    assert_eq!(response["code"], 404);
+    let response = http.post_json("/api/backups", &json!({})).await;
+    let name = data(&response)["name"].as_str().unwrap().to_string();
+    assert!(name.starts_with("config-") && name.ends_with(".tar.gz"), "{}", name);
+    assert_eq!(s3.keys("backups"), vec![format!("prod/{}", name)]);
+
+    let response = http.put("/api/configs/app.json", r#"{"database": {"port": 6000}}"#).await;
+    data(&response);
+    let response = http.post_json("/api/backups/restore", &json!({"backup": name})).await;
+    assert_eq!(data(&response), &json!(["app.json"]));
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["port"], 5432);
+
+    // 配置文件丢失后恢复最近一份备份
+    std::fs::remove_file(workspace.config_dir().join("app.json")).unwrap();
+    eventually("watcher to drop the deleted config", async || {
+        let response = http.get_json("/api/configs/app.json").await;
+        (response["code"] == 404).then_some(())
+    })
+    .await;
+    let response = http.post_json("/api/backups/restore", &json!({})).await;
+    assert_eq!(data(&response), &json!(["app.json"]));
+    assert_eq!(workspace.read("app.json").unwrap(), APP_JSON);
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["host"], "localhost");
+    let response = http
+        .post_json("/api/backups/restore", &json!({"backup": "config-20200101T000000.000Z.tar.gz"}))
+        .await;
+    assert_eq!(response["code"], 404);
+}
+
+// --storage s3:// 时配置目录与 bucket 双向同步：启动时拉取，接口写入上传，远端修改按 ETag 轮询推送给订阅者
+#[tokio::test]
+async fn s3_storage_syncs_configs_with_bucket() {
+    let workspace = Workspace::new("s3-storage");
+    workspace.write("local.json", r#"{"feature": true}"#);
+    let s3 = S3Server::start().await;
+    s3.set("configs", "prod/app.json", Some(APP_JSON));
+    s3.set("configs", "prod/notes.txt", Some("not a config"));
+    let http = Server::start_with_env(
+        &workspace,
+        Mode::Http,
+        &[
+            "--storage".to_string(),
+            "s3://configs/prod".to_string(),
+            "--storage-poll-interval".to_string(),
+            "200".to_string(),
+        ],
+        &[
+            ("AWS_ENDPOINT_URL", &s3.endpoint),
+            ("AWS_ACCESS_KEY_ID", "test"),
+            ("AWS_SECRET_ACCESS_KEY", "secret"),
+        ],
+    )
+    .await;
+    assert_eq!(workspace.read("app.json").unwrap(), APP_JSON);
+    assert!(workspace.read("notes.txt").is_none());
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["port"], 5432);
+    // 只存在于本地的配置启动时上传
+    assert_eq!(
+        s3.get("configs", "prod/local.json").as_deref(),
+        Some(r#"{"feature": true}"#)
+    );
+
+    let mut listener = http.listen("app.json").await;
+    listener.next_of("initial").await;
+    let response = http.put("/api/configs/app.json", r#"{"database": {"port": 6000}}"#).await;
+    data(&response);
+    listener.next_of("update").await;
+    eventually("api write to reach the bucket", async || {
+        s3.get("configs", "prod/app.json")?.contains("6000").then_some(())
+    })
+    .await;
+
+    s3.set("configs", "prod/app.json", Some(r#"{"database": {"port": 7000}}"#));
+    let update = listener.next_of("update").await;
+    assert_eq!(update["config"]["database"]["port"], 7000);
+    assert_eq!(workspace.read("app.json").unwrap(), r#"{"database": {"port": 7000}}"#);
+
+    s3.set("configs", "prod/local.json", None);
+    eventually("remote delete to remove the config", async || {
+        let response = http.get_json("/api/configs/local.json").await;
+        (response["code"] == 404).then_some(())
+    })
+    .await;
+    assert!(workspace.read("local.json").is_none());
+    assert_eq!(s3.keys("configs"), vec!["prod/app.json", "prod/notes.txt"]);
+}
+
+// --storage sqlite: 时配置、历史版本、审计记录和附加规则保存在数据库中，换一个空的配置目录重启后全部恢复
+#[tokio::test]
+async fn sqlite_storage_keeps_configs_history_and_audit() {
+    let workspace = Workspace::new("sqlite-storage");
+    workspace.write("app.json", APP_JSON);
+    std::fs::create_dir_all(workspace.root.join("rules")).unwrap();
+    std::fs::write(
+        workspace.root.join("rules/app.json.rules.yaml"),
+        "field_types:\n  database.port: { type: integer, max: 9999 }\n",
+    )
+    .unwrap();
+    let database = workspace.root.join("state/configs.db");
+    let storage = ["--storage".to_string(), format!("sqlite:{}", database.display())];
+    let mut http = Server::start(&workspace, Mode::Http, &storage).await;
+    let response = http
+        .put("/api/configs/app.json", r#"{"database": {"host": "localhost", "port": 6000}}"#)
+        .await;
+    data(&response);
+    eventually("history to record the update", async || {
+        let response = http.get_json("/api/configs/app.json/history").await;
+        (data(&response).as_array()?.len() == 2).then_some(())
+    })
+    .await;
+    assert!(!workspace.config_dir().join(".history").exists());
+    http.terminate().await;
+
+    let restored = Workspace::new("sqlite-storage-restored");
+    let http = Server::start(&restored, Mode::Http, &storage).await;
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["port"], 6000);
+    assert!(restored.read("app.json").unwrap().contains("6000"));
+    let response = http.get_json("/api/configs/app.json/versions/1").await;
+    assert_eq!(data(&response)["content"], APP_JSON);
+    let response = http.get_json("/api/audit?config=app.json").await;
+    let records = data(&response).as_array().unwrap();
+    assert!(records.iter().any(|record| record["source"] == "http_api"), "{}", response);
+
+    // 附加规则从数据库恢复到新配置目录旁的 rules/
+    assert!(restored.root.join("rules/app.json.rules.yaml").is_file());
+    let response = http
+        .put("/api/configs/app.json", r#"{"database": {"host": "localhost", "port": 65000}}"#)
+        .await;
+    assert_eq!(response["code"], 409);
+}
+
+// 文件仓储以目录为根，按相对路径读写；watch 产出被修改的配置名称
+#[tokio::test]
+async fn file_repository_reads_writes_and_watches_by_name() {
+    let workspace = Workspace::new("file-repository");
+    workspace.write("app.json", APP_JSON);
+    let repository = FileConfigRepository::new(workspace.config_dir().display().to_string());
+    let mut watch = repository.watch().await.unwrap();
+
+    let config = repository.get("app.json").await.unwrap();
+    repository.save("team-a/app.json", config).await.unwrap();
+    assert_eq!(repository.list().await.unwrap(), ["app.json", "team-a/app.json"]);
+    let saved: Value = serde_json::from_str(&workspace.read("team-a/app.json").unwrap()).unwrap();
+    assert_eq!(saved["database"]["port"], 5432);
+
+    repository.delete("app.json").await.unwrap();
+    assert!(matches!(
+        repository.delete("app.json").await,
+        Err(ConfigError::ConfigNotFound(_))
+    ));
+    let mut changed = Vec::new();
+    while !changed.contains(&"app.json".to_string()) {
+        let name = tokio::time::timeout(std::time::Duration::from_secs(5), watch.next())
+            .await
+            .expect("watch event")
+            .unwrap();
+        changed.push(name);
+    }
+    assert!(changed.contains(&"team-a/app.json".to_string()));
+}
+
+// --storage etcd:// 与 consul:// 镜像 KV 中前缀下的配置：watch 到的修改推送给订阅者，接口写入写回 KV；
+// --storage-read-only 时本地修改不写回
+#[tokio::test]
+async fn kv_storage_mirrors_etcd_and_consul() {
+    for (scheme, kv) in [("etcd", KvServer::etcd().await), ("consul", KvServer::consul().await)] {
+        kv.set("config/app.json", Some(APP_JSON));
+        kv.set("config/README", Some("not a config"));
+        kv.set("other/app.json", Some(r#"{"other": true}"#));
+        let storage = format!("{}://{}/config", scheme, kv.endpoint);
+        let workspace = Workspace::new(&format!("{}-storage", scheme));
+        let mut http = Server::start(&workspace, Mode::Http, &["--storage".to_string(), storage.clone()]).await;
+        let response = http.get_json("/api/configs/app.json").await;
+        assert_eq!(data(&response)["config"]["database"]["port"], 5432, "{}", scheme);
+        assert!(workspace.read("README").is_none());
+
+        let mut listener = http.listen("app.json").await;
+        listener.next_of("initial").await;
+        kv.set("config/app.json", Some(r#"{"database": {"port": 7000}}"#));
+        let update = listener.next_of("update").await;
+        assert_eq!(update["config"]["database"]["port"], 7000, "{}", scheme);
+
+        let response = http.put("/api/configs/feature.json", r#"{"enabled": true}"#).await;
+        data(&response);
+        eventually("api write to reach the kv store", async || {
+            kv.get("config/feature.json")?.contains("enabled").then_some(())
+        })
+        .await;
+        kv.set("config/app.json", None);
+        eventually("kv delete to remove the config", async || {
+            let response = http.get_json("/api/configs/app.json").await;
+            (response["code"] == 404).then_some(())
+        })
+        .await;
+        assert_eq!(kv.get("other/app.json").as_deref(), Some(r#"{"other": true}"#));
+        http.terminate().await;
+
+        let workspace = Workspace::new(&format!("{}-storage-read-only", scheme));
+        let args = ["--storage".to_string(), storage, "--storage-read-only".to_string()];
+        let http = Server::start(&workspace, Mode::Http, &args).await;
+        let response = http.get_json("/api/configs/feature.json").await;
+        assert_eq!(data(&response)["config"]["enabled"], true, "{}", scheme);
+        let response = http.put("/api/configs/local.json", r#"{"local": true}"#).await;
+        data(&response);
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        assert!(kv.get("config/local.json").is_none(), "{}", scheme);
+    }
+}
+
+// --storage k8s://namespace 同步 ConfigMap 与 Secret 中的配置文件键：ConfigMap billing 的键 app.json
+// 对应配置 billing/app.json，Secret 对应 secrets/billing/...；集群中的修改经 watch 推送给订阅者，
+// 接口写入以 merge patch 写回 ConfigMap，ConfigMap 不存在时创建
+#[tokio::test]
+async fn k8s_storage_syncs_configmaps_and_secrets() {
+    let kube = KubeServer::start().await;
+    kube.set("configmaps", "billing", "app.json", Some(APP_JSON));
+    kube.set("configmaps", "billing", "README", Some("not a config"));
+    kube.set("secrets", "billing", "credentials.yaml", Some("password: s3cret\n"));
+    let workspace = Workspace::new("k8s-storage");
+    let args = ["--storage".to_string(), "k8s://team-a".to_string()];
+    let http =
+        Server::start_with_env(&workspace, Mode::Http, &args, &[("KUBERNETES_API_URL", &kube.url)]).await;
+    let response = http.get_json("/api/configs/billing/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["port"], 5432);
+    let response = http.get_json("/api/configs/secrets/billing/credentials.yaml").await;
+    assert_eq!(data(&response)["config"]["password"], "s3cret");
+    assert!(workspace.read("billing/README").is_none());
+
+    let mut listener = http.listen("billing/app.json").await;
+    listener.next_of("initial").await;
+    kube.set("configmaps", "billing", "app.json", Some(r#"{"database": {"port": 7000}}"#));
+    let update = listener.next_of("update").await;
+    assert_eq!(update["config"]["database"]["port"], 7000);
+
+    data(&http.put("/api/configs/billing/feature.json", r#"{"enabled": true}"#).await);
+    data(&http.put("/api/configs/payments/app.json", r#"{"currency": "EUR"}"#).await);
+    eventually("api writes to reach the configmaps", async || {
+        kube.get("configmaps", "billing", "feature.json")?;
+        kube.get("configmaps", "payments", "app.json")?.contains("EUR").then_some(())
+    })
+    .await;
+    assert_eq!(kube.get("configmaps", "billing", "app.json").as_deref(), Some(r#"{"database": {"port": 7000}}"#));
+
+    kube.set("configmaps", "billing", "app.json", None);
+    eventually("configmap key removal to delete the config", async || {
+        let response = http.get_json("/api/configs/billing/app.json").await;
+        (response["code"] == 404).then_some(())
+    })
+    .await;
+}
+
+// --log-format json 时运行日志每行一个 JSON 对象；PUT /api/admin/log-level 按模块修改级别，
+// 无效的过滤规则返回 400；日志文件超过 --log-max-size 时切分，只保留 --log-keep 个
+#[tokio::test]
+async fn json_logs_rotate_and_levels_change_at_runtime() {
+    let workspace = Workspace::new("logging");
+    workspace.write("app.json", APP_JSON);
+    let log_file = workspace.write_server_file("server.log", "");
+    let args: Vec<String> = [
+        "--log-format", "json", "--log-filter", "info", "--log-file", &log_file.display().to_string(),
+        "--log-max-size", "150", "--log-keep", "2",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    let http = Server::start(&workspace, Mode::Http, &args).await;
+    let line = http.log().lines().find(|line| line.contains("listening on")).map(str::to_string).unwrap();
+    let line: Value = serde_json::from_str(&line).unwrap();
+    assert_eq!(line["level"], "INFO");
+
+    let response = http.get_json("/api/admin/log-level").await;
+    assert_eq!(data(&response)["filter"], "info");
+    let request = json!({"level": "warn", "modules": {"config_manager::interfaces::http": "debug"}});
+    let response = http.put_json("/api/admin/log-level", &request).await;
+    let filter = data(&response)["filter"].as_str().unwrap().to_string();
+    assert!(filter.contains("warn") && filter.contains("config_manager::interfaces::http=debug"), "{}", filter);
+    assert!(http.log().contains("log filter changed"));
+    let response = http.put_json("/api/admin/log-level", &json!({"modules": {"config_manager[": "info"}})).await;
+    assert_eq!(response["code"], 400);
+    assert_eq!(data(&http.get_json("/api/admin/log-level").await)["filter"], filter.as_str());
+
+    let rotated = || {
+        let dir = log_file.parent().unwrap();
+        std::fs::read_dir(dir)
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with("server.log."))
+            .count()
+    };
+    // 每次修改记录一行日志，间隔开避免监听器合并事件
+    for port in 1..=6 {
+        data(&http.put("/api/configs/app.json", &format!(r#"{{"database": {{"port": {}}}}}"#, port)).await);
+        tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    }
+    eventually("old log files to be pruned", async || (rotated() == 2).then_some(())).await;
+    let content = std::fs::read_to_string(&log_file).unwrap();
+    for line in content.lines() {
+        let entry: Value = serde_json::from_str(line).unwrap();
+        assert_eq!(entry["level"], "info");
+    }
+}
+
+// 子目录中的配置以相对路径为名称，HTTP 路径中可以直接带 "/"
+#[tokio::test]
+async fn nested_configs_use_relative_paths() {
+    let workspace = Workspace::new("nested");
+    workspace.write("app.json", APP_JSON);
+    workspace.write("services/api/app.json", r#"{"database": {"port": 1111}}"#);
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let response = http.get_json("/api/configs").await;
+    let names: Vec<&str> = data(&response)
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|summary| summary["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["app.json", "services/api/app.json"]);
+    let response = http.get_json("/api/configs/services/api/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["port"], 1111);
+    let response = http.get_json("/api/configs/services/api/app.json/keys/database.port").await;
+    assert_eq!(data(&response)["value"], 1111);
+
+    let response = http
+        .put("/api/configs/services/api/app.json", r#"{"database": {"port": 1112}}"#)
+        .await;
+    assert_eq!(data(&response), "Config 'services/api/app.json' updated successfully");
+    assert!(workspace.read("services/api/app.json").unwrap().contains("1112"));
+    assert!(workspace.read("app.json").unwrap().contains("5432"));
+    let response = http.get_json("/api/configs/services/api/app.json/history").await;
+    assert!(!data(&response).as_array().unwrap().is_empty());
+
+    let request = json!({"name": "services/web/app", "format": "yaml", "content": "port: 80\n"});
+    let response = http.post_json("/api/configs", &request).await;
+    assert_eq!(data(&response), "Config 'services/web/app.yaml' created successfully");
+    assert_eq!(workspace.read("services/web/app.yaml").unwrap(), "port: 80\n");
+
+    // 启动后新建的子目录同样被监听
+    workspace.write("jobs/nightly/cron.json", r#"{"schedule": "0 3 * * *"}"#);
+    eventually("new nested config", async || {
+        let response = http.get_json("/api/configs/jobs/nightly/cron.json").await;
+        (response["data"]["config"]["schedule"] == "0 3 * * *").then_some(())
+    })
+    .await;
+
+    let response = http.get_json("/api/configs/..%2Fsecret.json").await;
+    assert_eq!(response["code"], 404);
+    let request = json!({"name": "../secret.json", "content": "{}"});
+    let response = http.post_json("/api/configs", &request).await;
+    assert_eq!(response["code"], 400);
+}
+
+// 接口写入先写临时文件再重命名：并发读取始终读到完整的配置，写完后不留下临时文件
+#[tokio::test]
+async fn writes_replace_config_files_atomically() {
+    let workspace = Workspace::new("atomic-write");
+    workspace.write("app.json", APP_JSON);
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let done = std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false));
+    let reader = {
+        let (path, done) = (workspace.config_dir().join("app.json"), done.clone());
+        std::thread::spawn(move || {
+            let mut reads = 0;
+            while !done.load(std::sync::atomic::Ordering::Relaxed) {
+                let content = std::fs::read_to_string(&path).expect("read config");
+                let parsed: Result<Value, _> = serde_json::from_str(&content);
+                assert!(parsed.is_ok(), "read a partial file: {:?}", content);
+                reads += 1;
+            }
+            reads
+        })
+    };
+    for port in 6000..6040 {
+        let padding = "x".repeat(64 * 1024);
+        let body = json!({"database": {"host": "localhost", "port": port}, "padding": padding});
+        let response = http.put("/api/configs/app.json", &body.to_string()).await;
+        assert_eq!(data(&response), "Config 'app.json' updated successfully");
+    }
+    done.store(true, std::sync::atomic::Ordering::Relaxed);
+    assert!(reader.join().expect("reader thread") > 0);
+
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["port"], 6039);
+    let leftovers: Vec<String> = std::fs::read_dir(workspace.config_dir())
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|name| name.ends_with(".cm-tmp"))
+        .collect();
+    assert!(leftovers.is_empty(), "temp files left behind: {:?}", leftovers);
+}
+
+// 文件监听器按文件去抖：连续写入只推送最后的内容，内容不变的重写不推送
+#[tokio::test]
+async fn file_watcher_debounces_and_skips_unchanged_content() {
+    let workspace = Workspace::new("debounce");
+    workspace.write("app.json", APP_JSON);
+    let args = ["--watch-debounce".to_string(), "300".to_string()];
+    let http = Server::start(&workspace, Mode::Http, &args).await;
+
+    let mut listener = http.listen("app.json").await;
+    listener.next_of("initial").await;
+    for port in 7000..7010 {
+        workspace.write("app.json", &format!(r#"{{"database": {{"port": {}}}}}"#, port));
+        tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+    }
+    let update = listener.next_of("update").await;
+    assert_eq!(update["config"]["database"]["port"], 7009);
+    assert_eq!(update["source"], "file_watcher");
+
+    // 只改了格式，解析后的内容不变
+    workspace.write("app.json", "{\n  \"database\": {\n    \"port\": 7009\n  }\n}\n");
+    tokio::time::sleep(std::time::Duration::from_millis(600)).await;
+    workspace.write("app.json", r#"{"database": {"port": 7100}}"#);
+    let update = listener.next_of("update").await;
+    assert_eq!(update["config"]["database"]["port"], 7100);
+    assert_eq!(
+        update["patch"],
+        json!([{"op": "replace", "path": "/database/port", "value": 7100}])
+    );
+    listener.close().await;
+}
+
+// 目录中新建、删除和重命名配置文件后，缓存随之更新并推送 created / deleted
+#[tokio::test]
+async fn file_watcher_tracks_created_deleted_and_renamed_files() {
+    let workspace = Workspace::new("file-events");
+    workspace.write("app.json", APP_JSON);
+    std::fs::create_dir_all(workspace.config_dir().join("team-a")).unwrap();
+    let settings = workspace.write_server_file("settings.yaml", "namespaces: [team-a]\n");
+    let http = Server::start(
+        &workspace,
+        Mode::Http,
+        &["--settings".to_string(), settings.display().to_string()],
+    )
+    .await;
+
+    let mut events = http.namespace_events("team-a").await;
+    events.next_of("initial").await;
+    workspace.write("team-a/new.json", r#"{"port": 1}"#);
+    let created = events.next_of("created").await;
+    assert_eq!(created["file"], "team-a/new.json");
+    assert_eq!(created["config"]["port"], 1);
+    let response = http.get_json("/api/namespaces/team-a/configs/new.json").await;
+    assert_eq!(data(&response)["config"]["port"], 1);
+
+    let dir = workspace.config_dir().join("team-a");
+    std::fs::rename(dir.join("new.json"), dir.join("renamed.json")).unwrap();
+    let mut renamed = [events.next_event().await, events.next_event().await];
+    renamed.sort_by(|a, b| a.0.cmp(&b.0));
+    assert_eq!(renamed[0].0, "created");
+    assert_eq!(renamed[0].1["file"], "team-a/renamed.json");
+    assert_eq!(renamed[0].1["config"]["port"], 1);
+    assert_eq!(renamed[1].0, "deleted");
+    assert_eq!(renamed[1].1["file"], "team-a/new.json");
+    let response = http.get_json("/api/namespaces/team-a/configs/new.json").await;
+    assert_eq!(response["code"], 404);
+
+    let mut listener = http.listen("app.json").await;
+    listener.next_of("initial").await;
+    std::fs::remove_file(workspace.config_dir().join("app.json")).unwrap();
+    let deleted = listener.next_of("deleted").await;
+    assert_eq!(deleted["file"], "app.json");
+    assert_eq!(deleted["source"], "file_watcher");
+    listener.close().await;
+    eventually("deleted config to leave the cache", async || {
+        let response = http.get_json("/api/configs/app.json").await;
+        (response["code"] == 404).then_some(())
+    })
+    .await;
+    let response = http.get_json("/api/configs").await;
+    let names: Vec<&str> = data(&response)
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|summary| summary["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["team-a/renamed.json"]);
+}
+
+// poll 模式定时扫描配置目录，按内容哈希发现修改、新建和删除
+#[tokio::test]
+async fn poll_watcher_detects_changes_without_notifications() {
+    let workspace = Workspace::new("poll-watcher");
+    workspace.write("app.json", APP_JSON);
+    let args = ["--watch-mode", "poll", "--watch-poll-interval", "100"].map(String::from);
+    let http = Server::start(&workspace, Mode::Http, &args).await;
+
+    let response = reqwest::get(format!("{}/readyz", http.http_url())).await.unwrap();
+    let report: Value = response.json().await.unwrap();
+    assert_eq!(data(&report)["watcher"]["mode"], "poll");
+
+    let mut listener = http.listen("app.json").await;
+    listener.next_of("initial").await;
+    workspace.write("app.json", r#"{"database": {"host": "localhost", "port": 6543}}"#);
+    let update = listener.next_of("update").await;
+    assert_eq!(update["config"]["database"]["port"], 6543);
+    assert_eq!(update["source"], "file_watcher");
+    std::fs::remove_file(workspace.config_dir().join("app.json")).unwrap();
+    let deleted = listener.next_of("deleted").await;
+    assert_eq!(deleted["file"], "app.json");
+    listener.close().await;
+
+    workspace.write("extra.json", r#"{"enabled": true}"#);
+    eventually("polled config to be loaded", async || {
+        let response = http.get_json("/api/configs/extra.json").await;
+        (response["data"]["config"]["enabled"] == true).then_some(())
+    })
+    .await;
+}
+
+// 懒加载：启动时只登记文件，首次访问时解析，超出内存预算时整个条目被淘汰，
+// 被淘汰的配置修改后仍推送给订阅者
+#[tokio::test]
+async fn lazy_load_parses_on_access_and_evicts_cold_configs() {
+    let workspace = Workspace::new("lazy-load");
+    workspace.write("app.json", APP_JSON);
+    workspace.write("feature.json", r#"{"enabled": true}"#);
+    workspace.write("service.yaml", "name: billing\nreplicas: 3\n");
+    let args = ["--lazy-load", "--memory-budget", "1"].map(String::from);
+    let http = Server::start(&workspace, Mode::Http, &args).await;
+
+    let list = http.get_json("/api/configs").await;
+    let configs = data(&list).as_array().unwrap();
+    assert_eq!(configs.len(), 3);
+    assert_eq!(configs[2]["format"], "Yaml");
+    assert!(configs[0].get("key_count").is_none());
+    let metrics = http.get_json("/api/admin/cache").await;
+    assert_eq!(data(&metrics)["lazy"], true);
+    assert_eq!(data(&metrics)["unloaded_entries"], 3);
+
+    let mut listener = http.listen("app.json").await;
+    let initial = listener.next_of("initial").await;
+    assert_eq!(initial["config"]["database"]["port"], 5432);
+    let list = http.get_json("/api/configs").await;
+    assert_eq!(data(&list)[0]["key_count"], 3);
+
+    // 预算只容纳最近访问的一个配置，读取其他配置后 app.json 回到未加载状态
+    let response = http.get_json("/api/configs/feature.json").await;
+    assert_eq!(data(&response)["config"]["enabled"], true);
+    let metrics = http.get_json("/api/admin/cache").await;
+    assert_eq!(data(&metrics)["unloaded_entries"], 2);
+    assert!(data(&metrics)["evictions"].as_u64().unwrap() >= 1);
+
+    workspace.write("app.json", r#"{"database": {"host": "localhost", "port": 6543}}"#);
+    let update = listener.next_of("update").await;
+    assert_eq!(update["config"]["database"]["port"], 6543);
+    assert_eq!(update["patch"][0]["op"], "replace");
+    assert_eq!(update["patch"][0]["path"], "");
+    listener.close().await;
+}
+
+// 管理接口返回已加载的配置、订阅者、监听器和缓存状态；监听器没有发现的修改可以通过 reload 重新扫描
+#[tokio::test]
+async fn admin_state_reports_runtime_and_reload_rescans() {
+    let workspace = Workspace::new("admin-state");
+    workspace.write("app.json", APP_JSON);
+    let args = ["--watch-mode", "poll", "--watch-poll-interval", "60000"].map(String::from);
+    let http = Server::start(&workspace, Mode::Http, &args).await;
+    let mut listener = http.listen("app.json").await;
+    listener.next_of("initial").await;
+
+    let state = http.get_json("/api/admin/state").await;
+    let state = data(&state);
+    assert_eq!(state["configs"][0]["name"], "app.json");
+    assert_eq!(state["configs"][0]["size"], APP_JSON.len());
+    assert_eq!(state["configs"][0]["state"], "loaded");
+    assert!(state["configs"][0]["version"].is_string());
+    assert_eq!(state["clients"][0]["transport"], "ws");
+    assert!(state["clients"][0]["subscriptions"].get("app.json").is_some());
+    assert_eq!(state["watcher"]["mode"], "poll");
+    assert_eq!(state["cache"]["entries"], 1);
+    assert!(state["uptime_secs"].as_i64().unwrap() >= 0);
+    let version = state["configs"][0]["version"].clone();
+
+    workspace.write("app.json", r#"{"database": {"host": "db", "port": 5432}, "debug": false}"#);
+    workspace.write("feature.json", r#"{"enabled": true}"#);
+    let status = http.post_json("/api/admin/reload", &json!({})).await;
+    assert_eq!(data(&status)["state"], "completed");
+    assert_eq!(data(&status)["total"], 2);
+    assert_eq!(data(&status)["changed"], 2);
+    let update = listener.next_of("update").await;
+    assert_eq!(update["config"]["database"]["host"], "db");
+
+    let state = http.get_json("/api/admin/state").await;
+    let configs = data(&state)["configs"].as_array().unwrap();
+    assert_eq!(configs.len(), 2);
+    assert_ne!(configs[0]["version"], version);
+    assert_eq!(configs[1]["name"], "feature.json");
+    listener.close().await;
+}
+
+// 金丝雀发布只推给按标签或百分比选中的订阅者，文件不变；回滚时金丝雀订阅者收到稳定版本，
+// promote 后写入文件并推给所有订阅者
+#[tokio::test]
+async fn canary_rollout_targets_subscribers_and_promotes() {
+    let workspace = Workspace::new("rollout");
+    workspace.write("app.json", APP_JSON);
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+    let mut beta = http.listen_with_labels("app.json", "tier=beta,region=eu").await;
+    let mut stable = http.listen("app.json").await;
+    beta.next_of("initial").await;
+    stable.next_of("initial").await;
+
+    let canary = r#"{"database": {"host": "canary", "port": 5432}, "debug": true}"#;
+    let request = json!({"content": canary, "selector": {"tier": "beta"}});
+    let rollout = http.post_json("/api/configs/app.json/rollout/canary", &request).await;
+    assert_eq!(data(&rollout)["clients"].as_array().unwrap().len(), 1);
+    let update = beta.next_of("update").await;
+    assert_eq!(update["config"]["database"]["host"], "canary");
+    assert_eq!(update["source"], "rollout");
+    assert!(update["patch"].as_array().unwrap().iter().any(|op| op["path"] == "/debug"));
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["host"], "localhost");
+
+    // 发布期间新连接且被选中的订阅者先收到稳定版本，再收到金丝雀版本
+    let mut late = http.listen_with_labels("app.json", "tier=beta").await;
+    assert_eq!(late.next_of("initial").await["config"]["database"]["host"], "localhost");
+    assert_eq!(late.next_of("update").await["config"]["database"]["host"], "canary");
+    let status = http.get_json("/api/configs/app.json/rollout").await;
+    assert_eq!(data(&status)["clients"].as_array().unwrap().len(), 2);
+    assert_eq!(data(&status)["target"], json!({"selector": {"tier": "beta"}}));
+
+    let response = http.post_json("/api/configs/app.json/rollout/canary", &request).await;
+    assert_eq!(response["code"], 409);
+    let response = http.post_json("/api/configs/other.json/rollout/canary", &request).await;
+    assert_eq!(response["code"], 404);
+    let request = json!({"content": canary, "percentage": 101});
+    let response = http.post_json("/api/configs/app.json/rollout/canary", &request).await;
+    assert_eq!(response["code"], 400);
+
+    let rollback = http.post_json("/api/configs/app.json/rollout/rollback", &json!({})).await;
+    assert_eq!(data(&rollback)["file"], "app.json");
+    let update = beta.next_of("update").await;
+    assert_eq!(update["config"]["database"]["host"], "localhost");
+    assert_eq!(update["config"]["debug"], false);
+    late.next_of("update").await;
+    let status = http.get_json("/api/configs/app.json/rollout").await;
+    assert_eq!(status["code"], 404);
+
+    let promoted = r#"{"database": {"host": "db", "port": 5432}, "debug": false}"#;
+    let request = json!({"content": promoted, "percentage": 100});
+    http.post_json("/api/configs/app.json/rollout/canary", &request).await;
+    assert_eq!(stable.next_of("update").await["config"]["database"]["host"], "db");
+    let response = http.post_json("/api/configs/app.json/rollout/promote", &json!({})).await;
+    assert_eq!(data(&response)["hash"].as_str().unwrap().len(), 64);
+    let update = stable.next_of("update").await;
+    assert_eq!(update["config"]["database"]["host"], "db");
+    assert!(update["patch"].as_array().unwrap().is_empty());
+    assert_eq!(workspace.read("app.json").as_deref(), Some(promoted));
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["host"], "db");
+    eventually("rollout to end", async || {
+        let status = http.get_json("/api/configs/app.json/rollout").await;
+        (status["code"] == 404).then_some(())
+    })
+    .await;
+    for listener in [beta, stable, late] {
+        listener.close().await;
+    }
+}
+
+// 锁定的配置拒绝接口写入和磁盘修改，解锁后重新加载；冻结时所有配置都不可写，带 ttl 的锁到期自动解除
+#[tokio::test]
+async fn config_locks_and_freeze_block_changes() {
+    let workspace = Workspace::new("config-lock");
+    workspace.write("app.json", APP_JSON);
+    workspace.write("other.json", r#"{"enabled": false}"#);
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let request = json!({"owner": "oncall", "reason": "incident 42"});
+    let lock = http.post_json("/api/configs/app.json/lock", &request).await;
+    assert_eq!(data(&lock)["owner"], "oncall");
+    assert!(data(&lock)["expires_at"].is_null());
+    let status = http.get_json("/api/configs/app.json/lock").await;
+    assert_eq!(data(&status)["reason"], "incident 42");
+    let response = http.post_json("/api/configs/app.json/lock", &json!({"owner": " "})).await;
+    assert_eq!(response["code"], 400);
+
+    let response = http.put("/api/configs/app.json", r#"{"debug": true}"#).await;
+    assert_eq!(response["code"], 423);
+    assert!(response["message"].as_str().unwrap().contains("oncall"));
+    let response = http.send_with_key(Method::DELETE, "/api/configs/app.json", "", "").await;
+    assert_eq!(response["code"], 423);
+    let response = http
+        .post_json(
+            "/api/transactions",
+            &json!({"changes": [{ "file": "app.json", "key": "debug", "value": true }]}),
+        )
+        .await;
+    assert_eq!(response["code"], 423);
+    assert_eq!(workspace.read("app.json").as_deref(), Some(APP_JSON));
+
+    // 锁定期间磁盘上的修改不生效，状态为 stale
+    workspace.write("app.json", r#"{"database": {"host": "edited", "port": 5432}, "debug": false}"#);
+    eventually("external edit to be rejected", async || {
+        let status = http.get_json("/api/configs/app.json/status").await;
+        (data(&status)["state"] == "stale").then_some(())
+    })
+    .await;
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["host"], "localhost");
+
+    let unlocked = http.send_with_key(Method::DELETE, "/api/configs/app.json/lock", "", "").await;
+    assert_eq!(data(&unlocked)["owner"], "oncall");
+    eventually("edit to apply after unlock", async || {
+        let response = http.get_json("/api/configs/app.json").await;
+        (data(&response)["config"]["database"]["host"] == "edited").then_some(())
+    })
+    .await;
+    let response = http.send_with_key(Method::DELETE, "/api/configs/app.json/lock", "", "").await;
+    assert_eq!(response["code"], 404);
+
+    let freeze = http.post_json("/api/admin/freeze", &json!({"owner": "release"})).await;
+    assert_eq!(data(&freeze)["owner"], "release");
+    let response = http.put("/api/configs/other.json", r#"{"enabled": true}"#).await;
+    assert_eq!(response["code"], 423);
+    let request = json!({"name": "service", "content": "{}", "format": "json"});
+    let response = http.post_json("/api/configs", &request).await;
+    assert_eq!(response["code"], 423);
+    let locks = http.get_json("/api/admin/locks").await;
+    assert_eq!(data(&locks)["freeze"]["owner"], "release");
+    http.send_with_key(Method::DELETE, "/api/admin/freeze", "", "").await;
+    let response = http.put("/api/configs/other.json", r#"{"enabled": true}"#).await;
+    assert_eq!(response["success"], true);
+
+    let request = json!({"owner": "deploy", "ttl_secs": 1});
+    let lock = http.post_json("/api/configs/other.json/lock", &request).await;
+    assert!(data(&lock)["expires_at"].is_string());
+    let response = http.put("/api/configs/other.json", r#"{"enabled": false}"#).await;
+    assert_eq!(response["code"], 423);
+    eventually("lock to expire", async || {
+        let response = http.put("/api/configs/other.json", r#"{"enabled": false}"#).await;
+        (response["success"] == true).then_some(())
+    })
+    .await;
+    let status = http.get_json("/api/configs/other.json/lock").await;
+    assert_eq!(status["code"], 404);
+}
+
+// 定时修改到期后写入文件并推送给订阅者；未到期的修改在重启后保留
+#[tokio::test]
+async fn scheduled_changes_apply_at_time_and_survive_restart() {
+    let workspace = Workspace::new("schedule");
+    workspace.write("app.json", APP_JSON);
+    let mut http = Server::start(&workspace, Mode::Http, &[]).await;
+    let mut listener = http.listen("app.json").await;
+    listener.next_of("initial").await;
+
+    let soon = chrono::Utc::now() + chrono::Duration::seconds(2);
+    let later = chrono::Utc::now() + chrono::Duration::hours(1);
+    let content = r#"{"database": {"host": "scheduled", "port": 5432}, "debug": false}"#;
+    let request = json!({"content": content, "apply_at": later.to_rfc3339()});
+    let response = http.post_json("/api/configs/app.json/schedule", &request).await;
+    assert_eq!(data(&response)["apply_at"], json!(later));
+    let request = json!({"content": content, "apply_at": soon.to_rfc3339()});
+    http.post_json("/api/configs/app.json/schedule", &request).await;
+    let pending = http.get_json("/api/configs/app.json/pending").await;
+    let pending = data(&pending).as_array().unwrap().clone();
+    assert_eq!(pending.len(), 2);
+    assert_eq!(pending[0]["apply_at"], json!(soon));
+
+    let past = chrono::Utc::now() - chrono::Duration::seconds(1);
+    let request = json!({"content": content, "apply_at": past.to_rfc3339()});
+    let response = http.post_json("/api/configs/app.json/schedule", &request).await;
+    assert_eq!(response["code"], 400);
+    let request = json!({"content": "{not json", "apply_at": later.to_rfc3339()});
+    let response = http.post_json("/api/configs/app.json/schedule", &request).await;
+    assert_eq!(response["code"], 400);
+
+    let update = listener.next_of("update").await;
+    assert_eq!(update["config"]["database"]["host"], "scheduled");
+    assert_eq!(update["source"], "schedule");
+    let saved: Value = serde_json::from_str(&workspace.read("app.json").unwrap()).unwrap();
+    assert_eq!(saved["database"]["host"], "scheduled");
+    let pending = http.get_json("/api/configs/app.json/pending").await;
+    assert_eq!(data(&pending).as_array().unwrap().len(), 1);
+    listener.close().await;
+
+    http.terminate().await;
+    drop(http);
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+    let pending = http.get_json("/api/configs/app.json/pending").await;
+    let pending = data(&pending).as_array().unwrap().clone();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0]["apply_at"], json!(later));
+}
+
+// 键和整个配置到期后删除或恢复为默认值并推送给订阅者；规则可在配置的 $ttl 中声明，重启后保留
+#[tokio::test]
+async fn ttl_expires_keys_and_configs() {
+    let workspace = Workspace::new("ttl");
+    workspace.write(
+        "app.json",
+        r#"{"debug": true, "flags": {"beta": true, "dark": true}, "$ttl": {"debug": {"ttl_secs": 2, "default": false}}}"#,
+    );
+    workspace.write("other.json", r#"{"enabled": true}"#);
+    let mut http = Server::start(&workspace, Mode::Http, &[]).await;
+    let mut listener = http.listen("app.json").await;
+    listener.next_of("initial").await;
+
+    let ttls = http.get_json("/api/configs/app.json/ttl").await;
+    assert_eq!(data(&ttls)[0]["key"], "debug");
+    assert_eq!(data(&ttls)[0]["source"], "file");
+    let request = json!({"key": "flags.beta", "ttl_secs": 1});
+    let ttl = http.post_json("/api/configs/app.json/ttl", &request).await;
+    assert_eq!(data(&ttl)["source"], "api");
+    assert!(data(&ttl)["expires_at"].is_string());
+    let request = json!({"key": "flags.missing", "ttl_secs": 1});
+    let response = http.post_json("/api/configs/app.json/ttl", &request).await;
+    assert_eq!(response["code"], 404);
+    let response = http.post_json("/api/configs/app.json/ttl", &json!({"ttl_secs": 0})).await;
+    assert_eq!(response["code"], 400);
+
+    let update = listener.next_of("update").await;
+    assert_eq!(update["source"], "ttl");
+    let config = eventually("keys to expire", async || {
+        let response = http.get_json("/api/configs/app.json").await;
+        let config = data(&response)["config"].clone();
+        (config["debug"] == false && config["flags"].get("beta").is_none()).then_some(config)
+    })
+    .await;
+    assert_eq!(config["flags"]["dark"], true);
+    assert!(config.get("$ttl").is_none());
+    let ttls = http.get_json("/api/configs/app.json/ttl").await;
+    assert!(data(&ttls).as_array().unwrap().is_empty());
+    listener.close().await;
+
+    http.post_json("/api/configs/other.json/ttl", &json!({"ttl_secs": 1})).await;
+    eventually("config to expire", async || {
+        let response = http.get_json("/api/configs/other.json").await;
+        (response["code"] == 404).then_some(())
+    })
+    .await;
+    assert!(workspace.read("other.json").is_none());
+
+    let request = json!({"key": "flags.dark", "ttl_secs": 3600, "default": false});
+    let ttl = http.post_json("/api/configs/app.json/ttl", &request).await;
+    http.terminate().await;
+    drop(http);
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+    let ttls = http.get_json("/api/configs/app.json/ttl").await;
+    assert_eq!(data(&ttls)[0]["expires_at"], data(&ttl)["expires_at"]);
+    assert_eq!(data(&ttls)[0]["default"], false);
+}
+
+// 启动时解析失败的文件不影响就绪，失败原因通过配置状态接口查看；修复后恢复为已加载，
+// 之后的无效修改不覆盖上一个有效版本
+#[tokio::test]
+async fn startup_tolerates_broken_files_and_reports_config_status() {
+    let workspace = Workspace::new("config-status");
+    workspace.write("app.json", APP_JSON);
+    workspace.write("broken.json", "{not json");
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+
+    let report = eventually("server to become ready", async || {
+        let response = reqwest::get(format!("{}/readyz", http.http_url())).await.unwrap();
+        if response.status() != 200 {
+            return None;
+        }
+        response.json::<Value>().await.ok()
+    })
+    .await;
+    assert_eq!(data(&report)["startup_progress"], json!({"total": 2, "loaded": 1, "failed": 1}));
+
+    let status = http.get_json("/api/configs/app.json/status").await;
+    assert_eq!(data(&status)["state"], "loaded");
+    let status = http.get_json("/api/configs/broken.json/status").await;
+    assert_eq!(data(&status)["state"], "failed");
+    assert!(data(&status)["error"].is_string());
+    assert!(data(&status)["failed_at"].is_string());
+    let status = http.get_json("/api/configs/missing.json/status").await;
+    assert_eq!(status["code"], 404);
+
+    workspace.write("broken.json", r#"{"fixed": true}"#);
+    eventually("fixed config to load", async || {
+        let status = http.get_json("/api/configs/broken.json/status").await;
+        (data(&status)["state"] == "loaded").then_some(())
+    })
+    .await;
+    assert!(data(&http.get_json("/api/configs/broken.json/status").await)["error"].is_null());
+
+    workspace.write("app.json", "{not json");
+    eventually("invalid change to be reported", async || {
+        let status = http.get_json("/api/configs/app.json/status").await;
+        (data(&status)["state"] == "stale").then_some(())
+    })
+    .await;
+    let response = http.get_json("/api/configs/app.json").await;
+    assert_eq!(data(&response)["config"]["database"]["port"], 5432);
+}
+
+// 设置文件修改后无需重启：API key、命名空间和限流对之后的请求生效，无效的设置不替换当前设置
+#[tokio::test]
+async fn server_settings_reload_without_restart() {
+    let workspace = Workspace::new("settings-reload");
+    workspace.write("app.json", APP_JSON);
+    let settings = workspace.write_server_file(
+        "settings.yaml",
+        "api_keys:\n  - name: dashboard\n    key: old-key\n    scopes: [read]\n",
+    );
+    let args = ["--settings".to_string(), settings.display().to_string()];
+    let http = Server::start(&workspace, Mode::Http, &args).await;
+    let response = http.send_with_key(Method::GET, "/api/configs", "old-key", "").await;
+    assert_eq!(response["code"], 200);
+
+    workspace.write_server_file(
+        "settings.yaml",
+        "api_keys:\n  - name: dashboard\n    key: new-key\n    scopes: [read]\n\
+         namespaces: [team-a]\nrate_limit:\n  rate: 0.5\n  burst: 1\nlog_level: info\n",
+    );
+    eventually("old key to be rejected", async || {
+        let response = http.send_with_key(Method::GET, "/api/configs", "old-key", "").await;
+        (response["code"] == 401).then_some(())
+    })
+    .await;
+    let response = http.send_with_key(Method::GET, "/api/namespaces", "new-key", "").await;
+    assert_eq!(data(&response)[0]["name"], "team-a");
+    assert!(workspace.config_dir().join("team-a").is_dir());
+    let response = http.send_with_key(Method::GET, "/api/configs", "new-key", "").await;
+    assert_eq!(response["code"], 429);
+
+    // 无效的设置（限流速率为 0）被拒绝，继续使用上一份设置
+    workspace.write_server_file(
+        "settings.yaml",
+        "api_keys:\n  - name: dashboard\n    key: other-key\n    scopes: [read]\n\
+         rate_limit:\n  rate: 0\n",
+    );
+    eventually("invalid settings to be rejected", async || {
+        http.log().contains("keeping current settings").then_some(())
+    })
+    .await;
+    let response = http.send_with_key(Method::GET, "/api/configs", "other-key", "").await;
+    assert_eq!(response["code"], 401);
+}
+
+// 设置文件中配置的消息总线与 webhook 一样收到每次变更，连接在多次发布之间复用
+#[tokio::test]
+async fn notification_sinks_publish_changes_to_message_buses() {
+    let workspace = Workspace::new("notification-sinks");
+    workspace.write("app.json", APP_JSON);
+    let mut nats = BrokerReceiver::start(Broker::Nats).await;
+    let mut redis = BrokerReceiver::start(Broker::Redis).await;
+    let settings = workspace.write_server_file(
+        "settings.yaml",
+        &format!(
+            "notifications:\n  - sink: nats\n    address: {}\n    subject: config.changes\n  \
+             - sink: redis\n    address: {}\n    channel: config-changes\n",
+            nats.address, redis.address
+        ),
+    );
+    let args = ["--settings".to_string(), settings.display().to_string()];
+    let http = Server::start(&workspace, Mode::Http, &args).await;
+
+    for port in [6543, 7654] {
+        let body = format!(r#"{{"database": {{"host": "localhost", "port": {}}}}}"#, port);
+        http.put("/api/configs/app.json", &body).await;
+        let (subject, update) = nats.recv().await;
+        assert_eq!(subject, "config.changes");
+        assert_eq!(update["file"], "app.json");
+        assert_eq!(update["source"], "http_api");
+        let config: Value = serde_json::from_str(update["config"].as_str().unwrap()).unwrap();
+        assert_eq!(config["database"]["port"], port);
+        let (channel, update) = redis.recv().await;
+        assert_eq!(channel, "config-changes");
+        assert_eq!(update["file"], "app.json");
+    }
+    let letters = http.get_json("/api/admin/dead-letters").await;
+    assert_eq!(data(&letters).as_array().unwrap().len(), 0);
+}
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct AppConfig {
+    database: DatabaseConfig,
+    debug: bool,
+}
+
+#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+struct DatabaseConfig {
+    host: String,
+    port: u16,
+}
+
+async fn wait_for_subscriber(http: &Server) {
+    eventually("client to subscribe", async || {
+        let response = http.get_json("/api/subscribers").await;
+        (!data(&response)["subscribers"].as_array()?.is_empty()).then_some(())
+    })
+    .await;
+}
+
+// 客户端 SDK 按类型读写配置；watch 在服务重启后自动重连，断线期间的修改以 resync 更新补上
+#[tokio::test]
+async fn client_sdk_reads_writes_and_watches_across_restarts() {
+    let workspace = Workspace::new("client-sdk");
+    workspace.write("app.json", APP_JSON);
+    let mut http = Server::start(&workspace, Mode::Http, &[]).await;
+    let client = ConfigClient::new(http.http_url())
+        .with_cache(std::time::Duration::from_secs(60))
+        .with_reconnect(
+            std::time::Duration::from_millis(100),
+            std::time::Duration::from_millis(500),
+        );
+
+    let config: AppConfig = client.get("app.json").await.unwrap();
+    assert_eq!(config.database.port, 5432);
+    let error = client
+        .get::<std::collections::BTreeMap<String, std::collections::BTreeMap<String, String>>>(
+            "app.json",
+        )
+        .await
+        .unwrap_err();
+    match error {
+        ClientError::Deserialize { path, .. } => assert_eq!(path, "database.port"),
+        error => panic!("unexpected error: {}", error),
+    }
+    let error = client.get::<AppConfig>("missing.json").await.unwrap_err();
+    assert!(matches!(error, ClientError::NotFound(_)), "{}", error);
+
+    let mut updates = Box::pin(client.watch("app.json"));
+    wait_for_subscriber(&http).await;
+    let mut config = config;
+    config.database.port = 6000;
+    client.put("app.json", &config).await.unwrap();
+    let update = next_update(&mut updates).await;
+    assert_eq!(update.kind, ChangeKind::Updated);
+    assert_eq!(update.source, "http_api");
+    let pushed: AppConfig = serde_json::from_str(&update.config).unwrap();
+    assert_eq!(pushed, config);
+    let saved: Value = serde_json::from_str(&workspace.read("app.json").unwrap()).unwrap();
+    assert_eq!(saved["database"]["port"], 6000);
+
+    // 服务停止期间修改文件，重启后会话无法恢复，客户端对比内容后补发 resync
+    let port = http.port;
+    http.terminate().await;
+    drop(http);
+    workspace.write(
+        "app.json",
+        r#"{"database": {"host": "localhost", "port": 7000}, "debug": true}"#,
+    );
+    let http = Server::start_on(&workspace, Mode::Http, port, &[]).await;
+    let update = next_update(&mut updates).await;
+    assert_eq!(update.source, "resync");
+    let resynced: AppConfig = serde_json::from_str(&update.config).unwrap();
+    assert_eq!(resynced.database.port, 7000);
+    // watch 收到的更新直接刷新了缓存
+    let cached: AppConfig = client.get("app.json").await.unwrap();
+    assert_eq!(cached, resynced);
+
+    wait_for_subscriber(&http).await;
+    http.put("/api/configs/app.json", APP_JSON).await;
+    let update = next_update(&mut updates).await;
+    assert_eq!(update.source, "http_api");
+    let pushed: AppConfig = serde_json::from_str(&update.config).unwrap();
+    assert_eq!(pushed.database.port, 5432);
+}
+
+async fn next_update(
+    updates: &mut (impl futures_util::Stream<Item = ConfigUpdate> + Unpin),
+) -> ConfigUpdate {
+    use futures_util::StreamExt;
+    tokio::time::timeout(std::time::Duration::from_secs(10), updates.next())
+        .await
+        .expect("timed out waiting for client update")
+        .expect("watch stream ended")
+}
+
+#[derive(Debug, ConfigBind)]
+#[config(validate = "check_service")]
+struct ServiceConfig {
+    #[config(path = "database.host")]
+    db_host: String,
+    #[config(path = "database.port", validate = "check_port")]
+    db_port: u16,
+    #[config(default)]
+    debug: bool,
+    #[config(default = "default_workers")]
+    workers: u32,
+    replicas: Vec<ReplicaConfig>,
+    labels: Option<std::collections::BTreeMap<String, String>>,
+}
+
+#[derive(Debug, ConfigBind)]
+struct ReplicaConfig {
+    host: String,
+    port: u16,
+}
+
+fn default_workers() -> u32 {
+    4
+}
+
+fn check_port(port: &u16) -> Result<(), String> {
+    if *port < 1024 {
+        return Err(format!("port {} is reserved", port));
+    }
+    Ok(())
+}
+
+fn check_service(service: &ServiceConfig) -> Result<(), String> {
+    if service.replicas.iter().any(|replica| replica.port == service.db_port) {
+        return Err("replica port conflicts with database port".to_string());
+    }
+    Ok(())
+}
+
+async fn bind_error(client: &ConfigClient, name: &str) -> BindError {
+    match client.bind::<ServiceConfig>(name).await.unwrap_err() {
+        ClientError::Bind { error, .. } => error,
+        error => panic!("unexpected error: {}", error),
+    }
+}
+
+// derive 绑定按声明的路径取值，缺省字段使用默认值；缺失、类型错误和校验失败都指出具体路径
+#[tokio::test]
+async fn client_sdk_binds_configs_with_paths_defaults_and_validation() {
+    let workspace = Workspace::new("client-bind");
+    workspace.write(
+        "service.json",
+        r#"{"database": {"host": "db", "port": 5432},
+            "replicas": [{"host": "r1", "port": 6001}, {"host": "r2", "port": 6002}]}"#,
+    );
+    workspace.write(
+        "missing.json",
+        r#"{"database": {"host": "db"}, "replicas": []}"#,
+    );
+    workspace.write(
+        "mistyped.json",
+        r#"{"database": {"host": "db", "port": 5432}, "replicas": [{"host": "r1", "port": "x"}]}"#,
+    );
+    workspace.write(
+        "out-of-range.json",
+        r#"{"database": {"host": "db", "port": 70000}, "replicas": []}"#,
+    );
+    workspace.write(
+        "reserved.json",
+        r#"{"database": {"host": "db", "port": 80}, "replicas": []}"#,
+    );
+    workspace.write(
+        "conflict.json",
+        r#"{"database": {"host": "db", "port": 6001}, "replicas": [{"host": "r1", "port": 6001}]}"#,
+    );
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+    let client = ConfigClient::new(http.http_url());
+
+    let service: ServiceConfig = client.bind("service.json").await.unwrap();
+    assert_eq!(service.db_host, "db");
+    assert_eq!(service.db_port, 5432);
+    assert!(!service.debug);
+    assert_eq!(service.workers, 4);
+    assert_eq!(service.replicas[1].host, "r2");
+    assert!(service.labels.is_none());
+
+    let error = bind_error(&client, "missing.json").await;
+    assert_eq!(error, BindError::Missing { path: "database.port".to_string() });
+    let error = bind_error(&client, "mistyped.json").await;
+    assert_eq!(error.path(), "replicas[0].port");
+    assert!(matches!(error, BindError::Type { ref expected, .. } if expected == "u16"), "{}", error);
+    let error = bind_error(&client, "out-of-range.json").await;
+    assert_eq!(error.path(), "database.port");
+    let error = bind_error(&client, "reserved.json").await;
+    assert_eq!(error, BindError::invalid("database.port", "port 80 is reserved"));
+    let error = bind_error(&client, "conflict.json").await;
+    assert_eq!(error.path(), ".");
+    assert_eq!(
+        error.to_string(),
+        "invalid value at .: replica port conflicts with database port"
+    );
+}
+
+// Watched 句柄随服务端修改自动更新并调用回调；无法绑定的修改保留原值
+#[tokio::test]
+async fn client_sdk_watched_handle_follows_updates() {
+    let workspace = Workspace::new("client-watched");
+    let service = |port: u16| {
+        format!(
+            r#"{{"database": {{"host": "db", "port": {}}}, "replicas": [{{"host": "r1", "port": 6001}}]}}"#,
+            port
+        )
+    };
+    workspace.write("service.json", &service(5432));
+    let http = Server::start(&workspace, Mode::Http, &[]).await;
+    let client = ConfigClient::new(http.http_url());
+
+    let watched = client.watched::<ServiceConfig>("service.json").await.unwrap();
+    assert_eq!(watched.get().db_port, 5432);
+    let (sender, mut changes) = tokio::sync::mpsc::unbounded_channel();
+    watched.on_change(move |service: &ServiceConfig| {
+        let _ = sender.send(service.db_port);
+    });
+    wait_for_subscriber(&http).await;
+
+    let mut next_change = async || {
+        tokio::time::timeout(std::time::Duration::from_secs(10), changes.recv())
+            .await
+            .expect("timed out waiting for change callback")
+            .expect("watched handle closed")
+    };
+    data(&http.put("/api/configs/service.json", &service(6000)).await);
+    assert_eq!(next_change().await, 6000);
+    assert_eq!(watched.get().db_port, 6000);
+
+    // 端口 80 未通过 check_port，回调不会收到它，值保持 6000
+    data(&http.put("/api/configs/service.json", &service(80)).await);
+    data(&http.put("/api/configs/service.json", &service(7000)).await);
+    assert_eq!(next_change().await, 7000);
+    assert_eq!(watched.clone().get().db_port, 7000);
+}